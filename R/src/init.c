@@ -59,11 +59,21 @@ SEXP savvy_sas_metadata_json__impl(SEXP c_arg__path) {
     return handle_result(res);
 }
 
+SEXP savvy_sas_read_chunks__impl(SEXP c_arg__path, SEXP c_arg__chunk_rows, SEXP c_arg__callback) {
+    SEXP res = savvy_sas_read_chunks__ffi(c_arg__path, c_arg__chunk_rows, c_arg__callback);
+    return handle_result(res);
+}
+
 SEXP savvy_sas_row_count__impl(SEXP c_arg__path) {
     SEXP res = savvy_sas_row_count__ffi(c_arg__path);
     return handle_result(res);
 }
 
+SEXP savvy_sas_validate_issues__impl(SEXP c_arg__path) {
+    SEXP res = savvy_sas_validate_issues__ffi(c_arg__path);
+    return handle_result(res);
+}
+
 SEXP savvy_to_upper__impl(SEXP c_arg__x) {
     SEXP res = savvy_to_upper__ffi(c_arg__x);
     return handle_result(res);
@@ -101,7 +111,9 @@ static const R_CallMethodDef CallEntries[] = {
     {"savvy_read_sas__impl", (DL_FUNC) &savvy_read_sas__impl, 1},
     {"savvy_sas_column_names__impl", (DL_FUNC) &savvy_sas_column_names__impl, 1},
     {"savvy_sas_metadata_json__impl", (DL_FUNC) &savvy_sas_metadata_json__impl, 1},
+    {"savvy_sas_read_chunks__impl", (DL_FUNC) &savvy_sas_read_chunks__impl, 3},
     {"savvy_sas_row_count__impl", (DL_FUNC) &savvy_sas_row_count__impl, 1},
+    {"savvy_sas_validate_issues__impl", (DL_FUNC) &savvy_sas_validate_issues__impl, 1},
     {"savvy_to_upper__impl", (DL_FUNC) &savvy_to_upper__impl, 1},
     {"savvy_write_sas__impl", (DL_FUNC) &savvy_write_sas__impl, 3},
     {"savvy_Person_associated_function__impl", (DL_FUNC) &savvy_Person_associated_function__impl, 0},
@@ -3,7 +3,9 @@ SEXP savvy_int_times_int__ffi(SEXP c_arg__x, SEXP c_arg__y);
 SEXP savvy_read_sas__ffi(SEXP c_arg__path);
 SEXP savvy_sas_column_names__ffi(SEXP c_arg__path);
 SEXP savvy_sas_metadata_json__ffi(SEXP c_arg__path);
+SEXP savvy_sas_read_chunks__ffi(SEXP c_arg__path, SEXP c_arg__chunk_rows, SEXP c_arg__callback);
 SEXP savvy_sas_row_count__ffi(SEXP c_arg__path);
+SEXP savvy_sas_validate_issues__ffi(SEXP c_arg__path);
 SEXP savvy_to_upper__ffi(SEXP c_arg__x);
 SEXP savvy_write_sas__ffi(SEXP c_arg__path, SEXP c_arg__sink, SEXP c_arg__output);
 
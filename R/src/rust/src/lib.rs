@@ -3,7 +3,8 @@
 use savvy::NotAvailableValue;
 use savvy::savvy;
 use savvy::{
-    IntegerSexp, OwnedIntegerSexp, OwnedListSexp, OwnedRealSexp, OwnedStringSexp, StringSexp,
+    FunctionArgs, FunctionSexp, IntegerSexp, OwnedIntegerSexp, OwnedListSexp, OwnedRealSexp,
+    OwnedStringSexp, StringSexp,
 };
 use std::convert::TryFrom;
 use std::fs::File;
@@ -14,6 +15,9 @@ use sas7bdat::SasReader;
 use sas7bdat::dataset::{VariableKind, Vendor};
 use sas7bdat::sinks::{CsvSink, ParquetSink};
 use sas7bdat::CellValue;
+use sas7bdat::validate_file;
+use sas7bdat::catch_panic;
+use sas7bdat::value::{NumberLocale, parse_sas_number};
 
 /// Convert Input To Upper-Case
 ///
@@ -141,6 +145,12 @@ where
     savvy::Error::new(format!("sas7bdat error: {e}"))
 }
 
+/// Runs a binding body so that a panic in the core crate becomes an R error
+/// instead of unwinding into R.
+fn guarded<T>(body: impl FnOnce() -> savvy::Result<T>) -> savvy::Result<T> {
+    catch_panic(body).map_err(map_core_err)?
+}
+
 fn map_io_err(action: &str, path: &str, err: &std::io::Error) -> savvy::Error {
     savvy::Error::new(format!("failed to {action} '{path}': {err}"))
 }
@@ -152,14 +162,16 @@ fn map_io_err(action: &str, path: &str, err: &std::io::Error) -> savvy::Error {
 /// @export
 #[savvy]
 fn sas_row_count(path: &str) -> savvy::Result<savvy::Sexp> {
-    let file = SasReader::open(path).map_err(map_core_err)?;
-    // Prefer metadata row_count when available
-    let rc = file.metadata().row_count;
-    let mut out = OwnedIntegerSexp::new(1)?;
-    // R integers are 32-bit; cap if exceeded
-    let val = i32::try_from(rc).unwrap_or(i32::MAX);
-    out[0] = val;
-    Ok(out.into())
+    guarded(|| {
+        let file = SasReader::open(path).map_err(map_core_err)?;
+        // Prefer metadata row_count when available
+        let rc = file.metadata().row_count;
+        let mut out = OwnedIntegerSexp::new(1)?;
+        // R integers are 32-bit; cap if exceeded
+        let val = i32::try_from(rc).unwrap_or(i32::MAX);
+        out[0] = val;
+        Ok(out.into())
+    })
 }
 
 /// Column names of a SAS7BDAT file
@@ -169,18 +181,20 @@ fn sas_row_count(path: &str) -> savvy::Result<savvy::Sexp> {
 /// @export
 #[savvy]
 fn sas_column_names(path: &str) -> savvy::Result<savvy::Sexp> {
-    let file = SasReader::open(path).map_err(map_core_err)?;
-    let names: Vec<String> = file
-        .metadata()
-        .variables
-        .iter()
-        .map(|v| v.name.clone())
-        .collect();
-    let mut out = OwnedStringSexp::new(names.len())?;
-    for (i, n) in names.iter().enumerate() {
-        out.set_elt(i, n)?;
-    }
-    Ok(out.into())
+    guarded(|| {
+        let file = SasReader::open(path).map_err(map_core_err)?;
+        let names: Vec<String> = file
+            .metadata()
+            .variables
+            .iter()
+            .map(|v| v.name.clone())
+            .collect();
+        let mut out = OwnedStringSexp::new(names.len())?;
+        for (i, n) in names.iter().enumerate() {
+            out.set_elt(i, n)?;
+        }
+        Ok(out.into())
+    })
 }
 
 /// Basic metadata as JSON (for convenient consumption in R)
@@ -190,59 +204,62 @@ fn sas_column_names(path: &str) -> savvy::Result<savvy::Sexp> {
 /// @export
 #[savvy]
 fn sas_metadata_json(path: &str) -> savvy::Result<savvy::Sexp> {
-    let file = SasReader::open(path).map_err(map_core_err)?;
-    let md = file.metadata();
-
-    let vendor = match md.vendor {
-        Vendor::Sas => "SAS",
-        Vendor::StatTransfer => "StatTransfer",
-        Vendor::Other(_) => "Other",
-    };
-    let compression = match md.compression {
-        sas7bdat::dataset::Compression::None => "none",
-        sas7bdat::dataset::Compression::Row => "row",
-        sas7bdat::dataset::Compression::Binary => "binary",
-        sas7bdat::dataset::Compression::Unknown(_) => "unknown",
-    };
-    let endianness = match md.endianness {
-        sas7bdat::dataset::Endianness::Little => "little",
-        sas7bdat::dataset::Endianness::Big => "big",
-    };
-
-    let column_names: Vec<&str> = md.variables.iter().map(|v| v.name.as_str()).collect();
-    let column_types: Vec<&str> = md
-        .variables
-        .iter()
-        .map(|v| match v.kind {
-            VariableKind::Numeric => "numeric",
-            VariableKind::Character => "character",
-        })
-        .collect();
-
-    let obj = serde_json::json!({
-        "row_count": md.row_count,
-        "column_count": md.column_count,
-        "table_name": md.table_name,
-        "file_label": md.file_label,
-        "file_encoding": md.file_encoding,
-        "vendor": vendor,
-        "compression": compression,
-        "endianness": endianness,
-        "version": { "major": md.version.major, "minor": md.version.minor, "revision": md.version.revision },
-        "timestamps": {
-            "created": md.timestamps.created.as_ref().map(std::string::ToString::to_string),
-            "modified": md.timestamps.modified.as_ref().map(std::string::ToString::to_string)
-        },
-        "columns": {
-            "names": column_names,
-            "types": column_types
-        }
-    });
+    guarded(|| {
+        let file = SasReader::open(path).map_err(map_core_err)?;
+        let md = file.metadata();
+
+        let vendor = match md.vendor {
+            Vendor::Sas => "SAS",
+            Vendor::StatTransfer => "StatTransfer",
+            Vendor::Other(_) => "Other",
+        };
+        let compression = match &md.compression {
+            sas7bdat::dataset::Compression::None => "none",
+            sas7bdat::dataset::Compression::Row => "row",
+            sas7bdat::dataset::Compression::Binary => "binary",
+            sas7bdat::dataset::Compression::Unknown(_) => "unknown",
+            sas7bdat::dataset::Compression::Other(signature) => signature.as_str(),
+        };
+        let endianness = match md.endianness {
+            sas7bdat::dataset::Endianness::Little => "little",
+            sas7bdat::dataset::Endianness::Big => "big",
+        };
+
+        let column_names: Vec<&str> = md.variables.iter().map(|v| v.name.as_str()).collect();
+        let column_types: Vec<&str> = md
+            .variables
+            .iter()
+            .map(|v| match v.kind {
+                VariableKind::Numeric => "numeric",
+                VariableKind::Character => "character",
+            })
+            .collect();
+
+        let obj = serde_json::json!({
+            "row_count": md.row_count,
+            "column_count": md.column_count,
+            "table_name": md.table_name,
+            "file_label": md.file_label,
+            "file_encoding": md.file_encoding,
+            "vendor": vendor,
+            "compression": compression,
+            "endianness": endianness,
+            "version": { "major": md.version.major, "minor": md.version.minor, "revision": md.version.revision },
+            "timestamps": {
+                "created": md.timestamps.created.as_ref().map(std::string::ToString::to_string),
+                "modified": md.timestamps.modified.as_ref().map(std::string::ToString::to_string)
+            },
+            "columns": {
+                "names": column_names,
+                "types": column_types
+            }
+        });
 
-    let json = obj.to_string();
-    let mut out = OwnedStringSexp::new(1)?;
-    out.set_elt(0, &json)?;
-    Ok(out.into())
+        let json = obj.to_string();
+        let mut out = OwnedStringSexp::new(1)?;
+        out.set_elt(0, &json)?;
+        Ok(out.into())
+    })
 }
 
 enum NumericRole {
@@ -406,33 +423,24 @@ fn parse_numeric(text: &str, column_name: &str) -> savvy::Result<Option<f64>> {
     if trimmed.is_empty() {
         return Ok(None);
     }
-    trimmed.parse::<f64>().map(Some).map_err(|_| {
-        savvy::Error::new(format!(
-            "column '{column_name}' value '{trimmed}' cannot be parsed as double"
-        ))
-    })
+    // Character columns from European sites often use decimal commas.
+    parse_sas_number(trimmed, NumberLocale::Point)
+        .or_else(|| parse_sas_number(trimmed, NumberLocale::Comma))
+        .map(Some)
+        .ok_or_else(|| {
+            savvy::Error::new(format!(
+                "column '{column_name}' value '{trimmed}' cannot be parsed as double"
+            ))
+        })
 }
 
-/// Read a SAS7BDAT file into a column-oriented representation
-///
-/// @param path Path to a .sas7bdat file
-/// @return Named list of vectors, with column metadata attached as attributes
-/// @export
-#[savvy]
-fn read_sas(path: &str) -> savvy::Result<savvy::Sexp> {
-    let mut file = SasReader::open(path).map_err(map_core_err)?;
-    let metadata = file.metadata().clone();
-
-    let column_count = metadata.variables.len();
-    let capacity = usize::try_from(metadata.row_count).map_err(|_| {
-        savvy::Error::new(format!(
-            "row count {} exceeds supported size on this platform",
-            metadata.row_count
-        ))
-    })?;
-
-    let mut columns: Vec<ColumnData> = Vec::with_capacity(column_count);
-    let mut names: Vec<String> = Vec::with_capacity(column_count);
+/// Empty column buffers and trimmed names for every variable in `metadata`.
+fn new_columns(
+    metadata: &sas7bdat::dataset::DatasetMetadata,
+    capacity: usize,
+) -> (Vec<ColumnData>, Vec<String>) {
+    let mut columns: Vec<ColumnData> = Vec::with_capacity(metadata.variables.len());
+    let mut names: Vec<String> = Vec::with_capacity(metadata.variables.len());
 
     for variable in &metadata.variables {
         names.push(variable.name.trim_end().to_string());
@@ -445,28 +453,37 @@ fn read_sas(path: &str) -> savvy::Result<savvy::Sexp> {
             }
         }
     }
+    (columns, names)
+}
 
-    let mut rows_seen = 0usize;
-    {
-        let mut rows = file.rows().map_err(map_core_err)?;
-        while let Some(row) = rows.try_next().map_err(map_core_err)? {
-            if row.len() != columns.len() {
-                return Err(savvy::Error::new(format!(
-                    "expected {} columns, but got {} in row {}",
-                    columns.len(),
-                    row.len(),
-                    rows_seen + 1
-                )));
-            }
-            for (idx, value) in row.iter().enumerate() {
-                columns[idx].push(value, &names[idx])?;
-            }
-            rows_seen += 1;
-        }
+fn push_row(
+    columns: &mut [ColumnData],
+    names: &[String],
+    row: &[CellValue<'_>],
+    row_number: usize,
+) -> savvy::Result<()> {
+    if row.len() != columns.len() {
+        return Err(savvy::Error::new(format!(
+            "expected {} columns, but got {} in row {row_number}",
+            columns.len(),
+            row.len(),
+        )));
+    }
+    for (idx, value) in row.iter().enumerate() {
+        columns[idx].push(value, &names[idx])?;
     }
+    Ok(())
+}
 
-    let mut out = OwnedListSexp::new(column_count, true)?;
-    let mut type_labels: Vec<String> = Vec::with_capacity(column_count);
+/// Builds the named list returned by `read_sas()`, with `column_types` and
+/// `row_count` attributes.
+fn columns_to_list(
+    columns: Vec<ColumnData>,
+    names: &[String],
+    rows: usize,
+) -> savvy::Result<savvy::Sexp> {
+    let mut out = OwnedListSexp::new(columns.len(), true)?;
+    let mut type_labels: Vec<String> = Vec::with_capacity(columns.len());
 
     for (idx, (column, name)) in columns.into_iter().zip(names.iter()).enumerate() {
         let (sexp, label) = column.finalize()?;
@@ -481,11 +498,7 @@ fn read_sas(path: &str) -> savvy::Result<savvy::Sexp> {
     out.set_attrib("column_types", types_attr)?;
 
     let mut row_count_attr = OwnedIntegerSexp::new(1)?;
-    row_count_attr[0] = if rows_seen > i32::MAX as usize {
-        i32::MAX
-    } else {
-        i32::try_from(rows_seen)?
-    };
+    row_count_attr[0] = i32::try_from(rows).unwrap_or(i32::MAX);
     let row_count_attr: savvy::Result<savvy::Sexp> = row_count_attr.into();
     let row_count_attr = row_count_attr?;
     out.set_attrib("row_count", row_count_attr)?;
@@ -493,6 +506,103 @@ fn read_sas(path: &str) -> savvy::Result<savvy::Sexp> {
     out.into()
 }
 
+/// Read a SAS7BDAT file into a column-oriented representation
+///
+/// @param path Path to a .sas7bdat file
+/// @return Named list of vectors, with column metadata attached as attributes
+/// @export
+#[savvy]
+fn read_sas(path: &str) -> savvy::Result<savvy::Sexp> {
+    guarded(|| {
+        let mut file = SasReader::open(path).map_err(map_core_err)?;
+        let metadata = file.metadata().clone();
+
+        let capacity = usize::try_from(metadata.row_count).map_err(|_| {
+            savvy::Error::new(format!(
+                "row count {} exceeds supported size on this platform",
+                metadata.row_count
+            ))
+        })?;
+        let (mut columns, names) = new_columns(&metadata, capacity);
+
+        let mut rows_seen = 0usize;
+        {
+            let mut rows = file.rows().map_err(map_core_err)?;
+            while let Some(row) = rows.try_next().map_err(map_core_err)? {
+                push_row(&mut columns, &names, &row, rows_seen + 1)?;
+                rows_seen += 1;
+            }
+        }
+
+        columns_to_list(columns, &names, rows_seen)
+    })
+}
+
+/// Stream a SAS7BDAT file to an R function in chunks of rows.
+///
+/// Backs [sas_stream_chunks()]. Only one chunk is held in memory at a time.
+///
+/// @param path Path to a .sas7bdat file
+/// @param chunk_rows Maximum number of rows per chunk
+/// @param callback Function called as `callback(chunk, start)` with a list
+///   shaped like the result of [read_sas()] and the 1-based number of the
+///   chunk's first row
+/// @return Total number of rows streamed, as a double
+/// @keywords internal
+#[savvy]
+fn sas_read_chunks(
+    path: &str,
+    chunk_rows: i32,
+    callback: FunctionSexp,
+) -> savvy::Result<savvy::Sexp> {
+    guarded(|| {
+        let chunk_rows = usize::try_from(chunk_rows)
+            .ok()
+            .filter(|&rows| rows > 0)
+            .ok_or_else(|| savvy::Error::new("chunk_rows must be a positive integer"))?;
+        let mut file = SasReader::open(path).map_err(map_core_err)?;
+        let metadata = file.metadata().clone();
+        let capacity = usize::try_from(metadata.row_count)
+            .unwrap_or(usize::MAX)
+            .min(chunk_rows);
+
+        let (mut columns, names) = new_columns(&metadata, capacity);
+        let mut chunk_len = 0usize;
+        let mut rows_seen = 0usize;
+        let mut rows = file.rows().map_err(map_core_err)?;
+        loop {
+            let row = rows.try_next().map_err(map_core_err)?;
+            if let Some(row) = &row {
+                push_row(&mut columns, &names, row, rows_seen + 1)?;
+                chunk_len += 1;
+                rows_seen += 1;
+            }
+            if chunk_len == chunk_rows || (row.is_none() && chunk_len > 0) {
+                let (fresh, _) = new_columns(&metadata, capacity);
+                let chunk =
+                    columns_to_list(std::mem::replace(&mut columns, fresh), &names, chunk_len)?;
+                let mut start = OwnedRealSexp::new(1)?;
+                start[0] = (rows_seen - chunk_len + 1) as f64;
+                let start: savvy::Result<savvy::Sexp> = start.into();
+
+                let mut args = FunctionArgs::new();
+                args.add("chunk", chunk)?;
+                args.add("start", start?)?;
+                callback.call(args)?;
+                chunk_len = 0;
+            }
+            if row.is_none() {
+                break;
+            }
+        }
+
+        // R integers are 32-bit, so row counts travel as doubles.
+        let mut total = OwnedRealSexp::new(1)?;
+        total[0] = rows_seen as f64;
+        total.into()
+    })
+}
+
 /// Stream a SAS7BDAT file into an on-disk sink.
 ///
 /// @param path Path to the input `.sas7bdat` file.
@@ -501,27 +611,78 @@ fn read_sas(path: &str) -> savvy::Result<savvy::Sexp> {
 /// @export
 #[savvy]
 fn write_sas(path: &str, sink: &str, output: &str) -> savvy::Result<()> {
-    let mut sas = SasReader::open(path).map_err(map_core_err)?;
-    let sink_kind = sink.trim().to_ascii_lowercase();
-    match sink_kind.as_str() {
-        "parquet" => {
-            let file =
-                File::create(output).map_err(|e| map_io_err("create parquet file", output, &e))?;
-            let mut writer = ParquetSink::new(file);
-            sas.stream_into(&mut writer).map_err(map_core_err)?;
-        }
-        "csv" => {
-            let file =
-                File::create(output).map_err(|e| map_io_err("create csv file", output, &e))?;
-            let buf = BufWriter::new(file);
-            let mut writer = CsvSink::new(buf);
-            sas.stream_into(&mut writer).map_err(map_core_err)?;
+    guarded(|| {
+        let mut sas = SasReader::open(path).map_err(map_core_err)?;
+        let sink_kind = sink.trim().to_ascii_lowercase();
+        match sink_kind.as_str() {
+            "parquet" => {
+                let file =
+                    File::create(output).map_err(|e| map_io_err("create parquet file", output, &e))?;
+                let mut writer = ParquetSink::new(file);
+                sas.stream_into(&mut writer).map_err(map_core_err)?;
+            }
+            "csv" => {
+                let file =
+                    File::create(output).map_err(|e| map_io_err("create csv file", output, &e))?;
+                let buf = BufWriter::new(file);
+                let mut writer = CsvSink::new(buf);
+                sas.stream_into(&mut writer).map_err(map_core_err)?;
+            }
+            other => {
+                return Err(savvy::Error::new(format!(
+                    "unsupported sink '{other}'. expected 'parquet' or 'csv'"
+                )));
+            }
         }
-        other => {
-            return Err(savvy::Error::new(format!(
-                "unsupported sink '{other}'. expected 'parquet' or 'csv'"
-            )));
+        Ok(())
+    })
+}
+
+/// Structural validation issues of a SAS7BDAT file as parallel columns.
+///
+/// Backs [sas_validate()], which wraps the result in a data frame.
+///
+/// @param path Path to a .sas7bdat file
+/// @return Named list with `page` (integer, zero-based, `NA` when not tied to
+///   a page), `type` (`"warning"` or `"error"`) and `message` columns, plus
+///   `expected_rows` and `rows_read` attributes.
+/// @keywords internal
+#[savvy]
+fn sas_validate_issues(path: &str) -> savvy::Result<savvy::Sexp> {
+    guarded(|| {
+        let report = validate_file(path);
+        let count = report.issues.len();
+
+        let mut pages = OwnedIntegerSexp::new(count)?;
+        let mut types = OwnedStringSexp::new(count)?;
+        let mut messages = OwnedStringSexp::new(count)?;
+        for (idx, issue) in report.issues.iter().enumerate() {
+            let page = issue
+                .page
+                .and_then(|page| i32::try_from(page).ok())
+                .unwrap_or_else(i32::na);
+            pages[idx] = page;
+            types.set_elt(idx, issue.severity.as_str())?;
+            messages.set_elt(idx, &issue.message)?;
         }
-    }
-    Ok(())
+
+        let mut out = OwnedListSexp::new(3, true)?;
+        out.set_name_and_value(0, "page", pages)?;
+        out.set_name_and_value(1, "type", types)?;
+        out.set_name_and_value(2, "message", messages)?;
+
+        // R integers are 32-bit, so row counts travel as doubles.
+        let mut expected_rows = OwnedRealSexp::new(1)?;
+        expected_rows[0] = report
+            .expected_rows
+            .map_or_else(f64::na, |rows| rows as f64);
+        let mut rows_read = OwnedRealSexp::new(1)?;
+        rows_read[0] = report.rows_read as f64;
+        let expected_rows: savvy::Result<savvy::Sexp> = expected_rows.into();
+        out.set_attrib("expected_rows", expected_rows?)?;
+        let rows_read: savvy::Result<savvy::Sexp> = rows_read.into();
+        out.set_attrib("rows_read", rows_read?)?;
+
+        out.into()
+    })
 }
@@ -1,11 +1,22 @@
-use sas7bdat::SasReader;
+use sas7bdat::{
+    SasReader,
+    parser::{ColumnKind, Utf8Arena},
+};
 use std::{fs::File, time::Instant};
 
 fn main() {
-    let path = std::env::args().nth(1).unwrap_or_else(|| {
-        eprintln!("Usage: sas7bdat-rustbench <path-to-sas7bdat>");
-        std::process::exit(1);
-    });
+    let mut args = std::env::args().skip(1);
+    let mut columnar = false;
+    let path = loop {
+        match args.next().as_deref() {
+            Some("--columnar") => columnar = true,
+            Some(path) => break path.to_owned(),
+            None => {
+                eprintln!("Usage: sas7bdat-rustbench [--columnar] <path-to-sas7bdat>");
+                std::process::exit(1);
+            }
+        }
+    };
 
     let file = File::open(&path).unwrap_or_else(|err| {
         eprintln!("Input file not found: {path} ({err})");
@@ -23,22 +34,56 @@ fn main() {
     });
 
     let start = Instant::now();
-    let mut row_count = 0usize;
-    let mut rows = reader.rows().unwrap_or_else(|err| {
+    let row_count = if columnar {
+        read_columnar(&mut reader)
+    } else {
+        read_rows(&mut reader)
+    }
+    .unwrap_or_else(|err| {
         eprintln!("sas7bdat error: {err}");
         std::process::exit(1);
     });
-    while let Some(row) = rows.try_next().unwrap_or_else(|err| {
-        eprintln!("sas7bdat error: {err}");
-        std::process::exit(1);
-    }) {
-        let _row_len = row.len();
-        row_count += 1;
-    }
     let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
 
     println!("File           : {path}");
+    println!(
+        "Mode           : {}",
+        if columnar { "columnar" } else { "rows" }
+    );
     println!("Rows processed : {row_count}");
     println!("Columns        : {column_count}");
     println!("Elapsed (ms)   : {elapsed_ms:.2}");
 }
+
+fn read_rows(reader: &mut SasReader<File>) -> sas7bdat::Result<usize> {
+    let mut row_count = 0usize;
+    let mut rows = reader.rows()?;
+    while let Some(row) = rows.try_next()? {
+        let _row_len = row.len();
+        row_count += 1;
+    }
+    Ok(row_count)
+}
+
+/// Decodes every column batch by batch, with character columns going through
+/// a reused [`Utf8Arena`] the way the Arrow and Parquet sinks consume them.
+/// Point it at files with very wide character columns to measure the
+/// string path in isolation.
+fn read_columnar(reader: &mut SasReader<File>) -> sas7bdat::Result<usize> {
+    let mut row_count = 0usize;
+    let mut arena = Utf8Arena::new();
+    let mut rows = reader.rows()?;
+    while let Some(batch) = rows.next_columnar_batch(0)? {
+        for column in (0..).map_while(|index| batch.column(index)) {
+            match column.kind() {
+                ColumnKind::Character => column.decode_utf8_into(&mut arena)?,
+                ColumnKind::Numeric(_) => {
+                    let _ = column.iter_numeric_bits().count();
+                }
+                ColumnKind::Bytes => {}
+            }
+        }
+        row_count += batch.row_count;
+    }
+    Ok(row_count)
+}
@@ -0,0 +1,370 @@
+mod table;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sas7bdat::{
+    CellValue, CsvSink, JsonlSink, ParquetSink, RowSelection, RowSink, SasReader, Utf8Sink,
+    dataset::{Compression, DatasetMetadata, Endianness, VariableKind},
+};
+use serde_json::{Value, json};
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "sas7bdat",
+    version,
+    about = "Inspect SAS7BDAT datasets and convert them to Parquet, CSV or JSON Lines"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print dataset metadata as JSON.
+    Inspect {
+        /// Input .sas7bdat file.
+        input: PathBuf,
+    },
+    /// Print the first rows as a table.
+    Head {
+        /// Input .sas7bdat file.
+        input: PathBuf,
+        /// Number of rows to print.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        rows: u64,
+        #[command(flatten)]
+        select: SelectArgs,
+        #[command(flatten)]
+        preview: PreviewArgs,
+    },
+    /// Print the last rows as a table.
+    Tail {
+        /// Input .sas7bdat file.
+        input: PathBuf,
+        /// Number of rows to print.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        rows: u64,
+        /// Keep only these columns, in this order (comma-separated).
+        #[arg(long, value_delimiter = ',', value_name = "NAME[,NAME]")]
+        columns: Option<Vec<String>>,
+        #[command(flatten)]
+        preview: PreviewArgs,
+    },
+    /// Convert a dataset to Parquet, CSV or JSON Lines.
+    Convert {
+        /// Input .sas7bdat file.
+        input: PathBuf,
+        /// Output format.
+        #[arg(long, value_enum)]
+        to: Format,
+        /// Output file; `-` writes CSV or JSON Lines to stdout. Defaults to
+        /// the input path with the format's extension.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Write at most N rows.
+        #[arg(long, value_name = "N")]
+        limit: Option<u64>,
+        /// Replace invalid UTF-8 so every string written is valid UTF-8,
+        /// with CHAR (default U+FFFD) standing in for each bad sequence.
+        #[arg(
+            long,
+            value_name = "CHAR",
+            num_args = 0..=1,
+            default_missing_value = "\u{FFFD}"
+        )]
+        strict_utf8: Option<char>,
+        #[command(flatten)]
+        select: SelectArgs,
+    },
+}
+
+#[derive(Args)]
+struct SelectArgs {
+    /// Keep only these columns, in this order (comma-separated).
+    #[arg(long, value_delimiter = ',', value_name = "NAME[,NAME]")]
+    columns: Option<Vec<String>>,
+    /// Skip the first N rows.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    offset: u64,
+}
+
+impl SelectArgs {
+    fn selection(&self, limit: Option<u64>) -> RowSelection {
+        let mut selection = RowSelection::new().skip_rows(self.offset);
+        if let Some(columns) = &self.columns {
+            selection = selection.column_names(columns.iter().cloned());
+        }
+        if let Some(limit) = limit {
+            selection = selection.max_rows(limit);
+        }
+        selection
+    }
+}
+
+#[derive(Args)]
+struct PreviewArgs {
+    /// Print JSON Lines instead of a table.
+    #[arg(long, conflicts_with = "csv")]
+    json: bool,
+    /// Print CSV instead of a table.
+    #[arg(long)]
+    csv: bool,
+    /// Cut table cells longer than this many characters.
+    #[arg(long, value_name = "CHARS", default_value_t = 40)]
+    max_width: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Parquet,
+    Csv,
+    Jsonl,
+}
+
+impl Format {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Inspect { input } => inspect(input),
+        Command::Head {
+            input,
+            rows,
+            select,
+            preview: args,
+        } => head(input, select, *rows, args),
+        Command::Tail {
+            input,
+            rows,
+            columns,
+            preview: args,
+        } => tail(input, columns.as_deref(), *rows, args),
+        Command::Convert {
+            input,
+            to,
+            output,
+            limit,
+            strict_utf8,
+            select,
+        } => convert(
+            input,
+            *to,
+            output.as_deref(),
+            &select.selection(*limit),
+            *strict_utf8,
+        ),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("sas7bdat: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn inspect(input: &Path) -> sas7bdat::Result<()> {
+    let sas = SasReader::open(input)?;
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &metadata_json(sas.metadata()))
+        .map_err(io::Error::from)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+fn head(input: &Path, select: &SelectArgs, rows: u64, args: &PreviewArgs) -> sas7bdat::Result<()> {
+    let mut sas = SasReader::open(input)?;
+    // With --offset the page index lets the window start at its first row.
+    if select.offset > 0 {
+        sas.build_index()?;
+    }
+    preview(
+        &mut sas,
+        &select.selection(Some(rows)),
+        select.columns.as_deref(),
+        args,
+    )
+}
+
+fn tail(
+    input: &Path,
+    columns: Option<&[String]>,
+    rows: u64,
+    args: &PreviewArgs,
+) -> sas7bdat::Result<()> {
+    let mut sas = SasReader::open(input)?;
+    // The page index counts the stored rows and lets the window start on the
+    // page holding the first of the last rows, so nothing before it is decoded.
+    let total = sas.build_index()?.rows();
+    let mut selection = RowSelection::new()
+        .skip_rows(total.saturating_sub(rows))
+        .max_rows(rows);
+    if let Some(columns) = columns {
+        selection = selection.column_names(columns.iter().cloned());
+    }
+    preview(&mut sas, &selection, columns, args)
+}
+
+fn preview(
+    sas: &mut SasReader<File>,
+    selection: &RowSelection,
+    columns: Option<&[String]>,
+    args: &PreviewArgs,
+) -> sas7bdat::Result<()> {
+    let stdout = io::stdout();
+    if args.json {
+        return sas.stream_selection_into(selection, &mut JsonlSink::new(stdout));
+    }
+    if args.csv {
+        return sas.stream_selection_into(selection, &mut CsvSink::new(stdout));
+    }
+
+    let projected = columns.is_some();
+    let metadata = sas.metadata();
+    let indices: Vec<usize> = match columns {
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                metadata
+                    .column_index(name)
+                    .ok_or_else(|| sas7bdat::Error::InvalidArgument {
+                        details: format!("column '{name}' not found in dataset").into(),
+                    })
+            })
+            .collect::<sas7bdat::Result<_>>()?,
+        None => (0..metadata.variables.len()).collect(),
+    };
+    let columns: Vec<table::Column> = indices
+        .iter()
+        .map(|&index| {
+            let variable = &metadata.variables[index];
+            table::Column {
+                name: variable.name.trim_end().to_owned(),
+                format: variable.format.clone(),
+                numeric: variable.kind == VariableKind::Numeric,
+            }
+        })
+        .collect();
+
+    let render_row = |row: &[CellValue<'_>]| -> Vec<String> {
+        row.iter()
+            .zip(&columns)
+            .map(|(value, column)| table::render(value, column.format.as_ref()))
+            .collect()
+    };
+    let mut rendered = Vec::new();
+    if projected {
+        let mut rows = sas.select_with(selection)?;
+        while let Some(row) = rows.try_next()? {
+            rendered.push(render_row(&row));
+        }
+    } else {
+        let mut rows = sas.rows_windowed(selection)?;
+        while let Some(row) = rows.try_next()? {
+            rendered.push(render_row(&row));
+        }
+    }
+    table::write_table(&mut stdout.lock(), &columns, &rendered, args.max_width)?;
+    Ok(())
+}
+
+fn convert(
+    input: &Path,
+    format: Format,
+    output: Option<&Path>,
+    selection: &RowSelection,
+    strict_utf8: Option<char>,
+) -> sas7bdat::Result<()> {
+    let mut sas = SasReader::open(input)?;
+    let default_output = input.with_extension(format.extension());
+    let output = output.unwrap_or(&default_output);
+    if output == Path::new("-") {
+        let stdout = io::stdout();
+        return match format {
+            Format::Csv => write_to(&mut sas, selection, CsvSink::new(stdout), strict_utf8),
+            Format::Jsonl => write_to(&mut sas, selection, JsonlSink::new(stdout), strict_utf8),
+            Format::Parquet => Err(sas7bdat::Error::Unsupported {
+                feature: "writing Parquet to stdout".into(),
+            }),
+        };
+    }
+
+    let file = File::create(output)?;
+    match format {
+        Format::Parquet => write_to(&mut sas, selection, ParquetSink::new(file), strict_utf8),
+        Format::Csv => write_to(&mut sas, selection, CsvSink::new(file), strict_utf8),
+        Format::Jsonl => write_to(&mut sas, selection, JsonlSink::new(file), strict_utf8),
+    }
+}
+
+fn write_to<S: RowSink>(
+    sas: &mut SasReader<File>,
+    selection: &RowSelection,
+    mut sink: S,
+    strict_utf8: Option<char>,
+) -> sas7bdat::Result<()> {
+    match strict_utf8 {
+        Some(replacement) => {
+            let mut sink = Utf8Sink::new(sink).with_replacement(replacement);
+            sas.stream_selection_into(selection, &mut sink)
+        }
+        None => sas.stream_selection_into(selection, &mut sink),
+    }
+}
+
+fn metadata_json(metadata: &DatasetMetadata) -> Value {
+    let columns: Vec<Value> = metadata
+        .variables
+        .iter()
+        .map(|variable| {
+            json!({
+                "index": variable.index,
+                "name": variable.name.trim_end(),
+                "label": variable.label,
+                "kind": match variable.kind {
+                    VariableKind::Numeric => "numeric",
+                    VariableKind::Character => "character",
+                },
+                "format": variable.format.as_ref().map(ToString::to_string),
+                "width": variable.storage_width,
+                "measure": variable.measure.as_str(),
+                "alignment": variable.alignment.as_str(),
+            })
+        })
+        .collect();
+    json!({
+        "table_name": metadata.table_name,
+        "file_label": metadata.file_label,
+        "file_encoding": metadata.file_encoding,
+        "row_count": metadata.row_count,
+        "column_count": metadata.column_count,
+        "compression": match &metadata.compression {
+            Compression::None => "none",
+            Compression::Row => "row",
+            Compression::Binary => "binary",
+            Compression::Unknown(_) => "unknown",
+            Compression::Other(signature) => signature.as_str(),
+        },
+        "endianness": match metadata.endianness {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        },
+        "created": metadata.timestamps.created.as_ref().map(ToString::to_string),
+        "modified": metadata.timestamps.modified.as_ref().map(ToString::to_string),
+        "columns": columns,
+    })
+}
@@ -0,0 +1,239 @@
+//! Plain-text rendering of preview rows for `head` and `tail`.
+//!
+//! Cells are rendered the way their SAS format would print them where the
+//! format is common enough to matter in a preview (`DATE9.`, `DATETIME.`,
+//! `COMMA12.2`, `PERCENT8.1`, `w.d`, ...); anything else falls back to a
+//! plain rendering of the decoded value.
+
+use sas7bdat::{CellValue, MissingValue, dataset::Format};
+use std::io::{self, Write};
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Header and alignment of one table column.
+pub struct Column {
+    pub name: String,
+    pub format: Option<Format>,
+    /// Numeric columns are right-aligned.
+    pub numeric: bool,
+}
+
+/// Renders `value` as its SAS format would display it.
+pub fn render(value: &CellValue<'_>, format: Option<&Format>) -> String {
+    let name = format.map_or_else(String::new, |format| format.name.to_ascii_uppercase());
+    let width = format.and_then(|format| format.width).unwrap_or(0);
+    match value {
+        CellValue::Float(number) => render_number(*number, &name, format),
+        CellValue::Int32(number) => render_number(f64::from(*number), &name, format),
+        CellValue::Int64(number) => render_integer(*number, &name, format),
+        CellValue::NumericString(text) => text.to_string(),
+        CellValue::Str(text) => text.trim_end().to_owned(),
+        CellValue::Bytes(bytes) => String::from_utf8_lossy(bytes).trim_end().to_owned(),
+        CellValue::Date(date) => {
+            let (year, month, day) = (date.year(), u8::from(date.month()), date.day());
+            render_date(year, month, day, &name, width)
+        }
+        CellValue::DateTime(datetime) => {
+            let (year, month, day) = (datetime.year(), u8::from(datetime.month()), datetime.day());
+            let time = format!(
+                "{:02}:{:02}:{:02}",
+                datetime.hour(),
+                datetime.minute(),
+                datetime.second()
+            );
+            if name.starts_with("DATETIME") {
+                format!("{}:{time}", render_date(year, month, day, "DATE", 9))
+            } else {
+                format!("{year:04}-{month:02}-{day:02}T{time}")
+            }
+        }
+        CellValue::Time(duration) => {
+            let sign = if duration.is_negative() { "-" } else { "" };
+            let seconds = duration.abs().whole_seconds();
+            format!(
+                "{sign}{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        }
+        CellValue::Missing(MissingValue::Tagged(tagged)) => match tagged.tag {
+            Some('_') => "._".to_owned(),
+            Some(tag) => format!(".{tag}"),
+            None => ".".to_owned(),
+        },
+        CellValue::Missing(_) => ".".to_owned(),
+    }
+}
+
+fn render_date(year: i32, month: u8, day: u8, name: &str, width: u16) -> String {
+    // Formats narrower than their four-digit-year width print two digits.
+    let short = |full_width: u16| (1..full_width).contains(&width);
+    let year_text = |full_width: u16| {
+        if short(full_width) {
+            format!("{:02}", year.rem_euclid(100))
+        } else {
+            format!("{year:04}")
+        }
+    };
+    if name.starts_with("DATE") {
+        let month = MONTHS[usize::from(month.saturating_sub(1)) % 12];
+        format!("{day:02}{month}{}", year_text(9))
+    } else if name.starts_with("MMDDYY") {
+        format!("{month:02}/{day:02}/{}", year_text(10))
+    } else if name.starts_with("DDMMYY") {
+        format!("{day:02}/{month:02}/{}", year_text(10))
+    } else {
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+fn render_number(value: f64, name: &str, format: Option<&Format>) -> String {
+    let decimals = format.and_then(|format| format.decimals).map(usize::from);
+    if name.starts_with("PERCENT") {
+        return format!("{:.*}%", decimals.unwrap_or(0), value * 100.0);
+    }
+    if name.starts_with("COMMA") || name.starts_with("DOLLAR") {
+        let grouped = group_thousands(&format!("{:.*}", decimals.unwrap_or(0), value));
+        return if name.starts_with("DOLLAR") {
+            grouped
+                .strip_prefix('-')
+                .map_or_else(|| format!("${grouped}"), |digits| format!("-${digits}"))
+        } else {
+            grouped
+        };
+    }
+    match decimals {
+        Some(decimals) if !name.starts_with("BEST") => format!("{value:.decimals$}"),
+        _ => {
+            // Unformatted numbers print as SAS's default `BEST12.`.
+            let width = format
+                .and_then(|format| format.width)
+                .filter(|_| name.starts_with("BEST"))
+                .map_or(12, usize::from);
+            render_best(value, width)
+        }
+    }
+}
+
+/// Shortest rendering of `value`, rounded to fit `width` characters when
+/// the integer part leaves room for decimals.
+fn render_best(value: f64, width: usize) -> String {
+    let plain = if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.0}")
+    } else {
+        value.to_string()
+    };
+    let integer_digits = plain.find('.').unwrap_or(plain.len());
+    if plain.len() <= width || integer_digits + 1 >= width {
+        return plain;
+    }
+    let decimals = width - integer_digits - 1;
+    let rounded = format!("{value:.decimals$}");
+    rounded
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+fn render_integer(value: i64, name: &str, format: Option<&Format>) -> String {
+    if name.starts_with("COMMA") || name.starts_with("DOLLAR") || name.starts_with("PERCENT") {
+        // Only the formats above scale or group digits; precision loss
+        // past 2^53 is invisible at preview widths.
+        #[allow(clippy::cast_precision_loss)]
+        return render_number(value as f64, name, format);
+    }
+    value.to_string()
+}
+
+fn group_thousands(number: &str) -> String {
+    let (sign, unsigned) = number
+        .strip_prefix('-')
+        .map_or(("", number), |digits| ("-", digits));
+    let (integer, fraction) = unsigned
+        .split_once('.')
+        .map_or((unsigned, None), |(integer, fraction)| {
+            (integer, Some(fraction))
+        });
+    let mut grouped = String::with_capacity(number.len() + integer.len() / 3);
+    grouped.push_str(sign);
+    for (position, digit) in integer.chars().enumerate() {
+        if position > 0 && (integer.len() - position) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+/// Writes `rows` under a header line, each column as wide as its widest
+/// cell but no wider than `max_width` characters; longer cells are cut
+/// short with `…`.
+pub fn write_table<W: Write>(
+    out: &mut W,
+    columns: &[Column],
+    rows: &[Vec<String>],
+    max_width: usize,
+) -> io::Result<()> {
+    let max_width = max_width.max(1);
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .filter_map(|row| row.get(index))
+                .map(|cell| cell.chars().count())
+                .fold(column.name.chars().count(), usize::max)
+                .min(max_width)
+        })
+        .collect();
+
+    let header: Vec<String> = columns.iter().map(|column| column.name.clone()).collect();
+    write_line(out, columns, &widths, &header, false)?;
+    let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    write_line(out, columns, &widths, &rule, false)?;
+    for row in rows {
+        write_line(out, columns, &widths, row, true)?;
+    }
+    Ok(())
+}
+
+fn write_line<W: Write>(
+    out: &mut W,
+    columns: &[Column],
+    widths: &[usize],
+    cells: &[String],
+    align: bool,
+) -> io::Result<()> {
+    let mut line = String::new();
+    for (index, (column, &width)) in columns.iter().zip(widths).enumerate() {
+        if index > 0 {
+            line.push_str("  ");
+        }
+        let cell = fit(cells.get(index).map_or("", String::as_str), width);
+        let padding = " ".repeat(width.saturating_sub(cell.chars().count()));
+        if align && column.numeric {
+            line.push_str(&padding);
+            line.push_str(&cell);
+        } else {
+            line.push_str(&cell);
+            line.push_str(&padding);
+        }
+    }
+    writeln!(out, "{}", line.trim_end())
+}
+
+fn fit(cell: &str, width: usize) -> String {
+    if cell.chars().count() <= width {
+        return cell.to_owned();
+    }
+    let mut cut: String = cell.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
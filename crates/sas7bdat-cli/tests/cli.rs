@@ -0,0 +1,127 @@
+use sas7bdat_test_support::common;
+use serde_json::Value;
+use std::process::{Command, Output};
+
+const FIXTURE: &str = "fixtures/raw_data/pandas/test1.sas7bdat";
+
+fn sas7bdat(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_sas7bdat"))
+        .args(args)
+        .output()
+        .expect("run sas7bdat");
+    assert!(
+        output.status.success(),
+        "sas7bdat {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn fixture() -> String {
+    common::fixture_path(FIXTURE).to_string_lossy().into_owned()
+}
+
+#[test]
+fn inspect_prints_metadata_json() {
+    let output = sas7bdat(&["inspect", &fixture()]);
+    let metadata: Value = serde_json::from_slice(&output.stdout).expect("json");
+    assert_eq!(metadata["row_count"], 10);
+    let columns = metadata["columns"].as_array().expect("columns");
+    assert_eq!(metadata["column_count"], columns.len());
+    assert_eq!(columns[0]["name"], "Column1");
+    assert_eq!(columns[0]["measure"], "unknown");
+    assert_eq!(columns[0]["alignment"], "unknown");
+}
+
+#[test]
+fn head_prints_projected_csv_rows() {
+    let output = sas7bdat(&[
+        "head",
+        &fixture(),
+        "-n",
+        "3",
+        "--offset",
+        "1",
+        "--columns",
+        "Column2,Column1",
+        "--csv",
+    ]);
+    let stdout = String::from_utf8(output.stdout).expect("utf-8");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4, "{stdout}");
+    assert_eq!(lines[0], "Column2,Column1");
+}
+
+#[test]
+fn head_prints_a_formatted_table() {
+    let output = sas7bdat(&[
+        "head",
+        &fixture(),
+        "-n",
+        "2",
+        "--columns",
+        "Column1,Column4,Column2",
+    ]);
+    let stdout = String::from_utf8(output.stdout).expect("utf-8");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4, "{stdout}");
+    assert_eq!(lines[0], "Column1  Column4     Column2");
+    assert_eq!(lines[1], "-------  ----------  -------");
+    // Column4 carries MMDDYY10., so dates print the way SAS shows them.
+    assert_eq!(lines[2], "  0.636  12/10/1965  pear");
+}
+
+#[test]
+fn tail_prints_the_last_rows() {
+    let all = sas7bdat(&["head", &fixture(), "-n", "10", "--json"]).stdout;
+    let all: Vec<String> = String::from_utf8(all)
+        .expect("utf-8")
+        .lines()
+        .map(str::to_owned)
+        .collect();
+    assert_eq!(all.len(), 10);
+
+    let tail = sas7bdat(&["tail", &fixture(), "-n", "3", "--json"]).stdout;
+    let tail = String::from_utf8(tail).expect("utf-8");
+    assert_eq!(tail.lines().collect::<Vec<_>>(), all[7..]);
+
+    let table = sas7bdat(&["tail", &fixture(), "-n", "20", "--columns", "Column2"]).stdout;
+    let table = String::from_utf8(table).expect("utf-8");
+    assert_eq!(table.lines().count(), 12, "{table}");
+    let first: Value = serde_json::from_str(&all[0]).expect("json row");
+    assert_eq!(
+        table.lines().nth(2).map(str::trim_end),
+        first["Column2"].as_str()
+    );
+}
+
+#[test]
+fn convert_writes_each_format() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    for format in ["parquet", "csv", "jsonl"] {
+        let out = dir.path().join(format!("test1.{format}"));
+        sas7bdat(&[
+            "convert",
+            &fixture(),
+            "--to",
+            format,
+            "-o",
+            out.to_str().expect("utf-8 path"),
+            "--limit",
+            "5",
+        ]);
+        assert!(
+            out.metadata().expect("output written").len() > 0,
+            "{format}"
+        );
+    }
+
+    let jsonl = std::fs::read_to_string(dir.path().join("test1.jsonl")).expect("read jsonl");
+    assert_eq!(jsonl.lines().count(), 5);
+
+    let stdout = sas7bdat(&["convert", &fixture(), "--to", "jsonl", "-o", "-"]).stdout;
+    assert_eq!(
+        String::from_utf8(stdout).expect("utf-8").lines().count(),
+        10
+    );
+}
@@ -1,17 +1,8 @@
 #![allow(dead_code, clippy::pedantic)]
-use sas7bdat::CellValue;
-use serde_json::{Value as JsonValue, json};
+pub use sas7bdat::testing::value_to_json;
+use sas7bdat::{CellValue, RowSink, SinkContext};
 use std::path::{Path, PathBuf};
-use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time};
-
-const SECONDS_PER_DAY: f64 = 86_400.0;
-
-fn sas_epoch() -> PrimitiveDateTime {
-    PrimitiveDateTime::new(
-        Date::from_calendar_date(1960, Month::January, 1).expect("valid SAS epoch"),
-        Time::MIDNIGHT,
-    )
-}
+use time::{Duration, OffsetDateTime};
 
 pub fn repo_root() -> PathBuf {
     let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -23,30 +14,34 @@ pub fn fixture_path(path: impl AsRef<Path>) -> PathBuf {
     repo_root().join(path)
 }
 
-pub fn value_to_json(value: &CellValue<'_>) -> JsonValue {
-    match value {
-        CellValue::Float(v) => json!({ "kind": "number", "value": *v }),
-        CellValue::Int32(v) => json!({ "kind": "number", "value": *v as f64 }),
-        CellValue::Int64(v) => json!({ "kind": "number", "value": *v as f64 }),
-        CellValue::NumericString(s) => json!({ "kind": "string", "value": s }),
-        CellValue::Str(s) => json!({ "kind": "string", "value": s }),
-        CellValue::Bytes(b) => json!({
-            "kind": "bytes",
-            "value": b.iter().copied().collect::<Vec<u8>>()
-        }),
-        CellValue::DateTime(dt) => json!({
-            "kind": "datetime",
-            "value": datetime_to_seconds(dt)
-        }),
-        CellValue::Date(dt) => json!({
-            "kind": "date",
-            "value": datetime_to_days(dt)
-        }),
-        CellValue::Time(duration) => json!({
-            "kind": "time",
-            "value": duration_to_seconds(duration)
-        }),
-        CellValue::Missing(_) => json!({ "kind": "missing", "value": null }),
+/// Row sink that keeps the column names and every row it receives.
+#[derive(Default)]
+pub struct CollectSink {
+    pub names: Vec<String>,
+    pub rows: Vec<Vec<CellValue<'static>>>,
+}
+
+impl RowSink for CollectSink {
+    fn begin(&mut self, context: SinkContext<'_>) -> sas7bdat::Result<()> {
+        assert_eq!(context.metadata.variables.len(), context.columns.len());
+        self.names = context
+            .metadata
+            .variables
+            .iter()
+            .map(|variable| variable.name.clone())
+            .collect();
+        self.rows.clear();
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> sas7bdat::Result<()> {
+        self.rows
+            .push(row.iter().cloned().map(CellValue::into_owned).collect());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> sas7bdat::Result<()> {
+        Ok(())
     }
 }
 
@@ -93,16 +88,3 @@ pub fn round_to_millisecond(dt: &OffsetDateTime) -> OffsetDateTime {
     let new_nanos = (millis * 1_000_000) as u32;
     adjusted.replace_nanosecond(new_nanos).unwrap_or(*dt)
 }
-
-fn datetime_to_seconds(dt: &OffsetDateTime) -> f64 {
-    let epoch = sas_epoch().assume_utc();
-    (*dt - epoch).whole_microseconds() as f64 / 1_000_000.0
-}
-
-fn datetime_to_days(dt: &OffsetDateTime) -> f64 {
-    datetime_to_seconds(dt) / SECONDS_PER_DAY
-}
-
-fn duration_to_seconds(duration: &Duration) -> f64 {
-    duration.whole_microseconds() as f64 / 1_000_000.0
-}
@@ -4,10 +4,7 @@ use crate::{
     reference::{Snapshot, relative_to_manifest},
 };
 use csv::ReaderBuilder;
-use sas7bdat::{
-    SasReader,
-    parser::{ColumnKind, NumericKind, parse_metadata},
-};
+use sas7bdat::parser::{ColumnKind, NumericKind, parse_metadata};
 use serde_json::json;
 use std::{
     fs,
@@ -58,33 +55,8 @@ pub fn snapshot_name(path: &Path) -> String {
 }
 
 pub fn collect_snapshot(path: &Path) -> Snapshot {
-    let display = path.display();
-    let mut sas =
-        SasReader::open(path).unwrap_or_else(|err| panic!("failed to open {}: {}", display, err));
-    let metadata = sas.metadata().clone();
-    let columns = metadata
-        .variables
-        .iter()
-        .map(|var| var.name.trim_end().to_string())
-        .collect();
-
-    let mut rows_iter = sas
-        .rows()
-        .unwrap_or_else(|err| panic!("failed to create row iterator for {}: {}", display, err));
-    let mut rows = Vec::new();
-    while let Some(row) = rows_iter
-        .try_next()
-        .unwrap_or_else(|err| panic!("error reading row from {}: {}", display, err))
-    {
-        let json_row = row.iter().map(common::value_to_json).collect();
-        rows.push(json_row);
-    }
-
-    Snapshot {
-        columns,
-        row_count: metadata.row_count as usize,
-        rows,
-    }
+    sas7bdat::testing::collect_snapshot(path)
+        .unwrap_or_else(|err| panic!("failed to snapshot {}: {}", path.display(), err))
 }
 
 static READSTAT_AVAILABLE: OnceLock<bool> = OnceLock::new();
@@ -376,13 +348,14 @@ fn csv_field_to_json(
 ) -> serde_json::Value {
     if field.is_empty() || field == "." {
         return match kind {
-            ColumnKind::Character => json!({ "kind": "string", "value": "" }),
+            ColumnKind::Character | ColumnKind::Bytes => json!({ "kind": "string", "value": "" }),
             ColumnKind::Numeric(_) => json!({ "kind": "missing", "value": null }),
         };
     }
 
     match kind {
         ColumnKind::Character => json!({ "kind": "string", "value": field }),
+        ColumnKind::Bytes => json!({ "kind": "bytes", "value": field }),
         ColumnKind::Numeric(numeric_kind) => {
             let parsed = field.parse::<f64>().unwrap_or_else(|err| {
                 panic!(
@@ -391,7 +364,7 @@ fn csv_field_to_json(
                 )
             });
             let kind_label = match numeric_kind {
-                NumericKind::Double => "number",
+                NumericKind::Double | NumericKind::Float | NumericKind::Text => "number",
                 NumericKind::Date => "date",
                 NumericKind::DateTime => "datetime",
                 NumericKind::Time => "time",
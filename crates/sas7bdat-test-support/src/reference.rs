@@ -1,5 +1,4 @@
 use crate::common;
-use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::{
     fs, io,
@@ -11,14 +10,19 @@ pub const SNAPSHOT_FLOAT_TOLERANCE: f64 = 1e-4;
 pub const SNAPSHOT_DATE_TOLERANCE: f64 = 1e-4;
 pub const SNAPSHOT_TIME_TOLERANCE: f64 = 1e-4;
 pub const SNAPSHOT_DATETIME_TOLERANCE: f64 = 1.5;
+/// ReadStat reports raw SAS seconds, so datetimes must agree to the microsecond.
+pub const SNAPSHOT_DATETIME_READSTAT_TOLERANCE: f64 = 1e-6;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Snapshot {
-    pub columns: Vec<String>,
-    pub row_count: usize,
-    pub rows: Vec<Vec<JsonValue>>,
+fn datetime_tolerance(parser: &str) -> f64 {
+    if parser == "readstat-cli" {
+        SNAPSHOT_DATETIME_READSTAT_TOLERANCE
+    } else {
+        SNAPSHOT_DATETIME_TOLERANCE
+    }
 }
 
+pub use sas7bdat::testing::Snapshot;
+
 struct RelaxStats {
     column_decode_upgrade: AtomicUsize,
     kind_upgrade: AtomicUsize,
@@ -251,7 +255,7 @@ fn compare_cell(
             let tolerance = match actual_kind {
                 "date" => SNAPSHOT_DATE_TOLERANCE,
                 "time" => SNAPSHOT_TIME_TOLERANCE,
-                "datetime" => SNAPSHOT_DATETIME_TOLERANCE,
+                "datetime" => datetime_tolerance(parser),
                 _ => SNAPSHOT_FLOAT_TOLERANCE,
             };
             let actual_value = numeric_value(actual, row_index, column_index, relative_key, parser);
@@ -313,7 +317,7 @@ fn compare_cell(
                 "number" => SNAPSHOT_FLOAT_TOLERANCE,
                 "date" => SNAPSHOT_DATE_TOLERANCE,
                 "time" => SNAPSHOT_TIME_TOLERANCE,
-                "datetime" => SNAPSHOT_DATETIME_TOLERANCE,
+                "datetime" => datetime_tolerance(parser),
                 _ => unreachable!(),
             };
             let actual_value = numeric_value(actual, row_index, column_index, relative_key, parser);
@@ -0,0 +1,107 @@
+//! WebAssembly bindings for previewing SAS7BDAT files in the browser.
+//!
+//! Build with `wasm-pack build crates/sas7bdat-wasm --target web`, then hand
+//! the bytes of a dropped file to `new SasFile(bytes)`:
+//!
+//! ```js
+//! const file = new SasFile(new Uint8Array(await blob.arrayBuffer()));
+//! console.log(file.describe(false));
+//! const rows = file.preview(20).trim().split("\n").map(JSON.parse);
+//! ```
+
+// `#[wasm_bindgen]` rejects `const` methods.
+#![allow(clippy::missing_const_for_fn)]
+
+use bytes::Bytes;
+use sas7bdat::{JsonlSink, RowSelection, SasReader};
+use std::io::Cursor;
+use wasm_bindgen::prelude::*;
+
+/// A SAS7BDAT file read from memory.
+#[wasm_bindgen]
+pub struct SasFile {
+    reader: SasReader<Cursor<Bytes>>,
+}
+
+#[wasm_bindgen]
+impl SasFile {
+    /// Parses the metadata of the file held in `data`, which is copied.
+    ///
+    /// # Errors
+    ///
+    /// Throws if the metadata cannot be parsed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Result<Self, JsError> {
+        let reader = SasReader::from_bytes(data.to_vec())?;
+        Ok(Self { reader })
+    }
+
+    /// Row count from the file header, as a JavaScript number.
+    #[wasm_bindgen(getter, js_name = rowCount)]
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn row_count(&self) -> f64 {
+        self.reader.metadata().row_count as f64
+    }
+
+    #[wasm_bindgen(getter, js_name = columnCount)]
+    #[must_use]
+    pub fn column_count(&self) -> u32 {
+        self.reader.metadata().column_count
+    }
+
+    #[wasm_bindgen(js_name = columnNames)]
+    #[must_use]
+    pub fn column_names(&self) -> Vec<String> {
+        self.reader
+            .metadata()
+            .variables
+            .iter()
+            .map(|variable| variable.name.trim_end().to_owned())
+            .collect()
+    }
+
+    /// PROC CONTENTS-style summary of the dataset, as plain text or as
+    /// Markdown tables.
+    #[must_use]
+    pub fn describe(&self, markdown: bool) -> String {
+        let description = self.reader.metadata().describe();
+        if markdown {
+            description.markdown().to_string()
+        } else {
+            description.to_string()
+        }
+    }
+
+    /// Decodes the first `rows` rows as JSON Lines, one object per row keyed
+    /// by column name.
+    ///
+    /// # Errors
+    ///
+    /// Throws if a row cannot be decoded.
+    pub fn preview(&mut self, rows: u32) -> Result<String, JsError> {
+        let selection = RowSelection::new().max_rows(u64::from(rows));
+        let mut sink = JsonlSink::new(Vec::new());
+        self.reader.stream_selection_into(&selection, &mut sink)?;
+        Ok(String::from_utf8(sink.into_inner()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sas7bdat_test_support::common;
+
+    #[test]
+    fn previews_rows_from_memory() {
+        let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+        let data = std::fs::read(path).expect("read fixture");
+        let mut file = SasFile::new(&data).unwrap_or_else(|_| panic!("parse fixture"));
+
+        assert_eq!(file.column_count(), 100);
+        assert_eq!(file.column_names()[1], "Column2");
+        let preview = file.preview(3).unwrap_or_else(|_| panic!("decode rows"));
+        assert_eq!(preview.lines().count(), 3);
+        assert!(preview.starts_with('{'));
+    }
+}
@@ -0,0 +1,101 @@
+//! Compares the columnar decode layouts on a few fixtures.
+//!
+//! `RowMajor` is the decoder batches used before `ColumnMajor` was added, so
+//! the two columns of the report show what the staging copy costs or saves.
+//! Run with `cargo bench --bench decode_layout`, optionally followed by
+//! `-- <file.sas7bdat>...` to time other files.
+
+use sas7bdat::{
+    SasReader,
+    parser::{ColumnKind, DecodeLayout, Utf8Arena},
+};
+use sas7bdat_test_support::common;
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+const FIXTURES: &[&str] = &[
+    "fixtures/raw_data/pandas/many_columns.sas7bdat",
+    "fixtures/raw_data/pandas/productsales.sas7bdat",
+    "fixtures/raw_data/pandas/airline.sas7bdat",
+];
+const ROUNDS: usize = 20;
+const LAYOUTS: [DecodeLayout; 3] = [
+    DecodeLayout::RowMajor,
+    DecodeLayout::ColumnMajor,
+    DecodeLayout::Auto,
+];
+
+fn main() {
+    let mut paths: Vec<PathBuf> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(PathBuf::from)
+        .collect();
+    if paths.is_empty() {
+        paths = FIXTURES.iter().map(common::fixture_path).collect();
+    }
+
+    println!(
+        "{:<32} {:>12} {:>12} {:>12}",
+        "file", "row-major", "column-major", "auto"
+    );
+    for path in paths {
+        let data = fs::read(&path).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        let timings = LAYOUTS.map(|layout| fastest_round(&data, layout));
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        println!(
+            "{name:<32} {:>10.3}ms {:>10.3}ms {:>10.3}ms",
+            millis(timings[0]),
+            millis(timings[1]),
+            millis(timings[2]),
+        );
+    }
+}
+
+/// Best of [`ROUNDS`] full decodes, which filters out scheduler noise.
+fn fastest_round(data: &[u8], layout: DecodeLayout) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let mut sas = SasReader::from_bytes(data.to_vec()).expect("parse metadata");
+            let start = Instant::now();
+            decode_all(&mut sas, layout).expect("decode rows");
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Materialises every column of every batch, the way the Arrow and Parquet
+/// sinks consume them.
+fn decode_all<R: std::io::Read + std::io::Seek>(
+    sas: &mut SasReader<R>,
+    layout: DecodeLayout,
+) -> sas7bdat::Result<usize> {
+    let columns = usize::try_from(sas.metadata().column_count).unwrap_or(usize::MAX);
+    let mut arena = Utf8Arena::new();
+    let mut rows = sas.rows()?;
+    let mut row_count = 0;
+    while let Some(batch) = rows.next_batch_with_layout(layout, columns, 0)? {
+        for column in (0..).map_while(|index| batch.column(index)) {
+            match column.kind() {
+                ColumnKind::Character => column.decode_utf8_into(&mut arena)?,
+                ColumnKind::Numeric(_) => {
+                    let _ = column.iter_numeric_bits().count();
+                }
+                ColumnKind::Bytes => {}
+            }
+        }
+        row_count += batch.row_count;
+    }
+    Ok(row_count)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,195 @@
+//! Skeleton of a throughput-oriented [`RowSink`].
+//!
+//! `write_streaming_row` decodes each cell straight from the borrowed page
+//! buffer and appends it to a line buffer that is reused for every row, so
+//! the sink allocates nothing per row. A sink that only implements
+//! `write_row` instead gets every row materialised into a `Vec<CellValue>`
+//! first, and is rejected by [`SasReader::stream_into_low_memory`].
+//!
+//! The sink writes tab-separated values without quoting; swap `push_value`
+//! for a real encoder.
+
+use sas7bdat::{CellValue, Error, Result, RowSink, SasReader, SinkContext, parser::StreamingRow};
+use std::{
+    env,
+    io::{self, BufWriter, Write},
+};
+
+struct TsvSink<W: Write> {
+    writer: W,
+    /// Reused across rows; cleared, never shrunk.
+    line: Vec<u8>,
+    columns: usize,
+}
+
+impl<W: Write> TsvSink<W> {
+    const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            line: Vec::new(),
+            columns: 0,
+        }
+    }
+
+    fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len == self.columns {
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument {
+                details: format!("row length {len} does not match expected {}", self.columns)
+                    .into(),
+            })
+        }
+    }
+
+    fn push_value(&mut self, index: usize, value: &CellValue<'_>) -> Result<()> {
+        if index > 0 {
+            self.line.push(b'\t');
+        }
+        match value {
+            CellValue::Missing(_) => {}
+            CellValue::Float(v) => write!(self.line, "{v}")?,
+            CellValue::Int32(v) => write!(self.line, "{v}")?,
+            CellValue::Int64(v) => write!(self.line, "{v}")?,
+            CellValue::Str(text) | CellValue::NumericString(text) => {
+                self.line.extend_from_slice(text.as_bytes());
+            }
+            CellValue::Bytes(bytes) => self.line.extend_from_slice(bytes),
+            CellValue::Date(date) => write!(self.line, "{}", date.date())?,
+            CellValue::DateTime(datetime) => write!(self.line, "{datetime}")?,
+            CellValue::Time(time) => write!(self.line, "{time}")?,
+        }
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<()> {
+        self.line.push(b'\n');
+        self.writer.write_all(&self.line)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for TsvSink<W> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        self.columns = context.columns.len();
+        self.line.clear();
+        for (index, variable) in context.metadata.variables.iter().enumerate() {
+            self.push_value(index, &CellValue::Str(variable.name.trim_end().into()))?;
+        }
+        self.end_line()
+    }
+
+    /// Fallback for callers that hand over decoded rows.
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        self.check_len(row.len())?;
+        self.line.clear();
+        for (index, value) in row.iter().enumerate() {
+            self.push_value(index, value)?;
+        }
+        self.end_line()
+    }
+
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        self.check_len(row.len())?;
+        self.line.clear();
+        for (index, cell) in row.iter().enumerate() {
+            let cell = cell?;
+            // Missing cells are recognised from their raw bytes, without
+            // decoding.
+            if cell.is_missing() {
+                self.push_value(index, &CellValue::Str("".into()))?;
+            } else {
+                self.push_value(index, &cell.decode_value()?)?;
+            }
+        }
+        self.end_line()
+    }
+
+    fn streams_borrowed_rows(&self) -> bool {
+        true
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args_os();
+    let program = args
+        .next()
+        .and_then(|os| os.into_string().ok())
+        .unwrap_or_else(|| "custom_sink".to_owned());
+    let Some(path) = args.next() else {
+        eprintln!("usage: {program} <path-to-sas7bdat>");
+        std::process::exit(2);
+    };
+
+    let mut sas = SasReader::open(path)?;
+    let mut sink = TsvSink::new(BufWriter::new(io::stdout().lock()));
+    sas.stream_into_low_memory(&mut sink)?;
+    sink.into_inner().flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sas7bdat_test_support::common;
+    use std::fs::File;
+
+    /// Forwards everything but `write_streaming_row`, so rows take the
+    /// materialising default.
+    struct Materialized<S>(S);
+
+    impl<S: RowSink> RowSink for Materialized<S> {
+        fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+            self.0.begin(context)
+        }
+
+        fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+            self.0.write_row(row)
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            self.0.finish()
+        }
+    }
+
+    fn open_test1() -> SasReader<File> {
+        let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+        SasReader::open(path).expect("open reader")
+    }
+
+    #[test]
+    fn streaming_rows_match_the_materialised_fallback() {
+        let mut fast = TsvSink::new(Vec::new());
+        open_test1()
+            .stream_into_low_memory(&mut fast)
+            .expect("stream borrowed rows");
+        let mut slow = Materialized(TsvSink::new(Vec::new()));
+        open_test1()
+            .stream_into(&mut slow)
+            .expect("stream materialised rows");
+
+        let fast = fast.into_inner();
+        assert_eq!(fast, slow.0.into_inner());
+        let text = String::from_utf8(fast).expect("utf-8 output");
+        // Header plus ten rows.
+        assert_eq!(text.lines().count(), 11);
+        let header = text.lines().next().expect("header line");
+        assert!(header.starts_with("Column1\tColumn2\t"));
+    }
+
+    #[test]
+    fn low_memory_streaming_rejects_materialising_sinks() {
+        let mut slow = Materialized(TsvSink::new(Vec::new()));
+        let result = open_test1().stream_into_low_memory(&mut slow);
+        assert!(matches!(result, Err(Error::Unsupported { .. })));
+    }
+}
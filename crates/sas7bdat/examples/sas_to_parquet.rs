@@ -17,7 +17,7 @@ const ZIP_URL: &str = "https://www2.census.gov/programs-surveys/ahs/2013/AHS%202
 const DEFAULT_OUTPUT: &str = "ahs2013n.parquet";
 const ZIP_URL_ENV: &str = "AHS_ZIP_URL";
 const ZIP_PATH_ENV: &str = "AHS_ZIP_PATH";
-const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_mins(5);
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "hotpath")]
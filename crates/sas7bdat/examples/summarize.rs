@@ -30,7 +30,7 @@ struct DatasetSummary {
     columns: Vec<ColumnSummary>,
 }
 
-const fn variable_kind_name(kind: &VariableKind) -> &'static str {
+const fn variable_kind_name(kind: VariableKind) -> &'static str {
     match kind {
         VariableKind::Numeric => "numeric",
         VariableKind::Character => "character",
@@ -72,7 +72,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         .iter()
         .enumerate()
         .map(|(index, variable)| {
-            let kind = variable_kind_name(&variable.kind);
+            let kind = variable_kind_name(variable.kind);
             ColumnSummary {
                 index,
                 name: variable.name.clone(),
@@ -1,14 +1,17 @@
 use clap::{ArgAction, Parser, ValueEnum};
 use rayon::prelude::*;
 use sas7bdat::{
-    CellValue, ColumnarSink, CsvSink, ParquetSink, RowSink, SasReader,
-    dataset::DatasetMetadata,
+    BatchSizing, CellValue, ColumnarSink, CsvSink, HttpOptions, MetadataReadOptions, ParquetSink,
+    RowSink, SasReader,
+    dataset::{DatasetMetadata, compare_schemas},
     logger::{log_error, set_log_file, set_log_prefix},
-    parser::ColumnInfo,
+    parser::{ColumnInfo, DecodeLayout, TimestampRounding},
 };
 use std::{
+    fmt::Write as _,
     fs::File,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 use walkdir::WalkDir;
 
@@ -23,13 +26,23 @@ struct Cli {
     #[command(flatten)]
     convert: ConvertArgs,
 
-    /// Inspect dataset metadata and print a summary.
+    /// Inspect dataset metadata and print a summary. Accepts http(s) URLs, and
+    /// s3, gs and az URLs in builds with object store support.
     #[arg(long, value_name = "FILE", help_heading = "Inspect")]
     inspect: Option<PathBuf>,
 
     /// Emit JSON instead of human readable output (inspect only).
     #[arg(long, requires = "inspect", help_heading = "Inspect")]
     inspect_json: bool,
+
+    /// Emit Markdown tables instead of plain text (inspect only).
+    #[arg(
+        long,
+        requires = "inspect",
+        conflicts_with = "inspect_json",
+        help_heading = "Inspect"
+    )]
+    inspect_markdown: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
@@ -39,9 +52,42 @@ enum SinkKind {
     Tsv,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum DecodeLayoutArg {
+    Auto,
+    RowMajor,
+    ColumnMajor,
+}
+
+impl From<DecodeLayoutArg> for DecodeLayout {
+    fn from(value: DecodeLayoutArg) -> Self {
+        match value {
+            DecodeLayoutArg::Auto => Self::Auto,
+            DecodeLayoutArg::RowMajor => Self::RowMajor,
+            DecodeLayoutArg::ColumnMajor => Self::ColumnMajor,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum TimestampRoundingArg {
+    Truncate,
+    HalfEven,
+}
+
+impl From<TimestampRoundingArg> for TimestampRounding {
+    fn from(value: TimestampRoundingArg) -> Self {
+        match value {
+            TimestampRoundingArg::Truncate => Self::Truncate,
+            TimestampRoundingArg::HalfEven => Self::HalfEven,
+        }
+    }
+}
+
 #[derive(Parser, Clone)]
 struct ConvertArgs {
-    /// Input files or directories (recurses directories).
+    /// Input files, directories (recursed), or http(s) URLs of files; s3, gs
+    /// and az URLs too in builds with object store support.
     #[arg(
         required_unless_present = "inspect",
         value_name = "PATH",
@@ -87,12 +133,14 @@ struct ConvertArgs {
     )]
     column_indices: Option<Vec<usize>>,
 
-    /// Optional value-label catalog (.sas7bcat) to load.
+    /// Value-label catalog (.sas7bcat) to load; repeat to search several,
+    /// the first defining a format winning as with SAS `FMTSEARCH=`.
     #[arg(long, value_name = "FILE", help_heading = "Input")]
-    catalog: Option<PathBuf>,
+    catalog: Vec<PathBuf>,
 }
 
 #[derive(Parser, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 struct OutputOptions {
     /// Output directory (computed file names).
     #[arg(
@@ -137,6 +185,14 @@ struct OutputOptions {
     )]
     _no_headers: bool,
 
+    /// Write strict RFC 4180 CSV: CRLF line endings and quoted fields where needed.
+    #[arg(long, help_heading = "Output")]
+    rfc4180: bool,
+
+    /// Start CSV/TSV output with `#` comment lines giving each column's type, format and label.
+    #[arg(long, help_heading = "Output")]
+    metadata_comments: bool,
+
     /// Parquet row group size (rows). If unset, uses the library's heuristic.
     #[arg(long, value_name = "ROWS", help_heading = "Parquet")]
     parquet_row_group_size: Option<usize>,
@@ -145,6 +201,10 @@ struct OutputOptions {
     #[arg(long, value_name = "BYTES", help_heading = "Parquet")]
     parquet_target_bytes: Option<usize>,
 
+    /// Rounding for sub-microsecond timestamp and time precision: truncate or half-even.
+    #[arg(long, value_enum, default_value_t = TimestampRoundingArg::Truncate, help_heading = "Parquet")]
+    timestamp_rounding: TimestampRoundingArg,
+
     /// Flatten outputs into a single directory instead of mirroring input tree.
     #[arg(long, help_heading = "Output")]
     flatten: bool,
@@ -159,6 +219,23 @@ struct ExecutionOptions {
     /// Stop on first error.
     #[arg(long, help_heading = "Execution")]
     fail_fast: bool,
+
+    /// Batch decode layout for Parquet output: auto, row-major, or column-major.
+    #[arg(long, value_enum, default_value_t = DecodeLayoutArg::Auto, help_heading = "Execution")]
+    decode_layout: DecodeLayoutArg,
+
+    /// Rows per decoded batch. If unset, batches are sized from the row width.
+    #[arg(
+        long,
+        value_name = "ROWS",
+        conflicts_with = "batch_target_bytes",
+        help_heading = "Execution"
+    )]
+    batch_rows: Option<usize>,
+
+    /// Target bytes of row data per decoded batch when sizing from the row width.
+    #[arg(long, value_name = "BYTES", help_heading = "Execution")]
+    batch_target_bytes: Option<usize>,
 }
 
 #[derive(Parser, Clone)]
@@ -166,6 +243,16 @@ struct ValidationOptions {
     /// Enforce strict date/time conversion (fail on out-of-range or malformed values).
     #[arg(long, help_heading = "Validation")]
     strict_dates: bool,
+
+    /// Read rows until the data pages run out instead of stopping at the
+    /// header row count, for files whose header understates their rows.
+    #[arg(long, help_heading = "Validation")]
+    ignore_row_count: bool,
+
+    /// Check that every input has the same columns and column types before
+    /// converting any of them; all mismatches are reported and nothing is written.
+    #[arg(long, help_heading = "Validation")]
+    check_schemas: bool,
 }
 
 #[derive(Parser, Clone)]
@@ -175,7 +262,6 @@ struct LoggingOptions {
     log_file: Option<PathBuf>,
 }
 
-const DEFAULT_COLUMNAR_BATCH_ROWS: usize = 4096;
 const COLUMNAR_ROW_GROUP_MULTIPLIER: usize = 16;
 
 #[derive(Parser, Clone)]
@@ -184,6 +270,9 @@ struct InspectArgs {
     /// Emit JSON instead of human readable output.
     #[arg(long, hide = true)]
     json: bool,
+    /// Emit Markdown instead of plain text.
+    #[arg(long, hide = true)]
+    markdown: bool,
 }
 
 type AnyError = Box<dyn std::error::Error + Send + Sync>;
@@ -204,6 +293,7 @@ fn main() -> Result<(), AnyError> {
         let args = InspectArgs {
             input: path,
             json: cli.inspect_json,
+            markdown: cli.inspect_markdown,
         };
         run_inspect(&args)
     } else {
@@ -227,6 +317,9 @@ fn run_convert(args: &ConvertArgs) -> Result<(), AnyError> {
     if args.output.out.is_some() && files.len() != 1 {
         return Err("--out requires a single input".into());
     }
+    if args.validation.check_schemas {
+        check_schemas(&files)?;
+    }
 
     let mut tasks: Vec<(PathBuf, PathBuf, PathBuf)> = Vec::with_capacity(files.len());
     if let Some(ref out) = args.output.out {
@@ -239,30 +332,19 @@ fn run_convert(args: &ConvertArgs) -> Result<(), AnyError> {
         }
     }
 
+    let results = run_in_input_order(tasks, args.execution.fail_fast, |(_root, input, output)| {
+        convert_one(&input, &output, args).map_err(|e| format!("{}: {e}", input.display()).into())
+    });
     if args.execution.fail_fast {
-        tasks
-            .into_par_iter()
-            .map(|(_root, input, output)| {
-                convert_one(&input, &output, args)
-                    .map_err(|e| format!("{}: {e}", input.display()).into())
-            })
-            .collect::<Result<Vec<()>, AnyError>>()?;
+        if let Some(err) = results.into_iter().flatten().find_map(Result::err) {
+            return Err(err);
+        }
     } else {
-        let results = tasks
-            .into_par_iter()
-            .map(|(_root, input, output)| {
-                let res: Result<(), AnyError> = convert_one(&input, &output, args)
-                    .map_err(|e| format!("{}: {e}", input.display()).into());
-                if let Err(ref e) = res {
-                    log_error(&e.to_string());
-                }
-                res
-            })
-            .collect::<Vec<_>>();
-        let failures = results
-            .iter()
-            .filter(|r: &&Result<_, _>| r.is_err())
-            .count();
+        let mut failures = 0usize;
+        for err in results.into_iter().flatten().filter_map(Result::err) {
+            log_error(&err.to_string());
+            failures += 1;
+        }
         if failures > 0 {
             eprintln!("completed with {failures} failures");
         }
@@ -271,9 +353,76 @@ fn run_convert(args: &ConvertArgs) -> Result<(), AnyError> {
     Ok(())
 }
 
+/// Reads the metadata of every input and fails with a single report listing
+/// each input whose schema differs from the first.
+fn check_schemas(files: &[(PathBuf, PathBuf)]) -> Result<(), AnyError> {
+    let fingerprints = files
+        .par_iter()
+        .map(|(_root, input)| {
+            let fingerprint = input_url(input).map_or_else(
+                || SasReader::open(input).map(|sas| sas.schema_fingerprint()),
+                |url| SasReader::open_url(url).map(|sas| sas.schema_fingerprint()),
+            );
+            fingerprint.map_err(|e| AnyError::from(format!("{}: {e}", input.display())))
+        })
+        .collect::<Result<Vec<_>, AnyError>>()?;
+    let mismatches = compare_schemas(&fingerprints);
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    let mut report = format!(
+        "{} of {} inputs do not match the schema of {}:",
+        mismatches.len(),
+        files.len(),
+        files[0].1.display()
+    );
+    for mismatch in &mismatches {
+        let input = files[mismatch.index].1.display();
+        for difference in &mismatch.differences {
+            let _ = write!(report, "\n  {input}: {difference}");
+        }
+    }
+    Err(report.into())
+}
+
+/// Runs `tasks` on the Rayon pool and returns their outcomes in input order.
+///
+/// With `fail_fast`, tasks after the earliest failure seen so far are skipped
+/// (`None`), but every task before it still runs. The first `Err` in the
+/// result is therefore always the first failing input, regardless of which
+/// worker finished first.
+fn run_in_input_order<T, F>(
+    tasks: Vec<T>,
+    fail_fast: bool,
+    run: F,
+) -> Vec<Option<Result<(), AnyError>>>
+where
+    T: Send,
+    F: Fn(T) -> Result<(), AnyError> + Sync,
+{
+    let first_failure = AtomicUsize::new(usize::MAX);
+    tasks
+        .into_par_iter()
+        .enumerate()
+        .map(|(index, task)| {
+            if fail_fast && index > first_failure.load(Ordering::Relaxed) {
+                return None;
+            }
+            let result = run(task);
+            if result.is_err() {
+                first_failure.fetch_min(index, Ordering::Relaxed);
+            }
+            Some(result)
+        })
+        .collect()
+}
+
 fn run_inspect(args: &InspectArgs) -> Result<(), AnyError> {
-    let sas = SasReader::open(&args.input)?;
-    let meta = sas.metadata().clone();
+    let meta = if let Some(url) = input_url(&args.input) {
+        SasReader::open_url(url)?.metadata().clone()
+    } else {
+        SasReader::open(&args.input)?.metadata().clone()
+    };
     if args.json {
         #[derive(serde::Serialize)]
         struct ColumnInfoJson {
@@ -283,6 +432,8 @@ fn run_inspect(args: &InspectArgs) -> Result<(), AnyError> {
             kind: &'static str,
             format: Option<String>,
             width: usize,
+            measure: &'static str,
+            alignment: &'static str,
         }
         #[derive(serde::Serialize)]
         struct InspectJson {
@@ -303,6 +454,8 @@ fn run_inspect(args: &InspectArgs) -> Result<(), AnyError> {
                 },
                 format: v.format.as_ref().map(|f| f.name.clone()),
                 width: v.storage_width,
+                measure: v.measure.as_str(),
+                alignment: v.alignment.as_str(),
             })
             .collect();
         let payload = InspectJson {
@@ -312,32 +465,10 @@ fn run_inspect(args: &InspectArgs) -> Result<(), AnyError> {
         };
         serde_json::to_writer_pretty(std::io::stdout(), &payload)?;
         println!();
+    } else if args.markdown {
+        print!("{}", meta.describe().markdown());
     } else {
-        println!(
-            "Rows: {}  Columns: {}  Table: {}",
-            meta.row_count,
-            meta.column_count,
-            meta.table_name.as_deref().unwrap_or("")
-        );
-        for v in &meta.variables {
-            let kind = match v.kind {
-                sas7bdat::dataset::VariableKind::Numeric => "numeric",
-                sas7bdat::dataset::VariableKind::Character => "character",
-            };
-            let fmt = v
-                .format
-                .as_ref()
-                .map(|f| f.name.trim().to_owned())
-                .unwrap_or_default();
-            println!(
-                "[{idx:>3}] {name:<24}  {kind:<9}  width={w:<4}  fmt={fmt}",
-                idx = v.index,
-                name = v.name,
-                kind = kind,
-                w = v.storage_width,
-                fmt = fmt
-            );
-        }
+        print!("{}", meta.describe());
     }
     Ok(())
 }
@@ -345,9 +476,35 @@ fn run_inspect(args: &InspectArgs) -> Result<(), AnyError> {
 fn convert_one(input: &Path, output: &Path, args: &ConvertArgs) -> Result<(), AnyError> {
     let _log_prefix = set_log_prefix(input.to_string_lossy());
     // Prepare reader and metadata
-    let mut sas = SasReader::open(input)?;
-    if let Some(cat) = &args.catalog {
-        let _ = sas.attach_catalog(cat);
+    let options =
+        MetadataReadOptions::default().with_trust_row_count(!args.validation.ignore_row_count);
+    if let Some(url) = input_url(input) {
+        let sas = SasReader::open_url_with_options(url, HttpOptions::default(), options)?;
+        return convert_reader(sas, input, output, args);
+    }
+    convert_reader(
+        SasReader::open_with_options(input, options)?,
+        input,
+        output,
+        args,
+    )
+}
+
+fn convert_reader<R: std::io::Read + std::io::Seek>(
+    mut sas: SasReader<R>,
+    input: &Path,
+    output: &Path,
+    args: &ConvertArgs,
+) -> Result<(), AnyError> {
+    if !args.catalog.is_empty() {
+        for conflict in sas.attach_catalogs(&args.catalog)? {
+            eprintln!(
+                "warning: format {} from {} shadows {} other catalog(s)",
+                conflict.format,
+                args.catalog[conflict.used].display(),
+                conflict.shadowed.len()
+            );
+        }
     }
     let (mut reader, parsed) = sas.into_parts();
 
@@ -360,7 +517,13 @@ fn convert_one(input: &Path, output: &Path, args: &ConvertArgs) -> Result<(), An
     if let Some(parent) = output.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    let columnar_batch_rows = DEFAULT_COLUMNAR_BATCH_ROWS.max(1);
+    let batch_sizing = match (args.execution.batch_rows, args.execution.batch_target_bytes) {
+        (Some(rows), _) => BatchSizing::Rows(rows),
+        (None, Some(bytes)) => BatchSizing::TargetBytes(bytes),
+        (None, None) => BatchSizing::default(),
+    };
+    let columnar_batch_rows =
+        batch_sizing.rows_for(usize::try_from(parsed.row_info.row_length).unwrap_or(usize::MAX));
     let derived_row_group_rows = columnar_batch_rows
         .saturating_mul(COLUMNAR_ROW_GROUP_MULTIPLIER)
         .max(columnar_batch_rows);
@@ -372,7 +535,9 @@ fn convert_one(input: &Path, output: &Path, args: &ConvertArgs) -> Result<(), An
     match sink_kind {
         SinkKind::Parquet => {
             let file = File::create(output)?;
-            let mut sink = ParquetSink::new(file).with_lenient_dates(!args.validation.strict_dates);
+            let mut sink = ParquetSink::new(file)
+                .with_lenient_dates(!args.validation.strict_dates)
+                .with_timestamp_rounding(args.output.timestamp_rounding.into());
             let columnar_row_group_rows = if let Some(rows) = args.output.parquet_row_group_size {
                 sink = sink.with_row_group_size(rows);
                 Some(rows)
@@ -389,6 +554,7 @@ fn convert_one(input: &Path, output: &Path, args: &ConvertArgs) -> Result<(), An
             let col_opts = ColumnarOptions {
                 selection: &selection,
                 batch_rows,
+                layout: args.execution.decode_layout.into(),
                 source_path: Some(input.to_string_lossy().to_string()),
                 skip: args.skip,
                 max_rows: args.max_rows,
@@ -407,6 +573,8 @@ fn convert_one(input: &Path, output: &Path, args: &ConvertArgs) -> Result<(), An
             let file = File::create(output)?;
             let mut sink = CsvSink::new(file)
                 .with_headers(args.output.headers)
+                .with_rfc4180(args.output.rfc4180)
+                .with_metadata_comments(args.output.metadata_comments)
                 .with_delimiter(match (sink_kind, args.output.delimiter) {
                     (SinkKind::Tsv, None) => b'\t',
                     (_, Some(ch)) => ch as u8,
@@ -439,6 +607,7 @@ struct StreamOptions<'a> {
 struct ColumnarOptions<'a> {
     selection: &'a [usize],
     batch_rows: usize,
+    layout: DecodeLayout,
     source_path: Option<String>,
     skip: Option<u64>,
     max_rows: Option<u64>,
@@ -458,6 +627,7 @@ fn stream_into_sink<W: std::io::Read + std::io::Seek, S: RowSink>(
         metadata: meta_filtered,
         columns: cols_filtered,
         source_path,
+        logger: parsed.logger.clone(),
     };
     sink.begin(context)?;
 
@@ -517,13 +687,33 @@ fn stream_columnar_into_sink<W: std::io::Read + std::io::Seek, S: ColumnarSink>(
         metadata: meta_filtered,
         columns: cols_filtered,
         source_path: options.source_path.clone(),
+        logger: parsed.logger.clone(),
     };
     sink.begin(context)?;
 
     let mut it = parsed.row_iterator(reader)?;
     let mut skipped = 0u64;
     let mut remaining = options.max_rows;
-    while let Some(mut batch) = it.next_columnar_batch_contiguous(options.batch_rows)? {
+    let layout = options
+        .layout
+        .resolve(parsed.columns.len(), options.selection.len());
+    // Row-major batches hold only the selected columns, numbered in order.
+    let column_major = layout == DecodeLayout::ColumnMajor;
+    let projected: Vec<usize> = (0..options.selection.len()).collect();
+    let selection = if column_major {
+        options.selection
+    } else {
+        &projected
+    };
+    loop {
+        let next = if column_major {
+            it.next_columnar_batch_contiguous(options.batch_rows)?
+        } else {
+            it.next_columnar_batch_projected(options.selection, options.batch_rows)?
+        };
+        let Some(mut batch) = next else {
+            break;
+        };
         // Apply skip/max_rows on top of the batch.
         if let Some(skip) = options.skip
             && skipped < skip
@@ -549,7 +739,7 @@ fn stream_columnar_into_sink<W: std::io::Read + std::io::Seek, S: ColumnarSink>(
         if batch.row_count == 0 {
             break;
         }
-        sink.write_columnar_batch(&batch, options.selection)?;
+        sink.write_columnar_batch(&batch, selection)?;
     }
 
     sink.finish()?;
@@ -631,7 +821,9 @@ fn resolve_projection(
 fn discover_inputs(inputs: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
     let mut files = Vec::new();
     for input in inputs {
-        if input.is_dir() {
+        if input_url(input).is_some() {
+            files.push((PathBuf::new(), input.clone()));
+        } else if input.is_dir() {
             for entry in WalkDir::new(input)
                 .follow_links(false)
                 .into_iter()
@@ -658,6 +850,16 @@ fn discover_inputs(inputs: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
     files
 }
 
+/// Returns the input as a URL when it names an http(s) resource, or an
+/// object store one when built with the `object_store` feature.
+fn input_url(input: &Path) -> Option<&str> {
+    let text = input.to_str()?;
+    let scheme = text.split_once("://")?.0;
+    let is = |name: &str| scheme.eq_ignore_ascii_case(name);
+    let store = cfg!(feature = "object_store") && (is("s3") || is("gs") || is("az"));
+    (is("http") || is("https") || store).then_some(text)
+}
+
 fn is_sas7bdat(path: &Path) -> bool {
     path.extension()
         .is_some_and(|e| e.eq_ignore_ascii_case("sas7bdat"))
@@ -670,6 +872,20 @@ fn compute_output_path_unchecked(root: &Path, input: &Path, args: &ConvertArgs)
         SinkKind::Csv => "csv",
         SinkKind::Tsv => "tsv",
     };
+    if let Some(url) = input_url(input) {
+        // Name the output after the last path segment, next to the working
+        // directory unless an output directory is given.
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let fname = path
+            .rsplit('/')
+            .find(|segment| !segment.is_empty() && !segment.contains(':'))
+            .unwrap_or("output");
+        let renamed = PathBuf::from(fname).with_extension(new_ext);
+        return match &args.output.out_dir {
+            Some(dir) => dir.join(renamed),
+            None => renamed,
+        };
+    }
     args.output.out_dir.as_ref().map_or_else(
         || input.with_extension(new_ext),
         |dir| {
@@ -689,3 +905,52 @@ fn compute_output_path_unchecked(root: &Path, input: &Path, args: &ConvertArgs)
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn fail_fast_reports_first_failing_input() {
+        // Input 1 fails late, input 3 fails immediately; the reported error
+        // must still be input 1's.
+        let tasks: Vec<u64> = (0..6).collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .expect("thread pool");
+        let results = pool.install(|| {
+            run_in_input_order(tasks, true, |task| match task {
+                1 => {
+                    thread::sleep(Duration::from_millis(50));
+                    Err("input 1".into())
+                }
+                3 => Err("input 3".into()),
+                _ => Ok(()),
+            })
+        });
+        assert_eq!(results.len(), 6);
+        assert!(results[0].as_ref().is_some_and(Result::is_ok));
+        let first = results.into_iter().flatten().find_map(Result::err);
+        assert_eq!(first.map(|err| err.to_string()).as_deref(), Some("input 1"));
+    }
+
+    #[test]
+    fn check_schemas_reports_every_mismatching_input() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures/raw_data/pandas");
+        let input = |name: &str| (fixtures.clone(), fixtures.join(name));
+        let airline = input("airline.sas7bdat");
+        let test1 = input("test1.sas7bdat");
+        let test2 = input("test2.sas7bdat");
+
+        assert!(check_schemas(&[test1.clone(), test2.clone()]).is_ok());
+
+        let err = check_schemas(&[test1, airline, test2.clone(), test2])
+            .expect_err("airline differs")
+            .to_string();
+        assert!(err.starts_with("1 of 4 inputs"), "{err}");
+        assert!(err.contains("airline.sas7bdat: missing column"), "{err}");
+        assert!(err.contains("airline.sas7bdat: unexpected column"), "{err}");
+    }
+}
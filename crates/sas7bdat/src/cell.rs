@@ -45,6 +45,36 @@ impl CellValue<'_> {
     }
 }
 
+impl From<f64> for CellValue<'_> {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<i32> for CellValue<'_> {
+    fn from(value: i32) -> Self {
+        Self::Int32(value)
+    }
+}
+
+impl From<i64> for CellValue<'_> {
+    fn from(value: i64) -> Self {
+        Self::Int64(value)
+    }
+}
+
+impl<'a> From<&'a str> for CellValue<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Str(Cow::Borrowed(value))
+    }
+}
+
+impl From<String> for CellValue<'_> {
+    fn from(value: String) -> Self {
+        Self::Str(Cow::Owned(value))
+    }
+}
+
 /// Variants of missing values encountered in SAS datasets.
 #[derive(Debug, Clone, PartialEq)]
 pub enum MissingValue {
@@ -0,0 +1,79 @@
+use super::Compression;
+
+/// Row payload sizes measured on one data page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageCompression {
+    pub page_index: u64,
+    /// Rows stored on the page.
+    pub rows: u64,
+    /// Rows stored compressed. The remaining rows are stored verbatim, which
+    /// SAS does when compressing a row would not make it smaller.
+    pub compressed_rows: u64,
+    /// Bytes the rows occupy on the page.
+    pub stored_bytes: u64,
+    /// Bytes the rows occupy once expanded to the full row length.
+    pub expanded_bytes: u64,
+}
+
+impl PageCompression {
+    /// Stored bytes divided by expanded bytes; lower is better and values
+    /// close to 1.0 mean compression saved little on this page.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self) -> f64 {
+        if self.expanded_bytes == 0 {
+            1.0
+        } else {
+            self.stored_bytes as f64 / self.expanded_bytes as f64
+        }
+    }
+}
+
+/// Compression efficiency of a dataset, page by page.
+///
+/// SAS compresses whole rows rather than columns, so the page is the finest
+/// granularity at which the stored and expanded sizes can be compared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionReport {
+    pub compression: Compression,
+    pub row_length: u64,
+    /// Pages that hold rows, in file order.
+    pub pages: Vec<PageCompression>,
+}
+
+impl CompressionReport {
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.pages.iter().map(|page| page.rows).sum()
+    }
+
+    #[must_use]
+    pub fn stored_bytes(&self) -> u64 {
+        self.pages.iter().map(|page| page.stored_bytes).sum()
+    }
+
+    #[must_use]
+    pub fn expanded_bytes(&self) -> u64 {
+        self.pages.iter().map(|page| page.expanded_bytes).sum()
+    }
+
+    /// Stored bytes divided by expanded bytes across the whole file.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self) -> f64 {
+        let expanded = self.expanded_bytes();
+        if expanded == 0 {
+            1.0
+        } else {
+            self.stored_bytes() as f64 / expanded as f64
+        }
+    }
+
+    /// Pages whose ratio is at least `threshold`, such as pages where
+    /// compression saved less than a tenth of the space at `0.9`.
+    pub fn pages_above(&self, threshold: f64) -> impl Iterator<Item = &PageCompression> {
+        self.pages
+            .iter()
+            .filter(move |page| page.ratio() >= threshold)
+    }
+}
@@ -0,0 +1,224 @@
+use super::{
+    metadata::{Compression, DatasetMetadata, TimestampSource},
+    variables::VariableKind,
+};
+use std::fmt::{self, Display};
+use time::{OffsetDateTime, format_description::BorrowedFormatItem, macros::format_description};
+
+const TIMESTAMP_FORMAT: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+const VARIABLE_HEADERS: [&str; 7] = [
+    "#", "Variable", "Type", "Len", "Format", "Informat", "Label",
+];
+
+/// Layout used when rendering a [`DatasetDescription`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DescribeStyle {
+    /// Aligned plain-text columns for terminals.
+    #[default]
+    Text,
+    /// Markdown tables for notebooks and reports.
+    Markdown,
+}
+
+/// PROC CONTENTS-style summary of a dataset: its attributes followed by one
+/// line per variable. Rendered through [`Display`].
+#[derive(Debug, Clone, Copy)]
+pub struct DatasetDescription<'a> {
+    metadata: &'a DatasetMetadata,
+    style: DescribeStyle,
+}
+
+impl DatasetMetadata {
+    /// Summarises the dataset as plain text; see [`DatasetDescription`].
+    #[must_use]
+    pub const fn describe(&self) -> DatasetDescription<'_> {
+        DatasetDescription {
+            metadata: self,
+            style: DescribeStyle::Text,
+        }
+    }
+}
+
+impl DatasetDescription<'_> {
+    #[must_use]
+    pub const fn with_style(mut self, style: DescribeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Shorthand for `with_style(DescribeStyle::Markdown)`.
+    #[must_use]
+    pub const fn markdown(self) -> Self {
+        self.with_style(DescribeStyle::Markdown)
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        let metadata = self.metadata;
+        let version = metadata.version;
+        let mut attributes = vec![
+            (
+                "Data Set Name",
+                metadata.table_name.clone().unwrap_or_default(),
+            ),
+            ("Label", metadata.file_label.clone().unwrap_or_default()),
+            (
+                "Created",
+                timestamp(
+                    metadata.timestamps.created,
+                    metadata.timestamps.created_source,
+                ),
+            ),
+            (
+                "Last Modified",
+                timestamp(
+                    metadata.timestamps.modified,
+                    metadata.timestamps.modified_source,
+                ),
+            ),
+            (
+                "Encoding",
+                metadata.file_encoding.clone().unwrap_or_default(),
+            ),
+            ("Compression", compression(metadata.compression)),
+            ("Observations", metadata.row_count.to_string()),
+            ("Variables", metadata.variables.len().to_string()),
+        ];
+        if version.major > 0 {
+            attributes.push((
+                "Release",
+                format!(
+                    "{}.{:04}M{}",
+                    version.major, version.minor, version.revision
+                ),
+            ));
+        }
+        attributes
+    }
+
+    fn variable_rows(&self) -> Vec<[String; 7]> {
+        self.metadata
+            .variables
+            .iter()
+            .map(|variable| {
+                [
+                    (variable.index + 1).to_string(),
+                    variable.name.trim_end().to_owned(),
+                    match variable.kind {
+                        VariableKind::Numeric => "Num",
+                        VariableKind::Character => "Char",
+                    }
+                    .to_owned(),
+                    variable.storage_width.to_string(),
+                    variable
+                        .format
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                    variable
+                        .informat
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                    variable.label.clone().unwrap_or_default(),
+                ]
+            })
+            .collect()
+    }
+
+    fn fmt_text(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let attributes = self.attributes();
+        let key_width = attributes
+            .iter()
+            .map(|(key, _)| key.len())
+            .max()
+            .unwrap_or(0);
+        for (key, value) in &attributes {
+            writeln!(f, "{key:<key_width$}  {value}")?;
+        }
+
+        let rows = self.variable_rows();
+        let mut widths = VARIABLE_HEADERS.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        writeln!(f)?;
+        write_text_row(f, &VARIABLE_HEADERS, &widths)?;
+        for row in &rows {
+            write_text_row(f, row, &widths)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_markdown(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "| Attribute | Value |")?;
+        writeln!(f, "| --- | --- |")?;
+        for (key, value) in self.attributes() {
+            writeln!(f, "| {key} | {} |", escape_markdown(&value))?;
+        }
+        writeln!(f)?;
+        writeln!(f, "| {} |", VARIABLE_HEADERS.join(" | "))?;
+        writeln!(f, "|{}", " --- |".repeat(VARIABLE_HEADERS.len()))?;
+        for row in self.variable_rows() {
+            let cells: Vec<String> = row.iter().map(|cell| escape_markdown(cell)).collect();
+            writeln!(f, "| {} |", cells.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DatasetDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.style {
+            DescribeStyle::Text => self.fmt_text(f),
+            DescribeStyle::Markdown => self.fmt_markdown(f),
+        }
+    }
+}
+
+fn write_text_row<S: AsRef<str>>(
+    f: &mut fmt::Formatter<'_>,
+    cells: &[S],
+    widths: &[usize],
+) -> fmt::Result {
+    let line: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(idx, (cell, &width))| {
+            // Right-align the numeric position and length columns.
+            if idx == 0 || idx == 3 {
+                format!("{:>width$}", cell.as_ref())
+            } else {
+                format!("{:<width$}", cell.as_ref())
+            }
+        })
+        .collect();
+    writeln!(f, "{}", line.join("  ").trim_end())
+}
+
+fn timestamp(value: Option<OffsetDateTime>, source: TimestampSource) -> String {
+    let Some(formatted) = value.and_then(|value| value.format(TIMESTAMP_FORMAT).ok()) else {
+        return String::new();
+    };
+    match source {
+        TimestampSource::Header => formatted,
+        TimestampSource::FileSystem => format!("{formatted} (from file system)"),
+    }
+}
+
+fn compression(compression: Compression) -> String {
+    match compression {
+        Compression::None => "none".to_owned(),
+        Compression::Row => "CHAR (RLE)".to_owned(),
+        Compression::Binary => "BINARY (RDC)".to_owned(),
+        Compression::Unknown(code) => format!("unknown ({code})"),
+        Compression::Other(signature) => signature.to_string(),
+    }
+}
+
+fn escape_markdown(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,465 @@
+use super::DatasetMetadata;
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    iter_utils::next_from_result,
+    parser::{ColumnKind, NumericKind, RowIterator},
+    reader::{RowLookup, SasReader},
+};
+use std::{
+    borrow::Cow,
+    io::{Read, Seek},
+    path::Path,
+};
+use time::{Duration, OffsetDateTime};
+
+/// Values of a single column held in memory.
+///
+/// Numeric missing values are stored as `None`. Temporal values outside the
+/// range representable by [`OffsetDateTime`]/[`Duration`] are also stored as
+/// `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    Numeric(Vec<Option<f64>>),
+    Text(Vec<String>),
+    Date(Vec<Option<OffsetDateTime>>),
+    DateTime(Vec<Option<OffsetDateTime>>),
+    Time(Vec<Option<Duration>>),
+    /// Raw storage of numeric columns with an unsupported width.
+    Bytes(Vec<Vec<u8>>),
+}
+
+impl ColumnData {
+    fn with_capacity(kind: ColumnKind, capacity: usize) -> Self {
+        match kind {
+            ColumnKind::Character | ColumnKind::Numeric(NumericKind::Text) => {
+                Self::Text(Vec::with_capacity(capacity))
+            }
+            ColumnKind::Numeric(NumericKind::Double | NumericKind::Float) => {
+                Self::Numeric(Vec::with_capacity(capacity))
+            }
+            ColumnKind::Numeric(NumericKind::Date) => Self::Date(Vec::with_capacity(capacity)),
+            ColumnKind::Numeric(NumericKind::DateTime) => {
+                Self::DateTime(Vec::with_capacity(capacity))
+            }
+            ColumnKind::Numeric(NumericKind::Time) => Self::Time(Vec::with_capacity(capacity)),
+            ColumnKind::Bytes => Self::Bytes(Vec::with_capacity(capacity)),
+        }
+    }
+
+    /// Returns the number of values stored in the column.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        match self {
+            Self::Numeric(values) => values.len(),
+            Self::Text(values) => values.len(),
+            Self::Date(values) | Self::DateTime(values) => values.len(),
+            Self::Time(values) => values.len(),
+            Self::Bytes(values) => values.len(),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value at `index` as a borrowed cell.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<CellValue<'_>> {
+        let missing = || CellValue::Missing(crate::cell::MissingValue::System);
+        Some(match self {
+            Self::Numeric(values) => (*values.get(index)?).map_or_else(missing, CellValue::Float),
+            Self::Text(values) => CellValue::Str(Cow::Borrowed(values.get(index)?.as_str())),
+            Self::Date(values) => (*values.get(index)?).map_or_else(missing, CellValue::Date),
+            Self::DateTime(values) => {
+                (*values.get(index)?).map_or_else(missing, CellValue::DateTime)
+            }
+            Self::Time(values) => (*values.get(index)?).map_or_else(missing, CellValue::Time),
+            Self::Bytes(values) => CellValue::Bytes(Cow::Borrowed(values.get(index)?.as_slice())),
+        })
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn push(&mut self, value: CellValue<'_>) {
+        match (self, value) {
+            (Self::Numeric(values), CellValue::Float(v)) => values.push(Some(v)),
+            (Self::Numeric(values), CellValue::Int32(v)) => values.push(Some(f64::from(v))),
+            (Self::Numeric(values), CellValue::Int64(v)) => values.push(Some(v as f64)),
+            (Self::Numeric(values), CellValue::NumericString(text)) => {
+                values.push(text.trim().parse().ok());
+            }
+            (Self::Text(values), CellValue::Str(text) | CellValue::NumericString(text)) => {
+                values.push(text.into_owned());
+            }
+            (Self::Text(values), CellValue::Bytes(bytes)) => {
+                values.push(String::from_utf8_lossy(&bytes).into_owned());
+            }
+            (Self::Text(values), _) => values.push(String::new()),
+            (Self::Date(values), CellValue::Date(v))
+            | (Self::DateTime(values), CellValue::DateTime(v)) => values.push(Some(v)),
+            (Self::Time(values), CellValue::Time(v)) => values.push(Some(v)),
+            (Self::Bytes(values), CellValue::Bytes(bytes)) => values.push(bytes.into_owned()),
+            (Self::Bytes(values), _) => values.push(Vec::new()),
+            (Self::Numeric(values), _) => values.push(None),
+            (Self::Date(values) | Self::DateTime(values), _) => values.push(None),
+            (Self::Time(values), _) => values.push(None),
+        }
+    }
+
+    fn select(&self, indices: &[usize]) -> Self {
+        fn pick<T: Clone>(values: &[T], indices: &[usize]) -> Vec<T> {
+            indices.iter().map(|&idx| values[idx].clone()).collect()
+        }
+        match self {
+            Self::Numeric(values) => Self::Numeric(pick(values, indices)),
+            Self::Text(values) => Self::Text(pick(values, indices)),
+            Self::Date(values) => Self::Date(pick(values, indices)),
+            Self::DateTime(values) => Self::DateTime(pick(values, indices)),
+            Self::Time(values) => Self::Time(pick(values, indices)),
+            Self::Bytes(values) => Self::Bytes(pick(values, indices)),
+        }
+    }
+}
+
+/// Owned, column-oriented chunk of consecutive rows; see
+/// [`SasReader::read_batches`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    /// One column per dataset column, in dataset order, each holding
+    /// `row_count` values.
+    pub columns: Vec<ColumnData>,
+    pub row_count: usize,
+}
+
+/// Iterator over the rows of a dataset as owned [`Batch`]es.
+pub struct BatchIter<'a, R: Read + Seek> {
+    rows: RowIterator<'a, R>,
+    kinds: Vec<ColumnKind>,
+    batch_size: usize,
+    capacity: usize,
+    exhausted: bool,
+}
+
+impl<'a, R: Read + Seek> BatchIter<'a, R> {
+    /// A `batch_size` of zero uses the iterator's configured batch sizing.
+    pub(crate) fn new(
+        rows: RowIterator<'a, R>,
+        kinds: Vec<ColumnKind>,
+        batch_size: usize,
+        total_rows: u64,
+    ) -> Self {
+        let batch_size = if batch_size == 0 {
+            rows.batch_rows().max(1)
+        } else {
+            batch_size
+        };
+        // Caps the up-front allocation when `batch_size` exceeds the file.
+        let capacity = batch_size.min(usize::try_from(total_rows).unwrap_or(usize::MAX));
+        Self {
+            rows,
+            kinds,
+            batch_size,
+            capacity,
+            exhausted: false,
+        }
+    }
+
+    /// Decodes the next batch of at most `batch_size` rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row decoding fails.
+    pub fn try_next(&mut self) -> Result<Option<Batch>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+        let mut columns: Vec<ColumnData> = self
+            .kinds
+            .iter()
+            .map(|&kind| ColumnData::with_capacity(kind, self.capacity))
+            .collect();
+        let mut row_count = 0usize;
+        while row_count < self.batch_size {
+            let Some(row) = self.rows.try_next()? else {
+                self.exhausted = true;
+                break;
+            };
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+            row_count += 1;
+        }
+        Ok((row_count > 0).then_some(Batch { columns, row_count }))
+    }
+}
+
+impl<R: Read + Seek> Iterator for BatchIter<'_, R> {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.try_next();
+        next_from_result(
+            result,
+            |batch| batch,
+            || {
+                self.exhausted = true;
+            },
+        )
+    }
+}
+
+/// Owned, column-oriented copy of a dataset for small files.
+///
+/// `Dataset` decodes every row up front, so it is intended for data that fits
+/// comfortably in memory. Use [`SasReader`] for streaming access to larger
+/// files.
+#[derive(Debug)]
+pub struct Dataset {
+    metadata: DatasetMetadata,
+    columns: Vec<ColumnData>,
+    lookup: RowLookup,
+    row_count: usize,
+}
+
+impl Dataset {
+    /// Reads the SAS7BDAT file at `path` fully into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or decoded.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut reader = SasReader::open(path)?;
+        Self::from_reader(&mut reader)
+    }
+
+    /// Reads all rows from an open [`SasReader`] into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row decoding fails.
+    pub fn from_reader<R: Read + Seek>(reader: &mut SasReader<R>) -> Result<Self> {
+        let metadata = reader.metadata().clone();
+        let capacity = usize::try_from(metadata.row_count).unwrap_or(0);
+        let mut columns: Vec<ColumnData> = reader
+            .layout()
+            .columns
+            .iter()
+            .map(|column| ColumnData::with_capacity(column.kind, capacity))
+            .collect();
+
+        let mut row_count = 0usize;
+        let mut rows = reader.coded_rows()?;
+        while let Some(row) = rows.try_next()? {
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+            row_count += 1;
+        }
+
+        Ok(Self::from_parts(metadata, columns, row_count))
+    }
+
+    fn from_parts(metadata: DatasetMetadata, columns: Vec<ColumnData>, row_count: usize) -> Self {
+        let lookup = RowLookup::from_metadata(&metadata);
+        Self {
+            metadata,
+            columns,
+            lookup,
+            row_count,
+        }
+    }
+
+    #[must_use]
+    pub const fn metadata(&self) -> &DatasetMetadata {
+        &self.metadata
+    }
+
+    #[must_use]
+    pub const fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    #[must_use]
+    pub const fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> &[ColumnData] {
+        &self.columns
+    }
+
+    /// Returns the column with the given name, ignoring trailing blanks.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&ColumnData> {
+        self.lookup
+            .index(name)
+            .and_then(|index| self.columns.get(index))
+    }
+
+    /// Returns the values of a numeric column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the column does not exist or is not a plain
+    /// numeric column.
+    pub fn col_f64(&self, name: &str) -> Result<&[Option<f64>]> {
+        match self.require_column(name)? {
+            ColumnData::Numeric(values) => Ok(values),
+            _ => Err(type_mismatch(name, "numeric")),
+        }
+    }
+
+    /// Returns the values of a character column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the column does not exist or is not a character
+    /// column.
+    pub fn col_str(&self, name: &str) -> Result<&[String]> {
+        match self.require_column(name)? {
+            ColumnData::Text(values) => Ok(values),
+            _ => Err(type_mismatch(name, "character")),
+        }
+    }
+
+    /// Returns the values of a date-formatted column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the column does not exist or is not a date column.
+    pub fn col_date(&self, name: &str) -> Result<&[Option<OffsetDateTime>]> {
+        match self.require_column(name)? {
+            ColumnData::Date(values) => Ok(values),
+            _ => Err(type_mismatch(name, "date")),
+        }
+    }
+
+    /// Returns the values of a datetime-formatted column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the column does not exist or is not a datetime
+    /// column.
+    pub fn col_datetime(&self, name: &str) -> Result<&[Option<OffsetDateTime>]> {
+        match self.require_column(name)? {
+            ColumnData::DateTime(values) => Ok(values),
+            _ => Err(type_mismatch(name, "datetime")),
+        }
+    }
+
+    /// Returns the values of a time-formatted column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the column does not exist or is not a time column.
+    pub fn col_time(&self, name: &str) -> Result<&[Option<Duration>]> {
+        match self.require_column(name)? {
+            ColumnData::Time(values) => Ok(values),
+            _ => Err(type_mismatch(name, "time")),
+        }
+    }
+
+    /// Returns a view of the row at `index`.
+    #[must_use]
+    pub const fn row(&self, index: usize) -> Option<DatasetRow<'_>> {
+        if index < self.row_count {
+            Some(DatasetRow {
+                dataset: self,
+                index,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over row views in order.
+    #[must_use]
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = DatasetRow<'_>> + '_ {
+        (0..self.row_count).map(move |index| DatasetRow {
+            dataset: self,
+            index,
+        })
+    }
+
+    /// Returns a new dataset containing only the rows for which `predicate`
+    /// returns `true`.
+    #[must_use]
+    pub fn filter<F>(&self, mut predicate: F) -> Self
+    where
+        F: FnMut(&DatasetRow<'_>) -> bool,
+    {
+        let keep: Vec<usize> = self
+            .rows()
+            .filter(|row| predicate(row))
+            .map(|row| row.index)
+            .collect();
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| column.select(&keep))
+            .collect();
+        let mut metadata = self.metadata.clone();
+        metadata.row_count = keep.len() as u64;
+        Self::from_parts(metadata, columns, keep.len())
+    }
+
+    fn require_column(&self, name: &str) -> Result<&ColumnData> {
+        self.column(name).ok_or_else(|| Error::InvalidArgument {
+            details: format!("column name '{name}' not found in dataset").into(),
+        })
+    }
+}
+
+fn type_mismatch(name: &str, expected: &str) -> Error {
+    Error::InvalidArgument {
+        details: format!("column '{name}' is not a {expected} column").into(),
+    }
+}
+
+/// Borrowed view of a single row in a [`Dataset`].
+#[derive(Debug, Clone, Copy)]
+pub struct DatasetRow<'a> {
+    dataset: &'a Dataset,
+    index: usize,
+}
+
+impl<'a> DatasetRow<'a> {
+    /// Zero-based position of the row within its dataset.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the cell for the named column.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<CellValue<'a>> {
+        self.dataset.column(name)?.get(self.index)
+    }
+
+    /// Returns the cell at column position `column`.
+    #[must_use]
+    pub fn get_at(&self, column: usize) -> Option<CellValue<'a>> {
+        self.dataset.columns.get(column)?.get(self.index)
+    }
+
+    /// Returns the numeric value of the named column, or `None` when the
+    /// column is missing, not numeric, or the value is missing.
+    #[must_use]
+    pub fn f64(&self, name: &str) -> Option<f64> {
+        match self.dataset.column(name)? {
+            ColumnData::Numeric(values) => values[self.index],
+            _ => None,
+        }
+    }
+
+    /// Returns the text of the named character column.
+    #[must_use]
+    pub fn str(&self, name: &str) -> Option<&'a str> {
+        match self.dataset.column(name)? {
+            ColumnData::Text(values) => Some(values[self.index].as_str()),
+            _ => None,
+        }
+    }
+}
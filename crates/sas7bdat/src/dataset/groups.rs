@@ -0,0 +1,190 @@
+use super::{Variable, VariableKind};
+use std::collections::BTreeMap;
+
+/// A set of numbered variables such as `DIAG_1`..`DIAG_20` that together hold
+/// one repeated measure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepeatedGroup {
+    /// Name of the logical column, the shared stem without the separator.
+    pub name: String,
+    /// Member variable names ordered by their numeric suffix.
+    pub members: Vec<String>,
+}
+
+impl RepeatedGroup {
+    pub fn new<I, S>(name: impl Into<String>, members: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            members: members.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Detects groups of variables that share a stem and differ only in a
+/// trailing number, optionally separated by `_` (`DIAG_1`, `DIAG_2`, or
+/// `DX1`, `DX2`).
+///
+/// A group needs at least two members of the same [`VariableKind`] with
+/// distinct suffixes. Stems that would collide with another variable's name
+/// are skipped. When both spellings form groups (`DIAG_1`, `DIAG_2` and
+/// `DIAG1`, `DIAG2`), the separated group keeps its `_` (`DIAG_`) so the
+/// names stay distinct. Groups are returned in the order of their first
+/// member.
+#[must_use]
+pub fn detect_repeated_groups(variables: &[Variable]) -> Vec<RepeatedGroup> {
+    struct Candidate {
+        name: String,
+        stem: String,
+        first_index: usize,
+        kind: VariableKind,
+        mixed_kinds: bool,
+        members: BTreeMap<u64, String>,
+        duplicate_suffix: bool,
+    }
+
+    let mut candidates: BTreeMap<String, Candidate> = BTreeMap::new();
+    for (index, variable) in variables.iter().enumerate() {
+        let name = variable.name.trim_end();
+        let Some((stem, number)) = split_numeric_suffix(name) else {
+            continue;
+        };
+        let group_name = stem.strip_suffix('_').unwrap_or(stem);
+        if group_name.is_empty() {
+            continue;
+        }
+        let candidate = candidates
+            .entry(stem.to_ascii_uppercase())
+            .or_insert_with(|| Candidate {
+                name: group_name.to_owned(),
+                stem: stem.to_owned(),
+                first_index: index,
+                kind: variable.kind,
+                mixed_kinds: false,
+                members: BTreeMap::new(),
+                duplicate_suffix: false,
+            });
+        candidate.mixed_kinds |= candidate.kind != variable.kind;
+        candidate.duplicate_suffix |= candidate.members.insert(number, name.to_owned()).is_some();
+    }
+
+    let names_variable = |name: &str| {
+        variables
+            .iter()
+            .any(|v| v.name.trim_end().eq_ignore_ascii_case(name))
+    };
+    let accepted: Vec<Candidate> = candidates
+        .into_values()
+        .filter(|candidate| {
+            candidate.members.len() >= 2
+                && !candidate.mixed_kinds
+                && !candidate.duplicate_suffix
+                && !names_variable(&candidate.name)
+        })
+        .collect();
+    let mut groups: Vec<(usize, RepeatedGroup)> = accepted
+        .iter()
+        .filter_map(|candidate| {
+            let shared = accepted
+                .iter()
+                .filter(|other| other.name.eq_ignore_ascii_case(&candidate.name))
+                .count()
+                > 1;
+            let name = if shared {
+                &candidate.stem
+            } else {
+                &candidate.name
+            };
+            if shared && names_variable(name) {
+                return None;
+            }
+            Some((
+                candidate.first_index,
+                RepeatedGroup {
+                    name: name.clone(),
+                    members: candidate.members.values().cloned().collect(),
+                },
+            ))
+        })
+        .collect();
+    groups.sort_by_key(|(first_index, _)| *first_index);
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+fn split_numeric_suffix(name: &str) -> Option<(&str, u64)> {
+    let stem = name.trim_end_matches(|ch: char| ch.is_ascii_digit());
+    if stem.is_empty() || stem.len() == name.len() {
+        return None;
+    }
+    let number = name[stem.len()..].parse().ok()?;
+    Some((stem, number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RepeatedGroup, detect_repeated_groups};
+    use crate::dataset::{Variable, VariableKind};
+
+    fn vars(names: &[(&str, VariableKind)]) -> Vec<Variable> {
+        names
+            .iter()
+            .zip(0u32..)
+            .map(|((name, kind), index)| Variable::new(index, (*name).to_owned(), *kind, 8))
+            .collect()
+    }
+
+    #[test]
+    fn detects_numbered_groups_in_suffix_order() {
+        use VariableKind::{Character, Numeric};
+        let variables = vars(&[
+            ("ID", Numeric),
+            ("DIAG_2", Character),
+            ("DIAG_1", Character),
+            ("DIAG_10", Character),
+            ("dx1", Numeric),
+            ("DX2", Numeric),
+            ("YEAR2020", Numeric),
+            ("MIX1", Numeric),
+            ("MIX2", Character),
+        ]);
+
+        let groups = detect_repeated_groups(&variables);
+        assert_eq!(
+            groups,
+            vec![
+                RepeatedGroup::new("DIAG", ["DIAG_1", "DIAG_2", "DIAG_10"]),
+                RepeatedGroup::new("dx", ["dx1", "DX2"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_stems_that_collide_with_existing_names() {
+        let variables = vars(&[
+            ("VISIT", VariableKind::Numeric),
+            ("VISIT1", VariableKind::Numeric),
+            ("VISIT2", VariableKind::Numeric),
+        ]);
+        assert!(detect_repeated_groups(&variables).is_empty());
+    }
+
+    #[test]
+    fn keeps_the_separator_when_both_spellings_form_groups() {
+        let variables = vars(&[
+            ("DIAG_1", VariableKind::Character),
+            ("DIAG_2", VariableKind::Character),
+            ("diag1", VariableKind::Numeric),
+            ("diag2", VariableKind::Numeric),
+        ]);
+        assert_eq!(
+            detect_repeated_groups(&variables),
+            vec![
+                RepeatedGroup::new("DIAG_", ["DIAG_1", "DIAG_2"]),
+                RepeatedGroup::new("diag", ["diag1", "diag2"]),
+            ]
+        );
+    }
+}
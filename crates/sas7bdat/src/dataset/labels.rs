@@ -1,4 +1,11 @@
-#[derive(Debug, Clone, PartialEq)]
+use crate::{
+    cell::{CellValue, MissingValue},
+    parser::core::float_utils::exact_integer_from_f64,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct LabelSet {
     pub name: String,
     pub value_type: ValueType,
@@ -14,15 +21,54 @@ impl LabelSet {
             labels: Vec::new(),
         }
     }
+
+    /// Indexes the labels for looking up decoded cells; see [`LabelLookup`].
+    #[must_use]
+    pub fn lookup(&self) -> LabelLookup {
+        LabelLookup::from_set(self)
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// One entry of a label set: a single value, or an inclusive numeric range
+/// such as `0-17 = "child"` when `end` is set.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ValueLabel {
     pub key: ValueKey,
+    /// Upper bound of a range whose lower bound is `key`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<ValueKey>,
     pub label: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl ValueLabel {
+    #[must_use]
+    pub fn new(key: ValueKey, label: impl Into<String>) -> Self {
+        Self {
+            key,
+            end: None,
+            label: label.into(),
+        }
+    }
+
+    /// Labels every number from `start` to `end`, both inclusive.
+    #[must_use]
+    pub fn range(start: f64, end: f64, label: impl Into<String>) -> Self {
+        Self {
+            key: ValueKey::Numeric(start),
+            end: Some(ValueKey::Numeric(end)),
+            label: label.into(),
+        }
+    }
+
+    /// Returns the inclusive bounds of a numeric range entry.
+    #[must_use]
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        Some((self.key.as_f64()?, self.end.as_ref()?.as_f64()?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
 pub enum ValueKey {
     Numeric(f64),
     Integer(i32),
@@ -30,8 +76,102 @@ pub enum ValueKey {
     String(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl ValueKey {
+    const fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Numeric(value) => Some(*value),
+            Self::Integer(value) => Some(*value as f64),
+            Self::Tagged(_) | Self::String(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ValueType {
     Numeric,
     String,
 }
+
+/// The labels of a [`LabelSet`], keyed the way decoded cells are looked up.
+///
+/// Single values win over ranges, and a number outside every single value
+/// falls into the range that contains it. SAS rejects overlapping ranges
+/// outside multilabel formats, so each number has at most one range label;
+/// with overlaps the range starting last at or below the number is tried.
+#[derive(Debug, Clone, Default)]
+pub struct LabelLookup {
+    /// Numeric codes as `f64` bits, with `-0.0` folded into `0.0`.
+    numeric: HashMap<u64, String>,
+    /// Inclusive `(start, end, label)` ranges sorted by start.
+    ranges: Vec<(f64, f64, String)>,
+    tagged: HashMap<char, String>,
+    strings: HashMap<String, String>,
+}
+
+impl LabelLookup {
+    fn from_set(set: &LabelSet) -> Self {
+        let mut labels = Self::default();
+        for entry in &set.labels {
+            let label = entry.label.clone();
+            if let Some((start, end)) = entry.bounds() {
+                labels.ranges.push((start, end, label));
+                continue;
+            }
+            match &entry.key {
+                ValueKey::Numeric(value) => {
+                    labels.numeric.insert(numeric_key(*value), label);
+                }
+                ValueKey::Integer(value) => {
+                    labels.numeric.insert(numeric_key(f64::from(*value)), label);
+                }
+                ValueKey::Tagged(tag) => {
+                    labels.tagged.insert(*tag, label);
+                }
+                ValueKey::String(value) => {
+                    labels.strings.insert(value.trim_end().to_owned(), label);
+                }
+            }
+        }
+        labels.ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        labels
+    }
+
+    /// Returns the label attached to `value`, if any.
+    #[must_use]
+    pub fn label(&self, value: &CellValue<'_>) -> Option<&str> {
+        match value {
+            CellValue::Float(v) => self.numeric_label(*v),
+            CellValue::Int32(v) => self.numeric_label(f64::from(*v)),
+            CellValue::Int64(v) => exact_f64(*v).and_then(|v| self.numeric_label(v)),
+            CellValue::Str(s) => self.strings.get(s.trim_end()).map(String::as_str),
+            CellValue::Missing(MissingValue::System) => self.tagged.get(&'.').map(String::as_str),
+            CellValue::Missing(MissingValue::Tagged(tagged)) => self
+                .tagged
+                .get(&tagged.tag.unwrap_or('.'))
+                .map(String::as_str),
+            _ => None,
+        }
+    }
+
+    fn numeric_label(&self, value: f64) -> Option<&str> {
+        if let Some(label) = self.numeric.get(&numeric_key(value)) {
+            return Some(label);
+        }
+        let position = self.ranges.partition_point(|(start, ..)| *start <= value);
+        let (_, end, label) = self.ranges.get(position.checked_sub(1)?)?;
+        (value <= *end).then_some(label.as_str())
+    }
+}
+
+/// Converts `value` to the `f64` numeric codes are stored as, unless it is
+/// too large to be represented exactly.
+#[allow(clippy::cast_precision_loss)]
+fn exact_f64(value: i64) -> Option<f64> {
+    let float = value as f64;
+    (exact_integer_from_f64(float) == Some(i128::from(value))).then_some(float)
+}
+
+fn numeric_key(value: f64) -> u64 {
+    if value == 0.0 { 0 } else { value.to_bits() }
+}
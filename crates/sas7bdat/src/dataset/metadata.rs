@@ -1,5 +1,6 @@
 use super::{labels::LabelSet, variables::Variable};
-use std::collections::HashMap;
+use crate::time_conv::SAS_EPOCH;
+use std::{collections::HashMap, fmt, fs};
 use time::OffsetDateTime;
 
 /// High-level metadata for a SAS dataset.
@@ -40,15 +41,21 @@ impl DatasetMetadata {
         }
     }
 
+    /// Index of the column called `name`, ignoring trailing blanks. SAS
+    /// names are case-insensitive, so when no name matches exactly the
+    /// first one equal ignoring ASCII case is used.
     #[must_use]
     pub fn column_index(&self, name: &str) -> Option<usize> {
         let trimmed = name.trim_end();
-        for variable in &self.variables {
-            if variable.name == name || variable.name.trim_end() == trimmed {
-                return Some(variable.index as usize);
-            }
-        }
-        None
+        self.variables
+            .iter()
+            .find(|variable| variable.name == name || variable.name.trim_end() == trimmed)
+            .or_else(|| {
+                self.variables
+                    .iter()
+                    .find(|variable| variable.name.trim_end().eq_ignore_ascii_case(trimmed))
+            })
+            .map(|variable| variable.index as usize)
     }
 }
 
@@ -57,6 +64,46 @@ impl DatasetMetadata {
 pub struct DatasetTimestamps {
     pub created: Option<OffsetDateTime>,
     pub modified: Option<OffsetDateTime>,
+    pub created_source: TimestampSource,
+    pub modified_source: TimestampSource,
+}
+
+/// Where a dataset timestamp was obtained from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampSource {
+    /// Read from the SAS file header.
+    #[default]
+    Header,
+    /// Back-filled from filesystem metadata because the header value was
+    /// absent or zero.
+    FileSystem,
+}
+
+impl DatasetTimestamps {
+    /// Fills absent or zero timestamps from filesystem metadata.
+    ///
+    /// Timestamps present in the header are left untouched. Back-filled values
+    /// are marked with [`TimestampSource::FileSystem`]; values the platform
+    /// cannot report stay `None`.
+    pub fn backfill_from_file(&mut self, metadata: &fs::Metadata) {
+        if is_unset(self.created)
+            && let Ok(created) = metadata.created()
+        {
+            self.created = Some(OffsetDateTime::from(created));
+            self.created_source = TimestampSource::FileSystem;
+        }
+        if is_unset(self.modified)
+            && let Ok(modified) = metadata.modified()
+        {
+            self.modified = Some(OffsetDateTime::from(modified));
+            self.modified_source = TimestampSource::FileSystem;
+        }
+    }
+}
+
+/// Header timestamps equal to the SAS epoch were never populated by the writer.
+fn is_unset(value: Option<OffsetDateTime>) -> bool {
+    value.is_none_or(|ts| ts == SAS_EPOCH)
 }
 
 /// SAS version components extracted from the header.
@@ -80,6 +127,46 @@ pub enum Compression {
     Row,
     Binary,
     Unknown(u16),
+    /// A `SASYZ*` tag other than `SASYZCRL`/`SASYZCR2`; rows can only be
+    /// read once a matching
+    /// [`PageDecompressor`](crate::parser::PageDecompressor) is registered.
+    Other(CompressionSignature),
+}
+
+/// Compression tag stored in the column text subheader, e.g. `SASYZCRL`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompressionSignature([u8; 8]);
+
+impl CompressionSignature {
+    /// Returns `None` when `tag` is longer than the eight bytes SAS stores.
+    #[must_use]
+    pub fn new(tag: &str) -> Option<Self> {
+        let tag = tag.trim_end();
+        if tag.len() > 8 {
+            return None;
+        }
+        let mut bytes = [b' '; 8];
+        bytes[..tag.len()].copy_from_slice(tag.as_bytes());
+        Some(Self(bytes))
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // Built from a `&str` padded with ASCII spaces, so always UTF-8.
+        std::str::from_utf8(&self.0).unwrap_or_default().trim_end()
+    }
+}
+
+impl fmt::Debug for CompressionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for CompressionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1,11 +1,25 @@
+mod compression_report;
+mod describe;
+mod frame;
+mod groups;
 mod labels;
 mod metadata;
 mod missing;
+mod page_index;
+mod schema;
 mod variables;
 
-pub use labels::{LabelSet, ValueKey, ValueLabel, ValueType};
+pub use compression_report::{CompressionReport, PageCompression};
+pub use describe::{DatasetDescription, DescribeStyle};
+pub use frame::{Batch, BatchIter, ColumnData, Dataset, DatasetRow};
+pub use groups::{RepeatedGroup, detect_repeated_groups};
+pub use labels::{LabelLookup, LabelSet, ValueKey, ValueLabel, ValueType};
 pub use metadata::{
-    Compression, DatasetMetadata, DatasetTimestamps, Endianness, SasVersion, Vendor,
+    Compression, CompressionSignature, DatasetMetadata, DatasetTimestamps, Endianness, SasVersion,
+    TimestampSource, Vendor,
 };
 pub use missing::{MissingLiteral, MissingRange, MissingValuePolicy, TaggedMissing};
+pub use page_index::{PageIndex, PageIndexEntry};
+pub(crate) use schema::kind_name;
+pub use schema::{SchemaDifference, SchemaFingerprint, SchemaMismatch, compare_schemas};
 pub use variables::{Alignment, Format, Measure, Variable, VariableKind};
@@ -0,0 +1,190 @@
+use crate::{
+    error::{Error, Result},
+    parser::DatasetLayout,
+};
+use std::{
+    borrow::Cow,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+const MAGIC: &[u8; 8] = b"S7BDIDX\0";
+const VERSION: u32 = 1;
+
+/// Rows stored on one data page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageIndexEntry {
+    pub page_index: u64,
+    /// Zero-based position of the page's first row in the dataset.
+    pub first_row: u64,
+    pub rows: u64,
+}
+
+/// Where the rows of a dataset live: every page that stores rows with the
+/// range of row numbers it holds.
+///
+/// Building the index reads each page header once without decoding rows.
+/// With it, row iterators jump straight to the page holding a given row,
+/// which matters for compressed files where row positions cannot be
+/// computed from the page size. Row boundaries inside a compressed page come
+/// from the page's own pointer table once the page is loaded.
+///
+/// The index can be saved next to the dataset (see [`PageIndex::sidecar_path`])
+/// and reloaded on later opens; it records the page geometry and header row
+/// count of the file it was built from and is rejected for any other file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageIndex {
+    page_size: u64,
+    page_count: u64,
+    data_offset: u64,
+    header_rows: u64,
+    trust_row_count: bool,
+    pages: Vec<PageIndexEntry>,
+}
+
+impl PageIndex {
+    pub(crate) fn from_pages(layout: &DatasetLayout, pages: Vec<PageIndexEntry>) -> Self {
+        Self {
+            page_size: u64::from(layout.header.page_size),
+            page_count: layout.header.page_count,
+            data_offset: layout.header.data_offset,
+            header_rows: layout.row_info.total_rows,
+            trust_row_count: layout.trusts_row_count(),
+            pages,
+        }
+    }
+
+    /// Returns `true` when the index was built from a file with the same
+    /// page geometry and row count as `layout`.
+    pub(crate) fn matches(&self, layout: &DatasetLayout) -> bool {
+        self.page_size == u64::from(layout.header.page_size)
+            && self.page_count == layout.header.page_count
+            && self.data_offset == layout.header.data_offset
+            && self.header_rows == layout.row_info.total_rows
+            && self.trust_row_count == layout.trusts_row_count()
+    }
+
+    /// Pages that store rows, in file order.
+    #[must_use]
+    pub fn pages(&self) -> &[PageIndexEntry] {
+        &self.pages
+    }
+
+    /// Rows found on the data pages.
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.pages
+            .last()
+            .map_or(0, |page| page.first_row + page.rows)
+    }
+
+    /// Returns the page holding row `row` (zero-based), or `None` past the
+    /// last row.
+    #[must_use]
+    pub fn locate(&self, row: u64) -> Option<PageIndexEntry> {
+        let position = self.pages.partition_point(|page| page.first_row <= row);
+        let page = self.pages.get(position.checked_sub(1)?)?;
+        (row < page.first_row + page.rows).then_some(*page)
+    }
+
+    /// Returns the sidecar path for the dataset at `path`: the same name
+    /// with `.idx` appended, e.g. `claims.sas7bdat.idx`.
+    #[must_use]
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".idx");
+        PathBuf::from(name)
+    }
+
+    /// Writes the index in its little-endian binary sidecar format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        for value in [
+            self.page_size,
+            self.page_count,
+            self.data_offset,
+            self.header_rows,
+            u64::from(self.trust_row_count),
+            self.pages.len() as u64,
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        for page in &self.pages {
+            writer.write_all(&page.page_index.to_le_bytes())?;
+            writer.write_all(&page.first_row.to_le_bytes())?;
+            writer.write_all(&page.rows.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads an index written by [`PageIndex::write_to`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the data is not a page index of
+    /// a supported version.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::InvalidMetadata {
+                details: Cow::from("not a sas7bdat page index"),
+            });
+        }
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != VERSION {
+            return Err(Error::Unsupported {
+                feature: format!("page index version {version}").into(),
+            });
+        }
+        let mut read_u64 = || -> Result<u64> {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        };
+        let page_size = read_u64()?;
+        let page_count = read_u64()?;
+        let data_offset = read_u64()?;
+        let header_rows = read_u64()?;
+        let trust_row_count = read_u64()? != 0;
+        let entries = read_u64()?;
+        if entries > page_count {
+            return Err(Error::InvalidMetadata {
+                details: format!("page index lists {entries} pages for a {page_count}-page file")
+                    .into(),
+            });
+        }
+        let mut pages = Vec::with_capacity(usize::try_from(entries.min(1 << 16)).unwrap_or(0));
+        let mut next_row = 0;
+        for _ in 0..entries {
+            let page = PageIndexEntry {
+                page_index: read_u64()?,
+                first_row: read_u64()?,
+                rows: read_u64()?,
+            };
+            if page.first_row != next_row || page.page_index >= page_count {
+                return Err(Error::InvalidMetadata {
+                    details: Cow::from("page index entries are out of order"),
+                });
+            }
+            next_row = page.first_row.saturating_add(page.rows);
+            pages.push(page);
+        }
+        Ok(Self {
+            page_size,
+            page_count,
+            data_offset,
+            header_rows,
+            trust_row_count,
+            pages,
+        })
+    }
+}
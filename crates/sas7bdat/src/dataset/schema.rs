@@ -0,0 +1,171 @@
+use crate::parser::{ColumnKind, DatasetLayout, NumericKind};
+use std::{fmt, hash::Hasher};
+use twox_hash::XxHash3_64;
+
+/// Column names and decoded kinds of a dataset, in stored order.
+///
+/// Two datasets with equal fingerprints convert to the same output schema,
+/// so comparing fingerprints up front catches mismatched snapshots before
+/// any output is written. Storage widths, labels and formats that do not
+/// change the decoded kind are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaFingerprint {
+    columns: Vec<(String, ColumnKind)>,
+}
+
+impl SchemaFingerprint {
+    #[must_use]
+    pub fn from_layout(layout: &DatasetLayout) -> Self {
+        let columns = layout
+            .header
+            .metadata
+            .variables
+            .iter()
+            .zip(&layout.columns)
+            .map(|(variable, column)| (variable.name.trim_end().to_owned(), column.kind))
+            .collect();
+        Self { columns }
+    }
+
+    /// Column names and kinds, in stored order.
+    #[must_use]
+    pub fn columns(&self) -> &[(String, ColumnKind)] {
+        &self.columns
+    }
+
+    /// XXH3-64 of the names and kinds as 16 lowercase hex digits, stable
+    /// across platforms and runs.
+    #[must_use]
+    pub fn digest(&self) -> String {
+        let mut hasher = XxHash3_64::new();
+        for (name, kind) in &self.columns {
+            hasher.write(&(name.len() as u64).to_le_bytes());
+            hasher.write(name.as_bytes());
+            hasher.write_u8(kind_tag(*kind));
+        }
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Lists how `other` departs from this schema: columns it lacks, columns
+    /// it adds and columns whose kind changed. Column order is not compared.
+    #[must_use]
+    pub fn differences(&self, other: &Self) -> Vec<SchemaDifference> {
+        let mut differences = Vec::new();
+        for (name, expected) in &self.columns {
+            match other.kind_of(name) {
+                None => differences.push(SchemaDifference::Missing {
+                    column: name.clone(),
+                }),
+                Some(found) if found != *expected => {
+                    differences.push(SchemaDifference::KindChanged {
+                        column: name.clone(),
+                        expected: *expected,
+                        found,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, _) in &other.columns {
+            if self.kind_of(name).is_none() {
+                differences.push(SchemaDifference::Added {
+                    column: name.clone(),
+                });
+            }
+        }
+        differences
+    }
+
+    fn kind_of(&self, name: &str) -> Option<ColumnKind> {
+        self.columns
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, kind)| *kind)
+    }
+}
+
+/// One way a dataset's schema departs from a reference schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaDifference {
+    Missing {
+        column: String,
+    },
+    Added {
+        column: String,
+    },
+    KindChanged {
+        column: String,
+        expected: ColumnKind,
+        found: ColumnKind,
+    },
+}
+
+impl fmt::Display for SchemaDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { column } => write!(f, "missing column '{column}'"),
+            Self::Added { column } => write!(f, "unexpected column '{column}'"),
+            Self::KindChanged {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "column '{column}' is {} instead of {}",
+                kind_name(*found),
+                kind_name(*expected)
+            ),
+        }
+    }
+}
+
+/// Schema differences of one source against the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// Position of the source in the compared list.
+    pub index: usize,
+    pub differences: Vec<SchemaDifference>,
+}
+
+/// Compares every fingerprint against the first and returns one entry per
+/// source that differs, so all incompatibilities can be reported at once.
+#[must_use]
+pub fn compare_schemas(fingerprints: &[SchemaFingerprint]) -> Vec<SchemaMismatch> {
+    let Some((reference, rest)) = fingerprints.split_first() else {
+        return Vec::new();
+    };
+    rest.iter()
+        .enumerate()
+        .map(|(offset, fingerprint)| SchemaMismatch {
+            index: offset + 1,
+            differences: reference.differences(fingerprint),
+        })
+        .filter(|mismatch| !mismatch.differences.is_empty())
+        .collect()
+}
+
+const fn kind_tag(kind: ColumnKind) -> u8 {
+    match kind {
+        ColumnKind::Numeric(NumericKind::Double) => 0,
+        ColumnKind::Numeric(NumericKind::Date) => 1,
+        ColumnKind::Numeric(NumericKind::DateTime) => 2,
+        ColumnKind::Numeric(NumericKind::Time) => 3,
+        ColumnKind::Character => 4,
+        ColumnKind::Bytes => 5,
+        ColumnKind::Numeric(NumericKind::Float) => 6,
+        ColumnKind::Numeric(NumericKind::Text) => 7,
+    }
+}
+
+pub const fn kind_name(kind: ColumnKind) -> &'static str {
+    match kind {
+        ColumnKind::Numeric(NumericKind::Double) => "numeric",
+        ColumnKind::Numeric(NumericKind::Date) => "date",
+        ColumnKind::Numeric(NumericKind::DateTime) => "datetime",
+        ColumnKind::Numeric(NumericKind::Time) => "time",
+        ColumnKind::Character => "character",
+        ColumnKind::Bytes => "bytes",
+        ColumnKind::Numeric(NumericKind::Float) => "float",
+        ColumnKind::Numeric(NumericKind::Text) => "numeric_text",
+    }
+}
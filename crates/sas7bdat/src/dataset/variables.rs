@@ -1,4 +1,5 @@
 use super::missing::MissingValuePolicy;
+use std::fmt;
 
 /// Variable metadata mirroring the SAS column descriptor.
 #[derive(Debug, Clone)]
@@ -7,6 +8,7 @@ pub struct Variable {
     pub name: String,
     pub label: Option<String>,
     pub format: Option<Format>,
+    pub informat: Option<Format>,
     pub kind: VariableKind,
     pub storage_width: usize,
     pub user_width: Option<usize>,
@@ -26,6 +28,7 @@ impl Variable {
             name,
             label: None,
             format: None,
+            informat: None,
             kind,
             storage_width,
             user_width: None,
@@ -45,7 +48,7 @@ impl Default for Variable {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VariableKind {
     Numeric,
     Character,
@@ -58,6 +61,21 @@ pub struct Format {
     pub decimals: Option<u16>,
 }
 
+/// Renders the format the way SAS writes it, such as `BEST12.` or `DATE9.`.
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(width) = self.width.filter(|&width| width > 0) {
+            write!(f, "{width}")?;
+        }
+        f.write_str(".")?;
+        if let Some(decimals) = self.decimals.filter(|&decimals| decimals > 0) {
+            write!(f, "{decimals}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Measure {
     Unknown,
@@ -66,6 +84,19 @@ pub enum Measure {
     Scale,
 }
 
+impl Measure {
+    /// Lowercase name used in exported metadata, such as `"ordinal"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Nominal => "nominal",
+            Self::Ordinal => "ordinal",
+            Self::Scale => "scale",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Alignment {
     Unknown,
@@ -73,3 +104,16 @@ pub enum Alignment {
     Center,
     Right,
 }
+
+impl Alignment {
+    /// Lowercase name used in exported metadata, such as `"right"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+        }
+    }
+}
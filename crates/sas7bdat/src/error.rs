@@ -1,17 +1,32 @@
+#[cfg(feature = "arrow")]
+use arrow_schema::ArrowError;
 #[cfg(feature = "parquet")]
 use parquet::errors::ParquetError;
-use std::{borrow::Cow, fmt, io};
+use std::{
+    any::Any,
+    borrow::Cow,
+    fmt, io,
+    panic::{self, AssertUnwindSafe},
+};
 
 /// Result type used across the high-level SAS reader implementation.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// High-level error type surfaced by the idiomatic SAS reader.
+///
+/// Variants may be added in minor releases. Bindings should dispatch on
+/// [`Error::category`] or [`Error::code`], which are stable.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// I/O failure while reading from the underlying data source.
     #[error(transparent)]
     Io(#[from] io::Error),
 
+    /// The input is not a SAS7BDAT or SAS7BCAT file.
+    #[error("not a SAS file: {details}")]
+    NotSasFile { details: Cow<'static, str> },
+
     /// Failure to convert bytes between character encodings.
     #[error("encoding conversion from {encoding} failed: {details}")]
     Encoding {
@@ -34,13 +49,164 @@ pub enum Error {
     #[error("invalid SAS metadata: {details}")]
     InvalidMetadata { details: Cow<'static, str> },
 
+    /// The caller passed an argument that does not fit the dataset or the
+    /// object's state, such as an unknown column name or a sink used after
+    /// its output was taken.
+    #[error("invalid argument: {details}")]
+    InvalidArgument { details: Cow<'static, str> },
+
     /// Failure encountered while interacting with the Parquet writer.
     #[error("parquet error: {details}")]
     Parquet { details: Cow<'static, str> },
 
-    /// Failed to allocate or grow internal buffers.
-    #[error("allocation failed: {details}")]
-    Allocation { details: Cow<'static, str> },
+    /// Failure encountered while assembling Arrow record batches.
+    #[error("arrow error: {details}")]
+    Arrow { details: Cow<'static, str> },
+
+    /// Failure reported by an output sink other than the Parquet and Arrow
+    /// writers, such as a CSV record or JSON line that could not be written.
+    #[error("sink error: {details}")]
+    Sink { details: Cow<'static, str> },
+
+    /// A size exceeded what the reader or the current platform can handle.
+    #[error("limit exceeded: {details}")]
+    Limit { details: Cow<'static, str> },
+
+    /// A conversion spec could not be parsed or does not fit the dataset.
+    #[error("invalid conversion spec: {details}")]
+    Config { details: Cow<'static, str> },
+
+    /// The operation was stopped through a
+    /// [`CancellationToken`](crate::reader::CancellationToken).
+    #[error("operation cancelled")]
+    Cancelled,
+
+    /// The reader panicked; see [`catch_panic`]. Always a bug worth reporting.
+    #[error("internal error: {details}")]
+    Internal { details: Cow<'static, str> },
+}
+
+/// Stable classification of [`Error`] values for callers that map errors
+/// onto their own exception hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// Reading from or writing to the underlying stream failed.
+    Io,
+    /// The input is not a SAS file at all.
+    NotSasFile,
+    /// The file is a SAS file but its contents are inconsistent.
+    Corrupted,
+    /// The file uses a SAS feature the reader does not implement.
+    Unsupported,
+    /// A size or resource limit was exceeded.
+    Limit,
+    /// An output sink rejected the data.
+    Sink,
+    /// Caller-supplied configuration is invalid.
+    Config,
+    /// The caller misused the API, for example by naming a column the
+    /// dataset does not have.
+    InvalidArgument,
+    /// The caller cancelled the operation.
+    Cancelled,
+    /// The reader hit a bug instead of a well-formed error.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Machine-readable identifier, stable across releases.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Io => "io",
+            Self::NotSasFile => "not_sas_file",
+            Self::Corrupted => "corrupted",
+            Self::Unsupported => "unsupported",
+            Self::Limit => "limit",
+            Self::Sink => "sink",
+            Self::Config => "config",
+            Self::InvalidArgument => "invalid_argument",
+            Self::Cancelled => "cancelled",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Error {
+    /// Returns the broad category of this error.
+    #[must_use]
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Io(_) => ErrorCategory::Io,
+            Self::NotSasFile { .. } => ErrorCategory::NotSasFile,
+            Self::Encoding { .. } | Self::Corrupted { .. } | Self::InvalidMetadata { .. } => {
+                ErrorCategory::Corrupted
+            }
+            Self::Unsupported { .. } => ErrorCategory::Unsupported,
+            Self::Limit { .. } => ErrorCategory::Limit,
+            Self::Parquet { .. } | Self::Arrow { .. } | Self::Sink { .. } => ErrorCategory::Sink,
+            Self::Config { .. } => ErrorCategory::Config,
+            Self::InvalidArgument { .. } => ErrorCategory::InvalidArgument,
+            Self::Cancelled => ErrorCategory::Cancelled,
+            Self::Internal { .. } => ErrorCategory::Internal,
+        }
+    }
+
+    /// Machine-readable identifier of the specific error kind, stable across
+    /// releases. Codes are prefixed with the category code.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::NotSasFile { .. } => "not_sas_file",
+            Self::Encoding { .. } => "corrupted.encoding",
+            Self::Corrupted { .. } => "corrupted",
+            Self::InvalidMetadata { .. } => "corrupted.metadata",
+            Self::Unsupported { .. } => "unsupported",
+            Self::Limit { .. } => "limit",
+            Self::Parquet { .. } => "sink.parquet",
+            Self::Arrow { .. } => "sink.arrow",
+            Self::Sink { .. } => "sink",
+            Self::Config { .. } => "config",
+            Self::InvalidArgument { .. } => "invalid_argument",
+            Self::Cancelled => "cancelled",
+            Self::Internal { .. } => "internal",
+        }
+    }
+}
+
+/// Runs `f`, turning a panic into [`Error::Internal`] instead of unwinding
+/// further.
+///
+/// Malformed files are reported through ordinary errors, but foreign-function
+/// layers (the R and Python bindings, C callers) must never let a panic cross
+/// their boundary, where it would abort the host process. Wrap each entry
+/// point in this helper; the panic hook still runs, so the message is logged.
+///
+/// # Errors
+///
+/// Returns [`Error::Internal`] carrying the panic message if `f` panicked.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| Error::Internal {
+        details: panic_message(payload),
+    })
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> Cow<'static, str> {
+    match payload.downcast::<String>() {
+        Ok(message) => Cow::Owned(*message),
+        Err(payload) => payload.downcast_ref::<&'static str>().map_or(
+            Cow::Borrowed("panic with a non-string payload"),
+            |message| Cow::Borrowed(*message),
+        ),
+    }
 }
 
 /// Logical section of the parser used for diagnostic reporting.
@@ -75,6 +241,15 @@ impl From<ParquetError> for Error {
     }
 }
 
+#[cfg(feature = "arrow")]
+impl From<ArrowError> for Error {
+    fn from(err: ArrowError) -> Self {
+        Self::Arrow {
+            details: Cow::Owned(err.to_string()),
+        }
+    }
+}
+
 impl fmt::Display for Section {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -0,0 +1,346 @@
+//! Parsing and classification of SAS format specifications.
+//!
+//! A specification such as `DATETIME20.3` or `$CHAR10.` is split into the
+//! format name, width and decimals, and the name is classified against the
+//! formats documented in the SAS formats reference so callers can tell
+//! dates, times and datetimes from plain numbers without guessing from
+//! substrings.
+
+use crate::{dataset::Format, parser::NumericKind};
+use std::num::ParseIntError;
+
+/// What kind of value a format displays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatCategory {
+    /// Plain numbers: `BEST.`, `COMMA.`, `w.d`, user-defined numeric formats.
+    Numeric,
+    /// Character formats, whose names start with `$`.
+    Character,
+    /// Monetary amounts: `DOLLAR.`, `EURO.`, `NLMNY.`, ...
+    Currency,
+    /// Days since 1960-01-01.
+    Date,
+    /// Seconds since midnight.
+    Time,
+    /// Seconds since 1960-01-01 00:00:00.
+    DateTime,
+}
+
+impl FormatCategory {
+    /// Lowercase name used in exported metadata, such as `"datetime"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Numeric => "numeric",
+            Self::Character => "character",
+            Self::Currency => "currency",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::DateTime => "datetime",
+        }
+    }
+
+    /// The decoded value type of numeric columns carrying this format;
+    /// `None` for formats that leave numbers as plain doubles.
+    #[must_use]
+    pub const fn numeric_kind(self) -> Option<NumericKind> {
+        match self {
+            Self::Date => Some(NumericKind::Date),
+            Self::Time => Some(NumericKind::Time),
+            Self::DateTime => Some(NumericKind::DateTime),
+            Self::Numeric | Self::Character | Self::Currency => None,
+        }
+    }
+}
+
+impl Format {
+    /// Classifies this format by its name; see [`classify`].
+    #[must_use]
+    pub fn category(&self) -> FormatCategory {
+        classify(&self.name)
+    }
+}
+
+/// Parses a format specification such as `DATETIME20.3`, `DATE9.`,
+/// `$CHAR10.`, `8.2` or a bare name such as `YYMMDD`.
+///
+/// The name is upper-cased; SAS format names never end in a digit, so
+/// trailing digits before the period are the width. Returns `None` when
+/// `spec` is not a format specification.
+#[must_use]
+pub fn parse(spec: &str) -> Option<Format> {
+    let spec = spec.trim().to_ascii_uppercase();
+    let (head, decimals) = spec.split_once('.').unwrap_or((&spec, ""));
+    let name_len = head.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (name, width) = head.split_at(name_len);
+    let bare = name.strip_prefix('$').unwrap_or(name);
+    if bare.starts_with(|c: char| c.is_ascii_digit())
+        || !bare.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        || (name.is_empty() && width.is_empty())
+        || !decimals.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    Some(Format {
+        name: name.to_owned(),
+        width: parse_number(width).ok()?,
+        decimals: parse_number(decimals).ok()?,
+    })
+}
+
+/// Parses a run of digits; `Ok(None)` when it is empty.
+fn parse_number(digits: &str) -> Result<Option<u16>, ParseIntError> {
+    (!digits.is_empty()).then(|| digits.parse()).transpose()
+}
+
+/// Formats writing dates, in addition to the separator families in
+/// [`DATE_FAMILIES`] and the `NLDATE` and `EURDF` prefixes.
+const DATE_FORMATS: &[&str] = &[
+    "ANYDTDTE", "B8601DA", "DATE", "DAY", "DOWNAME", "E8601DA", "HDATE", "HEBDATE", "IS8601DA",
+    "JULDAY", "JULIAN", "MINGUO", "MONNAME", "MONTH", "MONYY", "ND8601DA", "NENGO", "PDJULG",
+    "PDJULI", "QTR", "QTRR", "WEEKDATE", "WEEKDATX", "WEEKDAY", "WEEKU", "WEEKV", "WEEKW",
+    "WORDDATE", "WORDDATX", "YEAR", "YYMON", "YYWEEKU", "YYWEEKV", "YYWEEKW",
+];
+
+/// Date formats that take an optional separator suffix, e.g. `MMDDYYS`.
+const DATE_FAMILIES: &[&str] = &["DDMMYY", "MMDDYY", "MMYY", "YYMM", "YYMMDD", "YYQ", "YYQR"];
+
+/// Separator suffixes of [`DATE_FAMILIES`]: blank, colon, dash, none,
+/// period and slash.
+const DATE_SEPARATORS: &[char] = &['B', 'C', 'D', 'N', 'P', 'S'];
+
+/// Formats writing times of day.
+const TIME_FORMATS: &[&str] = &[
+    "ANYDTTME", "B8601LZ", "B8601TM", "B8601TX", "B8601TZ", "E8601LZ", "E8601TM", "E8601TX",
+    "E8601TZ", "HHMM", "HOUR", "IS8601LZ", "IS8601TM", "IS8601TZ", "MMSS", "ND8601TM", "ND8601TZ",
+    "NLTIMAP", "NLTIME", "STIMER", "TIME", "TIMEAMPM", "TOD",
+];
+
+/// Formats writing datetimes, in addition to the `DT` and `NLDATM` prefixes.
+const DATETIME_FORMATS: &[&str] = &[
+    "ANYDTDTM", "B8601DN", "B8601DT", "B8601DX", "B8601DZ", "B8601LX", "DATEAMPM", "DATETIME",
+    "E8601DN", "E8601DT", "E8601DX", "E8601DZ", "E8601LX", "EURDFDT", "IS8601DN", "IS8601DT",
+    "IS8601DZ", "MDYAMPM", "ND8601DN", "ND8601DT", "ND8601DZ",
+];
+
+/// Formats writing monetary amounts, in addition to the `NLMNL` and
+/// `NLMNI` prefixes.
+const CURRENCY_FORMATS: &[&str] = &[
+    "DOLLAR", "DOLLARX", "EURO", "EUROX", "NLMNY", "NLMNYI", "YEN",
+];
+
+/// Classifies a format by name, ignoring case, surrounding blanks and any
+/// width or decimals, e.g. `"datetime20.3"` is a [`FormatCategory::DateTime`].
+///
+/// Names not in the SAS formats reference, including user-defined formats,
+/// are [`FormatCategory::Numeric`] unless they start with `$`.
+#[must_use]
+pub fn classify(name: &str) -> FormatCategory {
+    let Some(format) = parse(name) else {
+        return FormatCategory::Numeric;
+    };
+    let name = format.name.as_str();
+    if name.starts_with('$') {
+        FormatCategory::Character
+    } else if DATETIME_FORMATS.contains(&name)
+        || name.starts_with("DT")
+        || name.starts_with("NLDATM")
+    {
+        FormatCategory::DateTime
+    } else if TIME_FORMATS.contains(&name) {
+        FormatCategory::Time
+    } else if DATE_FORMATS.contains(&name)
+        || is_date_family(name)
+        || name.starts_with("NLDATE")
+        || name.starts_with("EURDF")
+    {
+        FormatCategory::Date
+    } else if CURRENCY_FORMATS.contains(&name)
+        || name.starts_with("NLMNL")
+        || name.starts_with("NLMNI")
+    {
+        FormatCategory::Currency
+    } else {
+        FormatCategory::Numeric
+    }
+}
+
+fn is_date_family(name: &str) -> bool {
+    DATE_FAMILIES.iter().any(|family| {
+        name.strip_prefix(family).is_some_and(|suffix| {
+            suffix.is_empty() || (suffix.len() == 1 && suffix.starts_with(DATE_SEPARATORS))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(spec: &str) -> (String, Option<u16>, Option<u16>) {
+        let format = parse(spec).unwrap_or_else(|| panic!("{spec} parses"));
+        (format.name, format.width, format.decimals)
+    }
+
+    #[test]
+    fn parses_name_width_and_decimals() {
+        assert_eq!(
+            parts("DATETIME20.3"),
+            ("DATETIME".into(), Some(20), Some(3))
+        );
+        assert_eq!(parts("date9."), ("DATE".into(), Some(9), None));
+        assert_eq!(parts("$CHAR10."), ("$CHAR".into(), Some(10), None));
+        assert_eq!(parts("$10."), ("$".into(), Some(10), None));
+        assert_eq!(parts("8.2"), (String::new(), Some(8), Some(2)));
+        assert_eq!(parts("E8601DT19.3"), ("E8601DT".into(), Some(19), Some(3)));
+        assert_eq!(parts(" YYMMDD "), ("YYMMDD".into(), None, None));
+        assert_eq!(parts("BEST."), ("BEST".into(), None, None));
+    }
+
+    #[test]
+    fn rejects_non_format_text() {
+        for spec in [
+            "",
+            ".",
+            "DATE9.X",
+            "DATE 9.",
+            "9DATE.",
+            "DATE9.3.1",
+            "BEST99999.",
+        ] {
+            assert!(parse(spec).is_none(), "{spec} should not parse");
+        }
+    }
+
+    #[test]
+    fn parsed_formats_render_back() {
+        for spec in ["DATETIME20.3", "DATE9.", "$CHAR10.", "COMMA12.2", "BEST."] {
+            assert_eq!(parse(spec).expect("parses").to_string(), spec);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn classifies_documented_formats() {
+        let cases: &[(FormatCategory, &[&str])] = &[
+            (
+                FormatCategory::Date,
+                &[
+                    "DATE9.",
+                    "DAY2.",
+                    "DDMMYY10.",
+                    "DDMMYYS10.",
+                    "DOWNAME.",
+                    "E8601DA.",
+                    "EURDFDD10.",
+                    "JULIAN7.",
+                    "MMDDYY10.",
+                    "MMDDYYN8.",
+                    "MMYY.",
+                    "MONNAME.",
+                    "MONTH.",
+                    "MONYY7.",
+                    "NLDATE20.",
+                    "NLDATEYM.",
+                    "QTR.",
+                    "WEEKDATE29.",
+                    "WEEKU.",
+                    "WORDDATE18.",
+                    "YEAR4.",
+                    "YYMM.",
+                    "YYMMDD10.",
+                    "YYMMDDD10.",
+                    "YYMON.",
+                    "YYQ6.",
+                    "YYQRC.",
+                    "MINGUO.",
+                    "B8601DA.",
+                ],
+            ),
+            (
+                FormatCategory::Time,
+                &[
+                    "TIME8.",
+                    "TIMEAMPM11.",
+                    "TOD8.",
+                    "HHMM5.",
+                    "HOUR.",
+                    "MMSS.",
+                    "E8601TM.",
+                    "B8601TZ.",
+                    "NLTIME.",
+                    "NLTIMAP.",
+                ],
+            ),
+            (
+                FormatCategory::DateTime,
+                &[
+                    "DATETIME20.",
+                    "DATEAMPM.",
+                    "DTDATE9.",
+                    "DTMONYY.",
+                    "DTWKDATX.",
+                    "DTYEAR.",
+                    "E8601DT19.3",
+                    "B8601DZ.",
+                    "E8601DN.",
+                    "IS8601DT.",
+                    "MDYAMPM.",
+                    "NLDATM.",
+                    "NLDATMAP.",
+                    "EURDFDT.",
+                ],
+            ),
+            (
+                FormatCategory::Currency,
+                &[
+                    "DOLLAR10.2",
+                    "DOLLARX.",
+                    "EURO.",
+                    "EUROX.",
+                    "NLMNY.",
+                    "NLMNLUSD.",
+                    "YEN.",
+                ],
+            ),
+            (
+                FormatCategory::Character,
+                &["$CHAR10.", "$10.", "$UPCASE.", "$HEX4."],
+            ),
+            (
+                FormatCategory::Numeric,
+                &[
+                    "BEST12.",
+                    "8.2",
+                    "F8.",
+                    "COMMA12.2",
+                    "PERCENT8.1",
+                    "Z5.",
+                    "MONEY.",
+                    "MYDATEFMT.",
+                    "TIMEFMT.",
+                    "DOLLARS.",
+                    "MMDDYYX.",
+                    "ABC",
+                ],
+            ),
+        ];
+        for (category, specs) in cases {
+            for spec in *specs {
+                assert_eq!(classify(spec), *category, "{spec}");
+            }
+        }
+    }
+
+    #[test]
+    fn categories_map_to_numeric_kinds() {
+        assert_eq!(classify("DATE9.").numeric_kind(), Some(NumericKind::Date));
+        assert_eq!(classify("TIME8.").numeric_kind(), Some(NumericKind::Time));
+        assert_eq!(
+            classify("DATETIME.").numeric_kind(),
+            Some(NumericKind::DateTime)
+        );
+        assert_eq!(classify("DOLLAR8.").numeric_kind(), None);
+        assert_eq!(classify("BEST.").numeric_kind(), None);
+    }
+}
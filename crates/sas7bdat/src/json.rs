@@ -0,0 +1,120 @@
+//! JSON representation of decoded values.
+//!
+//! [`CellValue::to_json`], [`Row::to_json`](crate::Row::to_json),
+//! [`RowView::to_json`](crate::RowView::to_json) and
+//! [`JsonlSink`](crate::JsonlSink) share these rules, so a row looks the
+//! same whichever layer serialises it:
+//!
+//! - missing values and non-finite floats become `null`;
+//! - strings are written as they are, and byte columns as strings with
+//!   invalid UTF-8 replaced;
+//! - dates, datetimes and times follow the chosen [`DateStyle`].
+
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    time_conv::{SAS_EPOCH, datetime_to_sas_seconds, time_to_sas_seconds},
+};
+use serde_json::{Number, Value};
+use std::borrow::Cow;
+use time::{Duration, OffsetDateTime, format_description::well_known::Rfc3339};
+
+const NANOS_PER_MILLI: i128 = 1_000_000;
+
+/// How dates, datetimes and times are written as JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DateStyle {
+    /// ISO 8601 strings: dates as `"YYYY-MM-DD"`, datetimes as RFC 3339 in
+    /// UTC and times as `"HH:MM:SS[.ffffff]"`.
+    #[default]
+    Iso,
+    /// The numbers SAS stores: whole days since 1960-01-01 for dates,
+    /// seconds since 1960-01-01 00:00 for datetimes and seconds since
+    /// midnight for times.
+    Sas,
+    /// Whole milliseconds since 1970-01-01 00:00 UTC for dates and
+    /// datetimes and since midnight for times, as JavaScript's `Date`
+    /// expects.
+    UnixMillis,
+}
+
+impl CellValue<'_> {
+    /// Converts the value to JSON following the [module rules](crate::json).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a datetime cannot be written in `style`, such as
+    /// a year outside 0–9999 as RFC 3339.
+    pub fn to_json(&self, style: DateStyle) -> Result<Value> {
+        Ok(match self {
+            Self::Missing(_) => Value::Null,
+            Self::Float(value) => Number::from_f64(*value).map_or(Value::Null, Value::Number),
+            Self::Int32(value) => Value::from(*value),
+            Self::Int64(value) => Value::from(*value),
+            Self::Str(text) | Self::NumericString(text) => Value::String(text.to_string()),
+            Self::Bytes(bytes) => Value::String(String::from_utf8_lossy(bytes).into_owned()),
+            Self::Date(date) => match style {
+                DateStyle::Iso => Value::String(date.date().to_string()),
+                DateStyle::Sas => Value::from((date.date() - SAS_EPOCH.date()).whole_days()),
+                DateStyle::UnixMillis => Value::from(unix_millis(date)?),
+            },
+            Self::DateTime(datetime) => match style {
+                DateStyle::Iso => Value::String(format_datetime(datetime)?),
+                DateStyle::Sas => seconds(datetime_to_sas_seconds(datetime)),
+                DateStyle::UnixMillis => Value::from(unix_millis(datetime)?),
+            },
+            Self::Time(duration) => match style {
+                DateStyle::Iso => Value::String(format_time(*duration)),
+                DateStyle::Sas => seconds(time_to_sas_seconds(*duration)),
+                DateStyle::UnixMillis => Value::from(millis(duration.whole_nanoseconds())?),
+            },
+        })
+    }
+}
+
+/// Collects `(name, value)` pairs into a JSON object.
+pub(crate) fn object<'a, I>(entries: I, style: DateStyle) -> Result<Value>
+where
+    I: IntoIterator<Item = (&'a str, Result<CellValue<'a>>)>,
+{
+    let mut object = serde_json::Map::new();
+    for (name, value) in entries {
+        object.insert(name.to_owned(), value?.to_json(style)?);
+    }
+    Ok(Value::Object(object))
+}
+
+fn seconds(seconds: f64) -> Value {
+    Number::from_f64(seconds).map_or(Value::Null, Value::Number)
+}
+
+fn unix_millis(datetime: &OffsetDateTime) -> Result<i64> {
+    millis(datetime.unix_timestamp_nanos())
+}
+
+fn millis(nanos: i128) -> Result<i64> {
+    i64::try_from(nanos.div_euclid(NANOS_PER_MILLI)).map_err(|_| Error::InvalidMetadata {
+        details: Cow::from("temporal value out of range for milliseconds"),
+    })
+}
+
+pub(crate) fn format_datetime(datetime: &OffsetDateTime) -> Result<String> {
+    datetime
+        .format(&Rfc3339)
+        .map_err(|err| Error::InvalidMetadata {
+            details: Cow::Owned(format!(
+                "datetime {datetime} cannot be written as RFC 3339: {err}"
+            )),
+        })
+}
+
+pub(crate) fn format_time(duration: Duration) -> String {
+    let sign = if duration.is_negative() { "-" } else { "" };
+    let duration = duration.abs();
+    let seconds = duration.whole_seconds();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match duration.subsec_microseconds() {
+        0 => format!("{sign}{hours:02}:{minutes:02}:{seconds:02}"),
+        micros => format!("{sign}{hours:02}:{minutes:02}:{seconds:02}.{micros:06}"),
+    }
+}
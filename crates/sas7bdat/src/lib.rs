@@ -1,22 +1,59 @@
 pub mod cell;
 pub mod dataset;
 pub mod error;
+pub mod formats;
 mod iter_utils;
+pub mod json;
 pub mod logger;
 pub mod parser;
 pub mod reader;
+pub mod schema;
 pub mod sinks;
-pub use crate::error::{Error, Result};
+#[cfg(feature = "spec")]
+pub mod spec;
+pub mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod time_conv;
+pub mod value;
+pub use crate::error::{Error, ErrorCategory, Result, catch_panic};
 pub use cell::{CellValue, MissingValue};
-pub use parser::{MetadataIoMode, MetadataReadOptions};
+pub use dataset::Dataset;
+pub use json::DateStyle;
+pub use parser::{
+    BatchSizing, ColumnOrder, ColumnType, Diagnostic, Diagnostics, MetadataIoMode,
+    MetadataReadOptions, PageDecompressor, ParseMode, ParseWarning, TimestampRounding,
+    UnsupportedNumericPolicy, register_page_decompressor,
+};
 pub use reader::{
-    Row, RowIter, RowLookup, RowSelection, RowValue, RowView, RowViewIter, SasReader,
+    CancellationToken, Catalog, CatalogConflict, ColumnStats, CompareOp, ContentFingerprint,
+    DateGranularity, DatePartition, DecryptLayer, DecryptingReader, DuplicateGroup,
+    DuplicateReport, IssueSeverity, LookupKey, MetadataEditor, Predicate, QualityCheckOptions,
+    QualityIssue, QualityIssueKind, QualityReport, ReadAhead, ReadAheadReader, Row, RowIter,
+    RowLookup, RowSelection, RowValue, RowView, RowViewIter, SasReader, StatValue, StreamReader,
+    ValidationIssue, ValidationReport, find_duplicate_files, validate_file,
 };
+#[cfg(feature = "http")]
+pub use reader::{HttpOptions, HttpReader};
+#[cfg(feature = "object_store")]
+pub use reader::{ObjectStoreOptions, ObjectStoreReader};
+#[cfg(feature = "arrow")]
+pub use sinks::ArrowSink;
 #[cfg(feature = "csv")]
 pub use sinks::CsvSink;
+#[cfg(feature = "ipc")]
+pub use sinks::IpcSink;
+pub use sinks::{
+    ChecksumSink, ColumnarSink, DedupKeep, DedupSink, JsonlSink, MeltSink, RowSink, SinkContext,
+    SinkKind, Utf8Replacements, Utf8Sink, estimate_output_size,
+};
 #[cfg(feature = "parquet")]
-pub use sinks::ParquetSink;
-pub use sinks::{ColumnarSink, RowSink, SinkContext};
+pub use sinks::{
+    ParquetColumnType, ParquetDataset, ParquetSink, PartitionedParquetSink, TimePrecision,
+    TypeMapper,
+};
+#[cfg(feature = "spec")]
+pub use spec::{ConversionReport, ConversionSpec};
 #[cfg(feature = "time")]
 pub use time::OffsetDateTime;
 
@@ -1,15 +1,17 @@
 use std::{
     cell::RefCell,
-    fmt::Display,
+    fmt::{self, Display},
     fs::File,
     io::{Result as IoResult, Write},
     path::Path,
     sync::{Arc, Mutex, OnceLock},
+    thread,
 };
 
 static LOG_FILE: OnceLock<Arc<Mutex<File>>> = OnceLock::new();
 thread_local! {
     static LOG_PREFIX: RefCell<Option<String>> = const { RefCell::new(None) };
+    static CURRENT_LOGGER: RefCell<Option<Logger>> = const { RefCell::new(None) };
 }
 
 fn with_prefix<F, R>(f: F) -> R
@@ -19,10 +21,6 @@ where
     LOG_PREFIX.with(|prefix| f(prefix.borrow().clone()))
 }
 
-fn format_with_prefix(message: impl Display) -> String {
-    with_prefix(|prefix| prefix.map_or_else(|| message.to_string(), |p| format!("{p}: {message}")))
-}
-
 /// Configures a log file for warnings/errors emitted by the converter.
 ///
 /// # Errors
@@ -62,20 +60,301 @@ impl Drop for LogPrefixGuard {
     }
 }
 
+/// Severity of a log message, ordered from least to most verbose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum LogLevel {
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warning",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single message handed to a [`LogSink`].
+#[derive(Debug, Clone, Copy)]
+pub struct LogRecord<'a> {
+    pub level: LogLevel,
+    /// Context of the emitting logger, typically the source file path.
+    pub context: Option<&'a str>,
+    /// Name of the emitting thread, if it has one.
+    pub thread: Option<&'a str>,
+    pub message: &'a str,
+}
+
+impl Display for LogRecord<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.level)?;
+        if let Some(thread) = self.thread {
+            write!(f, "[{thread}] ")?;
+        }
+        if let Some(context) = self.context {
+            write!(f, "{context}: ")?;
+        }
+        f.write_str(self.message)
+    }
+}
+
+/// Destination for log records. Implementations must be safe to share
+/// between threads, as one sink may serve several readers.
+pub trait LogSink: Send + Sync {
+    fn log(&self, record: &LogRecord<'_>);
+}
+
+/// Writes every record to standard error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StderrSink;
+
+impl LogSink for StderrSink {
+    fn log(&self, record: &LogRecord<'_>) {
+        eprintln!("{record}");
+    }
+}
+
+/// Writes every record as a line to a shared writer.
+#[derive(Debug)]
+pub struct WriterSink<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> WriterSink<W> {
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Returns the writer, recovering it if a writing thread panicked.
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<W: Write + Send> LogSink for WriterSink<W> {
+    fn log(&self, record: &LogRecord<'_>) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{record}");
+        }
+    }
+}
+
+/// Process-wide behaviour: standard error plus the file configured with
+/// [`set_log_file`], prefixed by the context or the thread-local prefix.
+struct GlobalSink;
+
+impl LogSink for GlobalSink {
+    fn log(&self, record: &LogRecord<'_>) {
+        let prefix = record
+            .context
+            .map_or_else(|| with_prefix(|p| p), |context| Some(context.to_owned()));
+        let message = prefix.map_or_else(
+            || record.message.to_owned(),
+            |p| format!("{p}: {}", record.message),
+        );
+        eprintln!("{message}");
+        if let Some(writer) = LOG_FILE.get()
+            && let Ok(mut file) = writer.lock()
+        {
+            let _ = writeln!(file, "{}: {message}", record.level);
+        }
+    }
+}
+
+/// Cheaply clonable logging handle with a level filter and a context string.
+///
+/// Each [`SasReader`](crate::SasReader) carries its own logger, so several
+/// files processed concurrently can report to different sinks and every
+/// message names the file it came from. The default logger keeps the
+/// process-wide behaviour of [`set_log_file`] and [`set_log_prefix`].
+#[derive(Clone)]
+pub struct Logger {
+    sink: Arc<dyn LogSink>,
+    level: LogLevel,
+    context: Option<Arc<str>>,
+}
+
+impl Logger {
+    /// Creates a logger that reports warnings and errors to `sink`.
+    pub fn new(sink: impl LogSink + 'static) -> Self {
+        Self::from_shared(Arc::new(sink))
+    }
+
+    /// Creates a logger for a sink shared with other loggers.
+    #[must_use]
+    pub fn from_shared(sink: Arc<dyn LogSink>) -> Self {
+        Self {
+            sink,
+            level: LogLevel::default(),
+            context: None,
+        }
+    }
+
+    /// Returns the logger installed on this thread with [`Logger::enter`], or
+    /// the default logger.
+    #[must_use]
+    pub fn current() -> Self {
+        CURRENT_LOGGER
+            .with(|slot| slot.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Sets the most verbose level that is still emitted.
+    #[must_use]
+    pub const fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the context included in every message, such as a file path.
+    #[must_use]
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(Arc::from(context.into()));
+        self
+    }
+
+    #[must_use]
+    pub const fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    #[must_use]
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    #[must_use]
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level <= self.level
+    }
+
+    /// Installs this logger as the current logger of the calling thread until
+    /// the returned guard is dropped. Messages from code without access to a
+    /// reader, such as metadata parsing, are routed to it.
+    #[must_use]
+    pub fn enter(&self) -> LoggerGuard {
+        let previous = CURRENT_LOGGER.with(|slot| slot.borrow_mut().replace(self.clone()));
+        LoggerGuard { previous }
+    }
+
+    pub fn log(&self, level: LogLevel, message: &str) {
+        if !self.enabled(level) {
+            return;
+        }
+        let current = thread::current();
+        self.sink.log(&LogRecord {
+            level,
+            context: self.context(),
+            thread: current.name(),
+            message,
+        });
+    }
+
+    pub fn error(&self, message: &str) {
+        self.log(LogLevel::Error, message);
+    }
+
+    pub fn warn(&self, message: &str) {
+        self.log(LogLevel::Warn, message);
+    }
+
+    pub fn info(&self, message: &str) {
+        self.log(LogLevel::Info, message);
+    }
+
+    pub fn debug(&self, message: &str) {
+        self.log(LogLevel::Debug, message);
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new(GlobalSink)
+    }
+}
+
+impl fmt::Debug for Logger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Logger")
+            .field("level", &self.level)
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Restores the previously installed thread logger on drop.
+pub struct LoggerGuard {
+    previous: Option<Logger>,
+}
+
+impl Drop for LoggerGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_LOGGER.with(|slot| {
+            *slot.borrow_mut() = previous;
+        });
+    }
+}
+
 pub fn log_warn(message: &str) {
-    log_with_level("warning", message);
+    Logger::current().warn(message);
 }
 
 pub fn log_error(message: &str) {
-    log_with_level("error", message);
+    Logger::current().error(message);
+}
+
+pub fn log_info(message: &str) {
+    Logger::current().info(message);
+}
+
+pub fn log_debug(message: &str) {
+    Logger::current().debug(message);
 }
 
-fn log_with_level(level: &str, message: &str) {
-    let message = format_with_prefix(message);
-    eprintln!("{message}");
-    if let Some(writer) = LOG_FILE.get()
-        && let Ok(mut file) = writer.lock()
-    {
-        let _ = writeln!(file, "{level}: {message}");
+#[cfg(test)]
+mod tests {
+    use super::{LogLevel, Logger, WriterSink};
+    use std::sync::Arc;
+
+    #[test]
+    fn level_filter_and_context_apply_per_logger() {
+        let sink = Arc::new(WriterSink::new(Vec::new()));
+        let logger = Logger::from_shared(sink.clone())
+            .with_level(LogLevel::Info)
+            .with_context("a.sas7bdat");
+        logger.debug("hidden");
+        logger.info("shown");
+        {
+            let _guard = logger.clone().with_context("b.sas7bdat").enter();
+            super::log_warn("scoped");
+        }
+        drop(logger);
+
+        let sink = Arc::into_inner(sink).expect("sole owner");
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2, "{output}");
+        assert!(lines[0].starts_with("info: "));
+        assert!(lines[0].ends_with("a.sas7bdat: shown"));
+        assert!(lines[1].ends_with("b.sas7bdat: scoped"));
     }
 }
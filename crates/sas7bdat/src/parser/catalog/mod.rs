@@ -6,6 +6,7 @@ use crate::{
             byteorder::{read_u16, read_u32, read_u64, read_u64_be},
             encoding::{resolve_encoding, trim_trailing},
             float_utils::try_int_from_f64,
+            platform::usize_from,
         },
         header::{SasHeader, parse_header},
     },
@@ -341,15 +342,9 @@ fn parse_value_labels(
     label_count_capacity: u64,
     value_type: ValueType,
 ) -> Result<Vec<ValueLabel>> {
-    let label_count = usize::try_from(label_count_used).map_err(|_| Error::Unsupported {
-        feature: Cow::from("catalog label count exceeds platform pointer width"),
-    })?;
-    let capacity = usize::try_from(label_count_capacity).map_err(|_| Error::Unsupported {
-        feature: Cow::from("catalog label capacity exceeds platform pointer width"),
-    })?;
-    let pad = usize::try_from(header.pad_alignment).map_err(|_| Error::Unsupported {
-        feature: Cow::from("catalog label padding exceeds platform pointer width"),
-    })?;
+    let label_count = usize_from(label_count_used, "catalog label count")?;
+    let capacity = usize_from(label_count_capacity, "catalog label capacity")?;
+    let pad = usize_from(header.pad_alignment, "catalog label padding")?;
 
     let (offsets, label_blob_offset) =
         parse_value_label_offsets(bytes, header, pad, label_count, capacity)?;
@@ -454,6 +449,7 @@ fn parse_value_label_entries(
         }
 
         let key = parse_value_label_key(entry, entry_len, header, encoding, value_type)?;
+        let end = parse_value_label_end(entry, entry_len, &key, value_type);
 
         if label_cursor + 10 > bytes.len() {
             return Err(Error::Corrupted {
@@ -466,7 +462,7 @@ fn parse_value_label_entries(
         let available = lbp2.len().saturating_sub(10);
         label_len = min(label_len, available);
         let label = decode_text(&lbp2[10..10 + label_len], encoding)?;
-        labels.push(ValueLabel { key, label });
+        labels.push(ValueLabel { key, end, label });
         let skip = 8 + 2 + label_len + 1;
         label_cursor = label_cursor.saturating_add(skip);
         if label_cursor >= bytes.len() {
@@ -508,6 +504,21 @@ fn parse_value_label_key(
     }
 }
 
+/// Numeric entries store the range end right after the start; single values
+/// repeat the start there.
+fn parse_value_label_end(
+    entry: &[u8],
+    entry_len: usize,
+    start: &ValueKey,
+    value_type: ValueType,
+) -> Option<ValueKey> {
+    if value_type != ValueType::Numeric || entry_len < 38 || matches!(start, ValueKey::Tagged(_)) {
+        return None;
+    }
+    let end = decode_numeric_key(read_u64_be(&entry[30..38]));
+    (!matches!(end, ValueKey::Tagged(_)) && end != *start).then_some(end)
+}
+
 fn read_chain_segment<R: Read + Seek>(
     reader: &mut R,
     header: &SasHeader,
@@ -1,3 +1,4 @@
 pub mod byteorder;
 pub mod encoding;
 pub mod float_utils;
+pub(crate) mod platform;
@@ -0,0 +1,64 @@
+//! Conversions of on-disk sizes to in-memory `usize` values.
+//!
+//! SAS files carry 64-bit offsets and counts, which do not fit the address
+//! space of 32-bit targets. Offsets are always checked against the page or
+//! buffer they index, so they saturate and fail that bounds check with the
+//! same error on every target; sizes that decide how much to allocate fail
+//! up front with an [`Error::Limit`] naming the value and the platform.
+
+use crate::error::{Error, Result};
+
+/// Converts a size or count that decides an allocation to `usize`.
+///
+/// `what` names the value in the error, e.g. `"page size"`.
+pub fn usize_from(value: impl Into<u64>, what: &str) -> Result<usize> {
+    checked(value.into(), what, usize::BITS)
+}
+
+/// Converts an offset or length that is bounds-checked later, saturating
+/// at `usize::MAX` so out-of-range values fail that check.
+pub fn saturating_usize(value: impl Into<u64>) -> usize {
+    usize::try_from(value.into()).unwrap_or(usize::MAX)
+}
+
+fn checked(value: u64, what: &str, bits: u32) -> Result<usize> {
+    // Allocations are limited to `isize::MAX` bytes on every target.
+    let limit = (1u64 << (bits - 1)) - 1;
+    if value <= limit
+        && let Ok(value) = usize::try_from(value)
+    {
+        return Ok(value);
+    }
+    Err(Error::Limit {
+        details: format!(
+            "{what} of {value} exceeds what a {bits}-bit platform can address (at most {limit})"
+        )
+        .into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_past_a_32_bit_address_space_are_limit_errors() {
+        assert_eq!(checked(16 << 20, "page size", 32).unwrap(), 16 << 20);
+        let err = checked(3 << 30, "row length", 32).unwrap_err();
+        assert!(matches!(err, Error::Limit { .. }));
+        assert_eq!(
+            err.to_string(),
+            "limit exceeded: row length of 3221225472 exceeds what a 32-bit platform can \
+             address (at most 2147483647)"
+        );
+        assert!(checked(3 << 30, "row length", 64).is_ok());
+    }
+
+    #[test]
+    fn offsets_saturate() {
+        assert_eq!(saturating_usize(7u32), 7);
+        if usize::BITS == 32 {
+            assert_eq!(saturating_usize(u64::MAX), usize::MAX);
+        }
+    }
+}
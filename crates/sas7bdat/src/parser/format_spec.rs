@@ -0,0 +1,227 @@
+//! Canonical constants describing the on-disk SAS7BDAT/SAS7BCAT layout.
+//!
+//! The format is undocumented; these values come from community reverse
+//! engineering and are confirmed against the fixture corpus. The parser takes
+//! its magic numbers from this module, so it is the single reference for
+//! contributors and for tools that inspect files directly.
+
+// ---------------------------------------------------------------------------
+// File header
+// ---------------------------------------------------------------------------
+
+/// Magic number at offset 0 of a SAS7BDAT dataset.
+pub const SAS7BDAT_MAGIC_NUMBER: [u8; 32] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC2, 0xEA, 0x81, 0x60,
+    0xB3, 0x14, 0x11, 0xCF, 0xBD, 0x92, 0x08, 0x00, 0x09, 0xC7, 0x31, 0x8C, 0x18, 0x1F, 0x10, 0x11,
+];
+
+/// Magic number at offset 0 of a SAS7BCAT catalog.
+pub const SAS7BCAT_MAGIC_NUMBER: [u8; 32] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC2, 0xEA, 0x81, 0x63,
+    0xB3, 0x14, 0x11, 0xCF, 0xBD, 0x92, 0x08, 0x00, 0x09, 0xC7, 0x31, 0x8C, 0x18, 0x1F, 0x10, 0x11,
+];
+
+/// Byte offsets of the fixed fields at the start of the file header.
+pub mod header_offsets {
+    /// 32-byte magic number.
+    pub const MAGIC: usize = 0;
+    /// Alignment flag; [`SAS_ALIGNMENT_OFFSET_4`](super::SAS_ALIGNMENT_OFFSET_4) marks
+    /// a 64-bit file.
+    pub const ALIGNMENT_2: usize = 32;
+    /// Alignment flag; [`SAS_ALIGNMENT_OFFSET_4`](super::SAS_ALIGNMENT_OFFSET_4) adds
+    /// four bytes of padding before the timestamps.
+    pub const ALIGNMENT_1: usize = 35;
+    /// Endianness flag.
+    pub const ENDIANNESS: usize = 37;
+    /// Platform flag (`'1'` Unix, `'2'` Windows).
+    pub const FILE_FORMAT: usize = 39;
+    /// Character set code.
+    pub const ENCODING: usize = 70;
+    /// Eight-byte file type, `"DATA    "` or `"CATALOG "`.
+    pub const FILE_TYPE: usize = 84;
+    /// 32-byte blank-padded dataset name.
+    pub const TABLE_NAME: usize = 92;
+    /// Creation timestamp, before alignment padding is applied.
+    pub const TIMESTAMPS: usize = 164;
+}
+
+/// Size of the header block read before the alignment padding.
+pub const SAS_HEADER_START_SIZE: usize = 164;
+/// Size of the header block following the page count, holding the release
+/// and host strings.
+pub const SAS_HEADER_END_SIZE: usize = 120;
+/// Value of an alignment flag that signals a four-byte shift.
+pub const SAS_ALIGNMENT_OFFSET_4: u8 = 0x33;
+/// Endianness flag for big-endian files.
+pub const SAS_ENDIAN_BIG: u8 = 0x00;
+/// Endianness flag for little-endian files.
+pub const SAS_ENDIAN_LITTLE: u8 = 0x01;
+
+/// Smallest header size accepted by the parser.
+pub const SAS_HEADER_MIN_SIZE: u32 = 1024;
+/// Smallest page size accepted by the parser.
+pub const SAS_PAGE_MIN_SIZE: u32 = 1024;
+/// Largest header or page size accepted by the parser.
+pub const SAS_MAX_SIZE: u32 = 1 << 24;
+/// Largest page count accepted by the parser.
+pub const SAS_PAGE_COUNT_MAX: u64 = 1 << 24;
+
+// ---------------------------------------------------------------------------
+// Word-size dependent layout
+// ---------------------------------------------------------------------------
+
+/// Sizes that depend on whether the file uses 32-bit or 64-bit words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordLayout {
+    /// Width of offsets and lengths in bytes.
+    pub word_size: usize,
+    /// Bytes preceding the subheader pointer table on each page.
+    pub page_header_size: u32,
+    /// Size of one subheader pointer entry.
+    pub subheader_pointer_size: u32,
+    /// Bytes occupied by a subheader signature.
+    pub signature_size: usize,
+    /// Start of the page header fields shared by both word sizes.
+    pub page_bit_offset: usize,
+}
+
+impl WordLayout {
+    /// Layout of files written with 32-bit words.
+    pub const BITS_32: Self = Self {
+        word_size: 4,
+        page_header_size: 24,
+        subheader_pointer_size: 12,
+        signature_size: 4,
+        page_bit_offset: 16,
+    };
+
+    /// Layout of files written with 64-bit words.
+    pub const BITS_64: Self = Self {
+        word_size: 8,
+        page_header_size: 40,
+        subheader_pointer_size: 24,
+        signature_size: 8,
+        page_bit_offset: 32,
+    };
+
+    #[must_use]
+    pub const fn new(uses_u64: bool) -> Self {
+        if uses_u64 {
+            Self::BITS_64
+        } else {
+            Self::BITS_32
+        }
+    }
+
+    /// Offset of the compression byte within a subheader pointer. The pointer
+    /// starts with the subheader offset and length, one word each.
+    #[must_use]
+    pub const fn pointer_compression_offset(&self) -> usize {
+        self.word_size * 2
+    }
+
+    /// Offset of the subheader type byte within a subheader pointer.
+    #[must_use]
+    pub const fn pointer_type_offset(&self) -> usize {
+        self.word_size * 2 + 1
+    }
+
+    /// Minimum number of bytes needed to decode a subheader pointer.
+    #[must_use]
+    pub const fn pointer_min_len(&self) -> usize {
+        self.word_size * 2 + 2
+    }
+}
+
+/// Offset of the subheader pointer table from the page bit offset. The page
+/// type (`u16`), block count (`u16`), and subheader count (`u16`) sit in
+/// between.
+pub const SUBHEADER_POINTER_OFFSET: usize = 8;
+
+// ---------------------------------------------------------------------------
+// Pages
+// ---------------------------------------------------------------------------
+
+/// Mask selecting the base page type.
+pub const SAS_PAGE_TYPE_MASK: u16 = 0x0F00;
+pub const SAS_PAGE_TYPE_META: u16 = 0x0000;
+pub const SAS_PAGE_TYPE_DATA: u16 = 0x0100;
+pub const SAS_PAGE_TYPE_MIX: u16 = 0x0200;
+pub const SAS_PAGE_TYPE_AMD: u16 = 0x0400;
+/// Flag marking an additional metadata page.
+pub const SAS_PAGE_TYPE_META2: u16 = 0x4000;
+/// Flag marking a compressed page (observed as -28672 signed).
+pub const SAS_PAGE_TYPE_COMP: u16 = 0x9000;
+/// Flag marking a compression table page.
+pub const SAS_PAGE_TYPE_COMP_TABLE: u16 = 0x8000;
+
+// ---------------------------------------------------------------------------
+// Subheaders
+// ---------------------------------------------------------------------------
+
+pub const SAS_SUBHEADER_SIGNATURE_ROW_SIZE: u32 = 0xF7F7_F7F7;
+pub const SAS_SUBHEADER_SIGNATURE_COLUMN_SIZE: u32 = 0xF6F6_F6F6;
+pub const SAS_SUBHEADER_SIGNATURE_COUNTS: u32 = 0xFFFF_FC00;
+pub const SAS_SUBHEADER_SIGNATURE_COLUMN_FORMAT: u32 = 0xFFFF_FBFE;
+pub const SAS_SUBHEADER_SIGNATURE_COLUMN_ATTRS: u32 = 0xFFFF_FFFC;
+pub const SAS_SUBHEADER_SIGNATURE_COLUMN_TEXT: u32 = 0xFFFF_FFFD;
+pub const SAS_SUBHEADER_SIGNATURE_COLUMN_LIST: u32 = 0xFFFF_FFFE;
+pub const SAS_SUBHEADER_SIGNATURE_COLUMN_NAME: u32 = 0xFFFF_FFFF;
+
+/// Subheader pointer compression byte: stored uncompressed.
+pub const SAS_COMPRESSION_NONE: u8 = 0x00;
+/// Subheader pointer compression byte: truncated (deleted) entry.
+pub const SAS_COMPRESSION_TRUNC: u8 = 0x01;
+/// Subheader pointer compression byte: compressed row data.
+pub const SAS_COMPRESSION_ROW: u8 = 0x04;
+
+/// Prefix shared by every compression signature SAS writes.
+pub const SAS_COMPRESSION_SIGNATURE_PREFIX: &str = "SASYZ";
+/// Compression signature stored in the column text subheader for RLE.
+pub const SAS_COMPRESSION_SIGNATURE_RLE: &str = "SASYZCRL";
+/// Compression signature stored in the column text subheader for RDC.
+pub const SAS_COMPRESSION_SIGNATURE_RDC: &str = "SASYZCR2";
+
+/// Column attribute type code for numeric columns.
+pub const SAS_COLUMN_TYPE_NUMERIC: u8 = 0x01;
+/// Column attribute type code for character columns.
+pub const SAS_COLUMN_TYPE_CHARACTER: u8 = 0x02;
+
+/// Narrowest storage width of a numeric column. SAS truncates doubles to as
+/// few as three bytes by dropping low-order mantissa bytes.
+pub const SAS_NUMERIC_WIDTH_MIN: u32 = 3;
+/// Widest storage width of a numeric column (a full IEEE 754 double).
+pub const SAS_NUMERIC_WIDTH_MAX: u32 = 8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_offsets_fit_header_start() {
+        // table name (32) + reserved (32) + file info (8) close the block.
+        assert_eq!(header_offsets::TABLE_NAME + 72, SAS_HEADER_START_SIZE);
+        assert_eq!(header_offsets::TIMESTAMPS, SAS_HEADER_START_SIZE);
+    }
+
+    #[test]
+    fn word_layouts_are_self_consistent() {
+        for layout in [WordLayout::BITS_32, WordLayout::BITS_64] {
+            assert!(layout.pointer_min_len() <= layout.subheader_pointer_size as usize);
+            assert_eq!(
+                layout.page_bit_offset + SUBHEADER_POINTER_OFFSET,
+                layout.page_header_size as usize
+            );
+        }
+        assert_eq!(WordLayout::new(true), WordLayout::BITS_64);
+    }
+
+    #[test]
+    fn compressed_page_type_reads_as_signed_sentinel() {
+        assert_eq!(i16::from_le_bytes(SAS_PAGE_TYPE_COMP.to_le_bytes()), -28672);
+        assert_eq!(
+            i16::from_le_bytes(SAS_PAGE_TYPE_COMP_TABLE.to_le_bytes()),
+            i16::MIN
+        );
+    }
+}
@@ -1,3 +1,12 @@
+use super::{
+    format_spec::{
+        SAS_ALIGNMENT_OFFSET_4, SAS_ENDIAN_BIG, SAS_ENDIAN_LITTLE, SAS_HEADER_END_SIZE,
+        SAS_HEADER_MIN_SIZE, SAS_HEADER_START_SIZE, SAS_MAX_SIZE, SAS_PAGE_COUNT_MAX,
+        SAS_PAGE_MIN_SIZE, SAS7BCAT_MAGIC_NUMBER, SAS7BDAT_MAGIC_NUMBER, SUBHEADER_POINTER_OFFSET,
+        WordLayout,
+    },
+    quirks::VendorQuirks,
+};
 use crate::{
     dataset::{Compression, DatasetMetadata, DatasetTimestamps, Endianness, SasVersion, Vendor},
     error::{Error, Result, Section},
@@ -9,40 +18,15 @@ use std::{
 };
 use time::{Duration, OffsetDateTime};
 
-const SAS_ALIGNMENT_OFFSET_4: u8 = 0x33;
-const SAS_ENDIAN_BIG: u8 = 0x00;
-const SAS_ENDIAN_LITTLE: u8 = 0x01;
-
-const SAS_PAGE_HEADER_SIZE_32BIT: u32 = 24;
-const SAS_PAGE_HEADER_SIZE_64BIT: u32 = 40;
-const SAS_SUBHEADER_POINTER_SIZE_32BIT: u32 = 12;
-const SAS_SUBHEADER_POINTER_SIZE_64BIT: u32 = 24;
-
-const SAS_HEADER_START_SIZE: usize = 164;
-const SAS_HEADER_END_SIZE: usize = 120;
-
-const SAS_HEADER_MIN_SIZE: u32 = 1024;
-const SAS_PAGE_MIN_SIZE: u32 = 1024;
-const SAS_MAX_SIZE: u32 = 1 << 24;
-const SAS_PAGE_COUNT_MAX: u64 = 1 << 24;
-
-const SAS_EPOCH_OFFSET_SECONDS: i64 = -3653 * 86_400;
-
-const SAS7BDAT_MAGIC_NUMBER: [u8; 32] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC2, 0xEA, 0x81, 0x60,
-    0xB3, 0x14, 0x11, 0xCF, 0xBD, 0x92, 0x08, 0x00, 0x09, 0xC7, 0x31, 0x8C, 0x18, 0x1F, 0x10, 0x11,
-];
-
-const SAS7BCAT_MAGIC_NUMBER: [u8; 32] = [
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC2, 0xEA, 0x81, 0x63,
-    0xB3, 0x14, 0x11, 0xCF, 0xBD, 0x92, 0x08, 0x00, 0x09, 0xC7, 0x31, 0x8C, 0x18, 0x1F, 0x10, 0x11,
-];
-
 #[derive(Debug, Clone)]
 pub struct SasHeader {
     pub metadata: DatasetMetadata,
     pub endianness: Endianness,
     pub uses_u64: bool,
+    /// Offset within a page of the page type, which the block count and
+    /// subheader count follow.
+    pub page_bit_offset: usize,
+    /// Offset within a page of the subheader pointer table.
     pub page_header_size: u32,
     pub subheader_pointer_size: u32,
     pub subheader_signature_size: usize,
@@ -58,6 +42,50 @@ impl SasHeader {
     pub fn into_metadata(self) -> DatasetMetadata {
         self.metadata
     }
+
+    /// Offset within a page of the page type.
+    #[must_use]
+    pub const fn page_type_offset(&self) -> usize {
+        self.page_bit_offset
+    }
+
+    /// Offset within a page of the block count, the number of rows on data
+    /// and mixed pages.
+    #[must_use]
+    pub const fn block_count_offset(&self) -> usize {
+        self.page_bit_offset + 2
+    }
+
+    /// Offset within a page of the subheader count.
+    #[must_use]
+    pub const fn subheader_count_offset(&self) -> usize {
+        self.page_bit_offset + 4
+    }
+
+    /// Moves the page header fields to `page_bit_offset`, with the
+    /// subheader pointer table [`SUBHEADER_POINTER_OFFSET`] bytes after it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the page header and one subheader pointer would
+    /// not fit in a page.
+    pub fn set_page_bit_offset(&mut self, page_bit_offset: usize) -> Result<()> {
+        let page_header_size = page_bit_offset + SUBHEADER_POINTER_OFFSET;
+        validate_page_layout(
+            page_header_size,
+            self.subheader_pointer_size,
+            self.page_size,
+        )?;
+        self.page_bit_offset = page_bit_offset;
+        self.page_header_size = u32::try_from(page_header_size).map_err(|_| page_layout_error())?;
+        Ok(())
+    }
+
+    /// Returns the vendor-specific workarounds that apply to this file.
+    #[must_use]
+    pub fn quirks(&self) -> VendorQuirks {
+        VendorQuirks::lookup(self.metadata.vendor, self.metadata.version)
+    }
 }
 
 /// Parses the SAS7BDAT file header.
@@ -74,8 +102,7 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<SasHeader> {
 
     let is_catalog = header_start.magic == SAS7BCAT_MAGIC_NUMBER;
     if header_start.magic != SAS7BDAT_MAGIC_NUMBER && !is_catalog {
-        return Err(Error::Corrupted {
-            section: Section::Header,
+        return Err(Error::NotSasFile {
             details: Cow::from("unrecognized SAS magic number"),
         });
     }
@@ -104,7 +131,7 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<SasHeader> {
             .map_err(Error::from)?;
     }
 
-    let timestamps = read_timestamps(reader, endianness)?;
+    let raw_timestamps = read_timestamps(reader, endianness)?;
     let (header_size, page_size) = read_sizes(reader, endianness)?;
     let page_count = read_page_count(reader, uses_u64, endianness)?;
 
@@ -116,6 +143,8 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<SasHeader> {
 
     let release = header_end.release()?;
     let (version, vendor) = parse_release(&release)?;
+    let quirks = VendorQuirks::lookup(vendor, version);
+    let timestamps = raw_timestamps.resolve(quirks.epoch_offset_seconds);
 
     let encoding = lookup_encoding(header_start.encoding).ok_or_else(|| Error::Unsupported {
         feature: Cow::from(format!("character set code {}", header_start.encoding)),
@@ -136,21 +165,20 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<SasHeader> {
     metadata.endianness = endianness;
     metadata.compression = Compression::None;
 
+    let word_layout = WordLayout::new(uses_u64);
+    validate_page_layout(
+        word_layout.page_header_size as usize,
+        word_layout.subheader_pointer_size,
+        page_size,
+    )?;
     Ok(SasHeader {
         metadata,
         endianness,
         uses_u64,
-        page_header_size: if uses_u64 {
-            SAS_PAGE_HEADER_SIZE_64BIT
-        } else {
-            SAS_PAGE_HEADER_SIZE_32BIT
-        },
-        subheader_pointer_size: if uses_u64 {
-            SAS_SUBHEADER_POINTER_SIZE_64BIT
-        } else {
-            SAS_SUBHEADER_POINTER_SIZE_32BIT
-        },
-        subheader_signature_size: if uses_u64 { 8 } else { 4 },
+        page_bit_offset: word_layout.page_bit_offset,
+        page_header_size: word_layout.page_header_size,
+        subheader_pointer_size: word_layout.subheader_pointer_size,
+        subheader_signature_size: word_layout.signature_size,
         header_size,
         page_size,
         page_count,
@@ -159,6 +187,23 @@ pub fn parse_header<R: Read + Seek>(reader: &mut R) -> Result<SasHeader> {
     })
 }
 
+/// Checks that a page header of `page_header_size` bytes and one subheader
+/// pointer fit in a page, so page offsets never need bounds clamping.
+fn validate_page_layout(page_header_size: usize, pointer_size: u32, page_size: u32) -> Result<()> {
+    let needed = page_header_size.saturating_add(pointer_size as usize);
+    if needed > page_size as usize {
+        return Err(page_layout_error());
+    }
+    Ok(())
+}
+
+fn page_layout_error() -> Error {
+    Error::Corrupted {
+        section: Section::Header,
+        details: Cow::from("page header and subheader pointer table do not fit in a page"),
+    }
+}
+
 fn read_sizes<R: Read + Seek>(reader: &mut R, endianness: Endianness) -> Result<(u32, u32)> {
     let header_size_raw = read_u32(reader, endianness)?;
     let header_size = normalize_size(header_size_raw, SAS_HEADER_MIN_SIZE, SAS_MAX_SIZE)
@@ -370,21 +415,39 @@ fn parse_release(release: &str) -> Result<(SasVersion, Vendor)> {
     ))
 }
 
-fn read_timestamps<R: Read>(reader: &mut R, endian: Endianness) -> Result<DatasetTimestamps> {
-    let creation_time = read_f64(reader, endian)?;
-    let modification_time = read_f64(reader, endian)?;
-    let creation_diff = read_f64(reader, endian)?;
-    let modification_diff = read_f64(reader, endian)?;
+struct RawTimestamps {
+    creation_time: f64,
+    modification_time: f64,
+    creation_diff: f64,
+    modification_diff: f64,
+}
 
-    let created = convert_sas_time(creation_time, creation_diff);
-    let modified = convert_sas_time(modification_time, modification_diff);
+impl RawTimestamps {
+    fn resolve(&self, epoch_offset_seconds: i64) -> DatasetTimestamps {
+        DatasetTimestamps {
+            created: convert_sas_time(self.creation_time, self.creation_diff, epoch_offset_seconds),
+            modified: convert_sas_time(
+                self.modification_time,
+                self.modification_diff,
+                epoch_offset_seconds,
+            ),
+            ..DatasetTimestamps::default()
+        }
+    }
+}
 
-    Ok(DatasetTimestamps { created, modified })
+fn read_timestamps<R: Read>(reader: &mut R, endian: Endianness) -> Result<RawTimestamps> {
+    Ok(RawTimestamps {
+        creation_time: read_f64(reader, endian)?,
+        modification_time: read_f64(reader, endian)?,
+        creation_diff: read_f64(reader, endian)?,
+        modification_diff: read_f64(reader, endian)?,
+    })
 }
 
-fn convert_sas_time(time: f64, diff: f64) -> Option<OffsetDateTime> {
+fn convert_sas_time(time: f64, diff: f64, epoch_offset_seconds: i64) -> Option<OffsetDateTime> {
     let delta = Duration::checked_seconds_f64(time - diff)?;
-    let offset = Duration::seconds(SAS_EPOCH_OFFSET_SECONDS);
+    let offset = Duration::seconds(epoch_offset_seconds);
     let total = offset.checked_add(delta)?;
     OffsetDateTime::UNIX_EPOCH.checked_add(total)
 }
@@ -558,6 +621,6 @@ mod tests {
 
     #[test]
     fn convert_time_handles_nan() {
-        assert!(convert_sas_time(f64::NAN, 0.0).is_none());
+        assert!(convert_sas_time(f64::NAN, 0.0, VendorQuirks::SAS.epoch_offset_seconds).is_none());
     }
 }
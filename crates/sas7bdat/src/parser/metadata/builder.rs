@@ -1,8 +1,11 @@
 use super::{
-    column_info::{ColumnInfo, ColumnKind, ColumnOffsets, NumericKind, infer_numeric_kind},
+    column_info::{ColumnInfo, ColumnKind, ColumnOffsets, NumericKind},
     text_store::TextStore,
 };
-use crate::dataset::{Alignment, Measure};
+use crate::{
+    dataset::{Alignment, Measure},
+    formats,
+};
 use encoding_rs::Encoding;
 use std::borrow::Cow;
 
@@ -71,6 +74,9 @@ impl ColumnMetadataBuilder {
                 name_ref: super::text_store::TextRef::EMPTY,
                 label_ref: super::text_store::TextRef::EMPTY,
                 format_ref: super::text_store::TextRef::EMPTY,
+                informat_width: None,
+                informat_decimals: None,
+                informat_ref: super::text_store::TextRef::EMPTY,
                 measure: Measure::Unknown,
                 alignment: Alignment::Unknown,
             });
@@ -152,9 +158,9 @@ impl ColumnMetadataBuilder {
             );
         }
 
-        for (column, format_name) in columns.iter_mut().zip(inferred_formats.into_iter()) {
+        for (column, format_name) in columns.iter_mut().zip(inferred_formats) {
             if let (ColumnKind::Numeric(kind), Some(format_name)) = (&mut column.kind, format_name)
-                && let Some(inferred) = infer_numeric_kind(&format_name)
+                && let Some(inferred) = formats::classify(&format_name).numeric_kind()
             {
                 *kind = inferred;
             }
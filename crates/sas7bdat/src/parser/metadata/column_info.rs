@@ -1,14 +1,22 @@
 use super::text_store::{TextRef, TextStore};
 use crate::{
     dataset::{Alignment, Format, Measure, MissingValuePolicy, Variable, VariableKind},
-    error::{Error, Result},
+    error::Result,
+    parser::{
+        core::platform::usize_from,
+        format_spec::{SAS_COLUMN_TYPE_CHARACTER, SAS_COLUMN_TYPE_NUMERIC},
+    },
 };
-use std::{borrow::Cow, convert::TryFrom};
+use serde::Deserialize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColumnKind {
     Numeric(NumericKind),
     Character,
+    /// Column surfaced as its raw stored bytes: a numeric column with a
+    /// storage width the decoder cannot interpret, or a character column
+    /// opted out of text decoding.
+    Bytes,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,19 +25,80 @@ pub enum NumericKind {
     Date,
     DateTime,
     Time,
+    /// Always decoded as a float, even when the value is integral.
+    Float,
+    /// Decoded as the plain decimal text of the number.
+    Text,
 }
 
 impl ColumnKind {
     #[must_use]
     pub const fn from_type_code(code: u8) -> Option<Self> {
         match code {
-            0x01 => Some(Self::Numeric(NumericKind::Double)),
-            0x02 => Some(Self::Character),
+            SAS_COLUMN_TYPE_NUMERIC => Some(Self::Numeric(NumericKind::Double)),
+            SAS_COLUMN_TYPE_CHARACTER => Some(Self::Character),
             _ => None,
         }
     }
 }
 
+/// How a column is decoded, overriding the type inferred from its format;
+/// see [`SasReader::override_column_type`](crate::SasReader::override_column_type).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    /// Plain number, with integral values decoded as integers.
+    Number,
+    /// Plain number, always decoded as a float.
+    Float,
+    /// Number decoded as its plain decimal text, such as `12345` or `0.5`,
+    /// for identifiers stored in numeric columns.
+    #[serde(rename = "numeric_text")]
+    NumericText,
+    /// Days since 1960-01-01.
+    Date,
+    /// Seconds since 1960-01-01T00:00:00.
+    Datetime,
+    /// Seconds since midnight.
+    Time,
+    /// Character column decoded to text.
+    Text,
+    /// Character column kept as raw stored bytes.
+    Bytes,
+}
+
+impl ColumnType {
+    #[must_use]
+    pub const fn column_kind(self) -> ColumnKind {
+        match self {
+            Self::Number => ColumnKind::Numeric(NumericKind::Double),
+            Self::Float => ColumnKind::Numeric(NumericKind::Float),
+            Self::NumericText => ColumnKind::Numeric(NumericKind::Text),
+            Self::Date => ColumnKind::Numeric(NumericKind::Date),
+            Self::Datetime => ColumnKind::Numeric(NumericKind::DateTime),
+            Self::Time => ColumnKind::Numeric(NumericKind::Time),
+            Self::Text => ColumnKind::Character,
+            Self::Bytes => ColumnKind::Bytes,
+        }
+    }
+
+    /// Whether the type applies to numeric columns rather than character
+    /// columns. [`ColumnType::NumericText`] counts as numeric: it reads a
+    /// numeric column as text.
+    #[must_use]
+    pub const fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::Number
+                | Self::Float
+                | Self::NumericText
+                | Self::Date
+                | Self::Datetime
+                | Self::Time
+        )
+    }
+}
+
 /// Tracks column offsets and widths for row parsing.
 #[derive(Debug, Clone, Copy)]
 pub struct ColumnOffsets {
@@ -48,6 +117,9 @@ pub struct ColumnInfo {
     pub name_ref: TextRef,
     pub label_ref: TextRef,
     pub format_ref: TextRef,
+    pub informat_width: Option<u16>,
+    pub informat_decimals: Option<u16>,
+    pub informat_ref: TextRef,
     pub measure: Measure,
     pub alignment: Alignment,
 }
@@ -61,13 +133,10 @@ impl ColumnInfo {
     pub fn apply_to_variable(&self, text_store: &TextStore, variable: &mut Variable) -> Result<()> {
         variable.index = self.index;
         variable.kind = match self.kind {
-            ColumnKind::Numeric(_) => VariableKind::Numeric,
+            ColumnKind::Numeric(_) | ColumnKind::Bytes => VariableKind::Numeric,
             ColumnKind::Character => VariableKind::Character,
         };
-        variable.storage_width =
-            usize::try_from(self.offsets.width).map_err(|_| Error::Unsupported {
-                feature: Cow::from("column width exceeds platform pointer width"),
-            })?;
+        variable.storage_width = usize_from(self.offsets.width, "column width")?;
         variable.missing = MissingValuePolicy::default();
         if matches!(variable.kind, VariableKind::Numeric) {
             variable.missing.system_missing = true;
@@ -89,44 +158,13 @@ impl ColumnInfo {
             };
             variable.format = Some(format);
         }
+        if let Some(informat_name) = text_store.resolve(self.informat_ref)? {
+            variable.informat = Some(Format {
+                name: informat_name.into_owned(),
+                width: self.informat_width,
+                decimals: self.informat_decimals,
+            });
+        }
         Ok(())
     }
 }
-
-pub fn infer_numeric_kind(format_name: &str) -> Option<NumericKind> {
-    if format_name.is_empty() {
-        return None;
-    }
-    let cleaned = format_name.trim().trim_matches('.').to_ascii_uppercase();
-    if cleaned.is_empty() {
-        return None;
-    }
-    if cleaned.contains("DATETIME")
-        || cleaned.starts_with("DT")
-        || cleaned.ends_with("DT")
-        || cleaned.starts_with("E8601DT")
-        || cleaned.starts_with("B8601DT")
-    {
-        return Some(NumericKind::DateTime);
-    }
-    if cleaned.contains("TIME")
-        || cleaned.ends_with("TM")
-        || cleaned.starts_with("E8601TM")
-        || cleaned.starts_with("HHMM")
-    {
-        return Some(NumericKind::Time);
-    }
-    if cleaned.contains("DATE")
-        || cleaned.contains("YY")
-        || cleaned.contains("MON")
-        || cleaned.contains("WEEK")
-        || cleaned.contains("YEAR")
-        || cleaned.contains("MINGUO")
-        || cleaned.ends_with("DA")
-        || cleaned.starts_with("E8601DA")
-        || cleaned.starts_with("B8601DA")
-    {
-        return Some(NumericKind::Date);
-    }
-    None
-}
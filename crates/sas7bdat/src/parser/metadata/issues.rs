@@ -0,0 +1,162 @@
+use crate::{
+    error::{Error, Result, Section},
+    logger::Logger,
+};
+use std::{
+    fmt,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// How parsing reacts to corrupted structures it could step over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Fail with [`Error::Corrupted`] on the first corrupted subheader count,
+    /// pointer table or pointer.
+    Strict,
+    /// Clamp or skip the corrupted structure, log a warning and record a
+    /// [`ParseWarning`], so data is salvaged from the rest of the page.
+    #[default]
+    Lenient,
+}
+
+/// A corrupted structure that was clamped or skipped in
+/// [`ParseMode::Lenient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Page holding the structure.
+    pub page_index: u64,
+    pub message: String,
+}
+
+/// An anomaly met while parsing metadata or reading rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Page holding the anomaly, if it is tied to one.
+    pub page_index: Option<u64>,
+    /// Signature of the subheader involved, if one was read.
+    pub signature: Option<u32>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(page_index) = self.page_index {
+            write!(f, "page {page_index}: ")?;
+        }
+        if let Some(signature) = self.signature {
+            write!(f, "subheader 0x{signature:08X}: ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Per-reader collection of [`Diagnostic`]s.
+///
+/// Unlike the log, which may be shared by every file of a process, the
+/// collection holds only the anomalies of one dataset. Clones share the
+/// collection, so a clone can be kept while the reader moves to another
+/// thread.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    entries: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl Diagnostics {
+    /// Returns the diagnostics recorded so far, oldest first.
+    #[must_use]
+    pub fn entries(&self) -> Vec<Diagnostic> {
+        self.lock().clone()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes and returns the diagnostics recorded so far.
+    #[must_use]
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn record(&self, diagnostic: Diagnostic) {
+        self.lock().push(diagnostic);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Diagnostic>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Applies the parse mode of one dataset and collects its warnings and
+/// diagnostics; clones share both, so row iterators add to the reader's
+/// lists.
+#[derive(Debug, Clone, Default)]
+pub struct ParseIssues {
+    mode: ParseMode,
+    warnings: Arc<Mutex<Vec<ParseWarning>>>,
+    diagnostics: Diagnostics,
+}
+
+impl ParseIssues {
+    pub(crate) fn new(mode: ParseMode) -> Self {
+        Self {
+            mode,
+            warnings: Arc::default(),
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    /// Reports a recoverable corruption on `page_index`: an error in strict
+    /// mode, otherwise a warning logged to `logger` and recorded.
+    pub(crate) fn recover(&self, logger: &Logger, page_index: u64, message: String) -> Result<()> {
+        if self.mode == ParseMode::Strict {
+            return Err(Error::Corrupted {
+                section: Section::Page { index: page_index },
+                details: message.into(),
+            });
+        }
+        self.note(logger, Some(page_index), None, message.clone());
+        self.warnings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(ParseWarning {
+                page_index,
+                message,
+            });
+        Ok(())
+    }
+
+    /// Logs an anomaly parsing carries on past and records it as a
+    /// [`Diagnostic`].
+    pub(crate) fn note(
+        &self,
+        logger: &Logger,
+        page_index: Option<u64>,
+        signature: Option<u32>,
+        message: String,
+    ) {
+        logger.warn(&message);
+        self.diagnostics.record(Diagnostic {
+            page_index,
+            signature,
+            message,
+        });
+    }
+
+    pub(crate) fn warnings(&self) -> Vec<ParseWarning> {
+        self.warnings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) const fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+}
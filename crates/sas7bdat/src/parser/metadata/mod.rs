@@ -1,19 +1,34 @@
 use crate::{
-    dataset::{Compression, Variable},
+    dataset::{Compression, CompressionSignature, PageIndex, Variable},
     error::{Error, Result, Section},
-    logger::log_warn,
+    logger::Logger,
     parser::{
         core::{
             byteorder::{read_u16, read_u32, read_u64},
             encoding::resolve_encoding,
+            platform::saturating_usize,
+        },
+        format_spec::{
+            SAS_COMPRESSION_SIGNATURE_PREFIX, SAS_COMPRESSION_SIGNATURE_RDC,
+            SAS_COMPRESSION_SIGNATURE_RLE, SAS_NUMERIC_WIDTH_MAX, SAS_NUMERIC_WIDTH_MIN,
+            SAS_PAGE_TYPE_AMD, SAS_PAGE_TYPE_COMP, SAS_PAGE_TYPE_COMP_TABLE, SAS_PAGE_TYPE_DATA,
+            SAS_PAGE_TYPE_MASK, SAS_PAGE_TYPE_META, SAS_PAGE_TYPE_META2, SAS_PAGE_TYPE_MIX,
+            SAS_SUBHEADER_SIGNATURE_COLUMN_ATTRS, SAS_SUBHEADER_SIGNATURE_COLUMN_FORMAT,
+            SAS_SUBHEADER_SIGNATURE_COLUMN_LIST, SAS_SUBHEADER_SIGNATURE_COLUMN_NAME,
+            SAS_SUBHEADER_SIGNATURE_COLUMN_SIZE, SAS_SUBHEADER_SIGNATURE_COLUMN_TEXT,
+            SAS_SUBHEADER_SIGNATURE_ROW_SIZE, SUBHEADER_POINTER_OFFSET, WordLayout,
         },
         header::{SasHeader, parse_header},
+        rows::{read_signature, signature_is_recognized},
     },
 };
+use bytes::Bytes;
 use std::{
     borrow::Cow,
+    collections::{HashMap, HashSet},
     convert::TryFrom,
     io::{Read, Seek, SeekFrom},
+    sync::OnceLock,
 };
 use subheaders::{
     parse_column_attrs_subheader, parse_column_format_subheader, parse_column_list_subheader,
@@ -23,6 +38,7 @@ use subheaders::{
 
 mod builder;
 mod column_info;
+mod issues;
 mod row_info;
 mod subheaders;
 #[cfg(test)]
@@ -30,7 +46,9 @@ mod tests;
 mod text_store;
 
 pub use builder::ColumnMetadataBuilder;
-pub use column_info::{ColumnInfo, ColumnKind, ColumnOffsets, NumericKind};
+pub use column_info::{ColumnInfo, ColumnKind, ColumnOffsets, ColumnType, NumericKind};
+pub(crate) use issues::ParseIssues;
+pub use issues::{Diagnostic, Diagnostics, ParseMode, ParseWarning};
 pub use row_info::RowInfo;
 use row_info::RowInfoRaw;
 pub use text_store::{TextRef, TextStore};
@@ -42,15 +60,127 @@ pub enum MetadataIoMode {
     Streaming,
 }
 
+/// How numeric columns with a storage width outside 3 to 8 bytes
+/// ([`SAS_NUMERIC_WIDTH_MIN`] to [`SAS_NUMERIC_WIDTH_MAX`]) are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnsupportedNumericPolicy {
+    /// Reject the file with [`Error::Unsupported`] naming the column.
+    #[default]
+    Error,
+    /// Expose the column as [`ColumnKind::Bytes`] so its raw storage is
+    /// returned as [`CellValue::Bytes`](crate::cell::CellValue::Bytes).
+    Bytes,
+}
+
+/// Order in which columns are presented to row iterators and sinks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnOrder {
+    /// The order the column descriptors are stored in the file.
+    #[default]
+    Stored,
+    /// SAS's logical order from the column-list subheader, as shown by
+    /// PROC CONTENTS. Falls back to [`ColumnOrder::Stored`] with a warning
+    /// when the file has no usable column list.
+    ColumnList,
+}
+
+const DEFAULT_STREAM_BUFFER: u64 = 64 * 1024 * 1024;
+
 #[derive(Debug, Clone, Copy)]
 pub struct MetadataReadOptions {
     pub io_mode: MetadataIoMode,
+    /// Populate absent or zero header timestamps from filesystem metadata.
+    /// Only honoured when the dataset is opened from a path.
+    pub backfill_timestamps: bool,
+    pub unsupported_numeric: UnsupportedNumericPolicy,
+    pub column_order: ColumnOrder,
+    /// Stop row iteration at the row count stored in the header. When
+    /// `false`, rows are read until the data pages run out.
+    pub trust_row_count: bool,
+    pub parse_mode: ParseMode,
+    /// Stop the metadata scan at the first data page instead of visiting
+    /// every page, so the file is only read up to that point.
+    pub leading_metadata_only: bool,
+    /// Bytes [`SasReader::from_stream`](crate::SasReader::from_stream) keeps
+    /// in memory while looking for metadata stored after the data pages.
+    pub stream_buffer: u64,
+}
+
+impl MetadataReadOptions {
+    #[must_use]
+    pub const fn with_io_mode(mut self, io_mode: MetadataIoMode) -> Self {
+        self.io_mode = io_mode;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_timestamp_backfill(mut self, enabled: bool) -> Self {
+        self.backfill_timestamps = enabled;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_unsupported_numeric(mut self, policy: UnsupportedNumericPolicy) -> Self {
+        self.unsupported_numeric = policy;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_column_order(mut self, order: ColumnOrder) -> Self {
+        self.column_order = order;
+        self
+    }
+
+    /// Reads past the header row count when `false`, for files whose
+    /// header understates the rows actually stored. A warning names the
+    /// stored and actual counts whenever the pages run out at a different
+    /// row than the header says.
+    #[must_use]
+    pub const fn with_trust_row_count(mut self, trust: bool) -> Self {
+        self.trust_row_count = trust;
+        self
+    }
+
+    /// Fails on corrupted subheaders and pointers with
+    /// [`ParseMode::Strict`] instead of stepping over them; the default
+    /// [`ParseMode::Lenient`] records each one as a [`ParseWarning`].
+    #[must_use]
+    pub const fn with_parse_mode(mut self, mode: ParseMode) -> Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Reads metadata only from the pages before the first data page, for
+    /// sources that cannot seek back. Column limits are then derived from
+    /// the page count in the header rather than the length of the file,
+    /// and metadata stored after the data pages is not read.
+    #[must_use]
+    pub const fn with_leading_metadata_only(mut self, enabled: bool) -> Self {
+        self.leading_metadata_only = enabled;
+        self
+    }
+
+    /// Sets how many bytes of a non-seekable stream are read ahead and kept
+    /// to find metadata stored after the data pages (64 MiB by default).
+    /// Streams that end within the buffer are read like seekable files.
+    #[must_use]
+    pub const fn with_stream_buffer(mut self, bytes: u64) -> Self {
+        self.stream_buffer = bytes;
+        self
+    }
 }
 
 impl Default for MetadataReadOptions {
     fn default() -> Self {
         Self {
             io_mode: MetadataIoMode::Auto,
+            backfill_timestamps: false,
+            unsupported_numeric: UnsupportedNumericPolicy::Error,
+            column_order: ColumnOrder::Stored,
+            trust_row_count: true,
+            parse_mode: ParseMode::Lenient,
+            leading_metadata_only: false,
+            stream_buffer: DEFAULT_STREAM_BUFFER,
         }
     }
 }
@@ -62,6 +192,20 @@ pub struct DatasetLayout {
     pub columns: Vec<ColumnInfo>,
     pub row_info: RowInfo,
     pub column_list: Option<Vec<i16>>,
+    /// Receives warnings raised while reading rows from this dataset.
+    pub logger: Logger,
+    /// The whole file, when it was opened from memory; row iterators slice
+    /// pages out of it instead of reading them.
+    pub(crate) source: Option<Bytes>,
+    /// Replace coded values with their catalog labels in materialised rows.
+    pub(crate) apply_value_labels: bool,
+    /// Bound row iteration by the header row count.
+    pub(crate) trust_row_count: bool,
+    /// Pages that store rows, built on the first seek or loaded from a
+    /// sidecar.
+    pub(crate) page_index: OnceLock<PageIndex>,
+    /// Parse mode and the corruption stepped over while reading.
+    pub(crate) issues: ParseIssues,
 }
 
 impl DatasetLayout {
@@ -76,24 +220,13 @@ impl DatasetLayout {
     ) -> Result<crate::parser::rows::RowIterator<'a, R>> {
         crate::parser::rows::row_iterator(reader, self)
     }
-}
-
-const SAS_PAGE_TYPE_MASK: u16 = 0x0F00;
-const SAS_PAGE_TYPE_META: u16 = 0x0000;
-const SAS_PAGE_TYPE_DATA: u16 = 0x0100;
-const SAS_PAGE_TYPE_MIX: u16 = 0x0200;
-const SAS_PAGE_TYPE_META2: u16 = 0x4000;
-const SAS_PAGE_TYPE_AMD: u16 = 0x0400;
-const SAS_PAGE_TYPE_COMP: u16 = 0x9000;
-const SAS_PAGE_TYPE_COMP_TABLE: u16 = 0x8000; // observed -28672 signed / 36864 unsigned
 
-const SIG_ROW_SIZE: u32 = 0xF7F7_F7F7;
-const SIG_COLUMN_SIZE: u32 = 0xF6F6_F6F6;
-const SIG_COLUMN_TEXT: u32 = 0xFFFF_FFFD;
-const SIG_COLUMN_NAME: u32 = 0xFFFF_FFFF;
-const SIG_COLUMN_ATTRS: u32 = 0xFFFF_FFFC;
-const SIG_COLUMN_FORMAT: u32 = 0xFFFF_FBFE;
-const SIG_COLUMN_LIST: u32 = 0xFFFF_FFFE;
+    /// Whether row iteration stops at the header row count: neither the
+    /// reader options nor the producer's quirks distrust it.
+    pub(crate) fn trusts_row_count(&self) -> bool {
+        self.trust_row_count && self.header.quirks().trust_row_count
+    }
+}
 
 /// Parses dataset metadata from a SAS7BDAT stream.
 ///
@@ -114,11 +247,16 @@ pub fn parse_metadata_with_options<R: Read + Seek>(
     options: MetadataReadOptions,
 ) -> Result<DatasetLayout> {
     let mut header = parse_header(reader)?;
+    detect_page_layout(reader, &mut header)?;
     let encoding = resolve_encoding(header.metadata.file_encoding.as_deref());
     let mut builder = ColumnMetadataBuilder::new(encoding);
 
-    let mut state = MetaState::default();
-    collect_metadata(reader, &header, &mut builder, &mut state, options)?;
+    let mut state = MetaState {
+        max_columns: max_column_count(reader, &header, options)?,
+        ..MetaState::default()
+    };
+    let issues = ParseIssues::new(options.parse_mode);
+    collect_metadata(reader, &header, &mut builder, &mut state, options, &issues)?;
 
     let column_count = state.column_count.ok_or_else(|| Error::InvalidMetadata {
         details: "column count not found in SAS metadata".into(),
@@ -135,12 +273,29 @@ pub fn parse_metadata_with_options<R: Read + Seek>(
     metadata.column_count = column_count;
     metadata.row_count = row_info.total_rows;
     metadata.compression = match row_info.compression {
-        Compression::Binary | Compression::Row => row_info.compression,
+        Compression::Binary | Compression::Row | Compression::Other(_) => row_info.compression,
         _ => metadata.compression,
     };
     metadata.file_label.clone_from(&row_info.file_label);
     metadata.variables = build_variables(column_count, &columns, &text_store)?;
+    check_numeric_widths(
+        &mut columns,
+        &metadata.variables,
+        options.unsupported_numeric,
+    )?;
     metadata.column_list = column_list.clone().unwrap_or_default();
+    if options.column_order == ColumnOrder::ColumnList {
+        match column_list_order(column_list.as_deref(), columns.len()) {
+            Some(order) => apply_column_order(&mut columns, &mut metadata.variables, &order),
+            None => issues.note(
+                &Logger::current(),
+                None,
+                None,
+                "column list is missing or does not cover every column; keeping stored column order"
+                    .to_owned(),
+            ),
+        }
+    }
 
     header.metadata = metadata;
 
@@ -150,9 +305,58 @@ pub fn parse_metadata_with_options<R: Read + Seek>(
         columns,
         row_info,
         column_list,
+        logger: Logger::current(),
+        source: None,
+        apply_value_labels: false,
+        trust_row_count: options.trust_row_count,
+        page_index: OnceLock::new(),
+        issues,
     })
 }
 
+/// Converts the column-list entries into zero-based column positions.
+///
+/// The list interleaves zero padding with signed one-based column numbers;
+/// it is only usable when the non-zero magnitudes name every column once.
+fn column_list_order(list: Option<&[i16]>, column_count: usize) -> Option<Vec<usize>> {
+    let mut seen = vec![false; column_count];
+    let mut order = Vec::with_capacity(column_count);
+    for &entry in list? {
+        if entry == 0 {
+            continue;
+        }
+        let position = usize::from(entry.unsigned_abs()) - 1;
+        let seen = seen.get_mut(position)?;
+        if *seen {
+            return None;
+        }
+        *seen = true;
+        order.push(position);
+    }
+    (order.len() == column_count).then_some(order)
+}
+
+/// Reorders columns and variables to `order`, renumbering their indices so
+/// they keep matching positions.
+fn apply_column_order(
+    columns: &mut Vec<ColumnInfo>,
+    variables: &mut Vec<Variable>,
+    order: &[usize],
+) {
+    let mut reordered_columns = Vec::with_capacity(order.len());
+    let mut reordered_variables = Vec::with_capacity(order.len());
+    for (index, &position) in (0_u32..).zip(order) {
+        let mut column = columns[position].clone();
+        let mut variable = variables[position].clone();
+        column.index = index;
+        variable.index = index;
+        reordered_columns.push(column);
+        reordered_variables.push(variable);
+    }
+    *columns = reordered_columns;
+    *variables = reordered_variables;
+}
+
 fn build_variables(
     column_count: u32,
     columns: &[ColumnInfo],
@@ -175,62 +379,93 @@ fn build_variables(
     Ok(variables)
 }
 
+fn check_numeric_widths(
+    columns: &mut [ColumnInfo],
+    variables: &[Variable],
+    policy: UnsupportedNumericPolicy,
+) -> Result<()> {
+    for (column, variable) in columns.iter_mut().zip(variables) {
+        let width = column.offsets.width;
+        if !matches!(column.kind, ColumnKind::Numeric(_))
+            || (SAS_NUMERIC_WIDTH_MIN..=SAS_NUMERIC_WIDTH_MAX).contains(&width)
+        {
+            continue;
+        }
+        match policy {
+            UnsupportedNumericPolicy::Error => {
+                return Err(Error::Unsupported {
+                    feature: format!(
+                        "numeric column '{}' with storage width {width} bytes",
+                        variable.name
+                    )
+                    .into(),
+                });
+            }
+            UnsupportedNumericPolicy::Bytes => column.kind = ColumnKind::Bytes,
+        }
+    }
+    Ok(())
+}
+
 fn collect_metadata<R: Read + Seek>(
     reader: &mut R,
     header: &SasHeader,
     builder: &mut ColumnMetadataBuilder,
     state: &mut MetaState,
     options: MetadataReadOptions,
+    issues: &ParseIssues,
 ) -> Result<()> {
-    scan_pages_with_stop(reader, header, options, |page_type, subheaders| {
+    let scan = MetaScan { options, issues };
+    scan_pages_with_stop(reader, header, scan, |page_type, subheaders| {
         if !is_meta_page(page_type) {
             return Ok(false);
         }
         for subheader in subheaders {
             match subheader.signature {
-                SIG_COLUMN_TEXT => parse_column_text_subheader(
+                SAS_SUBHEADER_SIGNATURE_COLUMN_TEXT => parse_column_text_subheader(
                     builder,
                     &subheader.data,
                     header.subheader_signature_size,
                     header.endianness,
                 )?,
-                SIG_COLUMN_NAME => parse_column_name_subheader(
+                SAS_SUBHEADER_SIGNATURE_COLUMN_NAME => parse_column_name_subheader(
                     builder,
                     &subheader.data,
                     header.subheader_signature_size,
                     header.endianness,
                     header.uses_u64,
                 )?,
-                SIG_COLUMN_ATTRS => parse_column_attrs_subheader(
+                SAS_SUBHEADER_SIGNATURE_COLUMN_ATTRS => parse_column_attrs_subheader(
                     builder,
                     &subheader.data,
                     header.subheader_signature_size,
                     header.endianness,
                     header.uses_u64,
                 )?,
-                SIG_COLUMN_FORMAT => parse_column_format_subheader(
+                SAS_SUBHEADER_SIGNATURE_COLUMN_FORMAT => parse_column_format_subheader(
                     builder,
                     &subheader.data,
                     header.endianness,
                     header.uses_u64,
                 )?,
-                SIG_COLUMN_LIST => parse_column_list_subheader(
+                SAS_SUBHEADER_SIGNATURE_COLUMN_LIST => parse_column_list_subheader(
                     builder,
                     &subheader.data,
                     header.subheader_signature_size,
                     header.endianness,
                     header.uses_u64,
                 )?,
-                SIG_COLUMN_SIZE => {
+                SAS_SUBHEADER_SIGNATURE_COLUMN_SIZE => {
                     let column_count = parse_column_size_subheader(
                         builder,
                         &subheader.data,
                         header.endianness,
                         header.uses_u64,
+                        state.max_columns,
                     )?;
                     state.column_count = Some(column_count);
                 }
-                SIG_ROW_SIZE => {
+                SAS_SUBHEADER_SIGNATURE_ROW_SIZE => {
                     let row_info = parse_row_size_subheader(
                         &subheader.data,
                         header.subheader_signature_size,
@@ -248,10 +483,44 @@ fn collect_metadata<R: Read + Seek>(
     })
 }
 
+/// Settings shared by the forward and backward metadata page scans.
+#[derive(Clone, Copy)]
+struct MetaScan<'a> {
+    options: MetadataReadOptions,
+    issues: &'a ParseIssues,
+}
+
 #[derive(Default)]
 struct MetaState {
     column_count: Option<u32>,
     row_info: Option<RowInfoRaw>,
+    max_columns: u64,
+}
+
+/// Upper bound on the column count a stream of this length can describe.
+///
+/// Every column needs at least one attribute entry, so a count beyond this
+/// can only come from a corrupted column size subheader; rejecting it avoids
+/// allocating per-column state for billions of phantom columns.
+fn max_column_count<R: Read + Seek>(
+    reader: &mut R,
+    header: &SasHeader,
+    options: MetadataReadOptions,
+) -> Result<u64> {
+    let len = if options.leading_metadata_only {
+        header.data_offset.saturating_add(
+            header
+                .page_count
+                .saturating_mul(u64::from(header.page_size)),
+        )
+    } else {
+        let position = reader.stream_position()?;
+        let len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(position))?;
+        len
+    };
+    let entry_size = if header.uses_u64 { 16 } else { 12 };
+    Ok(len / entry_size)
 }
 
 struct ParsedSubheader {
@@ -268,8 +537,11 @@ fn resolve_row_info(raw: RowInfoRaw, text_store: &TextStore) -> Result<RowInfo>
     let compression = text_store
         .resolve(raw.compression_ref)?
         .map_or(Compression::None, |value| match value.trim() {
-            "SASYZCR2" => Compression::Binary,
-            "SASYZCRL" => Compression::Row,
+            SAS_COMPRESSION_SIGNATURE_RDC => Compression::Binary,
+            SAS_COMPRESSION_SIGNATURE_RLE => Compression::Row,
+            other if other.starts_with(SAS_COMPRESSION_SIGNATURE_PREFIX) => {
+                CompressionSignature::new(other).map_or(Compression::None, Compression::Other)
+            }
             _ => Compression::None,
         });
 
@@ -282,24 +554,59 @@ fn resolve_row_info(raw: RowInfoRaw, text_store: &TextStore) -> Result<RowInfo>
     })
 }
 
+/// Page type and subheader count of every page header read while parsing
+/// metadata, so the backward scan never fetches a header the forward pass
+/// already read.
+struct PageHeaderCache {
+    buffer: Vec<u8>,
+    entries: HashMap<u64, (u16, u16)>,
+}
+
+impl PageHeaderCache {
+    fn new(header: &SasHeader) -> Self {
+        Self {
+            buffer: vec![0u8; header.page_header_size as usize],
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the page type and subheader count of `page_index`, reading
+    /// the page header only on the first request.
+    fn get<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        header: &SasHeader,
+        page_index: u64,
+    ) -> Result<(u16, u16)> {
+        if let Some(&entry) = self.entries.get(&page_index) {
+            return Ok(entry);
+        }
+        load_page_header(reader, header, &mut self.buffer, page_index)?;
+        let entry = (
+            page_type_from_header(header, &self.buffer)?,
+            subheader_count_from_header(header, &self.buffer)?,
+        );
+        self.entries.insert(page_index, entry);
+        Ok(entry)
+    }
+}
+
 fn scan_pages_with_stop<R, F>(
     reader: &mut R,
     header: &SasHeader,
-    options: MetadataReadOptions,
+    scan: MetaScan<'_>,
     mut f: F,
 ) -> Result<()>
 where
     R: Read + Seek,
     F: FnMut(u16, Vec<ParsedSubheader>) -> Result<bool>,
 {
-    let mut header_buf = vec![0u8; header.page_header_size as usize];
-    let mut visited = std::collections::HashSet::new();
+    let mut cache = PageHeaderCache::new(header);
+    let mut visited = HashSet::new();
     let mut last_examined = 0u64;
 
     for page_index in 0..header.page_count {
-        load_page_header(reader, header, &mut header_buf, page_index)?;
-        let page_type = page_type_from_header(header, &header_buf)?;
-        let subheader_count = subheader_count_from_header(header, &header_buf)?;
+        let (page_type, subheader_count) = cache.get(reader, header, page_index)?;
         last_examined = page_index;
         let kind = classify_page(page_type);
         if matches!(
@@ -309,6 +616,9 @@ where
             continue;
         }
         if matches!(kind, PageKind::Data) {
+            if scan.options.leading_metadata_only {
+                return Ok(());
+            }
             continue;
         }
         if !matches!(
@@ -319,14 +629,8 @@ where
         }
 
         visited.insert(page_index);
-        let subheaders = collect_subheaders(
-            reader,
-            header,
-            page_index,
-            page_type,
-            subheader_count,
-            options,
-        )?;
+        let subheaders =
+            collect_subheaders(reader, header, page_index, page_type, subheader_count, scan)?;
         if !subheaders.is_empty() && f(page_type, subheaders)? {
             return Ok(());
         }
@@ -336,10 +640,10 @@ where
         scan_backward_with_stop(
             reader,
             header,
-            &mut header_buf,
+            &mut cache,
             &visited,
             last_examined,
-            options,
+            scan,
             &mut f,
         )?;
     }
@@ -350,10 +654,10 @@ where
 fn scan_backward_with_stop<R, F>(
     reader: &mut R,
     header: &SasHeader,
-    header_buf: &mut [u8],
-    visited: &std::collections::HashSet<u64>,
+    cache: &mut PageHeaderCache,
+    visited: &HashSet<u64>,
     mut page_index: u64,
-    options: MetadataReadOptions,
+    scan: MetaScan<'_>,
     f: &mut F,
 ) -> Result<()>
 where
@@ -366,9 +670,7 @@ where
         if visited.contains(&page_index) {
             continue;
         }
-        load_page_header(reader, header, header_buf, page_index)?;
-        let page_type = page_type_from_header(header, header_buf)?;
-        let subheader_count = subheader_count_from_header(header, header_buf)?;
+        let (page_type, subheader_count) = cache.get(reader, header, page_index)?;
         let kind = classify_page(page_type);
         if matches!(
             kind,
@@ -387,14 +689,8 @@ where
         }
         seen_amd = true;
 
-        let subheaders = collect_subheaders(
-            reader,
-            header,
-            page_index,
-            page_type,
-            subheader_count,
-            options,
-        )?;
+        let subheaders =
+            collect_subheaders(reader, header, page_index, page_type, subheader_count, scan)?;
         if !subheaders.is_empty() && f(page_type, subheaders)? {
             return Ok(());
         }
@@ -403,6 +699,113 @@ where
     Ok(())
 }
 
+/// Moves the page header fields when the first page only makes sense with
+/// the page bit offset of the other word size.
+///
+/// Some third-party writers pair the offsets of one word size with the page
+/// header of the other. The first page of a dataset holds metadata, so the
+/// layout is taken as valid when its page type is a metadata type and its
+/// first subheader pointer leads to a known subheader signature.
+fn detect_page_layout<R: Read + Seek>(reader: &mut R, header: &mut SasHeader) -> Result<()> {
+    if header.page_count == 0 {
+        return Ok(());
+    }
+    // A truncated first page is reported by the metadata scan instead.
+    let mut page = Vec::with_capacity(header.page_size as usize);
+    reader.seek(SeekFrom::Start(header.data_offset))?;
+    reader
+        .by_ref()
+        .take(u64::from(header.page_size))
+        .read_to_end(&mut page)?;
+    if first_page_fits(header, &page, header.page_bit_offset) {
+        return Ok(());
+    }
+    for layout in [WordLayout::BITS_32, WordLayout::BITS_64] {
+        let page_bit_offset = layout.page_bit_offset;
+        if page_bit_offset != header.page_bit_offset
+            && first_page_fits(header, &page, page_bit_offset)
+        {
+            Logger::current().warn(&format!(
+                "page header fields found at offset {page_bit_offset} instead of {}; \
+                 reading pages with the {page_bit_offset}-byte layout",
+                header.page_bit_offset
+            ));
+            return header.set_page_bit_offset(page_bit_offset);
+        }
+    }
+    Ok(())
+}
+
+fn first_page_fits(header: &SasHeader, page: &[u8], page_bit_offset: usize) -> bool {
+    let field = |offset: usize| {
+        page.get(offset..offset + 2)
+            .map(|bytes| read_u16(header.endianness, bytes))
+    };
+    let (Some(page_type), Some(subheader_count)) =
+        (field(page_bit_offset), field(page_bit_offset + 4))
+    else {
+        return false;
+    };
+    if subheader_count == 0
+        || !matches!(
+            classify_page(page_type),
+            PageKind::Meta | PageKind::Mix | PageKind::Meta2 | PageKind::Amd
+        )
+    {
+        return false;
+    }
+    let start = page_bit_offset + SUBHEADER_POINTER_OFFSET;
+    let Some(pointer) = page.get(start..start + header.subheader_pointer_size as usize) else {
+        return false;
+    };
+    let Ok(pointer) = parse_pointer(pointer, header) else {
+        return false;
+    };
+    pointer.offset >= start + header.subheader_pointer_size as usize
+        && page
+            .get(pointer.offset..pointer.offset.saturating_add(pointer.length))
+            .is_some_and(|data| {
+                data.len() >= header.subheader_signature_size
+                    && signature_is_recognized(read_signature(
+                        data,
+                        header.endianness,
+                        header.uses_u64,
+                    ))
+            })
+}
+
+/// Index of the first metadata page stored after a data page, looking only
+/// at pages that end within the first `limit` bytes of the file.
+///
+/// Such pages hold column definitions that a scan stopping at the first
+/// data page misses; see [`MetadataReadOptions::with_leading_metadata_only`].
+pub(crate) fn trailing_metadata_page<R: Read + Seek>(
+    reader: &mut R,
+    limit: u64,
+) -> Result<Option<u64>> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = parse_header(reader)?;
+    detect_page_layout(reader, &mut header)?;
+    let page_size = u64::from(header.page_size);
+    let mut buffer = vec![0u8; header.page_header_size as usize];
+    let mut seen_data = false;
+    for page_index in 0..header.page_count {
+        let end = header
+            .data_offset
+            .saturating_add(page_index.saturating_add(1).saturating_mul(page_size));
+        if end > limit {
+            break;
+        }
+        load_page_header(reader, &header, &mut buffer, page_index)?;
+        match classify_page(page_type_from_header(&header, &buffer)?) {
+            PageKind::Data => seen_data = true,
+            PageKind::Amd | PageKind::Meta2 if seen_data => return Ok(Some(page_index)),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
 fn load_page_header<R: Read + Seek>(
     reader: &mut R,
     header: &SasHeader,
@@ -416,8 +819,7 @@ fn load_page_header<R: Read + Seek>(
 }
 
 fn page_type_from_header(header: &SasHeader, buffer: &[u8]) -> Result<u16> {
-    let page_header_size = header.page_header_size as usize;
-    let start = page_header_size.saturating_sub(8);
+    let start = header.page_type_offset();
     let end = start + 2;
     let slice = buffer.get(start..end).ok_or_else(|| Error::Corrupted {
         section: Section::Header,
@@ -427,8 +829,7 @@ fn page_type_from_header(header: &SasHeader, buffer: &[u8]) -> Result<u16> {
 }
 
 fn subheader_count_from_header(header: &SasHeader, buffer: &[u8]) -> Result<u16> {
-    let page_header_size = header.page_header_size as usize;
-    let start = page_header_size.saturating_sub(4);
+    let start = header.subheader_count_offset();
     let end = start + 2;
     let slice = buffer.get(start..end).ok_or_else(|| Error::Corrupted {
         section: Section::Header,
@@ -455,6 +856,7 @@ fn load_pointer_table<R: Read + Seek>(
     page_index: u64,
     page_type: u16,
     subheader_count: u16,
+    issues: &ParseIssues,
 ) -> Result<(u16, Vec<u8>)> {
     if subheader_count == 0 {
         return Ok((0, Vec::new()));
@@ -464,14 +866,18 @@ fn load_pointer_table<R: Read + Seek>(
     let original_count = subheader_count;
     let (subheader_count, max_subheaders) = clamp_subheader_count(header, subheader_count);
     if usize::from(original_count) > max_subheaders {
-        log_warn(&format!(
-            "Clamping subheader count from {} to {} to fit page bounds [page_len={}, header_size={}, pointer_size={}]",
-            original_count,
-            subheader_count,
-            header.page_size,
-            header.page_header_size,
-            pointer_size
-        ));
+        issues.recover(
+            &Logger::current(),
+            page_index,
+            format!(
+                "Clamping subheader count on metadata page {page_index} from {} to {} to fit page bounds [page_len={}, header_size={}, pointer_size={}]",
+                original_count,
+                subheader_count,
+                header.page_size,
+                header.page_header_size,
+                pointer_size
+            ),
+        )?;
     }
 
     let pointer_table_len = usize::from(subheader_count) * pointer_size;
@@ -482,14 +888,18 @@ fn load_pointer_table<R: Read + Seek>(
             details: Cow::from("subheader pointer table exceeds page bounds"),
         })?;
     if pointer_table_end > header.page_size as usize {
-        log_warn(&format!(
-            "Skipping metadata page {page_index} (type=0x{page_type:04X}): subheader pointer table exceeds page bounds \
-             [page_size={}, page_header_size={}, pointer_size={}, subheaders={}]",
-            header.page_size,
-            header.page_header_size,
-            header.subheader_pointer_size,
-            subheader_count
-        ));
+        issues.recover(
+            &Logger::current(),
+            page_index,
+            format!(
+                "Skipping metadata page {page_index} (type=0x{page_type:04X}): subheader pointer table exceeds page bounds \
+                 [page_size={}, page_header_size={}, pointer_size={}, subheaders={}]",
+                header.page_size,
+                header.page_header_size,
+                header.subheader_pointer_size,
+                subheader_count
+            ),
+        )?;
         return Ok((0, Vec::new()));
     }
 
@@ -509,7 +919,7 @@ fn collect_subheaders<R: Read + Seek>(
     page_index: u64,
     page_type: u16,
     subheader_count: u16,
-    options: MetadataReadOptions,
+    scan: MetaScan<'_>,
 ) -> Result<Vec<ParsedSubheader>> {
     let page_offset = header.data_offset + page_index * u64::from(header.page_size);
     let (subheader_count, pointer_table) = load_pointer_table(
@@ -519,6 +929,7 @@ fn collect_subheaders<R: Read + Seek>(
         page_index,
         page_type,
         subheader_count,
+        scan.issues,
     )?;
     if subheader_count == 0 {
         return Ok(Vec::new());
@@ -529,10 +940,9 @@ fn collect_subheaders<R: Read + Seek>(
     let total_payload: usize = pointers
         .iter()
         .filter(|info| info.length != 0 && info.compression == 0)
-        .map(|info| info.length)
-        .sum();
+        .fold(0, |total, info| total.saturating_add(info.length));
 
-    let use_full_page = match options.io_mode {
+    let use_full_page = match scan.options.io_mode {
         MetadataIoMode::FullPage => true,
         MetadataIoMode::Streaming => false,
         MetadataIoMode::Auto => {
@@ -742,18 +1152,8 @@ fn parse_pointer(pointer: &[u8], header: &SasHeader) -> Result<PointerInfo> {
                 details: Cow::from("64-bit subheader pointer too short"),
             });
         }
-        let offset =
-            usize::try_from(read_u64(header.endianness, &pointer[0..8])).map_err(|_| {
-                Error::Unsupported {
-                    feature: Cow::from("metadata subheader offset exceeds platform pointer width"),
-                }
-            })?;
-        let length =
-            usize::try_from(read_u64(header.endianness, &pointer[8..16])).map_err(|_| {
-                Error::Unsupported {
-                    feature: Cow::from("metadata subheader length exceeds platform pointer width"),
-                }
-            })?;
+        let offset = saturating_usize(read_u64(header.endianness, &pointer[0..8]));
+        let length = saturating_usize(read_u64(header.endianness, &pointer[8..16]));
         Ok(PointerInfo {
             offset,
             length,
@@ -767,18 +1167,8 @@ fn parse_pointer(pointer: &[u8], header: &SasHeader) -> Result<PointerInfo> {
                 details: Cow::from("32-bit subheader pointer too short"),
             });
         }
-        let offset =
-            usize::try_from(read_u32(header.endianness, &pointer[0..4])).map_err(|_| {
-                Error::Unsupported {
-                    feature: Cow::from("metadata subheader offset exceeds platform pointer width"),
-                }
-            })?;
-        let length =
-            usize::try_from(read_u32(header.endianness, &pointer[4..8])).map_err(|_| {
-                Error::Unsupported {
-                    feature: Cow::from("metadata subheader length exceeds platform pointer width"),
-                }
-            })?;
+        let offset = saturating_usize(read_u32(header.endianness, &pointer[0..4]));
+        let length = saturating_usize(read_u32(header.endianness, &pointer[4..8]));
         Ok(PointerInfo {
             offset,
             length,
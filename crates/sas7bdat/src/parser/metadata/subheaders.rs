@@ -329,13 +329,15 @@ pub fn parse_column_format_subheader(
         })?;
     let column = builder.ensure_column(column_index);
 
-    let (format_ref, label_ref) = if uses_u64 {
+    let (informat_ref, format_ref, label_ref) = if uses_u64 {
         (
+            parse_text_ref(endian, &bytes[40..46]),
             parse_text_ref(endian, &bytes[46..52]),
             parse_text_ref(endian, &bytes[52..58]),
         )
     } else {
         (
+            parse_text_ref(endian, &bytes[28..34]),
             parse_text_ref(endian, &bytes[34..40]),
             parse_text_ref(endian, &bytes[40..46]),
         )
@@ -344,8 +346,11 @@ pub fn parse_column_format_subheader(
     if uses_u64 {
         column.format_width = Some(read_u16(endian, &bytes[24..26]));
         column.format_decimals = Some(read_u16(endian, &bytes[26..28]));
+        column.informat_width = Some(read_u16(endian, &bytes[28..30]));
+        column.informat_decimals = Some(read_u16(endian, &bytes[30..32]));
     }
     column.format_ref = format_ref;
+    column.informat_ref = informat_ref;
     column.label_ref = label_ref;
 
     builder.note_formats_processed();
@@ -357,6 +362,7 @@ pub fn parse_column_size_subheader(
     bytes: &[u8],
     endian: Endianness,
     uses_u64: bool,
+    max_columns: u64,
 ) -> Result<u32> {
     let min_len = if uses_u64 { 16 } else { 8 };
     if bytes.len() < min_len {
@@ -375,6 +381,12 @@ pub fn parse_column_size_subheader(
     let column_count = u32::try_from(raw_count).map_err(|_| Error::InvalidMetadata {
         details: Cow::from("column count exceeds supported range"),
     })?;
+    if raw_count > max_columns {
+        return Err(Error::Corrupted {
+            section: Section::Header,
+            details: format!("column count {raw_count} exceeds what the file can describe").into(),
+        });
+    }
 
     builder.set_column_count(column_count);
     for idx in 0..column_count {
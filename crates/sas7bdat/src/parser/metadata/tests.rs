@@ -1,14 +1,33 @@
 use super::{
+    MetaScan, MetadataReadOptions, PageHeaderCache, ParseIssues, ParseMode,
+    UnsupportedNumericPolicy,
     builder::ColumnMetadataBuilder,
+    check_numeric_widths,
     column_info::ColumnKind,
+    column_list_order, detect_page_layout, load_pointer_table, scan_backward_with_stop,
+    scan_pages_with_stop,
     subheaders::{
         parse_column_attrs_subheader, parse_column_format_subheader, parse_column_list_subheader,
-        parse_column_name_subheader, parse_column_text_subheader,
+        parse_column_name_subheader, parse_column_size_subheader, parse_column_text_subheader,
+    },
+};
+use crate::{
+    dataset::{Alignment, DatasetMetadata, Endianness, Measure, Variable},
+    error::{Error, Section},
+    parser::{
+        format_spec::{
+            SAS_PAGE_TYPE_AMD, SAS_PAGE_TYPE_DATA, SAS_PAGE_TYPE_META,
+            SAS_SUBHEADER_SIGNATURE_ROW_SIZE,
+        },
+        header::SasHeader,
     },
 };
-use crate::dataset::{Alignment, Endianness, Measure};
 use encoding_rs::UTF_8;
-use std::convert::TryFrom;
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    io::{self, Cursor, Read, Seek, SeekFrom},
+};
 
 fn set_subheader_remainder(bytes: &mut [u8], signature_len: usize) {
     let remainder = u16::try_from(bytes.len() - (4 + 2 * signature_len))
@@ -114,6 +133,35 @@ fn column_list_subheader_collects_values() {
     assert_eq!(list[4], -1);
 }
 
+#[test]
+fn column_size_subheader_rejects_counts_beyond_file_size() {
+    let mut bytes = vec![0u8; 12];
+    bytes[4..8].copy_from_slice(&4_000_000_000u32.to_le_bytes());
+
+    let mut builder = ColumnMetadataBuilder::new(UTF_8);
+    let err = parse_column_size_subheader(&mut builder, &bytes, Endianness::Little, false, 1_000)
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::Corrupted { .. }),
+        "unexpected error: {err}"
+    );
+
+    bytes[4..8].copy_from_slice(&3u32.to_le_bytes());
+    let count = parse_column_size_subheader(&mut builder, &bytes, Endianness::Little, false, 1_000)
+        .unwrap();
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn column_list_order_requires_every_column_once() {
+    let list = [0, 3, -1, 0, -2];
+    assert_eq!(column_list_order(Some(&list), 3), Some(vec![2, 0, 1]));
+    assert_eq!(column_list_order(Some(&list), 4), None);
+    assert_eq!(column_list_order(Some(&[1, -1, 2]), 2), None);
+    assert_eq!(column_list_order(Some(&[1, 5]), 2), None);
+    assert_eq!(column_list_order(None, 2), None);
+}
+
 #[test]
 fn column_format_subheader_sets_refs() {
     let mut builder = ColumnMetadataBuilder::new(UTF_8);
@@ -149,3 +197,224 @@ fn build_column_attrs_subheader(
     set_subheader_remainder(&mut bytes, signature_len);
     bytes
 }
+
+#[test]
+fn unsupported_numeric_width_errors_or_degrades_to_bytes() {
+    let mut builder = ColumnMetadataBuilder::new(UTF_8);
+    let bytes = build_column_attrs_subheader(4, |entry| {
+        entry[0..4].copy_from_slice(&0u32.to_le_bytes());
+        entry[4..8].copy_from_slice(&16u32.to_le_bytes());
+        entry[10] = 0x01;
+    });
+    parse_column_attrs_subheader(&mut builder, &bytes, 4, Endianness::Little, false).unwrap();
+    let (_, columns, _) = builder.finalize();
+    let variables = vec![Variable {
+        name: "WIDE".to_owned(),
+        ..Variable::default()
+    }];
+
+    let mut rejected = columns.clone();
+    let err = check_numeric_widths(&mut rejected, &variables, UnsupportedNumericPolicy::Error)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Unsupported { ref feature } if feature.contains("'WIDE'") && feature.contains("16")
+    ));
+
+    let mut degraded = columns;
+    check_numeric_widths(&mut degraded, &variables, UnsupportedNumericPolicy::Bytes).unwrap();
+    assert_eq!(degraded[0].kind, ColumnKind::Bytes);
+}
+
+const PAGE_HEADER_SIZE: u32 = 24;
+const PAGE_SIZE: u32 = 64;
+
+/// Records the offset and length of every read so tests can count page
+/// header fetches.
+struct CountingReader {
+    inner: Cursor<Vec<u8>>,
+    reads: Vec<(u64, usize)>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reads.push((self.inner.position(), buf.len()));
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for CountingReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl CountingReader {
+    fn header_reads(&self, page_count: u64) -> Vec<usize> {
+        (0..page_count)
+            .map(|page| {
+                let start = page * u64::from(PAGE_SIZE);
+                self.reads
+                    .iter()
+                    .filter(|&&(offset, len)| offset == start && len == PAGE_HEADER_SIZE as usize)
+                    .count()
+            })
+            .collect()
+    }
+}
+
+/// Builds a file of empty pages of the given types and a matching header.
+fn synthetic_pages(page_types: &[u16]) -> (SasHeader, CountingReader) {
+    let mut bytes = Vec::new();
+    for page_type in page_types {
+        let mut page = vec![0u8; PAGE_SIZE as usize];
+        let type_offset = PAGE_HEADER_SIZE as usize - 8;
+        page[type_offset..type_offset + 2].copy_from_slice(&page_type.to_le_bytes());
+        bytes.extend_from_slice(&page);
+    }
+    let header = SasHeader {
+        metadata: DatasetMetadata::new(0),
+        endianness: Endianness::Little,
+        uses_u64: false,
+        page_bit_offset: 16,
+        page_header_size: PAGE_HEADER_SIZE,
+        subheader_pointer_size: 12,
+        subheader_signature_size: 4,
+        header_size: 0,
+        page_size: PAGE_SIZE,
+        page_count: page_types.len() as u64,
+        pad_alignment: 0,
+        data_offset: 0,
+    };
+    let reader = CountingReader {
+        inner: Cursor::new(bytes),
+        reads: Vec::new(),
+    };
+    (header, reader)
+}
+
+#[test]
+fn metadata_scan_reads_each_page_header_once() {
+    let page_types = [
+        SAS_PAGE_TYPE_META,
+        SAS_PAGE_TYPE_DATA,
+        SAS_PAGE_TYPE_DATA,
+        SAS_PAGE_TYPE_AMD,
+        SAS_PAGE_TYPE_DATA,
+        SAS_PAGE_TYPE_AMD,
+    ];
+    let (header, mut reader) = synthetic_pages(&page_types);
+    let issues = ParseIssues::default();
+    let scan = MetaScan {
+        options: MetadataReadOptions::default(),
+        issues: &issues,
+    };
+    scan_pages_with_stop(&mut reader, &header, scan, |_, _| Ok(false)).unwrap();
+    assert_eq!(reader.header_reads(header.page_count), [1; 6]);
+}
+
+#[test]
+fn backward_scan_reuses_headers_from_forward_pass() {
+    let page_types = [
+        SAS_PAGE_TYPE_META,
+        SAS_PAGE_TYPE_DATA,
+        SAS_PAGE_TYPE_AMD,
+        SAS_PAGE_TYPE_AMD,
+        SAS_PAGE_TYPE_AMD,
+    ];
+    let (header, mut reader) = synthetic_pages(&page_types);
+    let mut cache = PageHeaderCache::new(&header);
+    for page in 0..3 {
+        cache.get(&mut reader, &header, page).unwrap();
+    }
+    let issues = ParseIssues::default();
+    let scan = MetaScan {
+        options: MetadataReadOptions::default(),
+        issues: &issues,
+    };
+    scan_backward_with_stop(
+        &mut reader,
+        &header,
+        &mut cache,
+        &HashSet::from([0]),
+        header.page_count,
+        scan,
+        &mut |_, _| Ok(false),
+    )
+    .unwrap();
+    // The backward scan walks the AMD run down to the data page at index 1,
+    // reusing the headers of pages 1 and 2 that the forward pass cached.
+    assert_eq!(reader.header_reads(header.page_count), [1, 1, 1, 1, 1]);
+}
+
+#[test]
+fn oversized_subheader_count_is_clamped_or_rejected_by_parse_mode() {
+    // A 64-byte page fits three 12-byte pointers after its header.
+    let (header, mut reader) = synthetic_pages(&[SAS_PAGE_TYPE_META]);
+
+    let lenient = ParseIssues::new(ParseMode::Lenient);
+    let (count, table) =
+        load_pointer_table(&mut reader, &header, 0, 0, SAS_PAGE_TYPE_META, 10, &lenient).unwrap();
+    assert_eq!((count, table.len()), (3, 36));
+    let warnings = lenient.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].page_index, 0);
+    assert!(warnings[0].message.contains("from 10 to 3"), "{warnings:?}");
+    let diagnostics = lenient.diagnostics().entries();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].page_index, Some(0));
+    assert_eq!(diagnostics[0].message, warnings[0].message);
+
+    let strict = ParseIssues::new(ParseMode::Strict);
+    let err = load_pointer_table(&mut reader, &header, 0, 0, SAS_PAGE_TYPE_META, 10, &strict)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Corrupted {
+            section: Section::Page { index: 0 },
+            ..
+        }
+    ));
+    assert!(strict.warnings().is_empty());
+    assert!(strict.diagnostics().is_empty());
+}
+
+/// A 32-bit metadata page with its header fields at `page_bit_offset` and
+/// one pointer to a row size subheader.
+fn metadata_page(page_bit_offset: usize) -> Vec<u8> {
+    let mut page = vec![0u8; 128];
+    page[page_bit_offset..page_bit_offset + 2].copy_from_slice(&SAS_PAGE_TYPE_META.to_le_bytes());
+    page[page_bit_offset + 4..page_bit_offset + 6].copy_from_slice(&1u16.to_le_bytes());
+    let pointer = page_bit_offset + 8;
+    page[pointer..pointer + 4].copy_from_slice(&96u32.to_le_bytes());
+    page[pointer + 4..pointer + 8].copy_from_slice(&16u32.to_le_bytes());
+    page[96..100].copy_from_slice(&SAS_SUBHEADER_SIGNATURE_ROW_SIZE.to_le_bytes());
+    page
+}
+
+fn header_for(page: &[u8]) -> (SasHeader, Cursor<Vec<u8>>) {
+    let (mut header, _) = synthetic_pages(&[SAS_PAGE_TYPE_META]);
+    header.page_size = u32::try_from(page.len()).expect("page size");
+    (header, Cursor::new(page.to_vec()))
+}
+
+#[test]
+fn page_layout_follows_the_first_metadata_page() {
+    let (mut header, mut reader) = header_for(&metadata_page(16));
+    detect_page_layout(&mut reader, &mut header).expect("standard layout");
+    assert_eq!((header.page_bit_offset, header.page_header_size), (16, 24));
+
+    let (mut header, mut reader) = header_for(&metadata_page(32));
+    detect_page_layout(&mut reader, &mut header).expect("64-bit page header");
+    assert_eq!((header.page_bit_offset, header.page_header_size), (32, 40));
+    assert_eq!(header.subheader_count_offset(), 36);
+}
+
+#[test]
+fn unrecognised_first_page_keeps_the_word_size_layout() {
+    let mut page = metadata_page(32);
+    page[96..100].fill(0x11);
+    let (mut header, mut reader) = header_for(&page);
+    detect_page_layout(&mut reader, &mut header).expect("layout kept");
+    assert_eq!((header.page_bit_offset, header.page_header_size), (16, 24));
+}
@@ -1,21 +1,26 @@
 mod catalog;
 pub mod core;
+pub mod format_spec;
 mod header;
 pub mod metadata;
+mod quirks;
 mod rows;
 
 pub use catalog::{CatalogLayout, parse_catalog};
 pub use core::byteorder::{read_i16, read_u16, read_u32, read_u64, read_u64_be};
 pub use header::{SasHeader, parse_header};
+pub(crate) use metadata::trailing_metadata_page;
 pub use metadata::{
-    ColumnInfo, ColumnKind, ColumnMetadataBuilder, ColumnOffsets, DatasetLayout, MetadataIoMode,
-    MetadataReadOptions, NumericKind, RowInfo, TextRef, TextStore, parse_metadata,
+    ColumnInfo, ColumnKind, ColumnMetadataBuilder, ColumnOffsets, ColumnOrder, ColumnType,
+    DatasetLayout, Diagnostic, Diagnostics, MetadataIoMode, MetadataReadOptions, NumericKind,
+    ParseMode, ParseWarning, RowInfo, TextRef, TextStore, UnsupportedNumericPolicy, parse_metadata,
     parse_metadata_with_options,
 };
+pub use quirks::{MixPageAlignment, VendorQuirks};
+pub(crate) use rows::with_parallel_rows;
 pub use rows::{
-    ColumnarBatch, ColumnarColumn, MaterializedUtf8Column, OwnedRowIterator, RowIterator,
-    RowIteratorCore, RuntimeColumnRef, StagedUtf8Value, StreamingCell, StreamingRow,
-    TypedNumericColumn, row_iterator,
+    BatchSizing, ColumnarBatch, ColumnarColumn, DecodeLayout, MaterializedUtf8Column,
+    OwnedRowIterator, PageDecompressor, ParallelRowIterator, RowIterator, RowIteratorCore,
+    RuntimeColumnRef, StagedUtf8Value, StreamingCell, StreamingRow, TimestampRounding,
+    TypedNumericColumn, Utf8Arena, ValidityBitmap, register_page_decompressor, row_iterator,
 };
-#[cfg(feature = "parquet")]
-pub(crate) use rows::{sas_days_to_datetime, sas_seconds_to_datetime, sas_seconds_to_time};
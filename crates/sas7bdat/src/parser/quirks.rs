@@ -0,0 +1,118 @@
+//! Vendor-specific workarounds keyed by the producer and release recorded in
+//! the file header.
+//!
+//! Files written by tools other than SAS occasionally diverge from the layout
+//! SAS itself produces. Rather than branching on [`Vendor`] throughout the
+//! parser, each divergence is captured once in [`VendorQuirks`] and looked up
+//! through a small registry.
+
+use crate::dataset::{SasVersion, Vendor};
+
+/// Offset between the SAS epoch (1960-01-01) and the Unix epoch in seconds.
+pub const SAS_EPOCH_OFFSET_SECONDS: i64 = -3653 * 86_400;
+
+/// How the row area of a mixed (metadata + data) page is aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixPageAlignment {
+    /// Always skip the 4-byte pad when the row area lands on a 4-byte boundary.
+    Always,
+    /// Only skip the pad when it holds zeroes or blanks; otherwise rows start
+    /// immediately after the subheader pointers.
+    PaddingOnly,
+}
+
+/// Layout and interpretation rules that vary between file producers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VendorQuirks {
+    /// Alignment rule for the row area on mixed pages.
+    pub mix_page_alignment: MixPageAlignment,
+    /// Seconds added to header timestamps to convert them to Unix time.
+    pub epoch_offset_seconds: i64,
+    /// Whether the row count stored in the row-size subheader bounds iteration.
+    /// When `false`, rows are read until the data pages are exhausted.
+    pub trust_row_count: bool,
+}
+
+impl VendorQuirks {
+    /// Rules applied to files produced by SAS itself.
+    pub const SAS: Self = Self {
+        mix_page_alignment: MixPageAlignment::Always,
+        epoch_offset_seconds: SAS_EPOCH_OFFSET_SECONDS,
+        trust_row_count: true,
+    };
+
+    /// Looks up the quirks registered for the given vendor and release.
+    ///
+    /// Falls back to [`VendorQuirks::SAS`] when no entry matches.
+    #[must_use]
+    pub fn lookup(vendor: Vendor, version: SasVersion) -> Self {
+        REGISTRY
+            .iter()
+            .find(|entry| entry.matches(vendor, version))
+            .map_or(Self::SAS, |entry| entry.quirks)
+    }
+}
+
+impl Default for VendorQuirks {
+    fn default() -> Self {
+        Self::SAS
+    }
+}
+
+struct QuirkEntry {
+    vendor: Vendor,
+    /// Inclusive range of major versions the entry applies to.
+    major: (u16, u16),
+    quirks: VendorQuirks,
+}
+
+impl QuirkEntry {
+    fn matches(&self, vendor: Vendor, version: SasVersion) -> bool {
+        self.vendor == vendor && (self.major.0..=self.major.1).contains(&version.major)
+    }
+}
+
+const REGISTRY: &[QuirkEntry] = &[QuirkEntry {
+    vendor: Vendor::StatTransfer,
+    major: (0, u16::MAX),
+    quirks: VendorQuirks {
+        mix_page_alignment: MixPageAlignment::PaddingOnly,
+        ..VendorQuirks::SAS
+    },
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn version(major: u16) -> SasVersion {
+        SasVersion {
+            major,
+            minor: 0,
+            revision: 0,
+        }
+    }
+
+    #[test]
+    fn sas_files_use_default_quirks() {
+        let quirks = VendorQuirks::lookup(Vendor::Sas, version(9));
+        assert_eq!(quirks, VendorQuirks::SAS);
+        assert_eq!(quirks.mix_page_alignment, MixPageAlignment::Always);
+        assert!(quirks.trust_row_count);
+    }
+
+    #[test]
+    fn stat_transfer_only_skips_blank_padding() {
+        let quirks = VendorQuirks::lookup(Vendor::StatTransfer, version(9));
+        assert_eq!(quirks.mix_page_alignment, MixPageAlignment::PaddingOnly);
+        assert_eq!(quirks.epoch_offset_seconds, SAS_EPOCH_OFFSET_SECONDS);
+    }
+
+    #[test]
+    fn unknown_vendors_fall_back_to_sas() {
+        assert_eq!(
+            VendorQuirks::lookup(Vendor::Other(7), version(8)),
+            VendorQuirks::SAS
+        );
+    }
+}
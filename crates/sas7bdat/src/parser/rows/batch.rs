@@ -1,5 +1,5 @@
 use super::{
-    columnar::{COLUMNAR_BATCH_ROWS, COLUMNAR_INLINE_ROWS, ColumnarBatch},
+    columnar::{COLUMNAR_INLINE_ROWS, ColumnarBatch},
     iterator::RowIteratorCore,
 };
 use crate::{error::Result, parser::metadata::DatasetLayout};
@@ -13,6 +13,99 @@ use std::{
 // Cap columnar staging to avoid enormous allocations when row_length is very large.
 const MAX_COLUMNAR_BUFFER_BYTES: usize = 512 * 1024 * 1024;
 
+// Projections selecting at most 1/N of the columns stay on the borrowed path.
+const NARROW_PROJECTION_RATIO: usize = 4;
+// Below this many columns, copying rows into a staging buffer is not worth it.
+const COLUMN_MAJOR_MIN_COLUMNS: usize = 8;
+
+// Row data per batch when sizing adapts to the row width.
+const DEFAULT_BATCH_TARGET_BYTES: usize = 256 * 1024;
+// Bounds for adaptive batches: very wide rows still get a useful batch, and
+// very narrow rows do not stage unbounded row slice lists.
+const MIN_ADAPTIVE_BATCH_ROWS: usize = 64;
+const MAX_ADAPTIVE_BATCH_ROWS: usize = 65_536;
+
+/// Selects how many rows a columnar batch holds when the caller passes
+/// `max_rows == 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchSizing {
+    /// Always use this many rows.
+    Rows(usize),
+    /// Fit roughly this many bytes of row data into a batch, so narrow
+    /// datasets get large batches and wide ones small batches.
+    TargetBytes(usize),
+}
+
+impl Default for BatchSizing {
+    fn default() -> Self {
+        Self::TargetBytes(DEFAULT_BATCH_TARGET_BYTES)
+    }
+}
+
+impl BatchSizing {
+    /// Returns the batch row count for rows of `row_length` bytes.
+    #[must_use]
+    pub const fn rows_for(self, row_length: usize) -> usize {
+        match self {
+            Self::Rows(rows) => {
+                if rows == 0 {
+                    1
+                } else {
+                    rows
+                }
+            }
+            Self::TargetBytes(bytes) => {
+                let row_length = if row_length == 0 { 1 } else { row_length };
+                let rows = bytes / row_length;
+                if rows < MIN_ADAPTIVE_BATCH_ROWS {
+                    MIN_ADAPTIVE_BATCH_ROWS
+                } else if rows > MAX_ADAPTIVE_BATCH_ROWS {
+                    MAX_ADAPTIVE_BATCH_ROWS
+                } else {
+                    rows
+                }
+            }
+        }
+    }
+}
+
+/// Selects how rows are gathered into a [`ColumnarBatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeLayout {
+    /// Pick a layout from the column count and projection width.
+    #[default]
+    Auto,
+    /// Borrow row slices straight from the current page buffer. Batches never
+    /// span pages, but no bytes are copied.
+    RowMajor,
+    /// Copy rows into a contiguous staging buffer so batches can span pages and
+    /// columns can be materialised over larger runs.
+    ColumnMajor,
+}
+
+impl DecodeLayout {
+    /// Resolves [`DecodeLayout::Auto`] into a concrete layout.
+    ///
+    /// Wide projections over many columns favour the contiguous column-major
+    /// path; narrow projections or small tables avoid the copy and borrow rows
+    /// from the page. Explicit layouts are returned unchanged.
+    #[must_use]
+    pub const fn resolve(self, column_count: usize, projected_columns: usize) -> Self {
+        match self {
+            Self::Auto => {
+                if column_count < COLUMN_MAJOR_MIN_COLUMNS
+                    || projected_columns.saturating_mul(NARROW_PROJECTION_RATIO) <= column_count
+                {
+                    Self::RowMajor
+                } else {
+                    Self::ColumnMajor
+                }
+            }
+            other => other,
+        }
+    }
+}
+
 struct PageChunk {
     start: usize,
     row_end: usize,
@@ -20,12 +113,16 @@ struct PageChunk {
 }
 
 #[inline]
-const fn resolve_target(iter_exhausted: &std::cell::Cell<bool>, max_rows: usize) -> Option<usize> {
-    if iter_exhausted.get() {
+const fn resolve_target<R, L>(iter: &RowIteratorCore<R, L>, max_rows: usize) -> Option<usize>
+where
+    R: Read + Seek,
+    L: Deref<Target = DatasetLayout>,
+{
+    if iter.exhausted.get() {
         return None;
     }
     Some(if max_rows == 0 {
-        COLUMNAR_BATCH_ROWS
+        iter.batch_rows()
     } else {
         max_rows
     })
@@ -39,7 +136,7 @@ where
     R: Read + Seek,
     L: Deref<Target = DatasetLayout>,
 {
-    let target = resolve_target(&iter.exhausted, max_rows)?;
+    let target = resolve_target(iter, max_rows)?;
     let remaining_rows = usize::try_from(
         iter.total_rows
             .saturating_sub(iter.emitted_rows.get())
@@ -89,10 +186,51 @@ where
     }
 }
 
+pub fn next_batch_with_layout<R, L>(
+    iter: &mut RowIteratorCore<R, L>,
+    layout: DecodeLayout,
+    projected_columns: usize,
+    max_rows: usize,
+) -> Result<Option<ColumnarBatch<'_>>>
+where
+    R: Read + Seek,
+    L: Deref<Target = DatasetLayout>,
+{
+    match layout.resolve(iter.columnar_columns.len(), projected_columns) {
+        DecodeLayout::ColumnMajor => next_columnar_batch_contiguous(iter, max_rows),
+        DecodeLayout::Auto | DecodeLayout::RowMajor => next_columnar_batch(iter, max_rows),
+    }
+}
+
 pub fn next_columnar_batch<R, L>(
     iter: &mut RowIteratorCore<R, L>,
     max_rows: usize,
 ) -> Result<Option<ColumnarBatch<'_>>>
+where
+    R: Read + Seek,
+    L: Deref<Target = DatasetLayout>,
+{
+    next_row_major_batch(iter, max_rows, false)
+}
+
+/// Like [`next_columnar_batch`], but the batch exposes only the columns in
+/// `iter.projected_columns`, in their order.
+pub fn next_columnar_batch_projected<R, L>(
+    iter: &mut RowIteratorCore<R, L>,
+    max_rows: usize,
+) -> Result<Option<ColumnarBatch<'_>>>
+where
+    R: Read + Seek,
+    L: Deref<Target = DatasetLayout>,
+{
+    next_row_major_batch(iter, max_rows, true)
+}
+
+fn next_row_major_batch<R, L>(
+    iter: &mut RowIteratorCore<R, L>,
+    max_rows: usize,
+    projected: bool,
+) -> Result<Option<ColumnarBatch<'_>>>
 where
     R: Read + Seek,
     L: Deref<Target = DatasetLayout>,
@@ -112,13 +250,19 @@ where
         row_slices.push(slice);
     }
 
+    let columns = if projected {
+        &iter.projected_columns
+    } else {
+        &iter.columnar_columns
+    };
     let batch = ColumnarBatch::new(
         row_slices,
-        &iter.columnar_columns,
+        columns,
         iter.layout.header.endianness,
         iter.encoding,
         false,
-    );
+    )
+    .with_timestamp_rounding(iter.timestamp_rounding);
     Ok(Some(batch))
 }
 
@@ -192,6 +336,7 @@ where
         iter.layout.header.endianness,
         iter.encoding,
         true,
-    );
+    )
+    .with_timestamp_rounding(iter.timestamp_rounding);
     Ok(Some(batch))
 }
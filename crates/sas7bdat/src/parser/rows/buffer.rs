@@ -1,5 +1,45 @@
 use crate::error::{Error, Result, Section};
-use std::borrow::Cow;
+use bytes::Bytes;
+use std::{borrow::Cow, ops::Deref};
+
+/// Bytes of the page being decoded: either read into the reusable owned
+/// buffer, or sliced out of an in-memory file without copying.
+#[derive(Default)]
+pub struct PageBuffer {
+    owned: Vec<u8>,
+    shared: Option<Bytes>,
+}
+
+impl PageBuffer {
+    /// Returns the owned buffer to read the next page into.
+    pub fn owned_mut(&mut self) -> &mut [u8] {
+        self.shared = None;
+        &mut self.owned
+    }
+
+    /// Serves the next page from `page` instead of the owned buffer.
+    pub fn share(&mut self, page: Bytes) {
+        self.shared = Some(page);
+    }
+}
+
+impl From<Vec<u8>> for PageBuffer {
+    fn from(owned: Vec<u8>) -> Self {
+        Self {
+            owned,
+            shared: None,
+        }
+    }
+}
+
+impl Deref for PageBuffer {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.shared.as_deref().unwrap_or(&self.owned)
+    }
+}
 
 /// Borrowed or owned row data used while paging.
 pub enum RowData {
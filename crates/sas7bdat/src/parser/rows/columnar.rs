@@ -1,14 +1,17 @@
 use super::{
     decode::{
-        decode_string, is_blank, numeric_bits, numeric_bits_is_missing, sas_days_to_datetime,
-        sas_seconds_to_datetime, sas_seconds_to_time, trim_trailing_space_or_nul_simd,
+        TimestampRounding, decode_string, decode_string_with, decode_value_inner, is_blank,
+        numeric_bits, numeric_bits_is_missing, numeric_text, trim_trailing_space_or_nul_simd,
+        write_numeric_text,
     },
     runtime_column::RuntimeColumnRef,
 };
 use crate::{
+    cell::CellValue,
     dataset::Endianness,
     error::{Error, Result},
     parser::metadata::{ColumnKind, NumericKind},
+    time_conv::{sas_days_to_date, sas_seconds_to_datetime, sas_seconds_to_time},
 };
 use encoding_rs::{Encoding, UTF_8};
 use hashbrown::{HashMap, hash_map::RawEntryMut};
@@ -22,7 +25,6 @@ use std::{
     hash::BuildHasherDefault,
 };
 
-pub const COLUMNAR_BATCH_ROWS: usize = 256;
 pub const COLUMNAR_INLINE_ROWS: usize = 32;
 pub const STAGED_UTF8_DICTIONARY_LIMIT: usize = 2_048;
 const SECONDS_PER_DAY_I64: i64 = 86_400;
@@ -36,6 +38,7 @@ pub struct ColumnarBatch<'rows> {
     typed_numeric: RefCell<Vec<Option<TypedNumericColumn>>>,
     utf8_staged: RefCell<Vec<Option<MaterializedUtf8Column>>>,
     stage_utf8: bool,
+    timestamp_rounding: TimestampRounding,
 }
 
 pub struct MaterializedColumn<T> {
@@ -43,6 +46,20 @@ pub struct MaterializedColumn<T> {
     def_levels: Vec<i16>,
 }
 
+impl<T> MaterializedColumn<T> {
+    /// Definition level of every row: `1` when present, `0` when missing.
+    #[must_use]
+    pub fn def_levels(&self) -> &[i16] {
+        &self.def_levels
+    }
+
+    /// Values of the present rows, in row order.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+}
+
 pub enum TypedNumericColumn {
     Double(MaterializedColumn<f64>),
     Date(MaterializedColumn<i32>),
@@ -109,6 +126,202 @@ pub enum StagedUtf8Value {
     Inline(Vec<u8>),
 }
 
+/// A character column decoded into one contiguous UTF-8 buffer, laid out
+/// like an Arrow `Utf8` array: value `i` is `data[offsets[i]..offsets[i + 1]]`.
+///
+/// Blank cells are SAS missing values; they are marked invalid and occupy an
+/// empty range. The buffers can be reused across batches with
+/// [`ColumnarColumn::decode_utf8_into`].
+#[derive(Debug, Clone, Default)]
+pub struct Utf8Arena {
+    offsets: Vec<i32>,
+    data: Vec<u8>,
+    validity: Vec<bool>,
+    null_count: usize,
+    /// Holds a transcoded value on its way into `data`, reused across cells.
+    spill: String,
+}
+
+impl Utf8Arena {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            offsets: Vec::new(),
+            data: Vec::new(),
+            validity: Vec::new(),
+            null_count: 0,
+            spill: String::new(),
+        }
+    }
+
+    /// Drops all values while keeping the allocations.
+    pub fn clear(&mut self) {
+        self.offsets.clear();
+        self.data.clear();
+        self.validity.clear();
+        self.null_count = 0;
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.validity.is_empty()
+    }
+
+    #[must_use]
+    pub const fn null_count(&self) -> usize {
+        self.null_count
+    }
+
+    /// Value offsets into [`Utf8Arena::data`]; one more entry than values.
+    #[must_use]
+    pub fn offsets(&self) -> &[i32] {
+        &self.offsets
+    }
+
+    /// Concatenated UTF-8 bytes of every valid value.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// `false` for missing values.
+    #[must_use]
+    pub fn validity(&self) -> &[bool] {
+        &self.validity
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        if !*self.validity.get(index)? {
+            return None;
+        }
+        let start = usize::try_from(self.offsets[index]).ok()?;
+        let end = usize::try_from(self.offsets[index + 1]).ok()?;
+        // Only whole `str` values are ever appended to `data`.
+        basic::from_utf8(&self.data[start..end]).ok()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<&str>> + '_ {
+        (0..self.len()).map(move |index| self.get(index))
+    }
+
+    /// Splits the arena into its offsets, data and validity buffers.
+    #[must_use]
+    pub fn into_parts(self) -> (Vec<i32>, Vec<u8>, Vec<bool>) {
+        (self.offsets, self.data, self.validity)
+    }
+
+    fn reset(&mut self, rows: usize) {
+        self.clear();
+        self.offsets.reserve(rows + 1);
+        self.offsets.push(0);
+        self.validity.reserve(rows);
+    }
+
+    fn push(&mut self, value: Option<&str>) -> Result<()> {
+        if let Some(text) = value.filter(|text| !text.is_empty()) {
+            self.data.extend_from_slice(text.as_bytes());
+            self.validity.push(true);
+        } else {
+            self.validity.push(false);
+            self.null_count += 1;
+        }
+        let end = i32::try_from(self.data.len()).map_err(|_| Error::Limit {
+            details: Cow::from("string data in a batch exceeds 2 GiB"),
+        })?;
+        self.offsets.push(end);
+        Ok(())
+    }
+}
+
+/// Validity of one column of a batch, one bit per row, laid out like an
+/// Arrow validity buffer: bit `i % 8` of byte `i / 8` is set when row `i`
+/// holds a value.
+///
+/// SAS numeric missing values and blank character cells are unset, matching
+/// [`ColumnarColumn::iter_numeric_bits`] and [`ColumnarColumn::iter_strings`].
+/// The buffer can be reused across batches with
+/// [`ColumnarColumn::validity_into`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidityBitmap {
+    bits: Vec<u8>,
+    len: usize,
+    null_count: usize,
+}
+
+impl ValidityBitmap {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bits: Vec::new(),
+            len: 0,
+            null_count: 0,
+        }
+    }
+
+    /// Drops all bits while keeping the allocation.
+    pub fn clear(&mut self) {
+        self.bits.clear();
+        self.len = 0;
+        self.null_count = 0;
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub const fn null_count(&self) -> usize {
+        self.null_count
+    }
+
+    /// Returns `false` for missing values and indices past the end.
+    #[must_use]
+    pub fn is_valid(&self, index: usize) -> bool {
+        index < self.len && self.bits[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Packed bits; the unused high bits of the last byte are zero.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    #[must_use]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bits
+    }
+
+    fn reset(&mut self, rows: usize) {
+        self.clear();
+        self.bits.reserve(rows.div_ceil(8));
+    }
+
+    fn push(&mut self, valid: bool) {
+        let bit = self.len % 8;
+        if bit == 0 {
+            self.bits.push(0);
+        }
+        if valid {
+            *self.bits.last_mut().expect("byte pushed above") |= 1 << bit;
+        } else {
+            self.null_count += 1;
+        }
+        self.len += 1;
+    }
+}
+
 impl<'rows> ColumnarBatch<'rows> {
     #[must_use]
     pub(crate) fn new(
@@ -132,9 +345,25 @@ impl<'rows> ColumnarBatch<'rows> {
             typed_numeric: RefCell::new(typed_numeric),
             utf8_staged: RefCell::new(utf8_staged),
             stage_utf8,
+            timestamp_rounding: TimestampRounding::default(),
         }
     }
 
+    /// Sets the rounding [`materialize_numeric`](Self::materialize_numeric)
+    /// applies to timestamp and time columns.
+    #[must_use]
+    pub(crate) const fn with_timestamp_rounding(mut self, rounding: TimestampRounding) -> Self {
+        self.timestamp_rounding = rounding;
+        self
+    }
+
+    /// Rounding applied when timestamps and times are materialised as
+    /// microseconds.
+    #[must_use]
+    pub const fn timestamp_rounding(&self) -> TimestampRounding {
+        self.timestamp_rounding
+    }
+
     pub fn truncate_front(&mut self, rows: usize) {
         if rows >= self.row_count {
             self.row_slices.clear();
@@ -255,13 +484,18 @@ impl<'rows> ColumnarBatch<'rows> {
         kind: NumericKind,
     ) -> Result<TypedNumericColumn> {
         let column = self.column(index).expect("column index out of bounds");
+        let rounding = self.timestamp_rounding;
         match kind {
-            NumericKind::Double => Ok(TypedNumericColumn::Double(Self::materialize_f64(&column))),
+            NumericKind::Double | NumericKind::Float | NumericKind::Text => {
+                Ok(TypedNumericColumn::Double(Self::materialize_f64(&column)))
+            }
             NumericKind::Date => Ok(TypedNumericColumn::Date(Self::materialize_date(&column)?)),
             NumericKind::DateTime => Ok(TypedNumericColumn::DateTime(Self::materialize_datetime(
-                &column,
+                &column, rounding,
+            )?)),
+            NumericKind::Time => Ok(TypedNumericColumn::Time(Self::materialize_time(
+                &column, rounding,
             )?)),
-            NumericKind::Time => Ok(TypedNumericColumn::Time(Self::materialize_time(&column)?)),
         }
     }
 
@@ -271,7 +505,7 @@ impl<'rows> ColumnarBatch<'rows> {
 
     fn materialize_date(column: &ColumnarColumn<'_, '_>) -> Result<MaterializedColumn<i32>> {
         Self::materialize_numeric_result(column, |days| {
-            let datetime = sas_days_to_datetime(days).ok_or_else(|| Error::InvalidMetadata {
+            let datetime = sas_days_to_date(days).ok_or_else(|| Error::InvalidMetadata {
                 details: Cow::Owned(format!(
                     "column '{}' contains date outside supported range",
                     column.index()
@@ -324,7 +558,10 @@ impl<'rows> ColumnarBatch<'rows> {
         Self::materialize_numeric_result(column, map)
     }
 
-    fn materialize_datetime(column: &ColumnarColumn<'_, '_>) -> Result<MaterializedColumn<i64>> {
+    fn materialize_datetime(
+        column: &ColumnarColumn<'_, '_>,
+        rounding: TimestampRounding,
+    ) -> Result<MaterializedColumn<i64>> {
         Self::materialize_i64_mapped(column, |seconds| {
             let datetime =
                 sas_seconds_to_datetime(seconds).ok_or_else(|| Error::InvalidMetadata {
@@ -333,7 +570,7 @@ impl<'rows> ColumnarBatch<'rows> {
                         column.index()
                     )),
                 })?;
-            let micros = datetime.unix_timestamp_nanos().div_euclid(1_000);
+            let micros = rounding.datetime_micros(datetime);
             i64::try_from(micros).map_err(|_| Error::InvalidMetadata {
                 details: Cow::Owned(format!(
                     "column '{}' contains timestamp outside Parquet range",
@@ -343,7 +580,10 @@ impl<'rows> ColumnarBatch<'rows> {
         })
     }
 
-    fn materialize_time(column: &ColumnarColumn<'_, '_>) -> Result<MaterializedColumn<i64>> {
+    fn materialize_time(
+        column: &ColumnarColumn<'_, '_>,
+        rounding: TimestampRounding,
+    ) -> Result<MaterializedColumn<i64>> {
         Self::materialize_i64_mapped(column, |seconds| {
             let duration = sas_seconds_to_time(seconds).ok_or_else(|| Error::InvalidMetadata {
                 details: Cow::Owned(format!(
@@ -351,7 +591,7 @@ impl<'rows> ColumnarBatch<'rows> {
                     column.index()
                 )),
             })?;
-            let micros = duration.whole_microseconds();
+            let micros = rounding.duration_micros(duration);
             i64::try_from(micros).map_err(|_| Error::InvalidMetadata {
                 details: Cow::Owned(format!(
                     "column '{}' contains time outside Parquet range",
@@ -409,7 +649,7 @@ impl<'rows> ColumnarBatch<'rows> {
 
             def_levels.push(1);
             non_null_count = non_null_count.saturating_add(1);
-            let bytes_ref = bytes.as_ref();
+            let bytes_ref: &[u8] = bytes.as_ref();
 
             if dictionary_enabled {
                 if non_null_count <= high_card_sample {
@@ -519,6 +759,51 @@ impl ColumnarColumn<'_, '_> {
         self.column.raw_width
     }
 
+    /// Decodes a single cell as the row iterator would.
+    #[must_use]
+    pub fn decode_cell(&self, row_index: usize) -> Option<CellValue<'_>> {
+        self.raw_cell(row_index).map(|slice| {
+            decode_value_inner(
+                self.column.kind,
+                self.column.raw_width,
+                slice,
+                self.encoding,
+                self.endianness,
+            )
+        })
+    }
+
+    /// Returns `true` when the cell at `row_index` holds a value, testing
+    /// only its raw bytes.
+    #[must_use]
+    pub fn is_valid(&self, row_index: usize) -> bool {
+        self.raw_cell(row_index)
+            .is_some_and(|slice| match self.column.kind {
+                ColumnKind::Character => !is_blank(slice),
+                ColumnKind::Bytes => true,
+                ColumnKind::Numeric(_) => {
+                    !numeric_bits_is_missing(numeric_bits(slice, self.endianness))
+                }
+            })
+    }
+
+    /// Computes the validity of every cell into a fresh [`ValidityBitmap`].
+    #[must_use]
+    pub fn validity_bitmap(&self) -> ValidityBitmap {
+        let mut bitmap = ValidityBitmap::new();
+        self.validity_into(&mut bitmap);
+        bitmap
+    }
+
+    /// Computes the validity of every cell into `bitmap`, replacing its
+    /// contents.
+    pub fn validity_into(&self, bitmap: &mut ValidityBitmap) {
+        bitmap.reset(self.rows.len());
+        for row_index in 0..self.rows.len() {
+            bitmap.push(self.is_valid(row_index));
+        }
+    }
+
     pub fn iter_strings(&self) -> impl Iterator<Item = Option<Cow<'_, str>>> {
         self.iter_strings_range(0, self.rows.len())
     }
@@ -539,7 +824,11 @@ impl ColumnarColumn<'_, '_> {
         range.map(move |idx| {
             self.row_slice(idx).and_then(|row| {
                 self.column_slice(row).and_then(|slice| {
-                    if is_blank(slice) {
+                    if let ColumnKind::Numeric(_) = self.column.kind {
+                        let bits = numeric_bits(slice, self.endianness);
+                        (!numeric_bits_is_missing(bits))
+                            .then(|| Cow::Owned(numeric_text(f64::from_bits(bits))))
+                    } else if is_blank(slice) {
                         None
                     } else {
                         Some(decode_string(slice, self.encoding))
@@ -549,6 +838,67 @@ impl ColumnarColumn<'_, '_> {
         })
     }
 
+    /// Decodes every cell into a fresh [`Utf8Arena`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Limit`] when the decoded text does not fit `i32`
+    /// offsets.
+    pub fn utf8_arena(&self) -> Result<Utf8Arena> {
+        let mut arena = Utf8Arena::new();
+        self.decode_utf8_into(&mut arena)?;
+        Ok(arena)
+    }
+
+    /// Decodes every cell into `arena`, replacing its contents. Values match
+    /// [`ColumnarColumn::iter_strings`], but text is copied into one shared
+    /// buffer instead of being handed out cell by cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Limit`] when the decoded text does not fit `i32`
+    /// offsets.
+    pub fn decode_utf8_into(&self, arena: &mut Utf8Arena) -> Result<()> {
+        arena.reset(self.rows.len());
+        let mut spill = std::mem::take(&mut arena.spill);
+        let result = (0..self.rows.len())
+            .try_for_each(|row_index| arena.push(self.str_with(row_index, &mut spill)));
+        arena.spill = spill;
+        result
+    }
+
+    /// Decodes one cell like [`ColumnarColumn::iter_strings`], writing text
+    /// that needs transcoding into `spill` instead of a fresh allocation.
+    /// Blank, missing and out-of-range cells return `None`.
+    #[must_use]
+    pub fn str_with<'a>(&'a self, row_index: usize, spill: &'a mut String) -> Option<&'a str> {
+        let slice = self.raw_cell(row_index)?;
+        if let ColumnKind::Numeric(_) = self.column.kind {
+            let bits = numeric_bits(slice, self.endianness);
+            if numeric_bits_is_missing(bits) {
+                return None;
+            }
+            spill.clear();
+            write_numeric_text(f64::from_bits(bits), spill);
+            return Some(spill);
+        }
+        if is_blank(slice) {
+            return None;
+        }
+        Some(decode_string_with(slice, self.encoding, spill))
+    }
+
+    /// Iterates over the raw cell storage, as used for [`ColumnKind::Bytes`]
+    /// columns.
+    pub fn iter_bytes_range(
+        &self,
+        start: usize,
+        len: usize,
+    ) -> impl Iterator<Item = Option<&[u8]>> + '_ {
+        let end = start.saturating_add(len).min(self.rows.len());
+        (start..end).map(move |idx| self.raw_cell(idx))
+    }
+
     pub fn iter_numeric_bits(&self) -> impl Iterator<Item = Option<u64>> + '_ {
         self.iter_numeric_bits_range(0, self.rows.len())
     }
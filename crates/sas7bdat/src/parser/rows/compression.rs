@@ -1,3 +1,9 @@
+use crate::{
+    dataset::Compression,
+    parser::format_spec::{SAS_COMPRESSION_SIGNATURE_RDC, SAS_COMPRESSION_SIGNATURE_RLE},
+};
+use std::sync::{Arc, PoisonError, RwLock};
+
 const RLE_COMMAND_LENGTHS: [usize; 16] = [1, 1, 0, 0, 2, 1, 1, 1, 0, 0, 0, 0, 1, 0, 0, 0];
 
 struct RleOp {
@@ -238,3 +244,92 @@ pub fn decompress_rdc(
     }
     Ok(())
 }
+
+/// Decompresses the rows of datasets tagged with a given compression
+/// signature.
+///
+/// `SASYZCRL` and `SASYZCR2` are built in; register others with
+/// [`register_page_decompressor`] before opening a dataset that uses them.
+pub trait PageDecompressor: Send + Sync {
+    /// The tag stored in the column text subheader, e.g. `SASYZCRL`.
+    fn signature(&self) -> &str;
+
+    /// Decompresses one row into `output`, which must hold exactly
+    /// `expected_len` bytes afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns a short description of the malformed input; it is reported
+    /// as corruption of the page being read.
+    fn decompress(
+        &self,
+        input: &[u8],
+        expected_len: usize,
+        output: &mut Vec<u8>,
+    ) -> std::result::Result<(), &'static str>;
+}
+
+struct Rle;
+
+impl PageDecompressor for Rle {
+    fn signature(&self) -> &str {
+        SAS_COMPRESSION_SIGNATURE_RLE
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        expected_len: usize,
+        output: &mut Vec<u8>,
+    ) -> std::result::Result<(), &'static str> {
+        decompress_rle(input, expected_len, output)
+    }
+}
+
+struct Rdc;
+
+impl PageDecompressor for Rdc {
+    fn signature(&self) -> &str {
+        SAS_COMPRESSION_SIGNATURE_RDC
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        expected_len: usize,
+        output: &mut Vec<u8>,
+    ) -> std::result::Result<(), &'static str> {
+        decompress_rdc(input, expected_len, output)
+    }
+}
+
+static DECOMPRESSORS: RwLock<Vec<Arc<dyn PageDecompressor>>> = RwLock::new(Vec::new());
+
+/// Registers a decompressor for datasets whose compression tag matches its
+/// [`PageDecompressor::signature`].
+///
+/// Replaces any earlier registration for the same tag. The built-in RLE and
+/// RDC decoders cannot be replaced.
+pub fn register_page_decompressor(decompressor: Arc<dyn PageDecompressor>) {
+    let mut registered = DECOMPRESSORS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    registered.retain(|existing| existing.signature() != decompressor.signature());
+    registered.push(decompressor);
+}
+
+/// Looks up the decompressor for `compression`; `None` for uncompressed
+/// datasets and tags nothing has been registered for.
+pub fn page_decompressor(compression: Compression) -> Option<Arc<dyn PageDecompressor>> {
+    match compression {
+        Compression::None | Compression::Unknown(_) => None,
+        Compression::Row => Some(Arc::new(Rle)),
+        Compression::Binary => Some(Arc::new(Rdc)),
+        Compression::Other(signature) => DECOMPRESSORS
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|decompressor| decompressor.signature() == signature.as_str())
+            .cloned(),
+    }
+}
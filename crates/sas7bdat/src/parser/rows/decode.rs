@@ -5,15 +5,17 @@ use crate::{
         core::{encoding::trim_trailing, float_utils::try_int_from_f64},
         metadata::{ColumnKind, NumericKind},
     },
+    time_conv::{sas_days_to_date, sas_seconds_to_datetime, sas_seconds_to_time},
 };
-use encoding_rs::{Encoding, UTF_8};
+use encoding_rs::{CoderResult, DecoderResult, Encoding, UTF_8};
 use simdutf8::basic;
 use std::{
     borrow::Cow,
     convert::{TryFrom, TryInto},
+    fmt::Write,
     mem::size_of,
 };
-use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time};
+use time::{Duration, OffsetDateTime};
 
 #[derive(Clone)]
 pub enum NumericCell {
@@ -30,11 +32,12 @@ pub fn decode_value_inner<'data>(
 ) -> CellValue<'data> {
     match kind {
         ColumnKind::Character => CellValue::Str(decode_string(slice, encoding)),
+        ColumnKind::Bytes => CellValue::Bytes(Cow::Borrowed(slice)),
         ColumnKind::Numeric(numeric_kind) => match decode_numeric_cell(slice, endianness) {
             NumericCell::Missing(missing) => CellValue::Missing(missing),
             NumericCell::Number(number) => match numeric_kind {
                 NumericKind::Double => numeric_value_from_width(number, raw_width),
-                NumericKind::Date => sas_days_to_datetime(number).map_or_else(
+                NumericKind::Date => sas_days_to_date(number).map_or_else(
                     || numeric_value_from_width(number, raw_width),
                     CellValue::Date,
                 ),
@@ -46,6 +49,8 @@ pub fn decode_value_inner<'data>(
                     || numeric_value_from_width(number, raw_width),
                     CellValue::Time,
                 ),
+                NumericKind::Float => CellValue::Float(number),
+                NumericKind::Text => CellValue::NumericString(Cow::Owned(numeric_text(number))),
             },
         },
     }
@@ -82,33 +87,169 @@ pub fn decode_string<'a>(slice: &'a [u8], encoding: &'static Encoding) -> Cow<'a
     maybe_fix_mojibake(Cow::Owned(owned))
 }
 
+/// Returns `true` when the trimmed bytes are valid UTF-8 or decode without
+/// replacement characters in `encoding`, mirroring the fallbacks of
+/// [`decode_string`].
+pub fn decodes_cleanly(slice: &[u8], encoding: &'static Encoding) -> bool {
+    let trimmed = trim_trailing(slice);
+    if trimmed.is_empty() || basic::from_utf8(trimmed).is_ok() {
+        return true;
+    }
+    if encoding == UTF_8 {
+        return false;
+    }
+    // Decode through a stack buffer so wide cells are checked without
+    // allocating the decoded text.
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut buffer = [0u8; 1024];
+    let mut input = trimmed;
+    loop {
+        let (result, read, _) =
+            decoder.decode_to_utf8_without_replacement(input, &mut buffer, true);
+        match result {
+            DecoderResult::InputEmpty => return true,
+            DecoderResult::Malformed(..) => return false,
+            DecoderResult::OutputFull => input = &input[read..],
+        }
+    }
+}
+
+/// Decodes `slice` like [`decode_string`], but writes transcoded or repaired
+/// text into `spill` instead of a fresh allocation. UTF-8 text is returned
+/// borrowed from `slice`, so a reused `spill` decodes cells of any width
+/// without allocating once it has grown.
+pub fn decode_string_with<'a>(
+    slice: &'a [u8],
+    encoding: &'static Encoding,
+    spill: &'a mut String,
+) -> &'a str {
+    let trimmed = trim_trailing(slice);
+    if trimmed.is_empty() {
+        return "";
+    }
+    spill.clear();
+
+    if let Ok(text) = basic::from_utf8(trimmed) {
+        if !is_mojibake(text) {
+            return text;
+        }
+        spill.push_str(text);
+    } else if encoding == UTF_8 {
+        spill.push_str(&String::from_utf8_lossy(trimmed));
+    } else {
+        let mut decoder = encoding.new_decoder_without_bom_handling();
+        spill.reserve(
+            decoder
+                .max_utf8_buffer_length(trimmed.len())
+                .unwrap_or(trimmed.len()),
+        );
+        let mut input = trimmed;
+        let mut had_errors = false;
+        loop {
+            let (result, read, errors) = decoder.decode_to_string(input, spill, true);
+            had_errors |= errors;
+            input = &input[read..];
+            match result {
+                CoderResult::InputEmpty => break,
+                CoderResult::OutputFull => spill.reserve(input.len().max(4) * 3),
+            }
+        }
+        if had_errors && spill.is_empty() {
+            spill.push_str(&String::from_utf8_lossy(trimmed));
+        }
+    }
+
+    let trimmed_len = spill.trim_end_matches([' ', '\u{0000}']).len();
+    spill.truncate(trimmed_len);
+    if is_mojibake(spill) {
+        let repaired = latin1_bytes(spill);
+        spill.clear();
+        spill.push_str(std::str::from_utf8(&repaired).expect("checked by is_mojibake"));
+    }
+    spill
+}
+
+/// Decodes text that [`decodes_cleanly`] rejected, writing `replacement` for
+/// every malformed sequence instead of U+FFFD. Returns the text with trailing
+/// blanks removed and the number of sequences replaced.
+pub fn decode_string_replacing(
+    slice: &[u8],
+    encoding: &'static Encoding,
+    replacement: char,
+) -> (String, u64) {
+    let trimmed = trim_trailing(slice);
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut out = String::with_capacity(
+        decoder
+            .max_utf8_buffer_length_without_replacement(trimmed.len())
+            .unwrap_or(trimmed.len()),
+    );
+    let mut input = trimmed;
+    let mut replaced = 0u64;
+    loop {
+        let (result, read) = decoder.decode_to_string_without_replacement(input, &mut out, true);
+        input = &input[read..];
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::Malformed(..) => {
+                out.push(replacement);
+                replaced += 1;
+            }
+            DecoderResult::OutputFull => out.reserve(input.len().max(4) * 3),
+        }
+    }
+    let trimmed_len = out.trim_end_matches([' ', '\u{0000}']).len();
+    out.truncate(trimmed_len);
+    (out, replaced)
+}
+
 fn maybe_fix_mojibake(value: Cow<'_, str>) -> Cow<'_, str> {
-    let text = value.as_ref();
-    if text.is_ascii() {
+    if !is_mojibake(&value) {
         return value;
     }
+    let repaired = latin1_bytes(&value);
+    Cow::Owned(String::from_utf8(repaired).expect("checked by is_mojibake"))
+}
 
-    let mut bytes = Vec::with_capacity(text.len());
+/// Returns `true` when `text` is UTF-8 that was decoded as Latin-1: every
+/// character fits one byte, some are above ASCII, and those bytes form valid
+/// UTF-8. Checked a sequence at a time, so no copy of the text is made.
+fn is_mojibake(text: &str) -> bool {
+    if text.is_ascii() {
+        return false;
+    }
+    let mut bytes = text.chars().map(|ch| u8::try_from(u32::from(ch)));
     let mut has_extended = false;
-
-    for ch in text.chars() {
-        let code = ch as u32;
-        if code > 0xFF {
-            return value;
+    while let Some(lead) = bytes.next() {
+        let Ok(lead) = lead else { return false };
+        let width = match lead {
+            0x00..=0x7F => continue,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return false,
+        };
+        let mut sequence = [lead, 0, 0, 0];
+        for slot in &mut sequence[1..width] {
+            match bytes.next() {
+                Some(Ok(byte)) => *slot = byte,
+                _ => return false,
+            }
         }
-        if code >= 0x80 {
-            has_extended = true;
+        if std::str::from_utf8(&sequence[..width]).is_err() {
+            return false;
         }
-        bytes.push(u8::try_from(code).expect("code <= 0xFF enforced above"));
+        has_extended = true;
     }
+    has_extended
+}
 
-    if has_extended
-        && let Ok(decoded) = std::str::from_utf8(&bytes)
-        && decoded != text
-    {
-        return Cow::Owned(decoded.to_owned());
-    }
-    value
+/// Narrows every character of `text` to its Latin-1 byte; callers check
+/// [`is_mojibake`] first, so each character fits.
+fn latin1_bytes(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|ch| u8::try_from(u32::from(ch)).unwrap_or(b'?'))
+        .collect()
 }
 
 pub fn decode_numeric_cell(slice: &[u8], endian: Endianness) -> NumericCell {
@@ -161,6 +302,17 @@ pub const fn numeric_bits_is_missing(raw: u64) -> bool {
     (raw & EXP_MASK) == EXP_MASK && (raw & FRACTION_MASK) != 0
 }
 
+/// Returns `true` for NaN patterns SAS writes for `.`, `._` and `.A`-`.Z`:
+/// zeroed low bytes and an inverted tag in bits 40-47, stored either as the
+/// tag's ASCII code or as the compact `0..=27` index.
+#[inline]
+pub const fn is_canonical_missing_bits(raw: u64) -> bool {
+    const LOW_MASK: u64 = 0x00FF_FFFF_FFFF;
+    let tag_byte = !(((raw >> 40) & 0xFF) as u8);
+    let known_tag = matches!(tag_byte, 0..=27 | b'.' | b'_' | b'A'..=b'Z');
+    numeric_bits_is_missing(raw) && known_tag && raw & LOW_MASK == 0
+}
+
 const fn decode_missing_from_bits(raw: u64) -> MissingValue {
     let upper = (raw >> 40) & 0xFF;
     let tag_byte = !(upper as u8);
@@ -194,6 +346,24 @@ fn numeric_value_from_width<'a>(number: f64, width: u32) -> CellValue<'a> {
     CellValue::Float(number)
 }
 
+/// Plain decimal text of `number`: integers without a fractional part and
+/// other values in their shortest round-trip form, never in exponent
+/// notation.
+pub fn numeric_text(number: f64) -> String {
+    let mut text = String::new();
+    write_numeric_text(number, &mut text);
+    text
+}
+
+/// Appends [`numeric_text`] of `number` to `out`.
+pub fn write_numeric_text(number: f64, out: &mut String) {
+    // Writing into a `String` cannot fail.
+    let _ = match try_int_from_f64::<i64>(number) {
+        Some(int) => write!(out, "{int}"),
+        None => write!(out, "{number}"),
+    };
+}
+
 const fn repeat_byte_usize(byte: u8) -> usize {
     let mut value = 0usize;
     let mut i = 0usize;
@@ -270,40 +440,45 @@ pub fn trim_trailing_space_or_nul_simd(slice: &[u8]) -> &[u8] {
     &slice[..end]
 }
 
-fn sas_epoch() -> PrimitiveDateTime {
-    PrimitiveDateTime::new(
-        Date::from_calendar_date(1960, Month::January, 1).expect("valid SAS epoch"),
-        Time::MIDNIGHT,
-    )
+/// Rounding applied when sub-microsecond precision is dropped from decoded
+/// timestamps and times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampRounding {
+    /// Discards the sub-microsecond remainder, rounding toward negative
+    /// infinity.
+    #[default]
+    Truncate,
+    /// Rounds to the nearest microsecond, with ties going to the even value.
+    HalfEven,
 }
 
-fn sas_offset_datetime(seconds: f64) -> Option<OffsetDateTime> {
-    if !seconds.is_finite() {
-        return None;
-    }
-    let duration = Duration::seconds_f64(seconds.abs());
-    if seconds >= 0.0 {
-        sas_epoch()
-            .checked_add(duration)
-            .map(PrimitiveDateTime::assume_utc)
-    } else {
-        sas_epoch()
-            .checked_sub(duration)
-            .map(PrimitiveDateTime::assume_utc)
+impl TimestampRounding {
+    /// Converts nanoseconds to whole microseconds using this rounding mode.
+    #[must_use]
+    pub const fn nanos_to_micros(self, nanos: i128) -> i128 {
+        let micros = nanos.div_euclid(1_000);
+        match self {
+            Self::Truncate => micros,
+            Self::HalfEven => {
+                let remainder = nanos.rem_euclid(1_000);
+                if remainder > 500 || (remainder == 500 && micros % 2 != 0) {
+                    micros + 1
+                } else {
+                    micros
+                }
+            }
+        }
     }
-}
 
-pub fn sas_days_to_datetime(days: f64) -> Option<OffsetDateTime> {
-    sas_offset_datetime(days * 86_400.0)
-}
-
-pub fn sas_seconds_to_datetime(seconds: f64) -> Option<OffsetDateTime> {
-    sas_offset_datetime(seconds)
-}
+    /// Microseconds since the Unix epoch for `datetime`.
+    #[must_use]
+    pub const fn datetime_micros(self, datetime: OffsetDateTime) -> i128 {
+        self.nanos_to_micros(datetime.unix_timestamp_nanos())
+    }
 
-pub const fn sas_seconds_to_time(seconds: f64) -> Option<Duration> {
-    if !seconds.is_finite() {
-        return None;
+    /// Whole microseconds in `duration`.
+    #[must_use]
+    pub const fn duration_micros(self, duration: Duration) -> i128 {
+        self.nanos_to_micros(duration.whole_nanoseconds())
     }
-    Some(Duration::seconds_f64(seconds))
 }
@@ -1,22 +1,37 @@
 use super::{
-    batch::{next_columnar_batch, next_columnar_batch_contiguous},
-    buffer::RowData,
+    batch::{
+        BatchSizing, DecodeLayout, next_batch_with_layout, next_columnar_batch,
+        next_columnar_batch_contiguous, next_columnar_batch_projected,
+    },
+    buffer::{PageBuffer, RowData},
+    compression::{PageDecompressor, page_decompressor},
+    decode::TimestampRounding,
+    labels::column_labels,
+    page::TruncatedRow,
     runtime_column::{RuntimeColumn, RuntimeColumnRef},
     streaming::StreamingRow,
 };
 use crate::{
     cell::CellValue,
-    dataset::Compression,
+    dataset::{Compression, LabelLookup},
     error::{Error, Result, Section},
-    parser::{core::encoding::resolve_encoding, metadata::DatasetLayout},
+    parser::{
+        core::{
+            encoding::resolve_encoding,
+            platform::{saturating_usize, usize_from},
+        },
+        metadata::DatasetLayout,
+    },
+    telemetry::PageMetrics,
 };
+use bytes::Bytes;
 use encoding_rs::Encoding;
 use std::{
     borrow::Cow,
     cell::Cell,
-    convert::TryFrom,
     io::{Read, Seek},
     ops::Deref,
+    sync::Arc,
 };
 
 #[derive(Clone, Copy)]
@@ -35,12 +50,22 @@ where
     pub(crate) layout: L,
     pub(crate) runtime_columns: Vec<RuntimeColumn>,
     pub(crate) columnar_columns: Vec<RuntimeColumnRef>,
-    pub(crate) page_buffer: Vec<u8>,
+    /// Columns of the last projected batch, in projection order.
+    pub(crate) projected_columns: Vec<RuntimeColumnRef>,
+    /// Source columns materialised rows hold, in order; `None` for all.
+    projection: Option<Box<[usize]>>,
+    /// Label lookups per column; empty unless value labels are applied.
+    value_labels: Vec<Option<LabelLookup>>,
+    pub(crate) page_buffer: PageBuffer,
+    /// The whole file when it is held in memory; pages are sliced out of it.
+    pub(crate) source: Option<Bytes>,
     pub(crate) current_rows: Vec<RowData>,
     pub(crate) contiguous_base: Option<usize>,
     pub(crate) contiguous_rows: u16,
     pub(crate) reusable_row_buffers: Vec<Vec<u8>>,
     pub(crate) reusable_row_buffer: Vec<u8>,
+    /// Start of a row cut off at the end of the last page read.
+    pub(crate) truncated_row: Option<TruncatedRow>,
     pub(crate) columnar_owned_buffer: Vec<u8>,
     pub(crate) page_row_count: Cell<u16>,
     pub(crate) row_in_page: Cell<u16>,
@@ -50,6 +75,11 @@ where
     pub(crate) exhausted: Cell<bool>,
     pub(crate) row_length: usize,
     pub(crate) total_rows: u64,
+    pub(crate) batch_sizing: BatchSizing,
+    pub(crate) timestamp_rounding: TimestampRounding,
+    pub(crate) metrics: PageMetrics,
+    /// Decodes compressed rows; `None` for uncompressed datasets.
+    pub(crate) decompressor: Option<Arc<dyn PageDecompressor>>,
 }
 
 pub type RowIterator<'a, R> = RowIteratorCore<&'a mut R, &'a DatasetLayout>;
@@ -80,8 +110,8 @@ where
     /// Returns an error when the dataset uses an unsupported compression mode
     /// or the page size cannot be represented on this platform.
     pub fn new(reader: R, layout: L) -> Result<Self> {
+        let decompressor = page_decompressor(layout.row_info.compression);
         match layout.row_info.compression {
-            Compression::None | Compression::Row | Compression::Binary => {}
             Compression::Unknown(code) => {
                 return Err(Error::Unsupported {
                     feature: Cow::from(format!(
@@ -89,17 +119,20 @@ where
                     )),
                 });
             }
+            Compression::Other(signature) if decompressor.is_none() => {
+                return Err(Error::Unsupported {
+                    feature: Cow::from(format!(
+                        "row iteration for {signature} compression without a registered \
+                         PageDecompressor"
+                    )),
+                });
+            }
+            Compression::None | Compression::Row | Compression::Binary | Compression::Other(_) => {}
         }
 
         let encoding = resolve_encoding(layout.header.metadata.file_encoding.as_deref());
-        let page_size =
-            usize::try_from(layout.header.page_size).map_err(|_| Error::Unsupported {
-                feature: Cow::from("page size exceeds platform pointer width"),
-            })?;
-        let row_length =
-            usize::try_from(layout.row_info.row_length).map_err(|_| Error::Unsupported {
-                feature: Cow::from("row length exceeds platform pointer width"),
-            })?;
+        let page_size = usize_from(layout.header.page_size, "page size")?;
+        let row_length = usize_from(layout.row_info.row_length, "row length")?;
         if layout.columns.is_empty() || row_length == 0 {
             return Err(Error::InvalidMetadata {
                 details: Cow::from("dataset defines zero columns or row length is zero"),
@@ -109,14 +142,8 @@ where
             .columns
             .iter()
             .map(|column| {
-                let offset =
-                    usize::try_from(column.offsets.offset).map_err(|_| Error::Unsupported {
-                        feature: Cow::from("column offset exceeds platform pointer width"),
-                    })?;
-                let width =
-                    usize::try_from(column.offsets.width).map_err(|_| Error::Unsupported {
-                        feature: Cow::from("column width exceeds platform pointer width"),
-                    })?;
+                let offset = saturating_usize(column.offsets.offset);
+                let width = usize_from(column.offsets.width, "column width")?;
                 let end = offset.saturating_add(width);
                 Ok(RuntimeColumn {
                     index: column.index,
@@ -132,18 +159,34 @@ where
         let columnar_columns: Vec<RuntimeColumnRef> =
             runtime_columns.iter().map(RuntimeColumn::as_ref).collect();
 
-        let total_rows = layout.row_info.total_rows;
+        let total_rows = if layout.trusts_row_count() {
+            layout.row_info.total_rows
+        } else {
+            u64::MAX
+        };
+        let metrics = PageMetrics::new(layout.header.metadata.table_name.as_deref());
+        let source = layout.source.clone();
+        let page_buffer = PageBuffer::from(if source.is_some() {
+            Vec::new()
+        } else {
+            vec![0u8; page_size]
+        });
         Ok(Self {
             reader,
             layout,
             runtime_columns,
             columnar_columns,
-            page_buffer: vec![0u8; page_size],
+            projected_columns: Vec::new(),
+            projection: None,
+            value_labels: Vec::new(),
+            page_buffer,
+            source,
             current_rows: Vec::new(),
             contiguous_base: None,
             contiguous_rows: 0,
             reusable_row_buffers: Vec::new(),
             reusable_row_buffer: Vec::new(),
+            truncated_row: None,
             columnar_owned_buffer: Vec::new(),
             page_row_count: Cell::new(0),
             row_in_page: Cell::new(0),
@@ -153,9 +196,80 @@ where
             exhausted: Cell::new(false),
             row_length,
             total_rows,
+            batch_sizing: BatchSizing::default(),
+            timestamp_rounding: TimestampRounding::default(),
+            metrics,
+            decompressor,
         })
     }
 
+    /// Makes materialised rows carry catalog labels in place of coded values
+    /// when the layout asks for it.
+    #[must_use]
+    pub(crate) fn with_value_labels(mut self) -> Self {
+        if self.layout.apply_value_labels {
+            self.value_labels = column_labels(&self.layout.header.metadata);
+        }
+        self
+    }
+
+    /// Makes materialised rows hold only the columns at `indices`, in that
+    /// order. Unselected cells are never decoded; zero-copy row views still
+    /// cover every column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an index is out of range.
+    pub fn with_projection(mut self, indices: &[usize]) -> Result<Self> {
+        self.check_projection(indices)?;
+        self.projection = Some(indices.into());
+        Ok(self)
+    }
+
+    fn check_projection(&self, indices: &[usize]) -> Result<()> {
+        let column_count = self.runtime_columns.len();
+        indices
+            .iter()
+            .find(|&&index| index >= column_count)
+            .map_or(Ok(()), |index| {
+                Err(Error::InvalidArgument {
+                    details: Cow::Owned(format!(
+                        "column projection index {index} exceeds column count {column_count}"
+                    )),
+                })
+            })
+    }
+
+    /// Sets how many rows columnar batches hold when no explicit row count is
+    /// requested.
+    #[must_use]
+    pub const fn with_batch_sizing(mut self, sizing: BatchSizing) -> Self {
+        self.batch_sizing = sizing;
+        self
+    }
+
+    pub const fn set_batch_sizing(&mut self, sizing: BatchSizing) {
+        self.batch_sizing = sizing;
+    }
+
+    /// Sets the rounding columnar batches apply when they materialise
+    /// timestamp and time columns as microseconds.
+    #[must_use]
+    pub const fn with_timestamp_rounding(mut self, rounding: TimestampRounding) -> Self {
+        self.timestamp_rounding = rounding;
+        self
+    }
+
+    pub const fn set_timestamp_rounding(&mut self, rounding: TimestampRounding) {
+        self.timestamp_rounding = rounding;
+    }
+
+    /// Returns the row count of a batch requested with `max_rows == 0`.
+    #[must_use]
+    pub const fn batch_rows(&self) -> usize {
+        self.batch_sizing.rows_for(self.row_length)
+    }
+
     #[inline]
     pub(crate) fn ensure_page_ready(&mut self) -> Result<bool> {
         if self.row_in_page.get() >= self.page_row_count.get() {
@@ -164,13 +278,30 @@ where
                 return Err(err);
             }
             if self.page_row_count.get() == 0 {
-                self.exhausted.set(true);
+                if !self.exhausted.replace(true) {
+                    self.check_row_count();
+                }
                 return Ok(false);
             }
         }
         Ok(true)
     }
 
+    /// Warns when the data pages ran out at a different row than the header
+    /// row count.
+    fn check_row_count(&self) {
+        let stored = self.layout.row_info.total_rows;
+        let read = self.emitted_rows.get();
+        if read != stored {
+            self.layout.issues.note(
+                &self.layout.logger,
+                None,
+                None,
+                format!("header row count is {stored} but the data pages hold {read} rows"),
+            );
+        }
+    }
+
     #[inline]
     pub(crate) fn revert_row_progress(&self, prev_row_in_page: u16, prev_emitted: u64) {
         self.row_in_page.set(prev_row_in_page);
@@ -246,6 +377,65 @@ where
         }
     }
 
+    /// Advances to the next row accepted by `accept` and decodes it. Rows
+    /// are tested on their zero-copy view, so rejected rows are never
+    /// materialised.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row decoding fails or `accept` reports one.
+    pub(crate) fn try_next_matching<F>(
+        &mut self,
+        accept: &mut F,
+    ) -> Result<Option<Vec<CellValue<'_>>>>
+    where
+        F: FnMut(&StreamingRow<'_, '_>) -> Result<bool>,
+    {
+        let Some(progress) = self.reserve_matching_row(accept)? else {
+            return Ok(None);
+        };
+        match self.decode_row(progress.row_index) {
+            Ok(row) => Ok(Some(row)),
+            Err(err) => {
+                self.revert_row_progress(progress.prev_row_in_page, progress.prev_emitted);
+                Err(err)
+            }
+        }
+    }
+
+    /// Advances past the next row accepted by `accept` without decoding it,
+    /// returning `false` once the rows are exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the row fails or `accept` reports one.
+    pub(crate) fn skip_matching<F>(&mut self, accept: &mut F) -> Result<bool>
+    where
+        F: FnMut(&StreamingRow<'_, '_>) -> Result<bool>,
+    {
+        Ok(self.reserve_matching_row(accept)?.is_some())
+    }
+
+    fn reserve_matching_row<F>(&mut self, accept: &mut F) -> Result<Option<RowProgress>>
+    where
+        F: FnMut(&StreamingRow<'_, '_>) -> Result<bool>,
+    {
+        while let Some(progress) = self.reserve_next_row()? {
+            let accepted = self
+                .streaming_row(progress.row_index)
+                .and_then(|row| accept(&row));
+            match accepted {
+                Ok(true) => return Ok(Some(progress)),
+                Ok(false) => {}
+                Err(err) => {
+                    self.revert_row_progress(progress.prev_row_in_page, progress.prev_emitted);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(None)
+    }
+
     /// Advances the iterator and invokes the visitor with a zero-copy row view.
     ///
     /// Returns `Ok(None)` when no more rows remain or `Ok(Some(()))` when a row
@@ -292,7 +482,8 @@ where
         Ok(())
     }
 
-    /// Decodes the next chunk of rows into a column-oriented batch.
+    /// Decodes the next chunk of rows into a column-oriented batch. A
+    /// `max_rows` of zero uses the configured [`BatchSizing`].
     ///
     /// # Errors
     ///
@@ -304,6 +495,26 @@ where
         next_columnar_batch(self, max_rows)
     }
 
+    /// Decodes the next chunk of rows into a column-oriented batch holding
+    /// only the columns at `indices`, so batch column `i` is source column
+    /// `indices[i]`. Rows are borrowed as in [`Self::next_columnar_batch`]
+    /// and unselected columns are never decoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an index is out of range or decoding fails.
+    pub fn next_columnar_batch_projected(
+        &mut self,
+        indices: &[usize],
+        max_rows: usize,
+    ) -> Result<Option<super::ColumnarBatch<'_>>> {
+        self.check_projection(indices)?;
+        self.projected_columns.clear();
+        self.projected_columns
+            .extend(indices.iter().map(|&index| self.columnar_columns[index]));
+        next_columnar_batch_projected(self, max_rows)
+    }
+
     /// Decodes the next chunk of rows into a column-oriented batch stored contiguously.
     ///
     /// # Errors
@@ -316,6 +527,23 @@ where
         next_columnar_batch_contiguous(self, max_rows)
     }
 
+    /// Decodes the next chunk of rows using the requested [`DecodeLayout`].
+    ///
+    /// `projected_columns` is the number of columns the caller intends to read
+    /// and only influences [`DecodeLayout::Auto`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when decoding fails.
+    pub fn next_batch_with_layout(
+        &mut self,
+        layout: DecodeLayout,
+        projected_columns: usize,
+        max_rows: usize,
+    ) -> Result<Option<super::ColumnarBatch<'_>>> {
+        next_batch_with_layout(self, layout, projected_columns, max_rows)
+    }
+
     pub(crate) fn streaming_row(&self, row_index: u16) -> Result<StreamingRow<'_, '_>> {
         let data = self.row_slice(row_index)?;
 
@@ -329,7 +557,24 @@ where
 
     pub(crate) fn decode_row(&self, row_index: u16) -> Result<Vec<CellValue<'_>>> {
         let row = self.streaming_row(row_index)?;
-        row.materialize()
+        let mut values = match &self.projection {
+            Some(indices) => row.materialize_projected(indices)?,
+            None => row.materialize()?,
+        };
+        if self.value_labels.is_empty() {
+            return Ok(values);
+        }
+        for (position, value) in values.iter_mut().enumerate() {
+            let column = self
+                .projection
+                .as_ref()
+                .map_or(position, |indices| indices[position]);
+            let labels = self.value_labels.get(column).and_then(Option::as_ref);
+            if let Some(label) = labels.and_then(|labels| labels.label(value)) {
+                *value = CellValue::Str(Cow::Borrowed(label));
+            }
+        }
+        Ok(values)
     }
 
     pub(crate) fn row_slice(&self, row_index: u16) -> Result<&[u8]> {
@@ -0,0 +1,22 @@
+use crate::dataset::{DatasetMetadata, LabelLookup, LabelSet};
+
+/// Builds the per-column label lookups for columns whose variable names an
+/// attached label set; an empty result means no column has labels.
+pub(super) fn column_labels(metadata: &DatasetMetadata) -> Vec<Option<LabelLookup>> {
+    let labels: Vec<Option<LabelLookup>> = metadata
+        .variables
+        .iter()
+        .map(|variable| {
+            variable
+                .value_labels
+                .as_ref()
+                .and_then(|name| metadata.label_sets.get(name))
+                .map(LabelSet::lookup)
+        })
+        .collect();
+    if labels.iter().all(Option::is_none) {
+        Vec::new()
+    } else {
+        labels
+    }
+}
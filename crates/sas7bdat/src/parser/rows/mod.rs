@@ -2,20 +2,26 @@ mod batch;
 mod buffer;
 mod columnar;
 mod compression;
-mod constants;
 mod decode;
 mod iterator;
+mod labels;
 mod page;
+mod parallel;
 mod pointer;
 mod runtime_column;
+mod seek;
 mod streaming;
 
+pub use batch::{BatchSizing, DecodeLayout};
 pub use columnar::{
     ColumnarBatch, ColumnarColumn, MaterializedUtf8Column, StagedUtf8Value, TypedNumericColumn,
+    Utf8Arena, ValidityBitmap,
 };
-#[cfg(feature = "parquet")]
-pub use decode::{sas_days_to_datetime, sas_seconds_to_datetime, sas_seconds_to_time};
+pub use compression::{PageDecompressor, register_page_decompressor};
+pub use decode::TimestampRounding;
 pub use iterator::{OwnedRowIterator, RowIterator, RowIteratorCore, row_iterator};
+pub use parallel::{ParallelRowIterator, with_parallel_rows};
+pub use pointer::{read_signature, signature_is_recognized};
 pub use runtime_column::RuntimeColumnRef;
 pub use streaming::{StreamingCell, StreamingRow};
 
@@ -1,29 +1,61 @@
 use super::{
     buffer::RowData,
-    compression::{decompress_rdc, decompress_rle},
-    constants::{
-        SAS_COMPRESSION_NONE, SAS_COMPRESSION_ROW, SAS_COMPRESSION_TRUNC, SAS_PAGE_TYPE_COMP,
-        SAS_PAGE_TYPE_DATA, SAS_PAGE_TYPE_MASK, SAS_PAGE_TYPE_MIX, SUBHEADER_POINTER_OFFSET,
-    },
     iterator::RowIteratorCore,
     pointer::{PointerInfo, parse_pointer, read_signature, signature_is_recognized},
 };
 use crate::{
-    dataset::{Compression, Vendor},
+    dataset::PageCompression,
     error::{Error, Result, Section},
-    logger::log_warn,
     parser::{
         core::byteorder::read_u16,
+        format_spec::{
+            SAS_COMPRESSION_NONE, SAS_COMPRESSION_ROW, SAS_COMPRESSION_TRUNC, SAS_PAGE_TYPE_COMP,
+            SAS_PAGE_TYPE_DATA, SAS_PAGE_TYPE_MASK, SAS_PAGE_TYPE_MIX, SUBHEADER_POINTER_OFFSET,
+        },
         metadata::{DatasetLayout, PageKind, classify_page},
+        quirks::MixPageAlignment,
     },
+    telemetry::Stopwatch,
 };
 use std::{
     borrow::Cow,
     convert::TryInto,
-    io::{Read, Seek, SeekFrom},
+    io::{self, Read, Seek, SeekFrom},
     ops::Deref,
 };
 
+/// The start of a row cut off at the end of a page by a truncated pointer,
+/// kept until the next page shows whether it holds the rest.
+pub struct TruncatedRow {
+    page_index: u64,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// What decoding a page does with a row cut off at the end of the page
+/// before it, judged from the page's pointers alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarriedRow {
+    /// The first row pointer is uncompressed and shorter than a row, so it
+    /// may hold the rest.
+    MayContinue,
+    /// The first row pointer holds a whole row: the cut-off row written
+    /// again in full.
+    Superseded,
+    /// The page holds no row pointer ahead of another cut-off row, if any.
+    Dropped,
+    /// The page is skipped, or its pointers are damaged, and the row is
+    /// left for the decoder to settle.
+    Kept,
+}
+
+impl CarriedRow {
+    /// Whether the page decodes the same with or without a cut-off row
+    /// ahead of it.
+    pub(crate) const fn is_settled(self) -> bool {
+        matches!(self, Self::Superseded | Self::Dropped)
+    }
+}
+
 struct PointerContext {
     page_index: u64,
     page_type: u16,
@@ -40,103 +72,152 @@ where
 {
     pub(crate) fn fetch_next_page(&mut self) -> Result<()> {
         let page_count = self.layout.header.page_count;
-        let row_length = self.row_length;
 
         while self.next_page_index < page_count {
-            let (page_index, page_type, page_row_count) = self.read_page_header()?;
-            if (page_type & SAS_PAGE_TYPE_COMP) != 0 {
-                continue;
+            let started = Stopwatch::start();
+            let page_index = self.read_next_page()?;
+            let count = self.decode_loaded_page(page_index)?;
+            if count > 0 {
+                self.metrics.page_decoded(count, started);
+                return Ok(());
             }
+        }
 
-            let page_kind = classify_page(page_type);
-            if matches!(
-                page_kind,
-                PageKind::Comp | PageKind::CompTable | PageKind::Unknown
-            ) {
-                continue;
-            }
-            let base_page_type = page_type & SAS_PAGE_TYPE_MASK;
-            let target_rows = if page_row_count == 0 {
-                None
-            } else {
-                Some(page_row_count as usize)
-            };
+        self.drop_truncated_row(None);
+        self.page_row_count.set(0);
+        Ok(())
+    }
 
-            self.recycle_current_rows();
+    /// Splits the page held in `page_buffer` into rows and returns how many
+    /// it holds; pages without rows return `0` and leave the current rows
+    /// untouched.
+    pub(crate) fn decode_loaded_page(&mut self, page_index: u64) -> Result<u16> {
+        let row_length = self.row_length;
+        let (page_type, page_row_count) = self.page_type_and_row_count();
+        if (page_type & SAS_PAGE_TYPE_COMP) != 0 {
+            return Ok(0);
+        }
 
-            let Some(subheader_count) = self.read_subheader_count(page_index, page_type) else {
-                continue;
-            };
+        let page_kind = classify_page(page_type);
+        if matches!(
+            page_kind,
+            PageKind::Comp | PageKind::CompTable | PageKind::Unknown
+        ) {
+            return Ok(0);
+        }
+        let base_page_type = page_type & SAS_PAGE_TYPE_MASK;
+        let target_rows = if page_row_count == 0 {
+            None
+        } else {
+            Some(page_row_count as usize)
+        };
+
+        self.recycle_current_rows();
+
+        let Some(subheader_count) = self.read_subheader_count(page_index, page_type)? else {
+            return Ok(0);
+        };
 
-            self.process_subheaders(
+        self.process_subheaders(
+            page_index,
+            page_type,
+            subheader_count,
+            target_rows,
+            row_length,
+        )?;
+        // A fragment from an earlier page is only continued by the first
+        // row of the page that follows it.
+        if self
+            .truncated_row
+            .as_ref()
+            .is_some_and(|fragment| fragment.page_index < page_index)
+        {
+            self.drop_truncated_row(Some(page_index));
+        }
+
+        if self.current_rows.is_empty() {
+            self.collect_rows_from_data_area(
                 page_index,
-                page_type,
+                base_page_type,
+                page_row_count,
                 subheader_count,
-                target_rows,
                 row_length,
             )?;
-
-            if self.current_rows.is_empty() {
-                self.collect_rows_from_data_area(
-                    page_index,
-                    base_page_type,
-                    page_row_count,
-                    subheader_count,
-                    row_length,
-                )?;
-            }
-
-            let count = if self.contiguous_base.is_some() {
-                self.contiguous_rows
-            } else {
-                self.current_rows.len().try_into().unwrap_or(u16::MAX)
-            };
-            self.page_row_count.set(count);
-            self.row_in_page.set(0);
-            if self.page_row_count.get() > 0 {
-                return Ok(());
-            }
         }
 
-        self.page_row_count.set(0);
-        Ok(())
+        let count = if self.contiguous_base.is_some() {
+            self.contiguous_rows
+        } else {
+            self.current_rows.len().try_into().unwrap_or(u16::MAX)
+        };
+        self.page_row_count.set(count);
+        self.row_in_page.set(0);
+        Ok(count)
     }
 
     fn read_page_header(&mut self) -> Result<(u64, u16, u16)> {
+        let page_index = self.read_next_page()?;
+        let (page_type, page_row_count) = self.page_type_and_row_count();
+        Ok((page_index, page_type, page_row_count))
+    }
+
+    fn read_next_page(&mut self) -> Result<u64> {
         let header = &self.layout.header;
         let offset = header.data_offset + self.next_page_index * u64::from(header.page_size);
-        self.reader
-            .seek(SeekFrom::Start(offset))
-            .map_err(Error::from)?;
-        self.reader
-            .read_exact(&mut self.page_buffer)
-            .map_err(Error::from)?;
+        if let Some(source) = &self.source {
+            let page = usize::try_from(offset)
+                .ok()
+                .zip(usize::try_from(header.page_size).ok())
+                .and_then(|(start, len)| Some(start..start.checked_add(len)?))
+                .filter(|range| range.end <= source.len())
+                .ok_or_else(|| Error::from(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+            self.page_buffer.share(source.slice(page));
+        } else {
+            self.reader
+                .seek(SeekFrom::Start(offset))
+                .map_err(Error::from)?;
+            self.reader
+                .read_exact(self.page_buffer.owned_mut())
+                .map_err(Error::from)?;
+        }
         let page_index = self.next_page_index;
         self.next_page_index += 1;
+        self.metrics.page_read();
+        Ok(page_index)
+    }
 
+    fn page_type_and_row_count(&self) -> (u16, u16) {
+        let header = &self.layout.header;
         let page_type = read_u16(
             header.endianness,
-            &self.page_buffer[(header.page_header_size as usize) - 8..],
+            &self.page_buffer[header.page_type_offset()..],
         );
         let page_row_count = read_u16(
             header.endianness,
-            &self.page_buffer[(header.page_header_size as usize) - 6..],
+            &self.page_buffer[header.block_count_offset()..],
         );
-        Ok((page_index, page_type, page_row_count))
+        (page_type, page_row_count)
     }
 
-    fn read_subheader_count(&self, page_index: u64, page_type: u16) -> Option<u16> {
+    /// Steps over corruption on `page_index` as the parse mode allows.
+    fn recover(&self, page_index: u64, message: String) -> Result<()> {
+        self.layout
+            .issues
+            .recover(&self.layout.logger, page_index, message)
+    }
+
+    fn read_subheader_count(&self, page_index: u64, page_type: u16) -> Result<Option<u16>> {
         let header = &self.layout.header;
-        let subheader_count_pos = header.page_header_size as usize - 4;
+        let subheader_count_pos = header.subheader_count_offset();
         let Some(count_bytes) = self
             .page_buffer
             .get(subheader_count_pos..subheader_count_pos + 2)
         else {
-            log_warn(&format!(
+            self.recover(page_index, format!(
                 "Skipping page {page_index} (type=0x{page_type:04X}): subheader count exceeds page bounds [page_size={}, page_header_size={}]",
                 header.page_size, header.page_header_size
-            ));
-            return None;
+            ))?;
+            return Ok(None);
         };
         let subheader_count_raw = read_u16(header.endianness, count_bytes);
         let pointer_size = header.subheader_pointer_size as usize;
@@ -151,16 +232,16 @@ where
             (subheader_count_raw, false)
         };
         if truncated {
-            log_warn(&format!(
+            self.recover(page_index, format!(
                 "Clamping subheader count on page {page_index} (type=0x{page_type:04X}) from {} to {} to fit page bounds [page_size={}, header_size={}, pointer_size={}]",
                 subheader_count_raw,
                 max_subheaders,
                 header.page_size,
                 header.page_header_size,
                 header.subheader_pointer_size
-            ));
+            ))?;
         }
-        Some(subheader_count)
+        Ok(Some(subheader_count))
     }
 
     fn process_subheaders(
@@ -198,14 +279,14 @@ where
         let header = &self.layout.header;
         let pointer_end = ptr_cursor.saturating_add(ctx.pointer_size);
         let Some(pointer) = self.page_buffer.get(*ptr_cursor..pointer_end) else {
-            log_warn(&format!(
+            self.recover(ctx.page_index, format!(
                 "Skipping page {page_index} (type=0x{page_type:04X}): subheader pointer exceeds page bounds [cursor={}, pointer_size={}, page_len={}]",
                 *ptr_cursor,
                 ctx.pointer_size,
                 self.page_buffer.len(),
                 page_index = ctx.page_index,
                 page_type = ctx.page_type
-            ));
+            ))?;
             return Ok(());
         };
         *ptr_cursor = pointer_end;
@@ -214,7 +295,7 @@ where
         let min_data_offset =
             header.page_header_size as usize + usize::from(ctx.subheader_count) * ctx.pointer_size;
         if info.offset < min_data_offset {
-            log_warn(&format!(
+            self.recover(ctx.page_index, format!(
                 "Skipping page {page_index} (type=0x{page_type:04X}): subheader pointer starts before data section [offset={}, min_offset={}, pointer_size={}, subheaders={}]",
                 info.offset,
                 min_data_offset,
@@ -222,27 +303,27 @@ where
                 ctx.subheader_count,
                 page_index = ctx.page_index,
                 page_type = ctx.page_type
-            ));
+            ))?;
             return Ok(());
         }
         if info.length == 0 {
             return Ok(());
         }
-        if info.offset + info.length > self.page_buffer.len() {
-            log_warn(&format!(
+        if info.offset.saturating_add(info.length) > self.page_buffer.len() {
+            self.recover(ctx.page_index, format!(
                 "Skipping page {page_index} (type=0x{page_type:04X}): subheader pointer references data beyond page bounds [offset={}, length={}, page_len={}]",
                 info.offset,
                 info.length,
                 self.page_buffer.len(),
                 page_index = ctx.page_index,
                 page_type = ctx.page_type
-            ));
+            ))?;
             return Ok(());
         }
         if info.compression == SAS_COMPRESSION_NONE {
             let sig_len = header.subheader_signature_size;
             if info.length < sig_len || info.offset + sig_len > self.page_buffer.len() {
-                log_warn(&format!(
+                self.recover(ctx.page_index, format!(
                     "Skipping page {page_index} (type=0x{page_type:04X}): subheader pointer too small for signature [offset={}, length={}, required={}, page_len={}]",
                     info.offset,
                     info.length,
@@ -250,7 +331,7 @@ where
                     self.page_buffer.len(),
                     page_index = ctx.page_index,
                     page_type = ctx.page_type
-                ));
+                ))?;
                 return Ok(());
             }
         }
@@ -267,7 +348,12 @@ where
             SAS_COMPRESSION_NONE => {
                 let data = &self.page_buffer[data_start..data_end];
                 let signature = read_signature(data, header.endianness, header.uses_u64);
-                if info.is_compressed_data && !signature_is_recognized(signature) {
+                if signature_is_recognized(signature) {
+                    // Metadata subheaders carry no rows.
+                } else if info.is_compressed_data {
+                    if self.continue_truncated_row(ctx, data_start, data_end) {
+                        return Ok(());
+                    }
                     let mut local_offset = info.offset;
                     let mut remaining = info.length;
                     while remaining >= ctx.row_length {
@@ -280,51 +366,56 @@ where
                             break;
                         }
                     }
+                } else {
+                    self.layout.issues.note(
+                        &self.layout.logger,
+                        Some(ctx.page_index),
+                        Some(signature),
+                        format!(
+                            "Ignoring unrecognised subheader on page {page_index} (type=0x{page_type:04X}) [offset={}, length={}]",
+                            info.offset,
+                            info.length,
+                            page_index = ctx.page_index,
+                            page_type = ctx.page_type
+                        ),
+                    );
                 }
             }
             SAS_COMPRESSION_TRUNC => {
-                // Truncated rows are continuations that reappear in the
-                // next page; skip them to avoid emitting partial data.
+                // A row cut off at the end of the page. SAS writes it again
+                // in full on the next page; keep the start in case that page
+                // holds only the rest of it, uncompressed.
+                self.stash_truncated_row(ctx.page_index, data_start, data_end);
             }
             SAS_COMPRESSION_ROW => {
                 let mut buffer = self.take_row_buffer();
                 let data = &self.page_buffer[data_start..data_end];
                 let compression_mode = self.layout.row_info.compression;
-                match compression_mode {
-                    Compression::Row => decompress_rle(data, ctx.row_length, &mut buffer),
-                    Compression::Binary => decompress_rdc(data, ctx.row_length, &mut buffer),
-                    Compression::None => {
-                        return Err(Error::Unsupported {
-                            feature: Cow::from(
-                                "row compression pointer seen in uncompressed dataset",
-                            ),
-                        });
-                    }
-                    Compression::Unknown(code) => {
-                        return Err(Error::Unsupported {
-                            feature: Cow::from(format!(
-                                "row compression pointer for unsupported mode {code}",
-                            )),
-                        });
-                    }
-                }
-                .map_err(|msg| Error::Corrupted {
-                    section: Section::Page {
-                        index: ctx.page_index,
-                    },
-                    details: Cow::Owned(format!(
-                        "{msg} (compression={compression_mode:?}, page_type=0x{page_type:04X}, subheader_count={subheader_count}, pointer_range={data_start}..{data_end}, pointer_length={pointer_length}, row_length={row_length})",
-                        page_type = ctx.page_type,
-                        subheader_count = ctx.subheader_count,
-                        row_length = ctx.row_length,
-                        pointer_length = info.length
-                    )),
-                })?;
+                let Some(decompressor) = &self.decompressor else {
+                    return Err(Error::Unsupported {
+                        feature: Cow::from("row compression pointer seen in uncompressed dataset"),
+                    });
+                };
+                let result = decompressor.decompress(data, ctx.row_length, &mut buffer);
+                // A compressed row always stands alone.
+                self.supersede_truncated_row(ctx.page_index);
+                result.map_err(|msg| Error::Corrupted {
+                        section: Section::Page {
+                            index: ctx.page_index,
+                        },
+                        details: Cow::Owned(format!(
+                            "{msg} (compression={compression_mode:?}, page_type=0x{page_type:04X}, subheader_count={subheader_count}, pointer_range={data_start}..{data_end}, pointer_length={pointer_length}, row_length={row_length})",
+                            page_type = ctx.page_type,
+                            subheader_count = ctx.subheader_count,
+                            row_length = ctx.row_length,
+                            pointer_length = info.length
+                        )),
+                    })?;
                 self.current_rows.push(RowData::Owned(buffer));
             }
             other => {
                 return Err(Error::Unsupported {
-                    feature: Cow::from(format!("unsupported subheader compression mode {other}",)),
+                    feature: Cow::from(format!("unsupported subheader compression mode {other}")),
                 });
             }
         }
@@ -339,13 +430,48 @@ where
         subheader_count: u16,
         row_length: usize,
     ) -> Result<()> {
+        let Some((data_start, rows_to_take)) =
+            self.data_area_rows(base_page_type, page_row_count, subheader_count, row_length)
+        else {
+            return Ok(());
+        };
+
+        if self.current_rows.is_empty() {
+            self.contiguous_base = Some(data_start);
+            self.contiguous_rows = u16::try_from(rows_to_take).unwrap_or(u16::MAX);
+            return Ok(());
+        }
+
+        for idx in 0..rows_to_take {
+            let offset = data_start + idx * row_length;
+            if offset + row_length > self.page_buffer.len() {
+                return Err(Error::Corrupted {
+                    section: Section::Page { index: page_index },
+                    details: Cow::from("row slice exceeds page bounds"),
+                });
+            }
+            self.current_rows.push(RowData::Borrowed(offset));
+        }
+
+        Ok(())
+    }
+
+    /// Locates the rows stored after the subheader pointers of a data or mix
+    /// page, returning the offset of the first row and the row count.
+    fn data_area_rows(
+        &self,
+        base_page_type: u16,
+        page_row_count: u16,
+        subheader_count: u16,
+        row_length: usize,
+    ) -> Option<(usize, usize)> {
         let header = &self.layout.header;
         if base_page_type != SAS_PAGE_TYPE_DATA && base_page_type != SAS_PAGE_TYPE_MIX {
-            return Ok(());
+            return None;
         }
 
         let pointer_size = header.subheader_pointer_size as usize;
-        let bit_offset = if header.uses_u64 { 32usize } else { 16usize };
+        let bit_offset = header.page_bit_offset;
         let pointer_section_len = (subheader_count as usize) * pointer_size;
         let base_offset = header.page_header_size as usize + pointer_section_len;
         let alignment_base = bit_offset + SUBHEADER_POINTER_OFFSET + pointer_section_len;
@@ -365,19 +491,23 @@ where
                     .try_into()
                     .unwrap(),
             );
-            if word == 0 || word == 0x2020_2020 || header.metadata.vendor != Vendor::StatTransfer {
+            let skip_pad = match header.quirks().mix_page_alignment {
+                MixPageAlignment::Always => true,
+                MixPageAlignment::PaddingOnly => word == 0 || word == 0x2020_2020,
+            };
+            if skip_pad {
                 data_start = data_start.saturating_add(4);
             }
         }
 
         if data_start >= self.page_buffer.len() {
-            return Ok(());
+            return None;
         }
 
         let available = self.page_buffer.len().saturating_sub(data_start);
         let possible_rows = available / row_length;
         if possible_rows == 0 {
-            return Ok(());
+            return None;
         }
 
         let remaining_rows_u64 = self.total_rows.saturating_sub(self.emitted_rows.get());
@@ -391,7 +521,14 @@ where
             } else {
                 mix_limit
             };
-            mix_limit.min(possible_rows)
+            // Without the header row count to stop at, the page's block
+            // count, which covers subheaders and rows, bounds the rows.
+            let block_limit = if self.layout.trusts_row_count() {
+                usize::MAX
+            } else {
+                usize::from(page_row_count.saturating_sub(subheader_count))
+            };
+            mix_limit.min(block_limit).min(possible_rows)
         } else {
             let header_limit = usize::from(page_row_count);
             let header_limit = if header_limit == 0 {
@@ -405,61 +542,288 @@ where
         rows_to_take = rows_to_take.min(remaining_rows);
         rows_to_take = rows_to_take.min(possible_rows);
 
-        if rows_to_take == 0 {
-            return Ok(());
-        }
+        (rows_to_take > 0).then_some((data_start, rows_to_take))
+    }
 
-        if self.current_rows.is_empty() {
-            self.contiguous_base = Some(data_start);
-            self.contiguous_rows = u16::try_from(rows_to_take).unwrap_or(u16::MAX);
-            return Ok(());
-        }
+    /// Reads pages up to the next one that stores rows and measures its row
+    /// payload without decompressing it. Returns `None` after the last page.
+    pub(crate) fn scan_next_page_compression(&mut self) -> Result<Option<PageCompression>> {
+        let page_count = self.layout.header.page_count;
+        let row_length = self.row_length;
 
-        for idx in 0..rows_to_take {
-            let offset = data_start + idx * row_length;
-            if offset + row_length > self.page_buffer.len() {
-                return Err(Error::Corrupted {
-                    section: Section::Page { index: page_index },
-                    details: Cow::from("row slice exceeds page bounds"),
-                });
+        while self.next_page_index < page_count {
+            let (page_index, page_type, page_row_count) = self.read_page_header()?;
+            if (page_type & SAS_PAGE_TYPE_COMP) != 0
+                || matches!(
+                    classify_page(page_type),
+                    PageKind::Comp | PageKind::CompTable | PageKind::Unknown
+                )
+            {
+                continue;
+            }
+            let Some(subheader_count) = self.read_subheader_count(page_index, page_type)? else {
+                continue;
+            };
+
+            let mut stats = PageCompression {
+                page_index,
+                rows: 0,
+                compressed_rows: 0,
+                stored_bytes: 0,
+                expanded_bytes: 0,
+            };
+            self.measure_pointer_rows(subheader_count, page_row_count, &mut stats)?;
+            if stats.rows == 0
+                && let Some((_, rows)) = self.data_area_rows(
+                    page_type & SAS_PAGE_TYPE_MASK,
+                    page_row_count,
+                    subheader_count,
+                    row_length,
+                )
+            {
+                let bytes = (rows * row_length) as u64;
+                stats.rows = rows as u64;
+                stats.stored_bytes = bytes;
+                stats.expanded_bytes = bytes;
+            }
+            if stats.rows > 0 {
+                self.emitted_rows.set(self.emitted_rows.get() + stats.rows);
+                return Ok(Some(stats));
             }
-            self.current_rows.push(RowData::Borrowed(offset));
         }
+        Ok(None)
+    }
+
+    fn measure_pointer_rows(
+        &self,
+        subheader_count: u16,
+        page_row_count: u16,
+        stats: &mut PageCompression,
+    ) -> Result<()> {
+        let header = &self.layout.header;
+        let pointer_size = header.subheader_pointer_size as usize;
+        let row_length = self.row_length as u64;
+        let min_data_offset =
+            header.page_header_size as usize + usize::from(subheader_count) * pointer_size;
+        let target_rows = u64::from(page_row_count);
 
+        for slot in 0..usize::from(subheader_count) {
+            if target_rows > 0 && stats.rows >= target_rows {
+                break;
+            }
+            let start = header.page_header_size as usize + slot * pointer_size;
+            let Some(pointer) = self.page_buffer.get(start..start + pointer_size) else {
+                break;
+            };
+            let info = parse_pointer(pointer, header.uses_u64, header.endianness)?;
+            if info.length == 0
+                || info.offset < min_data_offset
+                || info.offset.saturating_add(info.length) > self.page_buffer.len()
+            {
+                continue;
+            }
+            let length = info.length as u64;
+            match info.compression {
+                SAS_COMPRESSION_ROW => {
+                    stats.rows += 1;
+                    stats.compressed_rows += 1;
+                    stats.stored_bytes += length;
+                    stats.expanded_bytes += row_length;
+                }
+                SAS_COMPRESSION_NONE if info.is_compressed_data => {
+                    let data = &self.page_buffer[info.offset..info.offset + info.length];
+                    if signature_is_recognized(read_signature(
+                        data,
+                        header.endianness,
+                        header.uses_u64,
+                    )) {
+                        continue;
+                    }
+                    let rows = length / row_length;
+                    stats.rows += rows;
+                    stats.stored_bytes += rows * row_length;
+                    stats.expanded_bytes += rows * row_length;
+                }
+                _ => {}
+            }
+        }
         Ok(())
     }
 
     pub(crate) fn recycle_current_rows(&mut self) {
         self.contiguous_base = None;
         self.contiguous_rows = 0;
-        let mut owned = Vec::new();
-        for entry in self.current_rows.drain(..) {
+        let mut rows = std::mem::take(&mut self.current_rows);
+        while let Some(entry) = rows.pop() {
             if let RowData::Owned(mut buffer) = entry {
                 buffer.clear();
-                owned.push(buffer);
+                self.return_row_buffer(buffer);
             }
         }
-        for buffer in owned {
-            self.return_row_buffer(buffer);
-        }
+        self.current_rows = rows;
     }
 
     pub(crate) fn recycle_owned_rows(&mut self) {
         self.columnar_owned_buffer.clear();
     }
 
+    /// Keeps the start of a row cut off at the end of page `page_index`.
+    fn stash_truncated_row(&mut self, page_index: u64, data_start: usize, data_end: usize) {
+        let mut bytes = self.take_row_buffer();
+        bytes.clear();
+        bytes.extend_from_slice(&self.page_buffer[data_start..data_end]);
+        if self.truncated_row.is_some() {
+            self.drop_truncated_row(Some(page_index));
+        }
+        self.truncated_row = Some(TruncatedRow { page_index, bytes });
+    }
+
+    /// Appends the uncompressed data at `data_start..data_end` to a pending
+    /// truncated row when the pointer lengths show it is the rest of that
+    /// row; returns whether the row was completed.
+    fn continue_truncated_row(
+        &mut self,
+        ctx: &PointerContext,
+        data_start: usize,
+        data_end: usize,
+    ) -> bool {
+        let Some(fragment) = self
+            .truncated_row
+            .take_if(|fragment| fragment.bytes.len() + (data_end - data_start) == ctx.row_length)
+        else {
+            self.supersede_truncated_row(ctx.page_index);
+            return false;
+        };
+        let mut row = fragment.bytes;
+        row.extend_from_slice(&self.page_buffer[data_start..data_end]);
+        self.note_reassembled(fragment.page_index, ctx.page_index);
+        self.current_rows.push(RowData::Owned(row));
+        true
+    }
+
+    /// Releases a pending truncated row once page `page_index` has shown
+    /// it was written again in full.
+    pub(crate) fn supersede_truncated_row(&mut self, page_index: u64) {
+        let Some(fragment) = self.truncated_row.take() else {
+            return;
+        };
+        self.layout.logger.debug(&format!(
+            "Row truncated on page {} was written again in full on page {page_index}",
+            fragment.page_index
+        ));
+        self.return_row_buffer(fragment.bytes);
+    }
+
+    /// Judges from the pointers of the loaded page what decoding it does
+    /// with a row cut off before it; see [`CarriedRow`].
+    pub(crate) fn carried_row(&self) -> CarriedRow {
+        let header = &self.layout.header;
+        let (page_type, _) = self.page_type_and_row_count();
+        if (page_type & SAS_PAGE_TYPE_COMP) != 0
+            || matches!(
+                classify_page(page_type),
+                PageKind::Comp | PageKind::CompTable | PageKind::Unknown
+            )
+        {
+            return CarriedRow::Kept;
+        }
+        let count_at = header.subheader_count_offset();
+        let Some(count) = self.page_buffer.get(count_at..count_at + 2) else {
+            return CarriedRow::Kept;
+        };
+        let count = usize::from(read_u16(header.endianness, count));
+        let pointer_size = header.subheader_pointer_size as usize;
+        let table = header.page_header_size as usize;
+        let min_data_offset = table + count * pointer_size;
+        for slot in 0..count {
+            let start = table + slot * pointer_size;
+            let Some(info) =
+                self.page_buffer
+                    .get(start..start + pointer_size)
+                    .and_then(|pointer| {
+                        parse_pointer(pointer, header.uses_u64, header.endianness).ok()
+                    })
+            else {
+                return CarriedRow::Kept;
+            };
+            if info.length == 0 {
+                continue;
+            }
+            if info.offset < min_data_offset
+                || info.offset.saturating_add(info.length) > self.page_buffer.len()
+            {
+                return CarriedRow::Kept;
+            }
+            match info.compression {
+                SAS_COMPRESSION_ROW => return CarriedRow::Superseded,
+                SAS_COMPRESSION_TRUNC => return CarriedRow::Dropped,
+                SAS_COMPRESSION_NONE => {
+                    if info.length < header.subheader_signature_size {
+                        return CarriedRow::Kept;
+                    }
+                    let data = &self.page_buffer[info.offset..info.offset + info.length];
+                    if !info.is_compressed_data
+                        || signature_is_recognized(read_signature(
+                            data,
+                            header.endianness,
+                            header.uses_u64,
+                        ))
+                    {
+                        continue;
+                    }
+                    return if info.length < self.row_length {
+                        CarriedRow::MayContinue
+                    } else {
+                        CarriedRow::Superseded
+                    };
+                }
+                _ => return CarriedRow::Kept,
+            }
+        }
+        CarriedRow::Dropped
+    }
+
+    fn note_reassembled(&self, first_page: u64, page_index: u64) {
+        self.layout.issues.note(
+            &self.layout.logger,
+            Some(page_index),
+            None,
+            format!("Reassembled row split across pages {first_page} and {page_index}"),
+        );
+    }
+
+    /// Discards a truncated row fragment that no later pointer continued,
+    /// recording it as a diagnostic against `page_index` (or the fragment's
+    /// own page at the end of the file).
+    pub(crate) fn drop_truncated_row(&mut self, page_index: Option<u64>) {
+        let Some(fragment) = self.truncated_row.take() else {
+            return;
+        };
+        self.layout.issues.note(
+            &self.layout.logger,
+            Some(page_index.unwrap_or(fragment.page_index)),
+            None,
+            format!(
+                "Dropping {} byte(s) of a row truncated on page {} that was not continued",
+                fragment.bytes.len(),
+                fragment.page_index
+            ),
+        );
+        self.return_row_buffer(fragment.bytes);
+    }
+
+    /// Hands out a pooled row buffer. Returned buffers are empty but keep
+    /// their capacity, so decompressing wide rows stops allocating once the
+    /// pool holds a page's worth of buffers.
     pub(crate) fn take_row_buffer(&mut self) -> Vec<u8> {
         if let Some(buffer) = self.reusable_row_buffers.pop() {
             return buffer;
         }
-        if !self.reusable_row_buffer.is_empty() {
-            return std::mem::take(&mut self.reusable_row_buffer);
-        }
-        Vec::new()
+        std::mem::take(&mut self.reusable_row_buffer)
     }
 
     pub(crate) fn return_row_buffer(&mut self, buffer: Vec<u8>) {
-        if self.reusable_row_buffer.is_empty() {
+        if self.reusable_row_buffer.capacity() == 0 {
             self.reusable_row_buffer = buffer;
         } else {
             self.reusable_row_buffers.push(buffer);
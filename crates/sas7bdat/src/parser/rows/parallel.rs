@@ -0,0 +1,408 @@
+use super::{
+    iterator::RowIteratorCore,
+    page::{CarriedRow, TruncatedRow},
+};
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    parser::{core::platform::usize_from, metadata::DatasetLayout},
+    telemetry::{PageMetrics, Stopwatch},
+};
+use bytes::Bytes;
+use rayon::prelude::*;
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    mem,
+    ops::Deref,
+    sync::mpsc::{Receiver, SyncSender, sync_channel},
+    thread::{self, JoinHandle},
+    vec,
+};
+
+/// Pages each worker thread is handed per batch when the caller passes `0`.
+const DEFAULT_PAGES_PER_WORKER: usize = 4;
+
+type OwnedRow = Vec<CellValue<'static>>;
+
+/// One page as read from disk, before it is split into rows.
+struct RawPage {
+    index: u64,
+    bytes: Bytes,
+}
+
+/// Rows of a group of pages, and the start of a row cut off at the end of
+/// its last page.
+struct DecodedPages {
+    rows: Vec<OwnedRow>,
+    truncated_row: Option<TruncatedRow>,
+}
+
+/// Where the data pages live, copied out of the layout for the reader thread.
+#[derive(Clone, Copy)]
+struct PageGeometry {
+    data_offset: u64,
+    page_size: usize,
+    page_count: u64,
+}
+
+/// Row iterator that decodes several pages at once.
+///
+/// A reader thread reads batches of `pages_per_batch` raw pages ahead of the
+/// consumer, and each batch is split into rows on the Rayon pool. Rows are
+/// still returned in file order, exactly as [`RowIteratorCore`] yields them,
+/// so sinks that rely on row position see the same stream; a group whose
+/// first page may hold the rest of a row cut off before it is decoded after
+/// the group ahead, with that row's start. Create one
+/// with [`SasReader::into_parallel_iter`](crate::SasReader::into_parallel_iter)
+/// or [`ParallelRowIterator::new`].
+pub struct ParallelRowIterator<L = Box<DatasetLayout>>
+where
+    L: Deref<Target = DatasetLayout>,
+{
+    layout: L,
+    pages: Option<Receiver<Result<Vec<RawPage>>>>,
+    reader: Option<JoinHandle<()>>,
+    rows: vec::IntoIter<OwnedRow>,
+    remaining_rows: u64,
+    /// Start of a row cut off at the end of the last batch.
+    truncated_row: Option<TruncatedRow>,
+}
+
+impl ParallelRowIterator {
+    /// Starts the reader thread on `reader` and returns the iterator.
+    ///
+    /// `pages_per_batch == 0` picks a few pages per Rayon worker thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`RowIteratorCore::new`] and fails if the reader
+    /// thread cannot be spawned.
+    pub fn new<R>(reader: R, layout: Box<DatasetLayout>, pages_per_batch: usize) -> Result<Self>
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        let (sender, pages) = sync_channel(1);
+        let geometry = PageGeometry::of(&layout)?;
+        let dataset = layout.header.metadata.table_name.clone();
+        let pages_per_batch = resolve_pages_per_batch(pages_per_batch);
+        let handle = thread::Builder::new()
+            .name("sas7bdat-pages".into())
+            .spawn(move || {
+                read_pages(
+                    reader,
+                    geometry,
+                    dataset.as_deref(),
+                    pages_per_batch,
+                    &sender,
+                );
+            })?;
+        Self::from_pages(layout, pages, Some(handle))
+    }
+}
+
+impl<L> ParallelRowIterator<L>
+where
+    L: Deref<Target = DatasetLayout>,
+{
+    fn from_pages(
+        layout: L,
+        pages: Receiver<Result<Vec<RawPage>>>,
+        reader: Option<JoinHandle<()>>,
+    ) -> Result<Self> {
+        let remaining_rows = RowIteratorCore::new(io::empty(), &*layout)?.total_rows;
+        Ok(Self {
+            layout,
+            pages: Some(pages),
+            reader,
+            rows: Vec::new().into_iter(),
+            remaining_rows,
+            truncated_row: None,
+        })
+    }
+
+    /// Returns the rows of the next batch of pages in file order, or `None`
+    /// once the dataset is exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page cannot be read or decoded; the iterator is
+    /// exhausted afterwards.
+    pub fn next_batch(&mut self) -> Result<Option<Vec<OwnedRow>>> {
+        let pending: Vec<OwnedRow> = mem::take(&mut self.rows).collect();
+        if !pending.is_empty() {
+            return Ok(Some(pending));
+        }
+        let result = self.decode_next_batch();
+        if !matches!(result, Ok(Some(_))) {
+            self.pages = None;
+        }
+        result
+    }
+
+    fn decode_next_batch(&mut self) -> Result<Option<Vec<OwnedRow>>> {
+        while self.remaining_rows > 0 {
+            let Some(pages) = &self.pages else {
+                return Ok(None);
+            };
+            let Ok(batch) = pages.recv() else {
+                self.drop_truncated_row()?;
+                self.join_reader()?;
+                return Ok(None);
+            };
+            let batch = batch?;
+            let mut rows = self.decode_batch(&batch)?;
+            let limit = usize::try_from(self.remaining_rows).unwrap_or(usize::MAX);
+            rows.truncate(limit);
+            self.remaining_rows -= rows.len() as u64;
+            if !rows.is_empty() {
+                return Ok(Some(rows));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decodes the groups of `batch` in parallel and joins their rows.
+    ///
+    /// A group is decoded on its own when its first page settles a row cut
+    /// off before it (see [`CarriedRow`]), and that row is released as a
+    /// serial read would. Otherwise the group waits for the one ahead and
+    /// is decoded with the row's start.
+    fn decode_batch(&mut self, batch: &[RawPage]) -> Result<Vec<OwnedRow>> {
+        let layout = &*self.layout;
+        let per_worker = batch.len().div_ceil(rayon::current_num_threads()).max(1);
+        let groups: Vec<&[RawPage]> = batch.chunks(per_worker).collect();
+        let Some((&first, rest)) = groups.split_first() else {
+            return Ok(Vec::new());
+        };
+        let carried = self.truncated_row.take();
+        let (first, rest_decoded) = rayon::join(
+            || decode_pages(layout, first, carried),
+            || {
+                rest.par_iter()
+                    .map(|group| decode_settled_pages(layout, group))
+                    .collect::<Vec<_>>()
+            },
+        );
+
+        let DecodedPages {
+            mut rows,
+            mut truncated_row,
+        } = first?;
+        for (group, decoded) in rest.iter().zip(rest_decoded) {
+            let decoded = match decoded? {
+                Some((carried, decoded)) => {
+                    if let Some(fragment) = truncated_row.take() {
+                        release_truncated_row(layout, fragment, carried, group[0].index)?;
+                    }
+                    decoded
+                }
+                None => decode_pages(layout, group, truncated_row.take())?,
+            };
+            rows.extend(decoded.rows);
+            truncated_row = decoded.truncated_row;
+        }
+        self.truncated_row = truncated_row;
+        Ok(rows)
+    }
+
+    /// Discards a row cut off at the end of the last page, recording it as
+    /// the serial reader does.
+    fn drop_truncated_row(&mut self) -> Result<()> {
+        if let Some(fragment) = self.truncated_row.take() {
+            let mut decoder = RowIteratorCore::new(io::empty(), &*self.layout)?;
+            decoder.truncated_row = Some(fragment);
+            decoder.drop_truncated_row(None);
+        }
+        Ok(())
+    }
+
+    fn join_reader(&mut self) -> Result<()> {
+        if let Some(handle) = self.reader.take()
+            && handle.join().is_err()
+        {
+            return Err(Error::Io(io::Error::other("page reader thread panicked")));
+        }
+        Ok(())
+    }
+}
+
+impl<L> Iterator for ParallelRowIterator<L>
+where
+    L: Deref<Target = DatasetLayout>,
+{
+    type Item = Result<OwnedRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(row) = self.rows.next() {
+            return Some(Ok(row));
+        }
+        match self.next_batch() {
+            Ok(Some(rows)) => {
+                self.rows = rows.into_iter();
+                self.rows.next().map(Ok)
+            }
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Runs `consume` on a [`ParallelRowIterator`] whose reader thread borrows
+/// `reader` for the duration of the call.
+pub fn with_parallel_rows<R, T, F>(
+    reader: &mut R,
+    layout: &DatasetLayout,
+    pages_per_batch: usize,
+    consume: F,
+) -> Result<T>
+where
+    R: Read + Seek + Send,
+    F: FnOnce(&mut ParallelRowIterator<&DatasetLayout>) -> Result<T>,
+{
+    let geometry = PageGeometry::of(layout)?;
+    let dataset = layout.header.metadata.table_name.as_deref();
+    let pages_per_batch = resolve_pages_per_batch(pages_per_batch);
+    thread::scope(|scope| {
+        let (sender, pages) = sync_channel(1);
+        scope.spawn(move || {
+            read_pages(reader, geometry, dataset, pages_per_batch, &sender);
+        });
+        let mut rows = ParallelRowIterator::from_pages(layout, pages, None)?;
+        consume(&mut rows)
+    })
+}
+
+impl PageGeometry {
+    fn of(layout: &DatasetLayout) -> Result<Self> {
+        let header = &layout.header;
+        Ok(Self {
+            data_offset: header.data_offset,
+            page_size: usize_from(header.page_size, "page size")?,
+            page_count: header.page_count,
+        })
+    }
+}
+
+fn resolve_pages_per_batch(pages_per_batch: usize) -> usize {
+    if pages_per_batch == 0 {
+        rayon::current_num_threads().saturating_mul(DEFAULT_PAGES_PER_WORKER)
+    } else {
+        pages_per_batch
+    }
+}
+
+/// Reads every page in order and sends them in batches until the consumer
+/// hangs up or a read fails.
+fn read_pages<R: Read + Seek>(
+    mut reader: R,
+    geometry: PageGeometry,
+    dataset: Option<&str>,
+    pages_per_batch: usize,
+    batches: &SyncSender<Result<Vec<RawPage>>>,
+) {
+    let metrics = PageMetrics::new(dataset);
+    let mut next = 0;
+    while next < geometry.page_count {
+        let end = next
+            .saturating_add(pages_per_batch as u64)
+            .min(geometry.page_count);
+        let batch = (next..end)
+            .map(|index| {
+                let bytes = read_page(&mut reader, geometry, index)?;
+                metrics.page_read();
+                Ok(RawPage {
+                    index,
+                    bytes: bytes.into(),
+                })
+            })
+            .collect::<Result<Vec<_>>>();
+        let failed = batch.is_err();
+        if batches.send(batch).is_err() || failed {
+            return;
+        }
+        next = end;
+    }
+}
+
+fn read_page<R: Read + Seek>(
+    reader: &mut R,
+    geometry: PageGeometry,
+    index: u64,
+) -> Result<Vec<u8>> {
+    let offset = geometry.data_offset + index * geometry.page_size as u64;
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut bytes = vec![0u8; geometry.page_size];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Splits consecutive pages into owned rows with one decoder per group,
+/// continuing `truncated_row` if it was cut off just before them.
+fn decode_pages(
+    layout: &DatasetLayout,
+    pages: &[RawPage],
+    truncated_row: Option<TruncatedRow>,
+) -> Result<DecodedPages> {
+    let mut decoder = RowIteratorCore::new(io::empty(), layout)?;
+    decoder.truncated_row = truncated_row;
+    decode_with(decoder, pages)
+}
+
+/// Decodes the non-empty `pages` when their first page settles a row cut
+/// off before it, returning what it does with such a row; `None` when the
+/// pages need the row's start to be decoded.
+fn decode_settled_pages(
+    layout: &DatasetLayout,
+    pages: &[RawPage],
+) -> Result<Option<(CarriedRow, DecodedPages)>> {
+    let mut decoder = RowIteratorCore::new(io::empty(), layout)?;
+    decoder.page_buffer.share(pages[0].bytes.clone());
+    let carried = decoder.carried_row();
+    if !carried.is_settled() {
+        return Ok(None);
+    }
+    Ok(Some((carried, decode_with(decoder, pages)?)))
+}
+
+/// Releases `fragment`, cut off before page `page_index`, the way decoding
+/// that page would have.
+fn release_truncated_row(
+    layout: &DatasetLayout,
+    fragment: TruncatedRow,
+    carried: CarriedRow,
+    page_index: u64,
+) -> Result<()> {
+    let mut decoder = RowIteratorCore::new(io::empty(), layout)?;
+    decoder.truncated_row = Some(fragment);
+    if carried == CarriedRow::Superseded {
+        decoder.supersede_truncated_row(page_index);
+    } else {
+        decoder.drop_truncated_row(Some(page_index));
+    }
+    Ok(())
+}
+
+fn decode_with(
+    mut decoder: RowIteratorCore<io::Empty, &DatasetLayout>,
+    pages: &[RawPage],
+) -> Result<DecodedPages> {
+    let mut rows = Vec::new();
+    for page in pages {
+        let started = Stopwatch::start();
+        decoder.page_buffer.share(page.bytes.clone());
+        let count = decoder.decode_loaded_page(page.index)?;
+        if count == 0 {
+            continue;
+        }
+        rows.reserve(usize::from(count));
+        for row_index in 0..count {
+            let row = decoder.decode_row(row_index)?;
+            rows.push(row.into_iter().map(CellValue::into_owned).collect());
+        }
+        decoder.metrics.page_decoded(count, started);
+    }
+    Ok(DecodedPages {
+        rows,
+        truncated_row: decoder.truncated_row.take(),
+    })
+}
@@ -1,13 +1,18 @@
-use super::constants::{
-    SAS_SUBHEADER_SIGNATURE_COLUMN_ATTRS, SAS_SUBHEADER_SIGNATURE_COLUMN_FORMAT,
-    SAS_SUBHEADER_SIGNATURE_COLUMN_LIST, SAS_SUBHEADER_SIGNATURE_COLUMN_NAME,
-    SAS_SUBHEADER_SIGNATURE_COLUMN_SIZE, SAS_SUBHEADER_SIGNATURE_COLUMN_TEXT,
-    SAS_SUBHEADER_SIGNATURE_COUNTS, SAS_SUBHEADER_SIGNATURE_ROW_SIZE,
-};
 use crate::{
     dataset::Endianness,
     error::{Error, Result, Section},
-    parser::core::byteorder::{read_u32, read_u64},
+    parser::{
+        core::{
+            byteorder::{read_u32, read_u64},
+            platform::saturating_usize,
+        },
+        format_spec::{
+            SAS_SUBHEADER_SIGNATURE_COLUMN_ATTRS, SAS_SUBHEADER_SIGNATURE_COLUMN_FORMAT,
+            SAS_SUBHEADER_SIGNATURE_COLUMN_LIST, SAS_SUBHEADER_SIGNATURE_COLUMN_NAME,
+            SAS_SUBHEADER_SIGNATURE_COLUMN_SIZE, SAS_SUBHEADER_SIGNATURE_COLUMN_TEXT,
+            SAS_SUBHEADER_SIGNATURE_COUNTS, SAS_SUBHEADER_SIGNATURE_ROW_SIZE, WordLayout,
+        },
+    },
 };
 use std::borrow::Cow;
 
@@ -19,47 +24,38 @@ pub struct PointerInfo {
 }
 
 pub fn parse_pointer(pointer: &[u8], uses_u64: bool, endian: Endianness) -> Result<PointerInfo> {
+    let layout = WordLayout::new(uses_u64);
+    let compression_at = layout.pointer_compression_offset();
+    let type_at = layout.pointer_type_offset();
     if uses_u64 {
-        if pointer.len() < 18 {
+        if pointer.len() < layout.pointer_min_len() {
             return Err(Error::Corrupted {
                 section: Section::Header,
                 details: Cow::from("64-bit pointer too short"),
             });
         }
-        let offset =
-            usize::try_from(read_u64(endian, &pointer[0..8])).map_err(|_| Error::Unsupported {
-                feature: Cow::from("64-bit pointer offset exceeds platform pointer width"),
-            })?;
-        let length =
-            usize::try_from(read_u64(endian, &pointer[8..16])).map_err(|_| Error::Unsupported {
-                feature: Cow::from("64-bit pointer length exceeds platform pointer width"),
-            })?;
+        let offset = saturating_usize(read_u64(endian, &pointer[0..8]));
+        let length = saturating_usize(read_u64(endian, &pointer[8..16]));
         Ok(PointerInfo {
             offset,
             length,
-            compression: pointer[16],
-            is_compressed_data: pointer[17] != 0,
+            compression: pointer[compression_at],
+            is_compressed_data: pointer[type_at] != 0,
         })
     } else {
-        if pointer.len() < 10 {
+        if pointer.len() < layout.pointer_min_len() {
             return Err(Error::Corrupted {
                 section: Section::Header,
                 details: Cow::from("32-bit pointer too short"),
             });
         }
-        let offset =
-            usize::try_from(read_u32(endian, &pointer[0..4])).map_err(|_| Error::Unsupported {
-                feature: Cow::from("32-bit pointer offset exceeds platform pointer width"),
-            })?;
-        let length =
-            usize::try_from(read_u32(endian, &pointer[4..8])).map_err(|_| Error::Unsupported {
-                feature: Cow::from("32-bit pointer length exceeds platform pointer width"),
-            })?;
+        let offset = saturating_usize(read_u32(endian, &pointer[0..4]));
+        let length = saturating_usize(read_u32(endian, &pointer[4..8]));
         Ok(PointerInfo {
             offset,
             length,
-            compression: pointer[8],
-            is_compressed_data: pointer[9] != 0,
+            compression: pointer[compression_at],
+            is_compressed_data: pointer[type_at] != 0,
         })
     }
 }
@@ -0,0 +1,93 @@
+use super::iterator::RowIteratorCore;
+use crate::{
+    dataset::{PageIndex, PageIndexEntry},
+    error::Result,
+    parser::metadata::DatasetLayout,
+};
+use std::{
+    io::{Read, Seek},
+    ops::Deref,
+};
+
+impl<R, L> RowIteratorCore<R, L>
+where
+    R: Read + Seek,
+    L: Deref<Target = DatasetLayout>,
+{
+    /// Positions the iterator so the next row it yields is row `row`
+    /// (zero-based), past the end when the dataset holds fewer rows.
+    ///
+    /// Whole pages are skipped without decoding them. Unless the layout
+    /// already carries a [`PageIndex`], the first seek reads every page
+    /// header once to build one; the index is kept on the layout, so later
+    /// seeks, including those of other iterators over the same reader, only
+    /// load the page holding the target row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page cannot be read.
+    pub fn seek_row(&mut self, row: u64) -> Result<()> {
+        if self.layout.page_index.get().is_none() {
+            let index = self.scan_page_index()?;
+            let _ = self.layout.page_index.set(index);
+        }
+        let target = self
+            .layout
+            .page_index
+            .get()
+            .and_then(|index| index.locate(row))
+            .filter(|_| row < self.total_rows);
+
+        self.page_row_count.set(0);
+        self.row_in_page.set(0);
+        if let Some(fragment) = self.truncated_row.take() {
+            self.return_row_buffer(fragment.bytes);
+        }
+        let Some(PageIndexEntry {
+            page_index,
+            first_row,
+            ..
+        }) = target
+        else {
+            self.next_page_index = self.layout.header.page_count;
+            self.exhausted.set(true);
+            return Ok(());
+        };
+        self.next_page_index = page_index;
+        self.emitted_rows.set(first_row);
+        self.exhausted.set(false);
+        if !self.ensure_page_ready()? {
+            return Ok(());
+        }
+
+        // The index bounds `row - first_row` by the rows on the page.
+        let offset = u16::try_from(row - first_row).unwrap_or(u16::MAX);
+        if offset >= self.page_row_count.get() {
+            self.row_in_page.set(self.page_row_count.get());
+            self.emitted_rows
+                .set(first_row + u64::from(self.page_row_count.get()));
+        } else {
+            self.row_in_page.set(offset);
+            self.emitted_rows.set(row);
+        }
+        Ok(())
+    }
+
+    /// Walks the page headers from the first page and records the rows each
+    /// page stores. Leaves the iterator past the last page.
+    pub(crate) fn scan_page_index(&mut self) -> Result<PageIndex> {
+        self.next_page_index = 0;
+        self.emitted_rows.set(0);
+        let mut pages = Vec::new();
+        let mut first_row = 0;
+        while let Some(page) = self.scan_next_page_compression()? {
+            pages.push(PageIndexEntry {
+                page_index: page.page_index,
+                first_row,
+                rows: page.rows,
+            });
+            first_row += page.rows;
+        }
+        Ok(PageIndex::from_pages(&self.layout, pages))
+    }
+}
@@ -1,5 +1,9 @@
 use super::{
-    decode::{decode_value_inner, is_blank, numeric_bits, numeric_bits_is_missing},
+    decode::{
+        decode_string_replacing, decode_value_inner, decodes_cleanly, is_blank,
+        is_canonical_missing_bits, numeric_bits, numeric_bits_is_missing,
+        trim_trailing_space_or_nul_simd,
+    },
     runtime_column::RuntimeColumn,
 };
 use crate::{
@@ -78,7 +82,7 @@ impl<'data, 'meta> StreamingRow<'data, 'meta> {
         let column = self
             .columns
             .get(index)
-            .ok_or_else(|| Error::InvalidMetadata {
+            .ok_or_else(|| Error::InvalidArgument {
                 details: Cow::Owned(format!("column index {index} out of bounds")),
             })?;
         self.cell_from_column(column)
@@ -128,6 +132,20 @@ impl<'data, 'meta> StreamingRow<'data, 'meta> {
         Ok(values.into_vec())
     }
 
+    /// Materialises only the columns at `indices`, in that order, leaving the
+    /// other cells undecoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an index is out of bounds and propagates
+    /// decoding failures for individual cells.
+    pub fn materialize_projected(&self, indices: &[usize]) -> Result<Vec<CellValue<'data>>> {
+        indices
+            .iter()
+            .map(|&index| self.cell(index)?.decode_value())
+            .collect()
+    }
+
     /// Materialises the row into the provided buffer, reusing its capacity.
     ///
     /// # Errors
@@ -169,6 +187,7 @@ impl<'data> StreamingCell<'data, '_> {
     pub fn is_missing(&self) -> bool {
         match self.column.kind {
             ColumnKind::Character => is_blank(self.slice),
+            ColumnKind::Bytes => false,
             ColumnKind::Numeric(_) => {
                 let raw = numeric_bits(self.slice, self.endianness);
                 numeric_bits_is_missing(raw)
@@ -176,6 +195,42 @@ impl<'data> StreamingCell<'data, '_> {
         }
     }
 
+    /// Returns `false` for character cells whose text is neither UTF-8 nor
+    /// valid in the dataset encoding.
+    pub(crate) fn text_is_decodable(&self) -> bool {
+        !matches!(self.column.kind, ColumnKind::Character)
+            || decodes_cleanly(self.slice, self.encoding)
+    }
+
+    /// Decodes a character cell that is not [decodable](Self::text_is_decodable),
+    /// substituting `replacement` for each malformed sequence. Returns the
+    /// text and the number of sequences replaced.
+    pub(crate) fn decode_text_replacing(&self, replacement: char) -> (String, u64) {
+        decode_string_replacing(self.slice, self.encoding, replacement)
+    }
+
+    /// Raw bits of a numeric cell holding a NaN or infinity that is not one
+    /// of the SAS missing codes.
+    pub(crate) fn non_canonical_bits(&self) -> Option<u64> {
+        if !matches!(self.column.kind, ColumnKind::Numeric(_)) {
+            return None;
+        }
+        let raw = numeric_bits(self.slice, self.endianness);
+        let exponent_saturated = raw & 0x7FF0_0000_0000_0000 == 0x7FF0_0000_0000_0000;
+        (exponent_saturated && !is_canonical_missing_bits(raw)).then_some(raw)
+    }
+
+    /// Raw bytes with trailing space and NUL padding removed.
+    pub(crate) fn trimmed_slice(&self) -> &'data [u8] {
+        trim_trailing_space_or_nul_simd(self.slice)
+    }
+
+    /// Stored double of a numeric cell, widened from truncated storage
+    /// without any date or missing-value interpretation.
+    pub(crate) fn raw_number(&self) -> f64 {
+        f64::from_bits(numeric_bits(self.slice, self.endianness))
+    }
+
     /// Decodes the cell into a `CellValue`.
     ///
     /// # Errors
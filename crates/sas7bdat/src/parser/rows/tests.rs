@@ -1,26 +1,40 @@
-use super::{iterator::RowIterator, row_iterator};
+use super::{
+    BatchSizing, DecodeLayout, TimestampRounding, TypedNumericColumn, iterator::RowIterator,
+    row_iterator, with_parallel_rows,
+};
 use crate::{
     cell::CellValue,
-    dataset::{Alignment, Compression, DatasetMetadata, Endianness, Measure, Vendor},
+    dataset::{
+        Alignment, Compression, CompressionSignature, DatasetMetadata, Endianness, Measure, Vendor,
+    },
+    error::Error,
+    logger::Logger,
     parser::{
         core::encoding::resolve_encoding,
+        format_spec::{
+            SAS_COMPRESSION_NONE, SAS_COMPRESSION_ROW, SAS_COMPRESSION_TRUNC, SAS_PAGE_TYPE_DATA,
+            SAS_PAGE_TYPE_META, SAS_PAGE_TYPE_MIX, SUBHEADER_POINTER_OFFSET,
+        },
         header::SasHeader,
         metadata::{
-            ColumnInfo, ColumnKind, ColumnOffsets, DatasetLayout, RowInfo, TextRef, TextStore,
+            ColumnInfo, ColumnKind, ColumnOffsets, DatasetLayout, NumericKind, ParseIssues,
+            RowInfo, TextRef, TextStore,
         },
-        rows::{
-            columnar::COLUMNAR_BATCH_ROWS,
-            compression::{decompress_rdc, decompress_rle},
-            constants::SAS_PAGE_TYPE_DATA,
+        rows::compression::{
+            PageDecompressor, decompress_rdc, decompress_rle, register_page_decompressor,
         },
     },
+    time_conv::sas_seconds_to_time,
 };
 use encoding_rs::Encoding;
 use std::{
     borrow::Cow,
     io::{Cursor, Read, Seek},
+    sync::{Arc, OnceLock},
 };
 
+const TEST_BATCH_ROWS: usize = 256;
+
 fn make_parsed_metadata(
     vendor: Vendor,
     compression: Compression,
@@ -40,6 +54,7 @@ fn make_parsed_metadata(
         metadata,
         endianness: Endianness::Little,
         uses_u64: false,
+        page_bit_offset: 16,
         page_header_size: 24,
         subheader_pointer_size: 12,
         subheader_signature_size: 4,
@@ -62,6 +77,9 @@ fn make_parsed_metadata(
         name_ref: TextRef::EMPTY,
         label_ref: TextRef::EMPTY,
         format_ref: TextRef::EMPTY,
+        informat_width: None,
+        informat_decimals: None,
+        informat_ref: TextRef::EMPTY,
         measure: Measure::Unknown,
         alignment: Alignment::Unknown,
     };
@@ -80,6 +98,12 @@ fn make_parsed_metadata(
         columns: vec![column],
         row_info,
         column_list: None,
+        logger: Logger::default(),
+        source: None,
+        apply_value_labels: false,
+        trust_row_count: true,
+        page_index: OnceLock::new(),
+        issues: ParseIssues::default(),
     }
 }
 
@@ -97,7 +121,7 @@ fn make_data_page(rows: &[&[u8]], row_length: usize, page_size: usize) -> Vec<u8
 fn make_mix_page(rows: &[&[u8]], row_length: usize, page_size: usize) -> Vec<u8> {
     let mut page = vec![0u8; page_size];
     // MIX page with no subheaders; rows start immediately after header.
-    let page_type = super::constants::SAS_PAGE_TYPE_MIX.to_le_bytes();
+    let page_type = SAS_PAGE_TYPE_MIX.to_le_bytes();
     page[(24 - 8)..(24 - 6)].copy_from_slice(&page_type);
     page[(24 - 4)..(24 - 2)].copy_from_slice(&0u16.to_le_bytes());
 
@@ -208,7 +232,7 @@ fn columnar_batch_uses_borrowed_rows() {
     let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
 
     let batch = iter
-        .next_columnar_batch(COLUMNAR_BATCH_ROWS)
+        .next_columnar_batch(TEST_BATCH_ROWS)
         .expect("batch ok")
         .expect("batch present");
     assert_eq!(batch.row_count, 2);
@@ -221,15 +245,347 @@ fn columnar_batch_uses_borrowed_rows() {
     assert_eq!(texts, vec![Some("A".to_string()), Some("B".to_string())]);
 }
 
+#[test]
+fn columnar_timestamps_use_the_configured_rounding() {
+    // 1.0000007 seconds lands between two microseconds.
+    let seconds = 1.000_000_7_f64.to_le_bytes();
+    for kind in [NumericKind::DateTime, NumericKind::Time] {
+        let micros = |rounding: TimestampRounding| {
+            let (mut cursor, mut parsed) = setup_data_iter(&[seconds.as_slice()], 8);
+            parsed.columns[0].kind = ColumnKind::Numeric(kind);
+            let mut iter = row_iterator(&mut cursor, &parsed)
+                .expect("construct row iterator")
+                .with_timestamp_rounding(rounding);
+            let batch = iter
+                .next_columnar_batch(TEST_BATCH_ROWS)
+                .expect("batch ok")
+                .expect("batch present");
+            assert_eq!(batch.timestamp_rounding(), rounding);
+            let column = batch
+                .materialize_numeric(0)
+                .expect("materialise")
+                .expect("numeric column");
+            match &*column {
+                TypedNumericColumn::DateTime(values) | TypedNumericColumn::Time(values) => {
+                    values.values()[0]
+                }
+                _ => panic!("{kind:?} materialised as another type"),
+            }
+        };
+        let truncated = micros(TimestampRounding::Truncate);
+        assert_eq!(truncated % 1_000_000, 0, "{kind:?}");
+        assert_eq!(
+            micros(TimestampRounding::HalfEven),
+            truncated + 1,
+            "{kind:?}"
+        );
+    }
+}
+
+/// Splits the single 4-byte column of a synthetic layout into two 2-byte
+/// columns.
+fn split_into_two_columns(parsed: &mut DatasetLayout) {
+    let mut second = parsed.columns[0].clone();
+    parsed.columns[0].offsets.width = 2;
+    second.index = 1;
+    second.offsets = ColumnOffsets {
+        offset: 2,
+        width: 2,
+    };
+    parsed.columns.push(second);
+}
+
+#[test]
+fn projected_rows_decode_selected_columns_in_order() {
+    let rows = [b"ABCD".as_slice(), b"EFGH".as_slice()];
+    let (mut cursor, mut parsed) = setup_data_iter(&rows, 4);
+    split_into_two_columns(&mut parsed);
+
+    assert!(matches!(
+        row_iterator(&mut cursor, &parsed)
+            .expect("construct row iterator")
+            .with_projection(&[2]),
+        Err(Error::InvalidArgument { .. })
+    ));
+
+    let mut iter = row_iterator(&mut cursor, &parsed)
+        .expect("construct row iterator")
+        .with_projection(&[1, 0])
+        .expect("valid projection");
+    let row = iter.try_next().expect("row result").expect("row present");
+    assert_eq!(
+        row,
+        vec![
+            CellValue::Str(Cow::Borrowed("CD")),
+            CellValue::Str(Cow::Borrowed("AB"))
+        ]
+    );
+    let view = iter
+        .try_next_streaming_row()
+        .expect("row result")
+        .expect("row present");
+    assert_eq!(view.len(), 2, "row views still cover every column");
+}
+
+#[test]
+fn projected_columnar_batch_holds_selected_columns_only() {
+    let rows = [b"ABCD".as_slice(), b"EFGH".as_slice()];
+    let (mut cursor, mut parsed) = setup_data_iter(&rows, 4);
+    split_into_two_columns(&mut parsed);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+
+    let batch = iter
+        .next_columnar_batch_projected(&[1], TEST_BATCH_ROWS)
+        .expect("batch ok")
+        .expect("batch present");
+    assert_eq!(batch.row_count, 2);
+    assert!(batch.column(1).is_none());
+    let col = batch.column(0).expect("column present");
+    let texts: Vec<_> = col
+        .iter_strings()
+        .map(|opt| opt.map(std::borrow::Cow::into_owned))
+        .collect();
+    assert_eq!(texts, vec![Some("CD".to_string()), Some("GH".to_string())]);
+}
+
+#[test]
+fn utf8_arena_matches_string_iterator() {
+    let row_length = 4usize;
+    let rows = [
+        b"AB  ".as_slice(),
+        b"    ".as_slice(),
+        b"\xc3\xa6 x".as_slice(),
+    ];
+    let (mut cursor, parsed) = setup_data_iter(&rows, row_length);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+
+    let batch = iter
+        .next_columnar_batch(TEST_BATCH_ROWS)
+        .expect("batch ok")
+        .expect("batch present");
+    let col = batch.column(0).expect("column present");
+    let arena = col.utf8_arena().expect("arena");
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.null_count(), 1);
+    assert_eq!(arena.offsets(), &[0, 2, 2, 6]);
+    assert_eq!(arena.data(), "AB\u{e6} x".as_bytes());
+    let expected: Vec<_> = col
+        .iter_strings()
+        .map(|opt| opt.map(std::borrow::Cow::into_owned))
+        .collect();
+    let actual: Vec<_> = arena.iter().map(|opt| opt.map(str::to_owned)).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn validity_bitmap_packs_one_bit_per_row() {
+    let row_length = 4usize;
+    let rows = [
+        b"A   ".as_slice(),
+        b"    ".as_slice(),
+        b"C   ".as_slice(),
+        b"D   ".as_slice(),
+        b"E   ".as_slice(),
+        b"F   ".as_slice(),
+        b"G   ".as_slice(),
+        b"H   ".as_slice(),
+        b"\0\0\0\0".as_slice(),
+        b"J   ".as_slice(),
+    ];
+    let (mut cursor, parsed) = setup_data_iter(&rows, row_length);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+
+    let batch = iter
+        .next_columnar_batch(TEST_BATCH_ROWS)
+        .expect("batch ok")
+        .expect("batch present");
+    let col = batch.column(0).expect("column present");
+    let bitmap = col.validity_bitmap();
+    assert_eq!(bitmap.len(), 10);
+    assert_eq!(bitmap.null_count(), 2);
+    assert_eq!(bitmap.as_bytes(), &[0b1111_1101, 0b0000_0010]);
+    let expected: Vec<bool> = col.iter_strings().map(|value| value.is_some()).collect();
+    let actual: Vec<bool> = (0..bitmap.len()).map(|row| bitmap.is_valid(row)).collect();
+    assert_eq!(actual, expected);
+    assert!(!bitmap.is_valid(10));
+}
+
+#[test]
+fn decode_layout_auto_prefers_borrowed_rows_for_narrow_projections() {
+    assert_eq!(DecodeLayout::Auto.resolve(4, 4), DecodeLayout::RowMajor);
+    assert_eq!(DecodeLayout::Auto.resolve(100, 10), DecodeLayout::RowMajor);
+    assert_eq!(
+        DecodeLayout::Auto.resolve(100, 80),
+        DecodeLayout::ColumnMajor
+    );
+    assert_eq!(
+        DecodeLayout::RowMajor.resolve(100, 100),
+        DecodeLayout::RowMajor
+    );
+}
+
+#[test]
+fn column_major_layout_copies_rows_into_batch() {
+    let row_length = 4usize;
+    let rows = [b"A   ".as_slice(), b"B   ".as_slice()];
+    let (mut cursor, parsed) = setup_data_iter(&rows, row_length);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+
+    let batch = iter
+        .next_batch_with_layout(DecodeLayout::ColumnMajor, 1, TEST_BATCH_ROWS)
+        .expect("batch ok")
+        .expect("batch present");
+    assert_eq!(batch.row_count, 2);
+    let col = batch.column(0).expect("column present");
+    let texts: Vec<_> = col
+        .iter_strings()
+        .map(|opt| opt.map(std::borrow::Cow::into_owned))
+        .collect();
+    assert_eq!(texts, vec![Some("A".to_string()), Some("B".to_string())]);
+}
+
+#[test]
+fn timestamp_rounding_modes() {
+    let truncate = TimestampRounding::Truncate;
+    let half_even = TimestampRounding::HalfEven;
+
+    assert_eq!(truncate.nanos_to_micros(1_999), 1);
+    assert_eq!(truncate.nanos_to_micros(-1), -1);
+    assert_eq!(half_even.nanos_to_micros(1_499), 1);
+    assert_eq!(half_even.nanos_to_micros(1_501), 2);
+    assert_eq!(half_even.nanos_to_micros(1_500), 2);
+    assert_eq!(half_even.nanos_to_micros(2_500), 2);
+    assert_eq!(half_even.nanos_to_micros(-1_500), -2);
+    assert_eq!(half_even.nanos_to_micros(-2_500), -2);
+}
+
+#[test]
+fn time_conversion_is_microsecond_exact() {
+    // Times as SAS stores them: whole seconds plus a decimal microsecond
+    // fraction, none of which are exact in binary.
+    let mut cases = vec![(0_u32, 1_u32), (0, 999_999), (59, 1), (86_399, 999_999)];
+    cases.extend(
+        (0..86_400)
+            .step_by(997)
+            .map(|second| (second, second * 11 % 1_000_000)),
+    );
+    for (whole, fraction) in cases {
+        let seconds = f64::from(whole) + f64::from(fraction) / 1_000_000.0;
+        let expected = i128::from(whole) * 1_000_000 + i128::from(fraction);
+        let duration = sas_seconds_to_time(seconds).expect("finite time");
+        for rounding in [TimestampRounding::Truncate, TimestampRounding::HalfEven] {
+            assert_eq!(
+                rounding.duration_micros(duration),
+                expected,
+                "{seconds} with {rounding:?}"
+            );
+        }
+        assert_eq!(sas_seconds_to_time(-seconds).map(|d| -d), Some(duration));
+    }
+
+    let nanos = sas_seconds_to_time(0.000_000_123).expect("finite time");
+    assert_eq!(nanos.whole_nanoseconds(), 123);
+    assert_eq!(sas_seconds_to_time(f64::NAN), None);
+    assert_eq!(sas_seconds_to_time(f64::INFINITY), None);
+}
+
 #[test]
 fn decompresses_row_compression_page_rle() {
     // Control 0xC1 + 'A' inserts 4 bytes of 'A' (row length 4).
     let compressed = [0xC1u8, b'A'];
-    let page = make_compressed_page(&compressed, 4, 96, super::constants::SAS_COMPRESSION_ROW);
+    let page = make_compressed_page(&compressed, 4, 96, SAS_COMPRESSION_ROW);
     let parsed = make_parsed_metadata(Vendor::Sas, Compression::Row, 4, 1, 1, 96);
     assert_rows_from_page(page, &parsed, &["AAAA"]);
 }
 
+/// Two pages of a row-compressed dataset with 8-byte rows: the first ends
+/// in a truncated pointer to `first`, the second holds a pointer to
+/// `second` with `compression`.
+fn make_straddling_pages(first: &[u8], second: &[u8], compression: u8) -> (Vec<u8>, DatasetLayout) {
+    let mut pages = make_compressed_page(first, 8, 96, SAS_COMPRESSION_TRUNC);
+    pages[(24 - 8)..(24 - 6)].copy_from_slice(&SAS_PAGE_TYPE_META.to_le_bytes());
+    pages.extend(make_compressed_page(second, 8, 96, compression));
+    let mut parsed = make_parsed_metadata(Vendor::Sas, Compression::Row, 8, 1, 1, 96);
+    parsed.header.page_count = 2;
+    (pages, parsed)
+}
+
+#[test]
+fn reassembles_row_split_across_pages() {
+    let (pages, parsed) = make_straddling_pages(b"AAAA", b"BBBB", SAS_COMPRESSION_NONE);
+    assert_rows_from_page(pages, &parsed, &["AAAABBBB"]);
+
+    let diagnostics = parsed.issues.diagnostics().entries();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].page_index, Some(1));
+    assert!(diagnostics[0].message.contains("Reassembled"));
+}
+
+#[test]
+fn truncated_row_written_again_in_full_is_read_once() {
+    let rewritten = [
+        ([0xC1, b'A', 0xC1, b'B'].as_slice(), SAS_COMPRESSION_ROW),
+        (b"AAAABBBB".as_slice(), SAS_COMPRESSION_NONE),
+    ];
+    for (second, compression) in rewritten {
+        let (pages, parsed) = make_straddling_pages(&[0xC1, b'A'], second, compression);
+        assert_rows_from_page(pages, &parsed, &["AAAABBBB"]);
+        assert!(parsed.issues.diagnostics().is_empty());
+    }
+}
+
+#[test]
+fn compressed_rows_never_continue_a_truncated_row() {
+    // The compressed pointer decodes to a whole row of its own, so the
+    // start kept from the first page is not joined to it.
+    let (pages, parsed) =
+        make_straddling_pages(b"AAAA", &[0xC1, b'B', 0xC1, b'C'], SAS_COMPRESSION_ROW);
+    assert_rows_from_page(pages, &parsed, &["BBBBCCCC"]);
+    assert!(parsed.issues.diagnostics().is_empty());
+}
+
+#[test]
+fn parallel_rows_reassemble_rows_split_across_groups() {
+    // One page per batch splits at a batch, two pages on two threads at a
+    // group within the batch.
+    let cases = [
+        (b"BBBB".as_slice(), SAS_COMPRESSION_NONE, "AAAABBBB", 1),
+        (
+            [0xC1, b'B', 0xC1, b'C'].as_slice(),
+            SAS_COMPRESSION_ROW,
+            "BBBBCCCC",
+            0,
+        ),
+    ];
+    for (second, compression, expected, diagnostic_count) in cases {
+        for pages_per_batch in [1, 2] {
+            let (pages, parsed) = make_straddling_pages(b"AAAA", second, compression);
+            let mut cursor = Cursor::new(pages);
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .expect("thread pool");
+            let rows = pool.install(|| {
+                with_parallel_rows(&mut cursor, &parsed, pages_per_batch, |rows| {
+                    rows.collect::<crate::error::Result<Vec<_>>>()
+                })
+            });
+            assert_eq!(
+                rows.expect("parallel rows"),
+                vec![vec![CellValue::Str(Cow::Borrowed(expected))]],
+                "{pages_per_batch} page(s) per batch"
+            );
+            let diagnostics = parsed.issues.diagnostics().entries();
+            assert_eq!(diagnostics.len(), diagnostic_count, "{diagnostics:?}");
+            assert!(
+                diagnostics
+                    .iter()
+                    .all(|diagnostic| diagnostic.message.contains("Reassembled"))
+            );
+        }
+    }
+}
+
 #[test]
 fn comp_table_pages_are_skipped() {
     let mut page = vec![0u8; 64];
@@ -251,11 +607,55 @@ fn decompresses_binary_compression_page_rdc() {
     let mut compressed = Vec::new();
     compressed.extend_from_slice(&0u16.to_be_bytes());
     compressed.extend_from_slice(b"BCDE");
-    let page = make_compressed_page(&compressed, 4, 96, super::constants::SAS_COMPRESSION_ROW);
+    let page = make_compressed_page(&compressed, 4, 96, SAS_COMPRESSION_ROW);
     let parsed = make_parsed_metadata(Vendor::Sas, Compression::Binary, 4, 1, 1, 96);
     assert_rows_from_page(page, &parsed, &["BCDE"]);
 }
 
+/// Reverses the compressed bytes; stands in for a third-party scheme.
+struct ReversingDecompressor;
+
+impl PageDecompressor for ReversingDecompressor {
+    fn signature(&self) -> &'static str {
+        "SASYZREV"
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        expected_len: usize,
+        output: &mut Vec<u8>,
+    ) -> std::result::Result<(), &'static str> {
+        if input.len() != expected_len {
+            return Err("reversed row has the wrong length");
+        }
+        output.clear();
+        output.extend(input.iter().rev());
+        Ok(())
+    }
+}
+
+#[test]
+fn registered_decompressor_decodes_other_compression() {
+    let page = make_compressed_page(b"ZYXW", 4, 96, SAS_COMPRESSION_ROW);
+    let signature = CompressionSignature::new("SASYZREV").expect("eight-byte tag");
+    let parsed = make_parsed_metadata(Vendor::Sas, Compression::Other(signature), 4, 1, 1, 96);
+    register_page_decompressor(Arc::new(ReversingDecompressor));
+    assert_rows_from_page(page, &parsed, &["WXYZ"]);
+}
+
+#[test]
+fn unregistered_compression_is_unsupported() {
+    let signature = CompressionSignature::new("SASYZX").expect("short tag");
+    let parsed = make_parsed_metadata(Vendor::Sas, Compression::Other(signature), 4, 1, 1, 96);
+    let mut cursor = Cursor::new(vec![0u8; 96]);
+    let Err(err) = row_iterator(&mut cursor, &parsed) else {
+        panic!("iterator constructed without a decompressor");
+    };
+    assert!(matches!(err, Error::Unsupported { .. }));
+    assert!(err.to_string().contains("SASYZX"));
+}
+
 #[test]
 fn invalid_pointer_before_data_section_is_ignored() {
     // A DATA page declaring a pointer that starts inside the pointer table; it should be skipped
@@ -272,8 +672,7 @@ fn invalid_pointer_before_data_section_is_ignored() {
 
     let pointer_section_len = 12usize;
     let bit_offset = 16usize;
-    let alignment_base =
-        bit_offset + super::constants::SUBHEADER_POINTER_OFFSET + pointer_section_len;
+    let alignment_base = bit_offset + SUBHEADER_POINTER_OFFSET + pointer_section_len;
     let align_adjust = if alignment_base.is_multiple_of(8) {
         0
     } else {
@@ -338,8 +737,127 @@ fn fixes_mojibake_sequences() {
     assert_eq!(repaired, "高雄市");
 }
 
+#[test]
+fn spilled_decoding_matches_decode_string() {
+    let encoding = Encoding::for_label(b"windows-1252").unwrap();
+    let mut spill = String::new();
+    for input in [
+        b"plain  ".as_slice(),
+        b"\xC9clair \0",
+        b"\xE9\xAB\x98\xE9\x9B\x84\xE5\xB8\x82",
+        b"\xc3\xa6 x",
+        b"   ",
+    ] {
+        let expected = super::decode::decode_string(input, encoding);
+        let actual = super::decode::decode_string_with(input, encoding, &mut spill);
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn replacing_decoder_counts_malformed_sequences() {
+    let encoding = Encoding::for_label(b"utf-8").unwrap();
+    let (text, replaced) = super::decode::decode_string_replacing(b"ab\xFFc\xC3 \0", encoding, '?');
+    assert_eq!(text, "ab?c?");
+    assert_eq!(replaced, 2);
+}
+
+const WIDE_ROW_LENGTH: usize = 32_767;
+
+fn setup_wide_iter(rows: &[&[u8]]) -> (Cursor<Vec<u8>>, DatasetLayout) {
+    let page_size = 24 + rows.len() * WIDE_ROW_LENGTH;
+    let page = make_data_page(rows, WIDE_ROW_LENGTH, page_size);
+    let row_count = rows.len() as u64;
+    let mut parsed = make_parsed_metadata(
+        Vendor::Sas,
+        Compression::None,
+        u32::try_from(WIDE_ROW_LENGTH).expect("row length fits u32"),
+        row_count,
+        row_count,
+        u32::try_from(page_size).expect("page size fits u32"),
+    );
+    parsed.header.metadata.file_encoding = Some("WINDOWS-1252".to_owned());
+    (Cursor::new(page), parsed)
+}
+
+#[test]
+fn widest_character_columns_decode_on_every_path() {
+    let filled = vec![b'x'; WIDE_ROW_LENGTH];
+    let mut accented = vec![0xC9u8; 16_000];
+    accented.resize(WIDE_ROW_LENGTH, b' ');
+    let blank = vec![b' '; WIDE_ROW_LENGTH];
+    let rows = [filled.as_slice(), accented.as_slice(), blank.as_slice()];
+    let expected_filled = "x".repeat(WIDE_ROW_LENGTH);
+    let expected_accented = "\u{c9}".repeat(16_000);
+
+    let (mut cursor, parsed) = setup_wide_iter(&rows);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+    let mut decoded = Vec::new();
+    while let Some(row) = iter.try_next().expect("row result") {
+        decoded.push(
+            row.into_iter()
+                .map(CellValue::into_owned)
+                .collect::<Vec<_>>(),
+        );
+    }
+    assert_eq!(
+        decoded,
+        vec![
+            vec![CellValue::Str(Cow::Owned(expected_filled.clone()))],
+            vec![CellValue::Str(Cow::Owned(expected_accented.clone()))],
+            vec![CellValue::Str(Cow::Borrowed(""))],
+        ]
+    );
+
+    let (mut cursor, parsed) = setup_wide_iter(&rows);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+    let batch = iter
+        .next_columnar_batch(TEST_BATCH_ROWS)
+        .expect("batch ok")
+        .expect("batch present");
+    let col = batch.column(0).expect("column present");
+    let arena = col.utf8_arena().expect("arena");
+    assert_eq!(arena.get(0), Some(expected_filled.as_str()));
+    assert_eq!(arena.get(1), Some(expected_accented.as_str()));
+    assert_eq!(arena.get(2), None);
+
+    let mut spill = String::new();
+    assert_eq!(
+        col.str_with(1, &mut spill),
+        Some(expected_accented.as_str())
+    );
+    let (capacity, buffer) = (spill.capacity(), spill.as_ptr());
+    assert_eq!(
+        col.str_with(1, &mut spill),
+        Some(expected_accented.as_str())
+    );
+    assert_eq!(col.str_with(0, &mut spill), Some(expected_filled.as_str()));
+    assert_eq!((spill.capacity(), spill.as_ptr()), (capacity, buffer));
+}
+
+#[test]
+fn recycled_row_buffers_keep_their_capacity() {
+    let (mut cursor, parsed) = setup_data_iter(&[b"AAAA".as_slice()], 4);
+    let mut iter = row_iterator(&mut cursor, &parsed).expect("construct row iterator");
+    iter.return_row_buffer(Vec::with_capacity(WIDE_ROW_LENGTH));
+    iter.return_row_buffer(Vec::with_capacity(WIDE_ROW_LENGTH));
+    assert!(iter.take_row_buffer().capacity() >= WIDE_ROW_LENGTH);
+    assert!(iter.take_row_buffer().capacity() >= WIDE_ROW_LENGTH);
+    assert_eq!(iter.take_row_buffer().capacity(), 0);
+}
+
 #[test]
 fn resolves_mac_aliases() {
     let encoding = resolve_encoding(Some("MACCYRILLIC"));
     assert_eq!(encoding.name(), "x-mac-cyrillic");
 }
+
+#[test]
+fn batch_sizing_adapts_to_row_width() {
+    let sizing = BatchSizing::TargetBytes(64 * 1024);
+    assert_eq!(sizing.rows_for(16), 4_096);
+    assert!(sizing.rows_for(8) > sizing.rows_for(512));
+    assert_eq!(sizing.rows_for(1 << 20), 64);
+    assert_eq!(sizing.rows_for(1), 65_536);
+    assert_eq!(BatchSizing::Rows(100).rows_for(1 << 20), 100);
+}
@@ -0,0 +1,117 @@
+use bytes::Bytes;
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+};
+
+/// Fetches byte ranges of a remote file for [`BlockReader`].
+pub(super) trait RangeFetch {
+    /// Returns the bytes in `range`, which never extends past the end of the
+    /// file.
+    fn fetch(&mut self, range: Range<u64>) -> io::Result<Bytes>;
+}
+
+/// `Read + Seek` over a remote file, served from a cache of fixed-size
+/// blocks that are each fetched with one range request.
+///
+/// The least recently read block is dropped first once the cache is full.
+pub(super) struct BlockReader<F> {
+    source: F,
+    len: u64,
+    block_size: u64,
+    cache_blocks: usize,
+    position: u64,
+    blocks: HashMap<u64, Bytes>,
+    /// Cached block indices, least recently read first.
+    recent: VecDeque<u64>,
+    requests: u64,
+}
+
+impl<F: RangeFetch> BlockReader<F> {
+    /// `block_size` and `cache_blocks` of `0` are treated as `1`.
+    pub(super) fn new(source: F, len: u64, block_size: usize, cache_blocks: usize) -> Self {
+        Self {
+            source,
+            len,
+            block_size: block_size.max(1) as u64,
+            cache_blocks: cache_blocks.max(1),
+            position: 0,
+            blocks: HashMap::new(),
+            recent: VecDeque::new(),
+            requests: 0,
+        }
+    }
+
+    pub(super) const fn source(&self) -> &F {
+        &self.source
+    }
+
+    pub(super) const fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub(super) const fn requests(&self) -> u64 {
+        self.requests
+    }
+
+    /// Returns block `index`, fetching it on a cache miss.
+    fn block(&mut self, index: u64) -> io::Result<Bytes> {
+        if let Some(block) = self.blocks.get(&index) {
+            let block = block.clone();
+            if let Some(at) = self.recent.iter().position(|&cached| cached == index) {
+                self.recent.remove(at);
+            }
+            self.recent.push_back(index);
+            return Ok(block);
+        }
+        let start = index * self.block_size;
+        let end = (start + self.block_size).min(self.len);
+        let block = self.source.fetch(start..end)?;
+        self.requests += 1;
+        while self.recent.len() >= self.cache_blocks {
+            if let Some(evicted) = self.recent.pop_front() {
+                self.blocks.remove(&evicted);
+            }
+        }
+        self.blocks.insert(index, block.clone());
+        self.recent.push_back(index);
+        Ok(block)
+    }
+}
+
+impl<F: RangeFetch> Read for BlockReader<F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+        let index = self.position / self.block_size;
+        let block = self.block(index)?;
+        let offset = usize::try_from(self.position - index * self.block_size).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, "block offset exceeds usize")
+        })?;
+        let available = block.get(offset..).unwrap_or_default();
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<F> Seek for BlockReader<F> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek to a negative or overflowing position",
+            )
+        })?;
+        self.position = target;
+        Ok(target)
+    }
+}
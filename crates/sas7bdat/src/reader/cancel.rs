@@ -0,0 +1,41 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Shared flag for stopping a running conversion from another thread.
+///
+/// Clones share the flag: hand one to the thread running
+/// [`SasReader::stream_into_with_cancel`](super::SasReader::stream_into_with_cancel)
+/// and call [`cancel`](Self::cancel) on another, for example when the user of
+/// a server abandons a conversion job. The stream checks the flag before
+/// every row, aborts the sink and returns [`Error::Cancelled`](crate::Error::Cancelled).
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation; every clone of the token observes it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Wraps a flag the caller already shares, such as one set by a signal
+/// handler.
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(cancelled: Arc<AtomicBool>) -> Self {
+        Self { cancelled }
+    }
+}
@@ -0,0 +1,178 @@
+use super::{labels::normalize_label_name, open_sas_file};
+use crate::{
+    dataset::{LabelSet, ValueLabel, ValueType},
+    error::Result,
+    parser::parse_catalog,
+};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// A SAS format catalog (`.sas7bcat`) inspected on its own, without a
+/// dataset to attach it to.
+///
+/// Each user-defined format in the catalog is exposed as a [`LabelSet`]
+/// mapping raw values to labels. Sets are ordered by name, and the whole
+/// catalog serialises with `serde`, so `serde_json::to_string(&catalog)`
+/// dumps every format and its mappings.
+///
+/// ```no_run
+/// use sas7bdat::Catalog;
+///
+/// # fn main() -> sas7bdat::Result<()> {
+/// let catalog = Catalog::open("formats.sas7bcat")?;
+/// for set in catalog.label_sets() {
+///     println!("{} ({} labels)", set.name, set.labels.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Catalog {
+    file_encoding: Option<String>,
+    label_sets: Vec<LabelSet>,
+}
+
+impl Catalog {
+    /// Opens and parses a catalog file from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or parsed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut file = open_sas_file(path.as_ref())?;
+        Self::from_reader(&mut file)
+    }
+
+    /// Parses a catalog from the start of `reader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the catalog cannot be parsed.
+    pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let layout = parse_catalog(reader)?;
+        let mut label_sets = layout.label_sets;
+        label_sets.sort_by(|left, right| left.name.cmp(&right.name));
+        Ok(Self {
+            file_encoding: layout.header.metadata.file_encoding,
+            label_sets,
+        })
+    }
+
+    /// Encoding declared in the catalog header, if any.
+    #[must_use]
+    pub fn file_encoding(&self) -> Option<&str> {
+        self.file_encoding.as_deref()
+    }
+
+    #[must_use]
+    pub fn label_sets(&self) -> &[LabelSet] {
+        &self.label_sets
+    }
+
+    /// Format names, as stored in the catalog (character formats start
+    /// with `$`).
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.label_sets.iter().map(|set| set.name.as_str())
+    }
+
+    /// Formats that label numeric or character values.
+    pub fn formats(&self, value_type: ValueType) -> impl Iterator<Item = &LabelSet> + '_ {
+        self.label_sets
+            .iter()
+            .filter(move |set| set.value_type == value_type)
+    }
+
+    /// Looks up a format by name the way variable formats are matched when
+    /// attaching a catalog: case-insensitively, ignoring a trailing `.`, and
+    /// falling back to the `$`-prefixed character format.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&LabelSet> {
+        let normalized = normalize_label_name(name);
+        self.find_normalized(&normalized).or_else(|| {
+            (!normalized.starts_with('$'))
+                .then(|| self.find_normalized(&format!("${normalized}")))
+                .flatten()
+        })
+    }
+
+    /// Value-to-label mappings of the named format.
+    #[must_use]
+    pub fn mappings(&self, name: &str) -> Option<&[ValueLabel]> {
+        self.get(name).map(|set| set.labels.as_slice())
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.label_sets.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.label_sets.is_empty()
+    }
+
+    #[must_use]
+    pub fn into_label_sets(self) -> Vec<LabelSet> {
+        self.label_sets
+    }
+
+    fn find_normalized(&self, normalized: &str) -> Option<&LabelSet> {
+        self.label_sets
+            .iter()
+            .find(|set| normalize_label_name(&set.name) == normalized)
+    }
+}
+
+/// A format defined by more than one catalog of a search list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CatalogConflict {
+    /// Format name as matched against variable formats: upper case, without
+    /// the trailing `.`.
+    pub format: String,
+    /// Search-list position of the catalog whose definition is used.
+    pub used: usize,
+    /// Search-list positions of the catalogs whose definitions are ignored.
+    pub shadowed: Vec<usize>,
+}
+
+/// Merges `catalogs` given in search order, the way SAS resolves formats
+/// through `FMTSEARCH=`: a format defined by several catalogs is taken from
+/// the first one. Returns the formats in effect and one conflict per format
+/// defined more than once, ordered by format name.
+pub(super) fn merge_in_search_order(
+    catalogs: impl IntoIterator<Item = Catalog>,
+) -> (Vec<LabelSet>, Vec<CatalogConflict>) {
+    let mut label_sets = Vec::new();
+    let mut defined_by: HashMap<String, usize> = HashMap::new();
+    let mut conflicts: Vec<CatalogConflict> = Vec::new();
+    for (position, catalog) in catalogs.into_iter().enumerate() {
+        for set in catalog.into_label_sets() {
+            match defined_by.entry(normalize_label_name(&set.name)) {
+                Entry::Vacant(entry) => {
+                    entry.insert(position);
+                    label_sets.push(set);
+                }
+                Entry::Occupied(entry) => {
+                    match conflicts
+                        .iter_mut()
+                        .find(|conflict| conflict.format == *entry.key())
+                    {
+                        Some(conflict) => conflict.shadowed.push(position),
+                        None => conflicts.push(CatalogConflict {
+                            format: entry.key().clone(),
+                            used: *entry.get(),
+                            shadowed: vec![position],
+                        }),
+                    }
+                }
+            }
+        }
+    }
+    conflicts.sort_by(|left, right| left.format.cmp(&right.format));
+    (label_sets, conflicts)
+}
@@ -0,0 +1,113 @@
+use super::{SasReader, open_regular_file};
+#[cfg(feature = "zip")]
+use crate::error::Error;
+use crate::{error::Result, logger::Logger};
+#[cfg(feature = "zip")]
+use std::borrow::Cow;
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+#[cfg(feature = "zip")]
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+impl SasReader<File> {
+    /// Opens a SAS7BDAT file that may be wrapped in a gzip stream
+    /// (`dataset.sas7bdat.gz`) or a zip archive holding one dataset.
+    ///
+    /// The container is recognised from its leading bytes, whatever the
+    /// file is called, and decompressed into an anonymous temporary file
+    /// that the reader then seeks in; the file is deleted when the reader
+    /// is dropped. Plain dataset files are opened as by [`SasReader::open`].
+    /// Gzip needs the `gzip` feature and zip the `zip` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or decompressed, if a
+    /// zip archive holds no `.sas7bdat` entry or more than one, or if the
+    /// decompressed metadata cannot be parsed.
+    pub fn open_compressed<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut file = open_regular_file(path)?;
+        let mut magic = [0; 4];
+        let read = read_prefix(&mut file, &mut magic)?;
+        let magic = &magic[..read];
+
+        #[cfg(feature = "gzip")]
+        if magic.starts_with(GZIP_MAGIC) {
+            file.seek(SeekFrom::Start(0))?;
+            let _logger = Logger::current()
+                .with_context(path.display().to_string())
+                .enter();
+            let mut decoder = flate2::read::MultiGzDecoder::new(io::BufReader::new(file));
+            return Self::from_reader(spill(&mut decoder)?);
+        }
+        #[cfg(feature = "zip")]
+        if magic.starts_with(ZIP_MAGIC) {
+            let _logger = Logger::current()
+                .with_context(path.display().to_string())
+                .enter();
+            return Self::from_reader(unzip_dataset(file)?);
+        }
+        Self::open(path)
+    }
+}
+
+/// Fills as much of `buf` as the file holds.
+fn read_prefix(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+/// Copies `reader` into a temporary file removed once it is closed.
+fn spill(reader: &mut impl Read) -> Result<File> {
+    let mut file = tempfile::tempfile()?;
+    io::copy(reader, &mut file)?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(file)
+}
+
+/// Extracts the only `.sas7bdat` entry of a zip archive.
+#[cfg(feature = "zip")]
+fn unzip_dataset(file: File) -> Result<File> {
+    let mut archive = zip::ZipArchive::new(io::BufReader::new(file)).map_err(zip_error)?;
+    let datasets: Vec<usize> = (0..archive.len())
+        .filter(|&index| {
+            archive.name_for_index(index).is_some_and(|name| {
+                Path::new(name)
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("sas7bdat"))
+            })
+        })
+        .collect();
+    let [index] = datasets[..] else {
+        return Err(Error::Unsupported {
+            feature: Cow::Owned(format!(
+                "zip archive with {} .sas7bdat entries; expected exactly one",
+                datasets.len()
+            )),
+        });
+    };
+    let mut entry = archive.by_index(index).map_err(zip_error)?;
+    spill(&mut entry)
+}
+
+#[cfg(feature = "zip")]
+fn zip_error(err: zip::result::ZipError) -> Error {
+    match err {
+        zip::result::ZipError::Io(err) => Error::Io(err),
+        err => Error::Unsupported {
+            feature: Cow::Owned(format!("unreadable zip archive: {err}")),
+        },
+    }
+}
@@ -0,0 +1,150 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Position-addressable decryption applied between storage and the parser.
+///
+/// The parser seeks freely between pages, so the layer must be able to
+/// decrypt any byte range on its own: counter-mode and other seekable stream
+/// ciphers fit, while chained block modes do not. Decryption must preserve
+/// length, with byte `n` of the ciphertext body decrypting to byte `n` of the
+/// SAS file.
+///
+/// An AES-CTR layer built on the `aes` and `ctr` crates looks like this:
+///
+/// ```ignore
+/// use ctr::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+///
+/// struct AesCtr(ctr::Ctr128BE<aes::Aes256>);
+///
+/// impl DecryptLayer for AesCtr {
+///     fn header_len(&self) -> u64 {
+///         16 // nonce stored in front of the ciphertext
+///     }
+///
+///     fn decrypt(&mut self, offset: u64, buf: &mut [u8]) -> std::io::Result<()> {
+///         self.0.seek(offset);
+///         self.0.apply_keystream(buf);
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// See `examples/decrypt_layer.rs` for a complete, dependency-free layer.
+pub trait DecryptLayer {
+    /// Bytes stored before the ciphertext body, such as a nonce or an
+    /// envelope header. They are skipped and never passed to
+    /// [`DecryptLayer::decrypt`].
+    fn header_len(&self) -> u64 {
+        0
+    }
+
+    /// Decrypts `buf` in place. The first byte of `buf` sits at `offset`
+    /// within the ciphertext body, not counting [`DecryptLayer::header_len`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range cannot be decrypted, for example when
+    /// an authenticated layer detects tampering.
+    fn decrypt(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()>;
+}
+
+impl<D: DecryptLayer + ?Sized> DecryptLayer for Box<D> {
+    fn header_len(&self) -> u64 {
+        (**self).header_len()
+    }
+
+    fn decrypt(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        (**self).decrypt(offset, buf)
+    }
+}
+
+/// Seekable plaintext view over an encrypted `Read + Seek` source.
+///
+/// Bytes are decrypted as the parser reads them, so encrypted files never
+/// need to be written out in the clear.
+pub struct DecryptingReader<R, D> {
+    inner: R,
+    layer: D,
+    len: u64,
+    position: u64,
+}
+
+impl<R: Read + Seek, D: DecryptLayer> DecryptingReader<R, D> {
+    /// Wraps `inner`, measuring its length and skipping the layer's header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `inner` cannot be seeked or is shorter than the
+    /// layer's header.
+    pub fn new(mut inner: R, layer: D) -> io::Result<Self> {
+        let header_len = layer.header_len();
+        let stored_len = inner.seek(SeekFrom::End(0))?;
+        let len = stored_len.checked_sub(header_len).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("encrypted input is {stored_len} bytes, shorter than its {header_len}-byte header"),
+            )
+        })?;
+        inner.seek(SeekFrom::Start(header_len))?;
+        Ok(Self {
+            inner,
+            layer,
+            len,
+            position: 0,
+        })
+    }
+}
+
+impl<R, D> DecryptingReader<R, D> {
+    /// Length of the decrypted stream in bytes.
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn layer(&self) -> &D {
+        &self.layer
+    }
+
+    pub fn into_inner(self) -> (R, D) {
+        (self.inner, self.layer)
+    }
+}
+
+impl<R: Read + Seek, D: DecryptLayer> Read for DecryptingReader<R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.position);
+        let wanted = usize::try_from(remaining).map_or(buf.len(), |left| left.min(buf.len()));
+        if wanted == 0 {
+            return Ok(0);
+        }
+        let count = self.inner.read(&mut buf[..wanted])?;
+        self.layer.decrypt(self.position, &mut buf[..count])?;
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<R: Read + Seek, D: DecryptLayer> Seek for DecryptingReader<R, D> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek to a negative or overflowing position",
+            )
+        })?;
+        self.inner
+            .seek(SeekFrom::Start(self.layer.header_len() + target))?;
+        self.position = target;
+        Ok(target)
+    }
+}
@@ -0,0 +1,194 @@
+use super::missing::merge_label_set_missing;
+use crate::{
+    dataset::{Format, LabelSet, ValueType, VariableKind},
+    error::{Error, Result},
+    parser::{ColumnKind, DatasetLayout, NumericKind},
+};
+use std::borrow::Cow;
+
+const MAX_NAME_BYTES: usize = 32;
+const MAX_LABEL_BYTES: usize = 256;
+
+/// Validated, in-place edits to variable metadata before rows are streamed.
+///
+/// Obtained from [`SasReader::edit_metadata`](super::SasReader::edit_metadata).
+/// Variables are addressed by name, ignoring trailing blanks. Every edit is
+/// validated before it is applied, so a failed call leaves the metadata
+/// unchanged.
+pub struct MetadataEditor<'a> {
+    layout: &'a mut DatasetLayout,
+}
+
+impl<'a> MetadataEditor<'a> {
+    pub(crate) const fn new(layout: &'a mut DatasetLayout) -> Self {
+        Self { layout }
+    }
+
+    /// Renames a variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is unknown, `new_name` is empty or longer
+    /// than 32 bytes, or another variable already uses `new_name`
+    /// (case-insensitively).
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<&mut Self> {
+        let index = self.resolve(name)?;
+        let new_name = new_name.trim_end();
+        if new_name.trim().is_empty() {
+            return Err(invalid("variable name may not be empty"));
+        }
+        if new_name.len() > MAX_NAME_BYTES {
+            return Err(invalid(format!(
+                "variable name '{new_name}' exceeds {MAX_NAME_BYTES} bytes"
+            )));
+        }
+        let clash = self
+            .layout
+            .header
+            .metadata
+            .variables
+            .iter()
+            .enumerate()
+            .any(|(idx, variable)| {
+                idx != index && variable.name.trim_end().eq_ignore_ascii_case(new_name)
+            });
+        if clash {
+            return Err(invalid(format!(
+                "variable name '{new_name}' is already in use"
+            )));
+        }
+        new_name.clone_into(&mut self.layout.header.metadata.variables[index].name);
+        Ok(self)
+    }
+
+    /// Sets or clears a variable label.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is unknown or the label exceeds 256 bytes.
+    pub fn set_label(&mut self, name: &str, label: Option<&str>) -> Result<&mut Self> {
+        let index = self.resolve(name)?;
+        if let Some(label) = label
+            && label.len() > MAX_LABEL_BYTES
+        {
+            return Err(invalid(format!(
+                "label for '{name}' exceeds {MAX_LABEL_BYTES} bytes"
+            )));
+        }
+        self.layout.header.metadata.variables[index].label = label.map(str::to_owned);
+        Ok(self)
+    }
+
+    /// Sets or clears a variable format.
+    ///
+    /// For numeric variables the decoded value type follows the new format, so
+    /// assigning `DATE9.` yields date cells and clearing the format yields
+    /// plain numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is unknown, the format name is empty or
+    /// longer than 32 bytes, or the `$` prefix does not match the variable
+    /// type (character formats must start with `$`, numeric formats must not).
+    pub fn set_format(&mut self, name: &str, format: Option<Format>) -> Result<&mut Self> {
+        let index = self.resolve(name)?;
+        let is_character = matches!(
+            self.layout.header.metadata.variables[index].kind,
+            VariableKind::Character
+        );
+        if let Some(format) = &format {
+            let format_name = format.name.trim();
+            if format_name.is_empty() {
+                return Err(invalid("format name may not be empty"));
+            }
+            if format_name.len() > MAX_NAME_BYTES {
+                return Err(invalid(format!(
+                    "format name '{format_name}' exceeds {MAX_NAME_BYTES} bytes"
+                )));
+            }
+            if format_name.starts_with('$') != is_character {
+                return Err(invalid(format!(
+                    "format '{format_name}' does not match the type of variable '{name}'"
+                )));
+            }
+        }
+
+        if !is_character {
+            let numeric_kind = format
+                .as_ref()
+                .and_then(|format| format.category().numeric_kind())
+                .unwrap_or(NumericKind::Double);
+            if let Some(column) = self.layout.columns.get_mut(index) {
+                column.kind = ColumnKind::Numeric(numeric_kind);
+            }
+        }
+        self.layout.header.metadata.variables[index].format = format;
+        Ok(self)
+    }
+
+    /// Attaches a value-label set to a variable, or detaches the current one.
+    ///
+    /// The set is stored under its name alongside any catalog sets, replacing
+    /// a set of the same name, and is used for labelled rows (see
+    /// [`SasReader::set_apply_value_labels`](super::SasReader::set_apply_value_labels))
+    /// and [`Predicate::has_label`](super::Predicate::has_label). This is how
+    /// label sets built in code, such as numeric ranges from
+    /// [`ValueLabel::range`](crate::dataset::ValueLabel::range), reach the
+    /// rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is unknown or the set's value type does not
+    /// match the variable type.
+    pub fn set_value_labels(&mut self, name: &str, set: Option<LabelSet>) -> Result<&mut Self> {
+        let index = self.resolve(name)?;
+        let metadata = &mut self.layout.header.metadata;
+        let Some(set) = set else {
+            metadata.variables[index].value_labels = None;
+            return Ok(self);
+        };
+        let is_character = matches!(metadata.variables[index].kind, VariableKind::Character);
+        if (set.value_type == ValueType::String) != is_character {
+            return Err(invalid(format!(
+                "label set '{}' does not match the type of variable '{name}'",
+                set.name
+            )));
+        }
+        let variable = &mut metadata.variables[index];
+        merge_label_set_missing(&mut variable.missing, &set);
+        variable.value_labels = Some(set.name.clone());
+        metadata.label_sets.insert(set.name.clone(), set);
+        Ok(self)
+    }
+
+    /// Sets or clears the dataset label.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the label exceeds 256 bytes.
+    pub fn set_file_label(&mut self, label: Option<&str>) -> Result<&mut Self> {
+        if let Some(label) = label
+            && label.len() > MAX_LABEL_BYTES
+        {
+            return Err(invalid(format!(
+                "dataset label exceeds {MAX_LABEL_BYTES} bytes"
+            )));
+        }
+        self.layout.header.metadata.file_label = label.map(str::to_owned);
+        Ok(self)
+    }
+
+    fn resolve(&self, name: &str) -> Result<usize> {
+        self.layout
+            .header
+            .metadata
+            .column_index(name)
+            .ok_or_else(|| invalid(format!("column name '{name}' not found in dataset")))
+    }
+}
+
+fn invalid(details: impl Into<Cow<'static, str>>) -> Error {
+    Error::InvalidArgument {
+        details: details.into(),
+    }
+}
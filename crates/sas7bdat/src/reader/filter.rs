@@ -0,0 +1,290 @@
+use crate::{
+    cell::CellValue,
+    dataset::{DatasetMetadata, LabelLookup, VariableKind},
+    error::{Error, Result},
+    parser::StreamingRow,
+    value,
+};
+use std::{cmp::Ordering, fmt, sync::Arc};
+
+/// Comparison applied by a [`Predicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    const fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+/// A `column op literal` row test for [`RowSelection::matching`](super::RowSelection::matching).
+///
+/// Only the named column of each row is decoded to evaluate the test, and
+/// values compare with SAS semantics ([`value::compare`]): missing values
+/// sort below every number, so `AGE < 18` also matches rows where `AGE` is
+/// missing, exactly as a SAS `WHERE` clause would.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    column: String,
+    test: Test,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Test {
+    Compare(CompareOp, CellValue<'static>),
+    Missing(bool),
+    Label(Vec<String>),
+}
+
+impl Predicate {
+    pub fn new(
+        column: impl Into<String>,
+        op: CompareOp,
+        literal: impl Into<CellValue<'static>>,
+    ) -> Self {
+        Self {
+            column: column.into(),
+            test: Test::Compare(op, literal.into()),
+        }
+    }
+
+    pub fn eq(column: impl Into<String>, literal: impl Into<CellValue<'static>>) -> Self {
+        Self::new(column, CompareOp::Eq, literal)
+    }
+
+    pub fn ne(column: impl Into<String>, literal: impl Into<CellValue<'static>>) -> Self {
+        Self::new(column, CompareOp::Ne, literal)
+    }
+
+    pub fn lt(column: impl Into<String>, literal: impl Into<CellValue<'static>>) -> Self {
+        Self::new(column, CompareOp::Lt, literal)
+    }
+
+    pub fn le(column: impl Into<String>, literal: impl Into<CellValue<'static>>) -> Self {
+        Self::new(column, CompareOp::Le, literal)
+    }
+
+    pub fn gt(column: impl Into<String>, literal: impl Into<CellValue<'static>>) -> Self {
+        Self::new(column, CompareOp::Gt, literal)
+    }
+
+    pub fn ge(column: impl Into<String>, literal: impl Into<CellValue<'static>>) -> Self {
+        Self::new(column, CompareOp::Ge, literal)
+    }
+
+    /// Matches rows where the column holds any missing value.
+    pub fn is_missing(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            test: Test::Missing(true),
+        }
+    }
+
+    /// Matches rows where the column is not missing.
+    pub fn not_missing(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            test: Test::Missing(false),
+        }
+    }
+
+    /// Matches rows where the column's value label is one of `labels`.
+    ///
+    /// Values are mapped through the label set attached to the column, by a
+    /// catalog or [`MetadataEditor::set_value_labels`](super::MetadataEditor::set_value_labels),
+    /// so numeric range labels bucket values while streaming: with
+    /// `0-17 = "child"`, `has_label("AGE", ["child"])` keeps every row whose
+    /// age falls in the range. Values without a label never match. Resolving
+    /// the filter fails when the column has no label set.
+    pub fn has_label<I, S>(column: impl Into<String>, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            column: column.into(),
+            test: Test::Label(labels.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+type RowPredicate = dyn Fn(&StreamingRow<'_, '_>) -> Result<bool> + Send + Sync;
+
+/// Row tests collected by a [`RowSelection`](super::RowSelection); a row is
+/// kept when every test accepts it.
+#[derive(Clone, Default)]
+pub(super) struct RowFilter {
+    predicates: Vec<Predicate>,
+    closures: Vec<Arc<RowPredicate>>,
+}
+
+impl fmt::Debug for RowFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RowFilter")
+            .field("predicates", &self.predicates)
+            .field("closures", &self.closures.len())
+            .finish()
+    }
+}
+
+impl RowFilter {
+    pub(super) const fn new() -> Self {
+        Self {
+            predicates: Vec::new(),
+            closures: Vec::new(),
+        }
+    }
+
+    pub(super) fn push_predicate(&mut self, predicate: Predicate) {
+        self.predicates.push(predicate);
+    }
+
+    pub(super) fn push_closure<F>(&mut self, accept: F)
+    where
+        F: Fn(&StreamingRow<'_, '_>) -> Result<bool> + Send + Sync + 'static,
+    {
+        self.closures.push(Arc::new(accept));
+    }
+
+    /// Binds predicate column names to indices; `None` when nothing filters.
+    pub(super) fn resolve(&self, metadata: &DatasetMetadata) -> Result<Option<ResolvedFilter>> {
+        if self.predicates.is_empty() && self.closures.is_empty() {
+            return Ok(None);
+        }
+        let predicates = self
+            .predicates
+            .iter()
+            .map(|predicate| {
+                let index = metadata.column_index(&predicate.column).ok_or_else(|| {
+                    Error::InvalidArgument {
+                        details: format!(
+                            "filter column '{}' not found in metadata",
+                            predicate.column
+                        )
+                        .into(),
+                    }
+                })?;
+                let check = match &predicate.test {
+                    Test::Label(labels) => {
+                        let lookup = metadata.variables[index]
+                            .value_labels
+                            .as_ref()
+                            .and_then(|name| metadata.label_sets.get(name))
+                            .ok_or_else(|| Error::InvalidArgument {
+                                details: format!(
+                                    "filter column '{}' has no value labels",
+                                    predicate.column
+                                )
+                                .into(),
+                            })?
+                            .lookup();
+                        Check::Label(lookup, labels.clone())
+                    }
+                    Test::Compare(op, literal) => {
+                        check_literal_kind(metadata, index, &predicate.column, literal)?;
+                        Check::Compare(*op, literal.clone())
+                    }
+                    Test::Missing(expected) => Check::Missing(*expected),
+                };
+                Ok((index, check))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Some(ResolvedFilter {
+            predicates,
+            closures: self.closures.clone(),
+        }))
+    }
+}
+
+/// Rejects comparing a character column with a number or a numeric column
+/// with text, which would otherwise fall back to ordering by value kind.
+fn check_literal_kind(
+    metadata: &DatasetMetadata,
+    index: usize,
+    column: &str,
+    literal: &CellValue<'_>,
+) -> Result<()> {
+    let literal_kind = match literal {
+        CellValue::Missing(_) => return Ok(()),
+        CellValue::Str(_) | CellValue::Bytes(_) => VariableKind::Character,
+        CellValue::Float(_)
+        | CellValue::Int32(_)
+        | CellValue::Int64(_)
+        | CellValue::NumericString(_)
+        | CellValue::Date(_)
+        | CellValue::DateTime(_)
+        | CellValue::Time(_) => VariableKind::Numeric,
+    };
+    let column_kind = metadata.variables[index].kind;
+    if column_kind == literal_kind {
+        return Ok(());
+    }
+    Err(Error::InvalidArgument {
+        details: format!(
+            "filter column '{column}' is {} but is compared with a {} literal",
+            kind_name(column_kind),
+            kind_name(literal_kind)
+        )
+        .into(),
+    })
+}
+
+const fn kind_name(kind: VariableKind) -> &'static str {
+    match kind {
+        VariableKind::Numeric => "numeric",
+        VariableKind::Character => "character",
+    }
+}
+
+/// A [`Test`] bound to its column, with label tests holding the column's
+/// label set.
+enum Check {
+    Compare(CompareOp, CellValue<'static>),
+    Missing(bool),
+    Label(LabelLookup, Vec<String>),
+}
+
+pub(super) struct ResolvedFilter {
+    predicates: Vec<(usize, Check)>,
+    closures: Vec<Arc<RowPredicate>>,
+}
+
+impl ResolvedFilter {
+    pub(super) fn matches(&self, row: &StreamingRow<'_, '_>) -> Result<bool> {
+        for (index, check) in &self.predicates {
+            let cell = row.cell(*index)?;
+            let accepted = match check {
+                Check::Missing(expected) => cell.is_missing() == *expected,
+                Check::Compare(op, literal) => {
+                    op.accepts(value::compare(&cell.decode_value()?, literal))
+                }
+                Check::Label(lookup, labels) => lookup
+                    .label(&cell.decode_value()?)
+                    .is_some_and(|label| labels.iter().any(|wanted| wanted == label)),
+            };
+            if !accepted {
+                return Ok(false);
+            }
+        }
+        for accept in &self.closures {
+            if !accept(row)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
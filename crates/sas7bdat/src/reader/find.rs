@@ -0,0 +1,117 @@
+use super::{SasReader, row::Row, row::RowLookup};
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    parser::{ColumnKind, core::encoding::resolve_encoding},
+};
+use std::{
+    borrow::Cow,
+    io::{Read, Seek, SeekFrom},
+    sync::Arc,
+};
+
+/// Value matched by [`SasReader::find_rows`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LookupKey<'a> {
+    /// Compared with character cells after trailing blanks are removed.
+    Text(&'a str),
+    /// Compared with the stored double of numeric cells.
+    Number(f64),
+}
+
+impl<'a> From<&'a str> for LookupKey<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<f64> for LookupKey<'_> {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<i32> for LookupKey<'_> {
+    fn from(value: i32) -> Self {
+        Self::Number(f64::from(value))
+    }
+}
+
+/// Key prepared once for comparison against raw cell bytes.
+enum RawKey {
+    Text(Vec<u8>),
+    Number(f64),
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Returns up to `limit` rows, in file order, whose `column` equals `key`.
+    ///
+    /// Text keys are encoded into the dataset encoding once and compared
+    /// with each cell's raw bytes, so non-matching rows are never decoded.
+    /// SAS7BDAT files carry no sort order this parser can rely on, so the
+    /// search scans pages from the start and stops as soon as `limit` rows
+    /// have matched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `column` does not exist or the
+    /// key type does not match the column type, and otherwise any error
+    /// raised while reading rows.
+    pub fn find_rows<'k>(
+        &mut self,
+        column: &str,
+        key: impl Into<LookupKey<'k>>,
+        limit: usize,
+    ) -> Result<Vec<Row>> {
+        let lookup = Arc::new(RowLookup::from_metadata(self.metadata()));
+        let index = lookup.index(column).ok_or_else(|| Error::InvalidArgument {
+            details: format!("unknown column '{column}'").into(),
+        })?;
+        let raw_key = match (key.into(), self.layout.columns[index].kind) {
+            (LookupKey::Text(text), ColumnKind::Character | ColumnKind::Bytes) => {
+                let encoding = resolve_encoding(self.metadata().file_encoding.as_deref());
+                let (encoded, _, _) = encoding.encode(text.trim_end_matches([' ', '\0']));
+                RawKey::Text(encoded.into_owned())
+            }
+            (LookupKey::Number(number), ColumnKind::Numeric(_)) => RawKey::Number(number),
+            (key, _) => {
+                return Err(Error::InvalidArgument {
+                    details: Cow::Owned(format!(
+                        "lookup key {key:?} does not match the type of column '{column}'"
+                    )),
+                });
+            }
+        };
+
+        let mut found = Vec::new();
+        if limit == 0 {
+            return Ok(found);
+        }
+        self.reader.seek(SeekFrom::Start(0))?;
+        let mut iterator = self.layout.row_iterator(&mut self.reader)?;
+        while let Some(row) = iterator.try_next_streaming_row()? {
+            let cell = row.cell(index)?;
+            // Exact comparison is intended: keys are looked up, not measured.
+            #[allow(clippy::float_cmp)]
+            let matched = match &raw_key {
+                RawKey::Text(bytes) => cell.trimmed_slice() == bytes.as_slice(),
+                RawKey::Number(number) => cell.raw_number() == *number,
+            };
+            if !matched {
+                continue;
+            }
+            let values = row
+                .materialize()?
+                .into_iter()
+                .map(CellValue::into_owned)
+                .collect();
+            found.push(Row::new(values, Arc::clone(&lookup)));
+            if found.len() == limit {
+                break;
+            }
+        }
+        drop(iterator);
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(found)
+    }
+}
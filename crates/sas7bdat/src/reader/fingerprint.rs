@@ -0,0 +1,199 @@
+use super::SasReader;
+use crate::{
+    error::{Error, Result},
+    parser::core::platform::usize_from,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hasher,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use twox_hash::XxHash3_64;
+use walkdir::WalkDir;
+
+/// Pages hashed by [`SasReader::content_fingerprint`].
+pub const DEFAULT_SAMPLE_PAGES: u64 = 8;
+
+/// A cheap stand-in for the content of a dataset file.
+///
+/// Two files with equal fingerprints have the same size, header, row and
+/// column counts, schema, and bytes in every sampled page, so a renamed or
+/// copied snapshot fingerprints the same as its original without reading it
+/// in full. Files that differ only in pages left out of the sample collide;
+/// hash them in full with [`ChecksumSink`](crate::ChecksumSink) when that
+/// matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentFingerprint {
+    digest: u64,
+    file_size: u64,
+}
+
+impl ContentFingerprint {
+    /// XXH3-64 of the hashed content as 16 lowercase hex digits, stable
+    /// across platforms and runs.
+    #[must_use]
+    pub fn digest(&self) -> String {
+        format!("{:016x}", self.digest)
+    }
+
+    #[must_use]
+    pub const fn file_size(&self) -> u64 {
+        self.file_size
+    }
+}
+
+/// The digest followed by the file size, such as `9f2c...e1-131072`.
+impl fmt::Display for ContentFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}-{}", self.digest, self.file_size)
+    }
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Fingerprints the file from its metadata and [`DEFAULT_SAMPLE_PAGES`]
+    /// pages spread evenly across it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header or a sampled page cannot be read.
+    pub fn content_fingerprint(&mut self) -> Result<ContentFingerprint> {
+        self.content_fingerprint_sampled(DEFAULT_SAMPLE_PAGES)
+    }
+
+    /// Fingerprints the file from its metadata and `pages` pages: the first,
+    /// the last, and the rest evenly spaced between them. Files with no more
+    /// than `pages` pages are hashed in full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header or a sampled page cannot be read.
+    pub fn content_fingerprint_sampled(&mut self, pages: u64) -> Result<ContentFingerprint> {
+        let header = &self.layout.header;
+        let (header_size, page_size) = (header.header_size, header.page_size);
+        let (data_offset, page_count) = (header.data_offset, header.page_count);
+        let metadata = &header.metadata;
+
+        let mut hasher = XxHash3_64::new();
+        let file_size = self.reader.seek(SeekFrom::End(0))?;
+        hasher.write_u64(file_size);
+        hasher.write_u64(metadata.row_count);
+        hasher.write_u32(metadata.column_count);
+        hasher.write(self.schema_fingerprint().digest().as_bytes());
+
+        let mut buffer = vec![0; usize_from(header_size.max(page_size), "page size")?];
+        self.reader.seek(SeekFrom::Start(0))?;
+        let header_bytes = &mut buffer[..usize_from(header_size, "header size")?];
+        self.reader.read_exact(header_bytes)?;
+        hasher.write(header_bytes);
+
+        let page_bytes = usize_from(page_size, "page size")?;
+        for page in sample_pages(page_count, pages) {
+            self.reader
+                .seek(SeekFrom::Start(data_offset + page * u64::from(page_size)))?;
+            let page_data = &mut buffer[..page_bytes];
+            self.reader.read_exact(page_data)?;
+            hasher.write_u64(page);
+            hasher.write(page_data);
+        }
+        self.reader.seek(SeekFrom::Start(0))?;
+
+        Ok(ContentFingerprint {
+            digest: hasher.finish(),
+            file_size,
+        })
+    }
+}
+
+/// Indices of `samples` pages out of `page_count`, first and last included.
+fn sample_pages(page_count: u64, samples: u64) -> impl Iterator<Item = u64> {
+    let samples = samples.min(page_count);
+    (0..samples).map(move |sample| match samples {
+        1 => 0,
+        _ => sample * (page_count - 1) / (samples - 1),
+    })
+}
+
+/// Files sharing a [`ContentFingerprint`], in path order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    pub fingerprint: ContentFingerprint,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Outcome of [`find_duplicate_files`].
+#[derive(Debug, Default)]
+pub struct DuplicateReport {
+    /// Groups of two or more files with equal fingerprints, ordered by their
+    /// first path.
+    pub groups: Vec<DuplicateGroup>,
+    /// Files that could not be fingerprinted, with the reason.
+    pub unreadable: Vec<(PathBuf, Error)>,
+    /// Number of files fingerprinted.
+    pub files: usize,
+}
+
+/// Fingerprints every dataset under `paths` and groups the identical ones.
+///
+/// Directories are searched recursively for `.sas7bdat` files; other paths
+/// are taken as dataset files whatever their extension. A path reached
+/// twice is fingerprinted once. Files that cannot be opened or read are
+/// listed in [`DuplicateReport::unreadable`] instead of stopping the pass.
+///
+/// # Errors
+///
+/// Returns an error if a directory cannot be listed.
+pub fn find_duplicate_files<I, P>(paths: I) -> Result<DuplicateReport>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(path.as_ref(), &mut files)?;
+    }
+    files.sort();
+    files.dedup();
+
+    let mut report = DuplicateReport::default();
+    let mut by_fingerprint: HashMap<ContentFingerprint, Vec<PathBuf>> = HashMap::new();
+    for path in files {
+        match SasReader::open(&path).and_then(|mut sas| sas.content_fingerprint()) {
+            Ok(fingerprint) => {
+                report.files += 1;
+                by_fingerprint.entry(fingerprint).or_default().push(path);
+            }
+            Err(err) => report.unreadable.push((path, err)),
+        }
+    }
+
+    report.groups = by_fingerprint
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(fingerprint, paths)| DuplicateGroup { fingerprint, paths })
+        .collect();
+    report
+        .groups
+        .sort_by(|left, right| left.paths[0].cmp(&right.paths[0]));
+    Ok(report)
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in WalkDir::new(path).follow_links(false) {
+        let entry = entry.map_err(io::Error::from)?;
+        let path = entry.path();
+        if entry.file_type().is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("sas7bdat"))
+        {
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use super::blocks::{BlockReader, RangeFetch};
+#[cfg(feature = "object_store")]
+use super::store::{ObjectStoreOptions, ObjectStoreReader};
+use crate::{
+    error::{Error, Result},
+    parser::format_spec::SAS_HEADER_MIN_SIZE,
+};
+use bytes::Bytes;
+use reqwest::{
+    StatusCode,
+    blocking::{Client, Response},
+    header::{CONTENT_RANGE, RANGE},
+};
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+    thread,
+    time::Duration,
+};
+
+const DEFAULT_BLOCK_SIZE: usize = 1024 * 1024;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+const DEFAULT_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Settings for [`HttpReader`].
+#[derive(Debug, Clone, Copy)]
+pub struct HttpOptions {
+    /// Bytes fetched per range request. Reads inside the last fetched block
+    /// are served without another request.
+    pub block_size: usize,
+    /// Attempts after the first one for requests that fail with a transport
+    /// error, a timeout, or a 408, 429, or 5xx status.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on each further attempt.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Timeout for a whole request, including reading the body.
+    pub timeout: Duration,
+}
+
+impl HttpOptions {
+    #[must_use]
+    pub const fn with_block_size(mut self, bytes: usize) -> Self {
+        self.block_size = bytes;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            block_size: DEFAULT_BLOCK_SIZE,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+/// Seekable reader over a file served by an HTTP(S) server that supports
+/// range requests.
+///
+/// All requests go through one client, so connections are reused between
+/// blocks. HTTPS URLs need the `https` feature. With the `object_store`
+/// feature, other URLs such as `s3://bucket/key`, `gs://bucket/key` and
+/// `az://container/blob` are read through an [`ObjectStoreReader`].
+pub struct HttpReader {
+    transport: Transport,
+}
+
+enum Transport {
+    Http(BlockReader<HttpSource>),
+    #[cfg(feature = "object_store")]
+    ObjectStore {
+        url: String,
+        reader: ObjectStoreReader,
+    },
+}
+
+/// The client and URL a [`HttpReader`] fetches blocks from.
+struct HttpSource {
+    client: Client,
+    url: String,
+    options: HttpOptions,
+}
+
+impl HttpReader {
+    /// Connects to `url` with default options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached, does not honour
+    /// range requests, or serves a file too small to be a SAS dataset.
+    pub fn open(url: impl Into<String>) -> Result<Self> {
+        Self::with_options(url, HttpOptions::default())
+    }
+
+    /// Connects to `url`, probing its size with a one-byte range request.
+    ///
+    /// Object store URLs take the block size from `options` and otherwise
+    /// use [`ObjectStoreOptions::default`].
+    ///
+    /// # Errors
+    ///
+    /// See [`HttpReader::open`].
+    pub fn with_options(url: impl Into<String>, options: HttpOptions) -> Result<Self> {
+        let url = url.into();
+        #[cfg(feature = "object_store")]
+        if !is_http_url(&url) {
+            let store = ObjectStoreOptions::default().with_block_size(options.block_size);
+            let reader = ObjectStoreReader::with_options(&url, store)?;
+            return Ok(Self {
+                transport: Transport::ObjectStore { url, reader },
+            });
+        }
+        let client = Client::builder()
+            .timeout(options.timeout)
+            .build()
+            .map_err(io::Error::other)?;
+        let source = HttpSource {
+            client,
+            url,
+            options,
+        };
+        let len = source.fetch_range(0, 0)?.total_len;
+        if len < u64::from(SAS_HEADER_MIN_SIZE) {
+            return Err(Error::NotSasFile {
+                details: format!(
+                    "'{}' is {len} bytes, smaller than the minimum SAS header of {SAS_HEADER_MIN_SIZE} bytes",
+                    source.url
+                )
+                .into(),
+            });
+        }
+        // One block is kept: rows are read front to back, and the metadata
+        // pages are scanned before them.
+        let blocks = BlockReader::new(source, len, options.block_size, 1);
+        Ok(Self {
+            transport: Transport::Http(blocks),
+        })
+    }
+
+    #[must_use]
+    pub fn url(&self) -> &str {
+        match &self.transport {
+            Transport::Http(blocks) => &blocks.source().url,
+            #[cfg(feature = "object_store")]
+            Transport::ObjectStore { url, .. } => url,
+        }
+    }
+
+    /// Size of the remote file in bytes.
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        match &self.transport {
+            Transport::Http(blocks) => blocks.len(),
+            #[cfg(feature = "object_store")]
+            Transport::ObjectStore { reader, .. } => reader.len(),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Range requests sent so far, for tuning the block size.
+    #[must_use]
+    pub const fn requests(&self) -> u64 {
+        match &self.transport {
+            Transport::Http(blocks) => blocks.requests(),
+            #[cfg(feature = "object_store")]
+            Transport::ObjectStore { reader, .. } => reader.requests(),
+        }
+    }
+}
+
+/// Returns `true` for `http://` and `https://` URLs.
+#[cfg(feature = "object_store")]
+fn is_http_url(url: &str) -> bool {
+    url.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    })
+}
+
+impl HttpSource {
+    /// Requests bytes `start..=end`, retrying transient failures.
+    fn fetch_range(&self, start: u64, end: u64) -> io::Result<RangeBody> {
+        let mut attempt = 0;
+        loop {
+            match self.fetch_once(start, end) {
+                Ok(body) => return Ok(body),
+                Err(FetchError::Fatal(err)) => return Err(err),
+                Err(FetchError::Retryable(err)) => {
+                    if attempt >= self.options.max_retries {
+                        return Err(io::Error::new(
+                            err.kind(),
+                            format!("{} after {} attempts: {err}", self.url, attempt + 1),
+                        ));
+                    }
+                    thread::sleep(self.options.backoff(attempt));
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn fetch_once(&self, start: u64, end: u64) -> std::result::Result<RangeBody, FetchError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .header(RANGE, format!("bytes={start}-{end}"))
+            .send()
+            .map_err(|err| FetchError::Retryable(io::Error::other(err)))?;
+        let status = response.status();
+        if status != StatusCode::PARTIAL_CONTENT {
+            let err = io::Error::other(format!("{}: unexpected HTTP status {status}", self.url));
+            return Err(
+                if status == StatusCode::REQUEST_TIMEOUT
+                    || status == StatusCode::TOO_MANY_REQUESTS
+                    || status.is_server_error()
+                {
+                    FetchError::Retryable(err)
+                } else if status.is_success() {
+                    FetchError::Fatal(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        format!("{}: server does not support range requests", self.url),
+                    ))
+                } else {
+                    FetchError::Fatal(err)
+                },
+            );
+        }
+        let total_len = content_range_total(&response).ok_or_else(|| {
+            FetchError::Fatal(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: missing or invalid Content-Range header", self.url),
+            ))
+        })?;
+        let bytes = response
+            .bytes()
+            .map_err(|err| FetchError::Retryable(io::Error::other(err)))?;
+        let expected = end.min(total_len.saturating_sub(1)) + 1 - start;
+        if bytes.len() as u64 != expected {
+            return Err(FetchError::Retryable(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "{}: expected {expected} bytes at offset {start}, received {}",
+                    self.url,
+                    bytes.len()
+                ),
+            )));
+        }
+        Ok(RangeBody { total_len, bytes })
+    }
+}
+
+impl RangeFetch for HttpSource {
+    fn fetch(&mut self, range: Range<u64>) -> io::Result<Bytes> {
+        Ok(self.fetch_range(range.start, range.end - 1)?.bytes)
+    }
+}
+
+struct RangeBody {
+    total_len: u64,
+    bytes: Bytes,
+}
+
+enum FetchError {
+    Retryable(io::Error),
+    Fatal(io::Error),
+}
+
+/// Parses the total size from `Content-Range: bytes START-END/TOTAL`.
+fn content_range_total(response: &Response) -> Option<u64> {
+    let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let (_, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    total.trim().parse().ok()
+}
+
+impl Read for HttpReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.transport {
+            Transport::Http(blocks) => blocks.read(buf),
+            #[cfg(feature = "object_store")]
+            Transport::ObjectStore { reader, .. } => reader.read(buf),
+        }
+    }
+}
+
+impl Seek for HttpReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match &mut self.transport {
+            Transport::Http(blocks) => blocks.seek(pos),
+            #[cfg(feature = "object_store")]
+            Transport::ObjectStore { reader, .. } => reader.seek(pos),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use super::SasReader;
+use crate::{
+    dataset::PageIndex,
+    error::{Error, Result},
+};
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom},
+    path::Path,
+    sync::OnceLock,
+};
+
+impl SasReader<File> {
+    /// Opens a SAS7BDAT file from disk together with its page index
+    /// sidecar (see [`PageIndex::sidecar_path`]).
+    ///
+    /// A sidecar that matches the file and is not older than it is loaded;
+    /// otherwise the index is built with one pass over the page headers and
+    /// written next to the file for later opens. Failing to write the
+    /// sidecar is logged and otherwise ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened, its metadata cannot be
+    /// parsed or its pages cannot be read.
+    pub fn open_with_index<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut sas = Self::open(path)?;
+        let sidecar = PageIndex::sidecar_path(path);
+        if let Some(index) = load_sidecar(path, &sidecar)
+            && index.matches(&sas.layout)
+        {
+            sas.layout.page_index = OnceLock::from(index);
+            return Ok(sas);
+        }
+
+        let index = sas.build_index()?;
+        let written = File::create(&sidecar)
+            .map_err(Error::from)
+            .and_then(|file| index.write_to(BufWriter::new(file)));
+        if let Err(err) = written {
+            sas.layout.logger.warn(&format!(
+                "could not write page index '{}': {err}",
+                sidecar.display()
+            ));
+        }
+        Ok(sas)
+    }
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Returns the page index, building it first with one pass over the page
+    /// headers if neither a seek nor [`SasReader::set_page_index`] has
+    /// provided one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page cannot be read.
+    pub fn build_index(&mut self) -> Result<&PageIndex> {
+        if self.layout.page_index.get().is_none() {
+            self.reader.seek(SeekFrom::Start(0))?;
+            let index = self
+                .layout
+                .row_iterator(&mut self.reader)?
+                .scan_page_index()?;
+            self.reader.seek(SeekFrom::Start(0))?;
+            let _ = self.layout.page_index.set(index);
+        }
+        self.layout.page_index.get().ok_or_else(|| Error::Internal {
+            details: Cow::from("page index missing after build"),
+        })
+    }
+
+    /// Returns the page index if one has been built or loaded.
+    #[must_use]
+    pub fn page_index(&self) -> Option<&PageIndex> {
+        self.layout.page_index.get()
+    }
+
+    /// Installs a previously saved page index so seeks and windowed reads
+    /// skip the page scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] when the index was built from a
+    /// file with different page geometry or row count.
+    pub fn set_page_index(&mut self, index: PageIndex) -> Result<()> {
+        if !index.matches(&self.layout) {
+            return Err(Error::InvalidArgument {
+                details: Cow::from("page index was built from a different file"),
+            });
+        }
+        self.layout.page_index = OnceLock::from(index);
+        Ok(())
+    }
+}
+
+/// Reads the sidecar unless it is missing, unreadable or older than the
+/// dataset.
+fn load_sidecar(path: &Path, sidecar: &Path) -> Option<PageIndex> {
+    let data_modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    let index_modified = fs::metadata(sidecar)
+        .and_then(|meta| meta.modified())
+        .ok()?;
+    if index_modified < data_modified {
+        return None;
+    }
+    PageIndex::read_from(BufReader::new(File::open(sidecar).ok()?)).ok()
+}
@@ -0,0 +1,204 @@
+use super::SasReader;
+use crate::{
+    error::Result,
+    parser::{
+        DatasetLayout, MetadataReadOptions, SasHeader, format_spec::SAS7BDAT_MAGIC_NUMBER,
+        parse_header,
+    },
+};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Presents one member of a concatenated stream as a standalone file.
+///
+/// Positions are shifted by the member's starting offset so the parser
+/// sees the member header at position 0. Seeks from the end resolve against
+/// the end of the member, `header_size + page_count * page_size` bytes in,
+/// rather than the end of the stream, and seeks never move before the
+/// member's start.
+#[derive(Debug)]
+pub struct MemberReader<R> {
+    inner: R,
+    start: u64,
+    /// Read from the member header on the first seek from the end.
+    len: Option<u64>,
+}
+
+impl<R> MemberReader<R> {
+    /// Wraps `inner` so that position 0 maps to `start`.
+    pub const fn new(inner: R, start: u64) -> Self {
+        Self {
+            inner,
+            start,
+            len: None,
+        }
+    }
+
+    /// Offset of the member within the underlying stream.
+    #[must_use]
+    pub const fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for MemberReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Read + Seek> MemberReader<R> {
+    /// Byte length of the member, parsed from its header once.
+    fn member_len(&mut self) -> io::Result<u64> {
+        if let Some(len) = self.len {
+            return Ok(len);
+        }
+        let position = self.inner.stream_position()?;
+        self.inner.seek(SeekFrom::Start(self.start))?;
+        let header = parse_header(&mut self.inner);
+        self.inner.seek(SeekFrom::Start(position))?;
+        let len = header_member_len(&header.map_err(io::Error::other)?);
+        self.len = Some(len);
+        Ok(len)
+    }
+}
+
+impl<R: Read + Seek> Seek for MemberReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::End(delta) => self.member_len()?.saturating_add_signed(delta),
+            SeekFrom::Current(delta) => self
+                .inner
+                .stream_position()?
+                .saturating_sub(self.start)
+                .saturating_add_signed(delta),
+        };
+        let absolute = self
+            .inner
+            .seek(SeekFrom::Start(self.start.saturating_add(target)))?;
+        Ok(absolute.saturating_sub(self.start))
+    }
+}
+
+/// Walks the SAS7BDAT members stored back to back in a single stream.
+///
+/// Each member ends after `header_size + page_count * page_size` bytes; a
+/// further member is recognised when a dataset magic number follows. Any
+/// other trailing bytes are ignored.
+///
+/// ```no_run
+/// use sas7bdat::reader::Members;
+/// use std::fs::File;
+///
+/// let mut members = Members::new(File::open("bundle.bin")?);
+/// while let Some(mut member) = members.next_member()? {
+///     println!("{} rows", member.metadata().row_count);
+///     let mut rows = member.rows()?;
+///     while let Some(_row) = rows.try_next()? {}
+/// }
+/// # Ok::<(), sas7bdat::Error>(())
+/// ```
+pub struct Members<R: Read + Seek> {
+    inner: R,
+    options: MetadataReadOptions,
+    next_offset: Option<u64>,
+    index: usize,
+}
+
+impl<R: Read + Seek> Members<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, MetadataReadOptions::default())
+    }
+
+    /// Creates a member walker that parses each member with `options`.
+    pub const fn with_options(reader: R, options: MetadataReadOptions) -> Self {
+        Self {
+            inner: reader,
+            options,
+            next_offset: Some(0),
+            index: 0,
+        }
+    }
+
+    /// Opens the next member, or returns `None` once no further header
+    /// follows the previous member.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the member metadata cannot be parsed or if probing
+    /// for a following header fails.
+    pub fn next_member(&mut self) -> Result<Option<SasReader<MemberReader<&mut R>>>> {
+        let Some(offset) = self.next_offset.take() else {
+            return Ok(None);
+        };
+        let mut sas = SasReader::from_reader_with_options(
+            MemberReader::new(&mut self.inner, offset),
+            self.options,
+        )?;
+        self.next_offset = sas
+            .trailing_member_offset()?
+            .map(|relative| offset + relative);
+        self.index += 1;
+        Ok(Some(sas))
+    }
+
+    /// Number of members returned so far.
+    #[must_use]
+    pub const fn members_read(&self) -> usize {
+        self.index
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Byte length of this dataset as described by its header.
+    #[must_use]
+    pub fn member_len(&self) -> u64 {
+        member_len(&self.layout)
+    }
+
+    /// Returns the offset of a second dataset header that directly follows
+    /// this dataset's last page, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if seeking the underlying stream fails.
+    pub fn trailing_member_offset(&mut self) -> Result<Option<u64>> {
+        let end = self.member_len();
+        let found = has_magic_at(&mut self.reader, end)?;
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(found.then_some(end))
+    }
+}
+
+fn member_len(layout: &DatasetLayout) -> u64 {
+    header_member_len(&layout.header)
+}
+
+fn header_member_len(header: &SasHeader) -> u64 {
+    u64::from(header.header_size).saturating_add(
+        header
+            .page_count
+            .saturating_mul(u64::from(header.page_size)),
+    )
+}
+
+fn has_magic_at<R: Read + Seek>(reader: &mut R, offset: u64) -> Result<bool> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut magic = [0u8; SAS7BDAT_MAGIC_NUMBER.len()];
+    let mut filled = 0;
+    while filled < magic.len() {
+        match reader.read(&mut magic[filled..])? {
+            0 => return Ok(false),
+            read => filled += read,
+        }
+    }
+    Ok(magic == SAS7BDAT_MAGIC_NUMBER)
+}
@@ -7,6 +7,43 @@ use crate::{
 };
 use std::collections::HashSet;
 
+/// Controls how much of the dataset [`scan_missing_policies_with`] reads.
+///
+/// [`scan_missing_policies_with`]: super::SasReader::scan_missing_policies_with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MissingScanOptions {
+    max_pages: Option<u64>,
+}
+
+impl MissingScanOptions {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { max_pages: None }
+    }
+
+    /// Only scans rows stored in the first `pages` pages of the file.
+    #[must_use]
+    pub const fn max_pages(mut self, pages: u64) -> Self {
+        self.max_pages = Some(pages);
+        self
+    }
+
+    pub(crate) const fn page_limit(&self) -> Option<u64> {
+        self.max_pages
+    }
+}
+
+/// Outcome of a missing-value policy scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingScanSummary {
+    pub rows_scanned: u64,
+    pub pages_scanned: u64,
+    /// `true` when every row was inspected.
+    pub complete: bool,
+    /// `true` when the progress callback stopped the scan early.
+    pub cancelled: bool,
+}
+
 pub(super) fn merge_label_set_missing(policy: &mut MissingValuePolicy, set: &LabelSet) {
     if matches!(set.value_type, ValueType::Numeric) {
         policy.system_missing = true;
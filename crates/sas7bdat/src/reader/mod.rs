@@ -1,26 +1,63 @@
+#[cfg(feature = "http")]
+mod blocks;
+mod cancel;
+mod catalog;
+#[cfg(any(feature = "gzip", feature = "zip"))]
+mod compressed;
+mod decrypt;
+mod edit;
+mod filter;
+mod find;
+mod fingerprint;
+#[cfg(feature = "http")]
+mod http;
+mod index;
 mod labels;
+mod members;
 mod missing;
+mod partition;
+mod progress;
 mod projection;
+mod quality;
+mod read_ahead;
 mod row;
 mod selection;
+mod stats;
+#[cfg(feature = "object_store")]
+mod store;
+mod stream;
+mod validate;
 mod window;
 
 use crate::{
-    dataset::{DatasetMetadata, MissingValuePolicy},
+    dataset::{
+        BatchIter, CompressionReport, DatasetDescription, DatasetMetadata, LabelSet,
+        MissingValuePolicy, SchemaFingerprint, VariableKind,
+    },
     error::{Error, Result},
+    logger::Logger,
     parser::{
-        DatasetLayout, MetadataReadOptions, RowIterator, parse_catalog, parse_metadata,
-        parse_metadata_with_options,
+        ColumnKind, ColumnType, DatasetLayout, Diagnostics, MetadataReadOptions,
+        ParallelRowIterator, ParseWarning, RowIterator, core::platform::usize_from,
+        format_spec::SAS_HEADER_MIN_SIZE, parse_metadata, parse_metadata_with_options,
+        with_parallel_rows,
+    },
+    sinks::{
+        OutputSizeEstimate, RowSink, SampleProfile, SinkContext, SinkKind,
+        estimate_output_size_with_sample,
     },
-    sinks::{RowSink, SinkContext},
 };
+use bytes::Bytes;
 use labels::{build_label_lookup, normalize_label_name};
 use missing::{dedup_missing_ranges, dedup_tagged_missing, merge_label_set_missing};
+use progress::ProgressReporter;
 use row::RowProjection;
 use std::{
     collections::HashSet,
-    fs::File,
-    io::{Read, Seek, SeekFrom},
+    fs::{self, File},
+    io::{self, Cursor, Read, Seek, SeekFrom},
+    mem,
+    ops::ControlFlow,
     path::Path,
     sync::Arc,
 };
@@ -28,11 +65,35 @@ use std::{
 pub struct SasReader<R: Read + Seek> {
     reader: R,
     layout: DatasetLayout,
+    progress: Option<Box<dyn ProgressObserver + Send>>,
 }
 
+pub use cancel::CancellationToken;
+pub use catalog::{Catalog, CatalogConflict};
+pub use decrypt::{DecryptLayer, DecryptingReader};
+pub use edit::MetadataEditor;
+pub use filter::{CompareOp, Predicate};
+pub use find::LookupKey;
+pub use fingerprint::{
+    ContentFingerprint, DEFAULT_SAMPLE_PAGES, DuplicateGroup, DuplicateReport, find_duplicate_files,
+};
+#[cfg(feature = "http")]
+pub use http::{HttpOptions, HttpReader};
+pub use members::{MemberReader, Members};
+pub use missing::{MissingScanOptions, MissingScanSummary};
+pub use partition::{DateGranularity, DatePartition};
+pub use progress::{ProgressEvent, ProgressObserver};
 pub use projection::ProjectedRowIter;
+pub use quality::{QualityCheckOptions, QualityIssue, QualityIssueKind, QualityReport};
+pub use read_ahead::{ReadAhead, ReadAheadReader};
 pub use row::{Row, RowIter, RowLookup, RowValue, RowView, RowViewIter};
 pub use selection::RowSelection;
+pub use stats::{ColumnStats, StatValue};
+#[cfg(feature = "object_store")]
+pub use store::{ObjectStoreOptions, ObjectStoreReader};
+pub use stream::StreamReader;
+pub use validate::{IssueSeverity, ValidationIssue, ValidationReport, validate_file};
+use window::FilteredRows;
 pub use window::{ProjectedRowWindow, RowWindow};
 
 impl SasReader<File> {
@@ -41,14 +102,21 @@ impl SasReader<File> {
     /// # Errors
     ///
     /// Returns an error if the file cannot be opened or if the metadata
-    /// cannot be parsed.
+    /// cannot be parsed. Directories, FIFOs and other non-regular files, as
+    /// well as files too small to hold a SAS header, are rejected before any
+    /// parsing starts.
+    ///
+    /// The reader logs through the calling thread's current [`Logger`] with
+    /// the file path as context.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let file = File::open(path)?;
-        Self::from_reader(file)
+        Self::open_with_options(path, MetadataReadOptions::default())
     }
 
     /// Opens a SAS7BDAT file from disk with custom metadata read options.
     ///
+    /// When [`MetadataReadOptions::backfill_timestamps`] is set, absent or zero
+    /// header timestamps are filled from the file's filesystem metadata.
+    ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be opened or if the metadata
@@ -57,8 +125,119 @@ impl SasReader<File> {
         path: P,
         options: MetadataReadOptions,
     ) -> Result<Self> {
-        let file = File::open(path)?;
-        Self::from_reader_with_options(file, options)
+        let path = path.as_ref();
+        let file = open_sas_file(path)?;
+        let _logger = Logger::current()
+            .with_context(path.display().to_string())
+            .enter();
+        let file_metadata = if options.backfill_timestamps {
+            Some(file.metadata()?)
+        } else {
+            None
+        };
+        let mut sas = Self::from_reader_with_options(file, options)?;
+        if let Some(file_metadata) = file_metadata {
+            sas.layout
+                .header
+                .metadata
+                .timestamps
+                .backfill_from_file(&file_metadata);
+        }
+        Ok(sas)
+    }
+}
+
+impl<D: DecryptLayer> SasReader<DecryptingReader<File, D>> {
+    /// Opens an encrypted SAS7BDAT file from disk, decrypting pages through
+    /// `layer` as they are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened, is shorter than the
+    /// layer's header, or if the decrypted metadata cannot be parsed.
+    pub fn open_encrypted<P: AsRef<Path>>(path: P, layer: D) -> Result<Self> {
+        let path = path.as_ref();
+        let file = open_sas_file(path)?;
+        let _logger = Logger::current()
+            .with_context(path.display().to_string())
+            .enter();
+        Self::from_reader(DecryptingReader::new(file, layer)?)
+    }
+}
+
+impl SasReader<ReadAheadReader<File>> {
+    /// Opens a SAS7BDAT file from disk and reads its pages on a background
+    /// thread, `read_ahead.pages` pages ahead of the one being decoded.
+    ///
+    /// Worth it on spinning disks and network filesystems, where waiting
+    /// for the next page otherwise stalls decoding. Metadata is parsed
+    /// before the thread starts.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`SasReader::open`] and fails if the reader
+    /// thread cannot be started.
+    pub fn open_with_read_ahead<P: AsRef<Path>>(path: P, read_ahead: ReadAhead) -> Result<Self> {
+        let (file, layout) = SasReader::open(path)?.into_parts();
+        let page_size = usize_from(layout.header.page_size, "page size")?;
+        let reader = ReadAheadReader::new(file, page_size, read_ahead)?;
+        Ok(Self {
+            reader,
+            layout,
+            progress: None,
+        })
+    }
+}
+
+impl SasReader<Cursor<Bytes>> {
+    /// Reads a SAS7BDAT file that is already held in memory.
+    ///
+    /// Row iterators slice pages straight out of `data` instead of copying
+    /// them into a page buffer, so rows of uncompressed files borrow from
+    /// the input. Pass a `Vec<u8>` or [`Bytes`] to hand over the buffer
+    /// without copying it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metadata cannot be parsed.
+    pub fn from_bytes(data: impl Into<Bytes>) -> Result<Self> {
+        let data = data.into();
+        let mut sas = Self::from_reader(Cursor::new(data.clone()))?;
+        sas.layout.source = Some(data);
+        Ok(sas)
+    }
+}
+
+#[cfg(feature = "http")]
+impl SasReader<HttpReader> {
+    /// Opens a SAS7BDAT file served over HTTP(S), fetching pages with range
+    /// requests as they are read.
+    ///
+    /// With the `object_store` feature, `s3://`, `gs://` and `az://` URLs
+    /// are opened too, as by [`SasReader::open_object_store`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached or does not support
+    /// range requests, or if the metadata cannot be parsed.
+    pub fn open_url(url: &str) -> Result<Self> {
+        Self::open_url_with_options(url, HttpOptions::default(), MetadataReadOptions::default())
+    }
+
+    /// Opens a SAS7BDAT file served over HTTP(S) with custom transfer and
+    /// metadata read options.
+    ///
+    /// # Errors
+    ///
+    /// See [`SasReader::open_url`].
+    pub fn open_url_with_options(
+        url: &str,
+        http: HttpOptions,
+        options: MetadataReadOptions,
+    ) -> Result<Self> {
+        let reader = HttpReader::with_options(url, http)?;
+        let _logger = Logger::current().with_context(url).enter();
+        Self::from_reader_with_options(reader, options)
     }
 }
 
@@ -71,7 +250,11 @@ impl<R: Read + Seek> SasReader<R> {
     pub fn from_reader(mut reader: R) -> Result<Self> {
         let layout = parse_metadata(&mut reader)?;
         reader.seek(SeekFrom::Start(0))?;
-        Ok(Self { reader, layout })
+        Ok(Self {
+            reader,
+            layout,
+            progress: None,
+        })
     }
 
     /// Builds a reader from any `Read + Seek` implementor with custom metadata read options.
@@ -82,20 +265,189 @@ impl<R: Read + Seek> SasReader<R> {
     pub fn from_reader_with_options(mut reader: R, options: MetadataReadOptions) -> Result<Self> {
         let layout = parse_metadata_with_options(&mut reader, options)?;
         reader.seek(SeekFrom::Start(0))?;
-        Ok(Self { reader, layout })
+        Ok(Self {
+            reader,
+            layout,
+            progress: None,
+        })
     }
 
     pub const fn metadata(&self) -> &DatasetMetadata {
         &self.layout.header.metadata
     }
 
+    /// PROC CONTENTS-style summary of the dataset and its variables,
+    /// rendered as text or Markdown through `Display`.
+    pub const fn describe(&self) -> DatasetDescription<'_> {
+        self.layout.header.metadata.describe()
+    }
+
+    pub(crate) const fn layout(&self) -> &DatasetLayout {
+        &self.layout
+    }
+
+    /// Returns an editor for renaming variables and changing labels or
+    /// formats before rows are streamed.
+    ///
+    /// Edits apply to every iterator and sink created afterwards.
+    pub const fn edit_metadata(&mut self) -> MetadataEditor<'_> {
+        MetadataEditor::new(&mut self.layout)
+    }
+
+    /// Column names and decoded kinds, for checking that several inputs
+    /// share a schema before converting any of them.
+    #[must_use]
+    pub fn schema_fingerprint(&self) -> SchemaFingerprint {
+        SchemaFingerprint::from_layout(&self.layout)
+    }
+
+    /// Logger receiving warnings raised while reading this dataset.
+    #[must_use]
+    pub const fn logger(&self) -> &Logger {
+        &self.layout.logger
+    }
+
+    /// Corrupted structures stepped over so far, while parsing metadata and
+    /// reading rows, under
+    /// [`ParseMode::Lenient`](crate::parser::ParseMode::Lenient).
+    /// An empty list means no data was salvaged from suspect pages.
+    #[must_use]
+    pub fn parse_warnings(&self) -> Vec<ParseWarning> {
+        self.layout.issues.warnings()
+    }
+
+    /// Anomalies met so far while parsing metadata and reading rows, with the
+    /// page and subheader signature involved. Unlike the logger, which may be
+    /// shared by many files, these belong to this dataset alone.
+    #[must_use]
+    pub const fn diagnostics(&self) -> &Diagnostics {
+        self.layout.issues.diagnostics()
+    }
+
+    /// Installs an observer notified on the first row of each page, and with
+    /// the final counts at the end, by [`SasReader::stream_into`],
+    /// [`SasReader::stream_selection_into`] and
+    /// [`SasReader::scan_missing_policies`], replacing any previous one.
+    ///
+    /// Closures need their argument type spelled out:
+    /// `sas.set_progress_observer(|event: &ProgressEvent| { .. })`.
+    pub fn set_progress_observer<O>(&mut self, observer: O)
+    where
+        O: ProgressObserver + Send + 'static,
+    {
+        self.progress = Some(Box::new(observer));
+    }
+
+    /// Removes and returns the installed progress observer.
+    pub fn take_progress_observer(&mut self) -> Option<Box<dyn ProgressObserver + Send>> {
+        self.progress.take()
+    }
+
+    /// Replaces the logger used for this dataset and the sinks it feeds.
+    ///
+    /// Warnings raised while parsing metadata go to the thread's current
+    /// logger; install one with [`Logger::enter`] before opening the file to
+    /// capture those as well.
+    pub fn set_logger(&mut self, logger: Logger) {
+        self.layout.logger = logger;
+    }
+
+    /// Returns the named character columns as their raw stored bytes,
+    /// [`CellValue::Bytes`](crate::CellValue::Bytes) including trailing
+    /// padding, instead of decoding them to text.
+    ///
+    /// Skipping the encoding step saves CPU when the sink only hashes or
+    /// copies the bytes. Each call replaces the previous selection, so an
+    /// empty slice restores decoding for every column. Applies to every
+    /// iterator and sink created afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name does not match a character column.
+    pub fn set_raw_character_columns(&mut self, names: &[&str]) -> Result<()> {
+        let variables = &self.layout.header.metadata.variables;
+        let mut raw = vec![false; variables.len()];
+        for name in names {
+            let index = variables
+                .iter()
+                .position(|variable| variable.name.trim_end() == *name)
+                .ok_or_else(|| Error::InvalidArgument {
+                    details: format!("column name '{name}' not found in metadata").into(),
+                })?;
+            if !matches!(variables[index].kind, VariableKind::Character) {
+                return Err(Error::InvalidArgument {
+                    details: format!("column '{name}' is not a character column").into(),
+                });
+            }
+            raw[index] = true;
+        }
+        for ((column, variable), raw) in self.layout.columns.iter_mut().zip(variables).zip(raw) {
+            if matches!(variable.kind, VariableKind::Character) {
+                column.kind = if raw {
+                    ColumnKind::Bytes
+                } else {
+                    ColumnKind::Character
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the named column as `column_type` instead of the type
+    /// inferred from its format.
+    ///
+    /// Numeric columns are read as dates, datetimes or times when their
+    /// format name looks temporal, which misfires on identifiers stored
+    /// with such a format; [`ColumnType::Number`] reads them as plain
+    /// numbers again, [`ColumnType::Float`] as floats even when integral,
+    /// and [`ColumnType::NumericText`] as their plain decimal text. The
+    /// name is matched case-insensitively. Applies to every iterator and
+    /// sink created afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no column has the name, or if `column_type`
+    /// does not fit its storage: numeric types need a numeric column of a
+    /// width the decoder supports, and text or bytes a character column.
+    pub fn override_column_type(&mut self, name: &str, column_type: ColumnType) -> Result<()> {
+        let metadata = &self.layout.header.metadata;
+        let variables = &metadata.variables;
+        let index = metadata
+            .column_index(name)
+            .ok_or_else(|| Error::InvalidArgument {
+                details: format!("column name '{name}' not found in metadata").into(),
+            })?;
+        let column = &mut self.layout.columns[index];
+        let fits = match column.kind {
+            ColumnKind::Numeric(_) => column_type.is_numeric(),
+            ColumnKind::Character => !column_type.is_numeric(),
+            ColumnKind::Bytes => {
+                matches!(variables[index].kind, VariableKind::Character)
+                    && !column_type.is_numeric()
+            }
+        };
+        if !fits {
+            return Err(Error::InvalidArgument {
+                details: format!("column '{name}' cannot be decoded as {column_type:?}").into(),
+            });
+        }
+        column.kind = column_type.column_kind();
+        Ok(())
+    }
+
     /// Loads value-label catalog metadata from a companion file.
     ///
+    /// Formats of the catalog replace formats of the same name attached
+    /// earlier, so of several calls the last one wins; use
+    /// [`SasReader::attach_catalogs`] for `FMTSEARCH=`-style precedence.
+    /// Missing-value policies declared by the catalog are merged, but the data
+    /// is not scanned; see [`SasReader::scan_missing_policies`].
+    ///
     /// # Errors
     ///
     /// Returns an error if the catalog cannot be opened or parsed.
     pub fn attach_catalog<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        let mut file = File::open(path)?;
+        let mut file = open_sas_file(path.as_ref())?;
         self.attach_catalog_reader(&mut file)
     }
 
@@ -105,51 +457,153 @@ impl<R: Read + Seek> SasReader<R> {
     ///
     /// Returns an error if the catalog cannot be parsed.
     pub fn attach_catalog_reader<C: Read + Seek>(&mut self, reader: &mut C) -> Result<()> {
-        reader.seek(SeekFrom::Start(0))?;
-        let catalog = parse_catalog(reader)?;
+        let catalog = Catalog::from_reader(reader)?;
+        self.merge_label_sets(catalog.into_label_sets());
+        Ok(())
+    }
 
-        {
-            let metadata = &mut self.layout.header.metadata;
+    /// Loads several catalogs searched in the given order, like the SAS
+    /// `FMTSEARCH=` option: a format defined by more than one catalog is
+    /// taken from the first, so list personal catalogs before site-wide
+    /// ones. The formats found replace formats of the same name attached by
+    /// earlier calls.
+    ///
+    /// Returns one [`CatalogConflict`] per format defined more than once,
+    /// with catalogs identified by their position in `paths`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a catalog cannot be opened or parsed; nothing is
+    /// attached in that case.
+    pub fn attach_catalogs<I, P>(&mut self, paths: I) -> Result<Vec<CatalogConflict>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let catalogs = paths
+            .into_iter()
+            .map(Catalog::open)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.attach_parsed_catalogs(catalogs))
+    }
 
-            for set in catalog.label_sets {
-                metadata.label_sets.insert(set.name.clone(), set);
-            }
+    /// Attaches catalogs that are already parsed, in search order, with the
+    /// precedence of [`SasReader::attach_catalogs`].
+    pub fn attach_parsed_catalogs(
+        &mut self,
+        catalogs: impl IntoIterator<Item = Catalog>,
+    ) -> Vec<CatalogConflict> {
+        let (label_sets, conflicts) = catalog::merge_in_search_order(catalogs);
+        self.merge_label_sets(label_sets);
+        conflicts
+    }
+
+    fn merge_label_sets(&mut self, label_sets: Vec<LabelSet>) {
+        let metadata = &mut self.layout.header.metadata;
+        for set in label_sets {
+            // Names differing only in case or a trailing period denote the
+            // same format; drop the older spelling so lookups stay
+            // unambiguous.
+            let normalized = normalize_label_name(&set.name);
+            metadata
+                .label_sets
+                .retain(|name, _| normalize_label_name(name) != normalized);
+            metadata.label_sets.insert(set.name.clone(), set);
+        }
 
-            let lookup = build_label_lookup(&metadata.label_sets);
-            for variable in &mut metadata.variables {
-                if let Some(format) = &variable.format {
-                    let normalized = normalize_label_name(&format.name);
-                    if let Some(matched) = lookup.get(&normalized) {
+        let lookup = build_label_lookup(&metadata.label_sets);
+        for variable in &mut metadata.variables {
+            if let Some(format) = &variable.format {
+                let normalized = normalize_label_name(&format.name);
+                if let Some(matched) = lookup.get(&normalized) {
+                    variable.value_labels = Some(matched.clone());
+                } else if !normalized.starts_with('$') {
+                    let prefixed = format!("${normalized}");
+                    if let Some(matched) = lookup.get(&prefixed) {
                         variable.value_labels = Some(matched.clone());
-                    } else if !normalized.starts_with('$') {
-                        let prefixed = format!("${normalized}");
-                        if let Some(matched) = lookup.get(&prefixed) {
-                            variable.value_labels = Some(matched.clone());
-                        }
                     }
                 }
+            }
 
-                if let Some(label_name) = variable.value_labels.clone()
-                    && let Some(set) = metadata.label_sets.get(&label_name)
-                {
-                    merge_label_set_missing(&mut variable.missing, set);
-                }
+            if let Some(label_name) = variable.value_labels.clone()
+                && let Some(set) = metadata.label_sets.get(&label_name)
+            {
+                merge_label_set_missing(&mut variable.missing, set);
             }
         }
+    }
 
-        self.scan_missing_policies()?;
-        Ok(())
+    /// Replaces coded values with their labels from the attached catalog.
+    ///
+    /// Applies to rows materialised by [`SasReader::rows`], `rows_named`,
+    /// `rows_windowed`, `select_columns`, `select_with` and
+    /// [`SasReader::into_iter`]: a cell whose column names a label set and
+    /// whose value has a label there becomes
+    /// [`CellValue::Str`](crate::CellValue::Str) holding the label, including
+    /// labelled tagged and system missing values. Cells without a label keep
+    /// their decoded value. Streaming rows, parallel iterators and sinks
+    /// always see the codes, since their schemas follow the stored column
+    /// types. Attach the catalog first; takes effect for iterators created
+    /// afterwards.
+    pub const fn set_apply_value_labels(&mut self, enabled: bool) {
+        self.layout.apply_value_labels = enabled;
+    }
+
+    /// Populates missing-value policies by scanning every row of the dataset.
+    ///
+    /// Catalog attachment only merges policies declared by value-label sets;
+    /// call this explicitly to also record the tagged and system missing
+    /// values that actually occur in the data. Progress goes to the observer
+    /// installed with [`SasReader::set_progress_observer`], which may stop
+    /// the scan early.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row iteration fails.
+    pub fn scan_missing_policies(&mut self) -> Result<MissingScanSummary> {
+        let mut observer = self.progress.take();
+        let result = self.scan_missing(MissingScanOptions::new(), as_observer(&mut observer));
+        self.progress = observer;
+        result
     }
 
-    /// Populates missing-value policies by scanning the dataset.
+    /// Populates missing-value policies from a full or sampled scan.
+    ///
+    /// `on_progress` is invoked when the scan reaches the first row of each
+    /// page, before the rest of the page is read, and once the scan ends
+    /// with the final counts; returning
+    /// [`ControlFlow::Break`] stops the scan. Observations gathered before the
+    /// scan stopped are still merged into the variable policies.
     ///
     /// # Errors
     ///
     /// Returns an error if row iteration fails.
-    pub fn scan_missing_policies(&mut self) -> Result<()> {
+    pub fn scan_missing_policies_with<F>(
+        &mut self,
+        options: MissingScanOptions,
+        mut on_progress: F,
+    ) -> Result<MissingScanSummary>
+    where
+        F: FnMut(&ProgressEvent) -> ControlFlow<()>,
+    {
+        self.scan_missing(options, Some(&mut on_progress))
+    }
+
+    fn scan_missing(
+        &mut self,
+        options: MissingScanOptions,
+        observer: Option<&mut dyn ProgressObserver>,
+    ) -> Result<MissingScanSummary> {
+        let mut summary = MissingScanSummary {
+            rows_scanned: 0,
+            pages_scanned: 0,
+            complete: false,
+            cancelled: false,
+        };
         let variable_count = self.layout.header.metadata.variables.len();
         if variable_count == 0 {
-            return Ok(());
+            summary.complete = true;
+            return Ok(summary);
         }
 
         let mut policies: Vec<MissingValuePolicy> = self
@@ -163,15 +617,34 @@ impl<R: Read + Seek> SasReader<R> {
 
         self.reader.seek(SeekFrom::Start(0))?;
         {
+            let mut progress = ProgressReporter::new(&self.layout, observer);
             let mut rows = self.layout.row_iterator(&mut self.reader)?;
-            for row in rows.by_ref() {
-                let row = row?;
+            loop {
+                let Some(row) = rows.next().transpose()? else {
+                    summary.complete = true;
+                    progress.complete();
+                    break;
+                };
+                let pages_touched = rows.next_page_index;
+                if options
+                    .page_limit()
+                    .is_some_and(|limit| pages_touched > limit)
+                {
+                    progress.finish(progress.pages());
+                    break;
+                }
                 for (idx, value) in row.iter().enumerate() {
                     if let crate::cell::CellValue::Missing(missing) = value {
                         missing::record_missing_observation(&mut policies[idx], missing);
                     }
                 }
+                if progress.row(pages_touched).is_break() {
+                    summary.cancelled = true;
+                    break;
+                }
             }
+            summary.rows_scanned = progress.rows();
+            summary.pages_scanned = progress.pages();
         }
         self.reader.seek(SeekFrom::Start(0))?;
 
@@ -181,7 +654,7 @@ impl<R: Read + Seek> SasReader<R> {
             .metadata
             .variables
             .iter_mut()
-            .zip(policies.into_iter())
+            .zip(policies)
         {
             let mut normalized_policy = policy;
             dedup_tagged_missing(&mut normalized_policy.tagged_missing);
@@ -189,7 +662,7 @@ impl<R: Read + Seek> SasReader<R> {
             variable.missing = normalized_policy;
         }
 
-        Ok(())
+        Ok(summary)
     }
 
     /// Creates a row iterator over the dataset.
@@ -198,6 +671,27 @@ impl<R: Read + Seek> SasReader<R> {
     ///
     /// Returns an error if row iteration cannot be initialised.
     pub fn rows(&mut self) -> Result<RowIterator<'_, R>> {
+        Ok(self.coded_rows()?.with_value_labels())
+    }
+
+    /// Creates a row iterator that starts at row `row` (zero-based), skipping
+    /// the preceding pages without decoding them; see
+    /// [`crate::parser::RowIteratorCore::seek_row`]. Useful to resume an interrupted
+    /// conversion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row iteration cannot be initialised or a page
+    /// cannot be read.
+    pub fn rows_from(&mut self, row: u64) -> Result<RowIterator<'_, R>> {
+        let mut rows = self.rows()?;
+        rows.seek_row(row)?;
+        Ok(rows)
+    }
+
+    /// Creates a row iterator that yields stored codes even when value labels
+    /// are applied, for callers that rely on the decoded column types.
+    pub(crate) fn coded_rows(&mut self) -> Result<RowIterator<'_, R>> {
         self.reader.seek(SeekFrom::Start(0))?;
         self.layout.row_iterator(&mut self.reader)
     }
@@ -211,7 +705,30 @@ impl<R: Read + Seek> SasReader<R> {
         let lookup = Arc::new(row::RowLookup::from_metadata(self.metadata()));
         self.reader.seek(SeekFrom::Start(0))?;
         let iterator = self.layout.row_iterator(&mut self.reader)?;
-        Ok(RowIter::new(iterator, lookup))
+        Ok(RowIter::new(iterator.with_value_labels(), lookup))
+    }
+
+    /// Creates an iterator over owned, column-oriented batches of at most
+    /// `batch_size` rows, with missing numeric and temporal values as `None`.
+    /// A `batch_size` of zero uses the configured [`crate::BatchSizing`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row iteration cannot be initialised.
+    pub fn read_batches(&mut self, batch_size: usize) -> Result<BatchIter<'_, R>> {
+        let kinds = self
+            .layout
+            .columns
+            .iter()
+            .map(|column| column.kind)
+            .collect();
+        let total_rows = self.metadata().row_count;
+        Ok(BatchIter::new(
+            self.coded_rows()?,
+            kinds,
+            batch_size,
+            total_rows,
+        ))
     }
 
     /// Creates a streaming iterator that yields borrowed row views.
@@ -241,7 +758,7 @@ impl<R: Read + Seek> SasReader<R> {
         let indices =
             selection
                 .resolve_projection(metadata)?
-                .ok_or_else(|| Error::InvalidMetadata {
+                .ok_or_else(|| Error::InvalidArgument {
                     details: "column projection not specified".into(),
                 })?;
         let normalized = self.normalize_projection(&indices)?;
@@ -255,7 +772,9 @@ impl<R: Read + Seek> SasReader<R> {
     /// Creates a row iterator configured by the provided selection.
     ///
     /// This method is intended for pagination without column projection. Use
-    /// [`select_with`] when selecting a subset of columns.
+    /// [`select_with`] when selecting a subset of columns. Once a page index
+    /// is available (see [`SasReader::build_index`]), unfiltered windows
+    /// start at the first kept row without decoding the skipped ones.
     ///
     /// # Errors
     ///
@@ -263,16 +782,24 @@ impl<R: Read + Seek> SasReader<R> {
     /// cannot be positioned, or if row iteration cannot be initialised.
     pub fn rows_windowed(&mut self, selection: &RowSelection) -> Result<RowWindow<'_, R>> {
         if selection.has_projection() {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: "rows_windowed does not accept column projection; use select_with instead"
                     .into(),
             });
         }
+        let filter = selection.resolve_filter(&self.layout.header.metadata)?;
         self.reader.seek(SeekFrom::Start(0))?;
-        let iterator = self.layout.row_iterator(&mut self.reader)?;
+        let mut iterator = self.layout.row_iterator(&mut self.reader)?;
+        let mut skip = selection.skip_count();
+        // With a page index, skipped rows need not be decoded unless a
+        // filter decides which rows count.
+        if filter.is_none() && skip > 0 && self.layout.page_index.get().is_some() {
+            iterator.seek_row(skip)?;
+            skip = 0;
+        }
         Ok(RowWindow::new(
-            iterator,
-            selection.skip_count(),
+            FilteredRows::new(iterator.with_value_labels(), filter),
+            skip,
             selection.max_count(),
         ))
     }
@@ -286,24 +813,23 @@ impl<R: Read + Seek> SasReader<R> {
     pub fn select_columns(&mut self, indices: &[usize]) -> Result<ProjectedRowIter<'_, R>> {
         let normalized = self.normalize_projection(indices)?;
         self.reader.seek(SeekFrom::Start(0))?;
-        let inner = self.layout.row_iterator(&mut self.reader)?;
-        let mut sorted_projection: Vec<(usize, usize)> = normalized
-            .iter()
-            .copied()
-            .enumerate()
-            .map(|(position, column_index)| (column_index, position))
-            .collect();
-        sorted_projection.sort_unstable_by_key(|entry| entry.0);
+        let inner = self
+            .layout
+            .row_iterator(&mut self.reader)?
+            .with_value_labels()
+            .with_projection(&normalized)?;
         Ok(ProjectedRowIter {
             inner,
-            selected_indices: normalized,
-            sorted_projection,
             exhausted: false,
+            filter: None,
         })
     }
 
     /// Creates an iterator configured by selection with column projection.
     ///
+    /// Skipped rows are passed over without decoding once a page index is
+    /// available, as in [`SasReader::rows_windowed`].
+    ///
     /// # Errors
     ///
     /// Returns an error when projection cannot be resolved or row decoding fails.
@@ -312,13 +838,21 @@ impl<R: Read + Seek> SasReader<R> {
         let indices =
             selection
                 .resolve_projection(metadata)?
-                .ok_or_else(|| Error::InvalidMetadata {
+                .ok_or_else(|| Error::InvalidArgument {
                     details: "column projection not specified".into(),
                 })?;
-        let projected = self.select_columns(&indices)?;
+        let filter = selection.resolve_filter(metadata)?;
+        let indexed = self.layout.page_index.get().is_some();
+        let mut projected = self.select_columns(&indices)?;
+        let mut skip = selection.skip_count();
+        if filter.is_none() && skip > 0 && indexed {
+            projected.inner.seek_row(skip)?;
+            skip = 0;
+        }
+        projected.filter = filter;
         Ok(ProjectedRowWindow::new(
             projected,
-            selection.skip_count(),
+            skip,
             selection.max_count(),
         ))
     }
@@ -334,7 +868,7 @@ impl<R: Read + Seek> SasReader<R> {
         let indices =
             selection
                 .resolve_projection(metadata)?
-                .ok_or_else(|| Error::InvalidMetadata {
+                .ok_or_else(|| Error::InvalidArgument {
                     details: "column projection not specified".into(),
                 })?;
         self.select_columns(&indices)
@@ -342,20 +876,274 @@ impl<R: Read + Seek> SasReader<R> {
 
     /// Streams the full dataset into a custom sink implementation.
     ///
+    /// Progress goes to the observer installed with
+    /// [`SasReader::set_progress_observer`]. When it stops the conversion,
+    /// the sink is finished with the rows written so far.
+    ///
     /// # Errors
     ///
     /// Returns an error if row decoding fails or if the sink reports a failure.
     pub fn stream_into<S: RowSink>(&mut self, sink: &mut S) -> Result<()> {
+        self.stream_rows_into(sink, None)
+    }
+
+    /// Streams the full dataset into `sink` until `token` is cancelled.
+    ///
+    /// The token is checked before every row. Once it is cancelled, the sink
+    /// is [aborted](RowSink::abort) instead of finished, leaving its output
+    /// valid but holding only the rows read so far, and
+    /// [`Error::Cancelled`] is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] when cancelled, and otherwise the errors
+    /// of [`SasReader::stream_into`].
+    pub fn stream_into_with_cancel<S: RowSink>(
+        &mut self,
+        sink: &mut S,
+        token: &CancellationToken,
+    ) -> Result<()> {
+        self.stream_rows_into(sink, Some(token))
+    }
+
+    fn stream_rows_into<S: RowSink>(
+        &mut self,
+        sink: &mut S,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<()> {
         self.reader.seek(SeekFrom::Start(0))?;
         let context = SinkContext::new(&self.layout);
         sink.begin(context)?;
+        let mut progress = ProgressReporter::new(&self.layout, as_observer(&mut self.progress));
         let mut iterator = self.layout.row_iterator(&mut self.reader)?;
-        iterator.stream_all(|row| sink.write_streaming_row(row))?;
+        loop {
+            check_cancel(cancel, sink)?;
+            if iterator
+                .try_next_streaming(&mut |row| sink.write_streaming_row(row))?
+                .is_none()
+            {
+                progress.complete();
+                break;
+            }
+            if progress.row(iterator.next_page_index).is_break() {
+                break;
+            }
+        }
+        sink.finish()?;
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(())
+    }
+
+    /// Streams the full dataset into `sink` in low-memory mode.
+    ///
+    /// Every row reaches the sink as a [`StreamingRow`](crate::parser::StreamingRow)
+    /// borrowing the page buffer, so no per-row `Vec<CellValue>` is built and
+    /// memory stays flat however many rows the file holds. The sink must
+    /// declare this via [`RowSink::streams_borrowed_rows`]; the check runs
+    /// before anything is read, so a sink that would fall back to
+    /// materialising rows is rejected up front.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unsupported`] if the sink does not stream borrowed
+    /// rows, and otherwise the errors of [`SasReader::stream_into`].
+    pub fn stream_into_low_memory<S: RowSink>(&mut self, sink: &mut S) -> Result<()> {
+        if !sink.streams_borrowed_rows() {
+            return Err(Error::Unsupported {
+                feature: format!(
+                    "low-memory streaming into {}, which materialises rows",
+                    std::any::type_name::<S>()
+                )
+                .into(),
+            });
+        }
+        self.stream_into(sink)
+    }
+
+    /// Streams the rows and columns picked by `selection` into `sink`.
+    ///
+    /// The sink sees only the projected columns, in selection order, and only
+    /// the rows left after filters, `skip_rows` and `max_rows`. Progress is
+    /// reported as in [`SasReader::stream_into`], counting the rows written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selection cannot be resolved, row decoding
+    /// fails, or the sink reports a failure.
+    pub fn stream_selection_into<S: RowSink>(
+        &mut self,
+        selection: &RowSelection,
+        sink: &mut S,
+    ) -> Result<()> {
+        self.stream_selection(selection, sink, None)
+    }
+
+    /// Streams the rows and columns picked by `selection` into `sink` until
+    /// `token` is cancelled, aborting the sink as
+    /// [`SasReader::stream_into_with_cancel`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] when cancelled, and otherwise the errors
+    /// of [`SasReader::stream_selection_into`].
+    pub fn stream_selection_into_with_cancel<S: RowSink>(
+        &mut self,
+        selection: &RowSelection,
+        sink: &mut S,
+        token: &CancellationToken,
+    ) -> Result<()> {
+        self.stream_selection(selection, sink, Some(token))
+    }
+
+    fn stream_selection<S: RowSink>(
+        &mut self,
+        selection: &RowSelection,
+        sink: &mut S,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<()> {
+        // Sinks build their schema from the column types, so they get codes.
+        let labels = mem::replace(&mut self.layout.apply_value_labels, false);
+        let mut observer = self.progress.take();
+        let result = self.write_selection(selection, sink, as_observer(&mut observer), cancel);
+        self.progress = observer;
+        self.layout.apply_value_labels = labels;
+        result
+    }
+
+    fn write_selection<S: RowSink>(
+        &mut self,
+        selection: &RowSelection,
+        sink: &mut S,
+        observer: Option<&mut dyn ProgressObserver>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<()> {
+        let mut progress = ProgressReporter::new(&self.layout, observer);
+        let Some(indices) = selection.resolve_projection(&self.layout.header.metadata)? else {
+            sink.begin(SinkContext::new(&self.layout))?;
+            let mut rows = self.rows_windowed(selection)?;
+            loop {
+                let Some(row) = rows.try_next()? else {
+                    progress.finish(rows.pages_read());
+                    break;
+                };
+                check_cancel(cancel, sink)?;
+                sink.write_row(&row)?;
+                if progress.row(rows.pages_read()).is_break() {
+                    break;
+                }
+            }
+            sink.finish()?;
+            self.reader.seek(SeekFrom::Start(0))?;
+            return Ok(());
+        };
+
+        let indices = self.normalize_projection(&indices)?;
+        let source = &self.layout.header.metadata;
+        let mut metadata = source.clone();
+        metadata.column_list.clear();
+        metadata.variables = Vec::with_capacity(indices.len());
+        let mut columns = Vec::with_capacity(indices.len());
+        for (position, &index) in indices.iter().enumerate() {
+            let position = u32::try_from(position).map_err(|_| Error::Limit {
+                details: "projected column count exceeds u32 range".into(),
+            })?;
+            let mut variable = source.variables[index].clone();
+            variable.index = position;
+            let mut column = self.layout.columns[index].clone();
+            column.index = position;
+            metadata.variables.push(variable);
+            columns.push(column);
+        }
+        metadata.column_count = u32::try_from(columns.len()).map_err(|_| Error::Limit {
+            details: "projected column count exceeds u32 range".into(),
+        })?;
+        sink.begin(SinkContext {
+            metadata: &metadata,
+            columns: &columns,
+            source_path: None,
+            logger: self.layout.logger.clone(),
+        })?;
+
+        let mut rows = self.select_with(selection)?;
+        loop {
+            let Some(row) = rows.try_next()? else {
+                progress.finish(rows.pages_read());
+                break;
+            };
+            check_cancel(cancel, sink)?;
+            sink.write_row(&row)?;
+            if progress.row(rows.pages_read()).is_break() {
+                break;
+            }
+        }
         sink.finish()?;
         self.reader.seek(SeekFrom::Start(0))?;
         Ok(())
     }
 
+    /// Measures the stored and expanded size of the rows on every data page
+    /// without decoding values, to judge how well RLE/RDC compression works
+    /// on this file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page cannot be read.
+    pub fn compression_report(&mut self) -> Result<CompressionReport> {
+        self.reader.seek(SeekFrom::Start(0))?;
+        let mut pages = Vec::new();
+        {
+            let mut iterator = self.layout.row_iterator(&mut self.reader)?;
+            while let Some(page) = iterator.scan_next_page_compression()? {
+                pages.push(page);
+            }
+        }
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(CompressionReport {
+            compression: self.layout.row_info.compression,
+            row_length: u64::from(self.layout.row_info.row_length),
+            pages,
+        })
+    }
+
+    /// Decodes up to `max_rows` leading rows and records value lengths, missing
+    /// rates, and string cardinality for output size estimation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row decoding fails.
+    pub fn sample_output_profile(&mut self, max_rows: usize) -> Result<SampleProfile> {
+        let mut profile = SampleProfile::new(self.layout.header.metadata.variables.len());
+        self.reader.seek(SeekFrom::Start(0))?;
+        {
+            let mut rows = self.layout.row_iterator(&mut self.reader)?;
+            while profile.rows < max_rows as u64 {
+                let Some(row) = rows.try_next()? else { break };
+                profile.observe(&row);
+            }
+        }
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(profile)
+    }
+
+    /// Predicts the size of converting this dataset to `kind`, calibrated on
+    /// the first `sample_rows` rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sampling the leading rows fails.
+    pub fn estimate_output_size(
+        &mut self,
+        kind: SinkKind,
+        sample_rows: usize,
+    ) -> Result<OutputSizeEstimate> {
+        let profile = self.sample_output_profile(sample_rows)?;
+        Ok(estimate_output_size_with_sample(
+            self.metadata(),
+            kind,
+            &profile,
+        ))
+    }
+
     /// Consumes the reader and returns a row iterator yielding owned rows.
     ///
     /// # Errors
@@ -367,6 +1155,56 @@ impl<R: Read + Seek> SasReader<R> {
         let mut reader = self.reader;
         reader.seek(SeekFrom::Start(0))?;
         crate::parser::RowIteratorCore::new(reader, layout)
+            .map(crate::parser::RowIteratorCore::with_value_labels)
+    }
+
+    /// Consumes the reader and returns a [`ParallelRowIterator`] that reads
+    /// `pages_per_batch` pages ahead on a background thread and decodes them
+    /// on the Rayon pool; `0` picks a batch size from the pool size.
+    ///
+    /// Rows come back in file order, as with [`SasReader::into_iter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row iteration cannot be initialised.
+    pub fn into_parallel_iter(self, pages_per_batch: usize) -> Result<ParallelRowIterator>
+    where
+        R: Send + 'static,
+    {
+        ParallelRowIterator::new(self.reader, Box::new(self.layout), pages_per_batch)
+    }
+
+    /// Streams the full dataset into `sink`, decoding `pages_per_batch` pages
+    /// at a time in parallel while the next batch is read.
+    ///
+    /// The sink still sees every row in file order on the calling thread;
+    /// only page decompression and cell decoding are spread across the Rayon
+    /// pool. Prefer this over [`SasReader::stream_into`] for large files where
+    /// decoding, not the sink, is the bottleneck.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if row decoding fails or if the sink reports a failure.
+    pub fn stream_into_parallel<S: RowSink>(
+        &mut self,
+        sink: &mut S,
+        pages_per_batch: usize,
+    ) -> Result<()>
+    where
+        R: Send,
+    {
+        sink.begin(SinkContext::new(&self.layout))?;
+        with_parallel_rows(&mut self.reader, &self.layout, pages_per_batch, |rows| {
+            while let Some(batch) = rows.next_batch()? {
+                for row in &batch {
+                    sink.write_row(row)?;
+                }
+            }
+            Ok(())
+        })?;
+        sink.finish()?;
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(())
     }
 
     pub fn into_parts(self) -> (R, DatasetLayout) {
@@ -376,7 +1214,7 @@ impl<R: Read + Seek> SasReader<R> {
     fn normalize_projection(&self, indices: &[usize]) -> Result<Vec<usize>> {
         let column_count = self.layout.header.metadata.column_count as usize;
         if indices.is_empty() {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: "projected column list may not be empty".into(),
             });
         }
@@ -384,7 +1222,7 @@ impl<R: Read + Seek> SasReader<R> {
         let mut seen = HashSet::with_capacity(indices.len());
         for &idx in indices {
             if idx >= column_count {
-                return Err(Error::InvalidMetadata {
+                return Err(Error::InvalidArgument {
                     details: format!(
                         "column projection index {idx} exceeds column count {column_count}"
                     )
@@ -392,7 +1230,7 @@ impl<R: Read + Seek> SasReader<R> {
                 });
             }
             if !seen.insert(idx) {
-                return Err(Error::InvalidMetadata {
+                return Err(Error::InvalidArgument {
                     details: format!("duplicate column projection index {idx}").into(),
                 });
             }
@@ -401,3 +1239,66 @@ impl<R: Read + Seek> SasReader<R> {
         Ok(normalized)
     }
 }
+
+/// Opens `path` after checking that it is a regular file large enough to
+/// hold a SAS header, so callers get a precise error instead of a short read.
+fn open_sas_file(path: &Path) -> Result<File> {
+    let file = open_regular_file(path)?;
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Err(Error::NotSasFile {
+            details: format!("'{}' is empty", path.display()).into(),
+        });
+    }
+    if len < u64::from(SAS_HEADER_MIN_SIZE) {
+        return Err(Error::NotSasFile {
+            details: format!(
+                "'{}' is {len} bytes, smaller than the minimum SAS header of {SAS_HEADER_MIN_SIZE} bytes",
+                path.display()
+            )
+            .into(),
+        });
+    }
+    Ok(file)
+}
+
+/// Opens `path`, rejecting directories, FIFOs and other files that cannot
+/// be seeked in.
+fn open_regular_file(path: &Path) -> Result<File> {
+    let file_type = fs::metadata(path)?.file_type();
+    if file_type.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::IsADirectory,
+            format!("'{}' is a directory, not a SAS file", path.display()),
+        )
+        .into());
+    }
+    if !file_type.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "'{}' is not a regular file; SAS files must be seekable",
+                path.display()
+            ),
+        )
+        .into());
+    }
+    Ok(File::open(path)?)
+}
+
+fn as_observer(
+    observer: &mut Option<Box<dyn ProgressObserver + Send>>,
+) -> Option<&mut dyn ProgressObserver> {
+    observer
+        .as_deref_mut()
+        .map(|observer| observer as &mut dyn ProgressObserver)
+}
+
+/// Aborts `sink` and fails with [`Error::Cancelled`] once `cancel` is set.
+fn check_cancel<S: RowSink>(cancel: Option<&CancellationToken>, sink: &mut S) -> Result<()> {
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        sink.abort()?;
+        return Err(Error::Cancelled);
+    }
+    Ok(())
+}
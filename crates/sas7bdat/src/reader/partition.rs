@@ -0,0 +1,120 @@
+use super::{SasReader, row::RowLookup};
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    parser::{ColumnKind, NumericKind},
+    sinks::{RowSink, SinkContext},
+};
+use std::{
+    collections::{BTreeMap, btree_map::Entry},
+    fmt,
+    io::{Read, Seek, SeekFrom},
+};
+use time::OffsetDateTime;
+
+/// Period length used by [`SasReader::export_partitioned_by_date`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateGranularity {
+    Year,
+    Month,
+}
+
+/// Period a row was routed to; displays as `2021` or `2021-03`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DatePartition {
+    pub year: i32,
+    /// `1..=12` for [`DateGranularity::Month`], `None` for years.
+    pub month: Option<u8>,
+}
+
+impl DatePartition {
+    fn of(datetime: OffsetDateTime, granularity: DateGranularity) -> Self {
+        Self {
+            year: datetime.year(),
+            month: match granularity {
+                DateGranularity::Year => None,
+                DateGranularity::Month => Some(u8::from(datetime.month())),
+            },
+        }
+    }
+}
+
+impl fmt::Display for DatePartition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.month {
+            Some(month) => write!(f, "{:04}-{month:02}", self.year),
+            None => write!(f, "{:04}", self.year),
+        }
+    }
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Streams every row into one sink per year or month of a date column,
+    /// in a single pass.
+    ///
+    /// `sink_factory` is called the first time a period is seen and the sink
+    /// it returns is begun with the full dataset context; rows keep their
+    /// file order within each sink. Rows whose date is missing or outside
+    /// the supported range are routed to the `None` partition. Every sink is
+    /// finished before the map of partitions to sinks is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if `column` does not exist or is
+    /// not a date or datetime column, and otherwise any error raised by the
+    /// factory, a sink, or while reading rows.
+    pub fn export_partitioned_by_date<S, F>(
+        &mut self,
+        column: &str,
+        granularity: DateGranularity,
+        mut sink_factory: F,
+    ) -> Result<BTreeMap<Option<DatePartition>, S>>
+    where
+        S: RowSink,
+        F: FnMut(Option<DatePartition>) -> Result<S>,
+    {
+        let index = RowLookup::from_metadata(self.metadata())
+            .index(column)
+            .ok_or_else(|| Error::InvalidArgument {
+                details: format!("unknown column '{column}'").into(),
+            })?;
+        if !matches!(
+            self.layout.columns[index].kind,
+            ColumnKind::Numeric(NumericKind::Date | NumericKind::DateTime)
+        ) {
+            return Err(Error::InvalidArgument {
+                details: format!("partition column '{column}' is not a date or datetime column")
+                    .into(),
+            });
+        }
+
+        let mut sinks = BTreeMap::new();
+        self.reader.seek(SeekFrom::Start(0))?;
+        {
+            let layout = &self.layout;
+            let mut iterator = layout.row_iterator(&mut self.reader)?;
+            iterator.stream_all(|row| {
+                let partition = match row.cell(index)?.decode_value()? {
+                    CellValue::Date(datetime) | CellValue::DateTime(datetime) => {
+                        Some(DatePartition::of(datetime, granularity))
+                    }
+                    _ => None,
+                };
+                let sink = match sinks.entry(partition) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let mut sink = sink_factory(partition)?;
+                        sink.begin(SinkContext::new(layout))?;
+                        entry.insert(sink)
+                    }
+                };
+                sink.write_streaming_row(row)
+            })?;
+        }
+        for sink in sinks.values_mut() {
+            sink.finish()?;
+        }
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(sinks)
+    }
+}
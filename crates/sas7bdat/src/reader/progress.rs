@@ -0,0 +1,225 @@
+use crate::parser::DatasetLayout;
+use std::{
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+/// Receives [`ProgressEvent`]s from long-running reads.
+///
+/// Install one with [`SasReader::set_progress_observer`](super::SasReader::set_progress_observer)
+/// to follow [`stream_into`](super::SasReader::stream_into),
+/// [`stream_selection_into`](super::SasReader::stream_selection_into) and
+/// [`scan_missing_policies`](super::SasReader::scan_missing_policies). Any
+/// `FnMut(&ProgressEvent) -> ControlFlow<()>` closure is an observer.
+pub trait ProgressObserver {
+    /// Called when the read reaches the first row of each page of rows, so
+    /// the event already counts that row, and once more with the final
+    /// counts when the rows run out; returning [`ControlFlow::Break`] stops
+    /// the operation early.
+    fn on_progress(&mut self, event: &ProgressEvent) -> ControlFlow<()>;
+}
+
+impl<F> ProgressObserver for F
+where
+    F: FnMut(&ProgressEvent) -> ControlFlow<()>,
+{
+    fn on_progress(&mut self, event: &ProgressEvent) -> ControlFlow<()> {
+        self(event)
+    }
+}
+
+/// Snapshot reported to progress callbacks as each page of rows is entered.
+///
+/// Rates are instantaneous, measured since the previous event. The ETA is
+/// derived from the average byte throughput since processing started, which
+/// stays meaningful when the row count in the header cannot be trusted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct ProgressEvent {
+    pub rows_processed: u64,
+    pub pages_processed: u64,
+    /// Zero-based index of the page the most recent row was read from.
+    pub page_index: u64,
+    pub total_rows: u64,
+    pub total_pages: u64,
+    /// Bytes of the file consumed so far, including the header.
+    pub bytes_processed: u64,
+    pub total_bytes: u64,
+    pub elapsed: Duration,
+    pub rows_per_sec: f64,
+    pub bytes_per_sec: f64,
+    /// Estimated time until every page has been read, or `None` before any
+    /// throughput has been measured.
+    pub eta: Option<Duration>,
+}
+
+impl ProgressEvent {
+    /// Fraction of the file consumed, in `0.0..=1.0`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 1.0;
+        }
+        (self.bytes_processed as f64 / self.total_bytes as f64).min(1.0)
+    }
+}
+
+/// Turns row and page counters into [`ProgressEvent`]s with throughput figures.
+pub(super) struct ThroughputTracker {
+    started: Instant,
+    last_at: Instant,
+    last_rows: u64,
+    last_bytes: u64,
+    total_rows: u64,
+    total_pages: u64,
+    header_bytes: u64,
+    page_size: u64,
+}
+
+impl ThroughputTracker {
+    pub(super) fn new(layout: &DatasetLayout) -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            last_at: now,
+            last_rows: 0,
+            last_bytes: 0,
+            total_rows: layout.row_info.total_rows,
+            total_pages: layout.header.page_count,
+            header_bytes: u64::from(layout.header.header_size),
+            page_size: u64::from(layout.header.page_size),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub(super) fn event(&mut self, rows_processed: u64, pages_processed: u64) -> ProgressEvent {
+        let now = Instant::now();
+        let total_bytes = self
+            .header_bytes
+            .saturating_add(self.total_pages.saturating_mul(self.page_size));
+        let bytes_processed = self
+            .header_bytes
+            .saturating_add(pages_processed.saturating_mul(self.page_size))
+            .min(total_bytes);
+
+        let interval = now.duration_since(self.last_at).as_secs_f64();
+        let rate = |delta: u64| {
+            if interval > 0.0 {
+                delta as f64 / interval
+            } else {
+                0.0
+            }
+        };
+        let rows_per_sec = rate(rows_processed.saturating_sub(self.last_rows));
+        let bytes_per_sec = rate(bytes_processed.saturating_sub(self.last_bytes));
+
+        let elapsed = now.duration_since(self.started);
+        let average_bytes_per_sec = if elapsed.is_zero() {
+            0.0
+        } else {
+            bytes_processed as f64 / elapsed.as_secs_f64()
+        };
+        let remaining = total_bytes.saturating_sub(bytes_processed);
+        let eta = if remaining == 0 {
+            Some(Duration::ZERO)
+        } else if average_bytes_per_sec > 0.0 {
+            Duration::try_from_secs_f64(remaining as f64 / average_bytes_per_sec).ok()
+        } else {
+            None
+        };
+
+        self.last_at = now;
+        self.last_rows = rows_processed;
+        self.last_bytes = bytes_processed;
+
+        ProgressEvent {
+            rows_processed,
+            pages_processed,
+            page_index: pages_processed.saturating_sub(1),
+            total_rows: self.total_rows,
+            total_pages: self.total_pages,
+            bytes_processed,
+            total_bytes,
+            elapsed,
+            rows_per_sec,
+            bytes_per_sec,
+            eta,
+        }
+    }
+}
+
+/// Feeds an observer one event per page from a row-by-row loop, plus a final
+/// one once the rows run out.
+pub(super) struct ProgressReporter<'o> {
+    /// Only started with an observer, so reads without one never query the
+    /// clock, which `wasm32-unknown-unknown` does not provide.
+    tracker: Option<ThroughputTracker>,
+    observer: Option<&'o mut dyn ProgressObserver>,
+    rows: u64,
+    pages: u64,
+    total_pages: u64,
+    /// Rows and pages carried by the last event sent.
+    reported: (u64, u64),
+}
+
+impl<'o> ProgressReporter<'o> {
+    pub(super) fn new(
+        layout: &DatasetLayout,
+        observer: Option<&'o mut dyn ProgressObserver>,
+    ) -> Self {
+        Self {
+            tracker: observer.is_some().then(|| ThroughputTracker::new(layout)),
+            observer,
+            rows: 0,
+            pages: 0,
+            total_pages: layout.header.page_count,
+            reported: (0, 0),
+        }
+    }
+
+    pub(super) const fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    pub(super) const fn pages(&self) -> u64 {
+        self.pages
+    }
+
+    /// Counts one more row read from `pages_touched` pages, reporting when
+    /// the row starts a new page.
+    pub(super) fn row(&mut self, pages_touched: u64) -> ControlFlow<()> {
+        self.rows += 1;
+        if pages_touched == self.pages {
+            return ControlFlow::Continue(());
+        }
+        self.pages = pages_touched;
+        self.report()
+    }
+
+    /// Reports the rows counted after the last event once the read stops
+    /// with `pages_touched` pages consumed. Nothing is sent when the last
+    /// event already carried these counts.
+    pub(super) fn finish(&mut self, pages_touched: u64) {
+        self.pages = self.pages.max(pages_touched);
+        if (self.rows, self.pages) != self.reported {
+            // The read is over, so there is nothing left for a break to stop.
+            let _ = self.report();
+        }
+    }
+
+    /// Like [`finish`](Self::finish) for a read that went through the whole
+    /// file, so the final event accounts for every page.
+    pub(super) fn complete(&mut self) {
+        self.finish(self.total_pages);
+    }
+
+    fn report(&mut self) -> ControlFlow<()> {
+        self.reported = (self.rows, self.pages);
+        let (Some(observer), Some(tracker)) = (self.observer.as_deref_mut(), &mut self.tracker)
+        else {
+            return ControlFlow::Continue(());
+        };
+        observer.on_progress(&tracker.event(self.rows, self.pages))
+    }
+}
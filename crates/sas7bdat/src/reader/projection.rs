@@ -1,16 +1,13 @@
-use crate::{
-    cell::CellValue,
-    error::{Error, Result},
-    iter_utils::next_from_result,
-    parser::RowIterator,
-};
+use super::filter::ResolvedFilter;
+use crate::{cell::CellValue, error::Result, iter_utils::next_from_result, parser::RowIterator};
 use std::io::{Read, Seek};
 
+/// Rows holding a subset of the columns. Projection happens while decoding,
+/// so unselected cells are never decoded.
 pub struct ProjectedRowIter<'a, R: Read + Seek> {
     pub(crate) inner: RowIterator<'a, R>,
-    pub(crate) selected_indices: Vec<usize>,
-    pub(crate) sorted_projection: Vec<(usize, usize)>,
     pub(crate) exhausted: bool,
+    pub(super) filter: Option<ResolvedFilter>,
 }
 
 impl<R: Read + Seek> ProjectedRowIter<'_, R> {
@@ -18,69 +15,25 @@ impl<R: Read + Seek> ProjectedRowIter<'_, R> {
     ///
     /// # Errors
     ///
-    /// Returns an error if row decoding fails or if a requested column is
-    /// missing from the row data.
+    /// Returns an error if row decoding fails.
     pub fn try_next(&mut self) -> Result<Option<Vec<CellValue<'static>>>> {
         if self.exhausted {
             return Ok(None);
         }
-        let maybe_row = match self.inner.try_next() {
-            Ok(value) => value,
-            Err(err) => {
-                self.exhausted = true;
-                return Err(err);
-            }
+        let next = match &self.filter {
+            Some(filter) => self.inner.try_next_matching(&mut |row| filter.matches(row)),
+            None => self.inner.try_next(),
         };
-        if let Some(row) = maybe_row {
-            let mut slots: Vec<Option<CellValue<'static>>> =
-                vec![None; self.selected_indices.len()];
-            let mut sorted_pos = 0usize;
-            let sorted_len = self.sorted_projection.len();
-            let mut filled = 0usize;
-            for (column_index, value) in row.into_iter().enumerate() {
-                if sorted_pos < sorted_len {
-                    let (target_index, result_position) = self.sorted_projection[sorted_pos];
-                    if target_index < column_index {
-                        return Err(Error::InvalidMetadata {
-                            details: format!(
-                                "projected column index {target_index} missing from row data"
-                            )
-                            .into(),
-                        });
-                    }
-                    if target_index == column_index {
-                        slots[result_position] = Some(value.into_owned());
-                        sorted_pos += 1;
-                        filled += 1;
-                        if filled == sorted_len {
-                            break;
-                        }
-                        continue;
-                    }
-                }
-                if filled == sorted_len {
-                    break;
-                }
-            }
-            if filled != sorted_len {
-                return Err(Error::InvalidMetadata {
-                    details: "row did not contain all projected columns".into(),
-                });
+        match next {
+            Ok(Some(row)) => Ok(Some(row.into_iter().map(CellValue::into_owned).collect())),
+            Ok(None) => {
+                self.exhausted = true;
+                Ok(None)
             }
-            let mut projected = Vec::with_capacity(self.selected_indices.len());
-            for slot in slots {
-                if let Some(value) = slot {
-                    projected.push(value);
-                } else {
-                    return Err(Error::InvalidMetadata {
-                        details: "projected column resolved to empty slot".into(),
-                    });
-                }
+            Err(err) => {
+                self.exhausted = true;
+                Err(err)
             }
-            Ok(Some(projected))
-        } else {
-            self.exhausted = true;
-            Ok(None)
         }
     }
 }
@@ -0,0 +1,219 @@
+use super::SasReader;
+use crate::{cell::CellValue, dataset::VariableKind, error::Result, parser::ColumnKind};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Kind of problem reported by [`SasReader::check_data_quality`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QualityIssueKind {
+    /// Character bytes that are neither UTF-8 nor valid in the dataset
+    /// encoding.
+    InvalidEncoding,
+    /// A character value longer than the width of its declared format.
+    ExceedsDeclaredWidth,
+    /// A NaN or infinity that is not one of the SAS missing codes `.`, `._`
+    /// or `.A`-`.Z`.
+    NonCanonicalMissing,
+    /// The row buffer ends before the column's declared offset and width.
+    TruncatedRow,
+}
+
+impl QualityIssueKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidEncoding => "invalid-encoding",
+            Self::ExceedsDeclaredWidth => "exceeds-declared-width",
+            Self::NonCanonicalMissing => "non-canonical-missing",
+            Self::TruncatedRow => "truncated-row",
+        }
+    }
+}
+
+/// One suspect cell found by a data-quality pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualityIssue {
+    /// Zero-based row index in file order.
+    pub row: u64,
+    /// Zero-based column index.
+    pub column: usize,
+    pub kind: QualityIssueKind,
+    pub detail: String,
+}
+
+/// Outcome of [`SasReader::check_data_quality`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QualityReport {
+    pub rows_scanned: u64,
+    /// Issues in row order, capped at [`QualityCheckOptions::max_issues`].
+    pub issues: Vec<QualityIssue>,
+    /// `true` when more issues were found than were recorded.
+    pub truncated: bool,
+}
+
+impl QualityReport {
+    /// Returns `true` when no issues were found.
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Distinct rows with at least one issue, in ascending order.
+    #[must_use]
+    pub fn suspect_rows(&self) -> Vec<u64> {
+        let mut rows: Vec<u64> = self.issues.iter().map(|issue| issue.row).collect();
+        rows.dedup();
+        rows
+    }
+}
+
+/// Controls a [`SasReader::check_data_quality_with`] pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualityCheckOptions {
+    max_issues: usize,
+}
+
+impl Default for QualityCheckOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QualityCheckOptions {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { max_issues: 1000 }
+    }
+
+    /// Stops recording issues after `max_issues`; the scan still reads every
+    /// row so [`QualityReport::rows_scanned`] stays accurate.
+    #[must_use]
+    pub const fn max_issues(mut self, max_issues: usize) -> Self {
+        self.max_issues = max_issues;
+        self
+    }
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Streams every row and flags cells that look corrupted; see
+    /// [`SasReader::check_data_quality_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pages cannot be read.
+    pub fn check_data_quality(&mut self) -> Result<QualityReport> {
+        self.check_data_quality_with(QualityCheckOptions::new())
+    }
+
+    /// Streams every row and flags character cells with undecodable bytes or
+    /// values wider than their format, numeric cells whose NaN bit pattern is
+    /// not a SAS missing code, and rows too short for the column layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pages cannot be read.
+    pub fn check_data_quality_with(
+        &mut self,
+        options: QualityCheckOptions,
+    ) -> Result<QualityReport> {
+        let declared_widths: Vec<Option<usize>> = self
+            .metadata()
+            .variables
+            .iter()
+            .map(|variable| match variable.kind {
+                VariableKind::Character => variable
+                    .format
+                    .as_ref()
+                    .and_then(|format| format.width)
+                    .filter(|&width| width > 0)
+                    .map(usize::from),
+                VariableKind::Numeric => None,
+            })
+            .collect();
+
+        let mut report = QualityReport::default();
+        let push = |report: &mut QualityReport, issue: QualityIssue| {
+            if report.issues.len() < options.max_issues {
+                report.issues.push(issue);
+            } else {
+                report.truncated = true;
+            }
+        };
+
+        self.reader.seek(SeekFrom::Start(0))?;
+        let mut iterator = self.layout.row_iterator(&mut self.reader)?;
+        iterator.stream_all(|row| {
+            let row_index = report.rows_scanned;
+            report.rows_scanned += 1;
+            for (position, cell) in row.iter().enumerate() {
+                let cell = match cell {
+                    Ok(cell) => cell,
+                    Err(err) => {
+                        push(
+                            &mut report,
+                            QualityIssue {
+                                row: row_index,
+                                column: position,
+                                kind: QualityIssueKind::TruncatedRow,
+                                detail: format!("{err} (row is {} bytes)", row.row_len),
+                            },
+                        );
+                        continue;
+                    }
+                };
+                let column = cell.column_index() as usize;
+                match cell.kind() {
+                    ColumnKind::Character => {
+                        if !cell.text_is_decodable() {
+                            push(
+                                &mut report,
+                                QualityIssue {
+                                    row: row_index,
+                                    column,
+                                    kind: QualityIssueKind::InvalidEncoding,
+                                    detail: format!("bytes are not valid {}", row.encoding.name()),
+                                },
+                            );
+                        }
+                        let Some(width) = declared_widths.get(column).copied().flatten() else {
+                            continue;
+                        };
+                        if let CellValue::Str(text) = cell.decode_value()? {
+                            let length = text.chars().count();
+                            if length > width {
+                                push(
+                                    &mut report,
+                                    QualityIssue {
+                                        row: row_index,
+                                        column,
+                                        kind: QualityIssueKind::ExceedsDeclaredWidth,
+                                        detail: format!(
+                                            "{length} characters exceed the declared width {width}"
+                                        ),
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    ColumnKind::Numeric(_) => {
+                        if let Some(bits) = cell.non_canonical_bits() {
+                            push(
+                                &mut report,
+                                QualityIssue {
+                                    row: row_index,
+                                    column,
+                                    kind: QualityIssueKind::NonCanonicalMissing,
+                                    detail: format!("bit pattern {bits:#018x}"),
+                                },
+                            );
+                        }
+                    }
+                    ColumnKind::Bytes => {}
+                }
+            }
+            Ok(())
+        })?;
+        drop(iterator);
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(report)
+    }
+}
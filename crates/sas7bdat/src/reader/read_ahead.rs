@@ -0,0 +1,299 @@
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    mem,
+    sync::mpsc::{Receiver, Sender, SyncSender, TryRecvError, channel, sync_channel},
+    thread::{self, JoinHandle},
+};
+
+/// How far a [`ReadAheadReader`] reads ahead of the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadAhead {
+    /// Blocks (pages, when opened through
+    /// [`SasReader::open_with_read_ahead`](super::SasReader::open_with_read_ahead))
+    /// fetched ahead of the one being decoded. `0` is treated as `1`.
+    pub pages: usize,
+}
+
+impl ReadAhead {
+    #[must_use]
+    pub const fn new(pages: usize) -> Self {
+        Self { pages }
+    }
+}
+
+impl Default for ReadAhead {
+    /// Double buffering: one page decodes while the next two are read.
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+/// `Read + Seek` adapter that reads fixed-size blocks on a background thread
+/// while the caller processes the previous ones.
+///
+/// Sequential reads are served from blocks that are already in memory, so
+/// disk or network latency overlaps with page decoding. Seeking elsewhere
+/// discards the blocks in flight and restarts reading at the new position;
+/// the parser only does that between passes, so it costs one round trip.
+pub struct ReadAheadReader<R> {
+    len: u64,
+    position: u64,
+    block: Block,
+    /// Start of the next block the worker will send, when it is streaming.
+    expected: Option<u64>,
+    generation: u64,
+    requests: Sender<Request>,
+    blocks: Receiver<Fetched>,
+    recycle: Sender<Vec<u8>>,
+    worker: JoinHandle<R>,
+}
+
+#[derive(Default)]
+struct Block {
+    start: u64,
+    data: Vec<u8>,
+}
+
+struct Request {
+    generation: u64,
+    offset: u64,
+}
+
+struct Fetched {
+    generation: u64,
+    start: u64,
+    data: io::Result<Vec<u8>>,
+}
+
+impl<R: Read + Seek + Send + 'static> ReadAheadReader<R> {
+    /// Measures `inner` and starts the reader thread, which reads
+    /// `block_size` bytes at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `inner` cannot be seeked, `block_size` is zero, or
+    /// the thread cannot be spawned.
+    pub fn new(mut inner: R, block_size: usize, read_ahead: ReadAhead) -> io::Result<Self> {
+        if block_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "read-ahead block size must be positive",
+            ));
+        }
+        let len = inner.seek(SeekFrom::End(0))?;
+        let (requests, request_rx) = channel();
+        let (block_tx, blocks) = sync_channel(read_ahead.pages.max(1));
+        let (recycle, recycle_rx) = channel();
+        let worker = thread::Builder::new()
+            .name("sas7bdat-read-ahead".into())
+            .spawn(move || read_blocks(inner, block_size, &request_rx, &block_tx, &recycle_rx))?;
+        Ok(Self {
+            len,
+            position: 0,
+            block: Block::default(),
+            expected: None,
+            generation: 0,
+            requests,
+            blocks,
+            recycle,
+            worker,
+        })
+    }
+}
+
+impl<R> ReadAheadReader<R> {
+    /// Length of the underlying stream in bytes.
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stops the reader thread and returns the wrapped reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reader thread panicked.
+    pub fn into_inner(self) -> io::Result<R> {
+        let Self {
+            requests,
+            blocks,
+            worker,
+            ..
+        } = self;
+        drop(requests);
+        drop(blocks);
+        worker
+            .join()
+            .map_err(|_| io::Error::other("read-ahead thread panicked"))
+    }
+
+    const fn block_holds(&self, position: u64) -> bool {
+        position >= self.block.start && position - self.block.start < self.block.data.len() as u64
+    }
+
+    fn load_block(&mut self) -> io::Result<()> {
+        if self.expected != Some(self.position) {
+            self.generation += 1;
+            self.requests
+                .send(Request {
+                    generation: self.generation,
+                    offset: self.position,
+                })
+                .map_err(|_| worker_gone())?;
+            self.expected = Some(self.position);
+        }
+        loop {
+            let fetched = self.blocks.recv().map_err(|_| worker_gone())?;
+            if fetched.generation != self.generation {
+                if let Ok(data) = fetched.data {
+                    let _ = self.recycle.send(data);
+                }
+                continue;
+            }
+            let data = match fetched.data {
+                Ok(data) => data,
+                Err(err) => {
+                    self.expected = None;
+                    return Err(err);
+                }
+            };
+            self.expected = (!data.is_empty()).then(|| fetched.start + data.len() as u64);
+            let previous = mem::replace(
+                &mut self.block,
+                Block {
+                    start: fetched.start,
+                    data,
+                },
+            );
+            let _ = self.recycle.send(previous.data);
+            return Ok(());
+        }
+    }
+}
+
+impl<R> Read for ReadAheadReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+        if !self.block_holds(self.position) {
+            self.load_block()?;
+            if !self.block_holds(self.position) {
+                return Ok(0);
+            }
+        }
+        let offset = usize::try_from(self.position - self.block.start)
+            .map_err(|_| io::Error::other("read-ahead block offset overflow"))?;
+        let available = &self.block.data[offset..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<R> Seek for ReadAheadReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        self.position = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+fn worker_gone() -> io::Error {
+    io::Error::other("read-ahead thread stopped unexpectedly")
+}
+
+/// Worker loop: waits for a start offset, then streams consecutive blocks
+/// until end of file, a read error, or a newer request.
+fn read_blocks<R: Read + Seek>(
+    mut inner: R,
+    block_size: usize,
+    requests: &Receiver<Request>,
+    blocks: &SyncSender<Fetched>,
+    recycle: &Receiver<Vec<u8>>,
+) -> R {
+    let mut generation = 0;
+    let mut next: Option<u64> = None;
+    let mut cursor: Option<u64> = None;
+    loop {
+        let request = if next.is_some() {
+            match requests.try_recv() {
+                Ok(request) => Some(request),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => return inner,
+            }
+        } else {
+            match requests.recv() {
+                Ok(request) => Some(request),
+                Err(_) => return inner,
+            }
+        };
+        if let Some(request) = request {
+            generation = request.generation;
+            next = Some(request.offset);
+            continue;
+        }
+        let Some(start) = next else { continue };
+
+        let mut data = recycle.try_recv().unwrap_or_default();
+        let data = read_block(&mut inner, &mut cursor, start, block_size, &mut data).map(|()| data);
+        next = match &data {
+            Ok(bytes) if !bytes.is_empty() => Some(start + bytes.len() as u64),
+            _ => None,
+        };
+        if blocks
+            .send(Fetched {
+                generation,
+                start,
+                data,
+            })
+            .is_err()
+        {
+            return inner;
+        }
+    }
+}
+
+/// Fills `data` with up to `block_size` bytes from `start`, leaving it
+/// shorter only at end of file.
+fn read_block<R: Read + Seek>(
+    inner: &mut R,
+    cursor: &mut Option<u64>,
+    start: u64,
+    block_size: usize,
+    data: &mut Vec<u8>,
+) -> io::Result<()> {
+    let positioned = *cursor == Some(start);
+    *cursor = None;
+    if !positioned {
+        inner.seek(SeekFrom::Start(start))?;
+    }
+    data.resize(block_size, 0);
+    let mut filled = 0;
+    while filled < block_size {
+        match inner.read(&mut data[filled..]) {
+            Ok(0) => break,
+            Ok(count) => filled += count,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    data.truncate(filled);
+    *cursor = Some(start + filled as u64);
+    Ok(())
+}
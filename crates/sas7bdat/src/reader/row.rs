@@ -2,7 +2,9 @@ use crate::{
     cell::CellValue,
     dataset::DatasetMetadata,
     error::{Error, Result},
+    json::{self, DateStyle},
     parser::{RowIterator, StreamingCell, StreamingRow},
+    value::{NumberLocale, parse_sas_number},
 };
 use std::{
     collections::HashMap,
@@ -13,14 +15,20 @@ use std::{
 #[derive(Debug)]
 pub struct RowLookup {
     name_to_index: HashMap<String, usize>,
+    /// Trimmed column names by index.
+    names: Vec<String>,
 }
 
 impl RowLookup {
     #[must_use]
     pub fn from_metadata(metadata: &DatasetMetadata) -> Self {
         let mut name_to_index = HashMap::with_capacity(metadata.variables.len() * 2);
+        let mut names = vec![String::new(); metadata.variables.len()];
         for variable in &metadata.variables {
             let trimmed = variable.name.trim_end();
+            if let Some(slot) = names.get_mut(variable.index as usize) {
+                trimmed.clone_into(slot);
+            }
             name_to_index
                 .entry(variable.name.clone())
                 .or_insert(variable.index as usize);
@@ -28,7 +36,10 @@ impl RowLookup {
                 .entry(trimmed.to_owned())
                 .or_insert(variable.index as usize);
         }
-        Self { name_to_index }
+        Self {
+            name_to_index,
+            names,
+        }
     }
 
     #[must_use]
@@ -42,6 +53,12 @@ impl RowLookup {
         }
         None
     }
+
+    /// Returns the trimmed name of the column at `index`.
+    #[must_use]
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -75,11 +92,29 @@ impl Row {
     ///
     /// Returns an error when the column name is unknown or the value cannot be converted.
     pub fn get_as<T: RowValue>(&self, name: &str) -> Result<Option<T>> {
-        let cell = self.get(name).ok_or_else(|| Error::InvalidMetadata {
+        let cell = self.get(name).ok_or_else(|| Error::InvalidArgument {
             details: format!("column name '{name}' not found in row").into(),
         })?;
         T::from_cell(cell)
     }
+
+    /// Converts the row to a JSON object keyed by the trimmed column names,
+    /// in column order, with values converted by [`CellValue::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a datetime cannot be written in `style`.
+    pub fn to_json(&self, style: DateStyle) -> Result<serde_json::Value> {
+        json::object(
+            self.values.iter().enumerate().map(|(index, value)| {
+                (
+                    self.lookup.name(index).unwrap_or_default(),
+                    Ok(value.clone()),
+                )
+            }),
+            style,
+        )
+    }
 }
 
 pub trait RowValue: Sized {
@@ -146,12 +181,13 @@ impl RowValue for f64 {
                 let value = *value as Self;
                 Ok(Some(value))
             }
-            CellValue::NumericString(text) | CellValue::Str(text) => text
-                .parse::<Self>()
-                .map(Some)
-                .map_err(|_| Error::InvalidMetadata {
-                    details: "string cannot be parsed as f64".into(),
-                }),
+            CellValue::NumericString(text) | CellValue::Str(text) => {
+                parse_sas_number(text, NumberLocale::Point)
+                    .map(Some)
+                    .ok_or_else(|| Error::InvalidMetadata {
+                        details: "string cannot be parsed as f64".into(),
+                    })
+            }
             _ => Err(Error::InvalidMetadata {
                 details: "cell type cannot be converted to f64".into(),
             }),
@@ -324,7 +360,7 @@ impl<'data, 'meta> RowView<'data, 'meta> {
         if let Some(projection) = &self.projection
             && !projection.allows(index)
         {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: format!("column index {index} not found in row").into(),
             });
         }
@@ -344,17 +380,42 @@ impl<'data, 'meta> RowView<'data, 'meta> {
         T::from_cell(&value)
     }
 
+    /// Converts the projected columns to a JSON object keyed by their
+    /// trimmed names, as [`Row::to_json`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a cell cannot be decoded or a datetime cannot be
+    /// written in `style`.
+    pub fn to_json(&self, style: DateStyle) -> Result<serde_json::Value> {
+        json::object(
+            (0..self.row.len())
+                .filter(|&index| {
+                    self.projection
+                        .as_ref()
+                        .is_none_or(|projection| projection.allows(index))
+                })
+                .map(|index| {
+                    (
+                        self.lookup.name(index).unwrap_or_default(),
+                        self.row.cell(index).and_then(|cell| cell.decode_value()),
+                    )
+                }),
+            style,
+        )
+    }
+
     fn resolve_index(&self, name: &str) -> Result<usize> {
         let index = self
             .lookup
             .index(name)
-            .ok_or_else(|| Error::InvalidMetadata {
+            .ok_or_else(|| Error::InvalidArgument {
                 details: format!("column name '{name}' not found in row").into(),
             })?;
         if let Some(projection) = &self.projection
             && !projection.allows(index)
         {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: format!("column name '{name}' not found in row").into(),
             });
         }
@@ -555,7 +616,7 @@ mod tests {
             CellValue::Missing(crate::cell::MissingValue::system()),
         ]);
         let err = row.get_as::<i64>("missing_column").unwrap_err();
-        assert!(matches!(err, Error::InvalidMetadata { .. }));
+        assert!(matches!(err, Error::InvalidArgument { .. }));
     }
 
     #[cfg(feature = "time")]
@@ -1,6 +1,8 @@
+use super::filter::{Predicate, ResolvedFilter, RowFilter};
 use crate::{
     dataset::DatasetMetadata,
     error::{Error, Result},
+    parser::StreamingRow,
 };
 #[cfg(feature = "fast-string")]
 use smallvec::SmallVec;
@@ -15,13 +17,17 @@ type NameList = SmallVec<[String; 8]>;
 #[cfg(not(feature = "fast-string"))]
 type NameList = Vec<String>;
 
-/// Defines pagination and column projection for row reading.
+/// Defines pagination, column projection and row filters for row reading.
+///
+/// Filters run first, on the undecoded row, so rejected rows are never
+/// materialised; `skip_rows` and `max_rows` then count matching rows only.
 #[derive(Debug, Clone, Default)]
 pub struct RowSelection {
     skip_rows: u64,
     max_rows: Option<u64>,
     column_indices: Option<IndexList>,
     column_names: Option<NameList>,
+    filter: RowFilter,
 }
 
 impl RowSelection {
@@ -32,6 +38,7 @@ impl RowSelection {
             max_rows: None,
             column_indices: None,
             column_names: None,
+            filter: RowFilter::new(),
         }
     }
 
@@ -97,6 +104,32 @@ impl RowSelection {
         self
     }
 
+    /// Keeps only rows matching `predicate`. Filter columns need not be part
+    /// of the projection.
+    #[must_use]
+    pub fn matching(mut self, predicate: Predicate) -> Self {
+        self.filter.push_predicate(predicate);
+        self
+    }
+
+    /// Keeps only rows for which `accept` returns `true`. The closure sees a
+    /// zero-copy [`StreamingRow`] and should decode just the cells it needs.
+    #[must_use]
+    pub fn filter<F>(mut self, accept: F) -> Self
+    where
+        F: Fn(&StreamingRow<'_, '_>) -> Result<bool> + Send + Sync + 'static,
+    {
+        self.filter.push_closure(accept);
+        self
+    }
+
+    pub(super) fn resolve_filter(
+        &self,
+        metadata: &DatasetMetadata,
+    ) -> Result<Option<ResolvedFilter>> {
+        self.filter.resolve(metadata)
+    }
+
     pub(crate) const fn skip_count(&self) -> u64 {
         self.skip_rows
     }
@@ -148,12 +181,12 @@ impl RowSelection {
                 Self::insert_projection_index(name, *index, &mut seen, &mut resolved)?;
                 continue;
             }
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: format!("column name '{name}' not found in metadata").into(),
             });
         }
         if resolved.is_empty() {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: "column projection resolved to an empty set".into(),
             });
         }
@@ -164,7 +197,7 @@ impl RowSelection {
         let mut seen = HashSet::with_capacity(indices.len());
         for &index in indices {
             if !seen.insert(index) {
-                return Err(Error::InvalidMetadata {
+                return Err(Error::InvalidArgument {
                     details: format!("duplicate column projection index {index} in selection")
                         .into(),
                 });
@@ -180,7 +213,7 @@ impl RowSelection {
         resolved: &mut Vec<usize>,
     ) -> Result<()> {
         if !seen.insert(index) {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: format!(
                     "column projection resolves duplicate column index {index} for name '{name}'"
                 )
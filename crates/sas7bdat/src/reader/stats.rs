@@ -0,0 +1,272 @@
+use super::SasReader;
+use crate::{
+    error::Result,
+    parser::{ColumnKind, ColumnarColumn},
+};
+use std::{
+    collections::HashSet,
+    io::{Read, Seek, SeekFrom},
+};
+use twox_hash::XxHash3_64;
+
+/// Registers in each distinct-count sketch, as a power of two. 2^12
+/// registers give a standard error of about 1.6% in 4 KiB per column.
+const SKETCH_PRECISION: u32 = 12;
+const SKETCH_REGISTERS: usize = 1 << SKETCH_PRECISION;
+/// Distinct hashes kept exactly before the sketch takes over.
+const EXACT_DISTINCT_LIMIT: usize = 1024;
+
+/// Smallest or largest value of a column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatValue {
+    Number(f64),
+    Text(String),
+}
+
+/// Profile of one column, computed by [`SasReader::column_stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub name: String,
+    pub kind: ColumnKind,
+    /// Non-missing values.
+    pub count: u64,
+    /// Missing numbers and blank strings.
+    pub null_count: u64,
+    /// Numbers compare numerically and text by code point; dates, datetimes
+    /// and times report their stored SAS value. `None` for byte columns and
+    /// columns without values.
+    pub min: Option<StatValue>,
+    pub max: Option<StatValue>,
+    /// Mean of the numeric values; `None` for character columns.
+    pub mean: Option<f64>,
+    /// Distinct non-missing values: exact up to 1024, beyond that a
+    /// `HyperLogLog` estimate within a few percent.
+    pub distinct_estimate: u64,
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Streams the file once in columnar batches and returns min, max, mean,
+    /// missing count and an approximate distinct count for every column,
+    /// without materialising rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pages cannot be read.
+    pub fn column_stats(&mut self) -> Result<Vec<ColumnStats>> {
+        let mut accumulators: Vec<Accumulator> = self
+            .layout
+            .columns
+            .iter()
+            .map(|column| Accumulator::new(column.kind))
+            .collect();
+
+        self.reader.seek(SeekFrom::Start(0))?;
+        {
+            let mut iterator = self.layout.row_iterator(&mut self.reader)?;
+            while let Some(batch) = iterator.next_columnar_batch(0)? {
+                for (index, accumulator) in accumulators.iter_mut().enumerate() {
+                    if let Some(column) = batch.column(index) {
+                        accumulator.observe(&column);
+                    }
+                }
+            }
+        }
+        self.reader.seek(SeekFrom::Start(0))?;
+
+        Ok(self
+            .layout
+            .header
+            .metadata
+            .variables
+            .iter()
+            .zip(accumulators)
+            .map(|(variable, accumulator)| accumulator.finish(variable.name.trim_end()))
+            .collect())
+    }
+}
+
+struct Accumulator {
+    kind: ColumnKind,
+    count: u64,
+    null_count: u64,
+    sum: f64,
+    min_number: Option<f64>,
+    max_number: Option<f64>,
+    min_text: Option<String>,
+    max_text: Option<String>,
+    sketch: DistinctSketch,
+}
+
+impl Accumulator {
+    fn new(kind: ColumnKind) -> Self {
+        Self {
+            kind,
+            count: 0,
+            null_count: 0,
+            sum: 0.0,
+            min_number: None,
+            max_number: None,
+            min_text: None,
+            max_text: None,
+            sketch: DistinctSketch::new(),
+        }
+    }
+
+    fn observe(&mut self, column: &ColumnarColumn<'_, '_>) {
+        match self.kind {
+            ColumnKind::Numeric(_) => {
+                for bits in column.iter_numeric_bits() {
+                    match bits {
+                        Some(bits) => self.observe_number(f64::from_bits(bits)),
+                        None => self.null_count += 1,
+                    }
+                }
+            }
+            ColumnKind::Character => {
+                for text in column.iter_strings() {
+                    match text {
+                        Some(text) => self.observe_text(&text),
+                        None => self.null_count += 1,
+                    }
+                }
+            }
+            ColumnKind::Bytes => {
+                for bytes in column.iter_bytes_range(0, column.len()) {
+                    match bytes {
+                        Some(bytes) => {
+                            self.count += 1;
+                            self.sketch.insert(bytes);
+                        }
+                        None => self.null_count += 1,
+                    }
+                }
+            }
+        }
+    }
+
+    fn observe_number(&mut self, value: f64) {
+        // Fold -0.0 into 0.0 so both count as one distinct value.
+        let value = if value == 0.0 { 0.0 } else { value };
+        self.count += 1;
+        self.sum += value;
+        self.min_number = Some(self.min_number.map_or(value, |min| min.min(value)));
+        self.max_number = Some(self.max_number.map_or(value, |max| max.max(value)));
+        self.sketch.insert(&value.to_bits().to_le_bytes());
+    }
+
+    fn observe_text(&mut self, text: &str) {
+        self.count += 1;
+        if self.min_text.as_deref().is_none_or(|min| text < min) {
+            self.min_text = Some(text.to_owned());
+        }
+        if self.max_text.as_deref().is_none_or(|max| text > max) {
+            self.max_text = Some(text.to_owned());
+        }
+        self.sketch.insert(text.as_bytes());
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(self, name: &str) -> ColumnStats {
+        let numeric = matches!(self.kind, ColumnKind::Numeric(_));
+        let (min, max) = if numeric {
+            (
+                self.min_number.map(StatValue::Number),
+                self.max_number.map(StatValue::Number),
+            )
+        } else {
+            (
+                self.min_text.map(StatValue::Text),
+                self.max_text.map(StatValue::Text),
+            )
+        };
+        ColumnStats {
+            name: name.to_owned(),
+            kind: self.kind,
+            count: self.count,
+            null_count: self.null_count,
+            min,
+            max,
+            mean: (numeric && self.count > 0).then(|| self.sum / self.count as f64),
+            distinct_estimate: self.sketch.estimate(),
+        }
+    }
+}
+
+/// `HyperLogLog` sketch over XXH3 hashes of the values, counting exactly
+/// while few distinct hashes have been seen.
+struct DistinctSketch {
+    registers: Box<[u8; SKETCH_REGISTERS]>,
+    exact: Option<HashSet<u64>>,
+}
+
+impl DistinctSketch {
+    fn new() -> Self {
+        Self {
+            registers: Box::new([0; SKETCH_REGISTERS]),
+            exact: Some(HashSet::new()),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn insert(&mut self, bytes: &[u8]) {
+        let hash = XxHash3_64::oneshot(bytes);
+        if let Some(exact) = &mut self.exact {
+            exact.insert(hash);
+            if exact.len() > EXACT_DISTINCT_LIMIT {
+                self.exact = None;
+            }
+        }
+        let index = (hash >> (64 - SKETCH_PRECISION)) as usize;
+        // The low bits left after taking the index, with a sentinel so the
+        // rank stays bounded when they are all zero.
+        let rest = (hash << SKETCH_PRECISION) | (1 << (SKETCH_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank);
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn estimate(&self) -> u64 {
+        if let Some(exact) = &self.exact {
+            return exact.len() as u64;
+        }
+        let m = SKETCH_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let harmonic: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum();
+        let raw = alpha * m * m / harmonic;
+        #[allow(clippy::naive_bytecount)]
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is far more accurate while registers are empty.
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DistinctSketch;
+
+    #[test]
+    fn sketch_estimate_stays_close_past_the_exact_limit() {
+        let mut sketch = DistinctSketch::new();
+        for value in 0u64..100_000 {
+            sketch.insert(&value.to_le_bytes());
+            sketch.insert(&value.to_le_bytes());
+        }
+        assert!(sketch.exact.is_none());
+        let estimate = sketch.estimate();
+        assert!((95_000..=105_000).contains(&estimate), "{estimate}");
+    }
+}
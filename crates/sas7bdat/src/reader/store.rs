@@ -0,0 +1,261 @@
+use super::{
+    SasReader,
+    blocks::{BlockReader, RangeFetch},
+};
+use crate::{
+    error::{Error, Result},
+    logger::Logger,
+    parser::{MetadataReadOptions, format_spec::SAS_HEADER_MIN_SIZE},
+};
+use bytes::Bytes;
+use object_store::{ObjectStore, ObjectStoreExt, path::Path as StorePath};
+use std::{
+    borrow::Cow,
+    future::Future,
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+    panic::resume_unwind,
+    sync::Arc,
+};
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use url::Url;
+
+const DEFAULT_BLOCK_SIZE: usize = 1024 * 1024;
+const DEFAULT_CACHE_BLOCKS: usize = 16;
+
+impl SasReader<ObjectStoreReader> {
+    /// Opens a SAS7BDAT file in S3 (`s3://bucket/key`), Google Cloud
+    /// Storage (`gs://bucket/key`) or Azure Blob Storage
+    /// (`az://container/blob`), fetching blocks with range requests as they
+    /// are read.
+    ///
+    /// Credentials and regions come from the environment variables the
+    /// cloud SDKs use, such as `AWS_ACCESS_KEY_ID` and `AWS_REGION`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL names no supported store, the object
+    /// cannot be read, or the metadata cannot be parsed.
+    pub fn open_object_store(url: &str) -> Result<Self> {
+        Self::open_object_store_with_options(
+            url,
+            ObjectStoreOptions::default(),
+            MetadataReadOptions::default(),
+        )
+    }
+
+    /// Opens a SAS7BDAT file in an object store with custom transfer and
+    /// metadata read options.
+    ///
+    /// # Errors
+    ///
+    /// See [`SasReader::open_object_store`].
+    pub fn open_object_store_with_options(
+        url: &str,
+        store: ObjectStoreOptions,
+        options: MetadataReadOptions,
+    ) -> Result<Self> {
+        let reader = ObjectStoreReader::with_options(url, store)?;
+        let _logger = Logger::current().with_context(url).enter();
+        Self::from_reader_with_options(reader, options)
+    }
+}
+
+/// Settings for [`ObjectStoreReader`].
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectStoreOptions {
+    /// Bytes fetched per range request, so the page headers visited while
+    /// scanning metadata share a few requests instead of one each.
+    pub block_size: usize,
+    /// Blocks kept in memory; the least recently read is dropped first.
+    /// `0` is treated as `1`.
+    pub cache_blocks: usize,
+}
+
+impl ObjectStoreOptions {
+    #[must_use]
+    pub const fn with_block_size(mut self, bytes: usize) -> Self {
+        self.block_size = bytes;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_cache_blocks(mut self, blocks: usize) -> Self {
+        self.cache_blocks = blocks;
+        self
+    }
+}
+
+impl Default for ObjectStoreOptions {
+    fn default() -> Self {
+        Self {
+            block_size: DEFAULT_BLOCK_SIZE,
+            cache_blocks: DEFAULT_CACHE_BLOCKS,
+        }
+    }
+}
+
+/// Seekable reader over an object in S3, GCS, Azure Blob Storage or any
+/// other [`ObjectStore`].
+///
+/// Reads are served from a cache of fixed-size blocks, each fetched with
+/// one range request; requests run on a private single-threaded Tokio
+/// runtime, so callers need no async context. The reader may also be used
+/// from inside a Tokio runtime: on a multi-threaded one the worker is
+/// handed over with [`tokio::task::block_in_place`], and on a
+/// current-thread one each request runs on a scoped helper thread.
+pub struct ObjectStoreReader {
+    blocks: BlockReader<StoreSource>,
+}
+
+/// The store and object an [`ObjectStoreReader`] fetches blocks from.
+struct StoreSource {
+    store: Arc<dyn ObjectStore>,
+    location: StorePath,
+    /// Only `None` while the source is being dropped.
+    runtime: Option<Runtime>,
+}
+
+impl ObjectStoreReader {
+    /// Opens the object named by `url` with default options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL names no supported store, the object
+    /// cannot be found, or it is too small to be a SAS dataset.
+    pub fn open(url: &str) -> Result<Self> {
+        Self::with_options(url, ObjectStoreOptions::default())
+    }
+
+    /// Opens the object named by `url`, configuring the store from the
+    /// environment.
+    ///
+    /// # Errors
+    ///
+    /// See [`ObjectStoreReader::open`].
+    pub fn with_options(url: &str, options: ObjectStoreOptions) -> Result<Self> {
+        let parsed = Url::parse(url).map_err(|err| unsupported_url(url, &err))?;
+        let environment = std::env::vars().map(|(key, value)| (key.to_ascii_lowercase(), value));
+        let (store, location) = object_store::parse_url_opts(&parsed, environment)
+            .map_err(|err| unsupported_url(url, &err))?;
+        Self::from_store(Arc::from(store), location, options)
+    }
+
+    /// Reads `location` from an already configured store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be started, the object cannot
+    /// be found, or it is too small to be a SAS dataset.
+    pub fn from_store(
+        store: Arc<dyn ObjectStore>,
+        location: StorePath,
+        options: ObjectStoreOptions,
+    ) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let source = StoreSource {
+            store,
+            location,
+            runtime: Some(runtime),
+        };
+        let len = source
+            .block_on(source.store.head(&source.location))
+            .map_err(io::Error::from)?
+            .size;
+        if len < u64::from(SAS_HEADER_MIN_SIZE) {
+            return Err(Error::NotSasFile {
+                details: format!(
+                    "'{}' is {len} bytes, smaller than the minimum SAS header of {SAS_HEADER_MIN_SIZE} bytes",
+                    source.location
+                )
+                .into(),
+            });
+        }
+        Ok(Self {
+            blocks: BlockReader::new(source, len, options.block_size, options.cache_blocks),
+        })
+    }
+
+    #[must_use]
+    pub const fn location(&self) -> &StorePath {
+        &self.blocks.source().location
+    }
+
+    /// Size of the object in bytes.
+    #[must_use]
+    pub const fn len(&self) -> u64 {
+        self.blocks.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Range requests sent so far, for tuning the block size and cache.
+    #[must_use]
+    pub const fn requests(&self) -> u64 {
+        self.blocks.requests()
+    }
+}
+
+impl StoreSource {
+    /// Runs `future` on the private runtime without blocking a runtime the
+    /// caller may already be inside.
+    fn block_on<T: Send>(&self, future: impl Future<Output = T> + Send) -> T {
+        let runtime = self
+            .runtime
+            .as_ref()
+            .expect("runtime is only taken on drop");
+        match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+            Err(_) => runtime.block_on(future),
+            Ok(RuntimeFlavor::MultiThread) => {
+                tokio::task::block_in_place(|| runtime.block_on(future))
+            }
+            Ok(_) => std::thread::scope(|scope| {
+                scope
+                    .spawn(|| runtime.block_on(future))
+                    .join()
+                    .unwrap_or_else(|panic| resume_unwind(panic))
+            }),
+        }
+    }
+}
+
+impl Drop for StoreSource {
+    /// Dropping a runtime blocks, which Tokio forbids inside another
+    /// runtime, so there it is shut down in the background instead.
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take()
+            && Handle::try_current().is_ok()
+        {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl RangeFetch for StoreSource {
+    fn fetch(&mut self, range: Range<u64>) -> io::Result<Bytes> {
+        Ok(self.block_on(self.store.get_range(&self.location, range))?)
+    }
+}
+
+fn unsupported_url(url: &str, err: &dyn std::fmt::Display) -> Error {
+    Error::Unsupported {
+        feature: Cow::Owned(format!("object store URL '{url}': {err}")),
+    }
+}
+
+impl Read for ObjectStoreReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.blocks.read(buf)
+    }
+}
+
+impl Seek for ObjectStoreReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.blocks.seek(pos)
+    }
+}
@@ -0,0 +1,242 @@
+use super::SasReader;
+use crate::{
+    error::{Error, Result},
+    parser::{MetadataReadOptions, parse_metadata_with_options, trailing_metadata_page},
+};
+use std::io::{self, Read, Seek, SeekFrom};
+
+impl<S: Read> SasReader<StreamReader<S>> {
+    /// Reads a SAS7BDAT file from a stream that cannot seek, such as stdin,
+    /// a pipe or an object-store download.
+    ///
+    /// The start of the stream, up to the
+    /// [stream buffer](MetadataReadOptions::with_stream_buffer), is kept in
+    /// memory while the metadata is parsed; rows are then decoded page by
+    /// page as the stream delivers them, and the kept pages are released
+    /// once the rows have moved past them. A stream that ends within the
+    /// buffer is read like a seekable file, including metadata stored after
+    /// the data pages. Longer streams only use the metadata ahead of the
+    /// first data page, and fail to open with [`Error::Unsupported`] naming
+    /// the page when a metadata page follows the data pages within the
+    /// buffer, or when the leading metadata is incomplete and one follows
+    /// further on.
+    ///
+    /// Rows can be read once, front to back: starting a second pass,
+    /// [`SasReader::rows_from`], selections and parallel decoding need to
+    /// seek back and fail with an I/O error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream cannot be read, the metadata cannot
+    /// be parsed, or metadata after the data pages of a stream longer than
+    /// the buffer would be needed.
+    pub fn from_stream(stream: S) -> Result<Self> {
+        Self::from_stream_with_options(stream, MetadataReadOptions::default())
+    }
+
+    /// Reads a SAS7BDAT file from a non-seekable stream with custom
+    /// metadata read options.
+    ///
+    /// [`MetadataReadOptions::leading_metadata_only`] is enabled for streams
+    /// longer than [`MetadataReadOptions::stream_buffer`].
+    ///
+    /// # Errors
+    ///
+    /// See [`SasReader::from_stream`].
+    pub fn from_stream_with_options(stream: S, options: MetadataReadOptions) -> Result<Self> {
+        let buffer = options.stream_buffer;
+        let mut reader = StreamReader::new(stream);
+        let layout = if reader.buffer_ahead(buffer)? {
+            parse_metadata_with_options(&mut reader, options)?
+        } else {
+            reader.seek(SeekFrom::Start(0))?;
+            let leading =
+                parse_metadata_with_options(&mut reader, options.with_leading_metadata_only(true));
+            let limit = if leading.is_ok() {
+                buffer
+            } else {
+                // A failed open may read on to name the page, without
+                // keeping the pages it skips.
+                reader.seek(SeekFrom::Start(0))?;
+                reader.stop_retaining();
+                u64::MAX
+            };
+            if let Some(page_index) = trailing_metadata_page(&mut reader, limit)? {
+                return Err(Error::Unsupported {
+                    feature: format!(
+                        "metadata on page {page_index}, after the data pages, in a stream \
+                         longer than its {buffer}-byte buffer"
+                    )
+                    .into(),
+                });
+            }
+            leading?
+        };
+        reader.seek(SeekFrom::Start(0))?;
+        reader.stop_retaining();
+        Ok(Self {
+            reader,
+            layout,
+            progress: None,
+        })
+    }
+}
+
+/// `Read + Seek` adapter over a forward-only stream.
+///
+/// Seeking forward skips bytes. Seeking back only works into the bytes kept
+/// from the start of the stream, which [`SasReader::from_stream`] keeps
+/// until the metadata is parsed; everything else is read exactly once.
+pub struct StreamReader<R> {
+    inner: R,
+    /// Bytes from the start of the stream, served again on backward seeks.
+    retained: Vec<u8>,
+    retaining: bool,
+    /// Bytes taken from `inner` so far.
+    consumed: u64,
+    position: u64,
+    /// Length of the stream, once `inner` has reported its end.
+    len: Option<u64>,
+}
+
+impl<R: Read> StreamReader<R> {
+    /// Wraps `inner`, keeping every byte it yields until
+    /// [`StreamReader::stop_retaining`] is called.
+    #[must_use]
+    pub const fn new(inner: R) -> Self {
+        Self {
+            inner,
+            retained: Vec::new(),
+            retaining: true,
+            consumed: 0,
+            position: 0,
+            len: None,
+        }
+    }
+
+    /// Stops keeping new bytes. The bytes already kept are dropped once a
+    /// read moves past them.
+    pub const fn stop_retaining(&mut self) {
+        self.retaining = false;
+    }
+
+    /// Returns the wrapped stream.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads ahead until more than `limit` bytes have been taken from the
+    /// stream or it ends, keeping the bytes while retaining. Returns whether
+    /// the stream ended, in which case it may be seeked from the end.
+    pub(crate) fn buffer_ahead(&mut self, limit: u64) -> io::Result<bool> {
+        let mut scratch = [0u8; 8 * 1024];
+        while self.len.is_none() && self.consumed <= limit {
+            let wanted = usize::try_from(limit - self.consumed)
+                .unwrap_or(usize::MAX)
+                .saturating_add(1)
+                .min(scratch.len());
+            self.read_inner(&mut scratch[..wanted])?;
+        }
+        Ok(self.len.is_some())
+    }
+
+    /// Reads from `inner` at `consumed`, keeping the bytes when retaining.
+    fn read_inner(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        if count == 0 && !buf.is_empty() {
+            self.len = Some(self.consumed);
+        }
+        if self.retaining {
+            self.retained.extend_from_slice(&buf[..count]);
+        }
+        self.consumed += count as u64;
+        Ok(count)
+    }
+
+    /// Reads and drops stream bytes up to `position`.
+    fn skip_to_position(&mut self) -> io::Result<()> {
+        let mut scratch = [0u8; 8 * 1024];
+        while self.consumed < self.position {
+            let wanted = usize::try_from(self.position - self.consumed)
+                .unwrap_or(usize::MAX)
+                .min(scratch.len());
+            if self.read_inner(&mut scratch[..wanted])? == 0 {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for StreamReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let retained_end = self.retained.len() as u64;
+        if self.position < retained_end {
+            let start = usize::try_from(self.position)
+                .map_err(|_| io::Error::other("stream position overflow"))?;
+            let available = &self.retained[start..];
+            let count = available.len().min(buf.len());
+            buf[..count].copy_from_slice(&available[..count]);
+            self.position += count as u64;
+            return Ok(count);
+        }
+        if !self.retaining && !self.retained.is_empty() {
+            self.retained = Vec::new();
+        }
+        if self.position < self.consumed {
+            return Err(backward_seek());
+        }
+        self.skip_to_position()?;
+        if self.consumed < self.position {
+            return Ok(0);
+        }
+        let count = self.read_inner(buf)?;
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<R: Read> Seek for StreamReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+            SeekFrom::End(delta) => match self.len {
+                Some(len) => len.checked_add_signed(delta),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "the length of a stream is unknown until it ends",
+                    ));
+                }
+            },
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek to a negative or overflowing position",
+            )
+        })?;
+        if !self.retaining && target < self.position && self.position >= self.retained.len() as u64
+        {
+            // The kept bytes are released once read past, even when they
+            // hold the whole stream, so rows are still read only once.
+            self.retained = Vec::new();
+        }
+        if target < self.consumed && target >= self.retained.len() as u64 {
+            return Err(backward_seek());
+        }
+        self.position = target;
+        Ok(target)
+    }
+}
+
+fn backward_seek() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "cannot seek back in a stream past the bytes kept for metadata",
+    )
+}
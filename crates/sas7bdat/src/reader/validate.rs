@@ -0,0 +1,209 @@
+use super::SasReader;
+use crate::{
+    error::Result,
+    logger::{LogLevel, LogRecord, LogSink, Logger},
+};
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IssueSeverity {
+    /// The reader recovered, for example by skipping a damaged page, but
+    /// the decoded data may be incomplete.
+    Warning,
+    /// The file could not be read past this point.
+    Error,
+}
+
+impl IssueSeverity {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// One structural problem found while validating a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Zero-based page the problem was found on, when it is tied to one.
+    pub page: Option<u64>,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// Outcome of [`validate_file`] or [`SasReader::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Issues in the order they were found.
+    pub issues: Vec<ValidationIssue>,
+    /// Row count declared in the header, if the header could be read.
+    pub expected_rows: Option<u64>,
+    /// Rows decoded before the scan finished or stopped on an error.
+    pub rows_read: u64,
+}
+
+impl ValidationReport {
+    /// Returns `true` when no issues were found.
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Warning)
+    }
+
+    fn push_error(&mut self, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            page: None,
+            severity: IssueSeverity::Error,
+            message: message.into(),
+        });
+    }
+}
+
+/// Collects warnings and errors logged by the parser as issues.
+#[derive(Default)]
+struct IssueCollector {
+    issues: Mutex<Vec<ValidationIssue>>,
+}
+
+impl IssueCollector {
+    fn take(&self) -> Vec<ValidationIssue> {
+        std::mem::take(&mut *self.issues.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl LogSink for IssueCollector {
+    fn log(&self, record: &LogRecord<'_>) {
+        let severity = match record.level {
+            LogLevel::Error => IssueSeverity::Error,
+            LogLevel::Warn => IssueSeverity::Warning,
+            LogLevel::Info | LogLevel::Debug => return,
+        };
+        self.issues
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(ValidationIssue {
+                page: page_of(record.message),
+                severity,
+                message: record.message.to_owned(),
+            });
+    }
+}
+
+/// Extracts the page index from messages of the form `... page 12 ...`.
+fn page_of(message: &str) -> Option<u64> {
+    let (_, rest) = message.split_once("page ")?;
+    let digits = rest.split(|c: char| !c.is_ascii_digit()).next()?;
+    digits.parse().ok()
+}
+
+/// Opens `path` and reads every page, reporting structural problems instead
+/// of failing on the first one.
+///
+/// Unlike [`SasReader::validate`], this also captures warnings raised while
+/// parsing metadata, and a file that cannot be opened at all yields a report
+/// with a single error.
+#[must_use]
+pub fn validate_file<P: AsRef<Path>>(path: P) -> ValidationReport {
+    let collector = Arc::new(IssueCollector::default());
+    let logger = Logger::from_shared(collector.clone());
+    let opened = {
+        let _guard = logger.enter();
+        SasReader::open(path)
+    };
+    let mut report = match opened {
+        Ok(mut sas) => sas.validate_with(logger),
+        Err(err) => {
+            let mut report = ValidationReport::default();
+            report.push_error(err.to_string());
+            report
+        }
+    };
+    let mut issues = collector.take();
+    issues.append(&mut report.issues);
+    report.issues = issues;
+    report
+}
+
+impl<R: Read + Seek> SasReader<R> {
+    /// Reads every page and reports structural problems: damaged pages the
+    /// reader skipped, a file size that disagrees with the header's page
+    /// count, a row count that disagrees with the header, and the error that
+    /// stopped decoding, if any.
+    ///
+    /// Warnings raised while the metadata was parsed are not included; use
+    /// [`validate_file`] to capture those too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the underlying reader cannot be rewound.
+    pub fn validate(&mut self) -> Result<ValidationReport> {
+        let collector = Arc::new(IssueCollector::default());
+        let mut report = self.validate_with(Logger::from_shared(collector.clone()));
+        let mut issues = collector.take();
+        issues.append(&mut report.issues);
+        report.issues = issues;
+        self.reader.seek(SeekFrom::Start(0))?;
+        Ok(report)
+    }
+
+    fn validate_with(&mut self, logger: Logger) -> ValidationReport {
+        let mut report = ValidationReport {
+            expected_rows: Some(self.metadata().row_count),
+            ..ValidationReport::default()
+        };
+        let header = &self.layout.header;
+        let expected_len = header.data_offset + header.page_count * u64::from(header.page_size);
+        match self.reader.seek(SeekFrom::End(0)) {
+            Ok(actual_len) if actual_len < expected_len => report.push_error(format!(
+                "file is {actual_len} bytes but the header describes {} pages ending at byte {expected_len}",
+                header.page_count
+            )),
+            Ok(_) => {}
+            Err(err) => report.push_error(err.to_string()),
+        }
+
+        let previous = std::mem::replace(&mut self.layout.logger, logger);
+        let mut rows_read = 0_u64;
+        let scanned = self
+            .reader
+            .seek(SeekFrom::Start(0))
+            .map_err(Into::into)
+            .and_then(|_| {
+                let mut iterator = self.layout.row_iterator(&mut self.reader)?;
+                iterator.stream_all(|_| {
+                    rows_read += 1;
+                    Ok(())
+                })
+            });
+        self.layout.logger = previous;
+        report.rows_read = rows_read;
+
+        match scanned {
+            Ok(()) if rows_read != self.metadata().row_count => report.push_error(format!(
+                "header declares {} rows but {rows_read} were decoded",
+                self.metadata().row_count
+            )),
+            Ok(()) => {}
+            Err(err) => report.push_error(err.to_string()),
+        }
+        report
+    }
+}
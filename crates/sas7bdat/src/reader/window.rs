@@ -1,8 +1,8 @@
-use super::projection::ProjectedRowIter;
+use super::{filter::ResolvedFilter, projection::ProjectedRowIter};
 use crate::{cell::CellValue, error::Result, parser::RowIterator};
 use std::io::{Read, Seek};
 
-pub struct RowWindow<'a, R: Read + Seek>(RowWindowInner<RowIterator<'a, R>>);
+pub struct RowWindow<'a, R: Read + Seek>(RowWindowInner<FilteredRows<'a, R>>);
 
 pub struct ProjectedRowWindow<'a, R: Read + Seek>(RowWindowInner<ProjectedRowIter<'a, R>>);
 
@@ -10,9 +10,24 @@ trait SkippableRows {
     fn advance(&mut self) -> Result<bool>;
 }
 
-impl<R: Read + Seek> SkippableRows for RowIterator<'_, R> {
+/// Full rows, optionally restricted to those a selection's filter accepts.
+pub(super) struct FilteredRows<'a, R: Read + Seek> {
+    inner: RowIterator<'a, R>,
+    filter: Option<ResolvedFilter>,
+}
+
+impl<'a, R: Read + Seek> FilteredRows<'a, R> {
+    pub(super) const fn new(inner: RowIterator<'a, R>, filter: Option<ResolvedFilter>) -> Self {
+        Self { inner, filter }
+    }
+}
+
+impl<R: Read + Seek> SkippableRows for FilteredRows<'_, R> {
     fn advance(&mut self) -> Result<bool> {
-        Ok(self.try_next()?.is_some())
+        match &self.filter {
+            Some(filter) => self.inner.skip_matching(&mut |row| filter.matches(row)),
+            None => Ok(self.inner.try_next()?.is_some()),
+        }
     }
 }
 
@@ -28,16 +43,26 @@ trait RowSource: SkippableRows {
         Self: 'a;
 
     fn next_row(&mut self) -> Result<Option<Self::Row<'_>>>;
+
+    /// Pages the underlying iterator has read, including skipped ones.
+    fn pages_read(&self) -> u64;
 }
 
-impl<R: Read + Seek> RowSource for RowIterator<'_, R> {
+impl<R: Read + Seek> RowSource for FilteredRows<'_, R> {
     type Row<'s>
         = Vec<CellValue<'s>>
     where
         Self: 's;
 
     fn next_row(&mut self) -> Result<Option<Self::Row<'_>>> {
-        self.try_next()
+        match &self.filter {
+            Some(filter) => self.inner.try_next_matching(&mut |row| filter.matches(row)),
+            None => self.inner.try_next(),
+        }
+    }
+
+    fn pages_read(&self) -> u64 {
+        self.inner.next_page_index
     }
 }
 
@@ -50,6 +75,10 @@ impl<R: Read + Seek> RowSource for ProjectedRowIter<'_, R> {
     fn next_row(&mut self) -> Result<Option<Self::Row<'_>>> {
         self.try_next()
     }
+
+    fn pages_read(&self) -> u64 {
+        self.inner.next_page_index
+    }
 }
 
 struct RowWindowState<I> {
@@ -119,11 +148,15 @@ macro_rules! impl_row_window {
             pub fn try_next(&mut self) -> Result<Option<$row>> {
                 self.0.try_next()
             }
+
+            pub(super) fn pages_read(&self) -> u64 {
+                self.0.state.inner.pages_read()
+            }
         }
     };
 }
 
-impl_row_window!(RowWindow => RowIterator<'a, R>, Vec<CellValue<'_>>);
+impl_row_window!(RowWindow => FilteredRows<'a, R>, Vec<CellValue<'_>>);
 impl_row_window!(
     ProjectedRowWindow => ProjectedRowIter<'a, R>,
     Vec<CellValue<'static>>
@@ -0,0 +1,309 @@
+//! Harmonising the schemas of datasets delivered in instalments.
+//!
+//! Registry extracts arrive as a series of files whose columns drift between
+//! deliveries: columns come and go, get renamed, or change type.
+//! [`SchemaDiff::between`] reports how one delivery departs from another,
+//! and [`union_schema`] builds one schema covering every delivery, with a
+//! [`ColumnMapping`] per delivery that lays its rows out in the union's
+//! column order.
+//!
+//! Column names are compared case-insensitively, as SAS does. A column that
+//! disappears while another with the same label and type appears is taken to
+//! be renamed. Types are the decoded [`ColumnKind`]s, so a numeric column
+//! that gains a date format counts as retyped.
+
+use crate::{
+    cell::{CellValue, MissingValue},
+    dataset::{DatasetMetadata, Variable, VariableKind, kind_name},
+    error::{Error, Result},
+    parser::{ColumnKind, NumericKind},
+};
+use std::fmt;
+
+pub use crate::dataset::{SchemaDifference, SchemaFingerprint, SchemaMismatch, compare_schemas};
+
+/// A column whose name changed between two deliveries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamedColumn {
+    pub from: String,
+    pub to: String,
+}
+
+/// A column whose decoded type changed between two deliveries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetypedColumn {
+    pub column: String,
+    pub from: ColumnKind,
+    pub to: ColumnKind,
+}
+
+/// How the columns of a later delivery depart from those of an earlier one.
+///
+/// Column order is not compared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<RenamedColumn>,
+    pub retyped: Vec<RetypedColumn>,
+}
+
+impl SchemaDiff {
+    /// Compares the columns of `new` against those of `old`.
+    #[must_use]
+    pub fn between(old: &DatasetMetadata, new: &DatasetMetadata) -> Self {
+        Self::between_variables(&old.variables, &new.variables)
+    }
+
+    fn between_variables(old: &[Variable], new: &[Variable]) -> Self {
+        let mut diff = Self::default();
+        let mut added = Vec::new();
+        for variable in new {
+            match find(old, &variable.name) {
+                Some(previous) => {
+                    let (from, to) = (column_kind(previous), column_kind(variable));
+                    if from != to {
+                        diff.retyped.push(RetypedColumn {
+                            column: column_name(variable),
+                            from,
+                            to,
+                        });
+                    }
+                }
+                None => added.push(variable),
+            }
+        }
+        for variable in old {
+            if find(new, &variable.name).is_some() {
+                continue;
+            }
+            match added
+                .iter()
+                .position(|candidate| is_rename(variable, candidate))
+            {
+                Some(position) => {
+                    let renamed = added.remove(position);
+                    diff.renamed.push(RenamedColumn {
+                        from: column_name(variable),
+                        to: column_name(renamed),
+                    });
+                }
+                None => diff.removed.push(column_name(variable)),
+            }
+        }
+        diff.added = added.into_iter().map(column_name).collect();
+        diff
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.retyped.is_empty()
+    }
+}
+
+/// One change per line, such as `renamed column 'PNR' to 'PERSON_ID'`.
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for column in &self.added {
+            writeln!(f, "added column '{column}'")?;
+        }
+        for column in &self.removed {
+            writeln!(f, "removed column '{column}'")?;
+        }
+        for RenamedColumn { from, to } in &self.renamed {
+            writeln!(f, "renamed column '{from}' to '{to}'")?;
+        }
+        for RetypedColumn { column, from, to } in &self.retyped {
+            writeln!(
+                f,
+                "column '{column}' changed from {} to {}",
+                kind_name(*from),
+                kind_name(*to)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the columns of one delivery land in a [`SchemaUnion`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    sources: Vec<Option<usize>>,
+}
+
+impl ColumnMapping {
+    /// Source column feeding each union column, in union order; `None`
+    /// where the delivery lacks the column.
+    #[must_use]
+    pub fn sources(&self) -> &[Option<usize>] {
+        &self.sources
+    }
+
+    /// Returns `true` when rows of the delivery are already in union layout.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        self.sources
+            .iter()
+            .enumerate()
+            .all(|(position, source)| *source == Some(position))
+    }
+
+    /// Lays `row` out in union order, filling the columns the delivery lacks
+    /// with system missing values.
+    #[must_use]
+    pub fn apply<'a>(&self, row: &[CellValue<'a>]) -> Vec<CellValue<'a>> {
+        self.sources
+            .iter()
+            .map(|source| {
+                source
+                    .and_then(|index| row.get(index))
+                    .cloned()
+                    .unwrap_or(CellValue::Missing(MissingValue::System))
+            })
+            .collect()
+    }
+}
+
+/// A schema covering a series of deliveries.
+#[derive(Debug, Clone)]
+pub struct SchemaUnion {
+    /// The columns of the first delivery in their stored order, followed by
+    /// the columns later deliveries add, in the order they first appear.
+    /// Renamed columns keep their first name; character widths are the
+    /// widest seen.
+    pub variables: Vec<Variable>,
+    /// One mapping per delivery, in input order.
+    pub mappings: Vec<ColumnMapping>,
+}
+
+/// Builds the union of the schemas of `deliveries`, given oldest first.
+///
+/// Renames are detected between consecutive deliveries, so a column can be
+/// followed through several renames.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] when a column changes type, since no
+/// single union column can hold both types, or when two columns of one
+/// delivery resolve to the same union column.
+pub fn union_schema(deliveries: &[DatasetMetadata]) -> Result<SchemaUnion> {
+    let mut variables: Vec<Variable> = Vec::new();
+    // Every name each union column has gone by.
+    let mut aliases: Vec<Vec<String>> = Vec::new();
+    let mut mappings = Vec::with_capacity(deliveries.len());
+    let mut previous: Option<&DatasetMetadata> = None;
+
+    for (delivery, metadata) in deliveries.iter().enumerate() {
+        if let Some(previous) = previous {
+            for RenamedColumn { from, to } in SchemaDiff::between(previous, metadata).renamed {
+                if let Some(names) = aliases
+                    .iter_mut()
+                    .find(|names| names.iter().any(|name| same_name(name, &from)))
+                {
+                    names.push(to);
+                }
+            }
+        }
+
+        let mut sources = vec![None; variables.len()];
+        for (position, variable) in metadata.variables.iter().enumerate() {
+            let existing = aliases
+                .iter()
+                .position(|names| names.iter().any(|name| same_name(name, &variable.name)));
+            let target = if let Some(target) = existing {
+                let union = &mut variables[target];
+                let (expected, found) = (column_kind(union), column_kind(variable));
+                if expected != found {
+                    return Err(Error::InvalidArgument {
+                        details: format!(
+                            "column '{}' is {} in delivery {delivery} but {} earlier",
+                            column_name(variable),
+                            kind_name(found),
+                            kind_name(expected)
+                        )
+                        .into(),
+                    });
+                }
+                union.storage_width = union.storage_width.max(variable.storage_width);
+                target
+            } else {
+                let mut union = variable.clone();
+                union.name = column_name(variable);
+                variables.push(union);
+                aliases.push(vec![column_name(variable)]);
+                sources.push(None);
+                variables.len() - 1
+            };
+            if sources[target].replace(position).is_some() {
+                return Err(Error::InvalidArgument {
+                    details: format!(
+                        "delivery {delivery} has two columns for '{}'",
+                        variables[target].name
+                    )
+                    .into(),
+                });
+            }
+        }
+        mappings.push(ColumnMapping { sources });
+        previous = Some(metadata);
+    }
+
+    for mapping in &mut mappings {
+        mapping.sources.resize(variables.len(), None);
+    }
+    for (position, variable) in variables.iter_mut().enumerate() {
+        variable.index = u32::try_from(position).map_err(|_| Error::Limit {
+            details: "union schema has too many columns".into(),
+        })?;
+    }
+    Ok(SchemaUnion {
+        variables,
+        mappings,
+    })
+}
+
+fn find<'a>(variables: &'a [Variable], name: &str) -> Option<&'a Variable> {
+    variables
+        .iter()
+        .find(|variable| same_name(&variable.name, name))
+}
+
+fn same_name(left: &str, right: &str) -> bool {
+    left.trim_end().eq_ignore_ascii_case(right.trim_end())
+}
+
+fn column_name(variable: &Variable) -> String {
+    variable.name.trim_end().to_owned()
+}
+
+/// A removed and an added column are one renamed column when they share a
+/// non-blank label and their type.
+fn is_rename(removed: &Variable, added: &Variable) -> bool {
+    fn label(variable: &Variable) -> Option<&str> {
+        variable
+            .label
+            .as_deref()
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+    }
+    matches!((label(removed), label(added)), (Some(old), Some(new)) if old == new)
+        && column_kind(removed) == column_kind(added)
+}
+
+/// The kind the column decodes to, inferred from its format the way the
+/// metadata parser does.
+fn column_kind(variable: &Variable) -> ColumnKind {
+    match variable.kind {
+        VariableKind::Character => ColumnKind::Character,
+        VariableKind::Numeric => ColumnKind::Numeric(
+            variable
+                .format
+                .as_ref()
+                .and_then(|format| format.category().numeric_kind())
+                .unwrap_or(NumericKind::Double),
+        ),
+    }
+}
@@ -0,0 +1,619 @@
+use crate::{
+    cell::{CellValue, MissingValue},
+    dataset::{Alignment, Measure, RepeatedGroup, Variable},
+    error::{Error, Result},
+    logger::Logger,
+    parser::{
+        ColumnKind, ColumnarBatch, ColumnarColumn, NumericKind, TimestampRounding, Utf8Arena,
+    },
+    sinks::{
+        ColumnarSink, RowSink, SinkContext, repeated_group_members, resolve_repeated_groups,
+        validate_sink_begin,
+    },
+    telemetry::{self, Stopwatch},
+    time_conv::{sas_days_to_date, sas_seconds_to_datetime, sas_seconds_to_time},
+};
+use arrow_array::{
+    ArrayRef, ListArray, RecordBatch, RecordBatchOptions, StringArray, StringViewArray,
+    builder::{
+        BinaryBuilder, Date32Builder, Float64Builder, StringBuilder, StringViewBuilder,
+        Time64MicrosecondBuilder, TimestampMicrosecondBuilder,
+    },
+};
+use arrow_buffer::{Buffer, NullBuffer, OffsetBuffer, ScalarBuffer};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use time::{Duration, OffsetDateTime};
+
+/// Field metadata key holding the SAS measurement level (`nominal`,
+/// `ordinal` or `scale`).
+pub const SAS_MEASURE_KEY: &str = "sas.measure";
+/// Field metadata key holding the SAS display alignment (`left`, `center`
+/// or `right`).
+pub const SAS_ALIGNMENT_KEY: &str = "sas.alignment";
+
+const DEFAULT_BATCH_ROWS: usize = 8_192;
+const SECONDS_PER_DAY: i64 = 86_400;
+const UTC: &str = "UTC";
+
+/// Collects decoded SAS rows as Arrow [`RecordBatch`]es, ready to hand to
+/// `DataFusion`, Polars or any other Arrow consumer without a Parquet round
+/// trip.
+///
+/// Columns map to `Utf8` (or `Utf8View`, see [`ArrowSink::with_string_view`]),
+/// `Binary` (for raw-bytes character columns),
+/// `Float64`, `Date32`, `Timestamp(Microsecond, "UTC")` and
+/// `Time64(Microsecond)`. Blank character values are SAS missing values and
+/// become nulls, as do dates and times outside Arrow's range, which are
+/// reported once per column through the context logger.
+///
+/// [Repeated groups](ArrowSink::with_repeated_groups) become `List` columns.
+pub struct ArrowSink {
+    batch_rows: usize,
+    rounding: TimestampRounding,
+    string_view: bool,
+    repeated_groups: Vec<RepeatedGroup>,
+    schema: Option<SchemaRef>,
+    /// `true` between `begin` and `finish`.
+    open: bool,
+    input_columns: usize,
+    columns: Vec<ColumnBuilder>,
+    /// Input columns feeding each output column: one, or a group's members.
+    sources: Vec<Vec<usize>>,
+    rows_buffered: usize,
+    batches: Vec<RecordBatch>,
+    logger: Logger,
+}
+
+impl Default for ArrowSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArrowSink {
+    /// Creates a sink that emits record batches of 8192 rows.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            batch_rows: DEFAULT_BATCH_ROWS,
+            rounding: TimestampRounding::Truncate,
+            string_view: false,
+            repeated_groups: Vec::new(),
+            schema: None,
+            open: false,
+            input_columns: 0,
+            columns: Vec::new(),
+            sources: Vec::new(),
+            rows_buffered: 0,
+            batches: Vec::new(),
+            logger: Logger::current(),
+        }
+    }
+
+    /// Sets the number of rows per record batch. Columnar input may overshoot
+    /// by up to one source batch, since source batches are never split.
+    #[must_use]
+    pub const fn with_batch_rows(mut self, rows: usize) -> Self {
+        self.batch_rows = if rows == 0 { 1 } else { rows };
+        self
+    }
+
+    /// Selects how sub-microsecond precision is dropped from timestamps and
+    /// times.
+    #[must_use]
+    pub const fn with_timestamp_rounding(mut self, rounding: TimestampRounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Emits character columns as `Utf8View` instead of `Utf8`. Columnar
+    /// input then shares each source batch's decoded text buffer with the
+    /// output array rather than copying it.
+    #[must_use]
+    pub const fn with_string_view(mut self, enabled: bool) -> Self {
+        self.string_view = enabled;
+        self
+    }
+
+    /// Writes each group's member columns as a single `List` column named
+    /// after the group, placed where its first member would have been.
+    ///
+    /// Groups can be found with
+    /// [`detect_repeated_groups`](crate::dataset::detect_repeated_groups).
+    /// Members must share a column type. Every row produces a list with one
+    /// element per member; missing values become null elements. Columnar
+    /// batches are written row by row while groups are configured.
+    #[must_use]
+    pub fn with_repeated_groups<I>(mut self, groups: I) -> Self
+    where
+        I: IntoIterator<Item = RepeatedGroup>,
+    {
+        self.repeated_groups = groups.into_iter().collect();
+        self
+    }
+
+    /// Schema of the emitted batches, available once the sink has begun and
+    /// kept after it finishes.
+    #[must_use]
+    pub fn schema(&self) -> Option<SchemaRef> {
+        self.schema.clone()
+    }
+
+    /// Record batches completed so far.
+    #[must_use]
+    pub fn batches(&self) -> &[RecordBatch] {
+        &self.batches
+    }
+
+    /// Removes and returns the completed record batches, so long conversions
+    /// can be drained incrementally.
+    pub fn take_batches(&mut self) -> Vec<RecordBatch> {
+        std::mem::take(&mut self.batches)
+    }
+
+    /// Consumes the sink and returns every completed record batch.
+    #[must_use]
+    pub fn into_batches(self) -> Vec<RecordBatch> {
+        self.batches
+    }
+
+    fn ensure_begun(&self, len: usize) -> Result<()> {
+        if !self.open {
+            return Err(Error::Unsupported {
+                feature: Cow::from("rows written before Arrow sink initialised"),
+            });
+        }
+        if len != self.input_columns {
+            return Err(Error::InvalidArgument {
+                details: Cow::Owned(format!(
+                    "row length {len} does not match column count {}",
+                    self.input_columns
+                )),
+            });
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let Some(schema) = self.schema.clone() else {
+            return Ok(());
+        };
+        if self.rows_buffered == 0 {
+            return Ok(());
+        }
+        let started = Stopwatch::start();
+        let rows = self.rows_buffered;
+        let arrays: Vec<ArrayRef> = self
+            .columns
+            .iter_mut()
+            .map(|column| column.finish(rows))
+            .collect();
+        let options = RecordBatchOptions::new().with_row_count(Some(self.rows_buffered));
+        self.batches
+            .push(RecordBatch::try_new_with_options(schema, arrays, &options)?);
+        self.rows_buffered = 0;
+        telemetry::sink_flushed("arrow", started);
+        Ok(())
+    }
+
+    fn finish_rows(&mut self, rows: usize) -> Result<()> {
+        self.rows_buffered = self.rows_buffered.saturating_add(rows);
+        if self.rows_buffered >= self.batch_rows {
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Carries the variable's measure and alignment on its field, leaving out
+/// whichever the file does not record.
+fn field_metadata(variable: &Variable) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    if variable.measure != Measure::Unknown {
+        metadata.insert(
+            SAS_MEASURE_KEY.to_owned(),
+            variable.measure.as_str().to_owned(),
+        );
+    }
+    if variable.alignment != Alignment::Unknown {
+        metadata.insert(
+            SAS_ALIGNMENT_KEY.to_owned(),
+            variable.alignment.as_str().to_owned(),
+        );
+    }
+    metadata
+}
+
+impl RowSink for ArrowSink {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        validate_sink_begin(&context, self.open, "Arrow")?;
+        self.logger = context.logger.clone();
+        let membership = resolve_repeated_groups(&self.repeated_groups, &context)?;
+        let mut emitted = vec![false; self.repeated_groups.len()];
+        let mut fields = Vec::with_capacity(context.columns.len());
+        self.columns.clear();
+        self.sources.clear();
+        for (index, (variable, column)) in context
+            .metadata
+            .variables
+            .iter()
+            .zip(context.columns)
+            .enumerate()
+        {
+            let Some(group_idx) = membership[index] else {
+                let builder = ColumnBuilder::new(
+                    &variable.name,
+                    column.kind,
+                    self.rounding,
+                    self.string_view,
+                );
+                fields.push(
+                    Field::new(&variable.name, builder.data_type(), true)
+                        .with_metadata(field_metadata(variable)),
+                );
+                self.columns.push(builder);
+                self.sources.push(vec![index]);
+                continue;
+            };
+            if std::mem::replace(&mut emitted[group_idx], true) {
+                continue;
+            }
+            let group = &self.repeated_groups[group_idx];
+            let members = repeated_group_members(group, &context);
+            let builder =
+                ColumnBuilder::new(&group.name, column.kind, self.rounding, self.string_view)
+                    .into_list(members.len());
+            fields.push(Field::new(&group.name, builder.data_type(), true));
+            self.columns.push(builder);
+            self.sources.push(members);
+        }
+        self.input_columns = context.columns.len();
+        self.schema = Some(Arc::new(Schema::new(fields)));
+        self.open = true;
+        self.batches.clear();
+        self.rows_buffered = 0;
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        self.ensure_begun(row.len())?;
+        for (column, sources) in self.columns.iter_mut().zip(&self.sources) {
+            for &source in sources {
+                column.append_value(&row[source], &self.logger);
+            }
+        }
+        self.finish_rows(1)
+    }
+
+    // Cells are decoded from the borrowed row, but completed batches stay in
+    // memory until drained, so the sink does not claim `streams_borrowed_rows`.
+    fn write_streaming_row(&mut self, row: crate::parser::StreamingRow<'_, '_>) -> Result<()> {
+        self.ensure_begun(row.len())?;
+        for (column, sources) in self.columns.iter_mut().zip(&self.sources) {
+            for &source in sources {
+                let value = row.cell(source)?.decode_value()?;
+                column.append_value(&value, &self.logger);
+            }
+        }
+        self.finish_rows(1)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.flush()?;
+        self.open = false;
+        self.columns.clear();
+        self.sources.clear();
+        Ok(())
+    }
+}
+
+impl ColumnarSink for ArrowSink {
+    fn write_columnar_batch(
+        &mut self,
+        batch: &ColumnarBatch<'_>,
+        selection: &[usize],
+    ) -> Result<()> {
+        self.ensure_begun(selection.len())?;
+        let sources = selection
+            .iter()
+            .map(|&source_idx| {
+                batch
+                    .column(source_idx)
+                    .ok_or_else(|| Error::InvalidArgument {
+                        details: Cow::Owned(format!(
+                            "column selection index {source_idx} exceeds available columns"
+                        )),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if !self.repeated_groups.is_empty() {
+            let missing = CellValue::Missing(MissingValue::System);
+            let mut row = Vec::with_capacity(sources.len());
+            for row_index in 0..batch.row_count {
+                row.clear();
+                row.extend(sources.iter().map(|column| {
+                    column
+                        .decode_cell(row_index)
+                        .unwrap_or_else(|| missing.clone())
+                }));
+                self.write_row(&row)?;
+            }
+            return Ok(());
+        }
+        for (column, source) in self.columns.iter_mut().zip(&sources) {
+            column.extend_columnar(source, &self.logger)?;
+        }
+        self.finish_rows(batch.row_count)
+    }
+}
+
+enum Values {
+    Utf8(StringBuilder),
+    Utf8View(StringViewBuilder),
+    Binary(BinaryBuilder),
+    Float64(Float64Builder),
+    Date32(Date32Builder),
+    Timestamp(TimestampMicrosecondBuilder),
+    Time64(Time64MicrosecondBuilder),
+}
+
+struct ColumnBuilder {
+    name: String,
+    values: Values,
+    /// Elements per row when the column is a repeated group's list.
+    list_width: Option<usize>,
+    rounding: TimestampRounding,
+    warned_invalid_value: bool,
+}
+
+impl ColumnBuilder {
+    fn new(name: &str, kind: ColumnKind, rounding: TimestampRounding, string_view: bool) -> Self {
+        let values = match kind {
+            ColumnKind::Character | ColumnKind::Numeric(NumericKind::Text) if string_view => {
+                Values::Utf8View(StringViewBuilder::new())
+            }
+            ColumnKind::Character | ColumnKind::Numeric(NumericKind::Text) => {
+                Values::Utf8(StringBuilder::new())
+            }
+            ColumnKind::Bytes => Values::Binary(BinaryBuilder::new()),
+            ColumnKind::Numeric(NumericKind::Double | NumericKind::Float) => {
+                Values::Float64(Float64Builder::new())
+            }
+            ColumnKind::Numeric(NumericKind::Date) => Values::Date32(Date32Builder::new()),
+            ColumnKind::Numeric(NumericKind::DateTime) => {
+                Values::Timestamp(TimestampMicrosecondBuilder::new().with_timezone(UTC))
+            }
+            ColumnKind::Numeric(NumericKind::Time) => {
+                Values::Time64(Time64MicrosecondBuilder::new())
+            }
+        };
+        Self {
+            name: name.to_owned(),
+            values,
+            list_width: None,
+            rounding,
+            warned_invalid_value: false,
+        }
+    }
+
+    /// Collects `width` values per row into a list.
+    const fn into_list(mut self, width: usize) -> Self {
+        self.list_width = Some(width);
+        self
+    }
+
+    fn data_type(&self) -> DataType {
+        let values = self.value_type();
+        match self.list_width {
+            Some(_) => DataType::List(Arc::new(Field::new_list_field(values, true))),
+            None => values,
+        }
+    }
+
+    fn value_type(&self) -> DataType {
+        match &self.values {
+            Values::Utf8(_) => DataType::Utf8,
+            Values::Utf8View(_) => DataType::Utf8View,
+            Values::Binary(_) => DataType::Binary,
+            Values::Float64(_) => DataType::Float64,
+            Values::Date32(_) => DataType::Date32,
+            Values::Timestamp(_) => DataType::Timestamp(TimeUnit::Microsecond, Some(UTC.into())),
+            Values::Time64(_) => DataType::Time64(TimeUnit::Microsecond),
+        }
+    }
+
+    fn finish(&mut self, rows: usize) -> ArrayRef {
+        let values = self.finish_values();
+        match self.list_width {
+            Some(width) => Arc::new(ListArray::new(
+                Arc::new(Field::new_list_field(values.data_type().clone(), true)),
+                OffsetBuffer::from_lengths(std::iter::repeat_n(width, rows)),
+                values,
+                None,
+            )),
+            None => values,
+        }
+    }
+
+    fn finish_values(&mut self) -> ArrayRef {
+        match &mut self.values {
+            Values::Utf8(builder) => Arc::new(builder.finish()),
+            Values::Utf8View(builder) => Arc::new(builder.finish()),
+            Values::Binary(builder) => Arc::new(builder.finish()),
+            Values::Float64(builder) => Arc::new(builder.finish()),
+            Values::Date32(builder) => Arc::new(builder.finish()),
+            Values::Timestamp(builder) => Arc::new(builder.finish()),
+            Values::Time64(builder) => Arc::new(builder.finish()),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn append_value(&mut self, value: &CellValue<'_>, logger: &Logger) {
+        let rounding = self.rounding;
+        let valid = match (&mut self.values, value) {
+            (Values::Utf8(builder), CellValue::Str(text) | CellValue::NumericString(text)) => {
+                builder.append_option((!text.is_empty()).then_some(&**text));
+                true
+            }
+            (Values::Utf8View(builder), CellValue::Str(text) | CellValue::NumericString(text)) => {
+                builder.append_option((!text.is_empty()).then_some(&**text));
+                true
+            }
+            (Values::Binary(builder), CellValue::Bytes(bytes)) => {
+                builder.append_value(bytes);
+                true
+            }
+            (Values::Float64(builder), CellValue::Float(number)) => {
+                builder.append_value(*number);
+                true
+            }
+            (Values::Float64(builder), CellValue::Int32(number)) => {
+                builder.append_value(f64::from(*number));
+                true
+            }
+            (Values::Float64(builder), CellValue::Int64(number)) => {
+                builder.append_value(*number as f64);
+                true
+            }
+            (Values::Date32(builder), CellValue::Date(date)) => {
+                append_checked(builder, unix_days(*date))
+            }
+            (Values::Timestamp(builder), CellValue::DateTime(datetime)) => append_checked(
+                builder,
+                i64::try_from(rounding.datetime_micros(*datetime)).ok(),
+            ),
+            (Values::Time64(builder), CellValue::Time(duration)) => {
+                append_checked(builder, time_micros(*duration, rounding))
+            }
+            (_, CellValue::Missing(_)) => {
+                self.append_null();
+                true
+            }
+            // Temporal values outside the `time` crate's range decode as plain
+            // numbers; retry them against the raw SAS value.
+            (_, CellValue::Float(number)) => self.append_bits(number.to_bits()),
+            _ => {
+                self.append_null();
+                false
+            }
+        };
+        if !valid {
+            self.warn_invalid(logger);
+        }
+    }
+
+    fn append_null(&mut self) {
+        match &mut self.values {
+            Values::Utf8(builder) => builder.append_null(),
+            Values::Utf8View(builder) => builder.append_null(),
+            Values::Binary(builder) => builder.append_null(),
+            Values::Float64(builder) => builder.append_null(),
+            Values::Date32(builder) => builder.append_null(),
+            Values::Timestamp(builder) => builder.append_null(),
+            Values::Time64(builder) => builder.append_null(),
+        }
+    }
+
+    /// Appends a raw SAS double to a numeric column, returning `false` when
+    /// it could not be represented and a null was written instead.
+    fn append_bits(&mut self, bits: u64) -> bool {
+        let number = f64::from_bits(bits);
+        let rounding = self.rounding;
+        match &mut self.values {
+            Values::Float64(builder) => {
+                builder.append_value(number);
+                true
+            }
+            Values::Date32(builder) => {
+                append_checked(builder, sas_days_to_date(number).and_then(unix_days))
+            }
+            Values::Timestamp(builder) => append_checked(
+                builder,
+                sas_seconds_to_datetime(number)
+                    .and_then(|datetime| i64::try_from(rounding.datetime_micros(datetime)).ok()),
+            ),
+            Values::Time64(builder) => append_checked(
+                builder,
+                sas_seconds_to_time(number).and_then(|duration| time_micros(duration, rounding)),
+            ),
+            Values::Utf8(_) | Values::Utf8View(_) | Values::Binary(_) => {
+                self.append_null();
+                false
+            }
+        }
+    }
+
+    fn extend_columnar(&mut self, column: &ColumnarColumn<'_, '_>, logger: &Logger) -> Result<()> {
+        let mut valid = true;
+        match &mut self.values {
+            Values::Utf8(builder) => {
+                builder.append_array(&string_array(column.utf8_arena()?))?;
+            }
+            Values::Utf8View(builder) => {
+                builder.append_array(&StringViewArray::from(&string_array(column.utf8_arena()?)));
+            }
+            Values::Binary(builder) => {
+                for bytes in column.iter_bytes_range(0, column.len()) {
+                    builder.append_option(bytes);
+                }
+            }
+            _ => {
+                for bits in column.iter_numeric_bits() {
+                    match bits {
+                        Some(bits) => valid &= self.append_bits(bits),
+                        None => self.append_null(),
+                    }
+                }
+            }
+        }
+        if !valid {
+            self.warn_invalid(logger);
+        }
+        Ok(())
+    }
+
+    fn warn_invalid(&mut self, logger: &Logger) {
+        if self.warned_invalid_value {
+            return;
+        }
+        logger.warn(&format!(
+            "column '{}' contains values outside the Arrow {} range; written as null",
+            self.name,
+            self.value_type()
+        ));
+        self.warned_invalid_value = true;
+    }
+}
+
+/// Wraps a decoded arena as a `Utf8` array without copying its buffers.
+fn string_array(arena: Utf8Arena) -> StringArray {
+    let (offsets, data, validity) = arena.into_parts();
+    let nulls = NullBuffer::from(validity);
+    let nulls = (nulls.null_count() > 0).then_some(nulls);
+    // The arena only ever appends whole `str` values at monotonic offsets.
+    StringArray::new(
+        OffsetBuffer::new(ScalarBuffer::from(offsets)),
+        Buffer::from_vec(data),
+        nulls,
+    )
+}
+
+fn append_checked<T>(
+    builder: &mut arrow_array::builder::PrimitiveBuilder<T>,
+    value: Option<T::Native>,
+) -> bool
+where
+    T: arrow_array::ArrowPrimitiveType,
+{
+    builder.append_option(value);
+    value.is_some()
+}
+
+fn unix_days(date: OffsetDateTime) -> Option<i32> {
+    i32::try_from(date.unix_timestamp().div_euclid(SECONDS_PER_DAY)).ok()
+}
+
+fn time_micros(duration: Duration, rounding: TimestampRounding) -> Option<i64> {
+    i64::try_from(rounding.duration_micros(duration)).ok()
+}
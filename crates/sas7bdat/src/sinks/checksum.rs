@@ -0,0 +1,189 @@
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    parser::StreamingRow,
+    sinks::{RowSink, SinkContext},
+};
+use serde::{Deserialize, Serialize};
+use std::hash::Hasher;
+use twox_hash::XxHash3_64;
+
+const TAG_MISSING: u8 = 0;
+const TAG_INTEGER: u8 = 1;
+const TAG_FLOAT: u8 = 2;
+const TAG_STRING: u8 = 3;
+const TAG_NUMERIC_STRING: u8 = 4;
+const TAG_BYTES: u8 = 5;
+const TAG_DATE: u8 = 6;
+const TAG_DATETIME: u8 = 7;
+const TAG_TIME: u8 = 8;
+
+/// Largest magnitude below which every integral `f64` converts to `i64`
+/// exactly (2^63).
+const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+/// Streaming checksum of one column's values, in row order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnChecksum {
+    pub column: String,
+    /// XXH3-64 of the canonicalised values as 16 lowercase hex digits.
+    pub xxh3: String,
+}
+
+/// Computes an XXH3 checksum per column while forwarding rows unchanged to
+/// another sink.
+///
+/// Values are canonicalised before hashing so the checksum depends on what
+/// a value means rather than how it was decoded: every missing value hashes
+/// alike, integral numbers hash the same whether they arrive as `Float`,
+/// `Int32` or `Int64`, and dates, datetimes and times hash as day, nanosecond
+/// and duration counts. Byte order is fixed, so checksums compare across
+/// platforms and between two conversions of the same source.
+pub struct ChecksumSink<S: RowSink> {
+    inner: S,
+    columns: Vec<String>,
+    hashers: Vec<XxHash3_64>,
+}
+
+impl<S: RowSink> ChecksumSink<S> {
+    #[must_use]
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            columns: Vec::new(),
+            hashers: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Checksums of the rows written so far, one per column in output order.
+    #[must_use]
+    pub fn checksums(&self) -> Vec<ColumnChecksum> {
+        self.columns
+            .iter()
+            .zip(&self.hashers)
+            .map(|(column, hasher)| ColumnChecksum {
+                column: column.clone(),
+                xxh3: format!("{:016x}", hasher.finish()),
+            })
+            .collect()
+    }
+
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len == self.hashers.len() {
+            return Ok(());
+        }
+        Err(Error::InvalidArgument {
+            details: format!(
+                "row length {len} does not match expected {}",
+                self.hashers.len()
+            )
+            .into(),
+        })
+    }
+}
+
+impl<S: RowSink> RowSink for ChecksumSink<S> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        self.columns = context
+            .metadata
+            .variables
+            .iter()
+            .map(|variable| variable.name.trim_end().to_owned())
+            .collect();
+        self.hashers = self.columns.iter().map(|_| XxHash3_64::new()).collect();
+        self.inner.begin(context)
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        self.check_len(row.len())?;
+        for (hasher, value) in self.hashers.iter_mut().zip(row) {
+            hash_value(hasher, value);
+        }
+        self.inner.write_row(row)
+    }
+
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        self.check_len(row.len())?;
+        for (hasher, cell) in self.hashers.iter_mut().zip(row.iter()) {
+            hash_value(hasher, &cell?.decode_value()?);
+        }
+        self.inner.write_streaming_row(row)
+    }
+
+    fn streams_borrowed_rows(&self) -> bool {
+        self.inner.streams_borrowed_rows()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort()
+    }
+}
+
+fn hash_value(hasher: &mut XxHash3_64, value: &CellValue<'_>) {
+    match value {
+        CellValue::Missing(_) => hasher.write_u8(TAG_MISSING),
+        CellValue::Float(v) => hash_float(hasher, *v),
+        CellValue::Int32(v) => hash_tagged(hasher, TAG_INTEGER, &i64::from(*v).to_le_bytes()),
+        CellValue::Int64(v) => hash_tagged(hasher, TAG_INTEGER, &v.to_le_bytes()),
+        CellValue::Str(s) => hash_sized(hasher, TAG_STRING, s.as_bytes()),
+        CellValue::NumericString(s) => hash_sized(hasher, TAG_NUMERIC_STRING, s.as_bytes()),
+        CellValue::Bytes(bytes) => hash_sized(hasher, TAG_BYTES, bytes),
+        CellValue::Date(dt) => {
+            hash_tagged(hasher, TAG_DATE, &dt.date().to_julian_day().to_le_bytes());
+        }
+        CellValue::DateTime(dt) => {
+            hash_tagged(
+                hasher,
+                TAG_DATETIME,
+                &dt.unix_timestamp_nanos().to_le_bytes(),
+            );
+        }
+        CellValue::Time(duration) => {
+            hash_tagged(
+                hasher,
+                TAG_TIME,
+                &duration.whole_nanoseconds().to_le_bytes(),
+            );
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn hash_float(hasher: &mut XxHash3_64, value: f64) {
+    if value.fract() == 0.0 && value.abs() < I64_BOUND {
+        // Integral and in range, so the conversion is exact; this also folds
+        // -0.0 into 0.
+        hash_tagged(hasher, TAG_INTEGER, &(value as i64).to_le_bytes());
+    } else if value.is_nan() {
+        hash_tagged(hasher, TAG_FLOAT, &f64::NAN.to_bits().to_le_bytes());
+    } else {
+        hash_tagged(hasher, TAG_FLOAT, &value.to_bits().to_le_bytes());
+    }
+}
+
+fn hash_tagged(hasher: &mut XxHash3_64, tag: u8, payload: &[u8]) {
+    hasher.write_u8(tag);
+    hasher.write(payload);
+}
+
+/// Length-prefixes variable-width payloads so adjacent values cannot run
+/// into each other.
+fn hash_sized(hasher: &mut XxHash3_64, tag: u8, payload: &[u8]) {
+    hasher.write_u8(tag);
+    hasher.write(&(payload.len() as u64).to_le_bytes());
+    hasher.write(payload);
+}
@@ -0,0 +1,95 @@
+use crate::error::Result;
+use std::io::{self, Write};
+
+/// Compression applied to the output of text sinks such as
+/// [`CsvSink`](crate::CsvSink) and [`JsonlSink`](crate::JsonlSink).
+///
+/// The variants are available with the crate features of the same name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputCompression {
+    /// Writes the output as it is.
+    #[default]
+    None,
+    /// Gzip at the given level, from 0 (store) to 9 (smallest); 6 is the
+    /// usual default.
+    #[cfg(feature = "gzip")]
+    Gzip(u32),
+    /// Zstandard at the given level, from 1 to 22; 0 picks the library
+    /// default (3).
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+/// An output writer wrapped in the chosen compressor.
+pub enum CompressedWriter<W: Write> {
+    Plain(W),
+    #[cfg(feature = "gzip")]
+    Gzip(Box<flate2::write::GzEncoder<W>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    // Only starting a zstd encoder can fail, and without the compression
+    // features there is nothing to do at runtime.
+    #[allow(clippy::unnecessary_wraps, clippy::missing_const_for_fn)]
+    pub fn new(output: W, compression: OutputCompression) -> Result<Self> {
+        Ok(match compression {
+            OutputCompression::None => Self::Plain(output),
+            #[cfg(feature = "gzip")]
+            OutputCompression::Gzip(level) => Self::Gzip(Box::new(flate2::write::GzEncoder::new(
+                output,
+                flate2::Compression::new(level.min(9)),
+            ))),
+            #[cfg(feature = "zstd")]
+            OutputCompression::Zstd(level) => Self::Zstd(zstd::Encoder::new(output, level)?),
+        })
+    }
+
+    /// Writes the compressor's trailer and returns the output writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(mut output) => {
+                output.flush()?;
+                Ok(output)
+            }
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => (*encoder).finish(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(output) => output.write(buf),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Plain(output) => output.write_all(buf),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => encoder.write_all(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(output) => output.flush(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
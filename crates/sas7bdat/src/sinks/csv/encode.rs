@@ -1,18 +1,34 @@
 use super::time_format::{write_date, write_datetime, write_time};
-use crate::{cell::CellValue, error::Result};
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+};
 use csv::ByteRecord;
 use itoa::Buffer as ItoaBuffer;
 use ryu::Buffer as RyuBuffer;
+use std::borrow::Cow;
+use time::{Duration, Time, format_description::OwnedFormatItem};
+
+/// How missing values and temporal values are rendered; `None` formats use
+/// the built-in ISO 8601 rendering.
+#[derive(Default)]
+pub struct CellFormats {
+    pub null: Vec<u8>,
+    pub date: Option<OwnedFormatItem>,
+    pub datetime: Option<OwnedFormatItem>,
+    pub time: Option<OwnedFormatItem>,
+}
 
 pub fn encode_value(
     value: &CellValue<'_>,
     out: &mut Vec<u8>,
     ryu: &mut RyuBuffer,
     itoa: &mut ItoaBuffer,
+    formats: &CellFormats,
 ) -> Result<()> {
     out.clear();
     match value {
-        CellValue::Missing(_) => {}
+        CellValue::Missing(_) => out.extend_from_slice(&formats.null),
         CellValue::Float(v) => {
             let s = ryu.format(*v);
             out.extend_from_slice(s.as_bytes());
@@ -29,20 +45,50 @@ pub fn encode_value(
             out.extend_from_slice(s.as_bytes());
         }
         CellValue::Bytes(bytes) => out.extend_from_slice(bytes),
-        CellValue::DateTime(dt) => write_datetime(dt, out),
-        CellValue::Date(dt) => write_date(dt, out),
-        CellValue::Time(dur) => write_time(dur, out)?,
+        CellValue::DateTime(dt) => match &formats.datetime {
+            Some(format) => {
+                dt.format_into(out, format).map_err(|e| format_error(&e))?;
+            }
+            None => write_datetime(dt, out),
+        },
+        CellValue::Date(dt) => match &formats.date {
+            Some(format) => {
+                dt.date()
+                    .format_into(out, format)
+                    .map_err(|e| format_error(&e))?;
+            }
+            None => write_date(dt, out),
+        },
+        CellValue::Time(dur) => match (&formats.time, time_of_day(*dur)) {
+            (Some(format), Some(time)) => {
+                time.format_into(out, format)
+                    .map_err(|e| format_error(&e))?;
+            }
+            _ => write_time(dur, out)?,
+        },
     }
     Ok(())
 }
 
+/// The time of day `duration` after midnight, or `None` for durations
+/// outside one day, which a time-of-day format cannot show.
+pub fn time_of_day(duration: Duration) -> Option<Time> {
+    (!duration.is_negative() && duration < Duration::DAY).then(|| Time::MIDNIGHT + duration)
+}
+
+fn format_error(e: &time::error::Format) -> Error {
+    Error::Sink {
+        details: Cow::Owned(format!("csv temporal format failed: {e}")),
+    }
+}
+
 pub fn flush_record<W: std::io::Write>(
     writer: &mut csv::Writer<W>,
     record: &ByteRecord,
 ) -> Result<()> {
     writer
         .write_byte_record(record)
-        .map_err(|e| crate::error::Error::InvalidMetadata {
+        .map_err(|e| crate::error::Error::Sink {
             details: std::borrow::Cow::Owned(format!("csv write failed: {e}")),
         })
 }
@@ -3,4 +3,4 @@ mod encode;
 mod sink;
 mod time_format;
 
-pub use sink::CsvSink;
+pub use sink::{CsvSink, QuoteStyle};
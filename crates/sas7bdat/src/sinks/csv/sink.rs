@@ -1,27 +1,64 @@
 use super::{
     constants::{DEFAULT_DELIMITER, DEFAULT_SCRATCH_CAPACITY, DEFAULT_WRITE_HEADERS},
-    encode::{encode_value, flush_record},
+    encode::{CellFormats, encode_value, flush_record},
 };
 use crate::{
     cell::CellValue,
+    dataset::VariableKind,
     error::{Error, Result},
     parser::{ColumnKind, NumericKind, StreamingRow},
-    sinks::{RowSink, SinkContext, validate_sink_begin},
+    sinks::{
+        RowSink, SinkContext,
+        compress::{CompressedWriter, OutputCompression},
+        validate_sink_begin,
+    },
+    telemetry::{self, Stopwatch},
 };
-use csv::{ByteRecord, Writer, WriterBuilder};
+use csv::{ByteRecord, Terminator, Writer, WriterBuilder};
 use itoa::Buffer as ItoaBuffer;
 use ryu::Buffer as RyuBuffer;
-use std::{borrow::Cow, io::Write};
+use std::{borrow::Cow, fmt::Write as _, fs::File, io::Write};
+use time::{
+    OffsetDateTime,
+    format_description::{self, OwnedFormatItem},
+};
+
+pub use csv::QuoteStyle;
+
+/// Placeholder in a part file naming template; see
+/// [`CsvSink::with_max_rows_per_file`].
+const PART_PLACEHOLDER: &str = "{part}";
 
 /// Writes decoded rows into a delimited text file (CSV/TSV).
 pub struct CsvSink<W: Write + Send> {
     output: Option<W>,
-    writer: Option<Writer<W>>,
+    writer: Option<Writer<CompressedWriter<W>>>,
+    compression: OutputCompression,
     delimiter: u8,
     write_headers: bool,
+    metadata_comments: bool,
+    rfc4180: bool,
+    quote_style: Option<QuoteStyle>,
+    null_value: String,
+    date_format: Option<String>,
+    datetime_format: Option<String>,
+    time_format: Option<String>,
+    formats: CellFormats,
     column_count: usize,
+    comments: Vec<u8>,
+    header: ByteRecord,
     record: ByteRecord,
     scratch: Vec<Vec<u8>>, // one scratch buffer per column
+    parts: Option<PartFiles<W>>,
+}
+
+/// Rotation of the output into part files of at most `max_rows` rows.
+struct PartFiles<W> {
+    max_rows: u64,
+    template: String,
+    rows_in_part: u64,
+    next_part: usize,
+    open: Box<dyn FnMut(usize) -> Result<W> + Send>,
 }
 
 enum RowValue<'a> {
@@ -44,11 +81,23 @@ impl<W: Write + Send> CsvSink<W> {
         Self {
             output: Some(writer),
             writer: None,
+            compression: OutputCompression::None,
             delimiter: DEFAULT_DELIMITER,
             write_headers: DEFAULT_WRITE_HEADERS,
+            metadata_comments: false,
+            rfc4180: false,
+            quote_style: None,
+            null_value: String::new(),
+            date_format: None,
+            datetime_format: None,
+            time_format: None,
+            formats: CellFormats::default(),
             column_count: 0,
+            comments: Vec::new(),
+            header: ByteRecord::new(),
             record: ByteRecord::new(),
             scratch: Vec::new(),
+            parts: None,
         }
     }
 
@@ -64,42 +113,207 @@ impl<W: Write + Send> CsvSink<W> {
         self
     }
 
+    /// Starts the output, and every part file, with `#` comment lines
+    /// naming the dataset and giving each column's position, type, format
+    /// and label in output order, so the CSV keeps the metadata a reader
+    /// needs to interpret it. Off by default because many CSV loaders
+    /// reject comment lines.
+    #[must_use]
+    pub const fn with_metadata_comments(mut self, enabled: bool) -> Self {
+        self.metadata_comments = enabled;
+        self
+    }
+
+    /// Writes strict RFC 4180 output: CRLF record terminators, and fields
+    /// quoted whenever they contain the delimiter, a double quote, CR or LF,
+    /// with embedded quotes doubled. Rejects delimiters that cannot be
+    /// escaped (`"`, CR and LF) when the sink begins.
+    #[must_use]
+    pub const fn with_rfc4180(mut self, strict: bool) -> Self {
+        self.rfc4180 = strict;
+        self
+    }
+
+    /// Compresses the output, and every part file when the output is split
+    /// with [`with_max_rows_per_file`](CsvSink::with_max_rows_per_file).
+    /// Name the files accordingly, such as `sales.csv.gz`.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: OutputCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Quotes fields according to `style`; by default fields are quoted only
+    /// when they need it. [`QuoteStyle::Never`] is rejected when the sink
+    /// begins if [`with_rfc4180`](Self::with_rfc4180) is set.
+    #[must_use]
+    pub const fn with_quote_style(mut self, style: QuoteStyle) -> Self {
+        self.quote_style = Some(style);
+        self
+    }
+
+    /// Writes `text` for missing values instead of an empty field.
+    #[must_use]
+    pub fn with_null_value(mut self, text: impl Into<String>) -> Self {
+        self.null_value = text.into();
+        self
+    }
+
+    /// Renders dates with `format`, a [`time` format description] such as
+    /// `"[day].[month].[year]"`, instead of `YYYY-MM-DD`.
+    ///
+    /// [`begin`](RowSink::begin) fails with [`Error::Config`] if the
+    /// description is invalid or uses components a date does not have.
+    ///
+    /// [`time` format description]: https://time-rs.github.io/book/api/format-description.html
+    #[must_use]
+    pub fn with_date_format(mut self, format: &str) -> Self {
+        self.date_format = Some(format.to_owned());
+        self
+    }
+
+    /// Renders datetimes with `format` instead of
+    /// `YYYY-MM-DD HH:MM:SS[.mmm]`; see
+    /// [`with_date_format`](Self::with_date_format). Datetimes are in UTC.
+    #[must_use]
+    pub fn with_datetime_format(mut self, format: &str) -> Self {
+        self.datetime_format = Some(format.to_owned());
+        self
+    }
+
+    /// Renders times with `format` instead of `HH:MM:SS[.mmm]`; see
+    /// [`with_date_format`](Self::with_date_format). Times outside a single
+    /// day, which no time-of-day format can show, keep the default
+    /// rendering.
+    #[must_use]
+    pub fn with_time_format(mut self, format: &str) -> Self {
+        self.time_format = Some(format.to_owned());
+        self
+    }
+
+    /// Returns the underlying writer once the sink has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sink has not finished or the output was
+    /// already taken.
+    pub fn into_inner(mut self) -> Result<W> {
+        if self.writer.is_some() {
+            return Err(Error::Unsupported {
+                feature: Cow::from("attempted to take CSV writer before sink was finished"),
+            });
+        }
+        self.output.take().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("CSV sink output already consumed"),
+        })
+    }
+
     fn build_writer(&mut self) -> Result<()> {
-        let output = self.output.take().ok_or_else(|| Error::InvalidMetadata {
+        if self.rfc4180 && matches!(self.delimiter, b'"' | b'\r' | b'\n') {
+            return Err(Error::Unsupported {
+                feature: Cow::Owned(format!(
+                    "RFC 4180 output with delimiter {:?}",
+                    char::from(self.delimiter)
+                )),
+            });
+        }
+        if self.rfc4180 && matches!(self.quote_style, Some(QuoteStyle::Never)) {
+            return Err(Error::Unsupported {
+                feature: Cow::from("RFC 4180 output without quoting"),
+            });
+        }
+        let output = self.output.take().ok_or_else(|| Error::InvalidArgument {
             details: Cow::from("CSV sink output already taken"),
         })?;
         let mut builder = WriterBuilder::new();
         builder.delimiter(self.delimiter);
+        if self.rfc4180 {
+            builder
+                .terminator(Terminator::CRLF)
+                .quote_style(QuoteStyle::Necessary)
+                .quote(b'"')
+                .double_quote(true);
+        }
+        if let Some(style) = self.quote_style {
+            builder.quote_style(style);
+        }
+        let mut output = CompressedWriter::new(output, self.compression)?;
+        output.write_all(&self.comments)?;
         let writer = builder.from_writer(output);
         self.writer = Some(writer);
         Ok(())
     }
 
-    fn write_headers(&mut self, context: &SinkContext<'_>) -> Result<()> {
+    fn cell_formats(&self) -> Result<CellFormats> {
+        let sample = OffsetDateTime::UNIX_EPOCH;
+        Ok(CellFormats {
+            null: self.null_value.as_bytes().to_vec(),
+            date: parse_format("date", self.date_format.as_deref(), |format| {
+                sample.date().format(format)
+            })?,
+            datetime: parse_format("datetime", self.datetime_format.as_deref(), |format| {
+                sample.format(format)
+            })?,
+            time: parse_format("time", self.time_format.as_deref(), |format| {
+                sample.time().format(format)
+            })?,
+        })
+    }
+
+    fn write_headers(&mut self) -> Result<()> {
         if !self.write_headers {
             return Ok(());
         }
-        let mut header = ByteRecord::new();
-        for (variable, _column) in context
-            .metadata
-            .variables
-            .iter()
-            .zip(context.columns.iter())
-        {
-            header.push_field(variable.name.trim_end().as_bytes());
-        }
         let writer = self.writer.as_mut().expect("csv writer must be present");
         writer
-            .write_byte_record(&header)
-            .map_err(|e| Error::InvalidMetadata {
+            .write_byte_record(&self.header)
+            .map_err(|e| Error::Sink {
                 details: Cow::Owned(format!("csv header write failed: {e}")),
             })?;
         Ok(())
     }
 
+    fn check_parts(&mut self) -> Result<()> {
+        let Some(parts) = self.parts.as_mut() else {
+            return Ok(());
+        };
+        if parts.max_rows == 0 || !parts.template.contains(PART_PLACEHOLDER) {
+            return Err(Error::InvalidArgument {
+                details: Cow::Owned(format!(
+                    "CSV part files need a positive row limit and {PART_PLACEHOLDER} in '{}'",
+                    parts.template
+                )),
+            });
+        }
+        parts.rows_in_part = 0;
+        parts.next_part = 1;
+        Ok(())
+    }
+
+    /// Counts the next row against the current part, first moving to a new
+    /// part file when the current one is full.
+    fn reserve_part_row(&mut self) -> Result<()> {
+        let Some(parts) = self.parts.as_mut() else {
+            return Ok(());
+        };
+        if parts.rows_in_part < parts.max_rows {
+            parts.rows_in_part += 1;
+            return Ok(());
+        }
+        let output = (parts.open)(parts.next_part)?;
+        parts.next_part += 1;
+        parts.rows_in_part = 1;
+        if let Some(writer) = self.writer.take() {
+            finish_writer(writer)?;
+        }
+        self.output = Some(output);
+        self.build_writer()?;
+        self.write_headers()
+    }
+
     fn ensure_row_len(&self, len: usize) -> Result<()> {
         if len != self.column_count {
-            return Err(Error::InvalidMetadata {
+            return Err(Error::InvalidArgument {
                 details: Cow::Owned(format!(
                     "row length {len} does not match expected {}",
                     self.column_count
@@ -120,11 +334,12 @@ impl<W: Write + Send> CsvSink<W> {
         I: IntoIterator<Item = Result<RowValue<'a>>>,
     {
         let (mut ryu, mut itoa) = self.prepare_row_buffers(len)?;
+        self.reserve_part_row()?;
 
         for (idx, value_result) in values.into_iter().enumerate() {
             let value = value_result?;
             let buf = &mut self.scratch[idx];
-            encode_value(value.as_ref(), buf, &mut ryu, &mut itoa)?;
+            encode_value(value.as_ref(), buf, &mut ryu, &mut itoa, &self.formats)?;
             self.record.push_field(buf);
         }
 
@@ -133,6 +348,117 @@ impl<W: Write + Send> CsvSink<W> {
     }
 }
 
+/// Flushes the CSV writer and ends the compressed stream under it.
+fn finish_writer<W: Write>(writer: Writer<CompressedWriter<W>>) -> Result<W> {
+    let output = writer.into_inner().map_err(|e| Error::Sink {
+        details: Cow::Owned(format!("csv into_inner failed: {e}")),
+    })?;
+    Ok(output.finish()?)
+}
+
+/// Comment block describing the dataset and its columns in output order.
+fn metadata_comments(context: &SinkContext<'_>, terminator: &str) -> Vec<u8> {
+    let metadata = context.metadata;
+    let mut text = String::new();
+    let mut line = |content: &str| {
+        text.push_str("# ");
+        text.push_str(&single_line(content));
+        text.push_str(terminator);
+    };
+    if let Some(name) = metadata
+        .table_name
+        .as_deref()
+        .filter(|name| !name.is_empty())
+    {
+        line(&format!("dataset: {name}"));
+    }
+    if let Some(label) = metadata.file_label.as_deref() {
+        line(&format!("label: {}", quoted(label)));
+    }
+    if let Some(path) = context.source_path.as_deref() {
+        line(&format!("source: {path}"));
+    }
+    for (position, variable) in metadata.variables.iter().enumerate() {
+        let kind = match variable.kind {
+            VariableKind::Numeric => "numeric",
+            VariableKind::Character => "character",
+        };
+        let mut description = format!("{} {}: {kind}", position + 1, variable.name.trim_end());
+        if let Some(format) = &variable.format {
+            let _ = write!(description, ", format {format}");
+        }
+        if let Some(label) = variable.label.as_deref().filter(|label| !label.is_empty()) {
+            let _ = write!(description, ", label {}", quoted(label));
+        }
+        line(&description);
+    }
+    text.into_bytes()
+}
+
+/// Replaces line breaks so a value cannot end its comment line early.
+fn single_line(text: &str) -> Cow<'_, str> {
+    if text.contains(['\r', '\n']) {
+        Cow::Owned(text.replace("\r\n", " ").replace(['\r', '\n'], " "))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Wraps `text` in double quotes, doubling the quotes inside it.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Parses a format description and checks it can render a sample value,
+/// so that a description using components the value lacks fails up front.
+fn parse_format(
+    kind: &str,
+    format: Option<&str>,
+    render: impl FnOnce(&OwnedFormatItem) -> std::result::Result<String, time::error::Format>,
+) -> Result<Option<OwnedFormatItem>> {
+    let Some(format) = format else {
+        return Ok(None);
+    };
+    let parsed = format_description::parse_owned::<1>(format)
+        .map_err(|e| invalid_format(kind, format, &e))?;
+    render(&parsed).map_err(|e| invalid_format(kind, format, &e))?;
+    Ok(Some(parsed))
+}
+
+fn invalid_format(kind: &str, format: &str, error: &dyn std::fmt::Display) -> Error {
+    Error::Config {
+        details: Cow::Owned(format!("invalid CSV {kind} format '{format}': {error}")),
+    }
+}
+
+impl CsvSink<File> {
+    /// Splits the output into part files of at most `max_rows` rows each,
+    /// every part starting with the header row.
+    ///
+    /// The file passed to [`CsvSink::new`] holds part 0. Part `n` is created
+    /// on demand at `naming_template` with `{part}` replaced by `n` padded to
+    /// five digits, so `"sales-{part}.csv"` yields `sales-00001.csv`,
+    /// `sales-00002.csv`, ... After [`finish`](RowSink::finish),
+    /// [`CsvSink::into_inner`] returns the last part. [`begin`](RowSink::begin)
+    /// fails unless `max_rows` is positive and the template holds `{part}`.
+    #[must_use]
+    pub fn with_max_rows_per_file(mut self, max_rows: u64, naming_template: &str) -> Self {
+        let template = naming_template.to_owned();
+        let open_template = template.clone();
+        self.parts = Some(PartFiles {
+            max_rows,
+            template,
+            rows_in_part: 0,
+            next_part: 1,
+            open: Box::new(move |part| {
+                let path = open_template.replace(PART_PLACEHOLDER, &format!("{part:05}"));
+                Ok(File::create(path)?)
+            }),
+        });
+        self
+    }
+}
+
 impl<W: Write + Send> RowSink for CsvSink<W> {
     fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
         validate_sink_begin(&context, self.writer.is_some(), "CSV")?;
@@ -147,15 +473,26 @@ impl<W: Write + Send> RowSink for CsvSink<W> {
                 ColumnKind::Character => {
                     let _ = var;
                 }
-                ColumnKind::Numeric(
+                ColumnKind::Bytes
+                | ColumnKind::Numeric(
                     NumericKind::Double
                     | NumericKind::Date
                     | NumericKind::DateTime
-                    | NumericKind::Time,
+                    | NumericKind::Time
+                    | NumericKind::Float
+                    | NumericKind::Text,
                 ) => {}
             }
         }
 
+        self.check_parts()?;
+        self.formats = self.cell_formats()?;
+        self.comments = if self.metadata_comments {
+            let terminator = if self.rfc4180 { "\r\n" } else { "\n" };
+            metadata_comments(&context, terminator)
+        } else {
+            Vec::new()
+        };
         self.build_writer()?;
         self.column_count = context.columns.len();
         self.record = ByteRecord::with_capacity(self.column_count, 0);
@@ -163,7 +500,14 @@ impl<W: Write + Send> RowSink for CsvSink<W> {
             .map(|_| Vec::with_capacity(DEFAULT_SCRATCH_CAPACITY))
             .collect();
 
-        self.write_headers(&context)?;
+        self.header = context
+            .metadata
+            .variables
+            .iter()
+            .zip(context.columns.iter())
+            .map(|(variable, _column)| variable.name.trim_end().as_bytes())
+            .collect();
+        self.write_headers()?;
         Ok(())
     }
 
@@ -185,12 +529,15 @@ impl<W: Write + Send> RowSink for CsvSink<W> {
         )
     }
 
+    fn streams_borrowed_rows(&self) -> bool {
+        true
+    }
+
     fn finish(&mut self) -> Result<()> {
-        if let Some(mut writer) = self.writer.take() {
-            writer.flush()?;
-            let out = writer.into_inner().map_err(|e| Error::InvalidMetadata {
-                details: Cow::Owned(format!("csv into_inner failed: {e}")),
-            })?;
+        if let Some(writer) = self.writer.take() {
+            let started = Stopwatch::start();
+            let out = finish_writer(writer)?;
+            telemetry::sink_flushed("csv", started);
             self.output = Some(out);
         }
         self.column_count = 0;
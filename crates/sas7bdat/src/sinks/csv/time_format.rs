@@ -33,7 +33,7 @@ pub fn write_time(dur: &Duration, out: &mut Vec<u8>) -> Result<()> {
     let nanos_total = dur.whole_nanoseconds();
     let nanos = nanos_total - i128::from(total_seconds) * 1_000_000_000;
     let mut millis =
-        i64::try_from((nanos.abs() + 500_000) / 1_000_000).map_err(|_| Error::InvalidMetadata {
+        i64::try_from((nanos.abs() + 500_000) / 1_000_000).map_err(|_| Error::Sink {
             details: std::borrow::Cow::from("time millisecond rounding overflow"),
         })?;
     if millis >= 1000 {
@@ -48,13 +48,13 @@ pub fn write_time(dur: &Duration, out: &mut Vec<u8>) -> Result<()> {
     remaining -= minutes * 60;
     let seconds = remaining;
 
-    let hours_u8 = u8::try_from(hours).map_err(|_| Error::InvalidMetadata {
+    let hours_u8 = u8::try_from(hours).map_err(|_| Error::Sink {
         details: std::borrow::Cow::from("time hours component out of range for CSV formatting"),
     })?;
-    let minutes_u8 = u8::try_from(minutes).map_err(|_| Error::InvalidMetadata {
+    let minutes_u8 = u8::try_from(minutes).map_err(|_| Error::Sink {
         details: std::borrow::Cow::from("time minutes component out of range for CSV formatting"),
     })?;
-    let seconds_u8 = u8::try_from(seconds).map_err(|_| Error::InvalidMetadata {
+    let seconds_u8 = u8::try_from(seconds).map_err(|_| Error::Sink {
         details: std::borrow::Cow::from("time seconds component out of range for CSV formatting"),
     })?;
 
@@ -66,7 +66,7 @@ pub fn write_time(dur: &Duration, out: &mut Vec<u8>) -> Result<()> {
 
     if millis != 0 {
         out.push(b'.');
-        let millis_u16 = u16::try_from(millis).map_err(|_| Error::InvalidMetadata {
+        let millis_u16 = u16::try_from(millis).map_err(|_| Error::Sink {
             details: std::borrow::Cow::from(
                 "time milliseconds component out of range for CSV formatting",
             ),
@@ -0,0 +1,218 @@
+use crate::{
+    cell::{CellValue, MissingValue},
+    dataset::{MissingLiteral, TaggedMissing},
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::{
+    borrow::Cow,
+    io::{self, Read},
+};
+use time::{Duration, OffsetDateTime};
+
+const TAG_FLOAT: u8 = 0;
+const TAG_INT32: u8 = 1;
+const TAG_INT64: u8 = 2;
+const TAG_NUMERIC_STRING: u8 = 3;
+const TAG_STR: u8 = 4;
+const TAG_BYTES: u8 = 5;
+const TAG_DATETIME: u8 = 6;
+const TAG_DATE: u8 = 7;
+const TAG_TIME: u8 = 8;
+const TAG_MISSING_SYSTEM: u8 = 9;
+const TAG_MISSING_TAGGED: u8 = 10;
+const TAG_MISSING_RANGE: u8 = 11;
+
+const LITERAL_NUMERIC: u8 = 0;
+const LITERAL_STRING: u8 = 1;
+const NO_TAG: u32 = u32::MAX;
+
+/// Appends a self-delimiting encoding of `value` to `out`.
+///
+/// Equal cells encode to equal bytes, so concatenated encodings double as
+/// hash keys.
+pub(super) fn encode_cell(value: &CellValue<'_>, out: &mut Vec<u8>) {
+    match value {
+        CellValue::Float(v) => {
+            out.push(TAG_FLOAT);
+            out.extend_from_slice(&v.to_bits().to_le_bytes());
+        }
+        CellValue::Int32(v) => {
+            out.push(TAG_INT32);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        CellValue::Int64(v) => {
+            out.push(TAG_INT64);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        CellValue::NumericString(text) => {
+            out.push(TAG_NUMERIC_STRING);
+            encode_bytes(text.as_bytes(), out);
+        }
+        CellValue::Str(text) => {
+            out.push(TAG_STR);
+            encode_bytes(text.as_bytes(), out);
+        }
+        CellValue::Bytes(bytes) => {
+            out.push(TAG_BYTES);
+            encode_bytes(bytes, out);
+        }
+        CellValue::DateTime(value) => {
+            out.push(TAG_DATETIME);
+            out.extend_from_slice(&value.unix_timestamp_nanos().to_le_bytes());
+        }
+        CellValue::Date(value) => {
+            out.push(TAG_DATE);
+            out.extend_from_slice(&value.unix_timestamp_nanos().to_le_bytes());
+        }
+        CellValue::Time(duration) => {
+            out.push(TAG_TIME);
+            out.extend_from_slice(&duration.whole_nanoseconds().to_le_bytes());
+        }
+        CellValue::Missing(MissingValue::System) => out.push(TAG_MISSING_SYSTEM),
+        CellValue::Missing(MissingValue::Tagged(tagged)) => {
+            out.push(TAG_MISSING_TAGGED);
+            out.extend_from_slice(&tagged.tag.map_or(NO_TAG, u32::from).to_le_bytes());
+            encode_literal(&tagged.literal, out);
+        }
+        CellValue::Missing(MissingValue::Range { lower, upper }) => {
+            out.push(TAG_MISSING_RANGE);
+            encode_literal(lower, out);
+            encode_literal(upper, out);
+        }
+    }
+}
+
+/// Decodes one cell written by [`encode_cell`].
+pub(super) fn decode_cell(input: &mut impl Read) -> io::Result<CellValue<'static>> {
+    let value = match input.read_u8()? {
+        TAG_FLOAT => CellValue::Float(f64::from_bits(input.read_u64::<LittleEndian>()?)),
+        TAG_INT32 => CellValue::Int32(input.read_i32::<LittleEndian>()?),
+        TAG_INT64 => CellValue::Int64(input.read_i64::<LittleEndian>()?),
+        TAG_NUMERIC_STRING => CellValue::NumericString(Cow::Owned(decode_string(input)?)),
+        TAG_STR => CellValue::Str(Cow::Owned(decode_string(input)?)),
+        TAG_BYTES => CellValue::Bytes(Cow::Owned(decode_bytes(input)?)),
+        TAG_DATETIME => CellValue::DateTime(decode_timestamp(input)?),
+        TAG_DATE => CellValue::Date(decode_timestamp(input)?),
+        TAG_TIME => {
+            let nanos = input.read_i128::<LittleEndian>()?;
+            let nanos = i64::try_from(nanos).map_err(|_| invalid("time out of range"))?;
+            CellValue::Time(Duration::nanoseconds(nanos))
+        }
+        TAG_MISSING_SYSTEM => CellValue::Missing(MissingValue::System),
+        TAG_MISSING_TAGGED => {
+            let tag = match input.read_u32::<LittleEndian>()? {
+                NO_TAG => None,
+                code => Some(char::from_u32(code).ok_or_else(|| invalid("invalid missing tag"))?),
+            };
+            let literal = decode_literal(input)?;
+            CellValue::Missing(MissingValue::Tagged(TaggedMissing { tag, literal }))
+        }
+        TAG_MISSING_RANGE => {
+            let lower = decode_literal(input)?;
+            let upper = decode_literal(input)?;
+            CellValue::Missing(MissingValue::Range { lower, upper })
+        }
+        _ => return Err(invalid("unknown cell tag")),
+    };
+    Ok(value)
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    let len = u32::try_from(bytes.len()).unwrap_or(u32::MAX);
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&bytes[..len as usize]);
+}
+
+fn encode_literal(literal: &MissingLiteral, out: &mut Vec<u8>) {
+    match literal {
+        MissingLiteral::Numeric(v) => {
+            out.push(LITERAL_NUMERIC);
+            out.extend_from_slice(&v.to_bits().to_le_bytes());
+        }
+        MissingLiteral::String(text) => {
+            out.push(LITERAL_STRING);
+            encode_bytes(text.as_bytes(), out);
+        }
+    }
+}
+
+fn decode_bytes(input: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = input.read_u32::<LittleEndian>()? as usize;
+    let mut bytes = vec![0; len];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn decode_string(input: &mut impl Read) -> io::Result<String> {
+    String::from_utf8(decode_bytes(input)?).map_err(|_| invalid("invalid UTF-8 in spilled text"))
+}
+
+fn decode_timestamp(input: &mut impl Read) -> io::Result<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp_nanos(input.read_i128::<LittleEndian>()?)
+        .map_err(|_| invalid("timestamp out of range"))
+}
+
+fn decode_literal(input: &mut impl Read) -> io::Result<MissingLiteral> {
+    match input.read_u8()? {
+        LITERAL_NUMERIC => Ok(MissingLiteral::Numeric(f64::from_bits(
+            input.read_u64::<LittleEndian>()?,
+        ))),
+        LITERAL_STRING => Ok(MissingLiteral::String(decode_string(input)?)),
+        _ => Err(invalid("unknown missing literal tag")),
+    }
+}
+
+fn invalid(details: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("dedup spill file: {details}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_cell, encode_cell};
+    use crate::{
+        cell::{CellValue, MissingValue},
+        dataset::{MissingLiteral, TaggedMissing},
+    };
+    use time::{Duration, OffsetDateTime};
+
+    #[test]
+    fn cells_round_trip() {
+        let cells = [
+            CellValue::Float(1.5),
+            CellValue::Int32(-4),
+            CellValue::Int64(1 << 40),
+            CellValue::NumericString("1.25".into()),
+            CellValue::Str("ÆØÅ".into()),
+            CellValue::Bytes(vec![0, 1, 2].into()),
+            CellValue::DateTime(OffsetDateTime::UNIX_EPOCH + Duration::seconds(90)),
+            CellValue::Date(OffsetDateTime::UNIX_EPOCH - Duration::days(3)),
+            CellValue::Time(Duration::milliseconds(1_500)),
+            CellValue::Missing(MissingValue::System),
+            CellValue::Missing(MissingValue::Tagged(TaggedMissing {
+                tag: Some('A'),
+                literal: MissingLiteral::Numeric(f64::NAN),
+            })),
+            CellValue::Missing(MissingValue::Range {
+                lower: MissingLiteral::String("a".into()),
+                upper: MissingLiteral::Numeric(2.0),
+            }),
+        ];
+        let mut encoded = Vec::new();
+        for cell in &cells {
+            encode_cell(cell, &mut encoded);
+        }
+        let mut input = encoded.as_slice();
+        for cell in &cells {
+            let decoded = decode_cell(&mut input).expect("decode");
+            let mut reencoded = Vec::new();
+            encode_cell(&decoded, &mut reencoded);
+            let mut expected = Vec::new();
+            encode_cell(cell, &mut expected);
+            assert_eq!(reencoded, expected);
+        }
+        assert!(input.is_empty());
+    }
+}
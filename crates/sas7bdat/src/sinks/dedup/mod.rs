@@ -0,0 +1,233 @@
+mod codec;
+mod spill;
+
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    sinks::{RowSink, SinkContext},
+    value,
+};
+use spill::SpillStore;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+const DEFAULT_SPILL_MEMORY: usize = 256 * 1024 * 1024;
+
+/// Which row of a set of duplicates [`DedupSink`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupKeep {
+    /// Keep the first row seen for each key.
+    #[default]
+    First,
+    /// Keep the last row seen for each key, at the position it appeared.
+    Last,
+}
+
+/// Drops rows whose key columns repeat an earlier row before forwarding them
+/// to another sink.
+///
+/// Keys are compared with SAS semantics (see [`value::compare`]), so `1` and
+/// `1.0` or `"AB"` and `"AB  "` are duplicates.
+///
+/// Rows keep their original order. With [`DedupKeep::First`] and no spill
+/// directory, rows are forwarded as they arrive and only the keys are held in
+/// memory; [`DedupKeep::Last`] buffers the surviving rows until
+/// [`RowSink::finish`]. Setting a spill directory bounds memory for large
+/// inputs, such as hundreds of millions of person-level keys: rows are
+/// sorted in batches that fit the
+/// [spill memory budget](Self::with_spill_memory), written to temporary run
+/// files (zstd-compressed with the `zstd` feature), and deduplicated by
+/// merging the runs from disk.
+pub struct DedupSink<S: RowSink> {
+    inner: S,
+    keys: Vec<String>,
+    keep: DedupKeep,
+    spill_dir: Option<PathBuf>,
+    spill_memory: usize,
+    key_indices: Vec<usize>,
+    key_buffer: Vec<u8>,
+    seen: HashSet<Box<[u8]>>,
+    last_slots: HashMap<Box<[u8]>, usize>,
+    last_rows: Vec<Option<Vec<CellValue<'static>>>>,
+    store: Option<SpillStore>,
+    next_seq: u64,
+    removed: u64,
+}
+
+impl<S: RowSink> DedupSink<S> {
+    /// Wraps `inner`, treating rows with equal values in `keys` as
+    /// duplicates. Key names are matched case-insensitively.
+    pub fn new<I, T>(inner: S, keys: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            inner,
+            keys: keys.into_iter().map(Into::into).collect(),
+            keep: DedupKeep::default(),
+            spill_dir: None,
+            spill_memory: DEFAULT_SPILL_MEMORY,
+            key_indices: Vec::new(),
+            key_buffer: Vec::new(),
+            seen: HashSet::new(),
+            last_slots: HashMap::new(),
+            last_rows: Vec::new(),
+            store: None,
+            next_seq: 0,
+            removed: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_keep(mut self, keep: DedupKeep) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Spills rows to temporary files in `dir` instead of keeping keys and
+    /// rows in memory. The files are removed when the sink finishes.
+    #[must_use]
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+
+    /// Caps the rows buffered in memory before a spill run is written
+    /// (256 MiB by default). Smaller budgets write more, smaller runs.
+    #[must_use]
+    pub const fn with_spill_memory(mut self, bytes: usize) -> Self {
+        self.spill_memory = bytes;
+        self
+    }
+
+    /// Kept for compatibility: sets the spill memory budget to the default
+    /// divided by `partitions`, so more partitions still mean less held in
+    /// memory at a time. `0` is treated as `1`.
+    #[deprecated(
+        since = "0.2.0",
+        note = "spilled rows are now merged from sorted runs; use `with_spill_memory`"
+    )]
+    #[must_use]
+    pub const fn with_spill_partitions(self, partitions: usize) -> Self {
+        let partitions = if partitions == 0 { 1 } else { partitions };
+        self.with_spill_memory(DEFAULT_SPILL_MEMORY / partitions)
+    }
+
+    /// Number of duplicate rows dropped by the last completed conversion.
+    #[must_use]
+    pub const fn removed_rows(&self) -> u64 {
+        self.removed
+    }
+
+    #[must_use]
+    pub const fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn reset(&mut self) {
+        self.seen.clear();
+        self.last_slots.clear();
+        self.last_rows.clear();
+        self.store = None;
+        self.next_seq = 0;
+    }
+}
+
+impl<S: RowSink> RowSink for DedupSink<S> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        if self.keys.is_empty() {
+            return Err(Error::InvalidArgument {
+                details: Cow::from("deduplication requires at least one key column"),
+            });
+        }
+        let variables = &context.metadata.variables;
+        self.key_indices = self
+            .keys
+            .iter()
+            .map(|key| {
+                variables
+                    .iter()
+                    .position(|v| v.name.trim_end().eq_ignore_ascii_case(key.trim_end()))
+                    .ok_or_else(|| Error::InvalidArgument {
+                        details: format!("dedup key column '{key}' not found in dataset").into(),
+                    })
+            })
+            .collect::<Result<_>>()?;
+        self.reset();
+        self.removed = 0;
+        if let Some(dir) = &self.spill_dir {
+            self.store = Some(SpillStore::create(dir, self.spill_memory)?);
+        }
+        self.inner.begin(context)
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        self.key_buffer.clear();
+        for &index in &self.key_indices {
+            let cell = row.get(index).ok_or_else(|| Error::InvalidArgument {
+                details: format!("row has no column at index {index}").into(),
+            })?;
+            value::encode_key(cell, &mut self.key_buffer);
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        if let Some(store) = self.store.as_mut() {
+            store.push(seq, &self.key_buffer, row)?;
+            return Ok(());
+        }
+        match self.keep {
+            DedupKeep::First => {
+                if self.seen.contains(self.key_buffer.as_slice()) {
+                    self.removed += 1;
+                    return Ok(());
+                }
+                self.seen.insert(self.key_buffer.as_slice().into());
+                self.inner.write_row(row)
+            }
+            DedupKeep::Last => {
+                let slot = self.last_rows.len();
+                if let Some(previous) = self
+                    .last_slots
+                    .insert(self.key_buffer.as_slice().into(), slot)
+                {
+                    self.last_rows[previous] = None;
+                    self.removed += 1;
+                }
+                self.last_rows.push(Some(
+                    row.iter().cloned().map(CellValue::into_owned).collect(),
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(store) = self.store.take() {
+            let inner = &mut self.inner;
+            self.removed += store.drain(self.keep, |row| inner.write_row(row))?;
+        }
+        for row in std::mem::take(&mut self.last_rows).into_iter().flatten() {
+            self.inner.write_row(&row)?;
+        }
+        self.reset();
+        self.inner.finish()
+    }
+
+    /// Drops the rows still held for comparison, and any spill files, then
+    /// aborts the inner sink.
+    fn abort(&mut self) -> Result<()> {
+        // Dropping the store removes its spill files.
+        self.reset();
+        self.inner.abort()
+    }
+}
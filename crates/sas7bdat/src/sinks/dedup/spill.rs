@@ -0,0 +1,333 @@
+use super::{
+    DedupKeep,
+    codec::{decode_cell, encode_cell},
+};
+use crate::{cell::CellValue, error::Result};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    mem,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static SPILL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Most run files read at once while merging; more runs are first merged
+/// into fewer, larger ones.
+const MERGE_FAN_IN: usize = 64;
+/// Buffered bytes counted per record on top of its key and row.
+const RECORD_OVERHEAD: usize = mem::size_of::<Record>();
+#[cfg(feature = "zstd")]
+const SPILL_ZSTD_LEVEL: i32 = 1;
+
+/// Rows spilled to sorted run files and deduplicated by an external merge,
+/// so that memory stays within a fixed budget however many keys the input
+/// holds.
+///
+/// Rows are buffered until the budget is reached, sorted by key and
+/// sequence number, and written out as a run. Merging the runs brings the
+/// rows of each key together; the survivors are spilled again, sorted by
+/// sequence number, and merged back into their original order.
+pub(super) struct SpillStore {
+    runs: Runs,
+}
+
+struct Record {
+    key: Vec<u8>,
+    seq: u64,
+    row: Vec<u8>,
+}
+
+/// Sorted run files sharing a name prefix, plus the records not yet
+/// written to one.
+struct Runs {
+    dir: PathBuf,
+    name: String,
+    memory: usize,
+    buffered: Vec<Record>,
+    buffered_bytes: usize,
+    paths: Vec<PathBuf>,
+    next_run: usize,
+}
+
+impl SpillStore {
+    pub(super) fn create(dir: &Path, memory: usize) -> io::Result<Self> {
+        if !dir.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("dedup spill directory '{}' does not exist", dir.display()),
+            ));
+        }
+        let id = SPILL_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let name = format!("sas7bdat-dedup-{}-{id}", process::id());
+        Ok(Self {
+            runs: Runs::new(dir, name, memory),
+        })
+    }
+
+    pub(super) fn push(&mut self, seq: u64, key: &[u8], row: &[CellValue<'_>]) -> io::Result<()> {
+        let mut encoded = Vec::new();
+        encode_row(row, &mut encoded)?;
+        self.runs.push(Record {
+            key: key.to_vec(),
+            seq,
+            row: encoded,
+        })
+    }
+
+    /// Deduplicates the spilled rows and forwards the survivors to `emit` in
+    /// their original order. Returns the number of rows dropped.
+    pub(super) fn drain<F>(mut self, keep: DedupKeep, mut emit: F) -> Result<u64>
+    where
+        F: FnMut(&[CellValue<'_>]) -> Result<()>,
+    {
+        let mut survivors = Runs::new(
+            &self.runs.dir,
+            format!("{}-kept", self.runs.name),
+            self.runs.memory,
+        );
+        let mut removed = 0;
+        let mut group: Option<Record> = None;
+        self.runs.merge(|record| {
+            if group
+                .as_ref()
+                .is_some_and(|current| current.key == record.key)
+            {
+                removed += 1;
+                if keep == DedupKeep::Last {
+                    group = Some(record);
+                }
+            } else if let Some(kept) = group.replace(record) {
+                survivors.push(kept.without_key())?;
+            }
+            Ok(())
+        })?;
+        if let Some(kept) = group {
+            survivors.push(kept.without_key())?;
+        }
+
+        let mut row = Vec::new();
+        survivors.merge(|record| {
+            decode_row(&record.row, &mut row)?;
+            emit(&row)
+        })?;
+        Ok(removed)
+    }
+}
+
+impl Record {
+    /// Drops the key, so records sort by sequence number alone.
+    fn without_key(self) -> Self {
+        Self {
+            key: Vec::new(),
+            ..self
+        }
+    }
+}
+
+impl Runs {
+    fn new(dir: &Path, name: String, memory: usize) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            name,
+            memory,
+            buffered: Vec::new(),
+            buffered_bytes: 0,
+            paths: Vec::new(),
+            next_run: 0,
+        }
+    }
+
+    fn push(&mut self, record: Record) -> io::Result<()> {
+        self.buffered_bytes += record.key.len() + record.row.len() + RECORD_OVERHEAD;
+        self.buffered.push(record);
+        if self.buffered_bytes >= self.memory {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered records out as a sorted run.
+    fn spill(&mut self) -> io::Result<()> {
+        if self.buffered.is_empty() {
+            return Ok(());
+        }
+        self.buffered
+            .sort_unstable_by(|left, right| (&left.key, left.seq).cmp(&(&right.key, right.seq)));
+        let mut writer = self.create_run()?;
+        for record in self.buffered.drain(..) {
+            write_record(&mut writer, &record)?;
+        }
+        finish_run(writer)?;
+        self.buffered_bytes = 0;
+        Ok(())
+    }
+
+    fn create_run(&mut self) -> io::Result<RunWriter> {
+        let path = self
+            .dir
+            .join(format!("{}-{}.spill", self.name, self.next_run));
+        self.next_run += 1;
+        let file = File::create_new(&path)?;
+        self.paths.push(path);
+        run_writer(file)
+    }
+
+    /// Passes every record to `visit`, ordered by key and then sequence
+    /// number, removing the run files as they are consumed.
+    fn merge<F>(&mut self, visit: F) -> Result<()>
+    where
+        F: FnMut(Record) -> Result<()>,
+    {
+        self.spill()?;
+        while self.paths.len() > MERGE_FAN_IN {
+            let batch: Vec<PathBuf> = self.paths.drain(..MERGE_FAN_IN).collect();
+            let mut writer = self.create_run()?;
+            let merged = merge_files(&batch, |record| Ok(write_record(&mut writer, &record)?));
+            remove_files(&batch);
+            merged?;
+            finish_run(writer)?;
+        }
+        let paths = mem::take(&mut self.paths);
+        let merged = merge_files(&paths, visit);
+        remove_files(&paths);
+        merged
+    }
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        remove_files(&self.paths);
+    }
+}
+
+fn merge_files<F>(paths: &[PathBuf], mut visit: F) -> Result<()>
+where
+    F: FnMut(Record) -> Result<()>,
+{
+    let mut readers = paths
+        .iter()
+        .map(|path| run_reader(path))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut pending = Vec::with_capacity(readers.len());
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (index, reader) in readers.iter_mut().enumerate() {
+        let record = read_record(reader)?;
+        if let Some(record) = &record {
+            heap.push(Reverse((record.key.clone(), record.seq, index)));
+        }
+        pending.push(record);
+    }
+    while let Some(Reverse((_, _, index))) = heap.pop() {
+        let next = read_record(&mut readers[index])?;
+        if let Some(record) = &next {
+            heap.push(Reverse((record.key.clone(), record.seq, index)));
+        }
+        if let Some(record) = mem::replace(&mut pending[index], next) {
+            visit(record)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_files(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(feature = "zstd")]
+type RunWriter = zstd::Encoder<'static, BufWriter<File>>;
+#[cfg(not(feature = "zstd"))]
+type RunWriter = BufWriter<File>;
+
+#[cfg(feature = "zstd")]
+fn run_writer(file: File) -> io::Result<RunWriter> {
+    zstd::Encoder::new(BufWriter::new(file), SPILL_ZSTD_LEVEL)
+}
+
+#[cfg(not(feature = "zstd"))]
+#[allow(clippy::unnecessary_wraps)]
+fn run_writer(file: File) -> io::Result<RunWriter> {
+    Ok(BufWriter::new(file))
+}
+
+#[cfg(feature = "zstd")]
+fn finish_run(writer: RunWriter) -> io::Result<()> {
+    writer.finish()?.flush()
+}
+
+#[cfg(not(feature = "zstd"))]
+fn finish_run(mut writer: RunWriter) -> io::Result<()> {
+    writer.flush()
+}
+
+#[cfg(feature = "zstd")]
+fn run_reader(path: &Path) -> io::Result<impl Read> {
+    zstd::Decoder::new(File::open(path)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn run_reader(path: &Path) -> io::Result<impl Read> {
+    Ok(io::BufReader::new(File::open(path)?))
+}
+
+fn write_record(writer: &mut impl Write, record: &Record) -> io::Result<()> {
+    writer.write_u64::<LittleEndian>(record.seq)?;
+    write_blob(writer, &record.key)?;
+    write_blob(writer, &record.row)
+}
+
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Record>> {
+    let seq = match reader.read_u64::<LittleEndian>() {
+        Ok(seq) => seq,
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let key = read_blob(reader)?;
+    let row = read_blob(reader)?;
+    Ok(Some(Record { key, seq, row }))
+}
+
+fn encode_row(row: &[CellValue<'_>], out: &mut Vec<u8>) -> io::Result<()> {
+    out.write_u32::<LittleEndian>(blob_len(row.len())?)?;
+    for value in row {
+        encode_cell(value, out);
+    }
+    Ok(())
+}
+
+fn decode_row(mut bytes: &[u8], row: &mut Vec<CellValue<'static>>) -> io::Result<()> {
+    let len = bytes.read_u32::<LittleEndian>()?;
+    row.clear();
+    for _ in 0..len {
+        row.push(decode_cell(&mut bytes)?);
+    }
+    Ok(())
+}
+
+fn blob_len(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "dedup spill record exceeds 4 GiB",
+        )
+    })
+}
+
+fn write_blob(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    writer.write_u32::<LittleEndian>(blob_len(bytes.len())?)?;
+    writer.write_all(bytes)
+}
+
+fn read_blob(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = reader.read_u32::<LittleEndian>()? as usize;
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
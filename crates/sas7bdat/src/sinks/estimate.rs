@@ -0,0 +1,365 @@
+use crate::{
+    cell::CellValue,
+    dataset::{DatasetMetadata, Variable, VariableKind},
+    parser::NumericKind,
+};
+use std::collections::HashSet;
+
+// Typical rendered widths for CSV output when no sample is available.
+const CSV_NUMERIC_BYTES: usize = 8;
+const CSV_DATE_BYTES: usize = 10;
+const CSV_DATETIME_BYTES: usize = 19;
+const CSV_TIME_BYTES: usize = 8;
+
+// Parquet physical sizes per value before encoding overhead.
+const PARQUET_DOUBLE_BYTES: f64 = 8.0;
+const PARQUET_DATE_BYTES: f64 = 4.0;
+const PARQUET_BYTE_ARRAY_PREFIX: f64 = 4.0;
+// One definition level bit per value for the optional columns the sink writes.
+const PARQUET_DEF_LEVEL_BYTES: f64 = 0.125;
+// Magic, footer framing, and per-column schema/statistics in the footer.
+const PARQUET_FILE_OVERHEAD: u64 = 1024;
+const PARQUET_COLUMN_OVERHEAD: u64 = 256;
+
+// Stop tracking distinct values once a sampled column exceeds this many.
+const DISTINCT_TRACKING_LIMIT: usize = 4096;
+
+/// Output formats supported by [`estimate_output_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkKind {
+    Csv,
+    Parquet,
+}
+
+/// Predicted size of a converted dataset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputSizeEstimate {
+    /// Estimated total output size in bytes.
+    pub total_bytes: u64,
+    /// Estimated encoded bytes per data row.
+    pub bytes_per_row: f64,
+    /// Fixed bytes independent of row count (CSV header, Parquet footer).
+    pub overhead_bytes: u64,
+    /// Number of rows the estimate was calibrated on; zero for width-only estimates.
+    pub sampled_rows: u64,
+}
+
+/// Per-column observations gathered from a prefix of the dataset.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnProfile {
+    /// Number of sampled values that were missing.
+    pub missing: u64,
+    /// Sum of rendered byte lengths of the non-missing sampled values.
+    pub rendered_bytes: u64,
+    distinct: DistinctValues,
+}
+
+/// Distinct sampled values of a text column.
+#[derive(Debug, Clone, Default)]
+enum DistinctValues {
+    /// No text value has been sampled.
+    #[default]
+    Untracked,
+    Exact(HashSet<Vec<u8>>),
+    /// More than [`DISTINCT_TRACKING_LIMIT`] values were seen; stays set for
+    /// the rest of the sample.
+    Overflowed,
+}
+
+impl ColumnProfile {
+    /// Returns the number of distinct sampled string values, or `None` when the
+    /// column is numeric or exceeded the tracking limit.
+    #[must_use]
+    pub fn distinct_values(&self) -> Option<usize> {
+        match &self.distinct {
+            DistinctValues::Exact(set) => Some(set.len()),
+            DistinctValues::Untracked | DistinctValues::Overflowed => None,
+        }
+    }
+}
+
+/// Sampled statistics used to calibrate [`estimate_output_size_with_sample`].
+#[derive(Debug, Clone, Default)]
+pub struct SampleProfile {
+    pub rows: u64,
+    pub columns: Vec<ColumnProfile>,
+}
+
+impl SampleProfile {
+    #[must_use]
+    pub fn new(column_count: usize) -> Self {
+        Self {
+            rows: 0,
+            columns: vec![ColumnProfile::default(); column_count],
+        }
+    }
+
+    /// Records a decoded row in the profile.
+    pub fn observe(&mut self, row: &[CellValue<'_>]) {
+        self.rows = self.rows.saturating_add(1);
+        for (profile, value) in self.columns.iter_mut().zip(row) {
+            let len = match value {
+                CellValue::Missing(_) => {
+                    profile.missing = profile.missing.saturating_add(1);
+                    continue;
+                }
+                CellValue::Str(text) | CellValue::NumericString(text) => {
+                    track_distinct(profile, text.as_bytes());
+                    text.len()
+                }
+                CellValue::Bytes(bytes) => {
+                    track_distinct(profile, bytes);
+                    bytes.len()
+                }
+                CellValue::Float(v) => ryu::Buffer::new().format(*v).len(),
+                CellValue::Int32(v) => itoa::Buffer::new().format(*v).len(),
+                CellValue::Int64(v) => itoa::Buffer::new().format(*v).len(),
+                CellValue::Date(_) => CSV_DATE_BYTES,
+                CellValue::DateTime(_) => CSV_DATETIME_BYTES,
+                CellValue::Time(_) => CSV_TIME_BYTES,
+            };
+            profile.rendered_bytes = profile.rendered_bytes.saturating_add(len as u64);
+        }
+    }
+}
+
+fn track_distinct(profile: &mut ColumnProfile, value: &[u8]) {
+    if matches!(profile.distinct, DistinctValues::Untracked) {
+        profile.distinct = DistinctValues::Exact(HashSet::new());
+    }
+    let DistinctValues::Exact(set) = &mut profile.distinct else {
+        return;
+    };
+    if set.len() < DISTINCT_TRACKING_LIMIT {
+        set.insert(value.to_vec());
+    } else if !set.contains(value) {
+        profile.distinct = DistinctValues::Overflowed;
+    }
+}
+
+/// Predicts the output size of converting `metadata` to `kind` from column
+/// widths and formats alone.
+///
+/// Character columns are assumed to fill their declared width, so the result
+/// is an upper bound for text-heavy datasets. Use
+/// [`estimate_output_size_with_sample`] with a [`SampleProfile`] for tighter
+/// numbers.
+#[must_use]
+pub fn estimate_output_size(metadata: &DatasetMetadata, kind: SinkKind) -> OutputSizeEstimate {
+    estimate(metadata, kind, None)
+}
+
+/// Predicts the output size of converting `metadata` to `kind`, calibrated
+/// with value lengths, missing rates, and string cardinality observed in
+/// `sample`.
+#[must_use]
+pub fn estimate_output_size_with_sample(
+    metadata: &DatasetMetadata,
+    kind: SinkKind,
+    sample: &SampleProfile,
+) -> OutputSizeEstimate {
+    estimate(
+        metadata,
+        kind,
+        Some(sample).filter(|sample| sample.rows > 0),
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn estimate(
+    metadata: &DatasetMetadata,
+    kind: SinkKind,
+    sample: Option<&SampleProfile>,
+) -> OutputSizeEstimate {
+    let total_rows = metadata.row_count;
+    let bytes_per_row: f64 = metadata
+        .variables
+        .iter()
+        .enumerate()
+        .map(|(idx, variable)| {
+            let profile = sample.and_then(|sample| sample.columns.get(idx));
+            let sampled_rows = sample.map_or(0, |sample| sample.rows);
+            match kind {
+                SinkKind::Csv => csv_column_bytes(variable, profile, sampled_rows),
+                SinkKind::Parquet => {
+                    parquet_column_bytes(variable, profile, sampled_rows, total_rows)
+                }
+            }
+        })
+        .sum();
+
+    let (bytes_per_row, overhead_bytes) = match kind {
+        SinkKind::Csv => {
+            let separators = metadata.variables.len() as f64;
+            let header: usize = metadata
+                .variables
+                .iter()
+                .map(|variable| variable.name.trim_end().len() + 1)
+                .sum();
+            (bytes_per_row + separators, header as u64)
+        }
+        SinkKind::Parquet => (
+            bytes_per_row,
+            PARQUET_FILE_OVERHEAD + PARQUET_COLUMN_OVERHEAD * metadata.variables.len() as u64,
+        ),
+    };
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let data_bytes = (bytes_per_row * total_rows as f64).ceil() as u64;
+    OutputSizeEstimate {
+        total_bytes: data_bytes.saturating_add(overhead_bytes),
+        bytes_per_row,
+        overhead_bytes,
+        sampled_rows: sample.map_or(0, |sample| sample.rows),
+    }
+}
+
+fn numeric_kind(variable: &Variable) -> NumericKind {
+    variable
+        .format
+        .as_ref()
+        .and_then(|format| format.category().numeric_kind())
+        .unwrap_or(NumericKind::Double)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn present_fraction(profile: Option<&ColumnProfile>, sampled_rows: u64) -> f64 {
+    match profile {
+        Some(profile) if sampled_rows > 0 => 1.0 - profile.missing as f64 / sampled_rows as f64,
+        _ => 1.0,
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn mean_rendered_bytes(profile: Option<&ColumnProfile>, sampled_rows: u64) -> Option<f64> {
+    let profile = profile?;
+    let present = sampled_rows.saturating_sub(profile.missing);
+    (present > 0).then(|| profile.rendered_bytes as f64 / present as f64)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn csv_column_bytes(variable: &Variable, profile: Option<&ColumnProfile>, sampled: u64) -> f64 {
+    let width = mean_rendered_bytes(profile, sampled).unwrap_or_else(|| {
+        let bytes = match variable.kind {
+            VariableKind::Character => variable.storage_width,
+            VariableKind::Numeric => match numeric_kind(variable) {
+                NumericKind::Double | NumericKind::Float | NumericKind::Text => CSV_NUMERIC_BYTES,
+                NumericKind::Date => CSV_DATE_BYTES,
+                NumericKind::DateTime => CSV_DATETIME_BYTES,
+                NumericKind::Time => CSV_TIME_BYTES,
+            },
+        };
+        bytes as f64
+    });
+    width * present_fraction(profile, sampled)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn parquet_column_bytes(
+    variable: &Variable,
+    profile: Option<&ColumnProfile>,
+    sampled: u64,
+    total_rows: u64,
+) -> f64 {
+    let present = present_fraction(profile, sampled);
+    let value_bytes = match variable.kind {
+        VariableKind::Numeric => match numeric_kind(variable) {
+            NumericKind::Date => PARQUET_DATE_BYTES * present,
+            NumericKind::Double
+            | NumericKind::DateTime
+            | NumericKind::Time
+            | NumericKind::Float
+            | NumericKind::Text => PARQUET_DOUBLE_BYTES * present,
+        },
+        VariableKind::Character => {
+            let mean =
+                mean_rendered_bytes(profile, sampled).unwrap_or(variable.storage_width as f64);
+            let plain = (mean + PARQUET_BYTE_ARRAY_PREFIX) * present;
+            match profile.and_then(ColumnProfile::distinct_values) {
+                Some(distinct) if total_rows > 0 => {
+                    // Dictionary pages store each value once; data pages hold
+                    // bit-packed indices into the dictionary.
+                    let distinct = distinct.max(1) as f64;
+                    let index_bytes = distinct.log2().ceil().max(1.0) / 8.0;
+                    let dictionary = distinct * (mean + PARQUET_BYTE_ARRAY_PREFIX);
+                    let dictionary_encoded = index_bytes * present + dictionary / total_rows as f64;
+                    dictionary_encoded.min(plain)
+                }
+                _ => plain,
+            }
+        }
+    };
+    value_bytes + PARQUET_DEF_LEVEL_BYTES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cell::MissingValue,
+        dataset::{Format, Variable},
+    };
+    use std::borrow::Cow;
+
+    fn sample_metadata(rows: u64) -> DatasetMetadata {
+        let mut metadata = DatasetMetadata::new(3);
+        metadata.row_count = rows;
+        metadata
+            .variables
+            .push(Variable::new(0, "AMOUNT".into(), VariableKind::Numeric, 8));
+        let mut date = Variable::new(1, "VISIT".into(), VariableKind::Numeric, 8);
+        date.format = Some(Format {
+            name: "DATE9".into(),
+            width: Some(9),
+            decimals: Some(0),
+        });
+        metadata.variables.push(date);
+        metadata
+            .variables
+            .push(Variable::new(2, "CITY".into(), VariableKind::Character, 40));
+        metadata
+    }
+
+    #[test]
+    fn width_only_csv_estimate_uses_declared_widths() {
+        let metadata = sample_metadata(100);
+        let estimate = estimate_output_size(&metadata, SinkKind::Csv);
+        // 8 (numeric) + 10 (date) + 40 (text) + 3 separators.
+        assert!((estimate.bytes_per_row - 61.0).abs() < f64::EPSILON);
+        assert_eq!(estimate.overhead_bytes, "AMOUNT,VISIT,CITY\n".len() as u64);
+        assert_eq!(estimate.total_bytes, 6100 + estimate.overhead_bytes);
+        assert_eq!(estimate.sampled_rows, 0);
+    }
+
+    #[test]
+    fn sampled_estimate_tightens_text_and_missing_columns() {
+        let metadata = sample_metadata(1_000);
+        let mut sample = SampleProfile::new(3);
+        for _ in 0..4 {
+            sample.observe(&[
+                CellValue::Missing(MissingValue::System),
+                CellValue::Float(1.5),
+                CellValue::Str(Cow::Borrowed("Oslo")),
+            ]);
+        }
+
+        let width_only = estimate_output_size(&metadata, SinkKind::Parquet);
+        let sampled = estimate_output_size_with_sample(&metadata, SinkKind::Parquet, &sample);
+        assert_eq!(sampled.sampled_rows, 4);
+        assert_eq!(sample.columns[2].distinct_values(), Some(1));
+        assert!(sampled.total_bytes < width_only.total_bytes);
+    }
+
+    #[test]
+    fn distinct_tracking_stays_off_after_overflow() {
+        let mut sample = SampleProfile::new(1);
+        for value in 0..=DISTINCT_TRACKING_LIMIT {
+            sample.observe(&[CellValue::Str(Cow::Owned(value.to_string()))]);
+        }
+        assert_eq!(sample.columns[0].distinct_values(), None);
+
+        // Later values must not restart the count from zero.
+        sample.observe(&[CellValue::Str(Cow::Borrowed("fresh"))]);
+        assert_eq!(sample.columns[0].distinct_values(), None);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    parser::{ColumnarBatch, StreamingRow, TimestampRounding},
+    sinks::{ArrowSink, ColumnarSink, RowSink, SinkContext, validate_sink_begin},
+    telemetry::{self, Stopwatch},
+};
+use arrow_ipc::writer::FileWriter;
+use std::{borrow::Cow, io::Write};
+
+/// Writes decoded rows as an Arrow IPC file, also known as Feather v2
+/// (`.arrow` / `.feather`).
+///
+/// Columns get the same Arrow types as [`ArrowSink`]: dates become `Date32`,
+/// datetimes `Timestamp(Microsecond, "UTC")` and times
+/// `Time64(Microsecond)`, so Python and R readers see native temporal
+/// columns. Each completed record batch is written straight to the output.
+pub struct IpcSink<W: Write> {
+    output: Option<W>,
+    writer: Option<FileWriter<W>>,
+    batches: ArrowSink,
+}
+
+impl<W: Write> IpcSink<W> {
+    #[must_use]
+    pub fn new(writer: W) -> Self {
+        Self {
+            output: Some(writer),
+            writer: None,
+            batches: ArrowSink::new(),
+        }
+    }
+
+    /// Sets the number of rows per record batch; see
+    /// [`ArrowSink::with_batch_rows`].
+    #[must_use]
+    pub fn with_batch_rows(mut self, rows: usize) -> Self {
+        self.batches = self.batches.with_batch_rows(rows);
+        self
+    }
+
+    /// Selects how sub-microsecond precision is dropped from timestamps and
+    /// times.
+    #[must_use]
+    pub fn with_timestamp_rounding(mut self, rounding: TimestampRounding) -> Self {
+        self.batches = self.batches.with_timestamp_rounding(rounding);
+        self
+    }
+
+    /// Returns the underlying writer once the sink has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sink has not finished or the output was
+    /// already taken.
+    pub fn into_inner(mut self) -> Result<W> {
+        if self.writer.is_some() {
+            return Err(Error::Unsupported {
+                feature: Cow::from("attempted to take IPC writer before sink was finished"),
+            });
+        }
+        self.output.take().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("IPC sink output already consumed"),
+        })
+    }
+
+    /// Writes the record batches completed since the last call.
+    fn drain(&mut self) -> Result<()> {
+        let writer = self.writer.as_mut().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("IPC sink written before begin"),
+        })?;
+        for batch in self.batches.take_batches() {
+            writer.write(&batch)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for IpcSink<W> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        validate_sink_begin(&context, self.writer.is_some(), "IPC")?;
+        let output = self.output.take().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("IPC sink output already taken"),
+        })?;
+        self.batches.begin(context)?;
+        let schema = self.batches.schema().ok_or_else(|| Error::Internal {
+            details: Cow::from("Arrow schema missing after begin"),
+        })?;
+        self.writer = Some(FileWriter::try_new(output, &schema)?);
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        self.batches.write_row(row)?;
+        self.drain()
+    }
+
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        self.batches.write_streaming_row(row)?;
+        self.drain()
+    }
+
+    fn streams_borrowed_rows(&self) -> bool {
+        true
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.writer.is_none() {
+            return Ok(());
+        }
+        self.batches.finish()?;
+        self.drain()?;
+        if let Some(mut writer) = self.writer.take() {
+            let started = Stopwatch::start();
+            writer.finish()?;
+            let out = writer.into_inner()?;
+            telemetry::sink_flushed("ipc", started);
+            self.output = Some(out);
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> ColumnarSink for IpcSink<W> {
+    fn write_columnar_batch(
+        &mut self,
+        batch: &ColumnarBatch<'_>,
+        selection: &[usize],
+    ) -> Result<()> {
+        self.batches.write_columnar_batch(batch, selection)?;
+        self.drain()
+    }
+}
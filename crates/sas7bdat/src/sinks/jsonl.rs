@@ -0,0 +1,197 @@
+use crate::{
+    cell::CellValue,
+    error::{Error, Result},
+    json::DateStyle,
+    parser::StreamingRow,
+    sinks::{
+        RowSink, SinkContext,
+        compress::{CompressedWriter, OutputCompression},
+        validate_sink_begin,
+    },
+    telemetry::{self, Stopwatch},
+};
+use itoa::Buffer as ItoaBuffer;
+use ryu::Buffer as RyuBuffer;
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    io::{BufWriter, IntoInnerError, Write},
+};
+
+/// Writes decoded rows as JSON Lines: one object per row, keyed by the
+/// trimmed column names.
+///
+/// Values are converted as by [`CellValue::to_json`]: missing values become
+/// `null`, and dates, datetimes and times ISO 8601 strings unless another
+/// [`DateStyle`] is chosen with [`with_date_style`](Self::with_date_style).
+pub struct JsonlSink<W: Write> {
+    output: Option<W>,
+    writer: Option<BufWriter<CompressedWriter<W>>>,
+    compression: OutputCompression,
+    date_style: DateStyle,
+    /// `"name":` for every column, already escaped.
+    keys: Vec<Vec<u8>>,
+    line: Vec<u8>,
+}
+
+impl<W: Write> JsonlSink<W> {
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            output: Some(writer),
+            writer: None,
+            compression: OutputCompression::None,
+            date_style: DateStyle::Iso,
+            keys: Vec::new(),
+            line: Vec::new(),
+        }
+    }
+
+    /// Compresses the output, for example into a `.jsonl.zst` file.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: OutputCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Writes dates, datetimes and times in `style` instead of ISO 8601.
+    #[must_use]
+    pub const fn with_date_style(mut self, style: DateStyle) -> Self {
+        self.date_style = style;
+        self
+    }
+
+    /// Returns the underlying writer once the sink has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sink has not finished or the output was
+    /// already taken.
+    pub fn into_inner(mut self) -> Result<W> {
+        if self.writer.is_some() {
+            return Err(Error::Unsupported {
+                feature: Cow::from("attempted to take JSONL writer before sink was finished"),
+            });
+        }
+        self.output.take().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("JSONL sink output already consumed"),
+        })
+    }
+
+    fn write_line<'a, I>(&mut self, len: usize, values: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<CellValue<'a>>>,
+    {
+        if len != self.keys.len() {
+            return Err(Error::InvalidArgument {
+                details: Cow::Owned(format!(
+                    "row length {len} does not match expected {}",
+                    self.keys.len()
+                )),
+            });
+        }
+        let writer = self.writer.as_mut().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("JSONL sink written before begin"),
+        })?;
+        let mut ryu = RyuBuffer::new();
+        let mut itoa = ItoaBuffer::new();
+        self.line.clear();
+        self.line.push(b'{');
+        for (idx, value) in values.into_iter().enumerate() {
+            if idx > 0 {
+                self.line.push(b',');
+            }
+            self.line.extend_from_slice(&self.keys[idx]);
+            encode_value(
+                &value?,
+                self.date_style,
+                &mut self.line,
+                &mut ryu,
+                &mut itoa,
+            )?;
+        }
+        self.line.extend_from_slice(b"}\n");
+        writer.write_all(&self.line)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for JsonlSink<W> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        validate_sink_begin(&context, self.writer.is_some(), "JSONL")?;
+        let output = self.output.take().ok_or_else(|| Error::InvalidArgument {
+            details: Cow::from("JSONL sink output already taken"),
+        })?;
+        self.keys = context
+            .metadata
+            .variables
+            .iter()
+            .map(|variable| {
+                let mut key = Vec::with_capacity(variable.name.len() + 3);
+                write_json(variable.name.trim_end(), &mut key)?;
+                key.push(b':');
+                Ok(key)
+            })
+            .collect::<Result<_>>()?;
+        self.writer = Some(BufWriter::new(CompressedWriter::new(
+            output,
+            self.compression,
+        )?));
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        self.write_line(row.len(), row.iter().map(|value| Ok(value.clone())))
+    }
+
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        self.write_line(
+            row.len(),
+            row.iter()
+                .map(|cell| cell.and_then(|cell| cell.decode_value())),
+        )
+    }
+
+    fn streams_borrowed_rows(&self) -> bool {
+        true
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            let started = Stopwatch::start();
+            let out = writer
+                .into_inner()
+                .map_err(IntoInnerError::into_error)?
+                .finish()?;
+            telemetry::sink_flushed("jsonl", started);
+            self.output = Some(out);
+        }
+        self.keys.clear();
+        self.line.clear();
+        Ok(())
+    }
+}
+
+fn encode_value(
+    value: &CellValue<'_>,
+    style: DateStyle,
+    out: &mut Vec<u8>,
+    ryu: &mut RyuBuffer,
+    itoa: &mut ItoaBuffer,
+) -> Result<()> {
+    match value {
+        CellValue::Float(v) if v.is_finite() => out.extend_from_slice(ryu.format(*v).as_bytes()),
+        CellValue::Missing(_) | CellValue::Float(_) => out.extend_from_slice(b"null"),
+        CellValue::Int32(v) => out.extend_from_slice(itoa.format(*v).as_bytes()),
+        CellValue::Int64(v) => out.extend_from_slice(itoa.format(*v).as_bytes()),
+        CellValue::NumericString(s) | CellValue::Str(s) => write_json(&**s, out)?,
+        _ => write_json(&value.to_json(style)?, out)?,
+    }
+    Ok(())
+}
+
+fn write_json<T: Serialize + ?Sized>(value: &T, out: &mut Vec<u8>) -> Result<()> {
+    serde_json::to_writer(out, value).map_err(|err| Error::Sink {
+        details: Cow::Owned(format!("JSON encoding failed: {err}")),
+    })
+}
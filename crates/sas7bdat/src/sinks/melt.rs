@@ -0,0 +1,257 @@
+use crate::{
+    cell::CellValue,
+    dataset::{DatasetMetadata, Variable, VariableKind},
+    error::{Error, Result},
+    parser::{ColumnInfo, ColumnKind},
+    sinks::{RowSink, SinkContext},
+};
+use std::borrow::Cow;
+
+const DEFAULT_VARIABLE_NAME: &str = "variable";
+const DEFAULT_VALUE_NAME: &str = "value";
+
+/// Unpivots measure columns into long format before forwarding rows to
+/// another sink.
+///
+/// Every input row becomes one output row per measure column, laid out as the
+/// id columns followed by a `variable` column holding the measure name and a
+/// `value` column holding its value. The wrapped sink sees the long layout in
+/// [`RowSink::begin`], so it can be any sink that accepts rows.
+pub struct MeltSink<S: RowSink> {
+    inner: S,
+    id_vars: Vec<String>,
+    value_vars: Vec<String>,
+    variable_name: String,
+    value_name: String,
+    id_indices: Vec<usize>,
+    measures: Vec<(usize, String)>,
+}
+
+impl<S: RowSink> MeltSink<S> {
+    /// Wraps `inner`, keeping `id_vars` on every row and unpivoting
+    /// `value_vars`. An empty `value_vars` melts every column that is not an
+    /// id column. Names are matched case-insensitively.
+    pub fn new<I, J, T, U>(inner: S, id_vars: I, value_vars: J) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        J: IntoIterator<Item = U>,
+        T: Into<String>,
+        U: Into<String>,
+    {
+        Self {
+            inner,
+            id_vars: id_vars.into_iter().map(Into::into).collect(),
+            value_vars: value_vars.into_iter().map(Into::into).collect(),
+            variable_name: DEFAULT_VARIABLE_NAME.to_owned(),
+            value_name: DEFAULT_VALUE_NAME.to_owned(),
+            id_indices: Vec::new(),
+            measures: Vec::new(),
+        }
+    }
+
+    /// Overrides the names of the generated `variable` and `value` columns.
+    #[must_use]
+    pub fn with_output_names(
+        mut self,
+        variable_name: impl Into<String>,
+        value_name: impl Into<String>,
+    ) -> Self {
+        self.variable_name = variable_name.into();
+        self.value_name = value_name.into();
+        self
+    }
+
+    #[must_use]
+    pub const fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn resolve(variables: &[Variable], name: &str, role: &str) -> Result<usize> {
+        variables
+            .iter()
+            .position(|v| v.name.trim_end().eq_ignore_ascii_case(name.trim_end()))
+            .ok_or_else(|| Error::InvalidArgument {
+                details: format!("melt {role} column '{name}' not found in dataset").into(),
+            })
+    }
+
+    fn resolve_columns(&mut self, variables: &[Variable]) -> Result<()> {
+        self.id_indices = self
+            .id_vars
+            .iter()
+            .map(|name| Self::resolve(variables, name, "id"))
+            .collect::<Result<_>>()?;
+        let value_indices: Vec<usize> = if self.value_vars.is_empty() {
+            (0..variables.len())
+                .filter(|index| !self.id_indices.contains(index))
+                .collect()
+        } else {
+            self.value_vars
+                .iter()
+                .map(|name| Self::resolve(variables, name, "value"))
+                .collect::<Result<_>>()?
+        };
+        if value_indices.is_empty() {
+            return Err(Error::InvalidArgument {
+                details: Cow::from("melt requires at least one value column"),
+            });
+        }
+        if let Some(index) = value_indices
+            .iter()
+            .find(|index| self.id_indices.contains(index))
+        {
+            return Err(Error::InvalidArgument {
+                details: format!(
+                    "column '{}' cannot be both an id and a value column",
+                    variables[*index].name.trim_end()
+                )
+                .into(),
+            });
+        }
+        for name in [&self.variable_name, &self.value_name] {
+            if self
+                .id_indices
+                .iter()
+                .any(|&index| variables[index].name.trim_end().eq_ignore_ascii_case(name))
+            {
+                return Err(Error::InvalidArgument {
+                    details: format!("melt output column '{name}' collides with an id column")
+                        .into(),
+                });
+            }
+        }
+        if self.variable_name.eq_ignore_ascii_case(&self.value_name) {
+            return Err(Error::InvalidArgument {
+                details: Cow::from("melt variable and value columns need distinct names"),
+            });
+        }
+        self.measures = value_indices
+            .into_iter()
+            .map(|index| (index, variables[index].name.trim_end().to_owned()))
+            .collect();
+        Ok(())
+    }
+
+    /// Builds the metadata and column layout of the long output.
+    fn long_layout(&self, context: &SinkContext<'_>) -> Result<(DatasetMetadata, Vec<ColumnInfo>)> {
+        let variables = &context.metadata.variables;
+        let (first_measure, _) = self.measures[0];
+        let value_kind = context.columns[first_measure].kind;
+        let mut value_width = 0;
+        for &(index, ref name) in &self.measures {
+            if context.columns[index].kind != value_kind {
+                return Err(Error::InvalidArgument {
+                    details: format!(
+                        "melt value column '{name}' has a different type than '{}'",
+                        self.measures[0].1
+                    )
+                    .into(),
+                });
+            }
+            value_width = value_width.max(context.columns[index].offsets.width);
+        }
+        let name_width = self
+            .measures
+            .iter()
+            .map(|(_, name)| name.len())
+            .max()
+            .unwrap_or(1);
+
+        let mut metadata = context.metadata.clone();
+        metadata.variables.clear();
+        metadata.column_list.clear();
+        let mut columns = Vec::with_capacity(self.id_indices.len() + 2);
+        for &index in &self.id_indices {
+            metadata.variables.push(variables[index].clone());
+            columns.push(context.columns[index].clone());
+        }
+
+        let variable = Variable::new(
+            0,
+            self.variable_name.clone(),
+            VariableKind::Character,
+            name_width,
+        );
+        let mut variable_column = context.columns[first_measure].clone();
+        variable_column.kind = ColumnKind::Character;
+        variable_column.offsets.width = u32::try_from(name_width).map_err(|_| Error::Limit {
+            details: Cow::from("melt measure name exceeds supported width"),
+        })?;
+        metadata.variables.push(variable);
+        columns.push(variable_column);
+
+        let mut value = variables[first_measure].clone();
+        value.name.clone_from(&self.value_name);
+        value.label = None;
+        value.storage_width = usize::try_from(value_width).unwrap_or(value.storage_width);
+        let mut value_column = context.columns[first_measure].clone();
+        value_column.offsets.width = value_width;
+        metadata.variables.push(value);
+        columns.push(value_column);
+
+        for (variable, (position, column)) in metadata
+            .variables
+            .iter_mut()
+            .zip(columns.iter_mut().enumerate())
+        {
+            let position = u32::try_from(position).map_err(|_| Error::Limit {
+                details: Cow::from("melt output has too many columns"),
+            })?;
+            variable.index = position;
+            column.index = position;
+        }
+        metadata.column_count = u32::try_from(columns.len()).map_err(|_| Error::Limit {
+            details: Cow::from("melt output has too many columns"),
+        })?;
+        metadata.row_count = metadata
+            .row_count
+            .saturating_mul(self.measures.len() as u64);
+        Ok((metadata, columns))
+    }
+}
+
+impl<S: RowSink> RowSink for MeltSink<S> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        self.resolve_columns(&context.metadata.variables)?;
+        let (metadata, columns) = self.long_layout(&context)?;
+        self.inner.begin(SinkContext {
+            metadata: &metadata,
+            columns: &columns,
+            source_path: context.source_path,
+            logger: context.logger,
+        })
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        let mut long_row = Vec::with_capacity(self.id_indices.len() + 2);
+        for (index, name) in &self.measures {
+            long_row.clear();
+            for &id in &self.id_indices {
+                long_row.push(cell(row, id)?.clone());
+            }
+            long_row.push(CellValue::Str(Cow::Borrowed(name.as_str())));
+            long_row.push(cell(row, *index)?.clone());
+            self.inner.write_row(&long_row)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort()
+    }
+}
+
+fn cell<'r, 'a>(row: &'r [CellValue<'a>], index: usize) -> Result<&'r CellValue<'a>> {
+    row.get(index).ok_or_else(|| Error::InvalidArgument {
+        details: format!("row has no column at index {index}").into(),
+    })
+}
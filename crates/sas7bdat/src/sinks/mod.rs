@@ -1,28 +1,59 @@
+#[cfg(feature = "arrow")]
+mod arrow;
+mod checksum;
+mod compress;
 #[cfg(feature = "csv")]
 mod csv;
+mod dedup;
+mod estimate;
+#[cfg(feature = "ipc")]
+mod ipc;
+mod jsonl;
+mod melt;
 #[cfg(feature = "parquet")]
 mod parquet;
+mod utf8;
 
-#[cfg(any(feature = "csv", feature = "parquet"))]
-use crate::error::Error;
+#[cfg(any(feature = "arrow", feature = "parquet"))]
+use crate::dataset::RepeatedGroup;
 use crate::{
     cell::CellValue,
     dataset::DatasetMetadata,
-    error::Result,
+    error::{Error, Result},
+    logger::Logger,
     parser::{ColumnInfo, ColumnarBatch, DatasetLayout, StreamingRow},
 };
+#[cfg(feature = "arrow")]
+pub use arrow::{ArrowSink, SAS_ALIGNMENT_KEY, SAS_MEASURE_KEY};
+pub use checksum::{ChecksumSink, ColumnChecksum};
+pub use compress::OutputCompression;
 #[cfg(feature = "csv")]
-pub use csv::CsvSink;
+pub use csv::{CsvSink, QuoteStyle};
+pub use dedup::{DedupKeep, DedupSink};
+pub use estimate::{
+    ColumnProfile, OutputSizeEstimate, SampleProfile, SinkKind, estimate_output_size,
+    estimate_output_size_with_sample,
+};
+#[cfg(feature = "ipc")]
+pub use ipc::IpcSink;
+pub use jsonl::JsonlSink;
+pub use melt::MeltSink;
 #[cfg(feature = "parquet")]
-pub use parquet::ParquetSink;
-#[cfg(any(feature = "csv", feature = "parquet"))]
+pub use parquet::{
+    Codec, DATASET_MANIFEST, DatasetColumn, DatasetFile, EnabledStatistics, HIVE_DEFAULT_PARTITION,
+    ParquetColumnType, ParquetDataset, ParquetSink, PartitionFile, PartitionedParquetSink,
+    SAS_COLUMNS_KEY, TimePrecision, TypeMapper,
+};
 use std::borrow::Cow;
+pub use utf8::{Utf8Replacements, Utf8Sink};
 
 /// Provides high-level dataset information to sinks during initialisation.
 pub struct SinkContext<'a> {
     pub metadata: &'a DatasetMetadata,
     pub columns: &'a [ColumnInfo],
     pub source_path: Option<String>,
+    /// Receives warnings raised by the sink, such as values written as null.
+    pub logger: Logger,
 }
 
 impl<'a> SinkContext<'a> {
@@ -32,11 +63,15 @@ impl<'a> SinkContext<'a> {
             metadata: &parsed.header.metadata,
             columns: &parsed.columns,
             source_path: None,
+            logger: parsed.logger.clone(),
         }
     }
 }
 
 /// Trait implemented by row sinks that consume decoded SAS rows.
+///
+/// Rows and columnar batches always arrive in file order, so sinks may rely
+/// on row position (for example to keep the first or last of a group).
 pub trait RowSink {
     /// Called before any rows are written to allow the sink to initialise internal state.
     ///
@@ -65,12 +100,66 @@ pub trait RowSink {
         self.write_row(&values)
     }
 
+    /// Returns `true` when [`write_streaming_row`](RowSink::write_streaming_row)
+    /// decodes cells straight from the borrowed row, without the
+    /// materialising default, and the sink buffers a bounded number of rows.
+    ///
+    /// [`SasReader::stream_into_low_memory`](crate::SasReader::stream_into_low_memory)
+    /// only accepts sinks that return `true`.
+    fn streams_borrowed_rows(&self) -> bool {
+        false
+    }
+
     /// Called once all rows have been forwarded to the sink.
     ///
     /// # Errors
     ///
     /// Returns an error if finalising the sink or flushing the underlying output fails.
     fn finish(&mut self) -> Result<()>;
+
+    /// Called instead of [`finish`](RowSink::finish) when a conversion is
+    /// cancelled.
+    ///
+    /// The output is left valid but partial: the rows already passed to the
+    /// sink are written out and the output is closed the way `finish` closes
+    /// it, so compressed streams are ended and Parquet and Arrow IPC files
+    /// get their footer. Rows a sink holds back until it has seen the whole
+    /// input, such as the ones [`DedupSink`] keeps to compare against, are
+    /// dropped. The default calls `finish`; wrapping sinks abort their inner
+    /// sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing or closing the output fails.
+    fn abort(&mut self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<S: RowSink + ?Sized> RowSink for Box<S> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        (**self).begin(context)
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        (**self).write_row(row)
+    }
+
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        (**self).write_streaming_row(row)
+    }
+
+    fn streams_borrowed_rows(&self) -> bool {
+        (**self).streams_borrowed_rows()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        (**self).finish()
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        (**self).abort()
+    }
 }
 
 /// Trait implemented by sinks that can consume columnar batches directly.
@@ -88,7 +177,6 @@ pub trait ColumnarSink: RowSink {
     ) -> Result<()>;
 }
 
-#[cfg(any(feature = "csv", feature = "parquet"))]
 pub(crate) fn validate_sink_begin(
     context: &SinkContext<'_>,
     writer_present: bool,
@@ -102,9 +190,78 @@ pub(crate) fn validate_sink_begin(
         });
     }
     if context.metadata.variables.len() != context.columns.len() {
-        return Err(Error::InvalidMetadata {
+        return Err(Error::InvalidArgument {
             details: Cow::from("column metadata length mismatch"),
         });
     }
     Ok(())
 }
+
+/// Maps each input column to the repeated group it belongs to, if any.
+///
+/// Every member must name a column, appear in only one group, and share its
+/// column type with the rest of its group.
+#[cfg(any(feature = "arrow", feature = "parquet"))]
+pub(crate) fn resolve_repeated_groups(
+    groups: &[RepeatedGroup],
+    context: &SinkContext<'_>,
+) -> Result<Vec<Option<usize>>> {
+    let variables = &context.metadata.variables;
+    let mut membership: Vec<Option<usize>> = vec![None; variables.len()];
+    for (group_idx, group) in groups.iter().enumerate() {
+        if group.members.is_empty() {
+            return Err(Error::InvalidArgument {
+                details: format!("repeated group '{}' has no members", group.name).into(),
+            });
+        }
+        let mut kind = None;
+        for member in &group.members {
+            let index = variables
+                .iter()
+                .position(|v| v.name.trim_end().eq_ignore_ascii_case(member))
+                .ok_or_else(|| Error::InvalidArgument {
+                    details: format!(
+                        "repeated group '{}' member '{member}' not found in dataset",
+                        group.name
+                    )
+                    .into(),
+                })?;
+            if membership[index].replace(group_idx).is_some() {
+                return Err(Error::InvalidArgument {
+                    details: format!("column '{member}' is listed in more than one repeated group")
+                        .into(),
+                });
+            }
+            let member_kind = context.columns.get(index).map(|column| column.kind);
+            if kind.get_or_insert(member_kind) != &member_kind {
+                return Err(Error::InvalidArgument {
+                    details: format!(
+                        "repeated group '{}' mixes column types at member '{member}'",
+                        group.name
+                    )
+                    .into(),
+                });
+            }
+        }
+    }
+    Ok(membership)
+}
+
+/// Input column indices of a group's members, in member order.
+#[cfg(any(feature = "arrow", feature = "parquet"))]
+pub(crate) fn repeated_group_members(
+    group: &RepeatedGroup,
+    context: &SinkContext<'_>,
+) -> Vec<usize> {
+    group
+        .members
+        .iter()
+        .filter_map(|member| {
+            context
+                .metadata
+                .variables
+                .iter()
+                .position(|v| v.name.trim_end().eq_ignore_ascii_case(member))
+        })
+        .collect()
+}
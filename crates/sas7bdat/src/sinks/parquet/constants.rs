@@ -5,3 +5,6 @@ pub const DEFAULT_TARGET_ROW_GROUP_BYTES: usize = 512 * 1024 * 1024;
 pub const MIN_AUTO_ROW_GROUP_ROWS: usize = 1_024;
 pub const MAX_AUTO_ROW_GROUP_ROWS: usize = 262_144;
 pub const UTF8_DICTIONARY_LIMIT: usize = 4_096;
+/// Values longer than this are written without interning; wide text rarely
+/// repeats, and keeping it would hold up to 4096 copies per column.
+pub const UTF8_DICTIONARY_MAX_VALUE_BYTES: usize = 1_024;
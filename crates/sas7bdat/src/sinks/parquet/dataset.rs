@@ -0,0 +1,341 @@
+use super::ParquetSink;
+use crate::{
+    error::{Error, Result},
+    reader::SasReader,
+    sinks::{ChecksumSink, ColumnChecksum},
+};
+use parquet::{
+    basic::Repetition,
+    file::reader::{FileReader, SerializedFileReader},
+    schema::types::Type,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek},
+    path::{Path, PathBuf},
+};
+
+/// Name of the manifest kept next to the data files. The leading underscore
+/// makes dataset readers skip it when scanning the directory.
+pub const DATASET_MANIFEST: &str = "_manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+
+/// A column of the dataset's combined schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetColumn {
+    pub name: String,
+    /// Physical and logical Parquet type, such as `INT32 (Date)`.
+    #[serde(rename = "type")]
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// A data file recorded in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetFile {
+    /// File name relative to the dataset directory.
+    pub file: String,
+    pub rows: u64,
+    /// SAS table name of the source, when the file header had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_table: Option<String>,
+    /// Per-column checksums of the converted values, recorded when the
+    /// dataset was opened with [`ParquetDataset::with_column_checksums`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checksums: Vec<ColumnChecksum>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    columns: Vec<DatasetColumn>,
+    files: Vec<DatasetFile>,
+}
+
+/// Directory of Parquet files that grows one file per appended SAS dataset.
+///
+/// Every append is checked against the schema of the files already in the
+/// directory. Existing columns must keep their type; new columns may be
+/// added as long as they are nullable, so older files read as null for
+/// them. Files are named `part-00000.parquet`, `part-00001.parquet`, ... and
+/// listed with their row counts in [`DATASET_MANIFEST`].
+#[derive(Debug)]
+pub struct ParquetDataset {
+    dir: PathBuf,
+    manifest: Manifest,
+    checksums: bool,
+}
+
+impl ParquetDataset {
+    /// Opens the dataset in `dir`, creating the directory and an empty
+    /// manifest if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created or the manifest
+    /// cannot be read.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let manifest_path = dir.join(DATASET_MANIFEST);
+        let manifest = match fs::read(&manifest_path) {
+            Ok(bytes) => serde_json::from_slice::<Manifest>(&bytes).map_err(|err| {
+                Error::InvalidArgument {
+                    details: format!("invalid manifest '{}': {err}", manifest_path.display())
+                        .into(),
+                }
+            })?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Manifest {
+                version: MANIFEST_VERSION,
+                columns: Vec::new(),
+                files: Vec::new(),
+            },
+            Err(err) => return Err(err.into()),
+        };
+        if manifest.version != MANIFEST_VERSION {
+            return Err(Error::Unsupported {
+                feature: format!("dataset manifest version {}", manifest.version).into(),
+            });
+        }
+        Ok(Self {
+            dir,
+            manifest,
+            checksums: false,
+        })
+    }
+
+    /// Records a [`ColumnChecksum`] per column for every file appended from
+    /// now on, computed while the rows stream through the sink. Comparing
+    /// them with a [`ChecksumSink`] run over the SAS source reconciles the
+    /// two without reading back the Parquet file.
+    #[must_use]
+    pub const fn with_column_checksums(mut self, enabled: bool) -> Self {
+        self.checksums = enabled;
+        self
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Combined schema of all files, in the order columns first appeared.
+    #[must_use]
+    pub fn columns(&self) -> &[DatasetColumn] {
+        &self.manifest.columns
+    }
+
+    #[must_use]
+    pub fn files(&self) -> &[DatasetFile] {
+        &self.manifest.files
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.manifest.files.iter().map(|file| file.rows).sum()
+    }
+
+    /// Converts `sas` into a new data file with default sink settings.
+    ///
+    /// # Errors
+    ///
+    /// See [`ParquetDataset::append_with`].
+    pub fn append<R: Read + Seek>(&mut self, sas: &mut SasReader<R>) -> Result<&DatasetFile> {
+        self.append_with(sas, |sink| sink)
+    }
+
+    /// Converts `sas` into a new data file, letting `configure` adjust the
+    /// sink, and records it in the manifest.
+    ///
+    /// The file is written under a temporary name and only renamed into
+    /// place once its schema has been accepted, so a rejected append leaves
+    /// the dataset untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if conversion fails, if the new schema drops or
+    /// retypes an existing column or adds a non-nullable one, or if the file
+    /// or manifest cannot be written.
+    pub fn append_with<R, F>(
+        &mut self,
+        sas: &mut SasReader<R>,
+        configure: F,
+    ) -> Result<&DatasetFile>
+    where
+        R: Read + Seek,
+        F: FnOnce(ParquetSink<File>) -> ParquetSink<File>,
+    {
+        let name = format!("part-{:05}.parquet", self.manifest.files.len());
+        let final_path = self.dir.join(&name);
+        if final_path.exists() {
+            return Err(Error::InvalidArgument {
+                details: format!(
+                    "'{}' already exists but is not listed in the manifest",
+                    final_path.display()
+                )
+                .into(),
+            });
+        }
+        let temp_path = self.dir.join(format!(".{name}.tmp"));
+        let written = write_file(sas, &temp_path, self.checksums, configure).and_then(
+            |(file, rows, checksums)| {
+                let columns = merge_schema(&self.manifest.columns, &file_columns(file)?)?;
+                Ok((columns, rows, checksums))
+            },
+        );
+        let (columns, rows, checksums) = match written {
+            Ok(written) => written,
+            Err(err) => {
+                let _ = fs::remove_file(&temp_path);
+                return Err(err);
+            }
+        };
+        fs::rename(&temp_path, &final_path)?;
+
+        let mut manifest = self.manifest.clone();
+        let index = manifest.files.len();
+        manifest.columns = columns;
+        manifest.files.push(DatasetFile {
+            file: name,
+            rows,
+            source_table: sas.metadata().table_name.clone(),
+            checksums,
+        });
+        if let Err(err) = self.write_manifest(&manifest) {
+            let _ = fs::remove_file(&final_path);
+            return Err(err);
+        }
+        self.manifest = manifest;
+        Ok(&self.manifest.files[index])
+    }
+
+    fn write_manifest(&self, manifest: &Manifest) -> Result<()> {
+        let json = serde_json::to_vec_pretty(manifest).map_err(|err| Error::Parquet {
+            details: format!("failed to serialise dataset manifest: {err}").into(),
+        })?;
+        let temp_path = self.dir.join(format!(".{DATASET_MANIFEST}.tmp"));
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, self.dir.join(DATASET_MANIFEST))?;
+        Ok(())
+    }
+}
+
+fn write_file<R, F>(
+    sas: &mut SasReader<R>,
+    path: &Path,
+    checksums: bool,
+    configure: F,
+) -> Result<(File, u64, Vec<ColumnChecksum>)>
+where
+    R: Read + Seek,
+    F: FnOnce(ParquetSink<File>) -> ParquetSink<File>,
+{
+    let output = File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    let mut sink = configure(ParquetSink::new(output));
+    let checksums = if checksums {
+        let mut checksum_sink = ChecksumSink::new(sink);
+        sas.stream_into(&mut checksum_sink)?;
+        let checksums = checksum_sink.checksums();
+        sink = checksum_sink.into_inner();
+        checksums
+    } else {
+        sas.stream_into(&mut sink)?;
+        Vec::new()
+    };
+    let file = sink.into_inner()?;
+    let rows = SerializedFileReader::new(file.try_clone()?)?
+        .metadata()
+        .file_metadata()
+        .num_rows();
+    Ok((file, u64::try_from(rows).unwrap_or_default(), checksums))
+}
+
+/// Reads the top-level columns back from a written file's footer.
+fn file_columns(file: File) -> Result<Vec<DatasetColumn>> {
+    let reader = SerializedFileReader::new(file)?;
+    let schema = reader.metadata().file_metadata().schema();
+    Ok(schema
+        .get_fields()
+        .iter()
+        .map(|field| DatasetColumn {
+            name: field.name().to_owned(),
+            data_type: describe_type(field),
+            nullable: field.get_basic_info().repetition() != Repetition::REQUIRED,
+        })
+        .collect())
+}
+
+fn describe_type(ty: &Type) -> String {
+    let info = ty.get_basic_info();
+    let logical = info
+        .logical_type_ref()
+        .map(|logical| format!(" ({logical:?})"))
+        .unwrap_or_default();
+    if ty.is_primitive() {
+        return format!("{}{logical}", ty.get_physical_type());
+    }
+    let children: Vec<String> = ty
+        .get_fields()
+        .iter()
+        .map(|child| {
+            format!(
+                "{} {}: {}",
+                child.get_basic_info().repetition(),
+                child.name(),
+                describe_type(child)
+            )
+        })
+        .collect();
+    format!("group{logical} {{{}}}", children.join(", "))
+}
+
+/// Returns the combined schema, or an error naming the first column that
+/// makes `incoming` incompatible with `existing`.
+fn merge_schema(
+    existing: &[DatasetColumn],
+    incoming: &[DatasetColumn],
+) -> Result<Vec<DatasetColumn>> {
+    if existing.is_empty() {
+        return Ok(incoming.to_vec());
+    }
+    let mut merged = existing.to_vec();
+    for column in &mut merged {
+        let Some(new) = incoming.iter().find(|new| new.name == column.name) else {
+            return Err(schema_error(&format!(
+                "column '{}' is missing",
+                column.name
+            )));
+        };
+        if new.data_type != column.data_type {
+            return Err(schema_error(&format!(
+                "column '{}' changed type from {} to {}",
+                column.name, column.data_type, new.data_type
+            )));
+        }
+        column.nullable |= new.nullable;
+    }
+    for new in incoming {
+        if existing.iter().any(|column| column.name == new.name) {
+            continue;
+        }
+        if !new.nullable {
+            return Err(schema_error(&format!(
+                "added column '{}' must be nullable",
+                new.name
+            )));
+        }
+        merged.push(new.clone());
+    }
+    Ok(merged)
+}
+
+fn schema_error(reason: &str) -> Error {
+    Error::InvalidArgument {
+        details: format!("schema is incompatible with the dataset: {reason}").into(),
+    }
+}
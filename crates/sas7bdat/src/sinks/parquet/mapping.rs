@@ -0,0 +1,74 @@
+use crate::{
+    dataset::Variable,
+    parser::{ColumnInfo, ColumnKind, NumericKind},
+};
+
+/// Parquet column types a [`TypeMapper`] can choose from.
+///
+/// Values are converted with the sink's usual coercions: numbers written to
+/// text columns are formatted, text written to `Double` columns is parsed,
+/// and dates written to `Double` columns become SAS day counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParquetColumnType {
+    /// `DOUBLE`.
+    Double,
+    /// `INT32` annotated as `DATE`.
+    Date,
+    /// `INT32` days since 1970-01-01 without a logical type.
+    Int32Days,
+    /// `INT64` annotated as `TIMESTAMP(MICROS)` in UTC.
+    Timestamp,
+    /// `INT64` annotated as `TIME` in the sink's
+    /// [`TimePrecision`](super::TimePrecision).
+    Time,
+    /// `BYTE_ARRAY` annotated as `STRING`.
+    Utf8,
+    /// `BYTE_ARRAY` without a logical type.
+    Binary,
+}
+
+impl ParquetColumnType {
+    /// Type the sink writes for `kind` when no mapper overrides it.
+    #[must_use]
+    pub const fn default_for(kind: ColumnKind) -> Self {
+        match kind {
+            ColumnKind::Character | ColumnKind::Numeric(NumericKind::Text) => Self::Utf8,
+            ColumnKind::Bytes => Self::Binary,
+            ColumnKind::Numeric(NumericKind::Double | NumericKind::Float) => Self::Double,
+            ColumnKind::Numeric(NumericKind::Date) => Self::Date,
+            ColumnKind::Numeric(NumericKind::DateTime) => Self::Timestamp,
+            ColumnKind::Numeric(NumericKind::Time) => Self::Time,
+        }
+    }
+}
+
+/// Overrides how individual columns map to Parquet types.
+///
+/// Install with [`ParquetSink::with_type_mapper`](super::ParquetSink::with_type_mapper).
+/// Closures with the same signature as [`TypeMapper::map_type`] implement
+/// the trait.
+pub trait TypeMapper: Send + Sync {
+    /// Returns the type to write for a column; `default` is what the sink
+    /// would choose on its own.
+    fn map_type(
+        &self,
+        variable: &Variable,
+        column: &ColumnInfo,
+        default: ParquetColumnType,
+    ) -> ParquetColumnType;
+}
+
+impl<F> TypeMapper for F
+where
+    F: Fn(&Variable, &ColumnInfo, ParquetColumnType) -> ParquetColumnType + Send + Sync,
+{
+    fn map_type(
+        &self,
+        variable: &Variable,
+        column: &ColumnInfo,
+        default: ParquetColumnType,
+    ) -> ParquetColumnType {
+        self(variable, column, default)
+    }
+}
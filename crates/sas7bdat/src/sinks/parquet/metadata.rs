@@ -0,0 +1,59 @@
+use crate::{
+    dataset::{Alignment, DatasetMetadata, Measure, Variable, VariableKind},
+    error::{Error, Result},
+};
+use parquet::file::metadata::KeyValue;
+use serde::Serialize;
+
+/// Footer key holding the JSON array of per-column SAS metadata.
+pub const SAS_COLUMNS_KEY: &str = "sas.columns";
+
+#[derive(Serialize)]
+struct ColumnEntry<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    informat: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value_labels: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    measure: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alignment: Option<&'static str>,
+}
+
+impl<'a> ColumnEntry<'a> {
+    fn new(variable: &'a Variable) -> Self {
+        Self {
+            name: variable.name.trim_end(),
+            kind: match variable.kind {
+                VariableKind::Numeric => "numeric",
+                VariableKind::Character => "character",
+            },
+            length: variable.storage_width,
+            label: variable.label.as_deref().filter(|label| !label.is_empty()),
+            format: variable.format.as_ref().map(ToString::to_string),
+            informat: variable.informat.as_ref().map(ToString::to_string),
+            value_labels: variable.value_labels.as_deref(),
+            measure: (variable.measure != Measure::Unknown).then(|| variable.measure.as_str()),
+            alignment: (variable.alignment != Alignment::Unknown)
+                .then(|| variable.alignment.as_str()),
+        }
+    }
+}
+
+/// Builds the footer entries describing every SAS variable, in dataset
+/// order, so labels and formats survive conversion.
+pub(super) fn sas_key_value_metadata(metadata: &DatasetMetadata) -> Result<Vec<KeyValue>> {
+    let columns: Vec<ColumnEntry<'_>> = metadata.variables.iter().map(ColumnEntry::new).collect();
+    let json = serde_json::to_string(&columns).map_err(|err| Error::Parquet {
+        details: format!("failed to serialise SAS column metadata: {err}").into(),
+    })?;
+    Ok(vec![KeyValue::new(SAS_COLUMNS_KEY.to_owned(), json)])
+}
@@ -1,8 +1,16 @@
 mod constants;
+mod dataset;
+mod mapping;
+mod metadata;
+mod partitioned;
 mod plan;
 mod plan_stream;
 mod sink;
 mod stream;
 mod utf8;
 
-pub use sink::ParquetSink;
+pub use dataset::{DATASET_MANIFEST, DatasetColumn, DatasetFile, ParquetDataset};
+pub use mapping::{ParquetColumnType, TypeMapper};
+pub use metadata::SAS_COLUMNS_KEY;
+pub use partitioned::{HIVE_DEFAULT_PARTITION, PartitionFile, PartitionedParquetSink};
+pub use sink::{Codec, EnabledStatistics, ParquetSink, TimePrecision};
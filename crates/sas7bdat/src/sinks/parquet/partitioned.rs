@@ -0,0 +1,416 @@
+use super::ParquetSink;
+use crate::{
+    cell::CellValue,
+    dataset::DatasetMetadata,
+    error::{Error, Result},
+    logger::Logger,
+    parser::ColumnInfo,
+    sinks::{RowSink, SinkContext, validate_sink_begin},
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Write as _,
+    fs::{self, File},
+    path::PathBuf,
+};
+use time::{Duration, OffsetDateTime};
+
+/// Directory name component Hive readers map back to a null partition
+/// value, used for missing values and blank strings.
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+const DEFAULT_MAX_OPEN_FILES: usize = 64;
+
+type ConfigureFile = Box<dyn Fn(ParquetSink<File>) -> ParquetSink<File> + Send>;
+
+/// A data file written by a [`PartitionedParquetSink`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionFile {
+    /// Path relative to the dataset root, such as
+    /// `YEAR=2020/REGION=North/part-00000.parquet`.
+    pub path: PathBuf,
+    pub rows: u64,
+}
+
+struct Partition {
+    writer: Option<ParquetSink<File>>,
+    /// Position in `files` of the file being written.
+    file: usize,
+    next_part: usize,
+    last_used: u64,
+}
+
+/// Writes rows into a Hive-style partitioned directory of Parquet files,
+/// `col=value/part-00000.parquet`, keyed on one or more columns.
+///
+/// Partition columns are encoded in the directory names and left out of
+/// the data files, as query engines expect. Each partition is written by
+/// its own [`ParquetSink`], which flushes a row group whenever it has
+/// buffered one, so every open partition holds up to a row group in
+/// memory. At most [`with_max_open_files`](Self::with_max_open_files) files
+/// are open at once; writing to another partition finishes the least
+/// recently used file, and later rows for that partition go to a new part
+/// file next to it.
+pub struct PartitionedParquetSink {
+    root: PathBuf,
+    partition_by: Vec<String>,
+    max_open_files: usize,
+    max_rows_per_file: u64,
+    configure: ConfigureFile,
+    key_columns: Vec<usize>,
+    /// Stored names of the partition columns, without their padding.
+    key_names: Vec<String>,
+    data_columns: Vec<usize>,
+    metadata: Option<DatasetMetadata>,
+    columns: Vec<ColumnInfo>,
+    source_path: Option<String>,
+    logger: Logger,
+    partitions: HashMap<String, Partition>,
+    open_files: usize,
+    clock: u64,
+    files: Vec<PartitionFile>,
+    key: String,
+}
+
+impl PartitionedParquetSink {
+    /// Creates a sink writing below `root`, partitioned on the columns
+    /// named in `partition_by`, outermost first. Names are matched
+    /// case-insensitively when the sink begins.
+    pub fn new<I, S>(root: impl Into<PathBuf>, partition_by: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            root: root.into(),
+            partition_by: partition_by.into_iter().map(Into::into).collect(),
+            max_open_files: DEFAULT_MAX_OPEN_FILES,
+            max_rows_per_file: 0,
+            configure: Box::new(|sink| sink),
+            key_columns: Vec::new(),
+            key_names: Vec::new(),
+            data_columns: Vec::new(),
+            metadata: None,
+            columns: Vec::new(),
+            source_path: None,
+            logger: Logger::default(),
+            partitions: HashMap::new(),
+            open_files: 0,
+            clock: 0,
+            files: Vec::new(),
+            key: String::new(),
+        }
+    }
+
+    /// Caps the number of part files open at once (64 by default, at
+    /// least 1).
+    #[must_use]
+    pub fn with_max_open_files(mut self, files: usize) -> Self {
+        self.max_open_files = files.max(1);
+        self
+    }
+
+    /// Starts a new part file in a partition once its current file holds
+    /// `rows` rows. Zero, the default, never splits a partition on size.
+    #[must_use]
+    pub const fn with_max_rows_per_file(mut self, rows: u64) -> Self {
+        self.max_rows_per_file = rows;
+        self
+    }
+
+    /// Applies `configure` to the [`ParquetSink`] of every part file, for
+    /// example to pick a codec or a smaller row group size.
+    #[must_use]
+    pub fn with_file_options<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(ParquetSink<File>) -> ParquetSink<File> + Send + 'static,
+    {
+        self.configure = Box::new(configure);
+        self
+    }
+
+    /// Files written so far, in the order they were opened.
+    #[must_use]
+    pub fn files(&self) -> &[PartitionFile] {
+        &self.files
+    }
+
+    /// Finishes the open file of the partition at `key`, if any.
+    fn close(&mut self, key: &str) -> Result<()> {
+        let Some(mut writer) = self
+            .partitions
+            .get_mut(key)
+            .and_then(|partition| partition.writer.take())
+        else {
+            return Ok(());
+        };
+        self.open_files -= 1;
+        writer.finish()
+    }
+
+    /// Finishes the least recently used open file.
+    fn close_least_recent(&mut self) -> Result<()> {
+        let Some(key) = self
+            .partitions
+            .iter()
+            .filter(|(_, partition)| partition.writer.is_some())
+            .min_by_key(|(_, partition)| partition.last_used)
+            .map(|(key, _)| key.clone())
+        else {
+            return Ok(());
+        };
+        self.close(&key)
+    }
+
+    /// Opens the next part file of the partition at `key`.
+    fn open(&mut self, key: &str) -> Result<()> {
+        while self.open_files >= self.max_open_files {
+            self.close_least_recent()?;
+        }
+        let next_part = self
+            .partitions
+            .get(key)
+            .map_or(0, |partition| partition.next_part);
+        let relative = PathBuf::from(key).join(format!("part-{next_part:05}.parquet"));
+        let path = self.root.join(&relative);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let metadata = self.metadata.as_ref().ok_or_else(|| Error::Unsupported {
+            feature: Cow::from("rows written before partitioned Parquet sink initialised"),
+        })?;
+        let mut writer = (self.configure)(ParquetSink::new(File::create(&path)?));
+        writer.begin(SinkContext {
+            metadata,
+            columns: &self.columns,
+            source_path: self.source_path.clone(),
+            logger: self.logger.clone(),
+        })?;
+
+        self.files.push(PartitionFile {
+            path: relative,
+            rows: 0,
+        });
+        self.open_files += 1;
+        self.partitions.insert(
+            key.to_owned(),
+            Partition {
+                writer: Some(writer),
+                file: self.files.len() - 1,
+                next_part: next_part + 1,
+                last_used: self.clock,
+            },
+        );
+        Ok(())
+    }
+
+    fn write_to_partition(&mut self, key: &str, row: &[CellValue<'_>]) -> Result<()> {
+        if self.needs_new_file(key) {
+            self.close(key)?;
+            self.open(key)?;
+        }
+        let values: Vec<CellValue<'_>> = self
+            .data_columns
+            .iter()
+            .map(|&index| row[index].clone())
+            .collect();
+        let partition = self
+            .partitions
+            .get_mut(key)
+            .expect("partition opened above");
+        partition.last_used = self.clock;
+        self.files[partition.file].rows += 1;
+        partition
+            .writer
+            .as_mut()
+            .expect("partition file opened above")
+            .write_row(&values)
+    }
+
+    fn needs_new_file(&self, key: &str) -> bool {
+        self.partitions.get(key).is_none_or(|partition| {
+            partition.writer.is_none()
+                || (self.max_rows_per_file > 0
+                    && self.files[partition.file].rows >= self.max_rows_per_file)
+        })
+    }
+}
+
+impl RowSink for PartitionedParquetSink {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        validate_sink_begin(&context, self.metadata.is_some(), "Partitioned Parquet")?;
+        if self.partition_by.is_empty() {
+            return Err(Error::InvalidArgument {
+                details: Cow::from("partitioned Parquet sink needs at least one partition column"),
+            });
+        }
+
+        let variables = &context.metadata.variables;
+        let mut key_columns = Vec::with_capacity(self.partition_by.len());
+        for name in &self.partition_by {
+            let index = variables
+                .iter()
+                .position(|variable| variable.name.trim_end().eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::InvalidArgument {
+                    details: format!("partition column '{name}' not found in dataset").into(),
+                })?;
+            key_columns.push(index);
+        }
+        let data_columns: Vec<usize> = (0..variables.len())
+            .filter(|index| !key_columns.contains(index))
+            .collect();
+        if data_columns.is_empty() {
+            return Err(Error::InvalidArgument {
+                details: Cow::from("every column is a partition column"),
+            });
+        }
+
+        let mut metadata = context.metadata.clone();
+        metadata.variables = data_columns
+            .iter()
+            .map(|&index| variables[index].clone())
+            .collect();
+        metadata.column_count = u32::try_from(data_columns.len()).map_err(|_| Error::Limit {
+            details: Cow::from("partitioned dataset has too many columns"),
+        })?;
+        self.columns = data_columns
+            .iter()
+            .map(|&index| context.columns[index].clone())
+            .collect();
+        self.metadata = Some(metadata);
+        self.key_names = key_columns
+            .iter()
+            .map(|&index| variables[index].name.trim_end().to_owned())
+            .collect();
+        self.key_columns = key_columns;
+        self.data_columns = data_columns;
+        self.source_path = context.source_path;
+        self.logger = context.logger;
+        self.partitions.clear();
+        self.open_files = 0;
+        self.files.clear();
+        fs::create_dir_all(&self.root)?;
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        let expected = self.key_columns.len() + self.data_columns.len();
+        if row.len() != expected {
+            return Err(Error::InvalidArgument {
+                details: format!(
+                    "row has {} values but the dataset has {expected} columns",
+                    row.len()
+                )
+                .into(),
+            });
+        }
+
+        let mut key = std::mem::take(&mut self.key);
+        key.clear();
+        for (position, (&index, name)) in self.key_columns.iter().zip(&self.key_names).enumerate() {
+            if position > 0 {
+                key.push('/');
+            }
+            escape_path_name(name, &mut key);
+            key.push('=');
+            escape_path_name(&partition_value(&row[index]), &mut key);
+        }
+
+        self.clock += 1;
+        let result = self.write_to_partition(&key, row);
+        self.key = key;
+        result
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let mut keys: Vec<String> = self
+            .partitions
+            .iter()
+            .filter(|(_, partition)| partition.writer.is_some())
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort_unstable();
+        for key in keys {
+            self.close(&key)?;
+        }
+        self.partitions.clear();
+        self.metadata = None;
+        Ok(())
+    }
+}
+
+/// Renders a partition value the way Hive writes it in a directory name.
+fn partition_value(value: &CellValue<'_>) -> String {
+    match value {
+        CellValue::Missing(_) => HIVE_DEFAULT_PARTITION.to_owned(),
+        CellValue::Str(text) | CellValue::NumericString(text) => match text.trim() {
+            "" => HIVE_DEFAULT_PARTITION.to_owned(),
+            text => text.to_owned(),
+        },
+        CellValue::Bytes(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        CellValue::Float(value) => format_float(*value),
+        CellValue::Int32(value) => value.to_string(),
+        CellValue::Int64(value) => value.to_string(),
+        CellValue::Date(datetime) => datetime.date().to_string(),
+        CellValue::DateTime(datetime) => format_datetime(datetime),
+        CellValue::Time(duration) => format_time(*duration),
+    }
+}
+
+/// Whole numbers are written without a fraction, so a numeric `YEAR`
+/// column yields `YEAR=2020`.
+#[allow(clippy::cast_possible_truncation)]
+fn format_float(value: f64) -> String {
+    const EXACT: f64 = 9_007_199_254_740_992.0; // 2^53
+    if !value.is_finite() {
+        return HIVE_DEFAULT_PARTITION.to_owned();
+    }
+    if value.fract() == 0.0 && value.abs() < EXACT {
+        return (value as i64).to_string();
+    }
+    value.to_string()
+}
+
+fn format_datetime(datetime: &OffsetDateTime) -> String {
+    let mut out = format!(
+        "{} {:02}:{:02}:{:02}",
+        datetime.date(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second()
+    );
+    match datetime.microsecond() {
+        0 => {}
+        micros => {
+            let _ = write!(out, ".{micros:06}");
+        }
+    }
+    out
+}
+
+fn format_time(duration: Duration) -> String {
+    let sign = if duration.is_negative() { "-" } else { "" };
+    let duration = duration.abs();
+    let seconds = duration.whole_seconds();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match duration.subsec_microseconds() {
+        0 => format!("{sign}{hours:02}:{minutes:02}:{seconds:02}"),
+        micros => format!("{sign}{hours:02}:{minutes:02}:{seconds:02}.{micros:06}"),
+    }
+}
+
+/// Percent-encodes the characters Hive escapes in partition paths.
+fn escape_path_name(name: &str, out: &mut String) {
+    for ch in name.chars() {
+        if ch.is_ascii_control()
+            || matches!(
+                ch,
+                '"' | '#' | '%' | '\'' | '*' | '/' | ':' | '=' | '?' | '\\' | '{' | '[' | ']' | '^'
+            )
+        {
+            let _ = write!(out, "%{:02X}", u32::from(ch));
+        } else {
+            out.push(ch);
+        }
+    }
+}
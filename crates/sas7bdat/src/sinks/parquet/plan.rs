@@ -1,10 +1,17 @@
-use super::{constants::SECONDS_PER_DAY, utf8::Utf8Scratch};
+use super::{
+    constants::SECONDS_PER_DAY, mapping::ParquetColumnType, sink::TimePrecision,
+    stream::write_def_levels, utf8::Utf8Scratch,
+};
 use crate::{
     cell::CellValue,
     dataset::Variable,
     error::{Error, Result},
-    logger::log_warn,
-    parser::{ColumnInfo, ColumnKind, NumericKind, sas_days_to_datetime, sas_seconds_to_datetime},
+    logger::Logger,
+    parser::TimestampRounding,
+    time_conv::{
+        date_to_sas_days, datetime_to_sas_seconds, sas_days_to_date, sas_seconds_to_datetime,
+        sas_seconds_to_time, time_to_sas_seconds,
+    },
 };
 use parquet::{
     basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType},
@@ -12,15 +19,33 @@ use parquet::{
     schema::types::{Type, TypePtr},
 };
 use std::{borrow::Cow, sync::Arc};
-use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time};
+use time::Duration;
+
+/// Definition levels contributed by the optional list group and its repeated
+/// child, added on top of the element's own level.
+const LIST_ELEMENT_DEF_OFFSET: i16 = 2;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub(super) enum ColumnValueEncoder {
     Double,
     Date,
     DateTime,
     Time,
     Utf8,
+    Binary,
+}
+
+impl ColumnValueEncoder {
+    pub(super) const fn of(column_type: ParquetColumnType) -> Self {
+        match column_type {
+            ParquetColumnType::Double => Self::Double,
+            ParquetColumnType::Date | ParquetColumnType::Int32Days => Self::Date,
+            ParquetColumnType::Timestamp => Self::DateTime,
+            ParquetColumnType::Time => Self::Time,
+            ParquetColumnType::Utf8 => Self::Utf8,
+            ParquetColumnType::Binary => Self::Binary,
+        }
+    }
 }
 
 pub(super) enum ColumnValues {
@@ -34,39 +59,47 @@ pub(super) struct ColumnPlan {
     pub name: String,
     pub encoder: ColumnValueEncoder,
     pub def_levels: Vec<i16>,
+    /// Repetition levels, present only for list columns.
+    pub rep_levels: Option<Vec<i16>>,
     pub def_bitmap: Vec<u8>,
     pub values: ColumnValues,
     pub utf8_scratch: Option<Utf8Scratch>,
     pub utf8_inlines: Vec<ByteArray>,
     lenient_dates: bool,
+    pub(super) rounding: TimestampRounding,
+    pub(super) time_precision: TimePrecision,
+    pub(super) required: bool,
     warned_invalid_value: bool,
-    source_path: Option<String>,
+    logger: Logger,
 }
 
 impl ColumnPlan {
     pub(super) fn new(
         variable: &Variable,
-        column: &ColumnInfo,
+        column_type: ParquetColumnType,
         lenient_dates: bool,
-        source_path: Option<&str>,
+        rounding: TimestampRounding,
+        time_precision: TimePrecision,
+        required: bool,
+        logger: Logger,
     ) -> Result<(Self, TypePtr)> {
-        let effective_kind = column.kind;
-
-        let (encoder, physical_type, logical_type) = match effective_kind {
-            ColumnKind::Character => (
+        let (encoder, physical_type, logical_type) = match column_type {
+            ParquetColumnType::Utf8 => (
                 ColumnValueEncoder::Utf8,
                 PhysicalType::BYTE_ARRAY,
                 Some(LogicalType::String),
             ),
-            ColumnKind::Numeric(NumericKind::Double) => {
-                (ColumnValueEncoder::Double, PhysicalType::DOUBLE, None)
+            ParquetColumnType::Binary => {
+                (ColumnValueEncoder::Binary, PhysicalType::BYTE_ARRAY, None)
             }
-            ColumnKind::Numeric(NumericKind::Date) => (
+            ParquetColumnType::Double => (ColumnValueEncoder::Double, PhysicalType::DOUBLE, None),
+            ParquetColumnType::Date => (
                 ColumnValueEncoder::Date,
                 PhysicalType::INT32,
                 Some(LogicalType::Date),
             ),
-            ColumnKind::Numeric(NumericKind::DateTime) => (
+            ParquetColumnType::Int32Days => (ColumnValueEncoder::Date, PhysicalType::INT32, None),
+            ParquetColumnType::Timestamp => (
                 ColumnValueEncoder::DateTime,
                 PhysicalType::INT64,
                 Some(LogicalType::Timestamp {
@@ -74,18 +107,25 @@ impl ColumnPlan {
                     unit: TimeUnit::MICROS,
                 }),
             ),
-            ColumnKind::Numeric(NumericKind::Time) => (
+            ParquetColumnType::Time => (
                 ColumnValueEncoder::Time,
                 PhysicalType::INT64,
                 Some(LogicalType::Time {
                     is_adjusted_to_u_t_c: true,
-                    unit: TimeUnit::MICROS,
+                    unit: match time_precision {
+                        TimePrecision::Micros => TimeUnit::MICROS,
+                        TimePrecision::Nanos => TimeUnit::NANOS,
+                    },
                 }),
             ),
         };
 
         let field = Type::primitive_type_builder(&variable.name, physical_type)
-            .with_repetition(Repetition::OPTIONAL)
+            .with_repetition(if required {
+                Repetition::REQUIRED
+            } else {
+                Repetition::OPTIONAL
+            })
             .with_logical_type(logical_type)
             .build()?;
 
@@ -93,6 +133,7 @@ impl ColumnPlan {
             name: variable.name.clone(),
             encoder,
             def_levels: Vec::new(),
+            rep_levels: None,
             def_bitmap: Vec::new(),
             values: match encoder {
                 ColumnValueEncoder::Double => ColumnValues::Double(Vec::new()),
@@ -100,20 +141,64 @@ impl ColumnPlan {
                 ColumnValueEncoder::DateTime | ColumnValueEncoder::Time => {
                     ColumnValues::Int64(Vec::new())
                 }
-                ColumnValueEncoder::Utf8 => ColumnValues::ByteArray(Vec::new()),
+                ColumnValueEncoder::Utf8 | ColumnValueEncoder::Binary => {
+                    ColumnValues::ByteArray(Vec::new())
+                }
             },
             utf8_scratch: match encoder {
-                ColumnValueEncoder::Utf8 => Some(Utf8Scratch::new()),
+                ColumnValueEncoder::Utf8 | ColumnValueEncoder::Binary => Some(Utf8Scratch::new()),
                 _ => None,
             },
             utf8_inlines: Vec::new(),
             lenient_dates,
+            rounding,
+            time_precision,
+            required,
             warned_invalid_value: false,
-            source_path: source_path.map(str::to_owned),
+            logger,
         };
         Ok((plan, Arc::new(field)))
     }
 
+    /// Turns a plan built for the first member of a repeated group into a
+    /// plan for the whole group, written as a three-level Parquet `LIST`:
+    ///
+    /// ```text
+    /// optional group <name> (LIST) {
+    ///   repeated group list {
+    ///     optional <type> element;
+    ///   }
+    /// }
+    /// ```
+    pub(super) fn into_list(mut self, name: &str, element: TypePtr) -> Result<(Self, TypePtr)> {
+        let list = Type::group_type_builder("list")
+            .with_repetition(Repetition::REPEATED)
+            .with_fields(vec![element])
+            .build()?;
+        let field = Type::group_type_builder(name)
+            .with_repetition(Repetition::OPTIONAL)
+            .with_logical_type(Some(LogicalType::List))
+            .with_fields(vec![Arc::new(list)])
+            .build()?;
+        name.clone_into(&mut self.name);
+        self.rep_levels = Some(Vec::new());
+        self.required = false;
+        Ok((self, Arc::new(field)))
+    }
+
+    /// Appends one element of a list row. `first` starts a new row; a
+    /// missing value becomes a null element rather than a null list.
+    pub(super) fn push_list_element(&mut self, value: &CellValue<'_>, first: bool) -> Result<()> {
+        self.push(value)?;
+        if let Some(level) = self.def_levels.last_mut() {
+            *level += LIST_ELEMENT_DEF_OFFSET;
+        }
+        if let Some(rep_levels) = &mut self.rep_levels {
+            rep_levels.push(i16::from(!first));
+        }
+        Ok(())
+    }
+
     pub(super) fn reserve_capacity(&mut self, capacity: usize) {
         self.def_levels.reserve(capacity);
         match &mut self.values {
@@ -138,7 +223,7 @@ impl ColumnPlan {
             ColumnValueEncoder::Date => self.push_date(value)?,
             ColumnValueEncoder::DateTime => self.push_datetime(value)?,
             ColumnValueEncoder::Time => self.push_time(value)?,
-            ColumnValueEncoder::Utf8 => {
+            ColumnValueEncoder::Utf8 | ColumnValueEncoder::Binary => {
                 let coerced = self.coerce_utf8(value);
                 match &mut self.values {
                     ColumnValues::ByteArray(values) => {
@@ -219,13 +304,8 @@ impl ColumnPlan {
         if self.warned_invalid_value {
             return;
         }
-        let prefix = self
-            .source_path
-            .as_deref()
-            .map(|p| format!("{p}: "))
-            .unwrap_or_default();
-        log_warn(&format!(
-            "{prefix}column '{}' contains non-{kind} value; written as null (use --strict-dates to fail)",
+        self.logger.warn(&format!(
+            "column '{}' contains non-{kind} value; written as null (use --strict-dates to fail)",
             self.name
         ));
         self.warned_invalid_value = true;
@@ -238,12 +318,26 @@ impl ColumnPlan {
         match (&mut self.values, self.encoder) {
             (ColumnValues::Double(values), ColumnValueEncoder::Double) => {
                 let writer = column_writer.typed::<parquet::data_type::DoubleType>();
-                writer.write_batch(values, Some(&self.def_levels), None)?;
+                writer.write_batch(
+                    values,
+                    write_def_levels(
+                        self.required.then_some(self.name.as_str()),
+                        &self.def_levels,
+                    )?,
+                    self.rep_levels.as_deref(),
+                )?;
                 values.clear();
             }
             (ColumnValues::Int32(values), ColumnValueEncoder::Date) => {
                 let writer = column_writer.typed::<parquet::data_type::Int32Type>();
-                writer.write_batch(values, Some(&self.def_levels), None)?;
+                writer.write_batch(
+                    values,
+                    write_def_levels(
+                        self.required.then_some(self.name.as_str()),
+                        &self.def_levels,
+                    )?,
+                    self.rep_levels.as_deref(),
+                )?;
                 values.clear();
             }
             (
@@ -251,12 +345,29 @@ impl ColumnPlan {
                 ColumnValueEncoder::DateTime | ColumnValueEncoder::Time,
             ) => {
                 let writer = column_writer.typed::<parquet::data_type::Int64Type>();
-                writer.write_batch(values, Some(&self.def_levels), None)?;
+                writer.write_batch(
+                    values,
+                    write_def_levels(
+                        self.required.then_some(self.name.as_str()),
+                        &self.def_levels,
+                    )?,
+                    self.rep_levels.as_deref(),
+                )?;
                 values.clear();
             }
-            (ColumnValues::ByteArray(values), ColumnValueEncoder::Utf8) => {
+            (
+                ColumnValues::ByteArray(values),
+                ColumnValueEncoder::Utf8 | ColumnValueEncoder::Binary,
+            ) => {
                 let writer = column_writer.typed::<parquet::data_type::ByteArrayType>();
-                writer.write_batch(values, Some(&self.def_levels), None)?;
+                writer.write_batch(
+                    values,
+                    write_def_levels(
+                        self.required.then_some(self.name.as_str()),
+                        &self.def_levels,
+                    )?,
+                    self.rep_levels.as_deref(),
+                )?;
                 values.clear();
             }
             _ => {
@@ -266,6 +377,9 @@ impl ColumnPlan {
             }
         }
         self.def_levels.clear();
+        if let Some(rep_levels) = &mut self.rep_levels {
+            rep_levels.clear();
+        }
         column_writer.close()?;
         Ok(())
     }
@@ -289,7 +403,7 @@ impl ColumnPlan {
                 const MAX_SAFE: i64 = 9_007_199_254_740_992; // 2^53
                 const MIN_SAFE: i64 = -9_007_199_254_740_992;
                 if *v < MIN_SAFE || *v > MAX_SAFE {
-                    return Err(Error::InvalidMetadata {
+                    return Err(Error::Parquet {
                         details: Cow::Owned(format!(
                             "column '{}' int64 value {} cannot be represented exactly as f64",
                             self.name, v
@@ -297,7 +411,7 @@ impl ColumnPlan {
                     });
                 }
                 let text = v.to_string();
-                let parsed = text.parse::<f64>().map_err(|_| Error::InvalidMetadata {
+                let parsed = text.parse::<f64>().map_err(|_| Error::Parquet {
                     details: Cow::Owned(format!(
                         "column '{}' int64 value '{text}' cannot be parsed as f64",
                         self.name
@@ -306,17 +420,16 @@ impl ColumnPlan {
                 Ok(Some(parsed))
             }
             CellValue::DateTime(dt) => Ok(Some(datetime_to_sas_seconds(dt))),
-            CellValue::Date(dt) => Ok(Some(datetime_to_sas_days(dt))),
-            CellValue::Time(duration) => Ok(Some(time_to_sas_seconds(duration))),
+            CellValue::Date(dt) => Ok(Some(date_to_sas_days(dt))),
+            CellValue::Time(duration) => Ok(Some(time_to_sas_seconds(*duration))),
             CellValue::NumericString(text) | CellValue::Str(text) => self.parse_f64(text.as_ref()),
             CellValue::Bytes(bytes) => {
-                let text =
-                    std::str::from_utf8(bytes.as_ref()).map_err(|_| Error::InvalidMetadata {
-                        details: Cow::Owned(format!(
-                            "column '{}' received non-UTF8 bytes for numeric sink",
-                            self.name
-                        )),
-                    })?;
+                let text = std::str::from_utf8(bytes.as_ref()).map_err(|_| Error::Parquet {
+                    details: Cow::Owned(format!(
+                        "column '{}' received non-UTF8 bytes for numeric sink",
+                        self.name
+                    )),
+                })?;
                 self.parse_f64(text)
             }
         }
@@ -328,7 +441,7 @@ impl ColumnPlan {
             CellValue::Date(datetime) => {
                 let seconds = datetime.unix_timestamp();
                 let days = seconds.div_euclid(SECONDS_PER_DAY);
-                let days = i32::try_from(days).map_err(|_| Error::InvalidMetadata {
+                let days = i32::try_from(days).map_err(|_| Error::Parquet {
                     details: Cow::Owned(format!(
                         "column '{}' contains date outside Parquet range",
                         self.name
@@ -349,9 +462,8 @@ impl ColumnPlan {
         match value {
             CellValue::Missing(_) => Ok(None),
             CellValue::DateTime(datetime) => {
-                let nanos = datetime.unix_timestamp_nanos();
-                let micros = nanos.div_euclid(1_000);
-                let micros = i64::try_from(micros).map_err(|_| Error::InvalidMetadata {
+                let micros = self.rounding.datetime_micros(*datetime);
+                let micros = i64::try_from(micros).map_err(|_| Error::Parquet {
                     details: Cow::Owned(format!(
                         "column '{}' contains timestamp outside Parquet range",
                         self.name
@@ -364,26 +476,29 @@ impl ColumnPlan {
     }
 
     fn coerce_time(&self, value: &CellValue<'_>) -> Result<Option<i64>> {
-        match value {
-            CellValue::Missing(_) => Ok(None),
-            CellValue::Time(duration) => {
-                let micros = duration.whole_microseconds();
-                let micros = i64::try_from(micros).map_err(|_| Error::InvalidMetadata {
+        let duration = match value {
+            CellValue::Missing(_) => return Ok(None),
+            CellValue::Time(duration) => *duration,
+            CellValue::Float(seconds) if !seconds.is_finite() => return Ok(None),
+            CellValue::Float(seconds) => {
+                sas_seconds_to_time(*seconds).ok_or_else(|| Error::Parquet {
                     details: Cow::Owned(format!(
-                        "column '{}' contains time outside Parquet range",
+                        "column '{}' contains time outside supported range",
                         self.name
                     )),
-                })?;
-                Ok(Some(micros))
+                })?
             }
-            other => self.coerce_seconds_to_micros(other, "time"),
-        }
+            CellValue::Int32(seconds) => Duration::seconds(i64::from(*seconds)),
+            CellValue::Int64(seconds) => Duration::seconds(*seconds),
+            other => return Err(self.type_mismatch_error("time", other)),
+        };
+        time_ticks(&self.name, duration, self.rounding, self.time_precision).map(Some)
     }
 
     fn coerce_seconds_to_micros(&self, value: &CellValue<'_>, kind: &str) -> Result<Option<i64>> {
         match value {
             CellValue::Float(seconds) => {
-                Self::float_seconds_to_micros(self.name.as_str(), *seconds)
+                Self::float_seconds_to_micros(self.name.as_str(), *seconds, self.rounding)
             }
             CellValue::Int32(seconds) => Ok(Some(i64::from(*seconds) * 1_000_000)),
             CellValue::Int64(seconds) => Ok(Some(*seconds * 1_000_000)),
@@ -462,7 +577,7 @@ impl ColumnPlan {
         trimmed
             .parse::<f64>()
             .map(Some)
-            .map_err(|_| Error::InvalidMetadata {
+            .map_err(|_| Error::Parquet {
                 details: Cow::Owned(format!(
                     "column '{}' value '{trimmed}' cannot be parsed as f64",
                     self.name
@@ -471,7 +586,7 @@ impl ColumnPlan {
     }
 
     fn type_mismatch_error(&self, expected: &str, value: &CellValue<'_>) -> Error {
-        Error::InvalidMetadata {
+        Error::Parquet {
             details: Cow::Owned(format!(
                 "column '{}' expected {expected} value but received {value:?}",
                 self.name
@@ -484,59 +599,56 @@ impl ColumnPlan {
             return Ok(None);
         }
         let rounded = days.trunc();
-        let dt = sas_days_to_datetime(rounded).ok_or_else(|| Error::InvalidMetadata {
+        let dt = sas_days_to_date(rounded).ok_or_else(|| Error::Parquet {
             details: Cow::Owned(format!(
                 "column '{column_name}' contains date outside supported range"
             )),
         })?;
         let seconds = dt.unix_timestamp();
         let day = seconds.div_euclid(SECONDS_PER_DAY);
-        i32::try_from(day)
-            .map(Some)
-            .map_err(|_| Error::InvalidMetadata {
-                details: Cow::Owned(format!(
-                    "column '{column_name}' contains date outside Parquet range"
-                )),
-            })
+        i32::try_from(day).map(Some).map_err(|_| Error::Parquet {
+            details: Cow::Owned(format!(
+                "column '{column_name}' contains date outside Parquet range"
+            )),
+        })
     }
 
-    fn float_seconds_to_micros(column_name: &str, seconds: f64) -> Result<Option<i64>> {
+    fn float_seconds_to_micros(
+        column_name: &str,
+        seconds: f64,
+        rounding: TimestampRounding,
+    ) -> Result<Option<i64>> {
         if !seconds.is_finite() {
             return Ok(None);
         }
-        let dt = sas_seconds_to_datetime(seconds).ok_or_else(|| Error::InvalidMetadata {
+        let dt = sas_seconds_to_datetime(seconds).ok_or_else(|| Error::Parquet {
             details: Cow::Owned(format!(
                 "column '{column_name}' contains timestamp outside supported range"
             )),
         })?;
-        let micros = dt.unix_timestamp_nanos().div_euclid(1_000);
-        i64::try_from(micros)
-            .map(Some)
-            .map_err(|_| Error::InvalidMetadata {
-                details: Cow::Owned(format!(
-                    "column '{column_name}' contains timestamp outside Parquet range"
-                )),
-            })
+        let micros = rounding.datetime_micros(dt);
+        i64::try_from(micros).map(Some).map_err(|_| Error::Parquet {
+            details: Cow::Owned(format!(
+                "column '{column_name}' contains timestamp outside Parquet range"
+            )),
+        })
     }
 }
 
-fn sas_epoch() -> OffsetDateTime {
-    PrimitiveDateTime::new(
-        Date::from_calendar_date(1960, Month::January, 1).expect("valid SAS epoch"),
-        Time::MIDNIGHT,
-    )
-    .assume_utc()
-}
-
-fn datetime_to_sas_seconds(datetime: &OffsetDateTime) -> f64 {
-    (*datetime - sas_epoch()).as_seconds_f64()
-}
-
-fn datetime_to_sas_days(datetime: &OffsetDateTime) -> f64 {
-    const SECONDS_PER_DAY_F64: f64 = 86_400.0;
-    datetime_to_sas_seconds(datetime) / SECONDS_PER_DAY_F64
-}
-
-const fn time_to_sas_seconds(duration: &Duration) -> f64 {
-    duration.as_seconds_f64()
+/// Converts a time of day to the integer stored for `precision`.
+pub(super) fn time_ticks(
+    column_name: &str,
+    duration: Duration,
+    rounding: TimestampRounding,
+    precision: TimePrecision,
+) -> Result<i64> {
+    let ticks = match precision {
+        TimePrecision::Micros => rounding.duration_micros(duration),
+        TimePrecision::Nanos => duration.whole_nanoseconds(),
+    };
+    i64::try_from(ticks).map_err(|_| Error::Parquet {
+        details: Cow::Owned(format!(
+            "column '{column_name}' contains time outside Parquet range"
+        )),
+    })
 }
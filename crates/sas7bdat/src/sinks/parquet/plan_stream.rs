@@ -1,14 +1,16 @@
 use super::{
     constants::SECONDS_PER_DAY,
-    plan::{ColumnPlan, ColumnValueEncoder, ColumnValues},
-    stream::{StreamNumericCtx, expand_bitmap_to_def_levels, prepare_def_bitmap, stream_numeric},
+    plan::{ColumnPlan, ColumnValueEncoder, ColumnValues, time_ticks},
+    sink::TimePrecision,
+    stream::{
+        StreamNumericCtx, expand_bitmap_to_def_levels, prepare_def_bitmap, stream_numeric,
+        write_def_levels,
+    },
 };
 use crate::{
     error::{Error, Result},
-    parser::{
-        ColumnarColumn, MaterializedUtf8Column, StagedUtf8Value, sas_days_to_datetime,
-        sas_seconds_to_datetime, sas_seconds_to_time,
-    },
+    parser::{ColumnarColumn, MaterializedUtf8Column, StagedUtf8Value, TimestampRounding},
+    time_conv::{sas_days_to_date, sas_seconds_to_datetime, sas_seconds_to_time},
 };
 use parquet::{
     data_type::{ByteArrayType, DataType, DoubleType, Int32Type, Int64Type},
@@ -24,75 +26,66 @@ where
 }
 
 fn stream_numeric_typed<T: DataType, S: NumericColumnSource>(
-    def_levels: &mut Vec<i16>,
-    def_bitmap: &mut Vec<u8>,
+    mut ctx: StreamNumericCtx<'_, T::T>,
     column_writer: &mut SerializedColumnWriter<'_>,
     column: &S,
-    values: &mut Vec<T::T>,
-    chunk: usize,
     convert: impl FnMut(u64) -> Result<T::T>,
 ) -> Result<()> {
     let writer = column_writer.typed::<T>();
-    let mut ctx = StreamNumericCtx {
-        def_levels,
-        def_bitmap,
-        values,
-        chunk,
-    };
     stream_numeric(
         &mut ctx,
         column.len(),
         |start, len| column.iter_numeric_bits_range(start, len),
         convert,
-        |vals, defs| writer.write_batch(vals, Some(defs), None),
+        |vals, defs| writer.write_batch(vals, defs, None),
     )
 }
 
 // Helper functions for data type conversions
 fn convert_date(bits: u64, column_name: &str) -> Result<i32> {
     let days = f64::from_bits(bits);
-    let datetime = sas_days_to_datetime(days).ok_or_else(|| Error::InvalidMetadata {
+    let datetime = sas_days_to_date(days).ok_or_else(|| Error::Parquet {
         details: Cow::Owned(format!(
             "column '{column_name}' contains date outside supported range"
         )),
     })?;
     let seconds = datetime.unix_timestamp();
     let day = seconds.div_euclid(SECONDS_PER_DAY);
-    i32::try_from(day).map_err(|_| Error::InvalidMetadata {
+    i32::try_from(day).map_err(|_| Error::Parquet {
         details: Cow::Owned(format!(
             "column '{column_name}' contains date outside Parquet range"
         )),
     })
 }
 
-fn convert_datetime(bits: u64, column_name: &str) -> Result<i64> {
+fn convert_datetime(bits: u64, column_name: &str, rounding: TimestampRounding) -> Result<i64> {
     let seconds = f64::from_bits(bits);
-    let datetime = sas_seconds_to_datetime(seconds).ok_or_else(|| Error::InvalidMetadata {
+    let datetime = sas_seconds_to_datetime(seconds).ok_or_else(|| Error::Parquet {
         details: Cow::Owned(format!(
             "column '{column_name}' contains timestamp outside supported range"
         )),
     })?;
-    let micros = datetime.unix_timestamp_nanos().div_euclid(1_000);
-    i64::try_from(micros).map_err(|_| Error::InvalidMetadata {
+    let micros = rounding.datetime_micros(datetime);
+    i64::try_from(micros).map_err(|_| Error::Parquet {
         details: Cow::Owned(format!(
             "column '{column_name}' contains timestamp outside Parquet range"
         )),
     })
 }
 
-fn convert_time(bits: u64, column_name: &str) -> Result<i64> {
+fn convert_time(
+    bits: u64,
+    column_name: &str,
+    rounding: TimestampRounding,
+    precision: TimePrecision,
+) -> Result<i64> {
     let seconds = f64::from_bits(bits);
-    let duration = sas_seconds_to_time(seconds).ok_or_else(|| Error::InvalidMetadata {
+    let duration = sas_seconds_to_time(seconds).ok_or_else(|| Error::Parquet {
         details: Cow::Owned(format!(
             "column '{column_name}' contains time outside supported range"
         )),
     })?;
-    let micros = duration.whole_microseconds();
-    i64::try_from(micros).map_err(|_| Error::InvalidMetadata {
-        details: Cow::Owned(format!(
-            "column '{column_name}' contains time outside Parquet range"
-        )),
-    })
+    time_ticks(column_name, duration, rounding, precision)
 }
 
 // Trait to abstract over different column types
@@ -129,6 +122,9 @@ impl ColumnPlan {
         encoder_name: &str,
     ) -> Result<()> {
         let column_name = self.name.clone();
+        let rounding = self.rounding;
+        let time_precision = self.time_precision;
+        let required = self.required.then_some(column_name.as_str());
         let def_levels = &mut self.def_levels;
         let def_bitmap = &mut self.def_bitmap;
 
@@ -136,12 +132,15 @@ impl ColumnPlan {
             (ColumnValues::Double(values), ColumnValueEncoder::Double) => {
                 measure_encoder(encoder_name, || {
                     stream_numeric_typed::<DoubleType, _>(
-                        def_levels,
-                        def_bitmap,
+                        StreamNumericCtx {
+                            def_levels,
+                            def_bitmap,
+                            values,
+                            chunk,
+                            required,
+                        },
                         &mut column_writer,
                         column,
-                        values,
-                        chunk,
                         |bits| Ok(f64::from_bits(bits)),
                     )
                 })
@@ -149,12 +148,15 @@ impl ColumnPlan {
             (ColumnValues::Int32(values), ColumnValueEncoder::Date) => {
                 measure_encoder(encoder_name, || {
                     stream_numeric_typed::<Int32Type, _>(
-                        def_levels,
-                        def_bitmap,
+                        StreamNumericCtx {
+                            def_levels,
+                            def_bitmap,
+                            values,
+                            chunk,
+                            required,
+                        },
                         &mut column_writer,
                         column,
-                        values,
-                        chunk,
                         |bits| convert_date(bits, &column_name),
                     )
                 })
@@ -162,26 +164,32 @@ impl ColumnPlan {
             (ColumnValues::Int64(values), ColumnValueEncoder::DateTime) => {
                 measure_encoder(encoder_name, || {
                     stream_numeric_typed::<Int64Type, _>(
-                        def_levels,
-                        def_bitmap,
+                        StreamNumericCtx {
+                            def_levels,
+                            def_bitmap,
+                            values,
+                            chunk,
+                            required,
+                        },
                         &mut column_writer,
                         column,
-                        values,
-                        chunk,
-                        |bits| convert_datetime(bits, &column_name),
+                        |bits| convert_datetime(bits, &column_name, rounding),
                     )
                 })
             }
             (ColumnValues::Int64(values), ColumnValueEncoder::Time) => {
                 measure_encoder(encoder_name, || {
                     stream_numeric_typed::<Int64Type, _>(
-                        def_levels,
-                        def_bitmap,
+                        StreamNumericCtx {
+                            def_levels,
+                            def_bitmap,
+                            values,
+                            chunk,
+                            required,
+                        },
                         &mut column_writer,
                         column,
-                        values,
-                        chunk,
-                        |bits| convert_time(bits, &column_name),
+                        |bits| convert_time(bits, &column_name, rounding, time_precision),
                     )
                 })
             }
@@ -224,7 +232,7 @@ impl ColumnPlan {
                 values.reserve(column.len());
                 for maybe_bits in column.iter_numeric_bits() {
                     if let Some(bits) = maybe_bits {
-                        let micros = convert_datetime(bits, &self.name)?;
+                        let micros = convert_datetime(bits, &self.name, self.rounding)?;
                         self.def_levels.push(1);
                         values.push(micros);
                     } else {
@@ -236,9 +244,10 @@ impl ColumnPlan {
                 values.reserve(column.len());
                 for maybe_bits in column.iter_numeric_bits() {
                     if let Some(bits) = maybe_bits {
-                        let micros = convert_time(bits, &self.name)?;
+                        let ticks =
+                            convert_time(bits, &self.name, self.rounding, self.time_precision)?;
                         self.def_levels.push(1);
-                        values.push(micros);
+                        values.push(ticks);
                     } else {
                         self.def_levels.push(0);
                     }
@@ -249,7 +258,18 @@ impl ColumnPlan {
                 for maybe_text in column.iter_strings() {
                     if let Some(text) = maybe_text {
                         self.def_levels.push(1);
-                        values.push(parquet::data_type::ByteArray::from(text.as_ref()));
+                        values.push(parquet::data_type::ByteArray::from(&*text));
+                    } else {
+                        self.def_levels.push(0);
+                    }
+                }
+            }
+            (ColumnValues::ByteArray(values), ColumnValueEncoder::Binary) => {
+                values.reserve(column.len());
+                for maybe_bytes in column.iter_bytes_range(0, column.len()) {
+                    if let Some(bytes) = maybe_bytes {
+                        self.def_levels.push(1);
+                        values.push(parquet::data_type::ByteArray::from(bytes.to_vec()));
                     } else {
                         self.def_levels.push(0);
                     }
@@ -287,18 +307,68 @@ impl ColumnPlan {
                         let take = (total - processed).min(chunk);
                         prepare_def_bitmap(&mut self.def_bitmap, take);
                         values.clear();
-                        for (idx, maybe_text) in
-                            column.iter_strings_range(processed, take).enumerate()
-                        {
-                            if let Some(text) = maybe_text {
+                        let mut spill = std::mem::take(&mut scratch.spill);
+                        for idx in 0..take {
+                            if let Some(text) = column.str_with(processed + idx, &mut spill) {
                                 let byte = idx >> 3;
                                 let bit = idx & 7;
                                 self.def_bitmap[byte] |= 1 << bit;
-                                values.push(scratch.intern_str(text.as_ref()));
+                                values.push(scratch.intern_str(text));
                             }
                         }
+                        scratch.spill = spill;
                         expand_bitmap_to_def_levels(&mut self.def_levels, &self.def_bitmap, take);
-                        writer.write_batch(values, Some(&self.def_levels), None)?;
+                        writer.write_batch(
+                            values,
+                            write_def_levels(
+                                self.required.then_some(self.name.as_str()),
+                                &self.def_levels,
+                            )?,
+                            None,
+                        )?;
+                        processed += take;
+                    }
+                }
+                Ok(())
+            })?;
+            column_writer.close()?;
+            return Ok(());
+        }
+
+        if matches!(
+            (&self.values, self.encoder),
+            (ColumnValues::ByteArray(_), ColumnValueEncoder::Binary)
+        ) {
+            measure_encoder("parquet::stream_columnar::binary", || {
+                let writer = column_writer.typed::<ByteArrayType>();
+                let total = column.len();
+                let mut processed = 0;
+                let scratch = self
+                    .utf8_scratch
+                    .as_mut()
+                    .expect("utf8 scratch missing for binary encoder");
+
+                if let ColumnValues::ByteArray(values) = &mut self.values {
+                    while processed < total {
+                        let take = (total - processed).min(chunk);
+                        self.def_levels.clear();
+                        values.clear();
+                        for maybe_bytes in column.iter_bytes_range(processed, take) {
+                            if let Some(bytes) = maybe_bytes {
+                                self.def_levels.push(1);
+                                values.push(scratch.intern_slice(bytes));
+                            } else {
+                                self.def_levels.push(0);
+                            }
+                        }
+                        writer.write_batch(
+                            values,
+                            write_def_levels(
+                                self.required.then_some(self.name.as_str()),
+                                &self.def_levels,
+                            )?,
+                            None,
+                        )?;
                         processed += take;
                     }
                 }
@@ -340,7 +410,7 @@ impl ColumnPlan {
                     match value {
                         StagedUtf8Value::Dictionary(id) => {
                             let handle = dictionary_handles.get(*id as usize).ok_or_else(|| {
-                                Error::InvalidMetadata {
+                                Error::Parquet {
                                     details: Cow::Owned(format!(
                                         "dictionary index {id} exceeds staged dictionary for column '{}'",
                                         self.name
@@ -359,14 +429,21 @@ impl ColumnPlan {
 
                 measure_encoder("parquet::stream_columnar::utf8_staged", || {
                     let writer = column_writer.typed::<ByteArrayType>();
-                    writer.write_batch(values, Some(&self.def_levels), None)?;
+                    writer.write_batch(
+                        values,
+                        write_def_levels(
+                            self.required.then_some(self.name.as_str()),
+                            &self.def_levels,
+                        )?,
+                        None,
+                    )?;
                     Ok(())
                 })?;
 
                 column_writer.close()?;
                 Ok(())
             }
-            _ => Err(Error::InvalidMetadata {
+            _ => Err(Error::Parquet {
                 details: Cow::from("materialized UTF-8 column type mismatch"),
             }),
         }
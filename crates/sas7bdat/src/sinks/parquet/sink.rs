@@ -3,21 +3,41 @@ use super::{
         DEFAULT_ROW_GROUP_SIZE, DEFAULT_TARGET_ROW_GROUP_BYTES, MAX_AUTO_ROW_GROUP_ROWS,
         MIN_AUTO_ROW_GROUP_ROWS,
     },
-    plan::ColumnPlan,
+    mapping::{ParquetColumnType, TypeMapper},
+    metadata::sas_key_value_metadata,
+    plan::{ColumnPlan, ColumnValueEncoder},
 };
 use crate::{
-    cell::CellValue,
+    cell::{CellValue, MissingValue},
+    dataset::{RepeatedGroup, Variable},
     error::{Error, Result},
-    parser::ColumnarBatch,
-    sinks::{ColumnarSink, RowSink, SinkContext, validate_sink_begin},
+    parser::{ColumnInfo, ColumnarBatch, StreamingRow, TimestampRounding},
+    sinks::{
+        ColumnarSink, RowSink, SinkContext, repeated_group_members, resolve_repeated_groups,
+        validate_sink_begin,
+    },
+    telemetry::{self, Stopwatch},
 };
+pub use parquet::{basic::Compression as Codec, file::properties::EnabledStatistics};
 use parquet::{
-    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    file::{metadata::KeyValue, properties::WriterProperties, writer::SerializedFileWriter},
     schema::types::{Type, TypePtr},
 };
 use std::{borrow::Cow, io::Write, sync::Arc};
 
+/// Unit used for the Parquet `TIME` logical type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimePrecision {
+    /// Microseconds since midnight, readable by every Parquet implementation.
+    #[default]
+    Micros,
+    /// Nanoseconds since midnight. Requires a reader that understands the
+    /// `TIME(NANOS)` annotation (Parquet format 2.6 or later).
+    Nanos,
+}
+
 /// Writes decoded SAS rows into a Parquet file.
+#[allow(clippy::struct_excessive_bools)]
 pub struct ParquetSink<W: Write + Send> {
     output: Option<W>,
     writer: Option<SerializedFileWriter<W>>,
@@ -28,6 +48,22 @@ pub struct ParquetSink<W: Write + Send> {
     target_row_group_bytes: usize,
     streaming_columnar: bool,
     lenient_dates: bool,
+    timestamp_rounding: TimestampRounding,
+    time_precision: TimePrecision,
+    required_columns: Vec<String>,
+    repeated_groups: Vec<RepeatedGroup>,
+    /// Input column positions feeding each plan; list plans have several.
+    sources: Vec<Vec<usize>>,
+    input_columns: usize,
+    sas_metadata: bool,
+    type_mapper: Option<Arc<dyn TypeMapper>>,
+    /// Set when a mapped type needs values the columnar fast paths cannot
+    /// produce, so batches go through the row path instead.
+    remapped: bool,
+    writer_properties: Option<WriterProperties>,
+    compression: Option<Codec>,
+    dictionary: Option<bool>,
+    statistics: Option<EnabledStatistics>,
 }
 
 impl<W: Write + Send> ParquetSink<W> {
@@ -44,6 +80,19 @@ impl<W: Write + Send> ParquetSink<W> {
             target_row_group_bytes: DEFAULT_TARGET_ROW_GROUP_BYTES,
             streaming_columnar: false,
             lenient_dates: true,
+            timestamp_rounding: TimestampRounding::Truncate,
+            time_precision: TimePrecision::Micros,
+            required_columns: Vec::new(),
+            repeated_groups: Vec::new(),
+            sources: Vec::new(),
+            input_columns: 0,
+            sas_metadata: true,
+            type_mapper: None,
+            remapped: false,
+            writer_properties: None,
+            compression: None,
+            dictionary: None,
+            statistics: None,
         }
     }
 
@@ -77,6 +126,152 @@ impl<W: Write + Send> ParquetSink<W> {
         self
     }
 
+    /// Selects how sub-microsecond precision is dropped when writing
+    /// timestamp and time columns.
+    #[must_use]
+    pub const fn with_timestamp_rounding(mut self, rounding: TimestampRounding) -> Self {
+        self.timestamp_rounding = rounding;
+        self
+    }
+
+    /// Selects the unit written for time columns. Nanosecond precision keeps
+    /// sub-microsecond fractions that the default microsecond unit rounds
+    /// away using the configured [`TimestampRounding`].
+    #[must_use]
+    pub const fn with_time_precision(mut self, precision: TimePrecision) -> Self {
+        self.time_precision = precision;
+        self
+    }
+
+    /// Controls whether per-column SAS metadata (label, format, informat,
+    /// type and value-label set) is stored as JSON in the file footer under
+    /// [`SAS_COLUMNS_KEY`](super::SAS_COLUMNS_KEY). Enabled by default.
+    #[must_use]
+    pub const fn with_sas_metadata(mut self, enabled: bool) -> Self {
+        self.sas_metadata = enabled;
+        self
+    }
+
+    /// Marks columns as `REQUIRED` in the Parquet schema instead of
+    /// `OPTIONAL`.
+    ///
+    /// Names are matched case-insensitively. Writing fails if a named column
+    /// does not exist or if a missing value is encountered in one of them.
+    #[must_use]
+    pub fn with_required_columns<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.required_columns = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Writes each group's member columns as a single `LIST` column named
+    /// after the group, placed where its first member would have been.
+    ///
+    /// Groups can be found with
+    /// [`detect_repeated_groups`](crate::dataset::detect_repeated_groups).
+    /// Members must share a column type. Every row produces a list with one
+    /// element per member; missing values become null elements. Batches are
+    /// written row by row while groups are configured, so streaming
+    /// columnar mode has no effect.
+    #[must_use]
+    pub fn with_repeated_groups<I>(mut self, groups: I) -> Self
+    where
+        I: IntoIterator<Item = RepeatedGroup>,
+    {
+        self.repeated_groups = groups.into_iter().collect();
+        self
+    }
+
+    /// Lets `mapper` choose the Parquet type of each column instead of the
+    /// default mapping from [`ColumnKind`](crate::parser::ColumnKind).
+    /// Repeated groups are mapped through their first member.
+    #[must_use]
+    pub fn with_type_mapper(mut self, mapper: impl TypeMapper + 'static) -> Self {
+        self.type_mapper = Some(Arc::new(mapper));
+        self
+    }
+
+    /// Compresses every column with `codec`, for example
+    /// `Codec::ZSTD(ZstdLevel::try_new(3)?)`. Columns are uncompressed by
+    /// default.
+    #[must_use]
+    pub const fn with_compression(mut self, codec: Codec) -> Self {
+        self.compression = Some(codec);
+        self
+    }
+
+    /// Controls dictionary encoding of every column. Enabled by default.
+    #[must_use]
+    pub const fn with_dictionary(mut self, enabled: bool) -> Self {
+        self.dictionary = Some(enabled);
+        self
+    }
+
+    /// Selects the column statistics written for query engines to prune
+    /// with: none, per column chunk, or per page as well (the default).
+    #[must_use]
+    pub const fn with_statistics(mut self, level: EnabledStatistics) -> Self {
+        self.statistics = Some(level);
+        self
+    }
+
+    /// Starts from `properties` instead of the Parquet defaults, for settings
+    /// without a method of their own such as per-column codecs or bloom
+    /// filters. [`with_compression`](Self::with_compression),
+    /// [`with_dictionary`](Self::with_dictionary) and
+    /// [`with_statistics`](Self::with_statistics) still apply on top, and the
+    /// SAS footer metadata is added to any key-value metadata `properties`
+    /// carries.
+    #[must_use]
+    pub fn with_writer_properties(mut self, properties: WriterProperties) -> Self {
+        self.writer_properties = Some(properties);
+        self
+    }
+
+    fn writer_properties(&mut self, sas_metadata: Option<Vec<KeyValue>>) -> WriterProperties {
+        let base = self.writer_properties.take().unwrap_or_default();
+        let mut key_value_metadata = base.key_value_metadata().cloned();
+        if let Some(entries) = sas_metadata {
+            key_value_metadata
+                .get_or_insert_with(Vec::new)
+                .extend(entries);
+        }
+        let mut builder = base
+            .into_builder()
+            .set_key_value_metadata(key_value_metadata);
+        if let Some(codec) = self.compression {
+            builder = builder.set_compression(codec);
+        }
+        if let Some(enabled) = self.dictionary {
+            builder = builder.set_dictionary_enabled(enabled);
+        }
+        if let Some(level) = self.statistics {
+            builder = builder.set_statistics_enabled(level);
+        }
+        builder.build()
+    }
+
+    fn column_type(&mut self, variable: &Variable, column: &ColumnInfo) -> ParquetColumnType {
+        let default = ParquetColumnType::default_for(column.kind);
+        let Some(mapper) = &self.type_mapper else {
+            return default;
+        };
+        let chosen = mapper.map_type(variable, column, default);
+        if ColumnValueEncoder::of(chosen) != ColumnValueEncoder::of(default) {
+            self.remapped = true;
+        }
+        chosen
+    }
+
+    fn is_required(&self, name: &str) -> bool {
+        self.required_columns
+            .iter()
+            .any(|required| required.eq_ignore_ascii_case(name.trim_end()))
+    }
+
     fn estimate_row_group_size(&self, context: &SinkContext<'_>) -> usize {
         let mut approx_row_bytes = context
             .columns
@@ -119,7 +314,7 @@ impl<W: Write + Send> ParquetSink<W> {
                 feature: Cow::from("attempted to take Parquet writer before sink was finished"),
             });
         }
-        self.output.take().ok_or_else(|| Error::InvalidMetadata {
+        self.output.take().ok_or_else(|| Error::InvalidArgument {
             details: Cow::from("Parquet sink output already consumed"),
         })
     }
@@ -129,10 +324,10 @@ impl<W: Write + Send> ParquetSink<W> {
             return Ok(());
         }
 
-        let selection: Vec<usize> = (0..self.columns.len()).collect();
-        self.with_selection_row_group(&selection, |plan, column_writer, _| {
-            plan.flush(column_writer)
-        })
+        let started = Stopwatch::start();
+        self.with_row_group(|plan, column_writer, _| plan.flush(column_writer))?;
+        telemetry::sink_flushed("parquet", started);
+        Ok(())
     }
 }
 
@@ -140,6 +335,23 @@ impl<W: Write + Send> RowSink for ParquetSink<W> {
     fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
         validate_sink_begin(&context, self.writer.is_some(), "Parquet")?;
 
+        if let Some(unknown) = self.required_columns.iter().find(|name| {
+            !context
+                .metadata
+                .variables
+                .iter()
+                .any(|variable| variable.name.trim_end().eq_ignore_ascii_case(name))
+        }) {
+            return Err(Error::InvalidArgument {
+                details: format!("required column '{unknown}' not found in dataset").into(),
+            });
+        }
+
+        let logger = match &context.source_path {
+            Some(path) => context.logger.clone().with_context(path.as_str()),
+            None => context.logger.clone(),
+        };
+        self.remapped = false;
         let mut plans = Vec::with_capacity(context.columns.len());
         let mut fields: Vec<TypePtr> = Vec::with_capacity(context.columns.len());
 
@@ -148,20 +360,56 @@ impl<W: Write + Send> RowSink for ParquetSink<W> {
             self.auto_row_group_size = false;
         }
 
-        for (variable, column) in context
+        let membership = resolve_repeated_groups(&self.repeated_groups, &context)?;
+        let mut emitted = vec![false; self.repeated_groups.len()];
+        let mut sources = Vec::with_capacity(context.columns.len());
+        for (index, (variable, column)) in context
             .metadata
             .variables
             .iter()
             .zip(context.columns.iter())
+            .enumerate()
         {
+            let Some(group_idx) = membership.get(index).copied().flatten() else {
+                let column_type = self.column_type(variable, column);
+                let (plan, field) = ColumnPlan::new(
+                    variable,
+                    column_type,
+                    self.lenient_dates,
+                    self.timestamp_rounding,
+                    self.time_precision,
+                    self.is_required(&variable.name),
+                    logger.clone(),
+                )?;
+                fields.push(field);
+                plans.push(plan);
+                sources.push(vec![index]);
+                continue;
+            };
+            if std::mem::replace(&mut emitted[group_idx], true) {
+                continue;
+            }
+            let members = repeated_group_members(&self.repeated_groups[group_idx], &context);
+            let first = members[0];
+            let element = Variable {
+                name: "element".to_owned(),
+                ..context.metadata.variables[first].clone()
+            };
+            let column_type =
+                self.column_type(&context.metadata.variables[first], &context.columns[first]);
             let (plan, field) = ColumnPlan::new(
-                variable,
-                column,
+                &element,
+                column_type,
                 self.lenient_dates,
-                context.source_path.as_deref(),
+                self.timestamp_rounding,
+                self.time_precision,
+                false,
+                logger.clone(),
             )?;
+            let (plan, field) = plan.into_list(&self.repeated_groups[group_idx].name, field)?;
             fields.push(field);
             plans.push(plan);
+            sources.push(members);
         }
         for plan in &mut plans {
             plan.reserve_capacity(self.row_group_size);
@@ -172,46 +420,56 @@ impl<W: Write + Send> RowSink for ParquetSink<W> {
             .build()?;
         let schema = Arc::new(schema);
 
-        let props = WriterProperties::builder().build();
-        let output = self.output.take().ok_or_else(|| Error::InvalidMetadata {
+        let key_value_metadata = if self.sas_metadata {
+            Some(sas_key_value_metadata(context.metadata)?)
+        } else {
+            None
+        };
+        let props = self.writer_properties(key_value_metadata);
+        let output = self.output.take().ok_or_else(|| Error::InvalidArgument {
             details: Cow::from("Parquet sink output already taken"),
         })?;
         let writer = SerializedFileWriter::new(output, schema, props.into())?;
 
         self.columns = plans;
+        self.sources = sources;
+        self.input_columns = context.columns.len();
         self.writer = Some(writer);
         self.rows_buffered = 0;
         Ok(())
     }
 
     fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
-        if self.writer.is_none() {
-            return Err(Error::Unsupported {
-                feature: Cow::from("rows written before Parquet sink initialised"),
-            });
-        }
-
-        if row.len() != self.columns.len() {
-            return Err(Error::InvalidMetadata {
-                details: Cow::Owned(format!(
-                    "row length {} does not match column count {}",
-                    row.len(),
-                    self.columns.len()
-                )),
-            });
-        }
-
-        for (value, plan) in row.iter().zip(self.columns.iter_mut()) {
-            plan.push(value)?;
+        self.check_row_len(row.len())?;
+        for (plan, sources) in self.columns.iter_mut().zip(&self.sources) {
+            if plan.rep_levels.is_some() {
+                for (position, &source) in sources.iter().enumerate() {
+                    plan.push_list_element(&row[source], position == 0)?;
+                }
+            } else {
+                plan.push(&row[sources[0]])?;
+            }
         }
+        self.finish_row()
+    }
 
-        self.rows_buffered = self.rows_buffered.saturating_add(1);
-
-        if self.row_group_size > 0 && self.rows_buffered >= self.row_group_size {
-            self.flush()?;
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        self.check_row_len(row.len())?;
+        for (plan, sources) in self.columns.iter_mut().zip(&self.sources) {
+            if plan.rep_levels.is_some() {
+                for (position, &source) in sources.iter().enumerate() {
+                    let value = row.cell(source)?.decode_value()?;
+                    plan.push_list_element(&value, position == 0)?;
+                }
+            } else {
+                plan.push(&row.cell(sources[0])?.decode_value()?)?;
+            }
         }
+        self.finish_row()
+    }
 
-        Ok(())
+    fn streams_borrowed_rows(&self) -> bool {
+        self.row_group_size > 0 || self.auto_row_group_size
     }
 
     fn finish(&mut self) -> Result<()> {
@@ -228,6 +486,7 @@ impl<W: Write + Send> RowSink for ParquetSink<W> {
             self.output = Some(output);
         }
         self.columns.clear();
+        self.sources.clear();
         self.rows_buffered = 0;
         Ok(())
     }
@@ -244,10 +503,10 @@ impl<W: Write + Send> ColumnarSink for ParquetSink<W> {
                 feature: Cow::from("rows written before Parquet sink initialised"),
             });
         }
-        if selection.len() != self.columns.len() {
-            return Err(Error::InvalidMetadata {
-                details: Cow::from("column selection length does not match sink columns"),
-            });
+        self.ensure_selection_valid(selection.len())?;
+
+        if !self.repeated_groups.is_empty() || self.remapped {
+            return self.write_columnar_batch_rows(batch, selection);
         }
 
         if self.streaming_columnar {
@@ -258,7 +517,7 @@ impl<W: Write + Send> ColumnarSink for ParquetSink<W> {
         for (plan, &source_idx) in self.columns.iter_mut().zip(selection.iter()) {
             let column = batch
                 .column(source_idx)
-                .ok_or_else(|| Error::InvalidMetadata {
+                .ok_or_else(|| Error::InvalidArgument {
                     details: Cow::Owned(format!(
                         "column selection index {source_idx} exceeds available columns"
                     )),
@@ -275,6 +534,34 @@ impl<W: Write + Send> ColumnarSink for ParquetSink<W> {
 }
 
 impl<W: Write + Send> ParquetSink<W> {
+    fn check_row_len(&self, len: usize) -> Result<()> {
+        if self.writer.is_none() {
+            return Err(Error::Unsupported {
+                feature: Cow::from("rows written before Parquet sink initialised"),
+            });
+        }
+
+        if len != self.input_columns {
+            return Err(Error::InvalidArgument {
+                details: Cow::Owned(format!(
+                    "row length {len} does not match column count {}",
+                    self.input_columns
+                )),
+            });
+        }
+        Ok(())
+    }
+
+    fn finish_row(&mut self) -> Result<()> {
+        self.rows_buffered = self.rows_buffered.saturating_add(1);
+
+        if self.row_group_size > 0 && self.rows_buffered >= self.row_group_size {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
     #[inline]
     const fn streaming_chunk_rows(&self) -> usize {
         // Align streaming chunking to the configured row group size to reduce write_batch calls.
@@ -288,16 +575,18 @@ impl<W: Write + Send> ParquetSink<W> {
         selection: &[usize],
     ) -> Result<()> {
         let chunk_rows = self.streaming_chunk_rows().max(1);
-        self.with_selection_row_group(selection, |plan, column_writer, source_idx| {
+        self.ensure_selection_valid(selection.len())?;
+        self.with_row_group(|plan, column_writer, plan_idx| {
+            let source_idx = selection[plan_idx];
             let column = batch
                 .column(source_idx)
-                .ok_or_else(|| Error::InvalidMetadata {
+                .ok_or_else(|| Error::InvalidArgument {
                     details: Cow::Owned(format!(
                         "column selection index {source_idx} exceeds available columns"
                     )),
                 })?;
             match plan.encoder {
-                super::plan::ColumnValueEncoder::Utf8 => {
+                ColumnValueEncoder::Utf8 => {
                     if let Some(materialized) = batch.materialize_utf8(source_idx)? {
                         plan.stream_columnar_materialized_utf8(column_writer, &materialized)?;
                     } else {
@@ -321,16 +610,49 @@ impl<W: Write + Send> ParquetSink<W> {
         Ok(())
     }
 
+    /// Pushes a batch through the row path, used when list columns are
+    /// assembled from several input columns.
+    fn write_columnar_batch_rows(
+        &mut self,
+        batch: &ColumnarBatch<'_>,
+        selection: &[usize],
+    ) -> Result<()> {
+        let columns = selection
+            .iter()
+            .map(|&source_idx| {
+                batch
+                    .column(source_idx)
+                    .ok_or_else(|| Error::InvalidArgument {
+                        details: Cow::Owned(format!(
+                            "column selection index {source_idx} exceeds available columns"
+                        )),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let missing = CellValue::Missing(MissingValue::System);
+        let mut row = Vec::with_capacity(columns.len());
+        for row_index in 0..batch.row_count {
+            row.clear();
+            row.extend(columns.iter().map(|column| {
+                column
+                    .decode_cell(row_index)
+                    .unwrap_or_else(|| missing.clone())
+            }));
+            self.write_row(&row)?;
+        }
+        Ok(())
+    }
+
     fn ensure_selection_valid(&self, len: usize) -> Result<()> {
-        if len != self.columns.len() {
-            return Err(Error::InvalidMetadata {
+        if len != self.input_columns {
+            return Err(Error::InvalidArgument {
                 details: Cow::from("column selection length does not match sink columns"),
             });
         }
         Ok(())
     }
 
-    fn with_selection_row_group<F>(&mut self, selection: &[usize], mut f: F) -> Result<()>
+    fn with_row_group<F>(&mut self, mut f: F) -> Result<()>
     where
         F: FnMut(
             &mut super::plan::ColumnPlan,
@@ -339,18 +661,17 @@ impl<W: Write + Send> ParquetSink<W> {
         ) -> Result<()>,
     {
         self.ensure_writer_initialised()?;
-        self.ensure_selection_valid(selection.len())?;
 
-        let writer = self.writer.as_mut().ok_or_else(|| Error::InvalidMetadata {
+        let writer = self.writer.as_mut().ok_or_else(|| Error::InvalidArgument {
             details: Cow::from("Parquet sink has not been initialised"),
         })?;
         let mut row_group = writer.next_row_group()?;
 
-        for (plan, &source_idx) in self.columns.iter_mut().zip(selection.iter()) {
+        for (plan_idx, plan) in self.columns.iter_mut().enumerate() {
             let column_writer = row_group.next_column()?.ok_or_else(|| Error::Parquet {
                 details: Cow::from("writer returned fewer columns than metadata described"),
             })?;
-            f(plan, column_writer, source_idx)?;
+            f(plan, column_writer, plan_idx)?;
         }
 
         if row_group.next_column()?.is_some() {
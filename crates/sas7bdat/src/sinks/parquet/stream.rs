@@ -6,6 +6,26 @@ pub(super) struct StreamNumericCtx<'a, T> {
     pub def_bitmap: &'a mut Vec<u8>,
     pub values: &'a mut Vec<T>,
     pub chunk: usize,
+    /// Name of the column when it is written as `REQUIRED`.
+    pub required: Option<&'a str>,
+}
+
+/// Returns the definition levels to pass to the Parquet writer. Required
+/// columns take no levels, so they are checked for missing values instead.
+pub(super) fn write_def_levels<'a>(
+    required: Option<&str>,
+    def_levels: &'a [i16],
+) -> Result<Option<&'a [i16]>> {
+    let Some(name) = required else {
+        return Ok(Some(def_levels));
+    };
+    if def_levels.contains(&0) {
+        return Err(Error::Parquet {
+            details: format!("column '{name}' is marked required but contains a missing value")
+                .into(),
+        });
+    }
+    Ok(None)
 }
 
 #[inline]
@@ -45,7 +65,7 @@ where
     P: FnMut(usize, usize) -> I,
     I: Iterator<Item = Option<u64>>,
     F: FnMut(u64) -> Result<T>,
-    W: FnMut(&[T], &[i16]) -> std::result::Result<usize, ParquetError>,
+    W: FnMut(&[T], Option<&[i16]>) -> std::result::Result<usize, ParquetError>,
 {
     let total = total_len;
     let mut processed = 0;
@@ -64,7 +84,8 @@ where
             }
         }
         expand_bitmap_to_def_levels(ctx.def_levels, ctx.def_bitmap, take);
-        write_chunk(ctx.values, ctx.def_levels).map_err(Error::from)?;
+        let defs = write_def_levels(ctx.required, ctx.def_levels)?;
+        write_chunk(ctx.values, defs).map_err(Error::from)?;
         processed += take;
     }
     Ok(())
@@ -85,6 +106,7 @@ mod tests {
             def_bitmap: &mut Vec::new(),
             values: &mut Vec::new(),
             chunk: 3,
+            required: None,
         };
 
         stream_numeric(
@@ -92,9 +114,9 @@ mod tests {
             data.len(),
             |start, len| data[start..start + len].iter().copied(),
             Ok,
-            |vals: &[u64], defs: &[i16]| {
+            |vals: &[u64], defs: Option<&[i16]>| {
                 captured_values.push(vals.to_vec());
-                captured_defs.push(defs.to_vec());
+                captured_defs.push(defs.expect("optional column has levels").to_vec());
                 Ok(vals.len())
             },
         )
@@ -1,4 +1,4 @@
-use super::constants::UTF8_DICTIONARY_LIMIT;
+use super::constants::{UTF8_DICTIONARY_LIMIT, UTF8_DICTIONARY_MAX_VALUE_BYTES};
 use ahash::RandomState;
 use bytes::Bytes;
 use hashbrown::{HashMap, hash_map::RawEntryMut};
@@ -7,7 +7,10 @@ use parquet::data_type::ByteArray;
 pub(super) struct Utf8Scratch {
     pub ryu: ryu::Buffer,
     pub itoa: itoa::Buffer,
-    dictionary: HashMap<Vec<u8>, ByteArray, RandomState>,
+    /// Reused buffer for character cells that need transcoding.
+    pub spill: String,
+    /// Keys share the stored bytes of their values.
+    dictionary: HashMap<Bytes, ByteArray, RandomState>,
     dictionary_enabled: bool,
     last_short: Option<(Vec<u8>, ByteArray)>,
 }
@@ -17,6 +20,7 @@ impl Utf8Scratch {
         Self {
             ryu: ryu::Buffer::new(),
             itoa: itoa::Buffer::new(),
+            spill: String::new(),
             dictionary: HashMap::with_capacity_and_hasher(
                 UTF8_DICTIONARY_LIMIT,
                 RandomState::new(),
@@ -37,6 +41,9 @@ impl Utf8Scratch {
             self.dictionary.clear();
             self.dictionary_enabled = false;
         }
+        if data.len() > UTF8_DICTIONARY_MAX_VALUE_BYTES {
+            return ByteArray::from(Bytes::copy_from_slice(data));
+        }
         if !self.dictionary_enabled {
             let stored = ByteArray::from(Bytes::copy_from_slice(data));
             if data.len() <= 32 {
@@ -53,8 +60,9 @@ impl Utf8Scratch {
                 cloned
             }
             RawEntryMut::Vacant(vacant) => {
-                let stored = ByteArray::from(Bytes::copy_from_slice(data));
-                vacant.insert(data.to_vec(), stored.clone());
+                let bytes = Bytes::copy_from_slice(data);
+                let stored = ByteArray::from(bytes.clone());
+                vacant.insert(bytes, stored.clone());
                 if data.len() <= 32 {
                     self.last_short = Some((data.to_vec(), stored.clone()));
                 }
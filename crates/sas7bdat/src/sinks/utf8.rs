@@ -0,0 +1,231 @@
+use crate::{
+    cell::CellValue,
+    error::Result,
+    logger::Logger,
+    parser::{ColumnKind, StreamingRow},
+    sinks::{RowSink, SinkContext},
+};
+use std::{
+    borrow::Cow,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Running total of the invalid UTF-8 sequences replaced by [`Utf8Sink`]s.
+///
+/// Clones share the total, so one counter can be handed to every sink of a
+/// conversion and read once it is done.
+#[derive(Debug, Clone, Default)]
+pub struct Utf8Replacements {
+    count: Arc<AtomicU64>,
+}
+
+impl Utf8Replacements {
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn add(&self, count: u64) {
+        if count > 0 {
+            self.count.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Guarantees that every string reaching another sink is valid UTF-8.
+///
+/// Character cells that are neither UTF-8 nor valid in the dataset encoding
+/// are decoded with a replacement character (U+FFFD unless
+/// [`with_replacement`](Self::with_replacement) picks another) in place of
+/// each malformed sequence, and raw byte cells holding invalid UTF-8 are
+/// repaired the same way. Rows that need no repair are forwarded untouched,
+/// as borrowed rows when the caller streams them.
+///
+/// Rows passed to [`RowSink::write_row`] were decoded before they got here,
+/// so U+FFFD already in their text is counted as a replacement.
+pub struct Utf8Sink<S: RowSink> {
+    inner: S,
+    replacement: char,
+    replaced: u64,
+    counter: Utf8Replacements,
+    logger: Logger,
+}
+
+impl<S: RowSink> Utf8Sink<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            replacement: char::REPLACEMENT_CHARACTER,
+            replaced: 0,
+            counter: Utf8Replacements::default(),
+            logger: Logger::default(),
+        }
+    }
+
+    /// Writes `replacement` in place of each invalid sequence.
+    #[must_use]
+    pub const fn with_replacement(mut self, replacement: char) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Adds the replacements made by this sink to `counter` as well.
+    #[must_use]
+    pub fn with_counter(mut self, counter: Utf8Replacements) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    /// Invalid sequences this sink has replaced so far.
+    #[must_use]
+    pub const fn replacements(&self) -> u64 {
+        self.replaced
+    }
+
+    #[must_use]
+    pub const fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn record(&mut self, count: u64) {
+        self.replaced += count;
+        self.counter.add(count);
+    }
+
+    /// Returns the repaired value, or `None` when `value` can be forwarded
+    /// as it is.
+    fn repair(&mut self, value: &CellValue<'_>) -> Option<CellValue<'static>> {
+        match value {
+            CellValue::Str(text) => self
+                .repair_text(text)
+                .map(|text| CellValue::Str(text.into())),
+            CellValue::NumericString(text) => self
+                .repair_text(text)
+                .map(|text| CellValue::NumericString(text.into())),
+            CellValue::Bytes(bytes) => self
+                .repair_bytes(bytes)
+                .map(|text| CellValue::Bytes(text.into_bytes().into())),
+            _ => None,
+        }
+    }
+
+    fn repair_text(&mut self, text: &str) -> Option<String> {
+        let found = text.matches(char::REPLACEMENT_CHARACTER).count() as u64;
+        if found == 0 {
+            return None;
+        }
+        self.record(found);
+        (self.replacement != char::REPLACEMENT_CHARACTER).then(|| {
+            let mut buffer = [0u8; 4];
+            text.replace(
+                char::REPLACEMENT_CHARACTER,
+                self.replacement.encode_utf8(&mut buffer),
+            )
+        })
+    }
+
+    fn repair_bytes(&mut self, bytes: &[u8]) -> Option<String> {
+        if std::str::from_utf8(bytes).is_ok() {
+            return None;
+        }
+        let mut text = String::with_capacity(bytes.len());
+        let mut found = 0;
+        for chunk in bytes.utf8_chunks() {
+            text.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                text.push(self.replacement);
+                found += 1;
+            }
+        }
+        self.record(found);
+        Some(text)
+    }
+}
+
+impl<S: RowSink> RowSink for Utf8Sink<S> {
+    fn begin(&mut self, context: SinkContext<'_>) -> Result<()> {
+        self.replaced = 0;
+        self.logger = context.logger.clone();
+        self.inner.begin(context)
+    }
+
+    fn write_row(&mut self, row: &[CellValue<'_>]) -> Result<()> {
+        let mut repaired: Option<Vec<CellValue<'_>>> = None;
+        for (index, value) in row.iter().enumerate() {
+            if let Some(value) = self.repair(value) {
+                repaired.get_or_insert_with(|| row.to_vec())[index] = value;
+            }
+        }
+        match repaired {
+            Some(values) => self.inner.write_row(&values),
+            None => self.inner.write_row(row),
+        }
+    }
+
+    fn write_streaming_row(&mut self, row: StreamingRow<'_, '_>) -> Result<()> {
+        let mut clean = true;
+        for cell in &row {
+            let cell = cell?;
+            let valid = match cell.kind() {
+                ColumnKind::Bytes => std::str::from_utf8(cell.raw_slice()).is_ok(),
+                _ => cell.text_is_decodable(),
+            };
+            if !valid {
+                clean = false;
+                break;
+            }
+        }
+        if clean {
+            return self.inner.write_streaming_row(row);
+        }
+
+        let mut values = Vec::with_capacity(row.len());
+        for cell in &row {
+            let cell = cell?;
+            if cell.text_is_decodable() {
+                // Decodable text is forwarded as is: U+FFFD in it came from
+                // the data, not from a replacement.
+                values.push(match cell.decode_value()? {
+                    CellValue::Bytes(bytes) => self
+                        .repair_bytes(&bytes)
+                        .map_or(CellValue::Bytes(bytes), |text| {
+                            CellValue::Bytes(Cow::Owned(text.into_bytes()))
+                        }),
+                    value => value,
+                });
+            } else {
+                let (text, found) = cell.decode_text_replacing(self.replacement);
+                self.record(found);
+                values.push(CellValue::Str(Cow::Owned(text)));
+            }
+        }
+        self.inner.write_row(&values)
+    }
+
+    fn streams_borrowed_rows(&self) -> bool {
+        self.inner.streams_borrowed_rows()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.replaced > 0 {
+            self.logger.warn(&format!(
+                "replaced {} invalid UTF-8 sequence(s) with '{}'",
+                self.replaced,
+                self.replacement.escape_default()
+            ));
+        }
+        self.inner.finish()
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        self.inner.abort()
+    }
+}
@@ -0,0 +1,643 @@
+//! Declarative conversion definitions that can live under version control.
+//!
+//! A [`ConversionSpec`] describes which files to read, which columns to keep
+//! and how to name and type them, how to spot nulls, where to write and how
+//! to split the output. Specs are plain TOML or JSON:
+//!
+//! ```toml
+//! input = "registers/**/*.sas7bdat"
+//! output_dir = "parquet"
+//! columns = ["PNR", "D_INDDTO", "C_ADIAG"]
+//!
+//! [rename]
+//! PNR = "person_id"
+//!
+//! [types]
+//! D_INDDTO = "date"
+//!
+//! [sink]
+//! format = "parquet"
+//! row_group_size = 100000
+//!
+//! [nulls]
+//! blank_strings = true
+//! values = { C_ADIAG = ["UNKNOWN"] }
+//!
+//! [partition]
+//! by = "C_ADIAG"
+//! ```
+//!
+//! [`ConversionSpec::run`] converts every matching file with a single call.
+//! Relative paths are resolved against the spec file's directory when the
+//! spec was loaded with [`ConversionSpec::from_path`], and against the
+//! working directory otherwise.
+
+pub use crate::parser::ColumnType;
+use crate::{
+    cell::{CellValue, MissingValue},
+    dataset::{DatasetMetadata, VariableKind},
+    error::{Error, Result},
+    parser::{ColumnInfo, DatasetLayout, TimestampRounding},
+    reader::SasReader,
+    sinks::{
+        CsvSink, ParquetSink, RowSink, SinkContext, TimePrecision, Utf8Replacements, Utf8Sink,
+    },
+};
+use serde::Deserialize;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, hash_map::Entry},
+    fmt::Write as _,
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+use time::format_description::well_known::Rfc3339;
+
+/// Directory name used for rows whose partition value is missing or blank,
+/// matching the Hive convention.
+pub const DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// A complete, serialisable description of a conversion job.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConversionSpec {
+    /// Glob pattern selecting the input `.sas7bdat` files.
+    pub input: String,
+    /// Directory receiving one output file (or partition tree) per input.
+    pub output_dir: PathBuf,
+    /// Columns to keep, in output order. Empty keeps every column.
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Output names keyed by source column name.
+    #[serde(default)]
+    pub rename: BTreeMap<String, String>,
+    /// Decoding overrides keyed by source column name.
+    #[serde(default)]
+    pub types: BTreeMap<String, ColumnType>,
+    #[serde(default)]
+    pub sink: SinkSpec,
+    #[serde(default)]
+    pub nulls: NullRules,
+    pub partition: Option<PartitionSpec>,
+    #[serde(skip)]
+    base_dir: Option<PathBuf>,
+}
+
+/// Output format written by [`ConversionSpec::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Parquet,
+    Csv,
+    Tsv,
+}
+
+impl OutputFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+        }
+    }
+}
+
+/// Rounding applied when sub-microsecond precision is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingSpec {
+    #[default]
+    Truncate,
+    HalfEven,
+}
+
+impl From<RoundingSpec> for TimestampRounding {
+    fn from(value: RoundingSpec) -> Self {
+        match value {
+            RoundingSpec::Truncate => Self::Truncate,
+            RoundingSpec::HalfEven => Self::HalfEven,
+        }
+    }
+}
+
+/// Sink selection and options. Options that do not apply to the chosen
+/// format are ignored.
+// Each flag is a separate key of the spec file.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinkSpec {
+    pub format: OutputFormat,
+    /// CSV/TSV: write a header row.
+    pub headers: bool,
+    /// CSV/TSV: field delimiter, defaulting to `,` or tab.
+    pub delimiter: Option<char>,
+    /// CSV/TSV: CRLF line endings and strict quoting.
+    pub rfc4180: bool,
+    /// CSV/TSV: start with comment lines describing each column.
+    pub metadata_comments: bool,
+    /// Parquet: rows per row group.
+    pub row_group_size: Option<usize>,
+    /// Parquet: target encoded bytes per row group.
+    pub target_row_group_bytes: Option<usize>,
+    /// Parquet: store `TIME` values in nanoseconds instead of microseconds.
+    pub nanosecond_times: bool,
+    pub timestamp_rounding: RoundingSpec,
+    /// Repair invalid UTF-8 so every string written is valid UTF-8.
+    pub strict_utf8: bool,
+    /// Character written in place of invalid UTF-8 under `strict_utf8`,
+    /// defaulting to U+FFFD.
+    pub utf8_replacement: Option<char>,
+}
+
+impl Default for SinkSpec {
+    fn default() -> Self {
+        Self {
+            format: OutputFormat::default(),
+            headers: true,
+            delimiter: None,
+            rfc4180: false,
+            metadata_comments: false,
+            row_group_size: None,
+            target_row_group_bytes: None,
+            nanosecond_times: false,
+            timestamp_rounding: RoundingSpec::default(),
+            strict_utf8: false,
+            utf8_replacement: None,
+        }
+    }
+}
+
+/// Values written as nulls in addition to SAS missing values.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NullRules {
+    /// Treat empty and all-blank strings as null in every column.
+    pub blank_strings: bool,
+    /// Sentinel values keyed by source column name.
+    pub values: BTreeMap<String, Vec<NullValue>>,
+}
+
+/// Sentinel compared with text cells (after trailing blanks are removed) or
+/// with numeric cells.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum NullValue {
+    Number(f64),
+    Text(String),
+}
+
+impl NullValue {
+    // Sentinels are exact codes, so exact float comparison is intended.
+    #[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+    fn matches(&self, value: &CellValue<'_>) -> bool {
+        match (self, value) {
+            (Self::Number(sentinel), CellValue::Float(number)) => number == sentinel,
+            (Self::Number(sentinel), CellValue::Int32(number)) => f64::from(*number) == *sentinel,
+            (Self::Number(sentinel), CellValue::Int64(number)) => *number as f64 == *sentinel,
+            (Self::Text(sentinel), CellValue::Str(text) | CellValue::NumericString(text)) => {
+                text.trim_end() == sentinel.trim_end()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Splits each input into one file per distinct value of a column, laid out
+/// as `<output_dir>/<stem>/<column>=<value>/part-00000.<ext>`.
+///
+/// Every partition keeps its writer open until the input is exhausted, so
+/// the column should have a modest number of distinct values.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartitionSpec {
+    /// Source column whose values name the partitions.
+    pub by: String,
+}
+
+/// Outcome of [`ConversionSpec::run`].
+#[derive(Debug, Clone, Default)]
+pub struct ConversionReport {
+    pub files: Vec<ConvertedFile>,
+}
+
+impl ConversionReport {
+    /// Total rows written across every input.
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.files.iter().map(|file| file.rows).sum()
+    }
+}
+
+/// One converted input file.
+#[derive(Debug, Clone)]
+pub struct ConvertedFile {
+    pub input: PathBuf,
+    /// Files written, in the order they were created.
+    pub outputs: Vec<PathBuf>,
+    pub rows: u64,
+    /// Invalid UTF-8 sequences replaced under [`SinkSpec::strict_utf8`].
+    pub utf8_replacements: u64,
+}
+
+impl ConversionSpec {
+    /// Parses a spec from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the document is not a valid spec.
+    pub fn from_toml_str(source: &str) -> Result<Self> {
+        toml::from_str(source).map_err(|err| Error::Config {
+            details: err.to_string().into(),
+        })
+    }
+
+    /// Parses a spec from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the document is not a valid spec.
+    pub fn from_json_str(source: &str) -> Result<Self> {
+        serde_json::from_str(source).map_err(|err| Error::Config {
+            details: err.to_string().into(),
+        })
+    }
+
+    /// Loads a `.toml` or `.json` spec. Relative paths inside the spec are
+    /// resolved against the directory containing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, has another extension,
+    /// or is not a valid spec.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let mut spec = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::from_toml_str(&source)?,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json_str(&source)?,
+            _ => {
+                return Err(Error::Config {
+                    details: format!(
+                        "spec '{}' must have a .toml or .json extension",
+                        path.display()
+                    )
+                    .into(),
+                });
+            }
+        };
+        spec.base_dir = path.parent().map(Path::to_path_buf);
+        Ok(spec)
+    }
+
+    /// Resolves relative paths against `dir` instead of the working
+    /// directory.
+    #[must_use]
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    /// Lists the files matched by [`input`](Self::input), sorted by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the pattern is invalid or matches no
+    /// files.
+    pub fn inputs(&self) -> Result<Vec<PathBuf>> {
+        let pattern = self.resolve(Path::new(&self.input));
+        let pattern = pattern.to_str().ok_or_else(|| Error::Config {
+            details: Cow::from("input pattern is not valid UTF-8"),
+        })?;
+        let entries = glob::glob(pattern).map_err(|err| Error::Config {
+            details: format!("invalid input pattern '{}': {err}", self.input).into(),
+        })?;
+        let mut inputs = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|err| Error::Io(err.into()))?;
+            if path.is_file() {
+                inputs.push(path);
+            }
+        }
+        if inputs.is_empty() {
+            return Err(Error::Config {
+                details: format!("input pattern '{}' matched no files", self.input).into(),
+            });
+        }
+        inputs.sort();
+        Ok(inputs)
+    }
+
+    /// Converts every input file, stopping at the first failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the spec names columns a dataset does not
+    /// have or overrides a type incompatibly, and otherwise any error raised
+    /// while reading or writing.
+    pub fn run(&self) -> Result<ConversionReport> {
+        let output_dir = self.resolve(&self.output_dir);
+        let mut report = ConversionReport::default();
+        for input in self.inputs()? {
+            report.files.push(self.convert_file(&input, &output_dir)?);
+        }
+        Ok(report)
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        match &self.base_dir {
+            Some(base) if path.is_relative() => base.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    fn convert_file(&self, input: &Path, output_dir: &Path) -> Result<ConvertedFile> {
+        let (mut reader, mut layout) = SasReader::open(input)?.into_parts();
+        let plan = ColumnPlan::new(self, &mut layout)?;
+        let stem = input
+            .file_stem()
+            .map_or_else(|| Cow::from("output"), |stem| stem.to_string_lossy());
+        let extension = self.sink.format.extension();
+        let source_path = Some(input.to_string_lossy().into_owned());
+        let context = || SinkContext {
+            metadata: &plan.metadata,
+            columns: &plan.columns,
+            source_path: source_path.clone(),
+            logger: layout.logger.clone(),
+        };
+
+        let replacements = Utf8Replacements::default();
+        let mut outputs = Vec::new();
+        let mut rows = 0u64;
+        let mut iterator = layout.row_iterator(&mut reader)?;
+        if let Some(partition) = plan.partition {
+            let name = &plan.metadata.variables[partition].name;
+            let root = output_dir.join(&*stem);
+            let mut sinks: HashMap<String, Box<dyn RowSink>> = HashMap::new();
+            while let Some(row) = iterator.try_next()? {
+                let values = plan.project(&row);
+                let key = partition_value(&values[partition]);
+                let sink = match sinks.entry(key) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let dir = root.join(format!("{name}={}", entry.key()));
+                        fs::create_dir_all(&dir)?;
+                        let path = dir.join(format!("part-00000.{extension}"));
+                        let mut sink = self.create_sink(&path, &replacements)?;
+                        sink.begin(context())?;
+                        outputs.push(path);
+                        entry.insert(sink)
+                    }
+                };
+                sink.write_row(&values)?;
+                rows += 1;
+            }
+            for sink in sinks.values_mut() {
+                sink.finish()?;
+            }
+        } else {
+            fs::create_dir_all(output_dir)?;
+            let path = output_dir.join(format!("{stem}.{extension}"));
+            let mut sink = self.create_sink(&path, &replacements)?;
+            sink.begin(context())?;
+            while let Some(row) = iterator.try_next()? {
+                sink.write_row(&plan.project(&row))?;
+                rows += 1;
+            }
+            sink.finish()?;
+            outputs.push(path);
+        }
+
+        Ok(ConvertedFile {
+            input: input.to_path_buf(),
+            outputs,
+            rows,
+            utf8_replacements: replacements.get(),
+        })
+    }
+
+    fn create_sink(
+        &self,
+        path: &Path,
+        replacements: &Utf8Replacements,
+    ) -> Result<Box<dyn RowSink>> {
+        let file = File::create(path)?;
+        let options = &self.sink;
+        let sink: Box<dyn RowSink> = match options.format {
+            OutputFormat::Parquet => {
+                let mut sink = ParquetSink::new(file)
+                    .with_timestamp_rounding(options.timestamp_rounding.into())
+                    .with_time_precision(if options.nanosecond_times {
+                        TimePrecision::Nanos
+                    } else {
+                        TimePrecision::Micros
+                    });
+                if let Some(rows) = options.row_group_size {
+                    sink = sink.with_row_group_size(rows);
+                }
+                if let Some(bytes) = options.target_row_group_bytes {
+                    sink = sink.with_target_row_group_bytes(bytes);
+                }
+                Box::new(sink)
+            }
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let delimiter = match (options.delimiter, options.format) {
+                    (Some(ch), _) => u8::try_from(ch).map_err(|_| Error::Config {
+                        details: format!("delimiter '{ch}' is not a single-byte character").into(),
+                    })?,
+                    (None, OutputFormat::Tsv) => b'\t',
+                    (None, _) => b',',
+                };
+                Box::new(
+                    CsvSink::new(file)
+                        .with_headers(options.headers)
+                        .with_delimiter(delimiter)
+                        .with_rfc4180(options.rfc4180)
+                        .with_metadata_comments(options.metadata_comments),
+                )
+            }
+        };
+        if !options.strict_utf8 {
+            return Ok(sink);
+        }
+        Ok(Box::new(
+            Utf8Sink::new(sink)
+                .with_replacement(
+                    options
+                        .utf8_replacement
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                )
+                .with_counter(replacements.clone()),
+        ))
+    }
+}
+
+/// The spec resolved against one dataset's columns.
+struct ColumnPlan {
+    /// Source column index of each output column.
+    selected: Vec<usize>,
+    /// Null sentinels of each output column.
+    sentinels: Vec<Vec<NullValue>>,
+    blank_strings: bool,
+    /// Output position of the partition column.
+    partition: Option<usize>,
+    metadata: DatasetMetadata,
+    columns: Vec<ColumnInfo>,
+}
+
+impl ColumnPlan {
+    fn new(spec: &ConversionSpec, layout: &mut DatasetLayout) -> Result<Self> {
+        let variables = &layout.header.metadata.variables;
+        let find = |name: &str, role: &str| {
+            variables
+                .iter()
+                .position(|variable| variable.name.trim_end() == name.trim_end())
+                .ok_or_else(|| Error::Config {
+                    details: format!("{role} column '{name}' not found in dataset").into(),
+                })
+        };
+
+        for (name, column_type) in &spec.types {
+            let index = find(name, "typed")?;
+            let numeric = matches!(variables[index].kind, VariableKind::Numeric);
+            if numeric != column_type.is_numeric() {
+                return Err(Error::Config {
+                    details: format!("column '{name}' cannot be decoded as {column_type:?}").into(),
+                });
+            }
+            layout.columns[index].kind = column_type.column_kind();
+        }
+
+        let selected = if spec.columns.is_empty() {
+            (0..variables.len()).collect()
+        } else {
+            let mut selected = Vec::with_capacity(spec.columns.len());
+            for name in &spec.columns {
+                let index = find(name, "selected")?;
+                if selected.contains(&index) {
+                    return Err(Error::Config {
+                        details: format!("column '{name}' is selected twice").into(),
+                    });
+                }
+                selected.push(index);
+            }
+            selected
+        };
+        let position = |name: &str, role: &str| {
+            let index = find(name, role)?;
+            selected
+                .iter()
+                .position(|&selected| selected == index)
+                .ok_or_else(|| Error::Config {
+                    details: format!("{role} column '{name}' is not selected").into(),
+                })
+        };
+
+        let mut metadata = layout.header.metadata.clone();
+        metadata.column_list.clear();
+        metadata.variables = Vec::with_capacity(selected.len());
+        let mut columns = Vec::with_capacity(selected.len());
+        for (output, &index) in selected.iter().enumerate() {
+            let mut variable = variables[index].clone();
+            let output = u32::try_from(output).map_err(|_| Error::Limit {
+                details: Cow::from("selected column count exceeds u32 range"),
+            })?;
+            variable.index = output;
+            variable.name.truncate(variable.name.trim_end().len());
+            let mut column = layout.columns[index].clone();
+            column.index = output;
+            metadata.variables.push(variable);
+            columns.push(column);
+        }
+        metadata.column_count = u32::try_from(selected.len()).map_err(|_| Error::Limit {
+            details: Cow::from("selected column count exceeds u32 range"),
+        })?;
+
+        for (from, to) in &spec.rename {
+            let output = position(from, "renamed")?;
+            metadata.variables[output].name.clone_from(to);
+        }
+        for (output, variable) in metadata.variables.iter().enumerate() {
+            if metadata.variables[..output]
+                .iter()
+                .any(|earlier| earlier.name == variable.name)
+            {
+                return Err(Error::Config {
+                    details: format!("output column name '{}' is used twice", variable.name).into(),
+                });
+            }
+        }
+
+        let mut sentinels = vec![Vec::new(); selected.len()];
+        for (name, values) in &spec.nulls.values {
+            let output = position(name, "null rule")?;
+            sentinels[output].clone_from(values);
+        }
+        let partition = spec
+            .partition
+            .as_ref()
+            .map(|partition| position(&partition.by, "partition"))
+            .transpose()?;
+
+        Ok(Self {
+            selected,
+            sentinels,
+            blank_strings: spec.nulls.blank_strings,
+            partition,
+            metadata,
+            columns,
+        })
+    }
+
+    fn project<'a>(&self, row: &[CellValue<'a>]) -> Vec<CellValue<'a>> {
+        self.selected
+            .iter()
+            .zip(&self.sentinels)
+            .map(|(&index, sentinels)| {
+                let value = &row[index];
+                let blank = self.blank_strings
+                    && matches!(value, CellValue::Str(text) if text.trim().is_empty());
+                if blank || sentinels.iter().any(|sentinel| sentinel.matches(value)) {
+                    CellValue::Missing(MissingValue::System)
+                } else {
+                    value.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Renders a partition value as a directory-safe string, percent-encoding
+/// path separators and other characters that are unsafe in file names.
+fn partition_value(value: &CellValue<'_>) -> String {
+    let raw = match value {
+        CellValue::Str(text) | CellValue::NumericString(text) => text.trim().to_owned(),
+        CellValue::Float(number) => number.to_string(),
+        CellValue::Int32(number) => number.to_string(),
+        CellValue::Int64(number) => number.to_string(),
+        CellValue::Date(datetime) => datetime.date().to_string(),
+        CellValue::DateTime(datetime) => datetime.format(&Rfc3339).unwrap_or_default(),
+        CellValue::Time(duration) => duration.as_seconds_f64().to_string(),
+        CellValue::Bytes(bytes) => bytes.iter().fold(String::new(), |mut out, byte| {
+            let _ = write!(out, "{byte:02x}");
+            out
+        }),
+        CellValue::Missing(_) => String::new(),
+    };
+    if raw.is_empty() || raw == "." || raw == ".." {
+        return DEFAULT_PARTITION.to_owned();
+    }
+    let mut escaped = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        if ch.is_control() || "/\\:*?\"<>|%=".contains(ch) {
+            let mut buffer = [0u8; 4];
+            for byte in ch.encode_utf8(&mut buffer).bytes() {
+                let _ = write!(escaped, "%{byte:02X}");
+            }
+        } else {
+            escaped.push(ch);
+        }
+    }
+    escaped
+}
@@ -0,0 +1,107 @@
+//! Metric names emitted through the [`metrics`](https://docs.rs/metrics)
+//! facade when the `metrics` feature is enabled.
+//!
+//! Install any `metrics` recorder (Prometheus, `StatsD`, an OpenTelemetry
+//! bridge, ...) before opening readers. Handles are registered when a row
+//! iterator or sink starts, so a recorder installed afterwards only sees
+//! later conversions. Without the feature every hook compiles to nothing.
+
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+/// Counter of pages read from the file, including metadata and skipped pages.
+pub const PAGES_READ: &str = "sas7bdat_pages_read_total";
+/// Counter of rows extracted from data pages.
+pub const ROWS_DECODED: &str = "sas7bdat_rows_decoded_total";
+/// Histogram of the time spent reading, decompressing and splitting one page
+/// into rows, in seconds.
+pub const PAGE_DECODE_SECONDS: &str = "sas7bdat_page_decode_seconds";
+/// Histogram of the time a sink spends writing out one buffered batch or row
+/// group, in seconds.
+pub const SINK_FLUSH_SECONDS: &str = "sas7bdat_sink_flush_seconds";
+
+/// Label holding the dataset's table name on reader metrics.
+pub const DATASET_LABEL: &str = "dataset";
+/// Label holding the sink kind (`parquet`, `arrow`, `csv`) on flush metrics.
+pub const SINK_LABEL: &str = "sink";
+
+/// Start time of a measured span; zero-sized without the `metrics` feature.
+#[derive(Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(feature = "metrics")]
+    started: Instant,
+}
+
+impl Stopwatch {
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub(crate) fn start() -> Self {
+        Self {
+            started: Instant::now(),
+        }
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    #[inline]
+    pub(crate) const fn start() -> Self {
+        Self {}
+    }
+}
+
+/// Per-iterator handles for the page counters and histogram.
+pub(crate) struct PageMetrics {
+    #[cfg(feature = "metrics")]
+    pages_read: metrics::Counter,
+    #[cfg(feature = "metrics")]
+    rows_decoded: metrics::Counter,
+    #[cfg(feature = "metrics")]
+    decode_seconds: metrics::Histogram,
+}
+
+#[cfg(feature = "metrics")]
+impl PageMetrics {
+    pub(crate) fn new(dataset: Option<&str>) -> Self {
+        let dataset = dataset.map_or_else(String::new, |name| name.trim_end().to_owned());
+        Self {
+            pages_read: metrics::counter!(PAGES_READ, DATASET_LABEL => dataset.clone()),
+            rows_decoded: metrics::counter!(ROWS_DECODED, DATASET_LABEL => dataset.clone()),
+            decode_seconds: metrics::histogram!(PAGE_DECODE_SECONDS, DATASET_LABEL => dataset),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn page_read(&self) {
+        self.pages_read.increment(1);
+    }
+
+    #[inline]
+    pub(crate) fn page_decoded(&self, rows: u16, started: Stopwatch) {
+        self.rows_decoded.increment(u64::from(rows));
+        self.decode_seconds.record(started.started.elapsed());
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+#[allow(clippy::unused_self)]
+impl PageMetrics {
+    pub(crate) const fn new(_dataset: Option<&str>) -> Self {
+        Self {}
+    }
+
+    #[inline]
+    pub(crate) const fn page_read(&self) {}
+
+    #[inline]
+    pub(crate) const fn page_decoded(&self, _rows: u16, _started: Stopwatch) {}
+}
+
+/// Records how long `sink` took to flush since `started`.
+#[cfg(feature = "metrics")]
+#[inline]
+pub(crate) fn sink_flushed(sink: &'static str, started: Stopwatch) {
+    metrics::histogram!(SINK_FLUSH_SECONDS, SINK_LABEL => sink).record(started.started.elapsed());
+}
+
+#[cfg(not(feature = "metrics"))]
+#[inline]
+pub(crate) const fn sink_flushed(_sink: &'static str, _started: Stopwatch) {}
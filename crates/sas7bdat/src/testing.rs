@@ -0,0 +1,297 @@
+//! Snapshot helpers for testing SAS fixtures against this parser.
+//!
+//! [`collect_snapshot`] decodes a whole file into a serialisable
+//! [`Snapshot`], which works directly with snapshot libraries such as
+//! `insta`:
+//!
+//! ```ignore
+//! let snapshot = sas7bdat::testing::collect_snapshot("tests/data/claims.sas7bdat")?;
+//! insta::assert_json_snapshot!(snapshot);
+//! ```
+//!
+//! Each cell becomes a `{"kind": ..., "value": ...}` object. Dates are
+//! stored as days and datetimes and times as seconds relative to the SAS
+//! epoch (1960-01-01), rounded half-even to the microsecond, so snapshots
+//! are stable across platforms.
+//!
+//! [`verify_decode_paths`] checks that the row iterator's separate decoders
+//! agree on a file, and reports the first cell where they do not.
+
+use crate::{
+    cell::{CellValue, MissingValue},
+    dataset::MissingLiteral,
+    error::Result,
+    parser::{ColumnarBatch, TimestampRounding},
+    reader::SasReader,
+    time_conv::SAS_EPOCH,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{
+    fmt,
+    io::{Read, Seek},
+    path::Path,
+};
+use time::{Duration, OffsetDateTime};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Column names and every decoded row of a dataset.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Variable names with trailing padding removed.
+    pub columns: Vec<String>,
+    /// Row count declared in the header.
+    pub row_count: usize,
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Opens `path` and decodes every row into a [`Snapshot`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or a row fails to decode.
+pub fn collect_snapshot<P: AsRef<Path>>(path: P) -> Result<Snapshot> {
+    let mut sas = SasReader::open(path)?;
+    snapshot_reader(&mut sas)
+}
+
+/// Decodes every row of an already opened dataset into a [`Snapshot`].
+///
+/// # Errors
+///
+/// Returns an error if a row fails to decode.
+pub fn snapshot_reader<R: Read + Seek>(sas: &mut SasReader<R>) -> Result<Snapshot> {
+    let metadata = sas.metadata();
+    let columns = metadata
+        .variables
+        .iter()
+        .map(|variable| variable.name.trim_end().to_owned())
+        .collect();
+    let row_count = usize::try_from(metadata.row_count).unwrap_or(usize::MAX);
+
+    let mut rows = Vec::new();
+    let mut iterator = sas.rows()?;
+    while let Some(row) = iterator.try_next()? {
+        rows.push(row.iter().map(value_to_json).collect());
+    }
+    Ok(Snapshot {
+        columns,
+        row_count,
+        rows,
+    })
+}
+
+/// Where a decode path departs from [`RowIterator::try_next`].
+///
+/// [`RowIterator::try_next`]: crate::parser::RowIterator::try_next
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodeDivergence {
+    /// The diverging path: `"stream_all"`, `"next_columnar_batch"` or
+    /// `"next_columnar_batch_contiguous"`.
+    pub path: &'static str,
+    pub row: usize,
+    /// `None` when the path produced a different number of rows or cells.
+    pub column: Option<usize>,
+    /// Value decoded by `try_next`; `None` past its last row.
+    pub expected: Option<CellValue<'static>>,
+    /// Value decoded by `path`; `None` past its last row.
+    pub found: Option<CellValue<'static>>,
+}
+
+impl fmt::Display for DecodeDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} diverges from try_next at row {}",
+            self.path, self.row
+        )?;
+        if let Some(column) = self.column {
+            write!(f, ", column {column}")?;
+        }
+        write!(f, ": expected {:?}, found {:?}", self.expected, self.found)
+    }
+}
+
+/// Opens `path` and decodes it through every row decoding path, comparing
+/// each against [`RowIterator::try_next`].
+///
+/// Returns the first divergence, or `None` when [`RowIterator::stream_all`],
+/// [`RowIterator::next_columnar_batch`] and the column-major
+/// [`RowIterator::next_columnar_batch_contiguous`] all decode the same
+/// values.
+///
+/// [`RowIterator::try_next`]: crate::parser::RowIterator::try_next
+/// [`RowIterator::stream_all`]: crate::parser::RowIterator::stream_all
+/// [`RowIterator::next_columnar_batch`]: crate::parser::RowIterator::next_columnar_batch
+/// [`RowIterator::next_columnar_batch_contiguous`]: crate::parser::RowIterator::next_columnar_batch_contiguous
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or any path fails to
+/// decode a row.
+pub fn verify_decode_paths<P: AsRef<Path>>(path: P) -> Result<Option<DecodeDivergence>> {
+    let mut sas = SasReader::open(path)?;
+    verify_reader_decode_paths(&mut sas)
+}
+
+/// Decode-path check of [`verify_decode_paths`] for an already opened
+/// dataset.
+///
+/// # Errors
+///
+/// Returns an error if any path fails to decode a row.
+pub fn verify_reader_decode_paths<R: Read + Seek>(
+    sas: &mut SasReader<R>,
+) -> Result<Option<DecodeDivergence>> {
+    let mut expected = Vec::new();
+    let mut rows = sas.rows()?;
+    while let Some(row) = rows.try_next()? {
+        expected.push(row.into_iter().map(CellValue::into_owned).collect());
+    }
+    drop(rows);
+
+    let mut streamed = Vec::new();
+    sas.rows()?.stream_all(|row| {
+        streamed.push(
+            row.materialize()?
+                .into_iter()
+                .map(CellValue::into_owned)
+                .collect(),
+        );
+        Ok(())
+    })?;
+    if let Some(divergence) = compare_rows("stream_all", &expected, &streamed) {
+        return Ok(Some(divergence));
+    }
+
+    let mut batched = Vec::new();
+    let mut rows = sas.rows()?;
+    while let Some(batch) = rows.next_columnar_batch(0)? {
+        collect_batch(&batch, &mut batched);
+    }
+    drop(rows);
+    if let Some(divergence) = compare_rows("next_columnar_batch", &expected, &batched) {
+        return Ok(Some(divergence));
+    }
+
+    let mut contiguous = Vec::new();
+    let mut rows = sas.rows()?;
+    while let Some(batch) = rows.next_columnar_batch_contiguous(0)? {
+        collect_batch(&batch, &mut contiguous);
+    }
+    Ok(compare_rows(
+        "next_columnar_batch_contiguous",
+        &expected,
+        &contiguous,
+    ))
+}
+
+fn collect_batch(batch: &ColumnarBatch<'_>, rows: &mut Vec<Vec<CellValue<'static>>>) {
+    let columns: Vec<_> = (0..).map_while(|index| batch.column(index)).collect();
+    rows.extend((0..batch.row_count).map(|row| {
+        columns
+            .iter()
+            .filter_map(|column| column.decode_cell(row).map(CellValue::into_owned))
+            .collect()
+    }));
+}
+
+fn compare_rows(
+    path: &'static str,
+    expected: &[Vec<CellValue<'static>>],
+    found: &[Vec<CellValue<'static>>],
+) -> Option<DecodeDivergence> {
+    let divergence =
+        |row, column, expected: Option<&CellValue<'static>>, found: Option<&CellValue<'static>>| {
+            DecodeDivergence {
+                path,
+                row,
+                column,
+                expected: expected.cloned(),
+                found: found.cloned(),
+            }
+        };
+    for row in 0..expected.len().max(found.len()) {
+        let (Some(expected_row), Some(found_row)) = (expected.get(row), found.get(row)) else {
+            return Some(divergence(row, None, None, None));
+        };
+        for column in 0..expected_row.len().max(found_row.len()) {
+            let (expected, found) = (expected_row.get(column), found_row.get(column));
+            if !expected
+                .zip(found)
+                .is_some_and(|(left, right)| same_cell(left, right))
+            {
+                let column = (expected.is_some() && found.is_some()).then_some(column);
+                return Some(divergence(row, column, expected, found));
+            }
+        }
+    }
+    None
+}
+
+/// Equality that also holds between identical NaNs, which tagged missing
+/// values carry as their literal.
+fn same_cell(left: &CellValue<'_>, right: &CellValue<'_>) -> bool {
+    match (left, right) {
+        (CellValue::Float(left), CellValue::Float(right)) => left.to_bits() == right.to_bits(),
+        (
+            CellValue::Missing(MissingValue::Tagged(left)),
+            CellValue::Missing(MissingValue::Tagged(right)),
+        ) => left.tag == right.tag && same_literal(&left.literal, &right.literal),
+        (
+            CellValue::Missing(MissingValue::Range {
+                lower: left_lower,
+                upper: left_upper,
+            }),
+            CellValue::Missing(MissingValue::Range {
+                lower: right_lower,
+                upper: right_upper,
+            }),
+        ) => same_literal(left_lower, right_lower) && same_literal(left_upper, right_upper),
+        _ => left == right,
+    }
+}
+
+fn same_literal(left: &MissingLiteral, right: &MissingLiteral) -> bool {
+    match (left, right) {
+        (MissingLiteral::Numeric(left), MissingLiteral::Numeric(right)) => {
+            left.to_bits() == right.to_bits()
+        }
+        _ => left == right,
+    }
+}
+
+/// Converts one cell into the `{"kind": ..., "value": ...}` form used by
+/// [`Snapshot`] rows.
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn value_to_json(value: &CellValue<'_>) -> Value {
+    match value {
+        CellValue::Float(v) => json!({ "kind": "number", "value": *v }),
+        CellValue::Int32(v) => json!({ "kind": "number", "value": f64::from(*v) }),
+        CellValue::Int64(v) => json!({ "kind": "number", "value": *v as f64 }),
+        CellValue::NumericString(s) | CellValue::Str(s) => {
+            json!({ "kind": "string", "value": s })
+        }
+        CellValue::Bytes(bytes) => json!({ "kind": "bytes", "value": &bytes[..] }),
+        CellValue::DateTime(dt) => json!({ "kind": "datetime", "value": datetime_to_seconds(dt) }),
+        CellValue::Date(dt) => json!({
+            "kind": "date",
+            "value": datetime_to_seconds(dt) / SECONDS_PER_DAY
+        }),
+        CellValue::Time(duration) => {
+            json!({ "kind": "time", "value": duration_to_seconds(*duration) })
+        }
+        CellValue::Missing(_) => json!({ "kind": "missing", "value": null }),
+    }
+}
+
+fn datetime_to_seconds(dt: &OffsetDateTime) -> f64 {
+    duration_to_seconds(*dt - SAS_EPOCH)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn duration_to_seconds(duration: Duration) -> f64 {
+    TimestampRounding::HalfEven.duration_micros(duration) as f64 / 1_000_000.0
+}
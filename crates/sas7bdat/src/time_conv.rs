@@ -0,0 +1,133 @@
+//! Conversions between SAS date, datetime and time values and [`time`] types.
+//!
+//! SAS stores dates as days and datetimes as seconds relative to
+//! 1960-01-01 00:00 UTC, and times as seconds since midnight. These are the
+//! conversions the decoder applies to [`CellValue::Date`],
+//! [`CellValue::DateTime`] and [`CellValue::Time`], together with their
+//! inverses, so callers get the same epoch and rounding as the reader.
+//!
+//! [`CellValue::Date`]: crate::CellValue::Date
+//! [`CellValue::DateTime`]: crate::CellValue::DateTime
+//! [`CellValue::Time`]: crate::CellValue::Time
+
+use time::{Duration, OffsetDateTime, macros::datetime};
+
+/// The SAS epoch, 1960-01-01 00:00 UTC.
+pub const SAS_EPOCH: OffsetDateTime = datetime!(1960-01-01 0:00 UTC);
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Converts a SAS date (days since the epoch) to the UTC timestamp a
+/// [`CellValue::Date`](crate::CellValue::Date) carries.
+///
+/// A fractional part is kept as a time of day. Returns `None` for
+/// non-finite values and values outside the range of [`OffsetDateTime`].
+#[must_use]
+pub fn sas_days_to_date(days: f64) -> Option<OffsetDateTime> {
+    sas_seconds_to_datetime(days * SECONDS_PER_DAY)
+}
+
+/// Converts a SAS datetime (seconds since the epoch) to a UTC timestamp
+/// rounded to the nearest nanosecond.
+///
+/// Returns `None` for non-finite values and values outside the range of
+/// [`OffsetDateTime`].
+#[must_use]
+pub fn sas_seconds_to_datetime(seconds: f64) -> Option<OffsetDateTime> {
+    SAS_EPOCH.checked_add(sas_seconds_to_time(seconds)?)
+}
+
+/// Converts a SAS time (seconds since midnight) to a [`Duration`] rounded to
+/// the nearest nanosecond.
+///
+/// `Duration::seconds_f64` truncates the binary fraction, so a value such as
+/// `0.000001`, which a double stores just below one microsecond, would lose
+/// that microsecond. Splitting off the whole seconds first keeps the
+/// fraction exact before it is scaled and rounded.
+///
+/// Returns `None` for non-finite values and magnitudes beyond `i64` seconds.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn sas_seconds_to_time(seconds: f64) -> Option<Duration> {
+    // The bound keeps the whole seconds within `i64`.
+    if !seconds.is_finite() || seconds.abs() >= 9.0e18 {
+        return None;
+    }
+    let whole = seconds.trunc();
+    let nanos = ((seconds - whole) * 1_000_000_000.0).round();
+    Duration::seconds(whole as i64).checked_add(Duration::nanoseconds(nanos as i64))
+}
+
+/// Converts a timestamp to a SAS date (days since the epoch), keeping the
+/// time of day as a fraction; the inverse of [`sas_days_to_date`].
+#[must_use]
+pub fn date_to_sas_days(date: &OffsetDateTime) -> f64 {
+    datetime_to_sas_seconds(date) / SECONDS_PER_DAY
+}
+
+/// Converts a timestamp to a SAS datetime (seconds since the epoch); the
+/// inverse of [`sas_seconds_to_datetime`].
+#[must_use]
+pub fn datetime_to_sas_seconds(datetime: &OffsetDateTime) -> f64 {
+    (*datetime - SAS_EPOCH).as_seconds_f64()
+}
+
+/// Converts a time of day to a SAS time (seconds since midnight); the
+/// inverse of [`sas_seconds_to_time`].
+#[must_use]
+pub const fn time_to_sas_seconds(time: Duration) -> f64 {
+    time.as_seconds_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn epoch_is_day_zero() {
+        assert_eq!(sas_days_to_date(0.0), Some(SAS_EPOCH));
+        assert_eq!(sas_seconds_to_datetime(0.0), Some(SAS_EPOCH));
+        assert_eq!(date_to_sas_days(&SAS_EPOCH), 0.0);
+        assert_eq!(datetime_to_sas_seconds(&SAS_EPOCH), 0.0);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn dates_round_trip_across_the_epoch() {
+        for (days, expected) in [
+            (-1.0, date!(1959 - 12 - 31)),
+            (3_653.0, date!(1970 - 01 - 01)),
+            (21_915.0, date!(2020 - 01 - 01)),
+        ] {
+            let converted = sas_days_to_date(days).expect("date in range");
+            assert_eq!(converted.date(), expected);
+            assert_eq!(date_to_sas_days(&converted), days);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn datetimes_round_to_the_nearest_nanosecond() {
+        let converted = sas_seconds_to_datetime(86_400.000_001).expect("datetime in range");
+        assert_eq!(converted, datetime!(1960-01-02 0:00:00.000_001 UTC));
+        assert_eq!(datetime_to_sas_seconds(&converted), 86_400.000_001);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn times_round_trip() {
+        let time = sas_seconds_to_time(45_296.5).expect("finite time");
+        assert_eq!(time, Duration::new(45_296, 500_000_000));
+        assert_eq!(time_to_sas_seconds(time), 45_296.5);
+    }
+
+    #[test]
+    fn unrepresentable_values_are_rejected() {
+        assert_eq!(sas_days_to_date(f64::NAN), None);
+        assert_eq!(sas_seconds_to_datetime(f64::INFINITY), None);
+        assert_eq!(sas_seconds_to_datetime(1.0e17), None);
+        assert_eq!(sas_seconds_to_time(9.5e18), None);
+    }
+}
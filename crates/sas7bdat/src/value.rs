@@ -0,0 +1,499 @@
+use crate::{
+    cell::{CellValue, MissingValue},
+    dataset::MissingLiteral,
+    time_conv::{date_to_sas_days, datetime_to_sas_seconds, time_to_sas_seconds},
+};
+use std::cmp::Ordering;
+
+const KEY_MISSING: u8 = 0;
+const KEY_NUMBER: u8 = 1;
+const KEY_TEXT: u8 = 2;
+
+/// Compares two cells with SAS semantics.
+///
+/// - Numeric cells compare by their SAS value: dates as days and datetimes
+///   and times as seconds since the SAS epoch, so `Int32(1)`, `Int64(1)` and
+///   `Float(1.0)` are equal.
+/// - Missing values sort below every number, in SAS order:
+///   `._` < `.` < `.A` < … < `.Z`. A `NaN` float counts as `.`.
+/// - Character values compare as if the shorter one were padded with blanks,
+///   so `"AB"` equals `"AB  "`. A blank string is the character missing
+///   value and equals `.`.
+/// - Character values sort after numbers; SAS never compares the two, but a
+///   total order keeps sorting and deduplication well defined.
+#[must_use]
+pub fn compare(left: &CellValue<'_>, right: &CellValue<'_>) -> Ordering {
+    SasValue::of(left).cmp(&SasValue::of(right))
+}
+
+/// Returns `true` when [`compare`] considers the cells equal.
+#[must_use]
+pub fn equals(left: &CellValue<'_>, right: &CellValue<'_>) -> bool {
+    compare(left, right) == Ordering::Equal
+}
+
+/// Appends a self-delimiting key for `value` to `out`.
+///
+/// Two cells produce the same bytes exactly when [`equals`] holds, so
+/// concatenated keys can be hashed to group rows by several columns.
+pub fn encode_key(value: &CellValue<'_>, out: &mut Vec<u8>) {
+    match SasValue::of(value) {
+        SasValue::Missing(missing) => {
+            out.push(KEY_MISSING);
+            out.push(missing.class);
+            out.extend_from_slice(&missing.tag.to_le_bytes());
+            if let Some((lower, upper)) = missing.range {
+                encode_literal(lower, out);
+                encode_literal(upper, out);
+            }
+        }
+        SasValue::Number(number) => encode_number(number, out),
+        SasValue::Text(text) => encode_text(text, out),
+    }
+}
+
+/// Digit grouping and decimal marks accepted by [`parse_sas_number`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// `1,234.56`: a point marks decimals; commas, spaces and apostrophes
+    /// group thousands.
+    #[default]
+    Point,
+    /// `1.234,56` or `1 234,56`: a comma marks decimals; points, spaces and
+    /// apostrophes group thousands.
+    Comma,
+}
+
+impl NumberLocale {
+    const fn decimal_mark(self) -> char {
+        match self {
+            Self::Point => '.',
+            Self::Comma => ',',
+        }
+    }
+
+    const fn is_group_mark(self, ch: char) -> bool {
+        let locale_mark = match self {
+            Self::Point => ',',
+            Self::Comma => '.',
+        };
+        ch == locale_mark || matches!(ch, ' ' | '\'' | '\u{a0}' | '\u{202f}')
+    }
+}
+
+/// Parses numeric text as stored in character columns, such as
+/// `"1 234,56"`, `"-1,234.5"` or `"1.5E-3"`.
+///
+/// Surrounding whitespace is ignored. Group marks must separate the integer
+/// part into groups of three digits, so `"1,5"` is rejected rather than read
+/// as `15` under [`NumberLocale::Point`]. Returns `None` for blank or
+/// malformed text.
+#[must_use]
+pub fn parse_sas_number(text: &str, locale: NumberLocale) -> Option<f64> {
+    let mut chars = text.trim().chars().peekable();
+    let mut normalized = String::with_capacity(text.len());
+    if let Some(&sign @ ('+' | '-')) = chars.peek() {
+        normalized.push(sign);
+        chars.next();
+    }
+
+    let mut group_len = 0usize;
+    let mut grouped = false;
+    while let Some(&ch) = chars.peek() {
+        if ch.is_ascii_digit() {
+            normalized.push(ch);
+            group_len += 1;
+        } else if locale.is_group_mark(ch) {
+            if group_len == 0 || group_len > 3 || (grouped && group_len != 3) {
+                return None;
+            }
+            grouped = true;
+            group_len = 0;
+        } else {
+            break;
+        }
+        chars.next();
+    }
+    if grouped && group_len != 3 {
+        return None;
+    }
+
+    if chars.next_if_eq(&locale.decimal_mark()).is_some() {
+        normalized.push('.');
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            normalized.push(digit);
+        }
+    }
+    if chars.next_if(|ch| matches!(ch, 'e' | 'E')).is_some() {
+        normalized.push('e');
+        if let Some(sign) = chars.next_if(|ch| matches!(ch, '+' | '-')) {
+            normalized.push(sign);
+        }
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            normalized.push(digit);
+        }
+    }
+    if chars.next().is_some() {
+        return None;
+    }
+    normalized.parse().ok()
+}
+
+/// A cell reduced to what SAS compares.
+enum SasValue<'a> {
+    Missing(MissingKey<'a>),
+    Number(f64),
+    Text(&'a [u8]),
+}
+
+impl<'a> SasValue<'a> {
+    fn of(value: &'a CellValue<'_>) -> Self {
+        match value {
+            CellValue::Float(number) => Self::number(*number),
+            CellValue::Int32(number) => Self::Number(f64::from(*number)),
+            #[allow(clippy::cast_precision_loss)]
+            CellValue::Int64(number) => Self::Number(*number as f64),
+            CellValue::NumericString(text) => parse_sas_number(text, NumberLocale::Point)
+                .map_or_else(|| Self::text(text.as_bytes()), Self::number),
+            CellValue::Str(text) => Self::text(text.as_bytes()),
+            CellValue::Bytes(bytes) => Self::text(bytes),
+            CellValue::DateTime(datetime) => Self::Number(datetime_to_sas_seconds(datetime)),
+            CellValue::Date(date) => Self::Number(date_to_sas_days(date)),
+            CellValue::Time(duration) => Self::Number(time_to_sas_seconds(*duration)),
+            CellValue::Missing(missing) => Self::Missing(MissingKey::of(missing)),
+        }
+    }
+
+    const fn number(number: f64) -> Self {
+        if number.is_nan() {
+            Self::Missing(MissingKey::SYSTEM)
+        } else {
+            Self::Number(number)
+        }
+    }
+
+    fn text(bytes: &'a [u8]) -> Self {
+        let trimmed = trim_blanks(bytes);
+        if trimmed.is_empty() {
+            Self::Missing(MissingKey::SYSTEM)
+        } else {
+            Self::Text(trimmed)
+        }
+    }
+
+    const fn rank(&self) -> u8 {
+        match self {
+            Self::Missing(_) => 0,
+            Self::Number(_) => 1,
+            Self::Text(_) => 2,
+        }
+    }
+}
+
+impl Ord for SasValue<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Missing(left), Self::Missing(right)) => left.cmp(right),
+            (Self::Number(left), Self::Number(right)) => compare_numbers(*left, *right),
+            (Self::Text(left), Self::Text(right)) => compare_padded(left, right),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for SasValue<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SasValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SasValue<'_> {}
+
+/// Position of a missing value in SAS sort order.
+struct MissingKey<'a> {
+    /// `._`, `.`, `.A`-`.Z`, other tags, then declared ranges.
+    class: u8,
+    tag: u32,
+    range: Option<(&'a MissingLiteral, &'a MissingLiteral)>,
+}
+
+impl<'a> MissingKey<'a> {
+    const SYSTEM: Self = Self {
+        class: 1,
+        tag: 0,
+        range: None,
+    };
+
+    fn of(missing: &'a MissingValue) -> Self {
+        match missing {
+            MissingValue::System => Self::SYSTEM,
+            MissingValue::Tagged(tagged) => match tagged.tag.map(|tag| tag.to_ascii_uppercase()) {
+                None | Some('.') => Self::SYSTEM,
+                Some('_') => Self {
+                    class: 0,
+                    tag: 0,
+                    range: None,
+                },
+                Some(tag @ 'A'..='Z') => Self {
+                    class: 2,
+                    tag: u32::from(tag),
+                    range: None,
+                },
+                Some(tag) => Self {
+                    class: 3,
+                    tag: u32::from(tag),
+                    range: None,
+                },
+            },
+            MissingValue::Range { lower, upper } => Self {
+                class: 4,
+                tag: 0,
+                range: Some((lower, upper)),
+            },
+        }
+    }
+}
+
+impl Ord for MissingKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.class, self.tag)
+            .cmp(&(other.class, other.tag))
+            .then_with(|| match (self.range, other.range) {
+                (Some((left_lower, left_upper)), Some((right_lower, right_upper))) => {
+                    compare_literals(left_lower, right_lower)
+                        .then_with(|| compare_literals(left_upper, right_upper))
+                }
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for MissingKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for MissingKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MissingKey<'_> {}
+
+fn compare_literals(left: &MissingLiteral, right: &MissingLiteral) -> Ordering {
+    match (left, right) {
+        (MissingLiteral::Numeric(left), MissingLiteral::Numeric(right)) => {
+            compare_numbers(*left, *right)
+        }
+        (MissingLiteral::String(left), MissingLiteral::String(right)) => {
+            compare_padded(left.as_bytes(), right.as_bytes())
+        }
+        (MissingLiteral::Numeric(_), MissingLiteral::String(_)) => Ordering::Less,
+        (MissingLiteral::String(_), MissingLiteral::Numeric(_)) => Ordering::Greater,
+    }
+}
+
+/// Orders non-`NaN` numbers, treating `-0.0` and `0.0` as equal.
+fn compare_numbers(left: f64, right: f64) -> Ordering {
+    left.partial_cmp(&right)
+        .unwrap_or_else(|| left.total_cmp(&right))
+}
+
+/// Compares byte strings as if the shorter were padded with blanks.
+fn compare_padded(left: &[u8], right: &[u8]) -> Ordering {
+    let (left, right) = (trim_blanks(left), trim_blanks(right));
+    let common = left.len().min(right.len());
+    left[..common]
+        .cmp(&right[..common])
+        .then_with(|| match left.len().cmp(&right.len()) {
+            Ordering::Greater => tail_against_blanks(&left[common..]),
+            Ordering::Less => tail_against_blanks(&right[common..]).reverse(),
+            Ordering::Equal => Ordering::Equal,
+        })
+}
+
+fn tail_against_blanks(tail: &[u8]) -> Ordering {
+    tail.iter()
+        .find(|&&byte| byte != b' ')
+        .map_or(Ordering::Equal, |byte| byte.cmp(&b' '))
+}
+
+fn trim_blanks(bytes: &[u8]) -> &[u8] {
+    let end = bytes
+        .iter()
+        .rposition(|&byte| byte != b' ')
+        .map_or(0, |index| index + 1);
+    &bytes[..end]
+}
+
+fn encode_number(number: f64, out: &mut Vec<u8>) {
+    // `-0.0 == 0.0`, so both must share a key.
+    let number = if number == 0.0 { 0.0 } else { number };
+    out.push(KEY_NUMBER);
+    out.extend_from_slice(&number.to_bits().to_le_bytes());
+}
+
+fn encode_text(text: &[u8], out: &mut Vec<u8>) {
+    let text = trim_blanks(text);
+    out.push(KEY_TEXT);
+    out.extend_from_slice(&(text.len() as u64).to_le_bytes());
+    out.extend_from_slice(text);
+}
+
+fn encode_literal(literal: &MissingLiteral, out: &mut Vec<u8>) {
+    match literal {
+        MissingLiteral::Numeric(number) => encode_number(*number, out),
+        MissingLiteral::String(text) => encode_text(text.as_bytes(), out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::TaggedMissing;
+    use crate::time_conv::SAS_EPOCH;
+    use std::borrow::Cow;
+    use time::Duration;
+
+    fn tagged(tag: char) -> CellValue<'static> {
+        CellValue::Missing(MissingValue::Tagged(TaggedMissing {
+            tag: Some(tag),
+            literal: MissingLiteral::Numeric(f64::NAN),
+        }))
+    }
+
+    fn key(value: &CellValue<'_>) -> Vec<u8> {
+        let mut out = Vec::new();
+        encode_key(value, &mut out);
+        out
+    }
+
+    #[test]
+    fn missing_values_sort_below_numbers_in_sas_order() {
+        let ordered = [
+            tagged('_'),
+            CellValue::Missing(MissingValue::System),
+            tagged('A'),
+            tagged('z'),
+            CellValue::Float(f64::NEG_INFINITY),
+            CellValue::Int32(-1),
+            CellValue::Float(0.5),
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(compare(&pair[0], &pair[1]), Ordering::Less, "{pair:?}");
+        }
+        assert!(equals(
+            &CellValue::Float(f64::NAN),
+            &CellValue::Missing(MissingValue::System)
+        ));
+    }
+
+    #[test]
+    fn strings_compare_blank_padded() {
+        let short = CellValue::Str(Cow::Borrowed("AB"));
+        let padded = CellValue::Str(Cow::Borrowed("AB   "));
+        let tab = CellValue::Str(Cow::Borrowed("AB\t"));
+        assert!(equals(&short, &padded));
+        assert_eq!(compare(&tab, &short), Ordering::Less);
+        assert_eq!(
+            compare(&short, &CellValue::Str("ABC".into())),
+            Ordering::Less
+        );
+        assert!(equals(
+            &CellValue::Str(Cow::Borrowed("   ")),
+            &CellValue::Missing(MissingValue::System)
+        ));
+        assert_eq!(
+            compare(&CellValue::Float(1e300), &short),
+            Ordering::Less,
+            "numbers sort before text"
+        );
+    }
+
+    #[test]
+    fn numeric_representations_agree() {
+        assert!(equals(&CellValue::Int32(3), &CellValue::Float(3.0)));
+        assert!(equals(
+            &CellValue::Int64(3),
+            &CellValue::NumericString("3".into())
+        ));
+        assert!(equals(&CellValue::Float(-0.0), &CellValue::Float(0.0)));
+        assert!(equals(
+            &CellValue::Date(SAS_EPOCH + Duration::days(2)),
+            &CellValue::Int32(2)
+        ));
+        assert!(equals(
+            &CellValue::Time(Duration::seconds(90)),
+            &CellValue::Float(90.0)
+        ));
+    }
+
+    #[test]
+    fn keys_match_equality() {
+        let values = [
+            CellValue::Int32(1),
+            CellValue::Float(1.0),
+            CellValue::Float(-0.0),
+            CellValue::Int64(0),
+            CellValue::Str("x ".into()),
+            CellValue::Bytes(Cow::Borrowed(b"x")),
+            CellValue::Str(" ".into()),
+            CellValue::Missing(MissingValue::System),
+            tagged('a'),
+            tagged('A'),
+            tagged('_'),
+        ];
+        for left in &values {
+            for right in &values {
+                assert_eq!(
+                    key(left) == key(right),
+                    equals(left, right),
+                    "{left:?} vs {right:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parses_locale_numbers() {
+        let point = |text| parse_sas_number(text, NumberLocale::Point);
+        let comma = |text| parse_sas_number(text, NumberLocale::Comma);
+        assert_eq!(point(" 1,234.5 "), Some(1_234.5));
+        assert_eq!(point("-1 234 567"), Some(-1_234_567.0));
+        assert_eq!(point("+.5"), Some(0.5));
+        assert_eq!(point("1.5E-3"), Some(0.0015));
+        assert_eq!(comma("1 234,56"), Some(1_234.56));
+        assert_eq!(comma("1.234.567,5"), Some(1_234_567.5));
+        assert_eq!(comma("1\u{a0}234,5e2"), Some(123_450.0));
+        for malformed in [
+            "",
+            " ",
+            "1,5",
+            "12,34,567",
+            ",123",
+            "1.2.3",
+            "1e",
+            "abc",
+            "1-",
+        ] {
+            assert_eq!(point(malformed), None, "{malformed:?}");
+        }
+        assert_eq!(comma("1,234.5"), None);
+    }
+
+    #[test]
+    fn numeric_strings_with_group_marks_compare_as_numbers() {
+        assert!(equals(
+            &CellValue::NumericString("1,234".into()),
+            &CellValue::Float(1_234.0)
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+#![cfg(feature = "arrow")]
+
+use arrow_array::{
+    Array, Date32Array, RecordBatch, TimestampMicrosecondArray,
+    cast::AsArray,
+    types::{Float64Type, TimestampMicrosecondType},
+};
+use arrow_schema::{DataType, TimeUnit};
+use sas7bdat::{
+    ArrowSink, ColumnType, ColumnarSink, RowSink, SasReader, SinkContext,
+    dataset::{Alignment, Measure, RepeatedGroup},
+    decode_layout,
+    sinks::{SAS_ALIGNMENT_KEY, SAS_MEASURE_KEY},
+};
+use sas7bdat_test_support::common;
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom},
+};
+
+fn row_batches(file: &str, batch_rows: usize) -> Vec<RecordBatch> {
+    let path = common::fixture_path("fixtures/raw_data/pandas").join(file);
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let mut sink = ArrowSink::new().with_batch_rows(batch_rows);
+    sas.stream_into(&mut sink).expect("stream rows");
+    sink.into_batches()
+}
+
+fn columnar_batches(file: &str, sink: ArrowSink) -> Vec<RecordBatch> {
+    columnar_batches_as(file, sink, &[])
+}
+
+/// Like [`columnar_batches`], decoding the listed columns as other types.
+fn columnar_batches_as(
+    file: &str,
+    mut sink: ArrowSink,
+    overrides: &[(usize, ColumnType)],
+) -> Vec<RecordBatch> {
+    let path = common::fixture_path("fixtures/raw_data/pandas").join(file);
+    let mut source = File::open(path).expect("open fixture");
+    let mut layout = decode_layout(&mut source).expect("layout");
+    for &(index, column_type) in overrides {
+        layout.columns[index].kind = column_type.column_kind();
+    }
+    source.seek(SeekFrom::Start(0)).expect("rewind");
+    let selection: Vec<usize> = (0..layout.columns.len()).collect();
+
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    let mut rows = layout.row_iterator(&mut source).expect("row iterator");
+    while let Some(batch) = rows.next_columnar_batch(3).expect("batch") {
+        sink.write_columnar_batch(&batch, &selection)
+            .expect("write batch");
+    }
+    sink.finish().expect("finish");
+    sink.into_batches()
+}
+
+#[test]
+fn temporal_columns_use_arrow_logical_types() {
+    let batches = row_batches("datetime.sas7bdat", usize::MAX);
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Date32);
+    assert_eq!(
+        schema.field(2).data_type(),
+        &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+    );
+
+    // Second fixture row is the SAS epoch, 1960-01-01.
+    let dates: &Date32Array = batch.column(0).as_primitive();
+    assert_eq!(dates.value(1), -3653);
+    let timestamps: &TimestampMicrosecondArray =
+        batch.column(2).as_primitive::<TimestampMicrosecondType>();
+    assert_eq!(timestamps.value(1), -3653 * 86_400 * 1_000_000);
+    assert_eq!(timestamps.null_count(), 0);
+}
+
+#[test]
+fn columnar_batches_match_row_output() {
+    for file in ["datetime.sas7bdat", "airline.sas7bdat", "test1.sas7bdat"] {
+        let rows = row_batches(file, usize::MAX);
+        let columnar = columnar_batches(file, ArrowSink::new().with_batch_rows(usize::MAX));
+        assert_eq!(rows, columnar, "{file}");
+    }
+}
+
+#[test]
+fn numeric_text_columns_become_strings() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let name = sas.metadata().variables[0].name.trim_end().to_owned();
+    sas.override_column_type(&name, ColumnType::NumericText)
+        .expect("read as text");
+    let mut sink = ArrowSink::new().with_batch_rows(usize::MAX);
+    sas.stream_into(&mut sink).expect("stream rows");
+    let rows = sink.into_batches();
+    let columnar = columnar_batches_as(
+        "airline.sas7bdat",
+        ArrowSink::new().with_batch_rows(usize::MAX),
+        &[(0, ColumnType::NumericText)],
+    );
+    assert_eq!(rows, columnar);
+
+    let plain = row_batches("airline.sas7bdat", usize::MAX);
+    let numbers = plain[0].column(0).as_primitive::<Float64Type>();
+    let texts = rows[0].column(0).as_string::<i32>();
+    assert_eq!(texts.len(), numbers.len());
+    for (text, number) in texts.iter().zip(numbers.iter()) {
+        let number = number.expect("no missing values");
+        assert_eq!(text.expect("rendered"), number.to_string());
+    }
+}
+
+#[test]
+fn repeated_groups_become_list_columns() {
+    // Column1 and Column3 are both BEST-formatted numerics.
+    let group = || RepeatedGroup::new("GROUPED", ["Column1", "Column3"]);
+    let flat = row_batches("test1.sas7bdat", usize::MAX);
+    let flat_schema = flat[0].schema();
+    let first = flat_schema.index_of("Column1").expect("Column1");
+    let third = flat_schema.index_of("Column3").expect("Column3");
+
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let mut sink = ArrowSink::new()
+        .with_batch_rows(usize::MAX)
+        .with_repeated_groups([group()]);
+    sas.stream_into(&mut sink).expect("stream rows");
+    let grouped = sink.into_batches();
+    let columnar = columnar_batches(
+        "test1.sas7bdat",
+        ArrowSink::new()
+            .with_batch_rows(usize::MAX)
+            .with_repeated_groups([group()]),
+    );
+    assert_eq!(grouped, columnar);
+
+    let batch = &grouped[0];
+    assert_eq!(batch.num_columns(), flat[0].num_columns() - 1);
+    assert_eq!(batch.schema().field(first).name(), "GROUPED");
+    let lists = batch.column(first).as_list::<i32>();
+    assert_eq!(lists.len(), flat[0].num_rows());
+    for row in 0..lists.len() {
+        let list = lists.value(row);
+        let list = list.as_primitive::<Float64Type>();
+        for (position, member) in [first, third].into_iter().enumerate() {
+            let expected = flat[0].column(member).as_primitive::<Float64Type>();
+            assert_eq!(list.is_null(position), expected.is_null(row), "row {row}");
+            if !expected.is_null(row) {
+                assert_eq!(
+                    list.value(position).to_bits(),
+                    expected.value(row).to_bits(),
+                    "row {row}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn batches_are_split_at_configured_size() {
+    let batches = row_batches("airline.sas7bdat", 10);
+    let sizes: Vec<usize> = batches.iter().map(RecordBatch::num_rows).collect();
+    let total: usize = sizes.iter().sum();
+    assert!(sizes.len() > 1);
+    assert!(sizes[..sizes.len() - 1].iter().all(|&size| size == 10));
+    assert_eq!(
+        total,
+        row_batches("airline.sas7bdat", usize::MAX)[0].num_rows()
+    );
+}
+
+#[test]
+fn string_view_columns_match_utf8_output() {
+    for file in ["productsales.sas7bdat", "test1.sas7bdat"] {
+        let expected = row_batches(file, usize::MAX);
+        let views = columnar_batches(
+            file,
+            ArrowSink::new()
+                .with_batch_rows(usize::MAX)
+                .with_string_view(true),
+        );
+        let (expected, views) = (&expected[0], &views[0]);
+        let mut checked = 0;
+        for (index, field) in expected.schema().fields().iter().enumerate() {
+            if field.data_type() != &DataType::Utf8 {
+                assert_eq!(expected.column(index), views.column(index), "{file}");
+                continue;
+            }
+            assert_eq!(views.schema().field(index).data_type(), &DataType::Utf8View);
+            let utf8 = expected.column(index).as_string::<i32>();
+            let view = views.column(index).as_string_view();
+            assert_eq!(
+                utf8.iter().collect::<Vec<_>>(),
+                view.iter().collect::<Vec<_>>()
+            );
+            checked += 1;
+        }
+        assert!(checked > 0, "{file} has character columns");
+    }
+}
+
+#[test]
+fn measure_and_alignment_become_field_metadata() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut source = File::open(path).expect("open fixture");
+    let mut layout = decode_layout(&mut source).expect("layout");
+    let variables = &mut layout.header.metadata.variables;
+    variables[0].measure = Measure::Ordinal;
+    variables[0].alignment = Alignment::Right;
+    variables[1].measure = Measure::Nominal;
+
+    let mut sink = ArrowSink::new();
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    let schema = sink.schema().expect("schema");
+
+    let first = schema.field(0).metadata();
+    assert_eq!(
+        first.get(SAS_MEASURE_KEY).map(String::as_str),
+        Some("ordinal")
+    );
+    assert_eq!(
+        first.get(SAS_ALIGNMENT_KEY).map(String::as_str),
+        Some("right")
+    );
+    let second = schema.field(1).metadata();
+    assert_eq!(
+        second.get(SAS_MEASURE_KEY).map(String::as_str),
+        Some("nominal")
+    );
+    assert!(!second.contains_key(SAS_ALIGNMENT_KEY));
+    assert!(schema.field(2).metadata().is_empty());
+}
+
+#[test]
+fn low_memory_streaming_rejects_buffered_batches() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let mut sink = ArrowSink::new();
+    let Err(err) = sas.stream_into_low_memory(&mut sink) else {
+        panic!("ArrowSink keeps every batch in memory");
+    };
+    assert!(matches!(err, sas7bdat::Error::Unsupported { .. }), "{err}");
+    assert!(sink.batches().is_empty());
+}
@@ -0,0 +1,185 @@
+use sas7bdat::{
+    CancellationToken, DedupKeep, DedupSink, Error, ErrorCategory, JsonlSink, RowSelection,
+    RowSink, SasReader, reader::ProgressEvent,
+};
+use sas7bdat_test_support::common;
+use std::{
+    ops::ControlFlow,
+    sync::{Arc, atomic::AtomicBool},
+};
+
+fn open_multi_page() -> SasReader<std::fs::File> {
+    let path = common::fixture_path("fixtures/raw_data/pandas/load_log.sas7bdat");
+    SasReader::open(path).expect("open fixture")
+}
+
+/// Cancels `token` once the stream reports its second page.
+fn cancel_on_second_page(sas: &mut SasReader<std::fs::File>, token: &CancellationToken) {
+    let token = token.clone();
+    sas.set_progress_observer(move |event: &ProgressEvent| {
+        if event.pages_processed >= 2 {
+            token.cancel();
+        }
+        ControlFlow::Continue(())
+    });
+}
+
+/// Streams the fixture into `sink`, cancelling it on the second page.
+fn stream_cancelled<S: RowSink>(sink: &mut S) {
+    let mut sas = open_multi_page();
+    let token = CancellationToken::new();
+    cancel_on_second_page(&mut sas, &token);
+    let err = sas
+        .stream_into_with_cancel(sink, &token)
+        .expect_err("stream is cancelled");
+    assert!(matches!(err, Error::Cancelled));
+    assert_eq!(err.category(), ErrorCategory::Cancelled);
+}
+
+/// Rows written before cancellation: some, but not all 2097.
+fn assert_partial(rows: usize) {
+    assert!(rows > 0 && rows < 2097, "{rows} rows");
+}
+
+#[test]
+fn aborted_jsonl_holds_complete_lines() {
+    let mut sink = JsonlSink::new(Vec::new());
+    stream_cancelled(&mut sink);
+
+    let output =
+        String::from_utf8(sink.into_inner().expect("aborted sink output")).expect("utf-8 output");
+    assert!(output.ends_with('\n'));
+    for line in output.lines() {
+        serde_json::from_str::<serde_json::Value>(line).expect("complete JSON line");
+    }
+    assert_partial(output.lines().count());
+}
+
+#[test]
+fn aborted_dedup_drops_the_rows_it_holds_back() {
+    let mut sink =
+        DedupSink::new(JsonlSink::new(Vec::new()), ["operation"]).with_keep(DedupKeep::Last);
+    stream_cancelled(&mut sink);
+    let output = sink.into_inner().into_inner().expect("aborted sink output");
+    assert!(output.is_empty());
+}
+
+#[test]
+fn cancelled_token_stops_a_selection_before_any_row() {
+    let mut sas = open_multi_page();
+    let token = CancellationToken::from(Arc::new(AtomicBool::new(true)));
+    assert!(token.is_cancelled());
+
+    let mut sink = JsonlSink::new(Vec::new());
+    let selection = RowSelection::new().skip_rows(10);
+    let err = sas
+        .stream_selection_into_with_cancel(&selection, &mut sink, &token)
+        .expect_err("stream is cancelled");
+    assert!(matches!(err, Error::Cancelled));
+    assert!(sink.into_inner().expect("aborted sink output").is_empty());
+
+    // An uncancelled token streams every row.
+    let mut sink = JsonlSink::new(Vec::new());
+    sas.stream_into_with_cancel(&mut sink, &CancellationToken::new())
+        .expect("stream rows");
+    let output = sink.into_inner().expect("finished sink");
+    assert_eq!(
+        String::from_utf8(output)
+            .expect("utf-8 output")
+            .lines()
+            .count(),
+        2097
+    );
+}
+
+#[cfg(all(feature = "csv", feature = "gzip"))]
+#[test]
+fn aborted_gzip_csv_is_a_complete_stream() {
+    use sas7bdat::{CsvSink, sinks::OutputCompression};
+    use std::io::Read;
+
+    let mut sink = CsvSink::new(Vec::new()).with_compression(OutputCompression::Gzip(6));
+    stream_cancelled(&mut sink);
+
+    let compressed = sink.into_inner().expect("aborted sink output");
+    let mut text = String::new();
+    flate2::read::GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut text)
+        .expect("valid gzip stream");
+    assert!(text.ends_with('\n'));
+    assert_partial(text.lines().count() - 1);
+}
+
+#[cfg(feature = "ipc")]
+#[test]
+fn aborted_ipc_file_is_readable() {
+    use arrow_ipc::reader::FileReader;
+    use sas7bdat::IpcSink;
+    use std::io::Cursor;
+
+    let mut sink = IpcSink::new(Vec::new()).with_batch_rows(100);
+    stream_cancelled(&mut sink);
+
+    let output = sink.into_inner().expect("aborted sink output");
+    let reader = FileReader::try_new(Cursor::new(output), None).expect("IPC footer");
+    let rows = reader
+        .map(|batch| batch.expect("record batch").num_rows())
+        .sum();
+    assert_partial(rows);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn aborted_arrow_sink_keeps_the_rows_read() {
+    use sas7bdat::ArrowSink;
+
+    let mut sink = ArrowSink::new().with_batch_rows(100);
+    stream_cancelled(&mut sink);
+    assert_partial(
+        sink.batches()
+            .iter()
+            .map(arrow_array::RecordBatch::num_rows)
+            .sum(),
+    );
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn aborted_parquet_file_is_readable() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use sas7bdat::ParquetSink;
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("out.parquet");
+    let mut sink = ParquetSink::new(std::fs::File::create(&path).expect("create output"))
+        .with_row_group_size(100);
+    stream_cancelled(&mut sink);
+    drop(sink);
+
+    let file = std::fs::File::open(&path).expect("open output");
+    let reader = SerializedFileReader::new(file).expect("Parquet footer");
+    let rows = reader.metadata().file_metadata().num_rows();
+    assert_partial(usize::try_from(rows).expect("row count"));
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn aborted_partitioned_parquet_files_are_readable() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use sas7bdat::PartitionedParquetSink;
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let mut sink = PartitionedParquetSink::new(dir.path(), ["operation"]);
+    stream_cancelled(&mut sink);
+
+    let mut rows = 0;
+    assert!(!sink.files().is_empty());
+    for file in sink.files() {
+        let data = std::fs::File::open(dir.path().join(&file.path)).expect("open part");
+        let reader = SerializedFileReader::new(data).expect("Parquet footer");
+        let count = reader.metadata().file_metadata().num_rows();
+        assert_eq!(u64::try_from(count).expect("row count"), file.rows);
+        rows += file.rows;
+    }
+    assert_partial(usize::try_from(rows).expect("row count"));
+}
@@ -1,4 +1,7 @@
-use sas7bdat::SasReader;
+use sas7bdat::{
+    Catalog, CellValue, Predicate, RowSelection, SasReader,
+    dataset::{LabelSet, ValueKey, ValueLabel, ValueType},
+};
 use sas7bdat_test_support::common;
 
 #[test]
@@ -38,3 +41,254 @@ fn attach_catalog_assigns_value_labels() {
         .expect("variable SEXB");
     assert_eq!(sex_b.value_labels.as_deref(), Some("$B"));
 }
+
+#[test]
+fn catalog_lists_formats_and_serialises_to_json() {
+    let catalog_path = common::fixture_path("fixtures/raw_data/readstat/test_formats_win.sas7bcat");
+    let catalog = Catalog::open(catalog_path).expect("open catalog");
+
+    let names: Vec<&str> = catalog.names().collect();
+    assert!(names.contains(&"$A") && names.contains(&"$B"), "{names:?}");
+    assert!(names.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(
+        catalog.formats(ValueType::String).count() + catalog.formats(ValueType::Numeric).count(),
+        catalog.len()
+    );
+
+    let set = catalog.get("a.").expect("lookup falls back to $A");
+    assert_eq!(set.name, "$A");
+    let mappings = catalog.mappings("$a").expect("mappings");
+    assert!(
+        mappings
+            .iter()
+            .any(|label| label.label == "Male" && matches!(label.key, ValueKey::String(_)))
+    );
+    assert!(catalog.get("NOSUCHFMT").is_none());
+
+    let json = serde_json::to_value(&catalog).expect("serialise catalog");
+    let sets = json["label_sets"].as_array().expect("label sets");
+    assert_eq!(sets.len(), catalog.len());
+    let first = &sets[0]["labels"][0];
+    assert!(first["key"]["kind"].is_string());
+    assert!(first["label"].is_string());
+}
+
+fn column_values(sas: &mut SasReader<std::fs::File>, name: &str) -> Vec<CellValue<'static>> {
+    let index = sas
+        .metadata()
+        .variables
+        .iter()
+        .position(|var| var.name == name)
+        .expect("column index");
+    let mut rows = sas.rows().expect("rows");
+    let mut values = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        values.push(row[index].clone().into_owned());
+    }
+    values
+}
+
+#[test]
+fn apply_value_labels_replaces_codes_in_rows() {
+    let data_path = common::fixture_path("fixtures/raw_data/readstat/test_data_win.sas7bdat");
+    let catalog_path = common::fixture_path("fixtures/raw_data/readstat/test_formats_win.sas7bcat");
+
+    let mut sas = SasReader::open(data_path).expect("open dataset");
+    sas.attach_catalog(catalog_path).expect("load catalog");
+    let label_set = sas.metadata().label_sets["$A"].clone();
+    let codes = column_values(&mut sas, "SEXA");
+
+    sas.set_apply_value_labels(true);
+    let labelled = column_values(&mut sas, "SEXA");
+    assert_eq!(codes.len(), labelled.len());
+    let mut replaced = 0;
+    for (code, value) in codes.iter().zip(&labelled) {
+        let expected = match code {
+            CellValue::Str(text) => label_set.labels.iter().find_map(|label| match &label.key {
+                ValueKey::String(key) if key.trim_end() == &**text => Some(label.label.as_str()),
+                _ => None,
+            }),
+            _ => None,
+        };
+        match expected {
+            Some(label) => {
+                assert_eq!(value, &CellValue::Str(label.into()));
+                replaced += 1;
+            }
+            None => assert_eq!(value, code),
+        }
+    }
+    assert!(replaced > 0, "codes: {codes:?}");
+
+    sas.set_apply_value_labels(false);
+    assert_eq!(column_values(&mut sas, "SEXA"), codes);
+}
+
+#[test]
+fn apply_value_labels_covers_tagged_missing_values() {
+    let data_path = common::fixture_path("fixtures/raw_data/readstat/missing_test.sas7bdat");
+    let catalog_path = common::fixture_path("fixtures/raw_data/readstat/missing_formats.sas7bcat");
+
+    let mut sas = SasReader::open(data_path).expect("open dataset");
+    sas.attach_catalog(catalog_path).expect("load catalog");
+    sas.set_apply_value_labels(true);
+    let variable = &sas.metadata().variables[0];
+    assert_eq!(variable.value_labels.as_deref(), Some("A"));
+    let name = variable.name.clone();
+
+    assert_eq!(
+        column_values(&mut sas, &name),
+        vec![CellValue::Str("missing".into())]
+    );
+}
+
+#[test]
+fn attach_catalogs_takes_each_format_from_the_first_catalog() {
+    let data_path = common::fixture_path("fixtures/raw_data/readstat/test_data_win.sas7bdat");
+    let site = common::fixture_path("fixtures/raw_data/readstat/test_formats_win.sas7bcat");
+    let personal = common::fixture_path("fixtures/raw_data/readstat/missing_formats.sas7bcat");
+    let site_formats = Catalog::open(&site).expect("open catalog").len();
+
+    let mut sas = SasReader::open(&data_path).expect("open dataset");
+    let conflicts = sas.attach_catalogs([&site, &site]).expect("load catalogs");
+    assert_eq!(conflicts.len(), site_formats);
+    assert!(
+        conflicts
+            .iter()
+            .all(|conflict| conflict.used == 0 && conflict.shadowed == [1])
+    );
+    assert_eq!(sas.metadata().label_sets.len(), site_formats);
+    let sex_a = sas
+        .metadata()
+        .variables
+        .iter()
+        .find(|var| var.name == "SEXA")
+        .expect("variable SEXA");
+    assert_eq!(sex_a.value_labels.as_deref(), Some("$A"));
+
+    let mut sas = SasReader::open(&data_path).expect("open dataset");
+    let conflicts = sas
+        .attach_catalogs([&personal, &site])
+        .expect("load catalogs");
+    let personal_catalog = Catalog::open(&personal).expect("open catalog");
+    for conflict in &conflicts {
+        assert_eq!(
+            (conflict.used, conflict.shadowed.as_slice()),
+            (0, [1].as_slice())
+        );
+        let used = sas
+            .metadata()
+            .label_sets
+            .values()
+            .find(|set| set.name.eq_ignore_ascii_case(&conflict.format))
+            .expect("attached format");
+        let expected = personal_catalog
+            .get(&conflict.format)
+            .expect("personal format");
+        assert_eq!(used.labels, expected.labels);
+    }
+    assert!(
+        sas.attach_catalogs([data_path.with_extension("missing")])
+            .is_err()
+    );
+}
+
+fn year_buckets() -> LabelSet {
+    let mut set = LabelSet::new("ERA".to_owned(), ValueType::Numeric);
+    set.labels = vec![
+        ValueLabel::new(ValueKey::Integer(1950), "midcentury"),
+        ValueLabel::range(1900.0, 1959.0, "early"),
+        ValueLabel::range(1960.0, 1999.0, "late"),
+    ];
+    set
+}
+
+#[test]
+fn range_labels_bucket_numeric_values() {
+    let lookup = year_buckets().lookup();
+    assert_eq!(lookup.label(&CellValue::Float(1948.0)), Some("early"));
+    assert_eq!(lookup.label(&CellValue::Int32(1959)), Some("early"));
+    assert_eq!(lookup.label(&CellValue::Float(1959.5)), None);
+    assert_eq!(lookup.label(&CellValue::Float(1950.0)), Some("midcentury"));
+    assert_eq!(lookup.label(&CellValue::Int64(1999)), Some("late"));
+    assert_eq!(lookup.label(&CellValue::Float(1899.0)), None);
+    assert_eq!(year_buckets().labels[1].bounds(), Some((1900.0, 1959.0)));
+    assert_eq!(year_buckets().labels[0].bounds(), None);
+
+    let json = serde_json::to_value(year_buckets()).expect("serialise set");
+    assert!(json["labels"][0].get("end").is_none());
+    assert_eq!(json["labels"][1]["end"]["value"], 1959.0);
+
+    let catalog_path = common::fixture_path("fixtures/raw_data/readstat/missing_formats.sas7bcat");
+    let catalog = Catalog::open(catalog_path).expect("open catalog");
+    let set = catalog.get("A").expect("numeric set");
+    assert!(set.labels.iter().all(|label| label.end.is_none()));
+}
+
+#[test]
+fn int64_codes_above_i32_find_their_labels() {
+    let mut set = LabelSet::new("PNR".to_owned(), ValueType::Numeric);
+    set.labels = vec![ValueLabel::new(
+        ValueKey::Numeric(3_012_345_678.0),
+        "registered",
+    )];
+    let lookup = set.lookup();
+    assert_eq!(
+        lookup.label(&CellValue::Int64(3_012_345_678)),
+        Some("registered")
+    );
+    assert_eq!(lookup.label(&CellValue::Int64(3_012_345_679)), None);
+    // 2^53 + 1 rounds to 2^53 as a double, so it must not borrow its label.
+    set.labels = vec![ValueLabel::new(
+        ValueKey::Numeric(9_007_199_254_740_992.0),
+        "edge",
+    )];
+    let lookup = set.lookup();
+    assert_eq!(
+        lookup.label(&CellValue::Int64(9_007_199_254_740_992)),
+        Some("edge")
+    );
+    assert_eq!(lookup.label(&CellValue::Int64(9_007_199_254_740_993)), None);
+}
+
+#[test]
+fn has_label_filters_rows_by_range_bucket() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let unlabelled = RowSelection::new().matching(Predicate::has_label("YEAR", ["early"]));
+    assert!(sas.rows_windowed(&unlabelled).is_err());
+
+    sas.edit_metadata()
+        .set_value_labels("YEAR", Some(year_buckets()))
+        .expect("attach labels");
+    assert!(
+        sas.edit_metadata()
+            .set_value_labels(
+                "YEAR",
+                Some(LabelSet::new("$ERA".to_owned(), ValueType::String))
+            )
+            .is_err()
+    );
+
+    let collect = |sas: &mut SasReader<std::fs::File>, selection: &RowSelection| {
+        sas.rows_windowed(selection)
+            .expect("windowed rows")
+            .map(|row| format!("{:?}", row.expect("row")))
+            .collect::<Vec<_>>()
+    };
+    let early = collect(
+        &mut sas,
+        &RowSelection::new().matching(Predicate::has_label("YEAR", ["early", "midcentury"])),
+    );
+    let expected = collect(
+        &mut sas,
+        &RowSelection::new().matching(Predicate::le("YEAR", 1959.0)),
+    );
+    assert!(!early.is_empty());
+    assert_eq!(early, expected);
+
+    sas.set_apply_value_labels(true);
+    let labelled = column_values(&mut sas, "YEAR");
+    assert!(labelled.contains(&CellValue::Str("early".into())));
+    assert!(labelled.contains(&CellValue::Str("midcentury".into())));
+}
@@ -0,0 +1,81 @@
+use sas7bdat::{
+    CellValue, ChecksumSink, MissingValue, RowSink, SasReader, SinkContext, decode_layout,
+    sinks::ColumnChecksum,
+};
+use sas7bdat_test_support::common::{self, CollectSink};
+use std::fs::File;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    common::fixture_path(format!("fixtures/raw_data/pandas/{name}"))
+}
+
+fn streamed_checksums(name: &str) -> Vec<ColumnChecksum> {
+    let mut sas = SasReader::open(fixture(name)).expect("open dataset");
+    let mut sink = ChecksumSink::new(CollectSink::default());
+    sas.stream_into(&mut sink).expect("stream rows");
+    sink.checksums()
+}
+
+fn written_checksums(name: &str, rows: &[Vec<CellValue<'_>>]) -> Vec<ColumnChecksum> {
+    let layout =
+        decode_layout(&mut File::open(fixture(name)).expect("open fixture")).expect("layout");
+    let mut sink = ChecksumSink::new(CollectSink::default());
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    for row in rows {
+        sink.write_row(row).expect("write row");
+    }
+    sink.finish().expect("finish");
+    sink.checksums()
+}
+
+#[test]
+fn streamed_and_materialised_rows_hash_alike() {
+    for name in ["airline.sas7bdat", "test1.sas7bdat", "test2.sas7bdat"] {
+        let streamed = streamed_checksums(name);
+        let mut sas = SasReader::open(fixture(name)).expect("open dataset");
+        assert_eq!(streamed.len(), sas.metadata().variables.len(), "{name}");
+        assert!(streamed.iter().all(|checksum| checksum.xxh3.len() == 16));
+
+        let rows = sas
+            .rows()
+            .expect("row iterator")
+            .collect::<sas7bdat::Result<Vec<_>>>()
+            .expect("rows");
+        assert_eq!(written_checksums(name, &rows), streamed, "{name}");
+    }
+}
+
+#[test]
+fn checksums_follow_values_not_their_representation() {
+    let name = "airline.sas7bdat";
+    let mut sas = SasReader::open(fixture(name)).expect("open dataset");
+    let rows = sas
+        .rows()
+        .expect("row iterator")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("rows");
+    let baseline = written_checksums(name, &rows);
+
+    let year = rows[0]
+        .iter()
+        .position(|value| matches!(value, CellValue::Int32(_)))
+        .expect("integer column");
+    let mut retyped = rows.clone();
+    for row in &mut retyped {
+        if let CellValue::Int32(v) = row[year] {
+            row[year] = CellValue::Float(f64::from(v));
+        }
+    }
+    assert_eq!(written_checksums(name, &retyped), baseline);
+
+    let mut changed = rows.clone();
+    changed[0][year] = CellValue::Missing(MissingValue::System);
+    let changed = written_checksums(name, &changed);
+    for (idx, (before, after)) in baseline.iter().zip(&changed).enumerate() {
+        assert_eq!(before == after, idx != year, "column {}", before.column);
+    }
+
+    let mut reordered = rows;
+    reordered.swap(0, 1);
+    assert_ne!(written_checksums(name, &reordered), baseline);
+}
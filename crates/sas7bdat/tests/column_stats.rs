@@ -0,0 +1,90 @@
+use sas7bdat::{CellValue, SasReader, StatValue};
+use sas7bdat_test_support::common;
+use std::collections::HashSet;
+
+fn open(name: &str) -> SasReader<std::fs::File> {
+    let path = common::fixture_path(format!("fixtures/raw_data/pandas/{name}"));
+    SasReader::open(path).expect("open dataset")
+}
+
+/// Column values as numbers or text, `None` for missing cells.
+fn column_values(sas: &mut SasReader<std::fs::File>, index: usize) -> Vec<Option<StatValue>> {
+    let mut rows = sas.rows().expect("rows");
+    let mut values = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        values.push(match &row[index] {
+            CellValue::Float(v) => Some(StatValue::Number(*v)),
+            CellValue::Int32(v) => Some(StatValue::Number(f64::from(*v))),
+            CellValue::Int64(v) => Some(StatValue::Number(
+                i32::try_from(*v).map(f64::from).expect("small integer"),
+            )),
+            CellValue::Str(text) if !text.is_empty() => Some(StatValue::Text(text.to_string())),
+            _ => None,
+        });
+    }
+    values
+}
+
+#[test]
+fn numeric_stats_match_decoded_rows() {
+    let mut sas = open("airline.sas7bdat");
+    let stats = sas.column_stats().expect("stats");
+    assert_eq!(stats.len(), sas.metadata().variables.len());
+
+    for (index, column) in stats.iter().enumerate() {
+        let numbers: Vec<f64> = column_values(&mut sas, index)
+            .into_iter()
+            .flatten()
+            .map(|value| match value {
+                StatValue::Number(v) => v,
+                StatValue::Text(text) => panic!("unexpected text {text}"),
+            })
+            .collect();
+        assert_eq!(column.count, numbers.len() as u64, "{}", column.name);
+        assert_eq!(column.count + column.null_count, sas.metadata().row_count);
+
+        let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(column.min, Some(StatValue::Number(min)));
+        assert_eq!(column.max, Some(StatValue::Number(max)));
+        let mean = numbers.iter().sum::<f64>()
+            / f64::from(u32::try_from(numbers.len()).expect("row count"));
+        assert!((column.mean.expect("mean") - mean).abs() <= mean.abs() * 1e-12);
+
+        let distinct: HashSet<u64> = numbers.iter().map(|v| v.to_bits()).collect();
+        assert_eq!(
+            column.distinct_estimate,
+            distinct.len() as u64,
+            "{}",
+            column.name
+        );
+    }
+}
+
+#[test]
+fn character_stats_use_text_order_and_skip_blanks() {
+    let mut sas = open("test1.sas7bdat");
+    let stats = sas.column_stats().expect("stats");
+    let index = stats
+        .iter()
+        .position(|column| matches!(column.min, Some(StatValue::Text(_))))
+        .expect("character column");
+    let column = &stats[index];
+    assert_eq!(column.mean, None);
+
+    let values = column_values(&mut sas, index);
+    let texts: Vec<String> = values
+        .iter()
+        .flatten()
+        .map(|value| match value {
+            StatValue::Text(text) => text.clone(),
+            StatValue::Number(v) => panic!("unexpected number {v}"),
+        })
+        .collect();
+    assert_eq!(column.count, texts.len() as u64);
+    assert_eq!(column.null_count, (values.len() - texts.len()) as u64);
+    assert_eq!(column.min, texts.iter().min().cloned().map(StatValue::Text));
+    assert_eq!(column.max, texts.iter().max().cloned().map(StatValue::Text));
+    let distinct: HashSet<&String> = texts.iter().collect();
+    assert_eq!(column.distinct_estimate, distinct.len() as u64);
+}
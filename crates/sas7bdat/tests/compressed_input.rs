@@ -0,0 +1,94 @@
+#![cfg(any(feature = "gzip", feature = "zip"))]
+
+use sas7bdat::SasReader;
+use sas7bdat_test_support::common;
+use std::fs;
+#[cfg(feature = "zip")]
+use std::path::Path;
+
+const FIXTURE: &str = "fixtures/raw_data/pandas/test1.sas7bdat";
+
+fn rows(mut sas: SasReader<fs::File>) -> String {
+    let rows = sas
+        .rows()
+        .expect("rows")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("decode rows");
+    format!("{rows:?}")
+}
+
+fn expected() -> String {
+    rows(SasReader::open(common::fixture_path(FIXTURE)).expect("open fixture"))
+}
+
+#[test]
+fn plain_files_open_as_usual() {
+    let sas = SasReader::open_compressed(common::fixture_path(FIXTURE)).expect("open plain");
+    assert_eq!(rows(sas), expected());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn reads_gzip_wrapped_dataset() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("test1.sas7bdat.gz");
+    let mut encoder = flate2::write::GzEncoder::new(
+        fs::File::create(&path).expect("create"),
+        flate2::Compression::default(),
+    );
+    encoder
+        .write_all(&fs::read(common::fixture_path(FIXTURE)).expect("read fixture"))
+        .expect("compress");
+    encoder.finish().expect("finish gzip");
+
+    let sas = SasReader::open_compressed(&path).expect("open gzip");
+    assert_eq!(rows(sas), expected());
+}
+
+#[cfg(feature = "zip")]
+fn write_zip(path: &Path, entries: &[&str]) {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let data = fs::read(common::fixture_path(FIXTURE)).expect("read fixture");
+    let mut zip = zip::ZipWriter::new(fs::File::create(path).expect("create"));
+    for name in entries {
+        zip.start_file(*name, SimpleFileOptions::default())
+            .expect("start entry");
+        zip.write_all(&data).expect("write entry");
+    }
+    zip.finish().expect("finish zip");
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn reads_the_dataset_of_a_zip_archive() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("delivery.zip");
+    write_zip(&path, &["README.txt", "data/TEST1.SAS7BDAT"]);
+
+    let sas = SasReader::open_compressed(&path).expect("open zip");
+    assert_eq!(rows(sas), expected());
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn rejects_zip_archives_without_a_single_dataset() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    for (name, entries) in [
+        ("none.zip", &["README.txt"][..]),
+        ("two.zip", &["a.sas7bdat", "b.sas7bdat"][..]),
+    ] {
+        let path = dir.path().join(name);
+        write_zip(&path, entries);
+        let Err(err) = SasReader::open_compressed(&path) else {
+            panic!("{name}: opened an archive without a single dataset");
+        };
+        assert!(
+            matches!(err, sas7bdat::Error::Unsupported { .. }),
+            "{name}: {err}"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(any(feature = "gzip", feature = "zstd"))]
+
+use sas7bdat::{JsonlSink, RowSink, SasReader, sinks::OutputCompression};
+use sas7bdat_test_support::common;
+#[cfg(feature = "gzip")]
+use std::io::Read;
+
+const FIXTURE: &str = "fixtures/raw_data/pandas/airline.sas7bdat";
+
+fn stream<S: RowSink>(sink: &mut S) {
+    let mut sas = SasReader::open(common::fixture_path(FIXTURE)).expect("open fixture");
+    sas.stream_into(sink).expect("stream");
+}
+
+fn jsonl(compression: OutputCompression) -> Vec<u8> {
+    let mut sink = JsonlSink::new(Vec::new()).with_compression(compression);
+    stream(&mut sink);
+    sink.into_inner().expect("output")
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_jsonl_decompresses_to_plain_output() {
+    let compressed = jsonl(OutputCompression::Gzip(6));
+    let mut text = Vec::new();
+    flate2::read::GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut text)
+        .expect("valid gzip stream");
+    assert_eq!(text, jsonl(OutputCompression::None));
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_jsonl_decompresses_to_plain_output() {
+    let compressed = jsonl(OutputCompression::Zstd(3));
+    let text = zstd::decode_all(compressed.as_slice()).expect("valid zstd stream");
+    assert_eq!(text, jsonl(OutputCompression::None));
+}
+
+#[cfg(all(feature = "csv", feature = "gzip"))]
+#[test]
+// `{part}` is the sink's placeholder, not a format argument.
+#[allow(clippy::literal_string_with_formatting_args)]
+fn every_csv_part_is_a_complete_gzip_stream() {
+    use sas7bdat::CsvSink;
+    use std::fs::{self, File};
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let first = File::create(dir.path().join("airline-00000.csv.gz")).expect("create part 0");
+    let template = dir.path().join("airline-{part}.csv.gz");
+    let mut sink = CsvSink::new(first)
+        .with_compression(OutputCompression::Gzip(9))
+        .with_max_rows_per_file(10, template.to_str().expect("UTF-8 temp path"));
+    stream(&mut sink);
+
+    let mut plain = CsvSink::new(Vec::new());
+    stream(&mut plain);
+    let plain = String::from_utf8(plain.into_inner().expect("output")).expect("utf8");
+    let mut lines = plain.lines();
+    let header = lines.next().expect("header");
+
+    let mut rows = Vec::new();
+    for part in 0.. {
+        let Ok(data) = fs::read(dir.path().join(format!("airline-{part:05}.csv.gz"))) else {
+            break;
+        };
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(data.as_slice())
+            .read_to_string(&mut text)
+            .expect("valid gzip stream");
+        let mut part_lines = text.lines();
+        assert_eq!(part_lines.next(), Some(header));
+        rows.extend(part_lines.map(str::to_owned));
+    }
+    assert_eq!(rows, lines.collect::<Vec<_>>());
+}
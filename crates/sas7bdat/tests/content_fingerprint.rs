@@ -0,0 +1,60 @@
+use sas7bdat::{SasReader, find_duplicate_files};
+use sas7bdat_test_support::common;
+use std::fs;
+
+#[test]
+fn renamed_snapshot_is_grouped_with_its_original() {
+    let airline = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let test1 = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let dir = tempfile::tempdir().expect("temp dir");
+    let (january, february) = (dir.path().join("2024-01"), dir.path().join("2024-02"));
+    fs::create_dir_all(&january).expect("create snapshot dir");
+    fs::create_dir_all(february.join("nested")).expect("create snapshot dir");
+    fs::copy(&airline, january.join("airline.sas7bdat")).expect("copy");
+    fs::copy(&airline, february.join("nested/flights.SAS7BDAT")).expect("copy");
+    fs::copy(&test1, february.join("test1.sas7bdat")).expect("copy");
+    fs::write(february.join("broken.sas7bdat"), b"not a dataset").expect("write");
+    fs::write(february.join("notes.txt"), b"ignored").expect("write");
+
+    let report = find_duplicate_files([&january, &february]).expect("inventory");
+    assert_eq!(report.files, 3);
+    assert_eq!(report.unreadable.len(), 1);
+    assert!(report.unreadable[0].0.ends_with("broken.sas7bdat"));
+    assert_eq!(report.groups.len(), 1);
+    assert_eq!(
+        report.groups[0].paths,
+        [
+            january.join("airline.sas7bdat"),
+            february.join("nested/flights.SAS7BDAT")
+        ]
+    );
+
+    let original = SasReader::open(&airline)
+        .expect("open fixture")
+        .content_fingerprint()
+        .expect("fingerprint");
+    assert_eq!(report.groups[0].fingerprint, original);
+    assert_eq!(
+        original.file_size(),
+        fs::metadata(&airline).expect("stat").len()
+    );
+}
+
+#[test]
+fn changed_page_changes_fingerprint() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut data = fs::read(&path).expect("read fixture");
+    let mut sas = SasReader::from_bytes(data.clone()).expect("parse fixture");
+    let original = sas.content_fingerprint().expect("fingerprint");
+    assert_eq!(original, sas.content_fingerprint().expect("fingerprint"));
+
+    // The last page is always sampled.
+    let last = data.len() - 1;
+    data[last] ^= 0xFF;
+    let changed = SasReader::from_bytes(data)
+        .expect("parse changed copy")
+        .content_fingerprint()
+        .expect("fingerprint");
+    assert_eq!(changed.file_size(), original.file_size());
+    assert_ne!(changed, original);
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "spec")]
+
+use sas7bdat::{ConversionSpec, Error, SasReader, spec::DEFAULT_PARTITION};
+use sas7bdat_test_support::common;
+use std::{collections::BTreeSet, fs};
+
+const FIXTURE: &str = "fixtures/raw_data/pandas/productsales.sas7bdat";
+
+fn workspace() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::create_dir(dir.path().join("in")).expect("input dir");
+    fs::copy(
+        common::fixture_path(FIXTURE),
+        dir.path().join("in/productsales.sas7bdat"),
+    )
+    .expect("copy fixture");
+    dir
+}
+
+/// Debug-formatted COUNTRY value of every row in the fixture.
+fn countries() -> Vec<String> {
+    let mut sas = SasReader::open(common::fixture_path(FIXTURE)).expect("open fixture");
+    let index = sas
+        .metadata()
+        .variables
+        .iter()
+        .position(|variable| variable.name.trim_end() == "COUNTRY")
+        .expect("COUNTRY column");
+    let mut values = Vec::new();
+    let mut rows = sas.rows().expect("rows");
+    while let Some(row) = rows.try_next().expect("row") {
+        values.push(format!("{:?}", row[index]));
+    }
+    values
+}
+
+#[test]
+fn toml_spec_selects_renames_and_nulls_columns() {
+    let dir = workspace();
+    let spec_path = dir.path().join("sales.toml");
+    fs::write(
+        &spec_path,
+        r#"
+input = "in/*.sas7bdat"
+output_dir = "out"
+columns = ["COUNTRY", "ACTUAL", "YEAR"]
+
+[rename]
+COUNTRY = "country"
+ACTUAL = "sales"
+
+[types]
+YEAR = "number"
+
+[sink]
+format = "csv"
+
+[nulls]
+values = { COUNTRY = ["GERMANY"] }
+"#,
+    )
+    .expect("write spec");
+
+    let report = ConversionSpec::from_path(&spec_path)
+        .expect("parse spec")
+        .run()
+        .expect("run spec");
+    let total = countries().len();
+    assert_eq!(report.files.len(), 1);
+    assert_eq!(report.rows(), total as u64);
+
+    let output = &report.files[0].outputs[0];
+    assert_eq!(output, &dir.path().join("out/productsales.csv"));
+    let mut reader = csv::Reader::from_path(output).expect("open csv");
+    let headers = reader.headers().expect("headers").clone();
+    assert_eq!(
+        headers.iter().collect::<Vec<_>>(),
+        ["country", "sales", "YEAR"]
+    );
+    let mut seen = BTreeSet::new();
+    let mut rows = 0;
+    for record in reader.records() {
+        let record = record.expect("record");
+        seen.insert(record[0].to_owned());
+        rows += 1;
+    }
+    assert_eq!(rows, total);
+    assert!(seen.contains(""), "GERMANY rows become null");
+    assert!(!seen.contains("GERMANY"));
+    assert!(seen.contains("CANADA"));
+}
+
+#[test]
+fn json_spec_partitions_by_column() {
+    let dir = workspace();
+    let spec = ConversionSpec::from_json_str(
+        r#"{
+            "input": "in/*.sas7bdat",
+            "output_dir": "out",
+            "sink": { "format": "parquet", "row_group_size": 256 },
+            "partition": { "by": "COUNTRY" }
+        }"#,
+    )
+    .expect("parse spec")
+    .with_base_dir(dir.path());
+
+    let report = spec.run().expect("run spec");
+    let values = countries();
+    let total = values.len();
+    let distinct: BTreeSet<_> = values.into_iter().collect();
+    let outputs = &report.files[0].outputs;
+    assert_eq!(outputs.len(), distinct.len());
+    assert_eq!(report.rows(), total as u64);
+    for output in outputs {
+        assert!(output.is_file(), "{} exists", output.display());
+        let partition = output
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .and_then(|name| name.to_str())
+            .expect("partition dir");
+        assert!(partition.starts_with("COUNTRY="), "{partition}");
+        assert_ne!(partition, format!("COUNTRY={DEFAULT_PARTITION}"));
+    }
+}
+
+#[test]
+fn spec_errors_are_reported_as_config() {
+    let unknown_field = ConversionSpec::from_toml_str(
+        r#"
+input = "*.sas7bdat"
+output_dir = "out"
+colums = ["A"]
+"#,
+    );
+    assert!(matches!(unknown_field, Err(Error::Config { .. })));
+
+    let dir = workspace();
+    let spec = ConversionSpec::from_toml_str(
+        r#"
+input = "in/*.sas7bdat"
+output_dir = "out"
+types = { COUNTRY = "date" }
+"#,
+    )
+    .expect("parse spec")
+    .with_base_dir(dir.path());
+    assert!(matches!(spec.run(), Err(Error::Config { .. })));
+
+    let spec = ConversionSpec::from_toml_str(
+        r#"
+input = "in/*.sas7bdat"
+output_dir = "out"
+columns = ["ACTUAL"]
+rename = { COUNTRY = "country" }
+"#,
+    )
+    .expect("parse spec")
+    .with_base_dir(dir.path());
+    let err = spec.run().expect_err("renamed column is not selected");
+    assert_eq!(err.code(), "config");
+}
@@ -0,0 +1,274 @@
+#![cfg(feature = "csv")]
+
+use sas7bdat::{
+    CellValue, CsvSink, Error, ErrorCategory, RowSelection, RowSink, SasReader, SinkContext,
+    cell::MissingValue, decode_layout, sinks::QuoteStyle,
+};
+use sas7bdat_test_support::common;
+use std::{
+    borrow::Cow,
+    fs,
+    fs::File,
+    io::{self, Write},
+};
+
+/// Characters that need escaping or are easy to mangle, mixed with plain text.
+const ALPHABET: &[char] = &[
+    'a', 'Z', '0', ' ', ',', ';', '\t', '"', '\'', '\r', '\n', '\\', 'æ', 'ø', '€', '日',
+];
+const ROWS: usize = 500;
+
+/// Small deterministic generator so failures reproduce without extra
+/// dependencies.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        usize::try_from(self.next() % bound as u64).expect("bound fits usize")
+    }
+
+    fn text(&mut self) -> String {
+        let len = self.below(12);
+        (0..len)
+            .map(|_| ALPHABET[self.below(ALPHABET.len())])
+            .collect()
+    }
+
+    fn value(&mut self) -> CellValue<'static> {
+        match self.below(4) {
+            0 => CellValue::Float(f64::from_bits(self.next() >> 2)),
+            1 => CellValue::Int64(i64::from_ne_bytes(self.next().to_ne_bytes())),
+            _ => CellValue::Str(Cow::Owned(self.text())),
+        }
+    }
+}
+
+fn assert_field(field: &str, value: &CellValue<'_>, row: usize) {
+    match value {
+        CellValue::Float(number) => {
+            let parsed: f64 = field.parse().expect("float field");
+            assert_eq!(parsed.to_bits(), number.to_bits(), "row {row}: {field}");
+        }
+        CellValue::Int64(number) => assert_eq!(field, number.to_string(), "row {row}"),
+        CellValue::Str(text) => assert_eq!(field, text, "row {row}"),
+        other => panic!("generator produced {other:?}"),
+    }
+}
+
+fn write_csv(sink: CsvSink<Vec<u8>>, rows: &[Vec<CellValue<'static>>]) -> Vec<u8> {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let layout = decode_layout(&mut File::open(path).expect("open fixture")).expect("layout");
+    let mut sink = sink.with_headers(false);
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    for row in rows {
+        sink.write_row(row).expect("write row");
+    }
+    sink.finish().expect("finish");
+    sink.into_inner().expect("output")
+}
+
+#[test]
+fn rfc4180_output_round_trips_arbitrary_values() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let columns = decode_layout(&mut File::open(path).expect("open fixture"))
+        .expect("layout")
+        .columns
+        .len();
+    let mut rng = SplitMix64(0x5A57_BDA7);
+    let rows: Vec<Vec<CellValue<'static>>> = (0..ROWS)
+        .map(|_| (0..columns).map(|_| rng.value()).collect())
+        .collect();
+
+    let output = write_csv(CsvSink::new(Vec::new()).with_rfc4180(true), &rows);
+    assert!(output.ends_with(b"\r\n"));
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(output.as_slice());
+    let mut parsed = 0;
+    for (record, row) in reader.records().zip(&rows) {
+        let record = record.expect("valid CSV record");
+        assert_eq!(record.len(), row.len(), "row {parsed}");
+        for (field, value) in record.iter().zip(row) {
+            assert_field(field, value, parsed);
+        }
+        parsed += 1;
+    }
+    assert_eq!(parsed, rows.len());
+}
+
+#[test]
+fn rfc4180_rejects_unescapable_delimiter() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let layout = decode_layout(&mut File::open(path).expect("open fixture")).expect("layout");
+    let mut sink = CsvSink::new(Vec::new())
+        .with_rfc4180(true)
+        .with_delimiter(b'"');
+    assert!(matches!(
+        sink.begin(SinkContext::new(&layout)),
+        Err(Error::Unsupported { .. })
+    ));
+}
+
+#[test]
+// `{part}` is the sink's placeholder, not a format argument.
+#[allow(clippy::literal_string_with_formatting_args)]
+fn max_rows_per_file_writes_numbered_parts_with_headers() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open fixture");
+    let total = usize::try_from(sas.metadata().row_count).expect("row count fits usize");
+    let first = File::create(dir.path().join("airline-00000.csv")).expect("create part 0");
+    let template = dir.path().join("airline-{part}.csv");
+    let mut sink =
+        CsvSink::new(first).with_max_rows_per_file(10, template.to_str().expect("UTF-8 temp path"));
+    sas.stream_into(&mut sink).expect("write parts");
+
+    let mut header = None;
+    let mut rows = 0;
+    let mut parts = 0;
+    loop {
+        let part = dir.path().join(format!("airline-{parts:05}.csv"));
+        let Ok(text) = fs::read_to_string(&part) else {
+            break;
+        };
+        let mut lines = text.lines();
+        let first_line = lines.next().expect("header line");
+        assert_eq!(
+            *header.get_or_insert_with(|| first_line.to_owned()),
+            first_line
+        );
+        let count = lines.count();
+        assert!((1..=10).contains(&count), "part {parts} has {count} rows");
+        rows += count;
+        parts += 1;
+    }
+    assert_eq!(rows, total);
+    assert_eq!(parts, total.div_ceil(10));
+}
+
+#[test]
+fn max_rows_per_file_requires_part_placeholder() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let layout = decode_layout(&mut File::open(path).expect("open fixture")).expect("layout");
+    let first = File::create(dir.path().join("airline.csv")).expect("create output");
+    let mut sink = CsvSink::new(first).with_max_rows_per_file(10, "airline.csv");
+    assert!(matches!(
+        sink.begin(SinkContext::new(&layout)),
+        Err(Error::InvalidArgument { .. })
+    ));
+}
+
+#[test]
+fn delimiter_quoting_and_temporal_formats_are_configurable() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open fixture");
+    let mut sink = CsvSink::new(Vec::new())
+        .with_delimiter(b';')
+        .with_quote_style(QuoteStyle::Always)
+        .with_headers(false)
+        .with_date_format("[day].[month].[year]")
+        .with_datetime_format("[year][month][day]T[hour][minute][second]");
+    sas.stream_into(&mut sink).expect("stream");
+    let output = String::from_utf8(sink.into_inner().expect("output")).expect("utf8");
+
+    let mut lines = output.lines();
+    assert!(
+        lines
+            .next()
+            .expect("first row")
+            .starts_with("\"22.09.1677\";")
+    );
+    assert_eq!(
+        lines.next().expect("second row"),
+        "\"01.01.1960\";\"01.01.1960\";\"19600101T000000\";\"19600101T000000\";\"01.01.1960\""
+    );
+}
+
+#[test]
+fn missing_values_use_null_representation() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let layout = decode_layout(&mut File::open(path).expect("open fixture")).expect("layout");
+    let row = vec![CellValue::Missing(MissingValue::System); layout.columns.len()];
+    let output = write_csv(
+        CsvSink::new(Vec::new())
+            .with_delimiter(b'\t')
+            .with_null_value("NA"),
+        &[row],
+    );
+    assert_eq!(output, b"NA\tNA\tNA\tNA\tNA\tNA\n");
+}
+
+#[test]
+fn date_format_with_time_components_is_rejected() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let layout = decode_layout(&mut File::open(path).expect("open fixture")).expect("layout");
+    for sink in [
+        CsvSink::new(Vec::new()).with_date_format("[year]-[month]-[day] [hour]"),
+        CsvSink::new(Vec::new()).with_time_format("[hour"),
+    ] {
+        let mut sink = sink;
+        assert!(matches!(
+            sink.begin(SinkContext::new(&layout)),
+            Err(Error::Config { .. })
+        ));
+    }
+}
+
+/// Output that rejects every write, like a full disk.
+struct FullDisk;
+
+impl Write for FullDisk {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_writes_are_reported_as_sink_errors() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open fixture");
+    let err = sas
+        .stream_into(&mut CsvSink::new(FullDisk))
+        .expect_err("writes fail");
+    assert_eq!(err.category(), ErrorCategory::Sink, "{err}");
+}
+
+#[test]
+fn metadata_comments_describe_selected_columns_in_order() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open fixture");
+    let selection = RowSelection::new()
+        .columns(&["MONTH", "ACTUAL", "COUNTRY"])
+        .max_rows(2);
+    let mut sink = CsvSink::new(Vec::new()).with_metadata_comments(true);
+    sas.stream_selection_into(&selection, &mut sink)
+        .expect("write csv");
+    let text = String::from_utf8(sink.into_inner().expect("output")).expect("UTF-8");
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[..6],
+        [
+            "# dataset: PRDSALE",
+            "# label: \"Furniture sales data\"",
+            "# 1 MONTH: numeric, format MONNAME., label \"Month\"",
+            "# 2 ACTUAL: numeric, format DOLLAR., label \"Actual Sales\"",
+            "# 3 COUNTRY: character, format $CHAR., label \"Country\"",
+            "MONTH,ACTUAL,COUNTRY",
+        ]
+    );
+    assert_eq!(lines.len(), 8);
+}
@@ -0,0 +1,88 @@
+use sas7bdat::{CellValue, Dataset, SasReader, dataset::ColumnData};
+use sas7bdat_test_support::common;
+
+#[test]
+fn read_loads_typed_columns() {
+    let dataset = open_test1();
+
+    assert_eq!(dataset.row_count(), 10);
+    assert_eq!(dataset.column_count(), 100);
+
+    let column1 = dataset.col_f64("Column1").expect("numeric column");
+    assert_eq!(column1.len(), 10);
+    assert!((column1[0].expect("value present") - 0.636).abs() < 1e-6);
+
+    let column2 = dataset.col_str("Column2").expect("character column");
+    assert_eq!(column2[0], "pear");
+
+    assert!(dataset.col_str("Column1").is_err());
+    assert!(dataset.col_f64("NoSuchColumn").is_err());
+    assert!(matches!(
+        dataset.column("Column2"),
+        Some(ColumnData::Text(_))
+    ));
+}
+
+#[test]
+fn rows_and_filter_preserve_column_alignment() {
+    let dataset = open_test1();
+
+    let first = dataset.rows().next().expect("first row");
+    assert_eq!(first.str("Column2"), Some("pear"));
+    assert!(matches!(first.get("Column2"), Some(CellValue::Str(text)) if text == "pear"));
+
+    let pears = dataset.filter(|row| row.str("Column2") == Some("pear"));
+    assert!(pears.row_count() > 0);
+    assert!(pears.row_count() <= dataset.row_count());
+    assert_eq!(pears.metadata().row_count, pears.row_count() as u64);
+    assert!(
+        pears
+            .col_str("Column2")
+            .expect("character column")
+            .iter()
+            .all(|value| value == "pear")
+    );
+    assert_eq!(
+        pears.col_f64("Column1").expect("numeric column").len(),
+        pears.row_count()
+    );
+}
+
+#[test]
+fn read_batches_chunks_rows_into_typed_columns() {
+    let dataset = open_test1();
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut reader = SasReader::open(path).expect("open reader");
+
+    let batches = reader
+        .read_batches(4)
+        .expect("batch iterator")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("decode batches");
+
+    let sizes: Vec<usize> = batches.iter().map(|batch| batch.row_count).collect();
+    assert_eq!(sizes, [4, 4, 2]);
+    for batch in &batches {
+        assert_eq!(batch.columns.len(), dataset.column_count());
+        assert!(
+            batch
+                .columns
+                .iter()
+                .all(|column| column.len() == batch.row_count)
+        );
+    }
+
+    let mut text = Vec::new();
+    for batch in &batches {
+        match &batch.columns[1] {
+            ColumnData::Text(values) => text.extend(values.iter().cloned()),
+            other => panic!("expected a text column, got {other:?}"),
+        }
+    }
+    assert_eq!(text, dataset.col_str("Column2").expect("character column"));
+}
+
+fn open_test1() -> Dataset {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    Dataset::read(path).expect("read dataset")
+}
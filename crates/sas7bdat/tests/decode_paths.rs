@@ -0,0 +1,29 @@
+#![cfg(feature = "testing")]
+
+use sas7bdat::testing::verify_decode_paths;
+use sas7bdat_test_support::common;
+use std::fs;
+
+#[test]
+fn every_decode_path_matches_try_next() {
+    let dir = common::fixture_path("fixtures/raw_data/pandas");
+    let mut checked = 0;
+    for entry in fs::read_dir(dir).expect("list fixtures") {
+        let path = entry.expect("fixture entry").path();
+        if path.extension().is_none_or(|ext| ext != "sas7bdat")
+            // Neither can be opened.
+            || path
+                .file_stem()
+                .is_some_and(|stem| stem == "corrupt" || stem == "zero_variables")
+        {
+            continue;
+        }
+        let divergence =
+            verify_decode_paths(&path).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        if let Some(divergence) = divergence {
+            panic!("{}: {divergence}", path.display());
+        }
+        checked += 1;
+    }
+    assert!(checked > 20, "only {checked} fixtures checked");
+}
@@ -0,0 +1,65 @@
+use sas7bdat::{CellValue, DecryptLayer, DecryptingReader, SasReader};
+use sas7bdat_test_support::common;
+use std::io::{self, Cursor, Read, Seek};
+
+const HEADER: &[u8] = b"ENCv1\0\0\0";
+
+/// Keystream byte `n` is derived from `n` alone, so any range decrypts
+/// independently.
+struct PositionalXor {
+    key: u8,
+}
+
+impl PositionalXor {
+    fn apply(&self, offset: u64, buf: &mut [u8]) {
+        for (position, byte) in (offset..).zip(buf.iter_mut()) {
+            *byte ^= self
+                .key
+                .wrapping_add(position.to_le_bytes()[0].rotate_left(3));
+        }
+    }
+}
+
+impl DecryptLayer for PositionalXor {
+    fn header_len(&self) -> u64 {
+        HEADER.len() as u64
+    }
+
+    fn decrypt(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        self.apply(offset, buf);
+        Ok(())
+    }
+}
+
+fn read_all_rows<R: Read + Seek>(sas: &mut SasReader<R>) -> Vec<Vec<CellValue<'static>>> {
+    let mut rows = sas.rows().expect("row iterator");
+    let mut collected = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        collected.push(row.into_iter().map(CellValue::into_owned).collect());
+    }
+    collected
+}
+
+#[test]
+fn reads_encrypted_file_through_layer() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test4.sas7bdat");
+    let layer = PositionalXor { key: 0xA7 };
+    let mut body = std::fs::read(&path).expect("read fixture");
+    layer.apply(0, &mut body);
+    let dir = tempfile::tempdir().expect("tempdir");
+    let encrypted = dir.path().join("test4.sas7bdat.enc");
+    std::fs::write(&encrypted, [HEADER, &body].concat()).expect("write encrypted");
+
+    let mut decrypted = SasReader::open_encrypted(&encrypted, layer).expect("open encrypted");
+    let mut plain = SasReader::open(&path).expect("open plain");
+    assert_eq!(decrypted.metadata().row_count, plain.metadata().row_count);
+    assert_eq!(read_all_rows(&mut decrypted), read_all_rows(&mut plain));
+}
+
+#[test]
+fn rejects_input_shorter_than_header() {
+    let err = DecryptingReader::new(Cursor::new(vec![0u8; 4]), PositionalXor { key: 1 })
+        .err()
+        .expect("input shorter than header");
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
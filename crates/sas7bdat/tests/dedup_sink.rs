@@ -0,0 +1,113 @@
+use sas7bdat::{CellValue, DedupKeep, DedupSink, SasReader};
+use sas7bdat_test_support::common::{self, CollectSink};
+use std::collections::HashMap;
+
+const KEY: &str = "Column2";
+
+fn read_rows(sas: &mut SasReader<std::fs::File>) -> Vec<Vec<CellValue<'static>>> {
+    let mut rows = sas.rows().expect("row iterator");
+    let mut collected = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        collected.push(row.into_iter().map(CellValue::into_owned).collect());
+    }
+    collected
+}
+
+fn expected_rows(
+    rows: &[Vec<CellValue<'static>>],
+    key_index: usize,
+    keep: DedupKeep,
+) -> Vec<Vec<CellValue<'static>>> {
+    let mut chosen: HashMap<String, usize> = HashMap::new();
+    for (position, row) in rows.iter().enumerate() {
+        let key = format!("{:?}", row[key_index]);
+        match keep {
+            DedupKeep::First => {
+                chosen.entry(key).or_insert(position);
+            }
+            DedupKeep::Last => {
+                chosen.insert(key, position);
+            }
+        }
+    }
+    let mut positions: Vec<usize> = chosen.into_values().collect();
+    positions.sort_unstable();
+    positions.into_iter().map(|idx| rows[idx].clone()).collect()
+}
+
+#[test]
+fn dedup_sink_keeps_first_or_last_row_per_key() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let rows = read_rows(&mut sas);
+    let key_index = sas.metadata().column_index(KEY).expect("key column");
+    let spill_dir = tempfile::tempdir().expect("temp dir");
+
+    for keep in [DedupKeep::First, DedupKeep::Last] {
+        let expected = expected_rows(&rows, key_index, keep);
+        assert!(expected.len() < rows.len(), "fixture has duplicate keys");
+
+        let mut in_memory = DedupSink::new(CollectSink::default(), [KEY]).with_keep(keep);
+        sas.stream_into(&mut in_memory).expect("dedup in memory");
+        assert_eq!(
+            in_memory.removed_rows(),
+            (rows.len() - expected.len()) as u64
+        );
+        assert_eq!(in_memory.inner().rows, expected, "{keep:?} in memory");
+
+        let mut spilled = DedupSink::new(CollectSink::default(), [KEY.to_lowercase()])
+            .with_keep(keep)
+            .with_spill_dir(spill_dir.path())
+            .with_spill_memory(4096);
+        sas.stream_into(&mut spilled).expect("dedup with spill");
+        assert_eq!(spilled.removed_rows(), in_memory.removed_rows());
+        assert_eq!(spilled.inner().rows, expected, "{keep:?} spilled");
+    }
+
+    let leftovers = std::fs::read_dir(spill_dir.path()).expect("list spill dir");
+    assert_eq!(leftovers.count(), 0, "spill files are removed");
+}
+
+#[test]
+fn spilled_dedup_merges_more_runs_than_fit_open_at_once() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let spill_dir = tempfile::tempdir().expect("temp dir");
+
+    for keep in [DedupKeep::First, DedupKeep::Last] {
+        let keys = ["COUNTRY", "PRODUCT", "YEAR"];
+        let mut in_memory = DedupSink::new(CollectSink::default(), keys).with_keep(keep);
+        sas.stream_into(&mut in_memory).expect("dedup in memory");
+
+        // A one-byte budget writes every row to a run of its own.
+        let mut spilled = DedupSink::new(CollectSink::default(), keys)
+            .with_keep(keep)
+            .with_spill_dir(spill_dir.path())
+            .with_spill_memory(1);
+        sas.stream_into(&mut spilled).expect("dedup with spill");
+        assert!(spilled.removed_rows() > 0);
+        assert_eq!(spilled.removed_rows(), in_memory.removed_rows());
+        assert_eq!(spilled.inner().rows, in_memory.inner().rows, "{keep:?}");
+    }
+    assert_eq!(
+        std::fs::read_dir(spill_dir.path()).expect("list").count(),
+        0
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn spill_partitions_still_configure_a_spilling_sink() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let spill_dir = tempfile::tempdir().expect("temp dir");
+    let keys = ["COUNTRY", "PRODUCT"];
+
+    let mut in_memory = DedupSink::new(CollectSink::default(), keys);
+    sas.stream_into(&mut in_memory).expect("dedup in memory");
+    let mut partitioned = DedupSink::new(CollectSink::default(), keys)
+        .with_spill_dir(spill_dir.path())
+        .with_spill_partitions(0);
+    sas.stream_into(&mut partitioned).expect("dedup with spill");
+    assert_eq!(partitioned.inner().rows, in_memory.inner().rows);
+}
@@ -0,0 +1,71 @@
+use sas7bdat::{CellValue, SasReader};
+use sas7bdat_test_support::common;
+use std::borrow::Cow;
+
+#[test]
+fn from_bytes_matches_file_rows() {
+    for fixture in [
+        "fixtures/raw_data/pandas/airline.sas7bdat",
+        "fixtures/raw_data/pandas/test1.sas7bdat",
+        "fixtures/raw_data/pandas/test2.sas7bdat",
+        "fixtures/raw_data/pandas/test4.sas7bdat",
+        "fixtures/raw_data/pandas/many_columns.sas7bdat",
+        "fixtures/raw_data/pandas/zero_rows.sas7bdat",
+    ] {
+        let path = common::fixture_path(fixture);
+        let expected = SasReader::open(&path)
+            .expect("open dataset")
+            .into_iter()
+            .expect("row iterator")
+            .collect::<sas7bdat::Result<Vec<_>>>()
+            .expect("rows");
+
+        let mut sas = SasReader::from_bytes(std::fs::read(&path).expect("read fixture"))
+            .expect("parse in-memory dataset");
+        for _pass in 0..2 {
+            let actual = sas
+                .rows()
+                .expect("row iterator")
+                .map(|row| row.expect("row"))
+                .collect::<Vec<_>>();
+            assert_eq!(format!("{actual:?}"), format!("{expected:?}"), "{fixture}");
+        }
+    }
+}
+
+#[test]
+fn from_bytes_borrows_strings_from_the_input() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let data = std::fs::read(path).expect("read fixture");
+    let input = data.as_ptr_range();
+    let mut sas = SasReader::from_bytes(data).expect("parse in-memory dataset");
+
+    let mut rows = sas.rows().expect("row iterator");
+    let mut borrowed = 0;
+    while let Some(row) = rows.try_next().expect("row") {
+        for value in &row {
+            if let CellValue::Str(Cow::Borrowed(text)) = value
+                && !text.is_empty()
+            {
+                assert!(input.contains(&text.as_ptr()), "string outside the input");
+                borrowed += 1;
+            }
+        }
+    }
+    assert!(borrowed > 0, "no borrowed strings decoded");
+}
+
+#[test]
+fn from_bytes_rejects_truncated_pages() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut data = std::fs::read(path).expect("read fixture");
+    data.truncate(data.len() - 100);
+    let Ok(mut sas) = SasReader::from_bytes(data) else {
+        return;
+    };
+    let result = sas
+        .rows()
+        .expect("row iterator")
+        .collect::<sas7bdat::Result<Vec<_>>>();
+    assert!(result.is_err());
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "http")]
+
+use sas7bdat::{CellValue, HttpOptions, MetadataReadOptions, SasReader};
+use sas7bdat_test_support::common;
+use std::{
+    io::{BufRead, BufReader, Read, Seek, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+/// Serves `data` over HTTP/1.1 keep-alive connections. Every `fail_every`-th
+/// request is answered with 503, and ranges are ignored when
+/// `honour_ranges` is false.
+fn serve(data: Vec<u8>, fail_every: usize, honour_ranges: bool) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!(
+        "http://{}/data/test1.sas7bdat",
+        listener.local_addr().unwrap()
+    );
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+    let data = Arc::new(data);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let data = Arc::clone(&data);
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || handle(stream, &data, &counter, fail_every, honour_ranges));
+        }
+    });
+    (url, requests)
+}
+
+fn handle(
+    stream: TcpStream,
+    data: &[u8],
+    counter: &AtomicUsize,
+    fail_every: usize,
+    honour_ranges: bool,
+) {
+    let mut writer = stream.try_clone().expect("clone stream");
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut range = None;
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header).unwrap_or(0) == 0 {
+                return;
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("range")
+            {
+                let (start, end) = value
+                    .trim()
+                    .trim_start_matches("bytes=")
+                    .split_once('-')
+                    .unwrap();
+                range = Some((
+                    start.parse::<usize>().unwrap(),
+                    end.parse::<usize>().unwrap(),
+                ));
+            }
+        }
+
+        let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        let response = if fail_every > 0 && count.is_multiple_of(fail_every) {
+            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n".to_vec()
+        } else if let (true, Some((start, end))) = (honour_ranges, range) {
+            let end = end.min(data.len() - 1);
+            let body = &data[start..=end];
+            let mut response = format!(
+                "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {start}-{end}/{}\r\nContent-Length: {}\r\n\r\n",
+                data.len(),
+                body.len()
+            )
+            .into_bytes();
+            response.extend_from_slice(body);
+            response
+        } else {
+            let mut response =
+                format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", data.len()).into_bytes();
+            response.extend_from_slice(data);
+            response
+        };
+        if writer.write_all(&response).is_err() {
+            return;
+        }
+    }
+}
+
+fn read_all_rows<R: Read + Seek>(sas: &mut SasReader<R>) -> Vec<Vec<CellValue<'static>>> {
+    let mut rows = sas.rows().expect("row iterator");
+    let mut collected = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        collected.push(row.into_iter().map(CellValue::into_owned).collect());
+    }
+    collected
+}
+
+fn fast_retries() -> HttpOptions {
+    HttpOptions::default()
+        .with_block_size(4096)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(4))
+}
+
+#[test]
+fn streams_rows_over_range_requests_with_retries() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let data = std::fs::read(&path).expect("read fixture");
+    let file_len = data.len();
+    let (url, requests) = serve(data, 3, true);
+
+    let mut remote =
+        SasReader::open_url_with_options(&url, fast_retries(), MetadataReadOptions::default())
+            .expect("open url");
+    let mut local = SasReader::open(&path).expect("open file");
+    assert_eq!(read_all_rows(&mut remote), read_all_rows(&mut local));
+
+    let served = requests.load(Ordering::SeqCst);
+    assert!(served >= 3, "at least one request was retried");
+    assert!(
+        served < file_len / 4096 * 6,
+        "blocks are cached between reads"
+    );
+}
+
+#[test]
+fn rejects_servers_without_range_support() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let (url, _) = serve(std::fs::read(path).expect("read fixture"), 0, false);
+    let err =
+        SasReader::open_url_with_options(&url, fast_retries(), MetadataReadOptions::default())
+            .err()
+            .expect("range support is required");
+    assert!(err.to_string().contains("range requests"), "{err}");
+}
@@ -169,8 +169,7 @@ fn assert_value_matches_csv(
         }
         CellValue::NumericString(actual) | CellValue::Str(actual) => {
             assert_eq!(
-                actual.as_ref(),
-                expected,
+                &**actual, expected,
                 "string mismatch for {column} in {file} row {row_idx}: actual {actual:?}, expected {expected:?}"
             );
         }
@@ -0,0 +1,48 @@
+#![cfg(feature = "ipc")]
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::FileReader;
+use sas7bdat::{ArrowSink, IpcSink, SasReader};
+use sas7bdat_test_support::common;
+use std::io::Cursor;
+
+fn open(file: &str) -> SasReader<std::fs::File> {
+    let path = common::fixture_path("fixtures/raw_data/pandas").join(file);
+    SasReader::open(path).expect("open dataset")
+}
+
+fn read_back(bytes: Vec<u8>) -> Vec<RecordBatch> {
+    FileReader::try_new(Cursor::new(bytes), None)
+        .expect("IPC file")
+        .collect::<Result<_, _>>()
+        .expect("record batches")
+}
+
+#[test]
+fn ipc_file_round_trips_arrow_batches() {
+    for file in ["datetime.sas7bdat", "airline.sas7bdat", "test1.sas7bdat"] {
+        let mut expected = ArrowSink::new().with_batch_rows(16);
+        open(file).stream_into(&mut expected).expect("arrow rows");
+
+        let mut sink = IpcSink::new(Vec::new()).with_batch_rows(16);
+        open(file).stream_into(&mut sink).expect("ipc rows");
+        let batches = read_back(sink.into_inner().expect("output"));
+
+        assert_eq!(batches, expected.into_batches(), "{file}");
+    }
+}
+
+#[test]
+fn empty_dataset_writes_schema_only_file() {
+    let mut sas = open("zero_rows.sas7bdat");
+    let mut sink = IpcSink::new(Vec::new());
+    sas.stream_into(&mut sink).expect("stream rows");
+    let bytes = sink.into_inner().expect("output");
+
+    let reader = FileReader::try_new(Cursor::new(bytes), None).expect("IPC file");
+    assert_eq!(
+        reader.schema().fields().len(),
+        sas.metadata().variables.len()
+    );
+    assert_eq!(reader.count(), 0);
+}
@@ -0,0 +1,140 @@
+use sas7bdat::{CellValue, DateStyle, JsonlSink, RowSelection, SasReader};
+use sas7bdat_test_support::common::{self, CollectSink};
+use serde_json::Value;
+
+#[test]
+fn jsonl_sink_writes_one_object_per_row() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let mut expected = CollectSink::default();
+    sas.stream_into(&mut expected).expect("collect rows");
+
+    let mut sink = JsonlSink::new(Vec::new());
+    sas.stream_into_low_memory(&mut sink).expect("write jsonl");
+    let output = String::from_utf8(sink.into_inner().expect("output")).expect("utf-8");
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), expected.rows.len());
+    for (line, row) in lines.iter().zip(&expected.rows) {
+        let Value::Object(object) = serde_json::from_str(line).expect("valid json") else {
+            panic!("row is not an object: {line}");
+        };
+        let keys: Vec<&str> = object.keys().map(String::as_str).collect();
+        let names: Vec<&str> = expected.names.iter().map(|name| name.trim_end()).collect();
+        assert_eq!(keys, names);
+        for (value, cell) in object.values().zip(row) {
+            match cell {
+                CellValue::Missing(_) => assert!(value.is_null()),
+                CellValue::Float(number) => assert_eq!(value.as_f64(), Some(*number)),
+                CellValue::Date(date) => {
+                    assert_eq!(value.as_str(), Some(date.date().to_string().as_str()));
+                }
+                _ => assert!(value.is_string(), "unexpected value {value} for {cell:?}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn stream_selection_into_projects_and_windows_rows() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let names: Vec<String> = sas
+        .metadata()
+        .variables
+        .iter()
+        .map(|variable| variable.name.clone())
+        .collect();
+    let picked = [names[2].as_str(), names[0].as_str()];
+    let selection = RowSelection::new()
+        .columns(&picked)
+        .skip_rows(2)
+        .max_rows(4);
+
+    let expected = sas
+        .select_with(&selection)
+        .expect("projected window")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("rows");
+    let mut sink = CollectSink::default();
+    sas.stream_selection_into(&selection, &mut sink)
+        .expect("stream selection");
+
+    assert_eq!(sink.names, [names[2].clone(), names[0].clone()]);
+    assert_eq!(sink.rows.len(), 4);
+    assert_eq!(format!("{:?}", sink.rows), format!("{expected:?}"));
+
+    let mut all = CollectSink::default();
+    sas.stream_selection_into(&RowSelection::new().max_rows(3), &mut all)
+        .expect("stream window");
+    assert_eq!(all.names, names);
+    assert_eq!(all.rows.len(), 3);
+}
+
+#[test]
+fn row_to_json_matches_jsonl_lines() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let mut sink = JsonlSink::new(Vec::new());
+    sas.stream_into(&mut sink).expect("write jsonl");
+    let output = String::from_utf8(sink.into_inner().expect("output")).expect("utf-8");
+
+    let rows = sas
+        .rows_named()
+        .expect("rows")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("decode rows");
+    assert_eq!(rows.len(), output.lines().count());
+    for (row, line) in rows.iter().zip(output.lines()) {
+        let parsed: Value = serde_json::from_str(line).expect("valid json");
+        assert_eq!(row.to_json(DateStyle::Iso).expect("row json"), parsed);
+    }
+
+    let mut views = sas.stream_rows().expect("row views");
+    let view = views.try_next().expect("decode").expect("first row");
+    assert_eq!(
+        view.to_json(DateStyle::Iso).expect("view json"),
+        rows[0].to_json(DateStyle::Iso).expect("row json")
+    );
+}
+
+#[test]
+fn date_styles_write_numbers_and_projected_views_keep_their_columns() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let first = sas
+        .rows_named()
+        .expect("rows")
+        .next()
+        .expect("first row")
+        .expect("decode row");
+    let index = first
+        .values()
+        .iter()
+        .position(|value| matches!(value, CellValue::Date(_)))
+        .expect("date column");
+    let CellValue::Date(date) = first.values()[index] else {
+        unreachable!()
+    };
+    let date_column = sas.metadata().variables[index].name.trim_end().to_owned();
+
+    let mut views = sas
+        .stream_rows_with_projection(&[date_column.as_str()])
+        .expect("projected views");
+    let view = views.try_next().expect("decode").expect("first row");
+
+    let iso = view.to_json(DateStyle::Iso).expect("iso json");
+    let object = iso.as_object().expect("object");
+    assert_eq!(object.keys().collect::<Vec<_>>(), [&date_column]);
+    assert_eq!(object[&date_column], Value::from(date.date().to_string()));
+
+    let sas_days = view.to_json(DateStyle::Sas).expect("sas json");
+    let days = (date.date() - sas7bdat::time_conv::SAS_EPOCH.date()).whole_days();
+    assert_eq!(sas_days[&date_column], Value::from(days));
+
+    let millis = view.to_json(DateStyle::UnixMillis).expect("millis json");
+    assert_eq!(
+        millis[&date_column],
+        Value::from(date.unix_timestamp() * 1000)
+    );
+}
@@ -0,0 +1,39 @@
+use sas7bdat::{CellValue, MeltSink, SasReader};
+use sas7bdat_test_support::common::{self, CollectSink};
+
+#[test]
+fn melt_sink_unpivots_measures_into_long_rows() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let wide: Vec<Vec<CellValue<'static>>> = {
+        let mut rows = sas.rows().expect("row iterator");
+        let mut collected = Vec::new();
+        while let Some(row) = rows.try_next().expect("row") {
+            collected.push(row.into_iter().map(CellValue::into_owned).collect());
+        }
+        collected
+    };
+
+    let mut sink = MeltSink::new(CollectSink::default(), ["column2"], ["Column1", "Column3"]);
+    sas.stream_into(&mut sink).expect("melt rows");
+    let sink = sink.into_inner();
+
+    assert_eq!(sink.names, ["Column2", "variable", "value"]);
+    assert_eq!(sink.rows.len(), wide.len() * 2);
+    for (wide_row, long_rows) in wide.iter().zip(sink.rows.chunks(2)) {
+        for (long_row, (measure, index)) in long_rows.iter().zip([("Column1", 0), ("Column3", 2)]) {
+            assert_eq!(long_row[0], wide_row[1]);
+            assert_eq!(long_row[1], CellValue::Str(measure.into()));
+            assert_eq!(long_row[2], wide_row[index]);
+        }
+    }
+}
+
+#[test]
+fn melt_sink_rejects_mixed_measure_types() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let mut sink = MeltSink::new(CollectSink::default(), ["Column3"], ["Column1", "Column2"]);
+    let err = sas.stream_into(&mut sink).unwrap_err();
+    assert!(err.to_string().contains("Column2"), "{err}");
+}
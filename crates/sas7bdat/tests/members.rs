@@ -0,0 +1,85 @@
+use sas7bdat::{SasReader, reader::Members};
+use sas7bdat_test_support::common;
+use std::{fs, io::Cursor};
+
+fn fixture_bytes(name: &str) -> Vec<u8> {
+    fs::read(common::fixture_path(format!(
+        "fixtures/raw_data/pandas/{name}"
+    )))
+    .expect("read fixture")
+}
+
+fn count_rows<R: std::io::Read + std::io::Seek>(sas: &mut SasReader<R>) -> usize {
+    let mut rows = sas.rows().expect("row iterator");
+    let mut count = 0;
+    while rows.try_next().expect("decode row").is_some() {
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn single_file_has_one_member() {
+    let mut sas =
+        SasReader::from_reader(Cursor::new(fixture_bytes("test1.sas7bdat"))).expect("open fixture");
+    assert_eq!(sas.trailing_member_offset().expect("probe"), None);
+
+    let mut members = Members::new(Cursor::new(fixture_bytes("test1.sas7bdat")));
+    assert!(members.next_member().expect("first member").is_some());
+    assert!(members.next_member().expect("end of stream").is_none());
+    assert_eq!(members.members_read(), 1);
+}
+
+#[test]
+fn iterates_concatenated_members() {
+    let first = fixture_bytes("test1.sas7bdat");
+    let second = fixture_bytes("datetime.sas7bdat");
+    let mut blob = first.clone();
+    blob.extend_from_slice(&second);
+    blob.extend_from_slice(b"trailing bytes");
+
+    let mut sas = SasReader::from_reader(Cursor::new(blob.clone())).expect("open blob");
+    assert_eq!(sas.member_len(), first.len() as u64);
+    assert_eq!(
+        sas.trailing_member_offset().expect("probe"),
+        Some(first.len() as u64)
+    );
+
+    let mut members = Members::new(Cursor::new(blob));
+    let mut summaries = Vec::new();
+    while let Some(mut member) = members.next_member().expect("open member") {
+        let columns = member.metadata().column_count;
+        summaries.push((columns, count_rows(&mut member)));
+    }
+
+    let expected_second = SasReader::from_reader(Cursor::new(second)).expect("open second");
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0], (100, 10));
+    assert_eq!(summaries[1].0, expected_second.metadata().column_count);
+    assert_eq!(summaries[1].1 as u64, expected_second.metadata().row_count);
+}
+
+#[test]
+fn member_seeks_stay_inside_the_member() {
+    use sas7bdat::reader::MemberReader;
+    use std::io::{Seek, SeekFrom};
+
+    let first = fixture_bytes("test1.sas7bdat");
+    let second = fixture_bytes("datetime.sas7bdat");
+    let mut blob = first.clone();
+    blob.extend_from_slice(&second);
+    blob.extend_from_slice(b"trailing bytes");
+
+    let mut member = MemberReader::new(Cursor::new(blob), first.len() as u64);
+    let end = member.seek(SeekFrom::End(0)).expect("seek to end");
+    assert_eq!(end, second.len() as u64);
+    assert_eq!(
+        member.seek(SeekFrom::End(-10)).expect("seek back"),
+        end - 10
+    );
+    assert_eq!(
+        member.seek(SeekFrom::Current(-10_000_000)).expect("clamp"),
+        0
+    );
+    assert_eq!(member.seek(SeekFrom::Current(3)).expect("forward"), 3);
+}
@@ -1,14 +1,266 @@
-use sas7bdat::{Error, decode_layout};
-use std::io::Cursor;
+use sas7bdat::{
+    CellValue, Error, ErrorCategory, MetadataReadOptions, SasReader,
+    dataset::{Compression, DatasetTimestamps, Format, TimestampSource},
+    decode_layout,
+};
+use sas7bdat_test_support::common;
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek, SeekFrom},
+};
 
 #[test]
 fn rejects_non_sas_streams() {
     let data = b"not a sas dataset";
     let mut cursor = Cursor::new(&data[..]);
     match decode_layout(&mut cursor).unwrap_err() {
-        Error::Corrupted { .. } | Error::InvalidMetadata { .. } | Error::Io(_) => {
+        Error::NotSasFile { .. }
+        | Error::Corrupted { .. }
+        | Error::InvalidMetadata { .. }
+        | Error::Io(_) => {
             // short buffer also acceptable at this stage
         }
         other => panic!("unexpected error: {other}"),
     }
 }
+
+#[test]
+fn wrong_magic_is_categorised_as_not_sas_file() {
+    let data = vec![0u8; 2048];
+    let err = decode_layout(&mut Cursor::new(data)).unwrap_err();
+    assert!(
+        matches!(err, Error::NotSasFile { .. }),
+        "unexpected error: {err}"
+    );
+    assert_eq!(err.category(), ErrorCategory::NotSasFile);
+    assert_eq!(err.code(), "not_sas_file");
+}
+
+#[test]
+fn backfill_fills_only_missing_timestamps() {
+    let file = tempfile::NamedTempFile::new().expect("create temp file");
+    let file_metadata = file.as_file().metadata().expect("read file metadata");
+    let header_time = time::macros::datetime!(2020-01-02 3:04:05 UTC);
+
+    let mut timestamps = DatasetTimestamps {
+        created: Some(header_time),
+        ..DatasetTimestamps::default()
+    };
+    timestamps.backfill_from_file(&file_metadata);
+
+    assert_eq!(timestamps.created, Some(header_time));
+    assert_eq!(timestamps.created_source, TimestampSource::Header);
+    assert!(timestamps.modified.is_some());
+    assert_eq!(timestamps.modified_source, TimestampSource::FileSystem);
+}
+
+#[test]
+fn open_with_backfill_keeps_header_timestamps() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let options = MetadataReadOptions::default().with_timestamp_backfill(true);
+    let sas = SasReader::open_with_options(&path, options).expect("open fixture");
+    let timestamps = &sas.metadata().timestamps;
+
+    assert!(timestamps.created.is_some());
+    assert!(timestamps.modified.is_some());
+    assert_eq!(timestamps.created_source, TimestampSource::Header);
+    assert_eq!(timestamps.modified_source, TimestampSource::Header);
+}
+
+fn open_test1() -> SasReader<std::fs::File> {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    SasReader::open(&path).expect("open fixture")
+}
+
+#[test]
+fn rename_and_relabel_apply_to_streamed_rows() {
+    let mut sas = open_test1();
+    sas.edit_metadata()
+        .rename("Column1", "score")
+        .and_then(|editor| editor.set_label("score", Some("Test score")))
+        .expect("edit metadata");
+
+    let variable = &sas.metadata().variables[0];
+    assert_eq!(variable.name, "score");
+    assert_eq!(variable.label.as_deref(), Some("Test score"));
+
+    let row = sas
+        .rows_named()
+        .expect("row iterator")
+        .next()
+        .expect("first row")
+        .expect("decode row");
+    assert!(row.get("Column1").is_none());
+    assert!(matches!(row.get("score"), Some(CellValue::Float(_))));
+}
+
+#[test]
+fn edits_are_validated() {
+    let mut sas = open_test1();
+    let mut editor = sas.edit_metadata();
+
+    assert!(matches!(
+        editor.rename("Column1", "column2"),
+        Err(Error::InvalidArgument { .. })
+    ));
+    assert!(editor.rename("Column1", "").is_err());
+    assert!(editor.rename("NoSuchColumn", "x").is_err());
+    assert!(
+        editor
+            .set_format(
+                "Column1",
+                Some(Format {
+                    name: "$CHAR".into(),
+                    width: Some(8),
+                    decimals: None,
+                }),
+            )
+            .is_err()
+    );
+    assert_eq!(sas.metadata().variables[0].name, "Column1");
+}
+
+#[test]
+fn numeric_format_changes_decoded_type() {
+    let mut sas = open_test1();
+    sas.edit_metadata()
+        .set_format(
+            "Column1",
+            Some(Format {
+                name: "DATE".into(),
+                width: Some(9),
+                decimals: None,
+            }),
+        )
+        .expect("set format");
+
+    let row = sas
+        .rows_named()
+        .expect("row iterator")
+        .next()
+        .expect("first row")
+        .expect("decode row");
+    assert!(matches!(row.get("Column1"), Some(CellValue::Date(_))));
+}
+
+#[test]
+fn open_attaches_path_context_to_reader_logger() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    assert_eq!(
+        sas.logger().context(),
+        Some(path.display().to_string().as_str())
+    );
+
+    let logger = sas7bdat::logger::Logger::default().with_context("renamed");
+    sas.set_logger(logger);
+    assert_eq!(sas.logger().context(), Some("renamed"));
+}
+
+#[test]
+fn compression_report_covers_every_row() {
+    let mut saw_compressed = false;
+    for name in ["test1", "test4", "test5", "test10", "test13", "test16"] {
+        let path = common::fixture_path(format!("fixtures/raw_data/pandas/{name}.sas7bdat"));
+        let mut sas = SasReader::open(&path).expect("open dataset");
+        let report = sas.compression_report().expect("compression report");
+        assert_eq!(report.rows(), sas.metadata().row_count, "{name}");
+        if report.compression == Compression::None {
+            assert_eq!(report.stored_bytes(), report.expanded_bytes(), "{name}");
+        } else {
+            saw_compressed = true;
+            assert!(report.pages.iter().any(|page| page.compressed_rows > 0));
+            assert!(report.ratio() < 1.0, "{name}: {}", report.ratio());
+        }
+        assert_eq!(
+            report.expanded_bytes(),
+            report.rows() * report.row_length,
+            "{name}"
+        );
+    }
+    assert!(saw_compressed, "fixtures include a compressed file");
+}
+
+#[test]
+fn describe_lists_attributes_and_variables() {
+    let sas = open_test1();
+    let metadata = sas.metadata();
+
+    let text = sas.describe().to_string();
+    assert!(text.contains(&format!("Observations   {}", metadata.row_count)));
+    assert!(text.contains(&format!("Variables      {}", metadata.variables.len())));
+    let variable_lines: Vec<&str> = text
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("# "))
+        .skip(1)
+        .collect();
+    assert_eq!(variable_lines.len(), metadata.variables.len());
+    for (line, variable) in variable_lines.iter().zip(&metadata.variables) {
+        assert!(line.contains(variable.name.trim_end()), "{line}");
+    }
+
+    let markdown = sas.describe().markdown().to_string();
+    assert!(markdown.starts_with("| Attribute | Value |"));
+    assert!(markdown.contains("| # | Variable | Type | Len | Format | Informat | Label |"));
+    let variable_rows = markdown
+        .lines()
+        .skip_while(|line| !line.starts_with("| # |"))
+        .skip(2);
+    assert_eq!(variable_rows.count(), metadata.variables.len());
+}
+
+/// Records the offset and length of every read.
+struct CountingReader<R> {
+    inner: R,
+    reads: Vec<(u64, usize)>,
+}
+
+impl<R: Read + Seek> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let offset = self.inner.stream_position()?;
+        self.reads.push((offset, buf.len()));
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn opening_reads_each_page_header_at_most_once() {
+    let dir = common::fixture_path("fixtures/raw_data/pandas");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(dir).expect("fixture dir") {
+        let path = entry.expect("dir entry").path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("sas7bdat") {
+            continue;
+        }
+        let mut reader = CountingReader {
+            inner: File::open(&path).expect("open fixture"),
+            reads: Vec::new(),
+        };
+        let Ok(layout) = decode_layout(&mut reader) else {
+            continue;
+        };
+        let header = &layout.header;
+        let header_len = header.page_header_size as usize;
+        for page in 0..header.page_count {
+            let start = header.data_offset + page * u64::from(header.page_size);
+            let reads = reader
+                .reads
+                .iter()
+                .filter(|&&(offset, len)| offset == start && len == header_len)
+                .count();
+            assert!(
+                reads <= 1,
+                "{}: page {page} header read {reads} times",
+                path.display()
+            );
+        }
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
@@ -0,0 +1,114 @@
+#![cfg(all(feature = "metrics", feature = "csv"))]
+
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use sas7bdat::{CsvSink, SasReader, telemetry};
+use sas7bdat_test_support::common;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Sums every counter increment and counts histogram samples by metric name.
+#[derive(Default)]
+struct TotalsRecorder {
+    totals: Arc<Mutex<HashMap<String, (u64, usize)>>>,
+}
+
+struct Handle {
+    name: String,
+    totals: Arc<Mutex<HashMap<String, (u64, usize)>>>,
+}
+
+impl CounterFn for Handle {
+    fn increment(&self, value: u64) {
+        self.totals
+            .lock()
+            .unwrap()
+            .entry(self.name.clone())
+            .or_default()
+            .0 += value;
+    }
+
+    fn absolute(&self, value: u64) {
+        self.totals
+            .lock()
+            .unwrap()
+            .entry(self.name.clone())
+            .or_default()
+            .0 = value;
+    }
+}
+
+impl HistogramFn for Handle {
+    fn record(&self, value: f64) {
+        assert!(value >= 0.0, "{} recorded {value}", self.name);
+        self.totals
+            .lock()
+            .unwrap()
+            .entry(self.name.clone())
+            .or_default()
+            .1 += 1;
+    }
+}
+
+impl TotalsRecorder {
+    fn handle(&self, key: &Key) -> Arc<Handle> {
+        Arc::new(Handle {
+            name: key.name().to_owned(),
+            totals: Arc::clone(&self.totals),
+        })
+    }
+
+    fn get(&self, name: &str) -> (u64, usize) {
+        self.totals
+            .lock()
+            .unwrap()
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Recorder for TotalsRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.handle(key))
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.handle(key))
+    }
+}
+
+#[test]
+fn conversion_reports_pages_rows_and_flushes() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let recorder = TotalsRecorder::default();
+
+    let row_count = metrics::with_local_recorder(&recorder, || {
+        let mut sas = SasReader::open(&path).expect("open dataset");
+        let mut sink = CsvSink::new(Vec::new());
+        sas.stream_into(&mut sink).expect("convert");
+        sas.metadata().row_count
+    });
+
+    let (rows, _) = recorder.get(telemetry::ROWS_DECODED);
+    assert_eq!(rows, row_count);
+    let (pages, _) = recorder.get(telemetry::PAGES_READ);
+    let (_, page_samples) = recorder.get(telemetry::PAGE_DECODE_SECONDS);
+    assert!(page_samples > 0);
+    assert!(pages >= u64::try_from(page_samples).unwrap());
+    assert_eq!(recorder.get(telemetry::SINK_FLUSH_SECONDS).1, 1);
+}
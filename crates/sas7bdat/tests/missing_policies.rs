@@ -1,5 +1,6 @@
-use sas7bdat::SasReader;
+use sas7bdat::{SasReader, reader::MissingScanOptions};
 use sas7bdat_test_support::common;
+use std::ops::ControlFlow;
 
 fn tagged_tags(policy: &sas7bdat::dataset::MissingValuePolicy) -> Vec<char> {
     policy
@@ -16,6 +17,10 @@ fn scan_missing_policies_records_numeric_tags() {
 
     let mut sas = SasReader::open(data).expect("open dataset");
     sas.attach_catalog(catalog).expect("load catalog");
+    let summary = sas.scan_missing_policies().expect("scan missing policies");
+    assert!(summary.complete);
+    assert!(!summary.cancelled);
+    assert_eq!(summary.rows_scanned, sas.metadata().row_count);
 
     let metadata = sas.metadata();
     let variables = &metadata.variables;
@@ -41,3 +46,62 @@ fn scan_missing_policies_records_numeric_tags() {
     let var7 = variables.iter().find(|var| var.name == "var7").unwrap();
     assert!(var7.missing.system_missing);
 }
+
+#[test]
+fn sampled_scan_stops_at_page_limit() {
+    let mut sas = open_missing_test();
+    let summary = sas
+        .scan_missing_policies_with(MissingScanOptions::new().max_pages(0), |_| {
+            ControlFlow::Continue(())
+        })
+        .expect("sampled scan");
+
+    assert_eq!(summary.rows_scanned, 0);
+    assert!(!summary.complete);
+    assert!(!summary.cancelled);
+}
+
+#[test]
+fn progress_callback_can_cancel_scan() {
+    let mut sas = open_missing_test();
+    let mut calls = 0;
+    let summary = sas
+        .scan_missing_policies_with(MissingScanOptions::new(), |progress| {
+            calls += 1;
+            assert!(progress.rows_processed > 0);
+            ControlFlow::Break(())
+        })
+        .expect("cancelled scan");
+
+    assert_eq!(calls, 1);
+    assert!(summary.cancelled);
+    assert!(!summary.complete);
+}
+
+#[test]
+fn progress_events_report_throughput() {
+    let mut sas = open_missing_test();
+    let mut events = Vec::new();
+    let summary = sas
+        .scan_missing_policies_with(MissingScanOptions::new(), |progress| {
+            events.push(*progress);
+            ControlFlow::Continue(())
+        })
+        .expect("full scan");
+
+    assert!(summary.complete);
+    let last = events.last().expect("at least one progress event");
+    assert_eq!(last.rows_processed, summary.rows_scanned);
+    assert_eq!(last.page_index + 1, last.pages_processed);
+    assert!(last.bytes_processed <= last.total_bytes);
+    assert!(last.rows_per_sec >= 0.0 && last.bytes_per_sec >= 0.0);
+    for pair in events.windows(2) {
+        assert!(pair[1].page_index > pair[0].page_index);
+        assert!(pair[1].elapsed >= pair[0].elapsed);
+    }
+}
+
+fn open_missing_test() -> SasReader<std::fs::File> {
+    let data = common::fixture_path("fixtures/raw_data/readstat/missing_test.sas7bdat");
+    SasReader::open(data).expect("open dataset")
+}
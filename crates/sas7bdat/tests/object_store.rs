@@ -0,0 +1,96 @@
+#![cfg(feature = "object_store")]
+
+use object_store::{ObjectStore, ObjectStoreExt, memory::InMemory, path::Path};
+use sas7bdat::{ObjectStoreOptions, ObjectStoreReader, SasReader};
+use sas7bdat_test_support::common;
+use std::{fs, sync::Arc};
+
+const FIXTURE: &str = "fixtures/raw_data/pandas/productsales.sas7bdat";
+
+fn store_with_fixture() -> (Arc<dyn ObjectStore>, Path) {
+    let store = InMemory::new();
+    let location = Path::from("extracts/productsales.sas7bdat");
+    let data = fs::read(common::fixture_path(FIXTURE)).expect("read fixture");
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime")
+        .block_on(store.put(&location, data.into()))
+        .expect("upload fixture");
+    (Arc::new(store), location)
+}
+
+fn rows_debug<R: std::io::Read + std::io::Seek>(sas: &mut SasReader<R>) -> String {
+    let rows = sas
+        .rows()
+        .expect("rows")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("decode rows");
+    format!("{:?}|{rows:?}", sas.metadata().variables)
+}
+
+#[test]
+fn reads_objects_like_local_files() {
+    let expected = rows_debug(&mut SasReader::open(common::fixture_path(FIXTURE)).expect("open"));
+    let (store, location) = store_with_fixture();
+    let options = ObjectStoreOptions::default().with_block_size(64 * 1024);
+    let reader = ObjectStoreReader::from_store(store, location, options).expect("open object");
+    let mut sas = SasReader::from_reader(reader).expect("parse metadata");
+    assert_eq!(rows_debug(&mut sas), expected);
+
+    // The 145 KiB file spans three blocks, each fetched once.
+    let (reader, _) = sas.into_parts();
+    assert_eq!(reader.requests(), 3);
+}
+
+#[test]
+fn small_caches_refetch_evicted_blocks() {
+    let (store, location) = store_with_fixture();
+    let options = ObjectStoreOptions::default()
+        .with_block_size(16 * 1024)
+        .with_cache_blocks(0);
+    let reader = ObjectStoreReader::from_store(store, location, options).expect("open object");
+    let mut sas = SasReader::from_reader(reader).expect("parse metadata");
+    assert_eq!(sas.rows().expect("rows").count(), 1440);
+    let (reader, _) = sas.into_parts();
+    assert!(reader.requests() > 10, "{}", reader.requests());
+}
+
+#[test]
+fn rejects_urls_without_a_supported_store() {
+    let Err(err) = SasReader::open_object_store("ftp://example.com/data.sas7bdat") else {
+        panic!("opened an ftp URL");
+    };
+    assert!(matches!(err, sas7bdat::Error::Unsupported { .. }), "{err}");
+}
+
+#[test]
+fn reads_inside_async_runtimes() {
+    let multi_thread = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .build()
+        .expect("runtime");
+    let current_thread = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime");
+    for runtime in [multi_thread, current_thread] {
+        let (store, location) = store_with_fixture();
+        let rows = runtime.block_on(async {
+            let options = ObjectStoreOptions::default().with_block_size(16 * 1024);
+            let reader = ObjectStoreReader::from_store(store, location, options).expect("open");
+            let mut sas = SasReader::from_reader(reader).expect("parse metadata");
+            sas.rows().expect("rows").count()
+        });
+        assert_eq!(rows, 1440);
+    }
+}
+
+#[test]
+fn open_url_routes_object_store_urls() {
+    let Err(err) = SasReader::open_url("memory:///missing.sas7bdat") else {
+        panic!("opened a missing object");
+    };
+    let sas7bdat::Error::Io(io) = &err else {
+        panic!("{err}");
+    };
+    assert_eq!(io.kind(), std::io::ErrorKind::NotFound, "{err}");
+}
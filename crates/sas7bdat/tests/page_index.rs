@@ -0,0 +1,98 @@
+use sas7bdat::{RowSelection, SasReader, dataset::PageIndex};
+use sas7bdat_test_support::common;
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    common::fixture_path(format!("fixtures/raw_data/pandas/{name}"))
+}
+
+fn row_count(sas: &mut SasReader<std::fs::File>) -> u64 {
+    let mut rows = sas.rows().expect("row iterator");
+    let mut count = 0;
+    while rows.try_next().expect("row iteration failed").is_some() {
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn build_index_covers_every_row_and_round_trips() {
+    // test2 is RLE-compressed, so row positions come from the pointer tables.
+    let mut sas = SasReader::open(fixture("test2.sas7bdat")).expect("open fixture");
+    let rows = row_count(&mut sas);
+    let index = sas.build_index().expect("build index").clone();
+
+    assert_eq!(index.rows(), rows);
+    assert!(!index.pages().is_empty());
+    let last = index.locate(rows - 1).expect("last row is indexed");
+    assert_eq!(last, *index.pages().last().expect("pages"));
+    assert!(index.locate(rows).is_none());
+
+    let mut bytes = Vec::new();
+    index.write_to(&mut bytes).expect("write index");
+    let reloaded = PageIndex::read_from(bytes.as_slice()).expect("read index");
+    assert_eq!(reloaded, index);
+    assert!(PageIndex::read_from(&bytes[..bytes.len() - 1]).is_err());
+    assert!(PageIndex::read_from(&b"not an index"[..]).is_err());
+}
+
+#[test]
+fn open_with_index_writes_and_reuses_the_sidecar() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("test2.sas7bdat");
+    std::fs::copy(fixture("test2.sas7bdat"), &path).expect("copy fixture");
+    let sidecar = PageIndex::sidecar_path(&path);
+    assert_eq!(sidecar, dir.path().join("test2.sas7bdat.idx"));
+
+    let sas = SasReader::open_with_index(&path).expect("open with index");
+    let built = sas.page_index().expect("index built").clone();
+    let saved = PageIndex::read_from(std::fs::File::open(&sidecar).expect("sidecar written"))
+        .expect("read sidecar");
+    assert_eq!(saved, built);
+
+    let sas = SasReader::open_with_index(&path).expect("reopen with index");
+    assert_eq!(sas.page_index(), Some(&built));
+
+    // A sidecar from another file is rebuilt rather than trusted.
+    let mut other = SasReader::open(fixture("airline.sas7bdat")).expect("open other");
+    other
+        .build_index()
+        .expect("build other index")
+        .write_to(std::fs::File::create(&sidecar).expect("overwrite sidecar"))
+        .expect("write other index");
+    let sas = SasReader::open_with_index(&path).expect("open with stale index");
+    assert_eq!(sas.page_index(), Some(&built));
+}
+
+#[test]
+fn set_page_index_rejects_an_index_from_another_file() {
+    let mut other = SasReader::open(fixture("airline.sas7bdat")).expect("open other");
+    let index = other.build_index().expect("build index").clone();
+
+    let mut sas = SasReader::open(fixture("test2.sas7bdat")).expect("open fixture");
+    assert!(sas.set_page_index(index.clone()).is_err());
+    assert!(sas.page_index().is_none());
+    other
+        .set_page_index(index)
+        .expect("same file accepts its index");
+}
+
+#[test]
+fn indexed_windows_match_decoded_skips() {
+    let mut sas = SasReader::open(fixture("test2.sas7bdat")).expect("open fixture");
+    let selection = RowSelection::new().skip_rows(3).max_rows(4);
+
+    let collect = |sas: &mut SasReader<std::fs::File>| {
+        let mut window = sas.rows_windowed(&selection).expect("window");
+        let mut rows = Vec::new();
+        while let Some(row) = window.try_next().expect("row iteration failed") {
+            rows.push(format!("{row:?}"));
+        }
+        rows
+    };
+    let decoded = collect(&mut sas);
+    sas.build_index().expect("build index");
+    let indexed = collect(&mut sas);
+    assert_eq!(decoded.len(), 4);
+    assert_eq!(indexed, decoded);
+}
@@ -0,0 +1,138 @@
+use sas7bdat::{
+    SasReader,
+    dataset::Endianness,
+    parser::{SasHeader, parse_header},
+};
+use sas7bdat_test_support::common;
+use std::io::Cursor;
+
+/// Bytes the 64-bit page header reserves ahead of the page type.
+const SHIFT: usize = 16;
+
+fn read_u64(header: &SasHeader, bytes: &[u8]) -> u64 {
+    let bytes = bytes.try_into().expect("eight bytes");
+    match header.endianness {
+        Endianness::Little => u64::from_le_bytes(bytes),
+        Endianness::Big => u64::from_be_bytes(bytes),
+    }
+}
+
+const fn write_u64(header: &SasHeader, value: u64) -> [u8; 8] {
+    match header.endianness {
+        Endianness::Little => value.to_le_bytes(),
+        Endianness::Big => value.to_be_bytes(),
+    }
+}
+
+/// Rewrites a 64-bit file so that every page keeps its fields at the
+/// 32-bit page bit offset, as some third-party writers do: page contents
+/// move 16 bytes towards the start and subheader pointers follow them.
+fn with_short_page_headers(data: &[u8]) -> Vec<u8> {
+    let header = parse_header(&mut Cursor::new(data)).expect("parse header");
+    assert!(header.uses_u64, "fixture must use 64-bit offsets");
+    let page_size = header.page_size as usize;
+    let pointer_size = header.subheader_pointer_size as usize;
+    let mut out = data.to_vec();
+    for index in 0..usize::try_from(header.page_count).expect("page count") {
+        let start = usize::try_from(header.data_offset).expect("offset") + index * page_size;
+        let page = &mut out[start..start + page_size];
+        page.copy_within(SHIFT.., 0);
+        page[page_size - SHIFT..].fill(0);
+
+        let count_at = header.subheader_count_offset() - SHIFT;
+        let count = match header.endianness {
+            Endianness::Little => u16::from_le_bytes([page[count_at], page[count_at + 1]]),
+            Endianness::Big => u16::from_be_bytes([page[count_at], page[count_at + 1]]),
+        };
+        let table = header.page_header_size as usize - SHIFT;
+        for slot in 0..usize::from(count) {
+            let at = table + slot * pointer_size;
+            let Some(field) = page.get_mut(at..at + 8) else {
+                break;
+            };
+            let offset = read_u64(&header, field);
+            if offset >= SHIFT as u64 {
+                field.copy_from_slice(&write_u64(&header, offset - SHIFT as u64));
+            }
+        }
+    }
+    out
+}
+
+fn rows_debug(mut sas: SasReader<Cursor<bytes::Bytes>>) -> String {
+    let rows = sas
+        .rows()
+        .expect("rows")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("decode rows");
+    format!("{:?}|{rows:?}", sas.metadata().variables)
+}
+
+#[test]
+fn files_with_short_page_headers_read_like_their_originals() {
+    for fixture in ["test7", "test8", "test9", "test13", "many_columns"] {
+        let path = common::fixture_path(format!("fixtures/raw_data/pandas/{fixture}.sas7bdat"));
+        let data = std::fs::read(path).expect("read fixture");
+        let expected = rows_debug(SasReader::from_bytes(data.clone()).expect("open original"));
+
+        let shifted = SasReader::from_bytes(with_short_page_headers(&data))
+            .unwrap_or_else(|err| panic!("{fixture}: {err}"));
+        assert_eq!(rows_debug(shifted), expected, "{fixture}");
+    }
+}
+
+#[test]
+fn header_rejects_page_layouts_that_do_not_fit() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test7.sas7bdat");
+    let data = std::fs::read(path).expect("read fixture");
+    let mut header = parse_header(&mut Cursor::new(&data)).expect("parse header");
+    assert_eq!(header.page_type_offset(), 32);
+    assert_eq!(header.subheader_count_offset(), 36);
+
+    header.set_page_bit_offset(16).expect("32-bit layout fits");
+    assert_eq!(header.page_header_size, 24);
+    assert_eq!(header.block_count_offset(), 18);
+
+    let too_far = header.page_size as usize;
+    assert!(matches!(
+        header.set_page_bit_offset(too_far),
+        Err(sas7bdat::Error::Corrupted { .. })
+    ));
+    assert_eq!(header.page_bit_offset, 16);
+}
+
+#[test]
+fn rows_cut_off_at_page_ends_are_read_once() {
+    // Row-compressed files whose data pages mostly end in a truncated
+    // pointer, with the row written again in full on the next page.
+    for fixture in ["ahs2013/rmov", "csharp/mix_data_misc", "csharp/tmp868_14"] {
+        let path = common::fixture_path(format!("fixtures/raw_data/{fixture}.sas7bdat"));
+        let mut sas = SasReader::open(&path).expect("open fixture");
+        let expected = sas.metadata().row_count;
+        let rows = sas
+            .rows()
+            .expect("rows")
+            .collect::<sas7bdat::Result<Vec<_>>>()
+            .expect("decode rows");
+        assert_eq!(rows.len() as u64, expected, "{fixture}");
+        assert!(
+            sas.diagnostics().is_empty(),
+            "{fixture}: {:?}",
+            sas.diagnostics().entries()
+        );
+
+        for pages_per_batch in [1, 3] {
+            let parallel = SasReader::open(&path)
+                .expect("open fixture")
+                .into_parallel_iter(pages_per_batch)
+                .expect("parallel iterator")
+                .collect::<sas7bdat::Result<Vec<_>>>()
+                .expect("parallel rows");
+            assert_eq!(
+                format!("{parallel:?}"),
+                format!("{rows:?}"),
+                "{fixture} with {pages_per_batch} pages per batch"
+            );
+        }
+    }
+}
@@ -18,7 +18,7 @@ fn parse_test1_metadata_smoke() {
         other => panic!("unexpected value for Column1: {other:?}"),
     }
     match &row[1] {
-        CellValue::Str(s) => assert_eq!(s.as_ref(), "pear"),
+        CellValue::Str(s) => assert_eq!(&**s, "pear"),
         other => panic!("unexpected value for Column2: {other:?}"),
     }
     assert_numeric_84(&row[2], "Column3");
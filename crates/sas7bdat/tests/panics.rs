@@ -0,0 +1,43 @@
+use sas7bdat::{Error, ErrorCategory, SasReader, catch_panic};
+use std::io::Cursor;
+
+#[test]
+fn catch_panic_turns_panics_into_internal_errors() {
+    let value = catch_panic(|| 42).expect("no panic");
+    assert_eq!(value, 42);
+
+    let err = catch_panic(|| -> u8 { panic!("boom {}", 7) }).unwrap_err();
+    assert!(
+        matches!(err, Error::Internal { .. }),
+        "unexpected error: {err}"
+    );
+    assert_eq!(err.category(), ErrorCategory::Internal);
+    assert_eq!(err.code(), "internal");
+    assert_eq!(err.to_string(), "internal error: boom 7");
+}
+
+#[test]
+fn damaged_page_pointers_surface_as_errors() {
+    let path =
+        sas7bdat_test_support::common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let original = std::fs::read(path).expect("read fixture");
+    let header_len = 1024;
+
+    // Overwrite everything past the file header with 0xFF so that page
+    // headers, pointer tables and subheader offsets all point far out of
+    // bounds.
+    for stride in [4usize, 7, 16] {
+        let mut bytes = original.clone();
+        for (idx, byte) in bytes.iter_mut().enumerate().skip(header_len) {
+            if idx % stride == 0 {
+                *byte = 0xFF;
+            }
+        }
+        let outcome = catch_panic(move || {
+            let mut sas = SasReader::from_reader(Cursor::new(bytes))?;
+            sas.rows()?.try_for_each(|row| row.map(drop))
+        })
+        .expect("malformed input must not panic");
+        assert!(outcome.is_err(), "stride {stride} decoded cleanly");
+    }
+}
@@ -0,0 +1,65 @@
+use sas7bdat::SasReader;
+use sas7bdat_test_support::common::{self, CollectSink};
+
+const FIXTURES: &[&str] = &[
+    "fixtures/raw_data/pandas/airline.sas7bdat",
+    "fixtures/raw_data/pandas/many_columns.sas7bdat",
+    "fixtures/raw_data/pandas/productsales.sas7bdat",
+    "fixtures/raw_data/pandas/test1.sas7bdat",
+    "fixtures/raw_data/pandas/test2.sas7bdat",
+    "fixtures/raw_data/pandas/test4.sas7bdat",
+    "fixtures/raw_data/pandas/test9.sas7bdat",
+    "fixtures/raw_data/pandas/test16.sas7bdat",
+    "fixtures/raw_data/pandas/zero_rows.sas7bdat",
+];
+
+#[test]
+fn parallel_iterator_matches_sequential_rows() {
+    for fixture in FIXTURES {
+        let path = common::fixture_path(fixture);
+        let expected = SasReader::open(&path)
+            .expect("open dataset")
+            .into_iter()
+            .expect("sequential iterator")
+            .collect::<sas7bdat::Result<Vec<_>>>()
+            .expect("sequential rows");
+
+        for pages_per_batch in [0, 1, 3] {
+            let actual = SasReader::open(&path)
+                .expect("open dataset")
+                .into_parallel_iter(pages_per_batch)
+                .expect("parallel iterator")
+                .collect::<sas7bdat::Result<Vec<_>>>()
+                .expect("parallel rows");
+            assert_eq!(
+                format!("{actual:?}"),
+                format!("{expected:?}"),
+                "{fixture} with {pages_per_batch} pages per batch"
+            );
+        }
+    }
+}
+
+#[test]
+fn stream_into_parallel_preserves_row_order() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test2.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+
+    let mut sequential = CollectSink::default();
+    sas.stream_into(&mut sequential).expect("sequential stream");
+    let mut parallel = CollectSink::default();
+    sas.stream_into_parallel(&mut parallel, 2)
+        .expect("parallel stream");
+
+    assert_eq!(parallel.names, sequential.names);
+    assert_eq!(parallel.rows.len(), sequential.rows.len());
+    assert_eq!(
+        format!("{:?}", parallel.rows),
+        format!("{:?}", sequential.rows)
+    );
+
+    let mut again = CollectSink::default();
+    sas.stream_into(&mut again)
+        .expect("reader is rewound after a parallel stream");
+    assert_eq!(again.rows.len(), sequential.rows.len());
+}
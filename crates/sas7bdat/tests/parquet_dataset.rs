@@ -0,0 +1,134 @@
+#![cfg(feature = "parquet")]
+
+use sas7bdat::{
+    CellValue, ChecksumSink, JsonlSink, ParquetDataset, SasReader, sinks::DATASET_MANIFEST,
+};
+use sas7bdat_test_support::common;
+use std::{fs, path::Path};
+
+fn open(name: &str) -> SasReader<fs::File> {
+    let path = common::fixture_path(format!("fixtures/raw_data/pandas/{name}"));
+    SasReader::open(path).expect("open dataset")
+}
+
+fn column_without_missing(sas: &mut SasReader<fs::File>) -> String {
+    let mut has_missing = vec![false; sas.metadata().variables.len()];
+    let mut rows = sas.rows().expect("row iterator");
+    while let Some(row) = rows.try_next().expect("row") {
+        for (idx, value) in row.iter().enumerate() {
+            has_missing[idx] |= matches!(value, CellValue::Missing(_));
+        }
+    }
+    drop(rows);
+    let idx = has_missing
+        .iter()
+        .position(|missing| !missing)
+        .expect("fixture has a column without missing values");
+    sas.metadata().variables[idx].name.clone()
+}
+
+fn listing(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .expect("read dir")
+        .map(|entry| entry.expect("entry").file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn appends_files_and_records_them_in_manifest() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mut sas = open("test1.sas7bdat");
+    let rows = sas.metadata().row_count;
+
+    let mut dataset = ParquetDataset::open(dir.path()).expect("open dataset");
+    assert_eq!(dataset.append(&mut sas).expect("first append").rows, rows);
+    assert_eq!(
+        dataset.append(&mut sas).expect("second append").file,
+        "part-00001.parquet"
+    );
+    assert_eq!(
+        listing(dir.path()),
+        [DATASET_MANIFEST, "part-00000.parquet", "part-00001.parquet"]
+    );
+
+    let reopened = ParquetDataset::open(dir.path()).expect("reopen dataset");
+    assert_eq!(reopened.rows(), rows * 2);
+    assert_eq!(reopened.columns().len(), sas.metadata().variables.len());
+}
+
+#[test]
+fn incompatible_schema_leaves_dataset_untouched() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mut dataset = ParquetDataset::open(dir.path()).expect("open dataset");
+    dataset
+        .append(&mut open("test1.sas7bdat"))
+        .expect("first append");
+
+    let err = dataset
+        .append(&mut open("airline.sas7bdat"))
+        .expect_err("different columns are rejected");
+    assert!(err.to_string().contains("is missing"), "{err}");
+    assert_eq!(
+        listing(dir.path()),
+        [DATASET_MANIFEST, "part-00000.parquet"]
+    );
+    assert_eq!(dataset.files().len(), 1);
+}
+
+#[test]
+fn added_columns_must_be_nullable() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mut sas = open("test1.sas7bdat");
+    ParquetDataset::open(dir.path())
+        .expect("open dataset")
+        .append(&mut sas)
+        .expect("first append");
+
+    // Pretend the first file predates a column that never holds missing
+    // values, so it can also be written as required.
+    let added = column_without_missing(&mut sas);
+    let manifest_path = dir.path().join(DATASET_MANIFEST);
+    let mut manifest: serde_json::Value =
+        serde_json::from_slice(&fs::read(&manifest_path).unwrap()).unwrap();
+    manifest["columns"]
+        .as_array_mut()
+        .unwrap()
+        .retain(|column| column["name"] != added.as_str());
+    fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    let mut dataset = ParquetDataset::open(dir.path()).expect("reopen dataset");
+    let err = dataset
+        .append_with(&mut sas, |sink| sink.with_required_columns([added.clone()]))
+        .expect_err("required added column is rejected");
+    assert!(err.to_string().contains("must be nullable"), "{err}");
+
+    dataset.append(&mut sas).expect("nullable added column");
+    assert_eq!(dataset.columns().last().unwrap().name, added);
+    assert_eq!(dataset.files().len(), 2);
+}
+
+#[test]
+fn column_checksums_are_recorded_in_manifest() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mut sas = open("test1.sas7bdat");
+    let mut source = ChecksumSink::new(JsonlSink::new(std::io::sink()));
+    sas.stream_into(&mut source).expect("checksum source");
+
+    let mut dataset = ParquetDataset::open(dir.path()).expect("open dataset");
+    assert!(
+        dataset
+            .append(&mut sas)
+            .expect("plain append")
+            .checksums
+            .is_empty()
+    );
+
+    let mut dataset = dataset.with_column_checksums(true);
+    let file = dataset.append(&mut sas).expect("checksummed append");
+    assert_eq!(file.checksums, source.checksums());
+
+    let reopened = ParquetDataset::open(dir.path()).expect("reopen dataset");
+    assert_eq!(reopened.files()[1].checksums, source.checksums());
+}
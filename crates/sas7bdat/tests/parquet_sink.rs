@@ -0,0 +1,542 @@
+#![cfg(feature = "parquet")]
+
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, TimeUnit, Type as PhysicalType, ZstdLevel},
+    column::reader::ColumnReader,
+    file::{
+        metadata::KeyValue,
+        properties::WriterProperties,
+        reader::{FileReader, SerializedFileReader},
+    },
+    record::{Field, ListAccessor, RowAccessor},
+};
+use sas7bdat::{
+    CellValue, ColumnarSink, Error, ParquetColumnType, ParquetSink, PartitionedParquetSink,
+    RowSink, SasReader, SinkContext, TimePrecision,
+    dataset::{Alignment, Measure, RepeatedGroup, Variable, VariableKind},
+    decode_layout,
+    parser::{ColumnInfo, ColumnKind, NumericKind},
+    sinks::{Codec, EnabledStatistics, SAS_COLUMNS_KEY},
+};
+use sas7bdat_test_support::common;
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom},
+};
+use time::Duration;
+
+fn column_without_missing(sas: &mut SasReader<std::fs::File>) -> String {
+    let column_count = sas.metadata().variables.len();
+    let mut has_missing = vec![false; column_count];
+    let mut rows = sas.rows().expect("row iterator");
+    while let Some(row) = rows.try_next().expect("row") {
+        for (idx, value) in row.iter().enumerate() {
+            has_missing[idx] |= matches!(value, CellValue::Missing(_));
+        }
+    }
+    drop(rows);
+    let idx = has_missing
+        .iter()
+        .position(|missing| !missing)
+        .expect("fixture has a column without missing values");
+    sas.metadata().variables[idx].name.clone()
+}
+
+#[test]
+fn required_columns_are_written_as_required() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let required = column_without_missing(&mut sas);
+
+    let output = tempfile::tempfile().expect("temp file");
+    let mut sink = ParquetSink::new(output).with_required_columns([required.to_lowercase()]);
+    sas.stream_into(&mut sink).expect("write parquet");
+    let file = sink.into_inner().expect("finished sink");
+
+    let reader = SerializedFileReader::new(file).expect("read parquet");
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    for column in schema.columns() {
+        let expected = if column.name() == required {
+            Repetition::REQUIRED
+        } else {
+            Repetition::OPTIONAL
+        };
+        assert_eq!(
+            column.self_type().get_basic_info().repetition(),
+            expected,
+            "column {}",
+            column.name()
+        );
+    }
+}
+
+#[test]
+fn required_column_with_missing_value_fails() {
+    let path = common::fixture_path("fixtures/raw_data/readstat/missing_test.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+
+    let mut sink = ParquetSink::new(Vec::new()).with_required_columns(["var1"]);
+    let err = sas
+        .stream_into(&mut sink)
+        .expect_err("missing value in required column");
+    assert!(
+        matches!(&err, Error::Parquet { details } if details.contains("'var1'")),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn unknown_required_column_is_rejected() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+
+    let mut sink = ParquetSink::new(Vec::new()).with_required_columns(["no_such_column"]);
+    let err = sas.stream_into(&mut sink).expect_err("unknown column");
+    assert!(
+        err.to_string().contains("no_such_column"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn repeated_groups_are_written_as_lists() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    // Column1 and Column3 are both BEST-formatted numerics.
+    let members = vec!["Column1".to_owned(), "Column3".to_owned()];
+
+    let indices: Vec<usize> = members
+        .iter()
+        .map(|name| {
+            sas.metadata()
+                .variables
+                .iter()
+                .position(|v| &v.name == name)
+                .expect("member index")
+        })
+        .collect();
+
+    let mut expected = Vec::new();
+    {
+        let mut rows = sas.rows().expect("row iterator");
+        while let Some(row) = rows.try_next().expect("row") {
+            expected.push(
+                indices
+                    .iter()
+                    .map(|&idx| match &row[idx] {
+                        CellValue::Float(value) => Some(*value),
+                        CellValue::Int32(value) => Some(f64::from(*value)),
+                        CellValue::Int64(value) => {
+                            Some(i32::try_from(*value).map(f64::from).unwrap())
+                        }
+                        CellValue::Missing(_) => None,
+                        other => panic!("unexpected value {other:?}"),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+    let column_count = sas.metadata().variables.len();
+
+    let output = tempfile::tempfile().expect("temp file");
+    let mut sink = ParquetSink::new(output)
+        .with_repeated_groups([RepeatedGroup::new("GROUPED", members.clone())]);
+    sas.stream_into(&mut sink).expect("write parquet");
+    let file = sink.into_inner().expect("finished sink");
+
+    let reader = SerializedFileReader::new(file).expect("read parquet");
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    let root = schema.root_schema();
+    assert_eq!(root.get_fields().len(), column_count - 1);
+    let (list_idx, list_field) = root
+        .get_fields()
+        .iter()
+        .enumerate()
+        .find(|(_, field)| field.name() == "GROUPED")
+        .expect("list column present");
+    assert_eq!(
+        list_field.get_basic_info().logical_type_ref(),
+        Some(&LogicalType::List)
+    );
+
+    let mut seen = 0;
+    for (row, expected) in reader
+        .get_row_iter(None)
+        .expect("row iterator")
+        .zip(&expected)
+    {
+        let row = row.expect("row");
+        let list = row.get_list(list_idx).expect("list value");
+        assert_eq!(list.len(), members.len());
+        for (position, value) in expected.iter().enumerate() {
+            match value {
+                Some(value) => assert_eq!(
+                    list.get_double(position).unwrap().to_bits(),
+                    value.to_bits()
+                ),
+                None => assert!(matches!(list.elements()[position], Field::Null)),
+            }
+        }
+        seen += 1;
+    }
+    assert_eq!(seen, expected.len());
+}
+
+#[test]
+fn sas_column_metadata_is_stored_in_footer() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    let variables = sas.metadata().variables.clone();
+
+    let output = tempfile::tempfile().expect("temp file");
+    let mut sink = ParquetSink::new(output);
+    sas.stream_into(&mut sink).expect("write parquet");
+    let file = sink.into_inner().expect("finished sink");
+
+    let reader = SerializedFileReader::new(file).expect("read parquet");
+    let entries = reader
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|entries| entries.iter().find(|kv| kv.key == SAS_COLUMNS_KEY))
+        .and_then(|kv| kv.value.clone())
+        .expect("SAS column metadata in footer");
+    let columns: Vec<serde_json::Value> = serde_json::from_str(&entries).expect("valid JSON");
+
+    assert_eq!(columns.len(), variables.len());
+    assert!(variables.iter().any(|variable| variable.format.is_some()));
+    for (column, variable) in columns.iter().zip(&variables) {
+        assert_eq!(column["name"], variable.name.trim_end());
+        let kind = match variable.kind {
+            VariableKind::Numeric => "numeric",
+            VariableKind::Character => "character",
+        };
+        assert_eq!(column["type"], kind);
+        assert_eq!(
+            column.get("format").and_then(|value| value.as_str()),
+            variable.format.as_ref().map(ToString::to_string).as_deref()
+        );
+        assert_eq!(
+            column.get("informat").and_then(|value| value.as_str()),
+            variable
+                .informat
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref()
+        );
+    }
+}
+
+#[test]
+fn writer_properties_are_applied_to_every_column() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+
+    let base = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_key_value_metadata(Some(vec![KeyValue::new(
+            "origin".to_owned(),
+            "registry".to_owned(),
+        )]))
+        .build();
+    let output = tempfile::tempfile().expect("temp file");
+    let mut sink = ParquetSink::new(output)
+        .with_compression(Codec::ZSTD(ZstdLevel::try_new(3).expect("valid level")))
+        .with_writer_properties(base)
+        .with_dictionary(false)
+        .with_statistics(EnabledStatistics::None);
+    sas.stream_into(&mut sink).expect("write parquet");
+    let file = sink.into_inner().expect("finished sink");
+
+    let reader = SerializedFileReader::new(file).expect("read parquet");
+    let metadata = reader.metadata();
+    for column in metadata.row_group(0).columns() {
+        // Files record the codec but not its level.
+        assert!(matches!(column.compression(), Compression::ZSTD(_)));
+        assert!(column.dictionary_page_offset().is_none());
+        assert!(column.statistics().is_none());
+    }
+    let keys: Vec<&str> = metadata
+        .file_metadata()
+        .key_value_metadata()
+        .expect("footer metadata")
+        .iter()
+        .map(|kv| kv.key.as_str())
+        .collect();
+    assert!(keys.contains(&"origin"));
+    assert!(keys.contains(&SAS_COLUMNS_KEY));
+}
+
+#[test]
+fn measure_and_alignment_are_stored_in_footer() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut source = File::open(path).expect("open fixture");
+    let mut layout = decode_layout(&mut source).expect("layout");
+    source.seek(SeekFrom::Start(0)).expect("rewind");
+    layout.header.metadata.variables[0].measure = Measure::Scale;
+    layout.header.metadata.variables[0].alignment = Alignment::Center;
+
+    let mut sink = ParquetSink::new(tempfile::tempfile().expect("temp file"));
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    let mut rows = layout.row_iterator(&mut source).expect("row iterator");
+    while let Some(row) = rows.try_next().expect("row") {
+        sink.write_row(&row).expect("write row");
+    }
+    sink.finish().expect("finish");
+
+    let reader = SerializedFileReader::new(sink.into_inner().expect("file")).expect("read");
+    let entries = reader
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|entries| entries.iter().find(|kv| kv.key == SAS_COLUMNS_KEY))
+        .and_then(|kv| kv.value.clone())
+        .expect("SAS column metadata in footer");
+    let columns: Vec<serde_json::Value> = serde_json::from_str(&entries).expect("valid JSON");
+    assert_eq!(columns[0]["measure"], "scale");
+    assert_eq!(columns[0]["alignment"], "center");
+    assert!(columns[1].get("measure").is_none());
+    assert!(columns[1].get("alignment").is_none());
+}
+
+#[test]
+fn low_memory_streaming_requires_borrowing_sink() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open dataset");
+
+    let mut collect = common::CollectSink::default();
+    let err = sas
+        .stream_into_low_memory(&mut collect)
+        .expect_err("materialising sink is rejected");
+    assert!(matches!(err, Error::Unsupported { .. }), "{err}");
+    assert!(collect.rows.is_empty());
+
+    let mut sink = ParquetSink::new(tempfile::tempfile().expect("temp file"));
+    sas.stream_into_low_memory(&mut sink)
+        .expect("low-memory parquet");
+    let reader = SerializedFileReader::new(sink.into_inner().expect("output")).expect("reader");
+    let rows = u64::try_from(reader.metadata().file_metadata().num_rows()).unwrap();
+    assert_eq!(rows, sas.metadata().row_count);
+
+    let mut expected = Vec::new();
+    let mut source = sas.rows().expect("rows");
+    while let Some(row) = source.try_next().expect("row") {
+        expected.push(row[0].clone().into_owned());
+    }
+    let first_column: Vec<f64> = reader
+        .get_row_iter(None)
+        .expect("row iter")
+        .map(|row| row.expect("row").get_double(0).expect("double"))
+        .collect();
+    assert_eq!(first_column.len(), expected.len());
+    for (value, cell) in first_column.iter().zip(&expected) {
+        let decoded = match cell {
+            CellValue::Float(number) => *number,
+            CellValue::Int64(number) => f64::from(i32::try_from(*number).expect("small integer")),
+            other => panic!("unexpected value {other:?}"),
+        };
+        assert!(
+            (decoded - value).abs() < f64::EPSILON,
+            "{decoded} != {value}"
+        );
+    }
+}
+
+/// Writes raw `seconds`, followed by a decoded 1.5µs time, into the first
+/// column of a layout patched to hold a time and returns the stored integers
+/// and the column's logical type.
+fn write_times(precision: TimePrecision, seconds: &[f64]) -> (Vec<i64>, Option<LogicalType>) {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut layout = decode_layout(&mut File::open(path).expect("open fixture")).expect("layout");
+    layout.columns[0].kind = ColumnKind::Numeric(NumericKind::Time);
+    let width = layout.columns.len();
+
+    let mut sink = ParquetSink::new(tempfile::tempfile().expect("temp file"))
+        .with_row_group_size(2)
+        .with_time_precision(precision);
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    for value in seconds {
+        let mut row = vec![CellValue::Float(0.0); width];
+        row[0] = CellValue::Float(*value);
+        sink.write_row(&row).expect("write row");
+    }
+    let mut row = vec![CellValue::Float(0.0); width];
+    row[0] = CellValue::Time(Duration::nanoseconds(1_500));
+    sink.write_row(&row).expect("write row");
+    sink.finish().expect("finish");
+
+    let reader = SerializedFileReader::new(sink.into_inner().expect("output")).expect("reader");
+    let logical = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .column(0)
+        .logical_type_ref()
+        .cloned();
+    let mut values = Vec::new();
+    for group in 0..reader.num_row_groups() {
+        let column = reader
+            .get_row_group(group)
+            .expect("row group")
+            .get_column_reader(0)
+            .expect("column reader");
+        let ColumnReader::Int64ColumnReader(mut column) = column else {
+            panic!("time column is not INT64");
+        };
+        let mut def_levels = Vec::new();
+        column
+            .read_records(seconds.len() + 1, Some(&mut def_levels), None, &mut values)
+            .expect("read times");
+    }
+    (values, logical)
+}
+
+#[test]
+fn time_columns_keep_microseconds_and_optional_nanoseconds() {
+    let seconds = [
+        0.000_001,
+        45_296.789_012,
+        86_399.999_999,
+        0.000_000_5,
+        1.234_567_891,
+    ];
+
+    let (micros, logical) = write_times(TimePrecision::Micros, &seconds);
+    assert_eq!(micros, [1, 45_296_789_012, 86_399_999_999, 0, 1_234_567, 1]);
+    assert!(matches!(
+        logical,
+        Some(LogicalType::Time {
+            unit: TimeUnit::MICROS,
+            ..
+        })
+    ));
+
+    let (nanos, logical) = write_times(TimePrecision::Nanos, &seconds);
+    let expected: Vec<i64> = [
+        Duration::microseconds(1),
+        Duration::microseconds(45_296_789_012),
+        Duration::microseconds(86_399_999_999),
+        Duration::nanoseconds(500),
+        Duration::nanoseconds(1_234_567_891),
+        Duration::nanoseconds(1_500),
+    ]
+    .iter()
+    .map(|duration| i64::try_from(duration.whole_nanoseconds()).unwrap())
+    .collect();
+    assert_eq!(nanos, expected);
+    assert!(matches!(
+        logical,
+        Some(LogicalType::Time {
+            unit: TimeUnit::NANOS,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn type_mapper_overrides_column_types_on_the_columnar_path() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let mut source = File::open(path).expect("open fixture");
+    let layout = decode_layout(&mut source).expect("layout");
+    source.seek(SeekFrom::Start(0)).expect("rewind");
+    let selection: Vec<usize> = (0..layout.columns.len()).collect();
+
+    let mut sink = ParquetSink::new(tempfile::tempfile().expect("temp file"))
+        .with_streaming_columnar(true)
+        .with_type_mapper(
+            |_: &Variable, _: &ColumnInfo, default: ParquetColumnType| match default {
+                ParquetColumnType::Date => ParquetColumnType::Int32Days,
+                ParquetColumnType::Timestamp => ParquetColumnType::Utf8,
+                other => other,
+            },
+        );
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    let mut rows = layout.row_iterator(&mut source).expect("row iterator");
+    while let Some(batch) = rows.next_columnar_batch(3).expect("batch") {
+        sink.write_columnar_batch(&batch, &selection)
+            .expect("write batch");
+    }
+    drop(rows);
+    sink.finish().expect("finish");
+
+    let reader = SerializedFileReader::new(sink.into_inner().expect("output")).expect("reader");
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    assert_eq!(schema.column(0).physical_type(), PhysicalType::INT32);
+    assert_eq!(schema.column(0).logical_type_ref(), None);
+    assert_eq!(schema.column(2).physical_type(), PhysicalType::BYTE_ARRAY);
+    assert_eq!(
+        schema.column(2).logical_type_ref(),
+        Some(&LogicalType::String)
+    );
+
+    // Second fixture row is the SAS epoch, 1960-01-01.
+    let row = reader
+        .get_row_iter(None)
+        .expect("rows")
+        .nth(1)
+        .expect("second row")
+        .expect("decoded row");
+    assert_eq!(row.get_int(0).expect("int32 days"), -3653);
+    assert!(
+        row.get_string(2)
+            .expect("datetime text")
+            .starts_with("1960-01-01 0:00:00")
+    );
+}
+
+#[test]
+fn partitioned_sink_writes_hive_directories() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open fixture");
+    let row_count = sas.metadata().row_count;
+    let column_count = sas.metadata().variables.len();
+    let dir = tempfile::tempdir().expect("temp dir");
+    // One open file at a time forces a new part file whenever the country
+    // changes, on top of the per-file row cap.
+    let mut sink = PartitionedParquetSink::new(dir.path(), ["country"])
+        .with_max_open_files(1)
+        .with_max_rows_per_file(200);
+    sas.stream_into(&mut sink).expect("stream");
+
+    let mut countries: Vec<String> = Vec::new();
+    let mut total = 0;
+    for file in sink.files() {
+        assert!(
+            file.rows <= 200,
+            "{} has {} rows",
+            file.path.display(),
+            file.rows
+        );
+        let partition = file.path.parent().expect("partition dir").to_string_lossy();
+        let country = partition.strip_prefix("COUNTRY=").expect("hive key");
+        if !countries.iter().any(|seen| seen == country) {
+            countries.push(country.to_owned());
+        }
+
+        let reader =
+            SerializedFileReader::new(File::open(dir.path().join(&file.path)).expect("open part"))
+                .expect("reader");
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), i64::try_from(file.rows).expect("rows"));
+        let schema = metadata.schema_descr();
+        assert_eq!(schema.num_columns(), column_count - 1);
+        assert!(
+            (0..schema.num_columns())
+                .all(|index| !schema.column(index).name().eq_ignore_ascii_case("COUNTRY"))
+        );
+        total += file.rows;
+    }
+    countries.sort_unstable();
+    assert_eq!(countries, ["CANADA", "GERMANY", "U.S.A."]);
+    assert_eq!(total, row_count);
+}
+
+#[test]
+fn partitioned_sink_rejects_unknown_partition_column() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open fixture");
+    let dir = tempfile::tempdir().expect("temp dir");
+    let mut sink = PartitionedParquetSink::new(dir.path(), ["NOPE"]);
+    let err = sas.stream_into(&mut sink).expect_err("unknown column");
+    assert!(matches!(err, Error::InvalidArgument { .. }), "{err}");
+}
@@ -0,0 +1,120 @@
+use sas7bdat::{JsonlSink, Predicate, RowSelection, SasReader, reader::ProgressEvent};
+use sas7bdat_test_support::common;
+use std::{
+    ops::ControlFlow,
+    sync::{Arc, Mutex},
+};
+
+fn open_multi_page() -> SasReader<std::fs::File> {
+    let path = common::fixture_path("fixtures/raw_data/pandas/load_log.sas7bdat");
+    SasReader::open(path).expect("open fixture")
+}
+
+fn record(sas: &mut SasReader<std::fs::File>, stop_after: usize) -> Arc<Mutex<Vec<ProgressEvent>>> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&events);
+    sas.set_progress_observer(move |event: &ProgressEvent| {
+        let mut seen = seen.lock().expect("events lock");
+        seen.push(*event);
+        if seen.len() == stop_after {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    events
+}
+
+fn stream_lines(sas: &mut SasReader<std::fs::File>) -> usize {
+    let mut sink = JsonlSink::new(Vec::new());
+    sas.stream_into(&mut sink).expect("stream rows");
+    let output = sink.into_inner().expect("finished sink");
+    String::from_utf8(output)
+        .expect("utf-8 output")
+        .lines()
+        .count()
+}
+
+#[test]
+fn stream_into_reports_each_page() {
+    let mut sas = open_multi_page();
+    let events = record(&mut sas, usize::MAX);
+    let rows = stream_lines(&mut sas);
+
+    let events = events.lock().expect("events lock").clone();
+    assert!(events.len() > 1, "events: {events:?}");
+    // The final event repeats the last page with the closing counts.
+    let (_, per_page) = events.split_last().expect("events");
+    for pair in per_page.windows(2) {
+        assert!(pair[1].page_index > pair[0].page_index);
+        assert!(pair[1].rows_processed > pair[0].rows_processed);
+        assert!(pair[1].bytes_processed > pair[0].bytes_processed);
+    }
+    let last = events.last().expect("events");
+    assert_eq!(last.rows_processed, rows as u64);
+    assert_eq!(last.bytes_processed, last.total_bytes);
+    assert!(last.rows_processed > per_page.last().expect("page events").rows_processed);
+}
+
+#[test]
+fn final_event_accounts_for_every_row_and_page() {
+    let mut sas = open_multi_page();
+    let total_rows = sas.metadata().row_count;
+    let events = record(&mut sas, usize::MAX);
+    let rows = stream_lines(&mut sas);
+
+    let events = events.lock().expect("events lock").clone();
+    let last = events.last().expect("events");
+    assert_eq!(last.rows_processed, rows as u64);
+    assert_eq!(last.rows_processed, total_rows);
+    assert_eq!(last.pages_processed, last.total_pages);
+    assert!((last.fraction() - 1.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn observer_can_stop_a_conversion() {
+    let mut sas = open_multi_page();
+    let full = stream_lines(&mut sas);
+    let events = record(&mut sas, 2);
+    let written = stream_lines(&mut sas);
+
+    let events = events.lock().expect("events lock").clone();
+    assert_eq!(events.len(), 2);
+    assert_eq!(written as u64, events[1].rows_processed);
+    assert!(written < full);
+}
+
+#[test]
+fn selections_and_missing_scans_report_to_the_observer() {
+    let mut sas = open_multi_page();
+    let events = record(&mut sas, usize::MAX);
+    let column = sas.metadata().variables[0].name.clone();
+    let selection = RowSelection::new().matching(Predicate::not_missing(column));
+    let mut sink = JsonlSink::new(Vec::new());
+    sas.stream_selection_into(&selection, &mut sink)
+        .expect("stream selection");
+    let written = String::from_utf8(sink.into_inner().expect("finished sink"))
+        .expect("utf-8 output")
+        .lines()
+        .count();
+    let (selected, last_selected) = {
+        let events = events.lock().expect("events lock");
+        (events.len(), *events.last().expect("events"))
+    };
+    assert!(selected > 0);
+    assert_eq!(last_selected.rows_processed, written as u64);
+
+    let summary = sas.scan_missing_policies().expect("scan");
+    let (count, last) = {
+        let events = events.lock().expect("events lock");
+        (events.len(), *events.last().expect("events"))
+    };
+    assert!(count > selected);
+    assert_eq!(last.pages_processed, summary.pages_scanned);
+    assert_eq!(last.rows_processed, summary.rows_scanned);
+    assert!(summary.complete);
+
+    let mut observer = sas.take_progress_observer().expect("observer installed");
+    assert!(sas.take_progress_observer().is_none());
+    assert!(observer.on_progress(&last).is_continue());
+}
@@ -0,0 +1,58 @@
+use sas7bdat::{QualityCheckOptions, QualityIssueKind, SasReader};
+use sas7bdat_test_support::common;
+
+/// Little-endian bit pattern SAS writes for the `.` missing value.
+const SYSTEM_MISSING_LE: [u8; 8] = [0, 0, 0, 0, 0, 0xD1, 0xFF, 0xFF];
+
+#[test]
+fn fixture_without_anomalies_is_clean() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let report = sas.check_data_quality().expect("quality check");
+    assert!(report.is_clean(), "{:?}", report.issues);
+    assert_eq!(report.rows_scanned, sas.metadata().row_count);
+}
+
+#[test]
+fn non_canonical_missing_codes_are_reported_by_row() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut bytes = std::fs::read(path).expect("read fixture");
+    let mut patched = 0;
+    let mut offset = 0;
+    while let Some(found) = bytes[offset..]
+        .windows(SYSTEM_MISSING_LE.len())
+        .position(|window| window == SYSTEM_MISSING_LE)
+    {
+        // Setting a low fraction bit keeps the value a NaN but no longer one
+        // SAS would write.
+        bytes[offset + found] = 0x01;
+        offset += found + SYSTEM_MISSING_LE.len();
+        patched += 1;
+    }
+    assert!(patched > 0, "fixture has missing values");
+
+    let mut sas = SasReader::from_reader(std::io::Cursor::new(bytes)).expect("open dataset");
+    let report = sas.check_data_quality().expect("quality check");
+    assert_eq!(report.issues.len(), patched);
+    assert!(!report.truncated);
+    assert!(
+        report
+            .issues
+            .iter()
+            .all(|issue| issue.kind == QualityIssueKind::NonCanonicalMissing)
+    );
+    assert!(
+        report
+            .issues
+            .windows(2)
+            .all(|pair| pair[0].row <= pair[1].row)
+    );
+    assert!(report.issues[0].detail.contains("0xffffd10000000001"));
+
+    let capped = sas
+        .check_data_quality_with(QualityCheckOptions::new().max_issues(3))
+        .expect("capped quality check");
+    assert_eq!(capped.issues, report.issues[..3]);
+    assert!(capped.truncated);
+    assert_eq!(capped.rows_scanned, report.rows_scanned);
+}
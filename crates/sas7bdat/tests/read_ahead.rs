@@ -0,0 +1,74 @@
+use sas7bdat::{ReadAhead, ReadAheadReader, RowSelection, SasReader};
+use sas7bdat_test_support::common;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+#[test]
+fn read_ahead_reader_yields_the_same_rows() {
+    for fixture in [
+        "fixtures/raw_data/pandas/airline.sas7bdat",
+        "fixtures/raw_data/pandas/test2.sas7bdat",
+        "fixtures/raw_data/pandas/many_columns.sas7bdat",
+    ] {
+        let path = common::fixture_path(fixture);
+        let expected = SasReader::open(&path)
+            .expect("open dataset")
+            .into_iter()
+            .expect("row iterator")
+            .collect::<sas7bdat::Result<Vec<_>>>()
+            .expect("rows");
+
+        for pages in [0, 1, 4] {
+            let mut sas = SasReader::open_with_read_ahead(&path, ReadAhead::new(pages))
+                .expect("open with read-ahead");
+            let first_pass = sas
+                .rows()
+                .expect("row iterator")
+                .map(|row| row.expect("row"))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                format!("{first_pass:?}"),
+                format!("{expected:?}"),
+                "{fixture}"
+            );
+
+            let selection = RowSelection::new().skip_rows(3).max_rows(2);
+            let window = sas
+                .rows_windowed(&selection)
+                .expect("windowed iterator")
+                .map(|row| row.expect("row"))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                format!("{window:?}"),
+                format!(
+                    "{:?}",
+                    &expected[3.min(expected.len())..5.min(expected.len())]
+                ),
+                "{fixture} after seeking back"
+            );
+        }
+    }
+}
+
+#[test]
+fn read_ahead_reader_serves_arbitrary_seeks() {
+    let bytes: Vec<u8> = (0..10_000u32).map(|value| (value % 251) as u8).collect();
+    let mut reader =
+        ReadAheadReader::new(Cursor::new(bytes.clone()), 64, ReadAhead::default()).expect("reader");
+    assert_eq!(reader.len(), bytes.len() as u64);
+
+    let mut buf = [0u8; 200];
+    for start in [0usize, 50, 9_950, 1_000, 1_200, 63, 64, 5_000] {
+        reader.seek(SeekFrom::Start(start as u64)).expect("seek");
+        let wanted = buf.len().min(bytes.len() - start);
+        reader.read_exact(&mut buf[..wanted]).expect("read");
+        assert_eq!(&buf[..wanted], &bytes[start..start + wanted]);
+    }
+
+    reader.seek(SeekFrom::End(-10)).expect("seek from end");
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).expect("read tail");
+    assert_eq!(tail, &bytes[bytes.len() - 10..]);
+
+    let inner = reader.into_inner().expect("inner reader");
+    assert_eq!(inner.get_ref().len(), bytes.len());
+}
@@ -1,5 +1,11 @@
-use sas7bdat::{CellValue, Error, RowSelection, SasReader};
-use sas7bdat_test_support::common;
+use sas7bdat::{
+    CellValue, ColumnOrder, ColumnType, DateGranularity, Diagnostic, Error, ErrorCategory,
+    MetadataReadOptions, Predicate, RowSelection, SasReader,
+    logger::{LogRecord, LogSink, Logger},
+    value,
+};
+use sas7bdat_test_support::common::{self, CollectSink};
+use std::sync::{Arc, Mutex};
 
 #[test]
 fn rows_windowed_respects_skip_and_limit() {
@@ -83,8 +89,8 @@ fn select_with_rejects_duplicate_names() {
         panic!("expected duplicate projection to fail");
     };
     match err {
-        Error::InvalidMetadata { .. } => {}
-        other => panic!("expected InvalidMetadata error, got {other:?}"),
+        Error::InvalidArgument { .. } => {}
+        other => panic!("expected InvalidArgument error, got {other:?}"),
     }
 }
 
@@ -92,3 +98,506 @@ fn open_datetime_fixture() -> SasReader<std::fs::File> {
     let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
     SasReader::open(path).expect("failed to open datetime fixture")
 }
+
+#[test]
+fn open_rejects_directories_and_short_files() {
+    let temp = tempfile::tempdir().expect("create temp dir");
+    let dir = temp.path();
+
+    let err = SasReader::open(dir)
+        .err()
+        .expect("directory should be rejected");
+    assert!(
+        matches!(&err, Error::Io(io) if io.kind() == std::io::ErrorKind::IsADirectory),
+        "unexpected error: {err}"
+    );
+
+    let empty = dir.join("empty.sas7bdat");
+    std::fs::write(&empty, []).expect("write empty file");
+    let err = SasReader::open(&empty)
+        .err()
+        .expect("empty file should be rejected");
+    assert!(
+        err.to_string().contains("is empty"),
+        "unexpected error: {err}"
+    );
+
+    let short = dir.join("short.sas7bdat");
+    std::fs::write(&short, [0u8; 100]).expect("write short file");
+    let err = SasReader::open(&short)
+        .err()
+        .expect("short file should be rejected");
+    assert!(
+        matches!(&err, Error::NotSasFile { details } if details.contains("100 bytes")),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn raw_character_columns_skip_decoding() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let variables = sas.metadata().variables.clone();
+    let (raw_index, raw_column) = variables
+        .iter()
+        .enumerate()
+        .find(|(_, variable)| matches!(variable.kind, sas7bdat::dataset::VariableKind::Character))
+        .expect("fixture has a character column");
+    let name = raw_column.name.trim_end().to_owned();
+
+    let decoded: Vec<String> = collect_column(&mut sas, raw_index)
+        .into_iter()
+        .map(|value| match value {
+            CellValue::Str(text) => text.into_owned(),
+            other => panic!("expected text, got {other:?}"),
+        })
+        .collect();
+
+    sas.set_raw_character_columns(&[&name])
+        .expect("select raw column");
+    let raw = collect_column(&mut sas, raw_index);
+    assert_eq!(raw.len(), decoded.len());
+    for (value, text) in raw.iter().zip(&decoded) {
+        let CellValue::Bytes(bytes) = value else {
+            panic!("expected bytes, got {value:?}");
+        };
+        assert_eq!(bytes.len(), raw_column.storage_width);
+        assert_eq!(bytes.trim_ascii_end(), text.as_bytes());
+    }
+
+    sas.set_raw_character_columns(&[])
+        .expect("restore decoding");
+    assert!(matches!(
+        collect_column(&mut sas, raw_index)[0],
+        CellValue::Str(_)
+    ));
+
+    let numeric = variables
+        .iter()
+        .find(|variable| matches!(variable.kind, sas7bdat::dataset::VariableKind::Numeric))
+        .expect("fixture has a numeric column");
+    assert!(matches!(
+        sas.set_raw_character_columns(&[numeric.name.trim_end()]),
+        Err(Error::InvalidArgument { .. })
+    ));
+    assert!(sas.set_raw_character_columns(&["no_such_column"]).is_err());
+}
+
+fn collect_column(sas: &mut SasReader<std::fs::File>, index: usize) -> Vec<CellValue<'static>> {
+    let mut rows = sas.rows().expect("row iterator");
+    let mut values = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        values.push(row[index].clone().into_owned());
+    }
+    values
+}
+
+#[test]
+fn column_list_order_reorders_variables_and_rows() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut stored = SasReader::open(&path).expect("open dataset");
+    let options = MetadataReadOptions::default().with_column_order(ColumnOrder::ColumnList);
+    let mut logical = SasReader::open_with_options(&path, options).expect("open dataset");
+
+    let order: Vec<usize> = stored
+        .metadata()
+        .column_list
+        .iter()
+        .filter(|&&entry| entry != 0)
+        .map(|entry| usize::from(entry.unsigned_abs()) - 1)
+        .collect();
+    let names = |sas: &SasReader<std::fs::File>| -> Vec<String> {
+        sas.metadata()
+            .variables
+            .iter()
+            .map(|variable| variable.name.clone())
+            .collect()
+    };
+    let stored_names = names(&stored);
+    let expected: Vec<String> = order.iter().map(|&idx| stored_names[idx].clone()).collect();
+    assert_ne!(expected, stored_names);
+    assert_eq!(names(&logical), expected);
+    assert!(
+        logical
+            .metadata()
+            .variables
+            .iter()
+            .enumerate()
+            .all(|(position, variable)| variable.index as usize == position)
+    );
+
+    let mut stored_rows = stored.rows().expect("rows");
+    let mut logical_rows = logical.rows().expect("rows");
+    let stored_row = stored_rows.try_next().expect("row").expect("first row");
+    let logical_row = logical_rows.try_next().expect("row").expect("first row");
+    let reordered: Vec<CellValue<'_>> = order.iter().map(|&idx| stored_row[idx].clone()).collect();
+    assert_eq!(logical_row, reordered);
+}
+
+#[test]
+fn find_rows_matches_full_scan() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+
+    let mut rows = sas.rows_named().expect("rows");
+    let mut countries = Vec::new();
+    let mut actuals = Vec::new();
+    while let Some(row) = rows.try_next().expect("row") {
+        countries.push(row.get("COUNTRY").cloned().expect("country"));
+        actuals.push(row.get("ACTUAL").cloned().expect("actual"));
+    }
+    drop(rows);
+
+    let CellValue::Str(country) = &countries[0] else {
+        panic!("COUNTRY is text: {:?}", countries[0]);
+    };
+    let expected = countries
+        .iter()
+        .filter(|value| *value == &countries[0])
+        .count();
+    assert!(expected > 3, "fixture repeats the first country");
+    let found = sas
+        .find_rows("COUNTRY", country.as_ref(), 3)
+        .expect("text lookup");
+    assert_eq!(found.len(), 3);
+    assert!(
+        found
+            .iter()
+            .all(|row| row.get("COUNTRY") == Some(&countries[0]))
+    );
+    let all = sas
+        .find_rows("COUNTRY", country.as_ref(), usize::MAX)
+        .expect("unbounded lookup");
+    assert_eq!(all.len(), expected);
+
+    let actual = match actuals[0] {
+        CellValue::Float(number) => number,
+        CellValue::Int64(number) => f64::from(i32::try_from(number).expect("small integer")),
+        ref other => panic!("ACTUAL is numeric: {other:?}"),
+    };
+    let found = sas.find_rows("ACTUAL", actual, 1).expect("numeric lookup");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].get("ACTUAL"), Some(&actuals[0]));
+
+    assert!(
+        sas.find_rows("COUNTRY", "NOWHERE", 10)
+            .expect("no match")
+            .is_empty()
+    );
+    assert!(matches!(
+        sas.find_rows("COUNTRY", 1, 1),
+        Err(Error::InvalidArgument { .. })
+    ));
+    assert!(matches!(
+        sas.find_rows("MISSING", "x", 1),
+        Err(Error::InvalidArgument { .. })
+    ));
+}
+
+#[test]
+fn row_filters_apply_before_pagination() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    let metadata = sas.metadata();
+    let country = metadata.column_index("COUNTRY").expect("COUNTRY");
+    let actual = metadata.column_index("ACTUAL").expect("ACTUAL");
+
+    let mut all = Vec::new();
+    let mut rows = sas.rows().expect("rows");
+    while let Some(row) = rows.try_next().expect("row") {
+        all.push(
+            row.into_iter()
+                .map(CellValue::into_owned)
+                .collect::<Vec<_>>(),
+        );
+    }
+    drop(rows);
+    let expected: Vec<_> = all
+        .iter()
+        .filter(|row| {
+            value::equals(&row[country], &"CANADA".into())
+                && value::compare(&row[actual], &500.0.into()).is_gt()
+        })
+        .skip(2)
+        .take(5)
+        .cloned()
+        .collect();
+    assert_eq!(expected.len(), 5);
+
+    let selection = RowSelection::new()
+        .matching(Predicate::eq("COUNTRY", "CANADA"))
+        .matching(Predicate::gt("ACTUAL", 500.0))
+        .skip_rows(2)
+        .max_rows(5);
+    let windowed = sas
+        .rows_windowed(&selection)
+        .expect("windowed rows")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("rows");
+    assert_eq!(windowed, expected);
+
+    let projected = sas
+        .select_with(&selection.clone().column_names(["ACTUAL"]))
+        .expect("projected rows")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("rows");
+    let expected_actual: Vec<_> = expected
+        .iter()
+        .map(|row| vec![row[actual].clone()])
+        .collect();
+    assert_eq!(projected, expected_actual);
+
+    let closure = RowSelection::new()
+        .filter(move |row| Ok(row.cell(country)?.decode_value()? == CellValue::from("CANADA")))
+        .matching(Predicate::gt("ACTUAL", 500.0))
+        .skip_rows(2)
+        .max_rows(5);
+    let filtered = sas
+        .rows_windowed(&closure)
+        .expect("closure rows")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("rows");
+    assert_eq!(filtered, expected);
+
+    let unknown = RowSelection::new().matching(Predicate::is_missing("NOPE"));
+    assert!(matches!(
+        sas.rows_windowed(&unknown),
+        Err(Error::InvalidArgument { .. })
+    ));
+    for mismatched in [
+        Predicate::gt("COUNTRY", 500.0),
+        Predicate::eq("ACTUAL", "500"),
+    ] {
+        let selection = RowSelection::new().matching(mismatched);
+        let Err(err) = sas.rows_windowed(&selection) else {
+            panic!("compared a column with a literal of the other kind");
+        };
+        assert!(matches!(err, Error::InvalidArgument { .. }), "{err}");
+    }
+    let Err(err) = sas.rows_windowed(&RowSelection::new().matching(Predicate::lt("COUNTRY", 1)))
+    else {
+        panic!("compared a character column with a number");
+    };
+    assert!(err.to_string().contains("'COUNTRY'"), "{err}");
+    assert_eq!(err.category(), ErrorCategory::InvalidArgument);
+}
+
+#[test]
+fn export_partitioned_by_date_routes_rows_by_year_and_month() {
+    let mut sas = open_datetime_fixture();
+    let total = usize::try_from(sas.metadata().row_count).expect("row count");
+    let date_name = sas.metadata().variables[0].name.clone();
+
+    for granularity in [DateGranularity::Year, DateGranularity::Month] {
+        let mut created = Vec::new();
+        let partitions = sas
+            .export_partitioned_by_date(&date_name, granularity, |partition| {
+                created.push(partition);
+                Ok(CollectSink::default())
+            })
+            .expect("partitioned export");
+        assert_eq!(created.len(), partitions.len(), "one sink per partition");
+        let rows: usize = partitions.values().map(|sink| sink.rows.len()).sum();
+        assert_eq!(rows, total);
+
+        for (partition, sink) in &partitions {
+            for row in &sink.rows {
+                match (partition, &row[0]) {
+                    (Some(partition), CellValue::Date(date)) => {
+                        assert_eq!(partition.year, date.year());
+                        assert_eq!(
+                            partition.month,
+                            (granularity == DateGranularity::Month).then(|| u8::from(date.month()))
+                        );
+                    }
+                    (None, value) => assert!(!matches!(value, CellValue::Date(_)), "{value:?}"),
+                    (partition, value) => panic!("{value:?} routed to {partition:?}"),
+                }
+            }
+        }
+    }
+
+    // Row 1 is the SAS epoch.
+    let by_year = sas
+        .export_partitioned_by_date(&date_name, DateGranularity::Year, |_| {
+            Ok(CollectSink::default())
+        })
+        .expect("partitioned export");
+    assert!(
+        by_year
+            .keys()
+            .flatten()
+            .any(|partition| partition.to_string() == "1960")
+    );
+
+    let unknown = sas.export_partitioned_by_date("NOPE", DateGranularity::Year, |_| {
+        Ok(CollectSink::default())
+    });
+    assert!(matches!(unknown, Err(Error::InvalidArgument { .. })));
+}
+
+#[derive(Default)]
+struct Warnings(Mutex<Vec<String>>);
+
+impl LogSink for Warnings {
+    fn log(&self, record: &LogRecord<'_>) {
+        self.0.lock().expect("lock").push(record.message.to_owned());
+    }
+}
+
+/// Reads the datetime fixture with its header row count replaced by
+/// `stored`, returning the rows read and the warnings raised.
+fn read_with_row_count(trust: bool, stored: u64) -> (u64, Vec<String>) {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let options = MetadataReadOptions::default().with_trust_row_count(trust);
+    let sas = SasReader::open_with_options(path, options).expect("open dataset");
+    let (mut reader, mut layout) = sas.into_parts();
+    layout.row_info.total_rows = stored;
+    let warnings = Arc::new(Warnings::default());
+    layout.logger = Logger::from_shared(warnings.clone());
+
+    let mut rows = layout.row_iterator(&mut reader).expect("row iterator");
+    let mut read = 0;
+    while rows.try_next().expect("row").is_some() {
+        read += 1;
+    }
+    drop(rows);
+    let messages = warnings.0.lock().expect("lock").clone();
+    (read, messages)
+}
+
+#[test]
+fn untrusted_row_count_reads_every_stored_row() {
+    let actual = open_datetime_fixture().metadata().row_count;
+    assert!(actual > 2);
+
+    assert_eq!(read_with_row_count(true, 2), (2, Vec::new()));
+
+    let (read, warnings) = read_with_row_count(false, 2);
+    assert_eq!(read, actual);
+    assert_eq!(
+        warnings,
+        vec![format!(
+            "header row count is 2 but the data pages hold {actual} rows"
+        )]
+    );
+
+    assert_eq!(read_with_row_count(false, actual), (actual, Vec::new()));
+}
+
+#[test]
+fn overstated_row_count_is_reported() {
+    let actual = open_datetime_fixture().metadata().row_count;
+    let (read, warnings) = read_with_row_count(false, actual + 5);
+    assert_eq!(read, actual);
+    assert_eq!(
+        warnings,
+        vec![format!(
+            "header row count is {} but the data pages hold {actual} rows",
+            actual + 5
+        )]
+    );
+}
+
+#[test]
+fn row_count_mismatch_is_recorded_in_reader_diagnostics() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/datetime.sas7bdat");
+    let options = MetadataReadOptions::default().with_trust_row_count(false);
+    let sas = SasReader::open_with_options(path, options).expect("open dataset");
+    let diagnostics = sas.diagnostics().clone();
+    assert!(diagnostics.is_empty());
+
+    let (mut reader, mut layout) = sas.into_parts();
+    let actual = layout.row_info.total_rows;
+    layout.row_info.total_rows = actual + 1;
+    layout.logger = Logger::from_shared(Arc::new(Warnings::default()));
+    let mut rows = layout.row_iterator(&mut reader).expect("row iterator");
+    while rows.try_next().expect("row").is_some() {}
+    drop(rows);
+
+    assert_eq!(
+        diagnostics.take(),
+        vec![Diagnostic {
+            page_index: None,
+            signature: None,
+            message: format!(
+                "header row count is {} but the data pages hold {actual} rows",
+                actual + 1
+            ),
+        }]
+    );
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn override_column_type_reads_temporal_columns_as_numbers() {
+    let mut sas = open_datetime_fixture();
+    let name = sas.metadata().variables[0].name.trim_end().to_owned();
+    let dates = collect_column(&mut sas, 0);
+    let epoch = time::macros::date!(1960 - 01 - 01);
+
+    sas.override_column_type(&name, ColumnType::Number)
+        .expect("read as number");
+    let numbers = collect_column(&mut sas, 0);
+    assert_eq!(numbers.len(), dates.len());
+    for (number, date) in numbers.iter().zip(&dates) {
+        match (number, date) {
+            (CellValue::Missing(_), CellValue::Missing(_)) => {}
+            (number, CellValue::Date(date)) => {
+                let days = match number {
+                    CellValue::Int32(days) => i64::from(*days),
+                    CellValue::Int64(days) => *days,
+                    other => panic!("expected whole days, got {other:?}"),
+                };
+                assert_eq!(days, (date.date() - epoch).whole_days());
+            }
+            other => panic!("unexpected pair {other:?}"),
+        }
+    }
+
+    sas.override_column_type(&name, ColumnType::Date)
+        .expect("read as date again");
+    assert_eq!(collect_column(&mut sas, 0), dates);
+
+    assert!(matches!(
+        sas.override_column_type(&name, ColumnType::Text),
+        Err(Error::InvalidArgument { .. })
+    ));
+    assert!(matches!(
+        sas.override_column_type("no_such_column", ColumnType::Number),
+        Err(Error::InvalidArgument { .. })
+    ));
+}
+
+#[test]
+fn override_column_type_reads_numbers_as_floats_or_text() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/airline.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open fixture");
+    let name = sas.metadata().variables[0]
+        .name
+        .trim_end()
+        .to_ascii_lowercase();
+    let numbers = collect_column(&mut sas, 0);
+    assert!(
+        numbers
+            .iter()
+            .any(|value| matches!(value, CellValue::Int32(_)))
+    );
+
+    sas.override_column_type(&name, ColumnType::Float)
+        .expect("lowercase name finds the column");
+    let floats = collect_column(&mut sas, 0);
+    sas.override_column_type(&name, ColumnType::NumericText)
+        .expect("read as text");
+    let texts = collect_column(&mut sas, 0);
+
+    for ((number, float), text) in numbers.iter().zip(&floats).zip(&texts) {
+        let (expected, rendered) = match number {
+            CellValue::Int32(value) => (f64::from(*value), value.to_string()),
+            CellValue::Float(value) => (*value, value.to_string()),
+            other => panic!("unexpected value {other:?}"),
+        };
+        assert_eq!(float, &CellValue::Float(expected));
+        assert_eq!(text, &CellValue::NumericString(rendered.into()));
+    }
+}
@@ -0,0 +1,76 @@
+use sas7bdat::{
+    SasReader,
+    dataset::{SchemaDifference, compare_schemas},
+    parser::ColumnKind,
+};
+use sas7bdat_test_support::common;
+
+fn fingerprint(name: &str) -> sas7bdat::dataset::SchemaFingerprint {
+    let path = common::fixture_path(format!("fixtures/raw_data/pandas/{name}"));
+    SasReader::open(path)
+        .expect("open dataset")
+        .schema_fingerprint()
+}
+
+#[test]
+fn fingerprint_ignores_storage_differences() {
+    // test1 is uncompressed and test2 row-compressed; the columns agree.
+    let test1 = fingerprint("test1.sas7bdat");
+    let test2 = fingerprint("test2.sas7bdat");
+    assert_eq!(test1, test2);
+    assert_eq!(test1.digest(), test2.digest());
+    assert_eq!(test1.digest().len(), 16);
+    assert!(compare_schemas(&[test1, test2]).is_empty());
+}
+
+#[test]
+fn compare_schemas_lists_every_difference() {
+    let test1 = fingerprint("test1.sas7bdat");
+    let airline = fingerprint("airline.sas7bdat");
+    assert_ne!(test1.digest(), airline.digest());
+
+    let mismatches = compare_schemas(&[test1.clone(), test1.clone(), airline.clone()]);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].index, 2);
+    let differences = &mismatches[0].differences;
+    let missing = differences
+        .iter()
+        .filter(|diff| matches!(diff, SchemaDifference::Missing { .. }))
+        .count();
+    let added = differences
+        .iter()
+        .filter(|diff| matches!(diff, SchemaDifference::Added { .. }))
+        .count();
+    assert_eq!(missing, test1.columns().len());
+    assert_eq!(added, airline.columns().len());
+}
+
+#[test]
+fn kind_changes_are_reported_by_column() {
+    let test1 = fingerprint("test1.sas7bdat");
+    let name = test1
+        .columns()
+        .iter()
+        .find(|(_, kind)| *kind == ColumnKind::Character)
+        .map(|(name, _)| name.clone())
+        .expect("character column");
+
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let mut sas = SasReader::open(path).expect("open dataset");
+    sas.set_raw_character_columns(&[&name]).expect("raw column");
+    let raw = sas.schema_fingerprint();
+
+    let differences = test1.differences(&raw);
+    assert_eq!(
+        differences,
+        vec![SchemaDifference::KindChanged {
+            column: name.clone(),
+            expected: ColumnKind::Character,
+            found: ColumnKind::Bytes,
+        }]
+    );
+    assert_eq!(
+        differences[0].to_string(),
+        format!("column '{name}' is bytes instead of character")
+    );
+}
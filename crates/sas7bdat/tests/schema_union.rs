@@ -0,0 +1,122 @@
+use sas7bdat::{
+    CellValue, MissingValue, SasReader,
+    dataset::{DatasetMetadata, VariableKind},
+    parser::ColumnKind,
+    schema::{RenamedColumn, RetypedColumn, SchemaDiff, union_schema},
+};
+use sas7bdat_test_support::common;
+
+fn test1() -> DatasetMetadata {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    SasReader::open(path)
+        .expect("open dataset")
+        .metadata()
+        .clone()
+}
+
+/// A later delivery of test1: `Column1` renamed to `Amount`, `Column3`
+/// dropped, a plain numeric `Column4` stored as text and a new `Extra`
+/// column at the end.
+fn next_delivery(first: &mut DatasetMetadata) -> DatasetMetadata {
+    first.variables[0].label = Some("Amount paid".to_owned());
+    first.variables[3].kind = VariableKind::Numeric;
+    first.variables[3].format = None;
+    let mut next = first.clone();
+    "Amount".clone_into(&mut next.variables[0].name);
+    next.variables.remove(2);
+    next.variables[2].kind = VariableKind::Character;
+    next.variables[2].format = None;
+    let mut extra = next.variables[1].clone();
+    "Extra".clone_into(&mut extra.name);
+    extra.label = None;
+    next.variables.push(extra);
+    next
+}
+
+#[test]
+fn diff_reports_added_removed_renamed_and_retyped_columns() {
+    let mut first = test1();
+    let next = next_delivery(&mut first);
+    let diff = SchemaDiff::between(&first, &next);
+
+    assert_eq!(diff.added, ["Extra"]);
+    assert_eq!(diff.removed, ["Column3"]);
+    assert_eq!(
+        diff.renamed,
+        [RenamedColumn {
+            from: "Column1".to_owned(),
+            to: "Amount".to_owned(),
+        }]
+    );
+    assert_eq!(diff.retyped.len(), 1);
+    let RetypedColumn { column, to, .. } = &diff.retyped[0];
+    assert_eq!(column, "Column4");
+    assert_eq!(*to, ColumnKind::Character);
+    assert!(
+        diff.to_string()
+            .contains("renamed column 'Column1' to 'Amount'")
+    );
+    assert!(SchemaDiff::between(&first, &first).is_empty());
+}
+
+#[test]
+fn union_maps_every_delivery_onto_one_layout() {
+    let first = test1();
+    let mut next = first.clone();
+    next.variables.swap(0, 1);
+    next.variables.remove(2);
+    let mut extra = next.variables[0].clone();
+    extra.name = "extra".to_owned();
+    extra.label = None;
+    next.variables.push(extra);
+
+    let union = union_schema(&[first.clone(), next.clone()]).expect("union");
+    assert_eq!(union.variables.len(), first.variables.len() + 1);
+    assert_eq!(
+        union.variables.last().map(|v| v.name.as_str()),
+        Some("extra")
+    );
+    // The first delivery lacks only the added column.
+    let sources = union.mappings[0].sources();
+    assert!(
+        sources[..first.variables.len()]
+            .iter()
+            .enumerate()
+            .all(|(position, source)| *source == Some(position))
+    );
+    assert_eq!(sources.last(), Some(&None));
+    assert!(!union.mappings[0].is_identity());
+    assert!(
+        union_schema(std::slice::from_ref(&first))
+            .expect("union")
+            .mappings[0]
+            .is_identity()
+    );
+
+    let mapping = &union.mappings[1];
+    assert_eq!(&mapping.sources()[..3], [Some(1), Some(0), None]);
+    let row: Vec<CellValue<'_>> = (0..next.variables.len())
+        .map(|index| CellValue::Int64(i64::try_from(index).expect("index")))
+        .collect();
+    let mapped = mapping.apply(&row);
+    assert_eq!(mapped.len(), union.variables.len());
+    assert_eq!(mapped[0], CellValue::Int64(1));
+    assert_eq!(mapped[1], CellValue::Int64(0));
+    assert_eq!(mapped[2], CellValue::Missing(MissingValue::System));
+}
+
+#[test]
+fn union_follows_renames_and_rejects_type_changes() {
+    let mut first = test1();
+    let mut next = next_delivery(&mut first);
+    next.variables[2] = first.variables[3].clone();
+
+    let union = union_schema(&[first.clone(), next]).expect("union");
+    assert_eq!(union.variables[0].name, "Column1");
+    assert_eq!(union.mappings[1].sources()[0], Some(0));
+
+    let mut retyped = first.clone();
+    retyped.variables[0].kind = VariableKind::Character;
+    let err = union_schema(&[first, retyped]).unwrap_err();
+    assert!(err.to_string().contains("Column1"), "{err}");
+}
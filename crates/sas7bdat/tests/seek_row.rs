@@ -0,0 +1,72 @@
+use sas7bdat::SasReader;
+use sas7bdat_test_support::common;
+
+/// Rows rendered with `Debug`, so missing values compare equal.
+type OwnedRow = String;
+
+fn read_all(sas: &mut SasReader<std::fs::File>) -> sas7bdat::Result<Vec<OwnedRow>> {
+    let mut rows = sas.rows()?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.try_next()? {
+        out.push(format!("{row:?}"));
+    }
+    Ok(out)
+}
+
+fn read_from(sas: &mut SasReader<std::fs::File>, start: u64) -> Vec<OwnedRow> {
+    let mut rows = sas.rows_from(start).expect("seeked row iterator");
+    let mut out = Vec::new();
+    while let Some(row) = rows.try_next().expect("row iteration failed") {
+        out.push(format!("{row:?}"));
+    }
+    out
+}
+
+#[test]
+fn rows_from_matches_skipping_a_full_scan() {
+    let dir = common::fixture_path("fixtures/raw_data/pandas");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(&dir).expect("fixture directory") {
+        let path = entry.expect("fixture entry").path();
+        // Corrupt and column-less fixtures are covered elsewhere.
+        let Ok(mut sas) = SasReader::open(&path) else {
+            continue;
+        };
+        let Ok(all) = read_all(&mut sas) else {
+            continue;
+        };
+        let total = all.len() as u64;
+        for start in [0, 1, total / 2, total.saturating_sub(1), total, total + 10] {
+            let expected = &all[usize::try_from(start.min(total)).expect("row index")..];
+            assert_eq!(
+                read_from(&mut sas, start),
+                expected,
+                "{} from row {start}",
+                path.display()
+            );
+        }
+        checked += 1;
+    }
+    assert!(checked > 10, "only {checked} fixtures were readable");
+}
+
+#[test]
+fn seek_row_repositions_an_iterator_backwards() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test2.sas7bdat");
+    let mut sas = SasReader::open(&path).expect("open fixture");
+    let all = read_all(&mut sas).expect("read fixture");
+    assert!(all.len() > 3);
+
+    let mut rows = sas.rows().expect("row iterator");
+    for _ in 0..all.len() {
+        rows.try_next().expect("row iteration failed");
+    }
+    assert!(rows.try_next().expect("final advance").is_none());
+
+    rows.seek_row(2).expect("seek");
+    let row = rows
+        .try_next()
+        .expect("row iteration failed")
+        .expect("row after seek");
+    assert_eq!(format!("{row:?}"), all[2]);
+}
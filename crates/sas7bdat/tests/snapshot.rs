@@ -0,0 +1,17 @@
+#![cfg(feature = "testing")]
+
+use sas7bdat::testing::{Snapshot, collect_snapshot};
+use sas7bdat_test_support::common;
+
+#[test]
+fn snapshot_round_trips_through_json() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let snapshot = collect_snapshot(&path).expect("snapshot");
+    assert_eq!(snapshot.rows.len(), snapshot.row_count);
+    assert_eq!(snapshot.columns.first().map(String::as_str), Some("ACTUAL"));
+    assert_eq!(snapshot.rows[0][0]["kind"], "number");
+
+    let json = serde_json::to_string(&snapshot).expect("serialise");
+    let parsed: Snapshot = serde_json::from_str(&json).expect("deserialise");
+    assert_eq!(parsed, snapshot);
+}
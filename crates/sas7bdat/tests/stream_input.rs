@@ -0,0 +1,102 @@
+use sas7bdat::{Error, MetadataReadOptions, SasReader};
+use sas7bdat_test_support::common;
+use std::{fs, io::Read, path::Path};
+
+/// Forward-only stream handing out a few bytes per read, like a pipe.
+struct Pipe {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl Pipe {
+    fn new(fixture: &str) -> Self {
+        Self::from_path(common::fixture_path(format!(
+            "fixtures/raw_data/pandas/{fixture}.sas7bdat"
+        )))
+    }
+
+    fn from_path(path: impl AsRef<Path>) -> Self {
+        Self {
+            data: fs::read(path).expect("read fixture"),
+            position: 0,
+        }
+    }
+}
+
+impl Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = &self.data[self.position..];
+        let count = available.len().min(buf.len()).min(1000);
+        buf[..count].copy_from_slice(&available[..count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+fn rows_debug<R: Read + std::io::Seek>(sas: &mut SasReader<R>) -> String {
+    let rows = sas
+        .rows()
+        .expect("rows")
+        .collect::<sas7bdat::Result<Vec<_>>>()
+        .expect("decode rows");
+    format!("{:?}|{rows:?}", sas.metadata().variables)
+}
+
+#[test]
+fn streamed_files_read_like_seekable_ones() {
+    for fixture in [
+        "test1",
+        "test2",
+        "test7",
+        "test10",
+        "airline",
+        "many_columns",
+        "productsales",
+        "zero_rows",
+    ] {
+        let path = common::fixture_path(format!("fixtures/raw_data/pandas/{fixture}.sas7bdat"));
+        let expected = rows_debug(&mut SasReader::open(path).expect("open fixture"));
+
+        let mut streamed = SasReader::from_stream(Pipe::new(fixture))
+            .unwrap_or_else(|err| panic!("{fixture}: {err}"));
+        assert_eq!(rows_debug(&mut streamed), expected, "{fixture}");
+    }
+}
+
+#[test]
+fn streamed_rows_cannot_be_read_twice() {
+    let mut sas = SasReader::from_stream(Pipe::new("productsales")).expect("open stream");
+    assert_eq!(sas.rows().expect("rows").count(), 1440);
+
+    let Err(err) = sas.rows() else {
+        panic!("a second pass needs to seek back");
+    };
+    assert!(matches!(err, sas7bdat::Error::Io(_)), "{err}");
+}
+
+/// These files keep column text on an AMD page after their data pages.
+const TRAILING_METADATA: [&str; 3] = ["owner", "ratiov", "homimp"];
+
+#[test]
+fn streams_within_the_buffer_read_trailing_metadata() {
+    for fixture in TRAILING_METADATA {
+        let path = common::fixture_path(format!("fixtures/raw_data/ahs2013/{fixture}.sas7bdat"));
+        let expected = rows_debug(&mut SasReader::open(&path).expect("open fixture"));
+
+        let mut streamed = SasReader::from_stream(Pipe::from_path(&path))
+            .unwrap_or_else(|err| panic!("{fixture}: {err}"));
+        assert_eq!(rows_debug(&mut streamed), expected, "{fixture}");
+    }
+}
+
+#[test]
+fn longer_streams_name_the_trailing_metadata_page() {
+    let path = common::fixture_path("fixtures/raw_data/ahs2013/owner.sas7bdat");
+    let options = MetadataReadOptions::default().with_stream_buffer(64 * 1024);
+    let Err(err) = SasReader::from_stream_with_options(Pipe::from_path(&path), options) else {
+        panic!("trailing metadata is past the buffer");
+    };
+    assert!(matches!(err, Error::Unsupported { .. }), "{err}");
+    // The column text of `owner` is on its last page, the 50th.
+    assert!(err.to_string().contains("page 49"), "{err}");
+}
@@ -0,0 +1,57 @@
+use sas7bdat::{
+    CellValue, RowSink, SasReader, SinkContext, Utf8Replacements, Utf8Sink, decode_layout,
+};
+use sas7bdat_test_support::common::{self, CollectSink};
+use std::{borrow::Cow, fs::File};
+
+fn test1() -> std::path::PathBuf {
+    common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat")
+}
+
+#[test]
+fn clean_rows_are_forwarded_unchanged() {
+    let mut plain = CollectSink::default();
+    SasReader::open(test1())
+        .expect("open dataset")
+        .stream_into(&mut plain)
+        .expect("stream rows");
+
+    let mut guarded = Utf8Sink::new(CollectSink::default());
+    SasReader::open(test1())
+        .expect("open dataset")
+        .stream_into(&mut guarded)
+        .expect("stream rows");
+
+    assert_eq!(guarded.replacements(), 0);
+    assert_eq!(guarded.into_inner().rows, plain.rows);
+}
+
+#[test]
+fn invalid_utf8_is_replaced_and_counted() {
+    let layout = decode_layout(&mut File::open(test1()).expect("open fixture")).expect("layout");
+    let counter = Utf8Replacements::default();
+    let mut sink = Utf8Sink::new(CollectSink::default())
+        .with_replacement('?')
+        .with_counter(counter.clone());
+    sink.begin(SinkContext::new(&layout)).expect("begin");
+    sink.write_row(&[
+        CellValue::Bytes(Cow::Borrowed(b"ok\xFF\xFEok")),
+        CellValue::Str("lossy \u{FFFD}".into()),
+        CellValue::Bytes(Cow::Borrowed(b"valid")),
+        CellValue::Float(1.5),
+    ])
+    .expect("write row");
+    sink.finish().expect("finish");
+
+    assert_eq!(sink.replacements(), 3);
+    assert_eq!(counter.get(), 3);
+    assert_eq!(
+        sink.into_inner().rows,
+        [vec![
+            CellValue::Bytes(Cow::Owned(b"ok??ok".to_vec())),
+            CellValue::Str("lossy ?".into()),
+            CellValue::Bytes(Cow::Borrowed(b"valid")),
+            CellValue::Float(1.5),
+        ]]
+    );
+}
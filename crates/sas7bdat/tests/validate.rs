@@ -0,0 +1,40 @@
+use sas7bdat::{IssueSeverity, SasReader, validate_file};
+use sas7bdat_test_support::common;
+
+#[test]
+fn clean_file_has_no_issues() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/test1.sas7bdat");
+    let report = validate_file(&path);
+    assert!(report.is_clean(), "{:?}", report.issues);
+    assert_eq!(report.expected_rows, Some(report.rows_read));
+
+    let mut sas = SasReader::open(&path).expect("open dataset");
+    assert_eq!(sas.validate().expect("validate"), report);
+}
+
+#[test]
+fn truncated_file_reports_errors() {
+    let path = common::fixture_path("fixtures/raw_data/pandas/productsales.sas7bdat");
+    let bytes = std::fs::read(&path).expect("read fixture");
+    let dir = tempfile::tempdir().expect("tempdir");
+    let truncated = dir.path().join("truncated.sas7bdat");
+    std::fs::write(&truncated, &bytes[..bytes.len() - 512]).expect("write truncated copy");
+
+    let report = validate_file(&truncated);
+    assert!(report.errors().count() >= 1, "{:?}", report.issues);
+    assert!(
+        report
+            .errors()
+            .any(|issue| issue.message.contains("bytes but the header describes")),
+        "{:?}",
+        report.issues
+    );
+}
+
+#[test]
+fn unreadable_file_yields_single_error() {
+    let report = validate_file("does/not/exist.sas7bdat");
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].severity, IssueSeverity::Error);
+    assert_eq!(report.expected_rows, None);
+}
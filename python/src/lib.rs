@@ -0,0 +1,227 @@
+//! Python bindings for the `sas7bdat` crate, importable as `sas7bdat`.
+
+// pyo3 extracts function arguments as owned values.
+#![allow(clippy::needless_pass_by_value)]
+
+use pyo3::{
+    IntoPyObjectExt,
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict, PyList, PyModule},
+};
+use sas7bdat::{
+    CellValue, JsonlSink, RowSelection, SasReader,
+    dataset::{Compression, Endianness, VariableKind, Vendor},
+    sinks::{CsvSink, ParquetSink},
+};
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Raises `OSError` for I/O failures and `ValueError` for everything the
+/// file's contents or the arguments are to blame for.
+fn map_core_err(err: sas7bdat::Error) -> PyErr {
+    match err {
+        sas7bdat::Error::Io(err) => PyIOError::new_err(format!("sas7bdat error: {err}")),
+        err => PyValueError::new_err(format!("sas7bdat error: {err}")),
+    }
+}
+
+fn map_io_err(action: &str, path: &Path, err: &std::io::Error) -> PyErr {
+    PyIOError::new_err(format!("failed to {action} '{}': {err}", path.display()))
+}
+
+/// Converts a decoded cell to the Python value pandas and pyarrow infer the
+/// matching column type from: missing values become `None`, dates
+/// `datetime.date`, datetimes naive `datetime.datetime` in UTC and times
+/// `datetime.timedelta`.
+///
+/// The `datetime` types are looked up through the `datetime` module, which
+/// callers import once per read, because the stable ABI the extension is
+/// built against does not expose them.
+#[allow(clippy::cast_possible_truncation)]
+fn cell_to_py<'py>(
+    py: Python<'py>,
+    datetime: &Bound<'py, PyModule>,
+    value: CellValue<'_>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let datetime_type = |name: &str| datetime.getattr(name);
+    match value {
+        CellValue::Missing(_) => Ok(py.None().into_bound(py)),
+        CellValue::Float(v) => v.into_bound_py_any(py),
+        CellValue::Int32(v) => v.into_bound_py_any(py),
+        CellValue::Int64(v) => v.into_bound_py_any(py),
+        CellValue::Str(text) | CellValue::NumericString(text) => (&*text).into_bound_py_any(py),
+        CellValue::Bytes(bytes) => Ok(PyBytes::new(py, &bytes).into_any()),
+        CellValue::Date(datetime) => {
+            let date = datetime.date();
+            datetime_type("date")?.call1((date.year(), u8::from(date.month()), date.day()))
+        }
+        CellValue::DateTime(datetime) => datetime_type("datetime")?.call1((
+            datetime.year(),
+            u8::from(datetime.month()),
+            datetime.day(),
+            datetime.hour(),
+            datetime.minute(),
+            datetime.second(),
+            datetime.microsecond(),
+        )),
+        CellValue::Time(duration) => {
+            let seconds = duration.whole_seconds();
+            let days = i32::try_from(seconds.div_euclid(SECONDS_PER_DAY))
+                .map_err(|_| PyValueError::new_err(format!("time {duration} out of range")))?;
+            // The remainder is below one day, so it fits `i32`.
+            let seconds = seconds.rem_euclid(SECONDS_PER_DAY) as i32;
+            datetime_type("timedelta")?.call1((days, seconds, duration.subsec_microseconds()))
+        }
+    }
+}
+
+/// Reads a SAS7BDAT file into a dict mapping column names to lists.
+///
+/// The result can be passed straight to `pandas.DataFrame` or
+/// `pyarrow.table`. `columns` picks and orders the columns to read; `nrows`
+/// stops after that many rows.
+#[pyfunction]
+#[pyo3(signature = (path, columns=None, nrows=None))]
+fn read_sas<'py>(
+    py: Python<'py>,
+    path: PathBuf,
+    columns: Option<Vec<String>>,
+    nrows: Option<u64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut reader = SasReader::open(&path).map_err(map_core_err)?;
+    let names = columns.unwrap_or_else(|| {
+        reader
+            .metadata()
+            .variables
+            .iter()
+            .map(|variable| variable.name.clone())
+            .collect()
+    });
+    let mut selection = RowSelection::new().column_names(names.iter().map(String::as_str));
+    if let Some(nrows) = nrows {
+        selection = selection.max_rows(nrows);
+    }
+
+    let datetime = py.import("datetime")?;
+    let lists: Vec<Bound<'py, PyList>> = names.iter().map(|_| PyList::empty(py)).collect();
+    let mut rows = reader.select_with(&selection).map_err(map_core_err)?;
+    while let Some(row) = rows.try_next().map_err(map_core_err)? {
+        for (list, value) in lists.iter().zip(row) {
+            list.append(cell_to_py(py, &datetime, value)?)?;
+        }
+    }
+
+    let out = PyDict::new(py);
+    for (name, list) in names.iter().zip(lists) {
+        out.set_item(name.trim_end(), list)?;
+    }
+    Ok(out)
+}
+
+/// Returns the dataset metadata of a SAS7BDAT file as a dict.
+#[pyfunction]
+fn metadata(py: Python<'_>, path: PathBuf) -> PyResult<Bound<'_, PyDict>> {
+    let reader = SasReader::open(&path).map_err(map_core_err)?;
+    let md = reader.metadata();
+
+    let vendor = match md.vendor {
+        Vendor::Sas => "SAS",
+        Vendor::StatTransfer => "StatTransfer",
+        Vendor::Other(_) => "Other",
+    };
+    let compression = match &md.compression {
+        Compression::None => "none",
+        Compression::Row => "row",
+        Compression::Binary => "binary",
+        Compression::Unknown(_) => "unknown",
+        Compression::Other(signature) => signature.as_str(),
+    };
+    let endianness = match md.endianness {
+        Endianness::Little => "little",
+        Endianness::Big => "big",
+    };
+
+    let columns = PyList::empty(py);
+    for variable in &md.variables {
+        let column = PyDict::new(py);
+        column.set_item("name", variable.name.trim_end())?;
+        column.set_item(
+            "type",
+            match variable.kind {
+                VariableKind::Numeric => "numeric",
+                VariableKind::Character => "character",
+            },
+        )?;
+        column.set_item("label", variable.label.as_deref())?;
+        column.set_item(
+            "format",
+            variable.format.as_ref().map(|format| format.name.trim()),
+        )?;
+        column.set_item("width", variable.storage_width)?;
+        columns.append(column)?;
+    }
+
+    let out = PyDict::new(py);
+    out.set_item("row_count", md.row_count)?;
+    out.set_item("column_count", md.column_count)?;
+    out.set_item("table_name", md.table_name.as_deref())?;
+    out.set_item("file_label", md.file_label.as_deref())?;
+    out.set_item("file_encoding", md.file_encoding.as_deref())?;
+    out.set_item("vendor", vendor)?;
+    out.set_item("compression", compression)?;
+    out.set_item("endianness", endianness)?;
+    out.set_item(
+        "version",
+        (md.version.major, md.version.minor, md.version.revision),
+    )?;
+    out.set_item(
+        "created",
+        md.timestamps.created.as_ref().map(ToString::to_string),
+    )?;
+    out.set_item(
+        "modified",
+        md.timestamps.modified.as_ref().map(ToString::to_string),
+    )?;
+    out.set_item("columns", columns)?;
+    Ok(out)
+}
+
+/// Converts a SAS7BDAT file to `"parquet"`, `"csv"` or `"jsonl"` at `out`.
+///
+/// The conversion streams and runs without holding the GIL.
+#[pyfunction]
+#[pyo3(signature = (path, out, format="parquet"))]
+fn convert(py: Python<'_>, path: PathBuf, out: PathBuf, format: &str) -> PyResult<()> {
+    let format = format.trim().to_ascii_lowercase();
+    if !matches!(format.as_str(), "parquet" | "csv" | "jsonl") {
+        return Err(PyValueError::new_err(format!(
+            "unsupported format '{format}', expected 'parquet', 'csv' or 'jsonl'"
+        )));
+    }
+    py.allow_threads(|| {
+        let mut sas = SasReader::open(&path).map_err(map_core_err)?;
+        let file =
+            File::create(&out).map_err(|err| map_io_err("create output file", &out, &err))?;
+        let result = match format.as_str() {
+            "parquet" => sas.stream_into(&mut ParquetSink::new(file)),
+            "csv" => sas.stream_into(&mut CsvSink::new(BufWriter::new(file))),
+            _ => sas.stream_into(&mut JsonlSink::new(file)),
+        };
+        result.map_err(map_core_err)
+    })
+}
+
+#[pymodule]
+#[pyo3(name = "sas7bdat")]
+fn sas7bdat_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read_sas, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+from pathlib import Path
+
+import pytest
+
+sas7bdat = pytest.importorskip("sas7bdat")
+
+
+def _fixture(name: str) -> Path:
+    return Path(__file__).resolve().parents[2] / "fixtures" / "raw_data" / "pandas" / name
+
+
+def test_read_sas_returns_columns_by_name() -> None:
+    columns = sas7bdat.read_sas(str(_fixture("test1.sas7bdat")))
+    assert len(columns) == 100
+    assert len(columns["Column1"]) == 10
+    assert columns["Column2"][0] == "pear"
+    assert columns["Column1"][0] == pytest.approx(0.636)
+
+
+def test_read_sas_projects_and_limits_rows() -> None:
+    columns = sas7bdat.read_sas(
+        str(_fixture("test1.sas7bdat")), columns=["Column2", "Column1"], nrows=3
+    )
+    assert list(columns) == ["Column2", "Column1"]
+    assert all(len(values) == 3 for values in columns.values())
+
+
+def test_metadata_describes_columns() -> None:
+    info = sas7bdat.metadata(str(_fixture("test1.sas7bdat")))
+    assert info["row_count"] == 10
+    assert info["column_count"] == 100
+    assert info["columns"][1]["name"] == "Column2"
+    assert info["columns"][1]["type"] == "character"
+
+
+def test_convert_writes_jsonl(tmp_path: Path) -> None:
+    out = tmp_path / "test1.jsonl"
+    sas7bdat.convert(str(_fixture("test1.sas7bdat")), str(out), "jsonl")
+    assert len(out.read_text(encoding="utf-8").splitlines()) == 10
+
+
+def test_convert_rejects_unknown_formats(tmp_path: Path) -> None:
+    with pytest.raises(ValueError):
+        sas7bdat.convert(str(_fixture("test1.sas7bdat")), str(tmp_path / "out"), "xlsx")
+
+
+def test_missing_files_raise_os_error(tmp_path: Path) -> None:
+    with pytest.raises(OSError):
+        sas7bdat.read_sas(str(tmp_path / "missing.sas7bdat"))
+
+
+def test_corrupt_files_raise_value_error() -> None:
+    with pytest.raises(ValueError):
+        sas7bdat.read_sas(str(_fixture("corrupt.sas7bdat")))